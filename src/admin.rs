@@ -0,0 +1,190 @@
+//! The admin surface served on `--admin-port`, for test harnesses that
+//! manage the emulator as a whole: aggregate health and state resets.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use serde_json::json;
+
+type ResetFn = Box<dyn Fn() -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Every service the admin endpoints can act on, keyed by the name used in
+/// its `--<service>-port` flag.
+#[derive(Default)]
+pub struct Registry {
+    resets: BTreeMap<&'static str, ResetFn>,
+}
+
+impl Registry {
+    pub fn register(&mut self, service: &'static str, reset: ResetFn) {
+        self.resets.insert(service, reset);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListenerStatus {
+    Starting,
+    Ok,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Listener {
+    service: &'static str,
+    name: &'static str,
+    port: u16,
+    status: ListenerStatus,
+}
+
+/// The port of every spawned service and whether it has been bound yet.
+#[derive(Clone, Default)]
+pub struct Listeners(Arc<RwLock<BTreeMap<&'static str, Listener>>>);
+
+impl Listeners {
+    pub fn starting(&self, service: &'static str, name: &'static str, port: u16) {
+        let listener = Listener { service, name, port, status: ListenerStatus::Starting };
+        self.0.write().unwrap().insert(service, listener);
+    }
+
+    pub fn set_status(&self, service: &str, status: ListenerStatus) {
+        if let Some(listener) = self.0.write().unwrap().get_mut(service) {
+            listener.status = status;
+        }
+    }
+}
+
+/// Registers a service state's `reset()` and evaluates to the state, so a
+/// router can be built from it in the same expression.
+#[macro_export]
+macro_rules! register_reset {
+    ($registry:expr, $state:ident) => {{
+        let service = stringify!($state).trim_end_matches("_state");
+        let state = $state.clone();
+        $registry.register(
+            service,
+            Box::new(move || {
+                let state = state.clone();
+                Box::pin(async move { state.reset().await })
+            }),
+        );
+        $state
+    }};
+}
+
+#[derive(Clone)]
+struct AdminState {
+    registry: Arc<Registry>,
+    listeners: Listeners,
+}
+
+// GET /_health — 200 once every service is listening, 503 before then or
+// if any port could not be bound.
+async fn health(State(admin): State<AdminState>) -> Response {
+    let services: Vec<Listener> = admin.listeners.0.read().unwrap().values().cloned().collect();
+    let status = if services.iter().any(|l| l.status == ListenerStatus::Failed) {
+        "degraded"
+    } else if services.iter().any(|l| l.status == ListenerStatus::Starting) {
+        "starting"
+    } else {
+        "ok"
+    };
+    let code = if status == "ok" { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(json!({ "status": status, "services": services }))).into_response()
+}
+
+async fn reset_all(State(admin): State<AdminState>) -> Response {
+    let registry = &admin.registry;
+    for reset in registry.resets.values() {
+        reset().await;
+    }
+    Json(json!({ "reset": registry.resets.keys().collect::<Vec<_>>() })).into_response()
+}
+
+async fn reset_service(State(admin): State<AdminState>, Path(service): Path<String>) -> Response {
+    match admin.registry.resets.get(service.as_str()) {
+        Some(reset) => {
+            reset().await;
+            Json(json!({ "reset": [service] })).into_response()
+        }
+        None => (StatusCode::NOT_FOUND, Json(json!({ "message": format!("Unknown service: {service}") })))
+            .into_response(),
+    }
+}
+
+pub fn create_router(registry: Registry, listeners: Listeners) -> Router {
+    Router::new()
+        .route("/_health", get(health))
+        .route("/_admin/reset", post(reset_all))
+        .route("/_admin/reset/{service}", post(reset_service))
+        .with_state(AdminState { registry: Arc::new(registry), listeners })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::sqs::state::SqsState;
+    use crate::sqs::types::CreateQueueRequest;
+
+    #[tokio::test]
+    async fn test_reset_clears_queues() {
+        let sqs_state = Arc::new(SqsState::new("123456789012".to_string(), "us-east-1".to_string(), 9324));
+        let mut registry = Registry::default();
+        let sqs = register_reset!(registry, sqs_state);
+        let app = create_router(registry, Listeners::default());
+
+        sqs.create_queue(CreateQueueRequest { queue_name: "orders".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(sqs.list_queues(Default::default()).await.unwrap().queue_urls.unwrap_or_default().len(), 1);
+
+        let req = Request::builder().method("POST").uri("/_admin/reset/sqs").body(Body::empty()).unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+        assert!(sqs.list_queues(Default::default()).await.unwrap().queue_urls.unwrap_or_default().is_empty());
+
+        // The queue name can be reused after the reset.
+        sqs.create_queue(CreateQueueRequest { queue_name: "orders".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        let req = Request::builder().method("POST").uri("/_admin/reset").body(Body::empty()).unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+        assert!(sqs.list_queues(Default::default()).await.unwrap().queue_urls.unwrap_or_default().is_empty());
+
+        let req = Request::builder().method("POST").uri("/_admin/reset/nope").body(Body::empty()).unwrap();
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_health_reports_ports() {
+        let listeners = Listeners::default();
+        listeners.starting("sqs", "SQS", 9324);
+        listeners.starting("sns", "SNS", 9911);
+        let app = create_router(Registry::default(), listeners.clone());
+        let get = || Request::builder().uri("/_health").body(Body::empty()).unwrap();
+
+        listeners.set_status("sqs", ListenerStatus::Ok);
+        let resp = app.clone().oneshot(get()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        listeners.set_status("sns", ListenerStatus::Ok);
+        let resp = app.oneshot(get()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["services"][1]["service"], "sqs");
+        assert_eq!(body["services"][1]["port"], 9324);
+        assert_eq!(body["services"][1]["status"], "ok");
+    }
+}
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<AmplifyState>) -> Router {
     Router::new()
         .route("/apps", post(create_app_handler).get(list_apps_handler))
         .route("/apps/{name}", get(get_app_handler).delete(delete_app_handler))
+        .merge(crate::healthcheck::router("amplify"))
         .with_state(state)
 }
 
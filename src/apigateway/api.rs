@@ -18,6 +18,7 @@ pub struct RestApi {
     pub resources: HashMap<String, Resource>,
     pub deployments: HashMap<String, Deployment>,
     pub stages: HashMap<String, Stage>,
+    pub authorizers: HashMap<String, Authorizer>,
     pub resource_counter: u64,
 }
 
@@ -43,6 +44,7 @@ impl RestApi {
             resources,
             deployments: HashMap::new(),
             stages: HashMap::new(),
+            authorizers: HashMap::new(),
             resource_counter: 1,
         }
     }
@@ -114,6 +116,7 @@ impl Integration {
 #[derive(Debug, Clone)]
 pub struct IntegrationResponse {
     pub status_code: String,
+    pub selection_pattern: Option<String>,
     pub response_parameters: HashMap<String, String>,
     pub response_templates: HashMap<String, String>,
 }
@@ -167,3 +170,14 @@ impl Stage {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct Authorizer {
+    pub id: String,
+    pub name: String,
+    pub authorizer_type: String,
+    pub authorizer_uri: Option<String>,
+    pub identity_source: Option<String>,
+    pub identity_validation_expression: Option<String>,
+    pub authorizer_result_ttl_in_seconds: u64,
+}
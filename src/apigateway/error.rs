@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum ApiGatewayError {
@@ -35,17 +36,23 @@ impl ApiGatewayError {
     }
 }
 
+impl AwsError for ApiGatewayError {
+    fn code(&self) -> &str {
+        self.error_type()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        ApiGatewayError::message(self)
+    }
+}
+
 impl IntoResponse for ApiGatewayError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "message": self.message(),
-        });
-        (
-            self.status_code(),
-            [("x-amzn-ErrorType", self.error_type())],
-            axum::Json(body),
-        )
-            .into_response()
+        error_response(&self, Protocol::RestJson)
     }
 }
 
@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use super::api::{Authorizer, Integration, IntegrationResponse, Resource};
+use super::pattern;
+use super::vtl::{self, TemplateInput};
+
+/// An incoming call to a deployed API (`/restapis/{id}/{stage}/_user_request_/...`).
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteRequest {
+    pub http_method: String,
+    pub path: String,
+    /// Header names are lower-case.
+    pub headers: HashMap<String, String>,
+    pub query: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecuteResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl ExecuteResponse {
+    pub fn json(status: u16, value: &Value) -> Self {
+        ExecuteResponse {
+            status,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    pub fn message(status: u16, message: &str) -> Self {
+        Self::json(status, &json!({ "message": message }))
+    }
+}
+
+/// Finds the resource whose path template matches `path`, preferring the
+/// template with the most literal segments. Returns the captured path
+/// parameters; a greedy `{name+}` segment captures the rest of the path.
+pub fn match_resource<'a>(
+    resources: &'a HashMap<String, Resource>,
+    path: &str,
+) -> Option<(&'a Resource, HashMap<String, String>)> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut best: Option<(usize, &Resource, HashMap<String, String>)> = None;
+    for resource in resources.values() {
+        let template: Vec<&str> = resource.path.split('/').filter(|s| !s.is_empty()).collect();
+        let Some((literals, params)) = match_template(&template, &segments) else {
+            continue;
+        };
+        if best.as_ref().is_none_or(|(score, _, _)| literals > *score) {
+            best = Some((literals, resource, params));
+        }
+    }
+    best.map(|(_, resource, params)| (resource, params))
+}
+
+/// Matches path `segments` against a path `template`, returning the number
+/// of literal segments matched and the captured parameters.
+pub fn match_template(template: &[&str], segments: &[&str]) -> Option<(usize, HashMap<String, String>)> {
+    let mut params = HashMap::new();
+    let mut literals = 0;
+    for (i, part) in template.iter().enumerate() {
+        if let Some(name) = part.strip_prefix('{').and_then(|p| p.strip_suffix("+}")) {
+            if i >= segments.len() || i != template.len() - 1 {
+                return None;
+            }
+            params.insert(name.to_string(), segments[i..].join("/"));
+            return Some((literals, params));
+        }
+        let segment = segments.get(i)?;
+        match part.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+            Some(name) => {
+                params.insert(name.to_string(), segment.to_string());
+            }
+            None if part == segment => literals += 1,
+            None => return None,
+        }
+    }
+    (template.len() == segments.len()).then_some((literals, params))
+}
+
+pub fn method_arn(region: &str, account_id: &str, api_id: &str, stage: &str, method: &str, path: &str) -> String {
+    format!(
+        "arn:aws:execute-api:{region}:{account_id}:{api_id}/{stage}/{method}/{}",
+        path.trim_start_matches('/')
+    )
+}
+
+/// Extracts the Lambda function ARN from an API Gateway integration URI
+/// (`arn:aws:apigateway:{region}:lambda:path/2015-03-31/functions/{arn}/invocations`).
+pub fn lambda_arn(uri: &str) -> Option<&str> {
+    let (_, rest) = uri.split_once("/functions/")?;
+    Some(rest.strip_suffix("/invocations").unwrap_or(rest))
+}
+
+/// Resolves the authorizer's identity sources against the request. Returns
+/// `None` if any source is missing, which API Gateway answers with 401.
+pub fn identity(
+    authorizer: &Authorizer,
+    req: &ExecuteRequest,
+    stage_variables: &HashMap<String, String>,
+) -> Option<Vec<String>> {
+    let sources = authorizer.identity_source.as_deref().unwrap_or_default();
+    let mut values = Vec::new();
+    for source in sources.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let value = if let Some(name) = source.strip_prefix("method.request.header.") {
+            req.headers.get(&name.to_ascii_lowercase())
+        } else if let Some(name) = source.strip_prefix("method.request.querystring.") {
+            req.query.get(name)
+        } else if let Some(name) = source.strip_prefix("stageVariables.") {
+            stage_variables.get(name)
+        } else {
+            None
+        };
+        values.push(value.filter(|v| !v.is_empty())?.clone());
+    }
+    Some(values)
+}
+
+pub struct InvocationContext<'a> {
+    pub api_id: &'a str,
+    pub account_id: &'a str,
+    pub stage: &'a str,
+    pub resource_path: &'a str,
+    pub method_arn: &'a str,
+    pub path_parameters: &'a HashMap<String, String>,
+    pub stage_variables: &'a HashMap<String, String>,
+}
+
+pub fn request_context(ctx: &InvocationContext, req: &ExecuteRequest) -> Value {
+    json!({
+        "accountId": ctx.account_id,
+        "apiId": ctx.api_id,
+        "stage": ctx.stage,
+        "resourcePath": ctx.resource_path,
+        "httpMethod": req.http_method,
+        "path": format!("/{}{}", ctx.stage, req.path),
+        "requestId": uuid::Uuid::new_v4().to_string(),
+        "identity": { "sourceIp": "127.0.0.1" },
+    })
+}
+
+fn optional_map(map: &HashMap<String, String>) -> Value {
+    if map.is_empty() {
+        Value::Null
+    } else {
+        json!(map)
+    }
+}
+
+pub fn authorizer_event(authorizer: &Authorizer, ctx: &InvocationContext, req: &ExecuteRequest, identity: &[String]) -> Value {
+    if authorizer.authorizer_type == "TOKEN" {
+        return json!({
+            "type": "TOKEN",
+            "authorizationToken": identity.first().cloned().unwrap_or_default(),
+            "methodArn": ctx.method_arn,
+        });
+    }
+    json!({
+        "type": "REQUEST",
+        "methodArn": ctx.method_arn,
+        "resource": ctx.resource_path,
+        "path": req.path,
+        "httpMethod": req.http_method,
+        "headers": req.headers,
+        "queryStringParameters": req.query,
+        "pathParameters": ctx.path_parameters,
+        "stageVariables": ctx.stage_variables,
+        "requestContext": request_context(ctx, req),
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PolicyEffect {
+    Allow,
+    Deny,
+    NoMatch,
+}
+
+/// Evaluates the IAM policy returned by an authorizer for `execute-api:Invoke`
+/// on `method_arn`. An explicit `Deny` wins over any `Allow`.
+pub fn evaluate_policy(result: &Value, method_arn: &str) -> PolicyEffect {
+    let statements = match &result["policyDocument"]["Statement"] {
+        Value::Array(items) => items.clone(),
+        single @ Value::Object(_) => vec![single.clone()],
+        _ => return PolicyEffect::NoMatch,
+    };
+    let mut effect = PolicyEffect::NoMatch;
+    for statement in &statements {
+        let applies = strings(&statement["Action"]).iter().any(|a| wildcard_match(a, "execute-api:Invoke"))
+            && strings(&statement["Resource"]).iter().any(|r| wildcard_match(r, method_arn));
+        if !applies {
+            continue;
+        }
+        match statement["Effect"].as_str() {
+            Some("Deny") => return PolicyEffect::Deny,
+            Some("Allow") => effect = PolicyEffect::Allow,
+            _ => {}
+        }
+    }
+    effect
+}
+
+/// The `context` map and `principalId` returned by an authorizer, as exposed
+/// to integrations under `requestContext.authorizer`.
+pub fn authorizer_context(result: &Value) -> Map<String, Value> {
+    let mut context = result["context"].as_object().cloned().unwrap_or_default();
+    if let Some(principal) = result.get("principalId") {
+        context.insert("principalId".to_string(), principal.clone());
+    }
+    context
+}
+
+fn strings(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Glob match supporting `*` (any run of characters) and `?` (one character).
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let v: Vec<char> = value.chars().collect();
+    let (mut pi, mut vi) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while vi < v.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == v[vi]) {
+            pi += 1;
+            vi += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, vi));
+            pi += 1;
+        } else if let Some((star, matched)) = backtrack {
+            pi = star + 1;
+            vi = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+pub fn proxy_event(ctx: &InvocationContext, req: &ExecuteRequest, authorizer: &Map<String, Value>) -> Value {
+    let mut request_context = request_context(ctx, req);
+    if !authorizer.is_empty() {
+        request_context["authorizer"] = Value::Object(authorizer.clone());
+    }
+    json!({
+        "resource": ctx.resource_path,
+        "path": req.path,
+        "httpMethod": req.http_method,
+        "headers": optional_map(&req.headers),
+        "queryStringParameters": optional_map(&req.query),
+        "pathParameters": optional_map(ctx.path_parameters),
+        "stageVariables": optional_map(ctx.stage_variables),
+        "requestContext": request_context,
+        "body": (!req.body.is_empty()).then(|| String::from_utf8_lossy(&req.body).into_owned()),
+        "isBase64Encoded": false,
+    })
+}
+
+/// Converts a Lambda proxy integration result (`statusCode`, `headers`,
+/// `body`) into a response; anything else is a 502 as in AWS.
+pub fn proxy_response(result: &Value) -> ExecuteResponse {
+    let Some(status) = result["statusCode"].as_u64().and_then(|s| u16::try_from(s).ok()) else {
+        return ExecuteResponse::message(502, "Internal server error");
+    };
+    let headers = result["headers"]
+        .as_object()
+        .map(|h| {
+            h.iter()
+                .map(|(k, v)| (k.clone(), v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let body = match &result["body"] {
+        Value::String(s) => s.clone().into_bytes(),
+        Value::Null => Vec::new(),
+        other => other.to_string().into_bytes(),
+    };
+    ExecuteResponse { status, headers, body }
+}
+
+/// What a non-proxy integration's mapping templates see as
+/// `$input.params()`, `$context` and `$stageVariables`.
+pub struct MappingContext {
+    params: Value,
+    context: Value,
+    stage_variables: Value,
+}
+
+impl MappingContext {
+    pub fn new(ctx: &InvocationContext, req: &ExecuteRequest, authorizer: &Map<String, Value>) -> Self {
+        let mut context = request_context(ctx, req);
+        if !authorizer.is_empty() {
+            context["authorizer"] = Value::Object(authorizer.clone());
+        }
+        MappingContext {
+            params: json!({
+                "path": ctx.path_parameters,
+                "querystring": req.query,
+                "header": req.headers,
+            }),
+            context,
+            stage_variables: json!(ctx.stage_variables),
+        }
+    }
+
+    fn render(&self, template: &str, body: &str) -> Result<vtl::Rendered, String> {
+        vtl::render(
+            template,
+            &TemplateInput {
+                body,
+                params: self.params.clone(),
+                context: self.context.clone(),
+                stage_variables: self.stage_variables.clone(),
+            },
+        )
+    }
+}
+
+/// Transforms the request body with the request template for its content
+/// type, honouring the integration's `passthroughBehavior` when there is
+/// none.
+pub fn map_request(
+    integration: &Integration,
+    mapping: &MappingContext,
+    req: &ExecuteRequest,
+) -> Result<String, ExecuteResponse> {
+    let body = String::from_utf8_lossy(&req.body);
+    let content_type = req
+        .headers
+        .get("content-type")
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "application/json".to_string());
+    let Some(template) = integration.request_templates.get(&content_type) else {
+        let passthrough = match integration.passthrough_behavior.as_str() {
+            "NEVER" => false,
+            "WHEN_NO_TEMPLATES" => integration.request_templates.is_empty(),
+            _ => true,
+        };
+        return if passthrough {
+            Ok(body.into_owned())
+        } else {
+            Err(ExecuteResponse::message(415, "Unsupported Media Type"))
+        };
+    };
+    mapping
+        .render(template, &body)
+        .map(|rendered| rendered.body)
+        .map_err(|_| ExecuteResponse::message(500, "Internal server error"))
+}
+
+/// Picks the integration response whose `selectionPattern` fully matches
+/// `selector`, falling back to the default response (the one without a
+/// pattern).
+pub fn select_integration_response<'a>(
+    integration: &'a Integration,
+    selector: Option<&str>,
+) -> Option<&'a IntegrationResponse> {
+    let mut responses: Vec<&IntegrationResponse> = integration.integration_responses.values().collect();
+    responses.sort_by(|a, b| a.status_code.cmp(&b.status_code));
+    let is_default = |ir: &IntegrationResponse| ir.selection_pattern.as_deref().is_none_or(str::is_empty);
+    selector
+        .and_then(|selector| {
+            responses.iter().find(|ir| {
+                !is_default(ir)
+                    && ir
+                        .selection_pattern
+                        .as_deref()
+                        .is_some_and(|p| pattern::full_match(p, selector).unwrap_or(false))
+            })
+        })
+        .or_else(|| responses.iter().find(|ir| is_default(ir)))
+        .copied()
+}
+
+/// Builds the method response from an integration response: renders its
+/// `application/json` template against the integration's output, applies
+/// `$context.responseOverride` and maps `method.response.header.*`
+/// parameters.
+pub fn map_response(ir: &IntegrationResponse, mapping: &MappingContext, body: &str) -> ExecuteResponse {
+    let mut status = ir.status_code.parse().unwrap_or(200);
+    let mut headers = vec![("content-type".to_string(), "application/json".to_string())];
+    let template = ir.response_templates.get("application/json").or_else(|| {
+        (ir.response_templates.len() == 1)
+            .then(|| ir.response_templates.values().next())
+            .flatten()
+    });
+    let output = match template {
+        Some(template) => match mapping.render(template, body) {
+            Ok(rendered) => {
+                let overrides = &rendered.context["responseOverride"];
+                if let Some(s) = overrides["status"].as_u64().and_then(|s| u16::try_from(s).ok()) {
+                    status = s;
+                }
+                if let Some(h) = overrides["header"].as_object() {
+                    for (name, value) in h {
+                        set_header(&mut headers, name, value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string()));
+                    }
+                }
+                rendered.body
+            }
+            Err(_) => return ExecuteResponse::message(500, "Internal server error"),
+        },
+        None => body.to_string(),
+    };
+    for (target, source) in &ir.response_parameters {
+        let Some(name) = target.strip_prefix("method.response.header.") else {
+            continue;
+        };
+        let value = if let Some(literal) = source.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+            Some(literal.to_string())
+        } else if source == "integration.response.body" {
+            Some(body.to_string())
+        } else if let Some(path) = source.strip_prefix("integration.response.body.") {
+            let parsed = serde_json::from_str(body).unwrap_or(Value::Null);
+            match vtl::json_path(&parsed, &format!("$.{path}")) {
+                Value::Null => None,
+                Value::String(s) => Some(s),
+                other => Some(other.to_string()),
+            }
+        } else {
+            None
+        };
+        if let Some(value) = value {
+            set_header(&mut headers, name, value);
+        }
+    }
+    ExecuteResponse { status, headers, body: output.into_bytes() }
+}
+
+fn set_header(headers: &mut Vec<(String, String)>, name: &str, value: String) {
+    headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+    headers.push((name.to_string(), value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(id: &str, path: &str) -> Resource {
+        Resource {
+            id: id.to_string(),
+            parent_id: None,
+            path_part: None,
+            path: path.to_string(),
+            resource_methods: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_match_resource_prefers_literal_segments() {
+        let resources: HashMap<String, Resource> = [
+            resource("root", "/"),
+            resource("a", "/pets/{id}"),
+            resource("b", "/pets/mine"),
+            resource("c", "/{proxy+}"),
+        ]
+        .into_iter()
+        .map(|r| (r.id.clone(), r))
+        .collect();
+        let (r, params) = match_resource(&resources, "/pets/mine").unwrap();
+        assert_eq!(r.id, "b");
+        assert!(params.is_empty());
+        let (r, params) = match_resource(&resources, "/pets/7").unwrap();
+        assert_eq!(r.id, "a");
+        assert_eq!(params["id"], "7");
+        let (r, params) = match_resource(&resources, "/a/b/c").unwrap();
+        assert_eq!(r.id, "c");
+        assert_eq!(params["proxy"], "a/b/c");
+        assert_eq!(match_resource(&resources, "/").unwrap().0.id, "root");
+    }
+
+    #[test]
+    fn test_evaluate_policy() {
+        let arn = "arn:aws:execute-api:us-east-1:123456789012:api/prod/GET/pets";
+        let policy = |effect: &str, resource: &str| {
+            json!({"policyDocument": {"Statement": [{
+                "Action": "execute-api:Invoke", "Effect": effect, "Resource": resource
+            }]}})
+        };
+        assert_eq!(evaluate_policy(&policy("Allow", arn), arn), PolicyEffect::Allow);
+        assert_eq!(
+            evaluate_policy(&policy("Allow", "arn:aws:execute-api:us-east-1:*:api/*/GET/*"), arn),
+            PolicyEffect::Allow
+        );
+        assert_eq!(evaluate_policy(&policy("Deny", "*"), arn), PolicyEffect::Deny);
+        assert_eq!(evaluate_policy(&policy("Allow", "arn:other"), arn), PolicyEffect::NoMatch);
+    }
+}
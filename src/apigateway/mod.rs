@@ -1,5 +1,8 @@
 pub mod api;
 pub mod error;
+pub mod execute;
+pub mod pattern;
 pub mod server;
 pub mod state;
 pub mod types;
+pub mod vtl;
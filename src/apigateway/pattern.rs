@@ -0,0 +1,32 @@
+use regex::Regex;
+
+/// Matches integration response `selectionPattern`s and `String.matches()`
+/// in mapping templates. Matches are always against the whole input, as in
+/// API Gateway, so the pattern is anchored at both ends.
+pub fn full_match(pattern: &str, input: &str) -> Result<bool, String> {
+    let regex = Regex::new(&format!("^(?:{pattern})$")).map_err(|e| format!("Invalid pattern {pattern}: {e}"))?;
+    Ok(regex.is_match(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_patterns() {
+        assert!(full_match("4\\d{2}", "404").unwrap());
+        assert!(!full_match("4\\d{2}", "4040").unwrap());
+        assert!(full_match(".*Not Found.*", "Error: Not Found here").unwrap());
+        assert!(full_match("2\\d\\d|3[0-9]{2}", "302").unwrap());
+        assert!(full_match("(ab)+c?", "ababc").unwrap());
+        assert!(!full_match("[^a-z]+", "ABc").unwrap());
+        assert!(full_match("", "").unwrap());
+        assert!(full_match("(a|b)*", "").unwrap());
+        assert!(full_match("(?:x*)*y", "xxxy").unwrap());
+        assert!(full_match("a|b", "b").unwrap());
+        assert!(!full_match("a|b", "ab").unwrap());
+        assert!(full_match("\\w+?@.+", "user@example.com").unwrap());
+        assert!(!full_match(".+", "multi\nline").unwrap());
+        assert!(full_match("a(b", "ab").is_err());
+    }
+}
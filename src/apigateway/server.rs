@@ -1,13 +1,16 @@
 use std::sync::Arc;
 
+use std::collections::HashMap;
+
 use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{get, post, put};
+use axum::routing::{any, get, post, put};
 use axum::{Json, Router};
 use serde::Deserialize;
 
 use super::error::ApiGatewayError;
+use super::execute::ExecuteRequest;
 use super::state::ApiGatewayState;
 use super::types::*;
 
@@ -261,6 +264,80 @@ async fn delete_stage_handler(
     Ok(StatusCode::ACCEPTED.into_response())
 }
 
+// --- Authorizer handlers ---
+
+async fn create_authorizer_handler(
+    State(state): State<Arc<ApiGatewayState>>,
+    Path(rest_api_id): Path<String>,
+    Json(req): Json<CreateAuthorizerRequest>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.create_authorizer(&rest_api_id, req).await?;
+    Ok(json_response(StatusCode::CREATED, &resp))
+}
+
+async fn get_authorizers_handler(
+    State(state): State<Arc<ApiGatewayState>>,
+    Path(rest_api_id): Path<String>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_authorizers(&rest_api_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn get_authorizer_handler(
+    State(state): State<Arc<ApiGatewayState>>,
+    Path((rest_api_id, authorizer_id)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_authorizer(&rest_api_id, &authorizer_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn update_authorizer_handler(
+    State(state): State<Arc<ApiGatewayState>>,
+    Path((rest_api_id, authorizer_id)): Path<(String, String)>,
+    Json(req): Json<UpdateAuthorizerRequest>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.update_authorizer(&rest_api_id, &authorizer_id, req).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn delete_authorizer_handler(
+    State(state): State<Arc<ApiGatewayState>>,
+    Path((rest_api_id, authorizer_id)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    state.delete_authorizer(&rest_api_id, &authorizer_id).await?;
+    Ok(StatusCode::ACCEPTED.into_response())
+}
+
+// --- Execution handler ---
+
+async fn execute_handler(
+    State(state): State<Arc<ApiGatewayState>>,
+    Path(params): Path<HashMap<String, String>>,
+    method: Method,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    body: axum::body::Bytes,
+) -> Result<Response, ApiGatewayError> {
+    let req = ExecuteRequest {
+        http_method: method.as_str().to_string(),
+        path: format!("/{}", params.get("path").map(String::as_str).unwrap_or_default()),
+        headers: headers
+            .iter()
+            .filter_map(|(k, v)| Some((k.as_str().to_string(), v.to_str().ok()?.to_string())))
+            .collect(),
+        query,
+        body: body.to_vec(),
+    };
+    let resp = state.execute(&params["rest_api_id"], &params["stage"], req).await?;
+    let mut response = (StatusCode::from_u16(resp.status).unwrap_or(StatusCode::OK), resp.body).into_response();
+    for (name, value) in resp.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    Ok(response)
+}
+
 // --- Tag handlers ---
 
 async fn tag_resource_handler(
@@ -369,6 +446,21 @@ pub fn create_router(state: Arc<ApiGatewayState>) -> Router {
                 .patch(update_stage_handler)
                 .delete(delete_stage_handler),
         )
+        // Authorizers
+        .route(
+            "/restapis/{rest_api_id}/authorizers",
+            post(create_authorizer_handler).get(get_authorizers_handler),
+        )
+        .route(
+            "/restapis/{rest_api_id}/authorizers/{authorizer_id}",
+            get(get_authorizer_handler)
+                .patch(update_authorizer_handler)
+                .delete(delete_authorizer_handler),
+        )
+        // Invocation of deployed stages
+        .route("/restapis/{rest_api_id}/{stage}/_user_request_", any(execute_handler))
+        .route("/restapis/{rest_api_id}/{stage}/_user_request_/", any(execute_handler))
+        .route("/restapis/{rest_api_id}/{stage}/_user_request_/{*path}", any(execute_handler))
         // Tags
         .route(
             "/tags/{rest_api_id}",
@@ -376,6 +468,7 @@ pub fn create_router(state: Arc<ApiGatewayState>) -> Router {
                 .delete(untag_resource_handler)
                 .get(get_tags_handler),
         )
+        .merge(crate::healthcheck::router("apigateway"))
         .with_state(state)
 }
 
@@ -395,6 +488,87 @@ mod tests {
         serde_json::from_slice(&body).unwrap()
     }
 
+    #[tokio::test]
+    async fn test_authorizer_and_proxy_with_stubbed_lambdas_through_routers() {
+        use serde_json::json;
+
+        let lambda = Arc::new(crate::lambda::state::LambdaState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        ));
+        let lambda_app = crate::lambda::server::create_router(lambda.clone());
+        let policy = json!({
+            "mode": "return",
+            "result": {
+                "principalId": "user-1",
+                "policyDocument": {
+                    "Version": "2012-10-17",
+                    "Statement": [{ "Action": "execute-api:Invoke", "Effect": "Allow", "Resource": "*" }]
+                }
+            }
+        });
+        crate::lambda::server::create_stubbed_function(&lambda_app, "auth", policy).await;
+        let backend = json!({ "mode": "return", "result": { "statusCode": 200, "body": "hello" } });
+        crate::lambda::server::create_stubbed_function(&lambda_app, "backend", backend).await;
+        let bus = Arc::new(crate::bus::ServiceBus::new());
+        bus.attach_lambda(lambda);
+        let state = ApiGatewayState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let app = create_router(Arc::new(state));
+
+        let send = |method: &str, uri: String, body: serde_json::Value| {
+            let app = app.clone();
+            let req = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", "token")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            async move { app.oneshot(req).await.unwrap() }
+        };
+        let uri = |name: &str| {
+            format!(
+                "arn:aws:apigateway:us-east-1:lambda:path/2015-03-31/functions/arn:aws:lambda:us-east-1:123456789012:function:{name}/invocations"
+            )
+        };
+        let api = extract_body(send("POST", "/restapis".to_string(), json!({ "name": "pets" })).await).await;
+        let api_id = api["id"].as_str().unwrap().to_string();
+        let resources = extract_body(send("GET", format!("/restapis/{api_id}/resources"), json!({})).await).await;
+        let root_id = resources["item"][0]["id"].as_str().unwrap().to_string();
+        let resource = send("POST", format!("/restapis/{api_id}/resources/{root_id}"), json!({ "pathPart": "pets" }));
+        let pets_id = extract_body(resource.await).await["id"].as_str().unwrap().to_string();
+        let authorizer = json!({
+            "name": "token-auth",
+            "type": "TOKEN",
+            "authorizerUri": uri("auth"),
+            "authorizerResultTtlInSeconds": 0,
+        });
+        let authorizer = extract_body(send("POST", format!("/restapis/{api_id}/authorizers"), authorizer).await).await;
+        let method = format!("/restapis/{api_id}/resources/{pets_id}/methods/GET");
+        let body = json!({ "authorizationType": "CUSTOM", "authorizerId": authorizer["id"] });
+        assert!(send("PUT", method.clone(), body).await.status().is_success());
+        let body = json!({ "type": "AWS_PROXY", "httpMethod": "POST", "uri": uri("backend") });
+        assert!(send("PUT", format!("{method}/integration"), body).await.status().is_success());
+        let deployment = send("POST", format!("/restapis/{api_id}/deployments"), json!({ "stageName": "prod" }));
+        assert!(deployment.await.status().is_success());
+
+        let invoke = format!("/restapis/{api_id}/prod/_user_request_/pets");
+        let resp = send("GET", invoke.clone(), json!({})).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"hello");
+
+        // An authorizer that fails with "Unauthorized" rejects the call.
+        let stub = json!({ "mode": "error", "errorMessage": "Unauthorized" });
+        let lambda_stub = Request::builder()
+            .method("PUT")
+            .uri("/_aws/lambda/functions/auth/stub")
+            .body(Body::from(stub.to_string()))
+            .unwrap();
+        assert_eq!(lambda_app.oneshot(lambda_stub).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(send("GET", invoke, json!({})).await.status(), StatusCode::UNAUTHORIZED);
+    }
+
     #[tokio::test]
     async fn test_list_rest_apis_empty() {
         let app = create_router(new_state());
@@ -763,4 +937,45 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_execute_mock_integration() {
+        let state = new_state();
+        let api = state
+            .create_rest_api(CreateRestApiRequest { name: "api".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        let root_id = state.get_resources(&api.id).await.unwrap().items[0].id.clone();
+        state
+            .put_method(&api.id, &root_id, "GET", PutMethodRequest {
+                authorization_type: "NONE".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .put_integration(&api.id, &root_id, "GET", PutIntegrationRequest {
+                integration_type: "MOCK".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .create_deployment(&api.id, CreateDeploymentRequest {
+                stage_name: Some("prod".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let app = create_router(state);
+        for (uri, expected) in [
+            (format!("/restapis/{}/prod/_user_request_/", api.id), StatusCode::OK),
+            (format!("/restapis/{}/prod/_user_request_/missing", api.id), StatusCode::FORBIDDEN),
+        ] {
+            let req = Request::builder().method("GET").uri(uri).body(Body::empty()).unwrap();
+            let resp = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), expected);
+        }
+    }
 }
@@ -1,12 +1,18 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use serde_json::{Map, Value};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
-use super::api::{Deployment, Integration, IntegrationResponse, Method, MethodResponse, Resource, RestApi, Stage};
+use super::api::{
+    Authorizer, Deployment, Integration, IntegrationResponse, Method, MethodResponse, Resource, RestApi, Stage,
+};
 use super::error::ApiGatewayError;
+use super::execute::{self, ExecuteRequest, ExecuteResponse, InvocationContext, PolicyEffect};
+use super::pattern;
 use super::types::*;
+use crate::bus::ServiceBus;
 
 fn now() -> f64 {
     std::time::SystemTime::now()
@@ -24,14 +30,22 @@ fn short_id() -> String {
         .collect()
 }
 
+struct CachedAuthorization {
+    result: Value,
+    expires_at: f64,
+}
+
 struct ApiGatewayStateInner {
     apis: HashMap<String, RestApi>,
-    _account_id: String,
-    _region: String,
+    // Authorizer results keyed by "{api}/{authorizer}/{identity}".
+    authorizer_cache: HashMap<String, CachedAuthorization>,
+    account_id: String,
+    region: String,
 }
 
 pub struct ApiGatewayState {
     inner: Arc<Mutex<ApiGatewayStateInner>>,
+    bus: Arc<ServiceBus>,
 }
 
 impl ApiGatewayState {
@@ -39,12 +53,26 @@ impl ApiGatewayState {
         ApiGatewayState {
             inner: Arc::new(Mutex::new(ApiGatewayStateInner {
                 apis: HashMap::new(),
-                _account_id: account_id,
-                _region: region,
+                authorizer_cache: HashMap::new(),
+                account_id,
+                region,
             })),
+            bus: Arc::new(ServiceBus::new()),
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
     // --- REST APIs ---
 
     pub async fn create_rest_api(
@@ -404,13 +432,18 @@ impl ApiGatewayState {
             ApiGatewayError::NotFoundException("Integration not found.".to_string())
         })?;
 
+        if let Some(pattern) = req.selection_pattern.as_deref() {
+            pattern::full_match(pattern, "").map_err(ApiGatewayError::BadRequestException)?;
+        }
         let ir = IntegrationResponse {
             status_code: status_code.to_string(),
+            selection_pattern: req.selection_pattern,
             response_parameters: req.response_parameters,
             response_templates: req.response_templates,
         };
         let output = IntegrationResponseOutput {
             status_code: ir.status_code.clone(),
+            selection_pattern: ir.selection_pattern.clone(),
             response_parameters: ir.response_parameters.clone(),
             response_templates: ir.response_templates.clone(),
         };
@@ -619,6 +652,315 @@ impl ApiGatewayState {
         Ok(())
     }
 
+    // --- Authorizers ---
+
+    pub async fn create_authorizer(
+        &self,
+        rest_api_id: &str,
+        req: CreateAuthorizerRequest,
+    ) -> Result<AuthorizerOutput, ApiGatewayError> {
+        if !matches!(req.authorizer_type.as_str(), "TOKEN" | "REQUEST") {
+            return Err(ApiGatewayError::BadRequestException(format!(
+                "Unsupported authorizer type: {}",
+                req.authorizer_type
+            )));
+        }
+        if req.authorizer_uri.as_deref().and_then(execute::lambda_arn).is_none() {
+            return Err(ApiGatewayError::BadRequestException(
+                "authorizerUri must reference a Lambda function".to_string(),
+            ));
+        }
+        let ttl = req.authorizer_result_ttl_in_seconds.unwrap_or(300);
+        validate_authorizer_ttl(ttl)?;
+        let identity_source = req.identity_source.or_else(|| {
+            (req.authorizer_type == "TOKEN").then(|| "method.request.header.Authorization".to_string())
+        });
+
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, rest_api_id)?;
+        let authorizer = Authorizer {
+            id: short_id(),
+            name: req.name,
+            authorizer_type: req.authorizer_type,
+            authorizer_uri: req.authorizer_uri,
+            identity_source,
+            identity_validation_expression: req.identity_validation_expression,
+            authorizer_result_ttl_in_seconds: ttl,
+        };
+        let output = authorizer_to_output(&authorizer);
+        api.authorizers.insert(authorizer.id.clone(), authorizer);
+        Ok(output)
+    }
+
+    pub async fn get_authorizers(&self, rest_api_id: &str) -> Result<AuthorizersOutput, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, rest_api_id)?;
+        let mut item: Vec<AuthorizerOutput> = api.authorizers.values().map(authorizer_to_output).collect();
+        item.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(AuthorizersOutput { item })
+    }
+
+    pub async fn get_authorizer(
+        &self,
+        rest_api_id: &str,
+        authorizer_id: &str,
+    ) -> Result<AuthorizerOutput, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, rest_api_id)?;
+        let authorizer = api.authorizers.get(authorizer_id).ok_or_else(|| authorizer_not_found(authorizer_id))?;
+        Ok(authorizer_to_output(authorizer))
+    }
+
+    pub async fn update_authorizer(
+        &self,
+        rest_api_id: &str,
+        authorizer_id: &str,
+        req: UpdateAuthorizerRequest,
+    ) -> Result<AuthorizerOutput, ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, rest_api_id)?;
+        let authorizer = api
+            .authorizers
+            .get_mut(authorizer_id)
+            .ok_or_else(|| authorizer_not_found(authorizer_id))?;
+
+        for op in req.patch_operations {
+            match (op.op.as_str(), op.path.as_str()) {
+                ("replace", "/name") => {
+                    if let Some(v) = op.value {
+                        authorizer.name = v;
+                    }
+                }
+                ("replace", "/authorizerUri") => {
+                    authorizer.authorizer_uri = op.value;
+                }
+                ("replace", "/identitySource") => {
+                    authorizer.identity_source = op.value;
+                }
+                ("replace", "/identityValidationExpression") => {
+                    authorizer.identity_validation_expression = op.value;
+                }
+                ("replace", "/authorizerResultTtlInSeconds") => {
+                    let ttl = op.value.as_deref().unwrap_or("0").parse().map_err(|_| {
+                        ApiGatewayError::BadRequestException("Invalid authorizerResultTtlInSeconds".to_string())
+                    })?;
+                    validate_authorizer_ttl(ttl)?;
+                    authorizer.authorizer_result_ttl_in_seconds = ttl;
+                }
+                _ => {}
+            }
+        }
+        let output = authorizer_to_output(authorizer);
+        let prefix = format!("{rest_api_id}/{authorizer_id}/");
+        state.authorizer_cache.retain(|key, _| !key.starts_with(&prefix));
+        Ok(output)
+    }
+
+    pub async fn delete_authorizer(&self, rest_api_id: &str, authorizer_id: &str) -> Result<(), ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, rest_api_id)?;
+        if api.authorizers.remove(authorizer_id).is_none() {
+            return Err(authorizer_not_found(authorizer_id));
+        }
+        let prefix = format!("{rest_api_id}/{authorizer_id}/");
+        state.authorizer_cache.retain(|key, _| !key.starts_with(&prefix));
+        Ok(())
+    }
+
+    // --- Execution ---
+
+    /// Runs a request against a deployed stage: matches the resource and
+    /// method, applies a custom authorizer and calls the integration.
+    pub async fn execute(
+        &self,
+        rest_api_id: &str,
+        stage_name: &str,
+        req: ExecuteRequest,
+    ) -> Result<ExecuteResponse, ApiGatewayError> {
+        let (account_id, region, stage_variables, matched) = {
+            let state = self.inner.lock().await;
+            let api = get_api(&state.apis, rest_api_id)?;
+            let stage = api.stages.get(stage_name).ok_or_else(|| {
+                ApiGatewayError::NotFoundException(format!("Invalid Stage identifier specified: {}", stage_name))
+            })?;
+            let matched = execute::match_resource(&api.resources, &req.path).and_then(|(resource, params)| {
+                let method = resource
+                    .resource_methods
+                    .get(&req.http_method)
+                    .or_else(|| resource.resource_methods.get("ANY"))?;
+                let authorizer = method.authorizer_id.as_ref().and_then(|id| api.authorizers.get(id)).cloned();
+                Some((resource.path.clone(), params, method.clone(), authorizer))
+            });
+            (state.account_id.clone(), state.region.clone(), stage.variables.clone(), matched)
+        };
+        let Some((resource_path, path_parameters, method, authorizer)) = matched else {
+            return Ok(ExecuteResponse::message(403, "Missing Authentication Token"));
+        };
+        let method_arn =
+            execute::method_arn(&region, &account_id, rest_api_id, stage_name, &req.http_method, &req.path);
+        let ctx = InvocationContext {
+            api_id: rest_api_id,
+            account_id: &account_id,
+            stage: stage_name,
+            resource_path: &resource_path,
+            method_arn: &method_arn,
+            path_parameters: &path_parameters,
+            stage_variables: &stage_variables,
+        };
+
+        let authorizer_context = if method.authorization_type == "CUSTOM" {
+            let Some(authorizer) = authorizer else {
+                return Ok(ExecuteResponse::message(500, "Internal server error"));
+            };
+            match self.authorize(rest_api_id, &authorizer, &ctx, &req).await {
+                Ok(context) => context,
+                Err(denied) => return Ok(denied),
+            }
+        } else {
+            Map::new()
+        };
+
+        let Some(integration) = method.method_integration else {
+            return Ok(ExecuteResponse::message(500, "Internal server error"));
+        };
+        Ok(self.integrate(&integration, &ctx, &req, &authorizer_context).await)
+    }
+
+    async fn authorize(
+        &self,
+        rest_api_id: &str,
+        authorizer: &Authorizer,
+        ctx: &InvocationContext<'_>,
+        req: &ExecuteRequest,
+    ) -> Result<Map<String, Value>, ExecuteResponse> {
+        let identity = execute::identity(authorizer, req, ctx.stage_variables)
+            .ok_or_else(|| ExecuteResponse::message(401, "Unauthorized"))?;
+        let cache_key = (!identity.is_empty() && authorizer.authorizer_result_ttl_in_seconds > 0)
+            .then(|| format!("{rest_api_id}/{}/{}", authorizer.id, identity.join(",")));
+        let cached = match &cache_key {
+            Some(key) => {
+                let state = self.inner.lock().await;
+                state.authorizer_cache.get(key).filter(|c| c.expires_at > now()).map(|c| c.result.clone())
+            }
+            None => None,
+        };
+
+        let result = match cached {
+            Some(result) => result,
+            None => {
+                let function = authorizer
+                    .authorizer_uri
+                    .as_deref()
+                    .and_then(execute::lambda_arn)
+                    .ok_or_else(|| ExecuteResponse::message(500, "Internal server error"))?;
+                let event = execute::authorizer_event(authorizer, ctx, req, &identity);
+                let result = match self.bus.invoke_lambda(function, event).await {
+                    Ok(result) => result,
+                    Err(e) if e == "Unauthorized" => return Err(ExecuteResponse::message(401, "Unauthorized")),
+                    Err(_) => return Err(ExecuteResponse::message(500, "Internal server error")),
+                };
+                if let Some(key) = cache_key {
+                    let mut state = self.inner.lock().await;
+                    let now = now();
+                    state.authorizer_cache.retain(|_, c| c.expires_at > now);
+                    state.authorizer_cache.insert(key, CachedAuthorization {
+                        result: result.clone(),
+                        expires_at: now + authorizer.authorizer_result_ttl_in_seconds as f64,
+                    });
+                }
+                result
+            }
+        };
+
+        match execute::evaluate_policy(&result, ctx.method_arn) {
+            PolicyEffect::Allow => Ok(execute::authorizer_context(&result)),
+            PolicyEffect::Deny => Err(ExecuteResponse::message(
+                403,
+                "User is not authorized to access this resource with an explicit deny",
+            )),
+            PolicyEffect::NoMatch => Err(ExecuteResponse::message(403, "User is not authorized to access this resource")),
+        }
+    }
+
+    async fn integrate(
+        &self,
+        integration: &Integration,
+        ctx: &InvocationContext<'_>,
+        req: &ExecuteRequest,
+        authorizer_context: &Map<String, Value>,
+    ) -> ExecuteResponse {
+        let function = integration.uri.as_deref().and_then(execute::lambda_arn);
+        match (integration.integration_type.as_str(), function) {
+            ("MOCK", _) => self.integrate_mapped(integration, None, ctx, req, authorizer_context).await,
+            ("AWS", Some(function)) => {
+                self.integrate_mapped(integration, Some(function), ctx, req, authorizer_context).await
+            }
+            ("AWS_PROXY", Some(function)) => {
+                let event = execute::proxy_event(ctx, req, authorizer_context);
+                match self.bus.invoke_lambda(function, event).await {
+                    Ok(result) => execute::proxy_response(&result),
+                    Err(_) => ExecuteResponse::message(502, "Internal server error"),
+                }
+            }
+            (other, _) => ExecuteResponse::message(500, &format!("Integration type {other} is not supported")),
+        }
+    }
+
+    /// Non-proxy integrations run the request through its mapping template,
+    /// call the backend and map the result back through the integration
+    /// response selected by `selectionPattern`. For MOCK the selector is the
+    /// `statusCode` of the mapped request, and the mapped request also serves
+    /// as the integration output; for Lambda it is the function's error
+    /// message, if any.
+    async fn integrate_mapped(
+        &self,
+        integration: &Integration,
+        function: Option<&str>,
+        ctx: &InvocationContext<'_>,
+        req: &ExecuteRequest,
+        authorizer_context: &Map<String, Value>,
+    ) -> ExecuteResponse {
+        let mapping = execute::MappingContext::new(ctx, req, authorizer_context);
+        let request_body = match execute::map_request(integration, &mapping, req) {
+            Ok(body) => body,
+            Err(response) => return response,
+        };
+        let (selector, output) = match function {
+            None => {
+                let status = serde_json::from_str::<Value>(&request_body)
+                    .ok()
+                    .and_then(|v| match &v["statusCode"] {
+                        Value::Number(n) => Some(n.to_string()),
+                        Value::String(s) => Some(s.clone()),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| "200".to_string());
+                if integration.integration_responses.is_empty() {
+                    return ExecuteResponse { status: 200, headers: Vec::new(), body: Vec::new() };
+                }
+                (Some(status), request_body)
+            }
+            Some(function) => {
+                let payload = serde_json::from_str(&request_body).unwrap_or(Value::Object(Map::new()));
+                let result = self.bus.invoke_lambda(function, payload).await;
+                if integration.integration_responses.is_empty() {
+                    return match result {
+                        Ok(result) => ExecuteResponse::json(200, &result),
+                        Err(_) => ExecuteResponse::message(502, "Internal server error"),
+                    };
+                }
+                match result {
+                    Ok(result) => (None, result.to_string()),
+                    Err(message) => (Some(message.clone()), serde_json::json!({ "errorMessage": message }).to_string()),
+                }
+            }
+        };
+        match execute::select_integration_response(integration, selector.as_deref()) {
+            Some(ir) => execute::map_response(ir, &mapping, &output),
+            None => ExecuteResponse::message(500, "Internal server error"),
+        }
+    }
+
     // --- Tags ---
 
     pub async fn tag_resource(
@@ -728,8 +1070,33 @@ fn get_method_mut<'a>(
         })
 }
 
+fn authorizer_not_found(authorizer_id: &str) -> ApiGatewayError {
+    ApiGatewayError::NotFoundException(format!("Invalid Authorizer identifier specified: {}", authorizer_id))
+}
+
+fn validate_authorizer_ttl(ttl: u64) -> Result<(), ApiGatewayError> {
+    if ttl > 3600 {
+        return Err(ApiGatewayError::BadRequestException(
+            "authorizerResultTtlInSeconds must be between 0 and 3600".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 // --- Conversion helpers ---
 
+fn authorizer_to_output(authorizer: &Authorizer) -> AuthorizerOutput {
+    AuthorizerOutput {
+        id: authorizer.id.clone(),
+        name: authorizer.name.clone(),
+        authorizer_type: authorizer.authorizer_type.clone(),
+        authorizer_uri: authorizer.authorizer_uri.clone(),
+        identity_source: authorizer.identity_source.clone(),
+        identity_validation_expression: authorizer.identity_validation_expression.clone(),
+        authorizer_result_ttl_in_seconds: authorizer.authorizer_result_ttl_in_seconds,
+    }
+}
+
 fn api_to_output(api: &RestApi) -> RestApiOutput {
     RestApiOutput {
         id: api.id.clone(),
@@ -798,6 +1165,7 @@ fn integration_to_output(integration: &Integration) -> IntegrationOutput {
                     k.clone(),
                     IntegrationResponseOutput {
                         status_code: v.status_code.clone(),
+                        selection_pattern: v.selection_pattern.clone(),
                         response_parameters: v.response_parameters.clone(),
                         response_templates: v.response_templates.clone(),
                     },
@@ -960,4 +1328,265 @@ mod tests {
         let result = state.get_tags(&api_id).await.unwrap();
         assert_eq!(result.len(), 1);
     }
+
+    async fn authorizer_setup() -> (ApiGatewayState, String, Arc<std::sync::atomic::AtomicUsize>) {
+        use crate::lambda::state::{FunctionHandler, LambdaState};
+        use serde_json::json;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let lambda = Arc::new(LambdaState::new("123456789012".to_string(), "us-east-1".to_string()));
+        for name in ["auth", "backend"] {
+            lambda
+                .create_function(serde_json::from_value(json!({
+                    "FunctionName": name,
+                    "Role": "arn:aws:iam::123456789012:role/r",
+                    "Code": {}
+                }))
+                .unwrap())
+                .await
+                .unwrap();
+        }
+        let calls = Arc::new(AtomicUsize::new(0));
+        let auth: FunctionHandler = {
+            let calls = calls.clone();
+            Arc::new(move |event| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    let effect = match event["authorizationToken"].as_str() {
+                        Some("allow-token") => "Allow",
+                        Some("deny-token") => "Deny",
+                        _ => return Err("Unauthorized".to_string()),
+                    };
+                    Ok(json!({
+                        "principalId": "user-1",
+                        "policyDocument": {
+                            "Version": "2012-10-17",
+                            "Statement": [{"Action": "execute-api:Invoke", "Effect": effect, "Resource": event["methodArn"]}]
+                        },
+                        "context": {"tenant": "acme"}
+                    }))
+                })
+            })
+        };
+        lambda.set_handler("auth", auth).await.unwrap();
+        let backend: FunctionHandler = Arc::new(|event| {
+            Box::pin(async move {
+                Ok(json!({"statusCode": 200, "body": event["requestContext"]["authorizer"].to_string()}))
+            })
+        });
+        lambda.set_handler("backend", backend).await.unwrap();
+        let bus = Arc::new(ServiceBus::new());
+        bus.attach_lambda(lambda);
+
+        let state = make_state().with_bus(bus);
+        let api_id = create_api(&state).await;
+        let uri = |name: &str| {
+            format!(
+                "arn:aws:apigateway:us-east-1:lambda:path/2015-03-31/functions/arn:aws:lambda:us-east-1:123456789012:function:{name}/invocations"
+            )
+        };
+        let authorizer = state
+            .create_authorizer(&api_id, CreateAuthorizerRequest {
+                name: "token-auth".to_string(),
+                authorizer_type: "TOKEN".to_string(),
+                authorizer_uri: Some(uri("auth")),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(authorizer.identity_source.as_deref(), Some("method.request.header.Authorization"));
+        let root_id = state.get_resources(&api_id).await.unwrap().items[0].id.clone();
+        let pets = state
+            .create_resource(&api_id, &root_id, CreateResourceRequest { path_part: "pets".to_string() })
+            .await
+            .unwrap();
+        state
+            .put_method(&api_id, &pets.id, "GET", PutMethodRequest {
+                authorization_type: "CUSTOM".to_string(),
+                authorizer_id: Some(authorizer.id),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .put_integration(&api_id, &pets.id, "GET", PutIntegrationRequest {
+                integration_type: "AWS_PROXY".to_string(),
+                uri: Some(uri("backend")),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .create_deployment(&api_id, CreateDeploymentRequest {
+                stage_name: Some("prod".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        (state, api_id, calls)
+    }
+
+    fn get_pets(token: Option<&str>) -> ExecuteRequest {
+        ExecuteRequest {
+            http_method: "GET".to_string(),
+            path: "/pets".to_string(),
+            headers: token.map(|t| ("authorization".to_string(), t.to_string())).into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_authorizer_allows_and_denies() {
+        use std::sync::atomic::Ordering;
+
+        let (state, api_id, calls) = authorizer_setup().await;
+        let allowed = state.execute(&api_id, "prod", get_pets(Some("allow-token"))).await.unwrap();
+        assert_eq!(allowed.status, 200);
+        let context: Value = serde_json::from_slice(&allowed.body).unwrap();
+        assert_eq!(context["tenant"], "acme");
+        assert_eq!(context["principalId"], "user-1");
+
+        let denied = state.execute(&api_id, "prod", get_pets(Some("deny-token"))).await.unwrap();
+        assert_eq!(denied.status, 403);
+        let unknown = state.execute(&api_id, "prod", get_pets(Some("bogus"))).await.unwrap();
+        assert_eq!(unknown.status, 401);
+        let missing = state.execute(&api_id, "prod", get_pets(None)).await.unwrap();
+        assert_eq!(missing.status, 401);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Results are cached per token for the authorizer TTL.
+        let again = state.execute(&api_id, "prod", get_pets(Some("allow-token"))).await.unwrap();
+        assert_eq!(again.status, 200);
+        let denied_again = state.execute(&api_id, "prod", get_pets(Some("deny-token"))).await.unwrap();
+        assert_eq!(denied_again.status, 403);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mock_integration_mapping_templates() {
+        let state = make_state();
+        let api_id = create_api(&state).await;
+        let root_id = state.get_resources(&api_id).await.unwrap().items[0].id.clone();
+        let greet = state
+            .create_resource(&api_id, &root_id, CreateResourceRequest { path_part: "greet".to_string() })
+            .await
+            .unwrap();
+        state
+            .put_method(&api_id, &greet.id, "POST", PutMethodRequest {
+                authorization_type: "NONE".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .put_integration(&api_id, &greet.id, "POST", PutIntegrationRequest {
+                integration_type: "MOCK".to_string(),
+                request_templates: HashMap::from([(
+                    "application/json".to_string(),
+                    r#"{"statusCode": #if(!$input.path('$.name'))400#{else}200#end, "name": "$util.escapeJavaScript($input.path('$.name'))"}"#
+                        .to_string(),
+                )]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let ok = state
+            .put_integration_response(&api_id, &greet.id, "POST", "200", PutIntegrationResponseRequest {
+                response_parameters: HashMap::from([(
+                    "method.response.header.X-Name".to_string(),
+                    "integration.response.body.name".to_string(),
+                )]),
+                response_templates: HashMap::from([(
+                    "application/json".to_string(),
+                    r#"{"greeting": "Hello, $input.path('$.name')!", "stage": "$context.stage"}"#.to_string(),
+                )]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(ok.selection_pattern, None);
+        state
+            .put_integration_response(&api_id, &greet.id, "POST", "400", PutIntegrationResponseRequest {
+                selection_pattern: Some("4\\d{2}".to_string()),
+                response_templates: HashMap::from([(
+                    "application/json".to_string(),
+                    r#"{"error": "name is required"}"#.to_string(),
+                )]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .create_deployment(&api_id, CreateDeploymentRequest {
+                stage_name: Some("prod".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let post = |body: &str| ExecuteRequest {
+            http_method: "POST".to_string(),
+            path: "/greet".to_string(),
+            body: body.as_bytes().to_vec(),
+            ..Default::default()
+        };
+        let resp = state.execute(&api_id, "prod", post(r#"{"name": "Ada"}"#)).await.unwrap();
+        assert_eq!(resp.status, 200);
+        let body: Value = serde_json::from_slice(&resp.body).unwrap();
+        assert_eq!(body["greeting"], "Hello, Ada!");
+        assert_eq!(body["stage"], "prod");
+        assert!(resp.headers.contains(&("X-Name".to_string(), "Ada".to_string())));
+
+        let resp = state.execute(&api_id, "prod", post("{}")).await.unwrap();
+        assert_eq!(resp.status, 400);
+        let body: Value = serde_json::from_slice(&resp.body).unwrap();
+        assert_eq!(body["error"], "name is required");
+
+        // Unknown content types are rejected once passthrough is disabled.
+        state
+            .put_integration(&api_id, &greet.id, "POST", PutIntegrationRequest {
+                integration_type: "MOCK".to_string(),
+                passthrough_behavior: Some("NEVER".to_string()),
+                request_templates: HashMap::from([("application/json".to_string(), "{}".to_string())]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let mut xml = post("<name>Ada</name>");
+        xml.headers.insert("content-type".to_string(), "application/xml".to_string());
+        let resp = state.execute(&api_id, "prod", xml).await.unwrap();
+        assert_eq!(resp.status, 415);
+    }
+
+    #[tokio::test]
+    async fn test_authorizer_crud() {
+        let (state, api_id, _) = authorizer_setup().await;
+        let listed = state.get_authorizers(&api_id).await.unwrap();
+        assert_eq!(listed.item.len(), 1);
+        let id = listed.item[0].id.clone();
+        let updated = state
+            .update_authorizer(&api_id, &id, UpdateAuthorizerRequest {
+                patch_operations: vec![PatchOperation {
+                    op: "replace".to_string(),
+                    path: "/authorizerResultTtlInSeconds".to_string(),
+                    value: Some("0".to_string()),
+                }],
+            })
+            .await
+            .unwrap();
+        assert_eq!(updated.authorizer_result_ttl_in_seconds, 0);
+        let invalid = state
+            .create_authorizer(&api_id, CreateAuthorizerRequest {
+                name: "bad".to_string(),
+                authorizer_type: "TOKEN".to_string(),
+                authorizer_uri: Some("not-a-lambda".to_string()),
+                ..Default::default()
+            })
+            .await;
+        assert!(invalid.is_err());
+        state.delete_authorizer(&api_id, &id).await.unwrap();
+        assert!(state.get_authorizer(&api_id, &id).await.is_err());
+        let missing = state.execute(&api_id, "prod", get_pets(Some("allow-token"))).await.unwrap();
+        assert_eq!(missing.status, 500);
+    }
 }
@@ -152,6 +152,8 @@ pub struct IntegrationOutput {
 #[serde(rename_all = "camelCase")]
 pub struct IntegrationResponseOutput {
     pub status_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_pattern: Option<String>,
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     pub response_parameters: HashMap<String, String>,
     #[serde(skip_serializing_if = "HashMap::is_empty")]
@@ -208,6 +210,8 @@ pub struct PutMethodResponseRequest {
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct PutIntegrationResponseRequest {
+    #[serde(default)]
+    pub selection_pattern: Option<String>,
     #[serde(default)]
     pub response_parameters: HashMap<String, String>,
     #[serde(default)]
@@ -304,3 +308,54 @@ pub struct UpdateStageRequest {
 pub struct TagResourceRequest {
     pub tags: HashMap<String, String>,
 }
+
+// --- Authorizer types ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizerOutput {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub authorizer_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorizer_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity_source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity_validation_expression: Option<String>,
+    pub authorizer_result_ttl_in_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizersOutput {
+    pub item: Vec<AuthorizerOutput>,
+}
+
+// --- CreateAuthorizer ---
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAuthorizerRequest {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub authorizer_type: String,
+    #[serde(default)]
+    pub authorizer_uri: Option<String>,
+    #[serde(default)]
+    pub identity_source: Option<String>,
+    #[serde(default)]
+    pub identity_validation_expression: Option<String>,
+    #[serde(default)]
+    pub authorizer_result_ttl_in_seconds: Option<u64>,
+}
+
+// --- UpdateAuthorizer ---
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAuthorizerRequest {
+    #[serde(default)]
+    pub patch_operations: Vec<PatchOperation>,
+}
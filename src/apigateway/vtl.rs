@@ -0,0 +1,1123 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::{Map, Number, Value};
+
+use super::pattern;
+
+/// The most items a `[from..to]` range may produce.
+const MAX_RANGE_LEN: usize = 10_000;
+
+/// Data exposed to a mapping template as `$input`, `$context` and
+/// `$stageVariables`.
+pub struct TemplateInput<'a> {
+    pub body: &'a str,
+    /// `{"path": {...}, "querystring": {...}, "header": {...}}`
+    pub params: Value,
+    pub context: Value,
+    pub stage_variables: Value,
+}
+
+pub struct Rendered {
+    pub body: String,
+    /// `$context` after rendering, including any `responseOverride` set by
+    /// the template.
+    pub context: Value,
+}
+
+/// Renders a Velocity mapping template as API Gateway does. Supports
+/// references with properties, indexes and method calls, `$input`,
+/// `$util`, `#set`, `#if`/`#elseif`/`#else`, `#foreach` and comments.
+pub fn render(template: &str, input: &TemplateInput) -> Result<Rendered, String> {
+    let mut parser = Parser { chars: template.chars().collect(), pos: 0, pending_condition: None };
+    let (nodes, terminator) = parser.nodes()?;
+    if let Some(t) = terminator {
+        return Err(format!("Unexpected #{t}"));
+    }
+    let mut vars = HashMap::new();
+    vars.insert("context".to_string(), input.context.clone());
+    vars.insert("stageVariables".to_string(), input.stage_variables.clone());
+    let mut renderer = Renderer {
+        vars,
+        body: input.body,
+        json_body: None,
+        params: &input.params,
+        out: String::new(),
+    };
+    renderer.render(&nodes)?;
+    Ok(Rendered {
+        body: renderer.out,
+        context: renderer.vars.remove("context").unwrap_or(Value::Null),
+    })
+}
+
+// --- Syntax tree ---
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Ref { reference: Reference, silent: bool, source: String },
+    Set(Reference, Expr),
+    If(Vec<(Expr, Vec<Node>)>, Vec<Node>),
+    Foreach(String, Expr, Vec<Node>),
+}
+
+#[derive(Debug)]
+struct Reference {
+    root: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Property(String),
+    Index(Expr),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug)]
+enum Expr {
+    Literal(Value),
+    Interpolated(Vec<Node>),
+    Ref(Reference),
+    List(Vec<Expr>),
+    Map(Vec<(Expr, Expr)>),
+    Range(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+// --- Parser ---
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    // Condition of the `#elseif` that ended the last block.
+    pending_condition: Option<Expr>,
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_ws();
+        if self.peek() != Some(c) {
+            return Err(format!("Expected '{c}' at position {}", self.pos));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(is_ident_char) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Parses template nodes until EOF or a block terminator (`end`, `else`,
+    /// `elseif`), which is returned; `#elseif` leaves its condition unparsed.
+    fn nodes(&mut self) -> Result<(Vec<Node>, Option<String>), String> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            if self.starts_with("##") {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+                self.pos += 1;
+                continue;
+            }
+            if self.starts_with("#*") {
+                while self.pos < self.chars.len() && !self.starts_with("*#") {
+                    self.pos += 1;
+                }
+                self.pos += 2;
+                continue;
+            }
+            if c == '\\' && matches!(self.peek_at(1), Some('$') | Some('#')) {
+                text.push(self.chars[self.pos + 1]);
+                self.pos += 2;
+                continue;
+            }
+            if c == '#' {
+                if let Some((directive, len)) = self.directive_name() {
+                    let directive_start = self.pos;
+                    self.pos += len;
+                    match directive.as_str() {
+                        "set" => {
+                            let node = self.set_directive()?;
+                            self.end_directive(directive_start, &mut text, &mut nodes);
+                            nodes.push(node);
+                        }
+                        "if" => {
+                            let cond = self.condition()?;
+                            self.end_directive(directive_start, &mut text, &mut nodes);
+                            nodes.push(self.if_body(cond)?);
+                        }
+                        "foreach" => {
+                            let (var, iter) = self.foreach_header()?;
+                            self.end_directive(directive_start, &mut text, &mut nodes);
+                            let (body, terminator) = self.nodes()?;
+                            if terminator.as_deref() != Some("end") {
+                                return Err("Missing #end for #foreach".to_string());
+                            }
+                            nodes.push(Node::Foreach(var, iter, body));
+                        }
+                        _ => {
+                            if directive == "elseif" {
+                                self.pending_condition = Some(self.condition()?);
+                            }
+                            self.end_directive(directive_start, &mut text, &mut nodes);
+                            return Ok((nodes, Some(directive)));
+                        }
+                    }
+                    continue;
+                }
+            }
+            if c == '$' {
+                let start = self.pos;
+                if let Some((reference, silent)) = self.reference(true)? {
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+                    let source = self.chars[start..self.pos].iter().collect();
+                    nodes.push(Node::Ref { reference, silent, source });
+                    continue;
+                }
+                self.pos = start;
+            }
+            text.push(c);
+            self.pos += 1;
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok((nodes, None))
+    }
+
+    /// Recognises `#name` and `#{name}` directives, returning the name and
+    /// the number of characters the directive keyword occupies.
+    fn directive_name(&self) -> Option<(String, usize)> {
+        let braced = self.peek_at(1) == Some('{');
+        let start = self.pos + 1 + usize::from(braced);
+        let name: String = self.chars[start.min(self.chars.len())..]
+            .iter()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect();
+        if !["set", "if", "elseif", "else", "end", "foreach"].contains(&name.as_str()) {
+            return None;
+        }
+        if braced {
+            (self.chars.get(start + name.len()) == Some(&'}')).then(|| (name.clone(), name.len() + 3))
+        } else {
+            Some((name.clone(), name.len() + 1))
+        }
+    }
+
+    fn end_directive(&mut self, directive_start: usize, text: &mut String, nodes: &mut Vec<Node>) {
+        self.gobble_line(directive_start, text);
+        if !text.is_empty() {
+            nodes.push(Node::Text(std::mem::take(text)));
+        }
+    }
+
+    /// A directive alone on its line swallows the line's indentation and
+    /// trailing newline, so block structure does not leak blank lines.
+    fn gobble_line(&mut self, directive_start: usize, text: &mut String) {
+        let line_start = self.chars[..directive_start]
+            .iter()
+            .rev()
+            .take_while(|c| **c != '\n')
+            .all(|c| *c == ' ' || *c == '\t');
+        if !line_start {
+            return;
+        }
+        let mut end = self.pos;
+        while self.chars.get(end).is_some_and(|c| *c == ' ' || *c == '\t' || *c == '\r') {
+            end += 1;
+        }
+        if end < self.chars.len() && self.chars[end] != '\n' {
+            return;
+        }
+        let trimmed = text.trim_end_matches([' ', '\t']).len();
+        text.truncate(trimmed);
+        self.pos = (end + 1).min(self.chars.len());
+    }
+
+    fn set_directive(&mut self) -> Result<Node, String> {
+        self.expect('(')?;
+        self.skip_ws();
+        let (target, _) = self.reference(false)?.ok_or("#set requires a reference")?;
+        self.expect('=')?;
+        let value = self.expr()?;
+        self.expect(')')?;
+        Ok(Node::Set(target, value))
+    }
+
+    fn condition(&mut self) -> Result<Expr, String> {
+        self.expect('(')?;
+        let cond = self.expr()?;
+        self.expect(')')?;
+        Ok(cond)
+    }
+
+    fn if_body(&mut self, first: Expr) -> Result<Node, String> {
+        let mut branches = Vec::new();
+        let mut cond = first;
+        loop {
+            let (body, terminator) = self.nodes()?;
+            match terminator.as_deref() {
+                Some("end") => {
+                    branches.push((cond, body));
+                    return Ok(Node::If(branches, Vec::new()));
+                }
+                Some("else") => {
+                    branches.push((cond, body));
+                    let (otherwise, terminator) = self.nodes()?;
+                    if terminator.as_deref() != Some("end") {
+                        return Err("Missing #end for #if".to_string());
+                    }
+                    return Ok(Node::If(branches, otherwise));
+                }
+                Some("elseif") => {
+                    branches.push((cond, body));
+                    cond = self.pending_condition.take().ok_or("Missing #elseif condition")?;
+                }
+                _ => return Err("Missing #end for #if".to_string()),
+            }
+        }
+    }
+
+    fn foreach_header(&mut self) -> Result<(String, Expr), String> {
+        self.expect('(')?;
+        self.skip_ws();
+        let (var, _) = self.reference(false)?.ok_or("#foreach requires a loop variable")?;
+        self.skip_ws();
+        if self.ident() != "in" {
+            return Err("Expected 'in' in #foreach".to_string());
+        }
+        let iter = self.expr()?;
+        self.expect(')')?;
+        Ok((var.root, iter))
+    }
+
+    /// Parses `$name`, `$!name`, `${name}` and chains of `.prop`, `.call()`
+    /// and `[index]`. In template text a trailing `.` not followed by an
+    /// identifier is left as text.
+    fn reference(&mut self, in_text: bool) -> Result<Option<(Reference, bool)>, String> {
+        if self.peek() != Some('$') {
+            return Ok(None);
+        }
+        self.pos += 1;
+        let silent = self.peek() == Some('!');
+        if silent {
+            self.pos += 1;
+        }
+        let braced = self.peek() == Some('{');
+        if braced {
+            self.pos += 1;
+        }
+        if !self.peek().is_some_and(is_ident_start) {
+            return Ok(None);
+        }
+        let root = self.ident();
+        let mut segments = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') if self.peek_at(1).is_some_and(is_ident_start) => {
+                    self.pos += 1;
+                    let name = self.ident();
+                    if self.peek() == Some('(') {
+                        self.pos += 1;
+                        segments.push(Segment::Call(name, self.args(')')?));
+                    } else {
+                        segments.push(Segment::Property(name));
+                    }
+                }
+                Some('[') if !in_text || braced || self.bracket_closes() => {
+                    self.pos += 1;
+                    let index = self.expr()?;
+                    self.expect(']')?;
+                    segments.push(Segment::Index(index));
+                }
+                _ => break,
+            }
+        }
+        if braced {
+            self.expect('}')?;
+        }
+        Ok(Some((Reference { root, segments }, silent)))
+    }
+
+    fn bracket_closes(&self) -> bool {
+        self.chars[self.pos..].iter().take_while(|c| **c != '\n').any(|c| *c == ']')
+    }
+
+    fn args(&mut self, close: char) -> Result<Vec<Expr>, String> {
+        let mut args = Vec::new();
+        self.skip_ws();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expr()?);
+            self.skip_ws();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(args);
+                }
+                _ => return Err(format!("Expected ',' or '{close}' at position {}", self.pos)),
+            }
+        }
+    }
+
+    // --- Expressions, lowest precedence first ---
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        self.binary(0)
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        const LEVELS: &[&[(&str, BinaryOp)]] = &[
+            &[("||", BinaryOp::Or), ("or", BinaryOp::Or)],
+            &[("&&", BinaryOp::And), ("and", BinaryOp::And)],
+            &[("==", BinaryOp::Eq), ("!=", BinaryOp::Ne), ("eq", BinaryOp::Eq), ("ne", BinaryOp::Ne)],
+            &[
+                ("<=", BinaryOp::Le),
+                (">=", BinaryOp::Ge),
+                ("<", BinaryOp::Lt),
+                (">", BinaryOp::Gt),
+                ("le", BinaryOp::Le),
+                ("ge", BinaryOp::Ge),
+                ("lt", BinaryOp::Lt),
+                ("gt", BinaryOp::Gt),
+            ],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            &[("*", BinaryOp::Mul), ("/", BinaryOp::Div), ("%", BinaryOp::Rem)],
+        ];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut lhs = self.binary(level + 1)?;
+        'outer: loop {
+            self.skip_ws();
+            for (token, op) in LEVELS[level] {
+                let word = token.chars().all(|c| c.is_ascii_alphabetic());
+                let boundary = !word || !self.peek_at(token.len()).is_some_and(is_ident_char);
+                if self.starts_with(token) && boundary {
+                    self.pos += token.len();
+                    let rhs = self.binary(level + 1)?;
+                    lhs = Expr::Binary(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'outer;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        self.skip_ws();
+        if self.peek() == Some('!') && self.peek_at(1) != Some('=') {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.starts_with("not") && !self.peek_at(3).is_some_and(is_ident_char) {
+            self.pos += 3;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some('-') && !self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        self.skip_ws();
+        let c = self.peek().ok_or("Unexpected end of expression")?;
+        match c {
+            '$' => {
+                let (reference, _) = self.reference(false)?.ok_or("Invalid reference")?;
+                Ok(Expr::Ref(reference))
+            }
+            '\'' | '"' => self.string(c),
+            '(' => {
+                self.pos += 1;
+                let e = self.expr()?;
+                self.expect(')')?;
+                Ok(e)
+            }
+            '[' => {
+                self.pos += 1;
+                self.skip_ws();
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                    return Ok(Expr::List(Vec::new()));
+                }
+                let first = self.expr()?;
+                self.skip_ws();
+                if self.starts_with("..") {
+                    self.pos += 2;
+                    let last = self.expr()?;
+                    self.expect(']')?;
+                    return Ok(Expr::Range(Box::new(first), Box::new(last)));
+                }
+                let mut items = vec![first];
+                loop {
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(',') => {
+                            self.pos += 1;
+                            items.push(self.expr()?);
+                        }
+                        Some(']') => {
+                            self.pos += 1;
+                            return Ok(Expr::List(items));
+                        }
+                        _ => return Err(format!("Expected ',' or ']' at position {}", self.pos)),
+                    }
+                }
+            }
+            '{' => {
+                self.pos += 1;
+                let mut entries = Vec::new();
+                self.skip_ws();
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    return Ok(Expr::Map(entries));
+                }
+                loop {
+                    let key = self.expr()?;
+                    self.expect(':')?;
+                    let value = self.expr()?;
+                    entries.push((key, value));
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(',') => self.pos += 1,
+                        Some('}') => {
+                            self.pos += 1;
+                            return Ok(Expr::Map(entries));
+                        }
+                        _ => return Err(format!("Expected ',' or '}}' at position {}", self.pos)),
+                    }
+                }
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let start = self.pos;
+                self.pos += 1;
+                while self.peek().is_some_and(|c| c.is_ascii_digit())
+                    || (self.peek() == Some('.') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()))
+                {
+                    self.pos += 1;
+                }
+                let literal: String = self.chars[start..self.pos].iter().collect();
+                let value: Value = serde_json::from_str(&literal).map_err(|_| format!("Invalid number {literal}"))?;
+                Ok(Expr::Literal(value))
+            }
+            c if is_ident_start(c) => {
+                let word = self.ident();
+                match word.as_str() {
+                    "true" => Ok(Expr::Literal(Value::Bool(true))),
+                    "false" => Ok(Expr::Literal(Value::Bool(false))),
+                    "null" => Ok(Expr::Literal(Value::Null)),
+                    _ => Err(format!("Unexpected '{word}' in expression")),
+                }
+            }
+            c => Err(format!("Unexpected '{c}' in expression")),
+        }
+    }
+
+    fn string(&mut self, quote: char) -> Result<Expr, String> {
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let c = self.peek().ok_or("Unterminated string literal")?;
+            self.pos += 1;
+            if c == quote {
+                // A doubled quote is an escaped quote.
+                if self.peek() == Some(quote) {
+                    self.pos += 1;
+                    s.push(quote);
+                    continue;
+                }
+                break;
+            }
+            s.push(c);
+        }
+        if quote == '"' && (s.contains('$') || s.contains('#')) {
+            let mut inner = Parser { chars: s.chars().collect(), pos: 0, pending_condition: None };
+            let (nodes, _) = inner.nodes()?;
+            return Ok(Expr::Interpolated(nodes));
+        }
+        Ok(Expr::Literal(Value::String(s)))
+    }
+}
+
+// --- Evaluation ---
+
+struct Renderer<'a> {
+    vars: HashMap<String, Value>,
+    body: &'a str,
+    json_body: Option<Value>,
+    params: &'a Value,
+    out: String,
+}
+
+/// Velocity treats only `null` and `false` as false.
+fn truthy(value: Option<&Value>) -> bool {
+    !matches!(value, None | Some(Value::Null) | Some(Value::Bool(false)))
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        Value::Number(Number::from(value as i64))
+    } else {
+        Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null)
+    }
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::String(_), Value::String(_)) | (Value::Bool(_), Value::Bool(_)) | (Value::Null, Value::Null) => a == b,
+        (Value::Null, _) | (_, Value::Null) => false,
+        _ => to_text(a) == to_text(b),
+    }
+}
+
+/// Escapes a string for embedding in a JavaScript/JSON string literal, as
+/// `$util.escapeJavaScript` does (single quotes included).
+fn escape_javascript(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\'' => out.push_str("\\'"),
+            '\\' => out.push_str("\\\\"),
+            '/' => out.push_str("\\/"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Evaluates a JSONPath expression (`$`, `.name`, `['name']`, `[0]`, `[*]`, `.*`).
+pub fn json_path(root: &Value, path: &str) -> Value {
+    let Some(mut rest) = path.trim().strip_prefix('$') else {
+        return Value::Null;
+    };
+    let mut current = vec![root.clone()];
+    let mut wildcard = false;
+    while !rest.is_empty() {
+        let (selector, remainder) = if let Some(r) = rest.strip_prefix("[") {
+            let Some(end) = r.find(']') else {
+                return Value::Null;
+            };
+            (r[..end].trim().trim_matches(|c| c == '\'' || c == '"').to_string(), &r[end + 1..])
+        } else if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            (r[..end].to_string(), &r[end..])
+        } else {
+            return Value::Null;
+        };
+        rest = remainder;
+        current = current
+            .into_iter()
+            .flat_map(|v| match (selector.as_str(), &v) {
+                ("*", Value::Array(items)) => items.clone(),
+                ("*", Value::Object(map)) => map.values().cloned().collect(),
+                (key, Value::Object(map)) => map.get(key).cloned().into_iter().collect(),
+                (index, Value::Array(items)) => index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| items.get(i).cloned())
+                    .into_iter()
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        wildcard |= selector == "*";
+    }
+    if wildcard {
+        Value::Array(current)
+    } else {
+        current.into_iter().next().unwrap_or(Value::Null)
+    }
+}
+
+impl Renderer<'_> {
+    fn render(&mut self, nodes: &[Node]) -> Result<(), String> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.out.push_str(text),
+                Node::Ref { reference, silent, source } => match self.eval_ref(reference)? {
+                    Some(value) => self.out.push_str(&to_text(&value)),
+                    None if *silent => {}
+                    None => self.out.push_str(source),
+                },
+                Node::Set(target, expr) => {
+                    let value = self.eval(expr)?.unwrap_or(Value::Null);
+                    self.assign(target, value)?;
+                }
+                Node::If(branches, otherwise) => {
+                    let mut taken = false;
+                    for (cond, body) in branches {
+                        if truthy(self.eval(cond)?.as_ref()) {
+                            self.render(body)?;
+                            taken = true;
+                            break;
+                        }
+                    }
+                    if !taken {
+                        self.render(otherwise)?;
+                    }
+                }
+                Node::Foreach(var, iter, body) => {
+                    let items = match self.eval(iter)? {
+                        Some(Value::Array(items)) => items,
+                        Some(Value::Object(map)) => map.into_iter().map(|(_, v)| v).collect(),
+                        Some(Value::Null) | None => Vec::new(),
+                        Some(other) => vec![other],
+                    };
+                    let saved = (self.vars.remove(var), self.vars.remove("foreach"), self.vars.remove("velocityCount"));
+                    let len = items.len();
+                    for (index, item) in items.into_iter().enumerate() {
+                        self.vars.insert(var.clone(), item);
+                        self.vars.insert(
+                            "foreach".to_string(),
+                            serde_json::json!({
+                                "index": index,
+                                "count": index + 1,
+                                "hasNext": index + 1 < len,
+                                "first": index == 0,
+                                "last": index + 1 == len,
+                            }),
+                        );
+                        self.vars.insert("velocityCount".to_string(), Value::from(index + 1));
+                        self.render(body)?;
+                    }
+                    for (name, value) in [(var.as_str(), saved.0), ("foreach", saved.1), ("velocityCount", saved.2)] {
+                        match value {
+                            Some(v) => self.vars.insert(name.to_string(), v),
+                            None => self.vars.remove(name),
+                        };
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn assign(&mut self, target: &Reference, value: Value) -> Result<(), String> {
+        let mut path = Vec::new();
+        for segment in &target.segments {
+            match segment {
+                Segment::Property(name) => path.push(name.clone()),
+                Segment::Index(expr) => path.push(to_text(&self.eval(expr)?.unwrap_or(Value::Null))),
+                Segment::Call(..) => return Err("Cannot assign to a method call".to_string()),
+            }
+        }
+        let Some((last, parents)) = path.split_last() else {
+            self.vars.insert(target.root.clone(), value);
+            return Ok(());
+        };
+        let mut slot = self.vars.entry(target.root.clone()).or_insert_with(|| Value::Object(Map::new()));
+        for key in parents {
+            if !slot.is_object() {
+                *slot = Value::Object(Map::new());
+            }
+            slot = slot.as_object_mut().unwrap().entry(key.clone()).or_insert_with(|| Value::Object(Map::new()));
+        }
+        match slot {
+            Value::Object(map) => {
+                map.insert(last.clone(), value);
+            }
+            Value::Array(items) => {
+                if let Some(item) = last.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                    *item = value;
+                }
+            }
+            other => {
+                *other = Value::Object(Map::from_iter([(last.clone(), value)]));
+            }
+        }
+        Ok(())
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Option<Value>, String> {
+        Ok(match expr {
+            Expr::Literal(v) => Some(v.clone()),
+            Expr::Interpolated(nodes) => {
+                let saved = std::mem::take(&mut self.out);
+                let result = self.render(nodes);
+                let text = std::mem::replace(&mut self.out, saved);
+                result?;
+                Some(Value::String(text))
+            }
+            Expr::Ref(reference) => self.eval_ref(reference)?,
+            Expr::List(items) => {
+                let mut values = Vec::new();
+                for item in items {
+                    values.push(self.eval(item)?.unwrap_or(Value::Null));
+                }
+                Some(Value::Array(values))
+            }
+            Expr::Map(entries) => {
+                let mut map = Map::new();
+                for (k, v) in entries {
+                    let key = to_text(&self.eval(k)?.unwrap_or(Value::Null));
+                    map.insert(key, self.eval(v)?.unwrap_or(Value::Null));
+                }
+                Some(Value::Object(map))
+            }
+            Expr::Range(from, to) => {
+                let from = self.eval(from)?.and_then(|v| v.as_i64()).ok_or("Range bounds must be integers")?;
+                let to = self.eval(to)?.and_then(|v| v.as_i64()).ok_or("Range bounds must be integers")?;
+                if (i128::from(to) - i128::from(from)).unsigned_abs() >= MAX_RANGE_LEN as u128 {
+                    return Err(format!("Range [{from}..{to}] has more than {MAX_RANGE_LEN} items"));
+                }
+                let items: Vec<Value> = if from <= to {
+                    (from..=to).map(Value::from).collect()
+                } else {
+                    (to..=from).rev().map(Value::from).collect()
+                };
+                Some(Value::Array(items))
+            }
+            Expr::Not(inner) => Some(Value::Bool(!truthy(self.eval(inner)?.as_ref()))),
+            Expr::Neg(inner) => self.eval(inner)?.and_then(|v| v.as_f64()).map(|n| number(-n)),
+            Expr::Binary(op, lhs, rhs) => self.binary(*op, lhs, rhs)?,
+        })
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: &Expr, rhs: &Expr) -> Result<Option<Value>, String> {
+        let left = self.eval(lhs)?;
+        // Short-circuit the logical operators.
+        match op {
+            BinaryOp::Or if truthy(left.as_ref()) => return Ok(Some(Value::Bool(true))),
+            BinaryOp::And if !truthy(left.as_ref()) => return Ok(Some(Value::Bool(false))),
+            BinaryOp::Or | BinaryOp::And => return Ok(Some(Value::Bool(truthy(self.eval(rhs)?.as_ref())))),
+            _ => {}
+        }
+        let left = left.unwrap_or(Value::Null);
+        let right = self.eval(rhs)?.unwrap_or(Value::Null);
+        let ordering = || match (left.as_f64(), right.as_f64()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => Some(to_text(&left).cmp(&to_text(&right))),
+        };
+        let arithmetic = |f: fn(f64, f64) -> f64| match (left.as_f64(), right.as_f64()) {
+            (Some(a), Some(b)) => Some(number(f(a, b))),
+            _ => None,
+        };
+        Ok(match op {
+            BinaryOp::Eq => Some(Value::Bool(values_equal(&left, &right))),
+            BinaryOp::Ne => Some(Value::Bool(!values_equal(&left, &right))),
+            BinaryOp::Lt => Some(Value::Bool(ordering().is_some_and(|o| o.is_lt()))),
+            BinaryOp::Le => Some(Value::Bool(ordering().is_some_and(|o| o.is_le()))),
+            BinaryOp::Gt => Some(Value::Bool(ordering().is_some_and(|o| o.is_gt()))),
+            BinaryOp::Ge => Some(Value::Bool(ordering().is_some_and(|o| o.is_ge()))),
+            BinaryOp::Add => match (&left, &right) {
+                (Value::String(_), _) | (_, Value::String(_)) => {
+                    Some(Value::String(format!("{}{}", to_text(&left), to_text(&right))))
+                }
+                _ => arithmetic(|a, b| a + b),
+            },
+            BinaryOp::Sub => arithmetic(|a, b| a - b),
+            BinaryOp::Mul => arithmetic(|a, b| a * b),
+            BinaryOp::Div => match right.as_f64() {
+                Some(d) if d != 0.0 => {
+                    let integer = left.is_i64() && right.is_i64();
+                    arithmetic(if integer { |a: f64, b: f64| (a / b).trunc() } else { |a, b| a / b })
+                }
+                _ => None,
+            },
+            BinaryOp::Rem => match right.as_f64() {
+                Some(d) if d != 0.0 => arithmetic(|a, b| a % b),
+                _ => None,
+            },
+            BinaryOp::Or | BinaryOp::And => unreachable!(),
+        })
+    }
+
+    fn args(&mut self, args: &[Expr]) -> Result<Vec<Value>, String> {
+        let mut values = Vec::new();
+        for arg in args {
+            values.push(self.eval(arg)?.unwrap_or(Value::Null));
+        }
+        Ok(values)
+    }
+
+    fn json_body(&mut self) -> &Value {
+        let body = self.body;
+        self.json_body.get_or_insert_with(|| {
+            if body.trim().is_empty() {
+                Value::Object(Map::new())
+            } else {
+                serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string()))
+            }
+        })
+    }
+
+    fn eval_ref(&mut self, reference: &Reference) -> Result<Option<Value>, String> {
+        let mut segments = reference.segments.iter();
+        let mut current = match reference.root.as_str() {
+            "input" => match segments.next() {
+                Some(Segment::Call(name, args)) => {
+                    let args = self.args(args)?;
+                    let arg = args.first().map(to_text);
+                    match (name.as_str(), arg) {
+                        ("json", Some(path)) => {
+                            let selected = json_path(self.json_body(), &path);
+                            Some(Value::String(selected.to_string()))
+                        }
+                        ("path", Some(path)) => Some(json_path(self.json_body(), &path)),
+                        ("params", None) => Some(self.params.clone()),
+                        ("params", Some(name)) => {
+                            let found = ["path", "querystring", "header"].iter().find_map(|kind| {
+                                let group = &self.params[*kind];
+                                group.get(&name).or_else(|| {
+                                    // Header names are case-insensitive.
+                                    (*kind == "header")
+                                        .then(|| group.get(name.to_ascii_lowercase()))
+                                        .flatten()
+                                })
+                            });
+                            Some(found.cloned().unwrap_or(Value::String(String::new())))
+                        }
+                        _ => None,
+                    }
+                }
+                Some(Segment::Property(name)) if name == "body" => Some(Value::String(self.body.to_string())),
+                _ => None,
+            },
+            "util" => match segments.next() {
+                Some(Segment::Call(name, args)) => {
+                    let args = self.args(args)?;
+                    let arg = args.first().map(to_text).unwrap_or_default();
+                    match name.as_str() {
+                        "escapeJavaScript" => Some(Value::String(escape_javascript(&arg))),
+                        "parseJson" => Some(serde_json::from_str(&arg).map_err(|e| format!("Invalid JSON: {e}"))?),
+                        "urlEncode" => Some(Value::String(utf8_percent_encode(&arg, NON_ALPHANUMERIC).to_string())),
+                        "urlDecode" => Some(Value::String(
+                            percent_decode_str(&arg.replace('+', " ")).decode_utf8_lossy().into_owned(),
+                        )),
+                        "base64Encode" => Some(Value::String(BASE64.encode(arg.as_bytes()))),
+                        "base64Decode" => Some(Value::String(
+                            String::from_utf8_lossy(&BASE64.decode(arg.as_bytes()).unwrap_or_default()).into_owned(),
+                        )),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            root => self.vars.get(root).cloned(),
+        };
+        for segment in segments {
+            let Some(value) = current else {
+                return Ok(None);
+            };
+            current = match segment {
+                Segment::Property(name) => match &value {
+                    Value::Object(map) => map.get(name).cloned(),
+                    _ => None,
+                },
+                Segment::Index(expr) => {
+                    let index = self.eval(expr)?.unwrap_or(Value::Null);
+                    match (&value, &index) {
+                        (Value::Array(items), Value::Number(n)) => {
+                            let i = n.as_i64().unwrap_or(0);
+                            let i = if i < 0 { items.len() as i64 + i } else { i };
+                            usize::try_from(i).ok().and_then(|i| items.get(i).cloned())
+                        }
+                        (Value::Object(map), key) => map.get(&to_text(key)).cloned(),
+                        _ => None,
+                    }
+                }
+                Segment::Call(name, args) => {
+                    let args = self.args(args)?;
+                    call_method(&value, name, &args)?
+                }
+            };
+        }
+        Ok(current)
+    }
+}
+
+/// The Java methods templates commonly call on strings, lists and maps.
+fn call_method(value: &Value, name: &str, args: &[Value]) -> Result<Option<Value>, String> {
+    let arg = |i: usize| args.get(i).map(to_text).unwrap_or_default();
+    let int_arg = |i: usize| args.get(i).and_then(Value::as_i64).unwrap_or(0).max(0) as usize;
+    Ok(match (value, name) {
+        (_, "toString") => Some(Value::String(to_text(value))),
+        (Value::String(s), "length") | (Value::String(s), "size") => Some(Value::from(s.chars().count())),
+        (Value::String(s), "isEmpty") => Some(Value::Bool(s.is_empty())),
+        (Value::String(s), "contains") => Some(Value::Bool(s.contains(&arg(0)))),
+        (Value::String(s), "startsWith") => Some(Value::Bool(s.starts_with(&arg(0)))),
+        (Value::String(s), "endsWith") => Some(Value::Bool(s.ends_with(&arg(0)))),
+        (Value::String(s), "equals") => Some(Value::Bool(*s == arg(0))),
+        (Value::String(s), "equalsIgnoreCase") => Some(Value::Bool(s.eq_ignore_ascii_case(&arg(0)))),
+        (Value::String(s), "toLowerCase") => Some(Value::String(s.to_lowercase())),
+        (Value::String(s), "toUpperCase") => Some(Value::String(s.to_uppercase())),
+        (Value::String(s), "trim") => Some(Value::String(s.trim().to_string())),
+        (Value::String(s), "replace") | (Value::String(s), "replaceAll") => {
+            Some(Value::String(s.replace(&arg(0), &arg(1))))
+        }
+        (Value::String(s), "indexOf") => {
+            Some(Value::from(s.find(&arg(0)).map(|i| s[..i].chars().count() as i64).unwrap_or(-1)))
+        }
+        (Value::String(s), "substring") => {
+            let chars: Vec<char> = s.chars().collect();
+            let start = int_arg(0).min(chars.len());
+            let end = if args.len() > 1 { int_arg(1).min(chars.len()) } else { chars.len() };
+            Some(Value::String(chars[start..end.max(start)].iter().collect()))
+        }
+        (Value::String(s), "split") => {
+            Some(Value::Array(s.split(arg(0).as_str()).map(|p| Value::String(p.to_string())).collect()))
+        }
+        (Value::String(s), "matches") => Some(Value::Bool(pattern::full_match(&arg(0), s)?)),
+        (Value::Array(items), "size") => Some(Value::from(items.len())),
+        (Value::Array(items), "isEmpty") => Some(Value::Bool(items.is_empty())),
+        (Value::Array(items), "get") => items.get(int_arg(0)).cloned(),
+        (Value::Array(items), "contains") => {
+            Some(Value::Bool(args.first().is_some_and(|a| items.iter().any(|i| values_equal(i, a)))))
+        }
+        (Value::Object(map), "size") => Some(Value::from(map.len())),
+        (Value::Object(map), "isEmpty") => Some(Value::Bool(map.is_empty())),
+        (Value::Object(map), "get") => map.get(&arg(0)).cloned(),
+        (Value::Object(map), "containsKey") => Some(Value::Bool(map.contains_key(&arg(0)))),
+        (Value::Object(map), "keySet") => Some(Value::Array(map.keys().cloned().map(Value::String).collect())),
+        (Value::Object(map), "values") => Some(Value::Array(map.values().cloned().collect())),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render_with(template: &str, body: &str) -> String {
+        let input = TemplateInput {
+            body,
+            params: json!({"path": {"id": "7"}, "querystring": {"q": "cats"}, "header": {"x-trace": "abc"}}),
+            context: json!({"requestId": "req-1", "authorizer": {"tenant": "acme"}}),
+            stage_variables: json!({"env": "prod"}),
+        };
+        render(template, &input).unwrap().body
+    }
+
+    #[test]
+    fn test_input_functions() {
+        let body = r#"{"name": "Ann", "tags": ["a", "b"], "nested": {"n": 1}}"#;
+        assert_eq!(render_with("$input.json('$.nested')", body), r#"{"n":1}"#);
+        assert_eq!(render_with("$input.path('$.name')", body), "Ann");
+        assert_eq!(render_with("$input.path('$.tags').size()", body), "2");
+        assert_eq!(render_with("$input.path('$.tags[1]')", body), "b");
+        assert_eq!(render_with("$input.params('id')/$input.params('q')/$input.params('X-Trace')", body), "7/cats/abc");
+        assert_eq!(render_with("$context.requestId $context.authorizer.tenant $stageVariables.env", body), "req-1 acme prod");
+    }
+
+    #[test]
+    fn test_util_functions() {
+        assert_eq!(render_with(r#"$util.escapeJavaScript("it's ""q""")"#, ""), r#"it\'s \"q\""#);
+        assert_eq!(render_with("$util.urlEncode('a b&c')", ""), "a%20b%26c");
+        assert_eq!(render_with("#set($o = $util.parseJson('{\"k\": [1,2]}'))$o.k[1]", ""), "2");
+    }
+
+    #[test]
+    fn test_directives() {
+        let template = "#set($items = $input.path('$.items'))\n{\n#foreach($item in $items)\n  \"$item.id\": $item.qty#if($foreach.hasNext),#end\n#end\n}";
+        let body = r#"{"items": [{"id": "a", "qty": 1}, {"id": "b", "qty": 2}]}"#;
+        assert_eq!(render_with(template, body), "{\n  \"a\": 1,\n  \"b\": 2\n}");
+
+        let branch = "#if($input.path('$.n') > 5)big#elseif($input.path('$.n') == 5)five#{else}small#end";
+        assert_eq!(render_with(branch, r#"{"n": 9}"#), "big");
+        assert_eq!(render_with(branch, r#"{"n": 5}"#), "five");
+        assert_eq!(render_with(branch, r#"{"n": 1}"#), "small");
+        assert_eq!(render_with("#set($x = 2 * 3 + 1)$x ## comment", ""), "7 ");
+        assert_eq!(render_with("$missing $!missing.", ""), "$missing .");
+        assert_eq!(render_with("#set($context.responseOverride.status = 201)ok", ""), "ok");
+    }
+
+    #[test]
+    fn test_ranges_are_bounded() {
+        assert_eq!(render_with("#foreach($i in [1..3])$i#end", ""), "123");
+        assert_eq!(render_with("#foreach($i in [3..1])$i#end", ""), "321");
+        let input = TemplateInput { body: "", params: json!({}), context: json!({}), stage_variables: json!({}) };
+        let Err(err) = render("#foreach($i in [0..9999999999])$i#end", &input) else {
+            panic!("an oversized range should not render");
+        };
+        assert!(err.contains("more than"), "{err}");
+    }
+
+    #[test]
+    fn test_response_override() {
+        let input = TemplateInput {
+            body: "",
+            params: json!({}),
+            context: json!({}),
+            stage_variables: json!({}),
+        };
+        let rendered = render("#set($context.responseOverride.status = 418)", &input).unwrap();
+        assert_eq!(rendered.context["responseOverride"]["status"], 418);
+    }
+}
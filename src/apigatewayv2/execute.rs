@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use base64::Engine;
+use serde_json::{json, Value};
+
+use super::types::Route;
+use crate::apigateway::execute::{match_template, ExecuteRequest, ExecuteResponse};
+
+pub const HTTP_METHODS: &[&str] = &["ANY", "DELETE", "GET", "HEAD", "OPTIONS", "PATCH", "POST", "PUT"];
+
+/// Splits an HTTP API route key (`GET /items/{id}`) into method and path.
+/// `$default` has neither.
+pub fn parse_route_key(route_key: &str) -> Option<(&str, &str)> {
+    let (method, path) = route_key.split_once(' ')?;
+    (HTTP_METHODS.contains(&method) && path.starts_with('/')).then_some((method, path))
+}
+
+/// A matched route and the path parameters it captured.
+pub type RouteMatch<'a> = (&'a Route, HashMap<String, String>);
+
+/// Selects the route for a request: the path template with the most
+/// literal segments wins, a specific method beats `ANY`, and `$default`
+/// catches everything else. Returns the captured path parameters.
+pub fn match_route<'a>(
+    routes: &'a HashMap<String, Route>,
+    req: &ExecuteRequest,
+) -> Option<RouteMatch<'a>> {
+    let segments: Vec<&str> = req.path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut best: Option<((usize, bool), RouteMatch<'a>)> = None;
+    for route in routes.values() {
+        let Some((method, path)) = parse_route_key(&route.route_key) else {
+            continue;
+        };
+        if method != "ANY" && method != req.http_method {
+            continue;
+        }
+        let template: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let Some((literals, params)) = match_template(&template, &segments) else {
+            continue;
+        };
+        let score = (literals, method != "ANY");
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, (route, params)));
+        }
+    }
+    best.map(|(_, matched)| matched)
+        .or_else(|| routes.values().find(|r| r.route_key == "$default").map(|r| (r, HashMap::new())))
+}
+
+pub struct RouteContext<'a> {
+    pub api_id: &'a str,
+    pub account_id: &'a str,
+    pub region: &'a str,
+    pub stage: &'a str,
+    pub route_key: &'a str,
+    pub path_parameters: &'a HashMap<String, String>,
+    pub stage_variables: &'a HashMap<String, String>,
+}
+
+/// The Lambda event for payload format version 2.0.
+pub fn proxy_event(ctx: &RouteContext, req: &ExecuteRequest) -> Value {
+    // Named stages are part of the invoked URL; `$default` is not.
+    let raw_path = if ctx.stage == "$default" {
+        req.path.clone()
+    } else {
+        format!("/{}{}", ctx.stage, req.path)
+    };
+    let mut query: Vec<(&String, &String)> = req.query.iter().collect();
+    query.sort();
+    let raw_query_string = form_urlencoded::Serializer::new(String::new()).extend_pairs(query).finish();
+    let cookies: Vec<&str> = req
+        .headers
+        .get("cookie")
+        .map(|c| c.split(';').map(str::trim).filter(|c| !c.is_empty()).collect())
+        .unwrap_or_default();
+    let headers: HashMap<&String, &String> = req.headers.iter().filter(|(k, _)| *k != "cookie").collect();
+    let (body, is_base64_encoded) = match std::str::from_utf8(&req.body) {
+        Ok(body) => (body.to_string(), false),
+        Err(_) => (base64::engine::general_purpose::STANDARD.encode(&req.body), true),
+    };
+    let domain_name = format!("{}.execute-api.{}.amazonaws.com", ctx.api_id, ctx.region);
+    let now = chrono::Utc::now();
+
+    let mut event = json!({
+        "version": "2.0",
+        "routeKey": ctx.route_key,
+        "rawPath": raw_path,
+        "rawQueryString": raw_query_string,
+        "headers": headers,
+        "requestContext": {
+            "accountId": ctx.account_id,
+            "apiId": ctx.api_id,
+            "domainName": domain_name,
+            "domainPrefix": ctx.api_id,
+            "http": {
+                "method": req.http_method,
+                "path": raw_path,
+                "protocol": "HTTP/1.1",
+                "sourceIp": "127.0.0.1",
+                "userAgent": req.headers.get("user-agent").cloned().unwrap_or_default(),
+            },
+            "requestId": uuid::Uuid::new_v4().to_string(),
+            "routeKey": ctx.route_key,
+            "stage": ctx.stage,
+            "time": now.format("%d/%b/%Y:%H:%M:%S %z").to_string(),
+            "timeEpoch": now.timestamp_millis(),
+        },
+        "isBase64Encoded": is_base64_encoded,
+    });
+    // Absent rather than empty, as in AWS.
+    if !cookies.is_empty() {
+        event["cookies"] = json!(cookies);
+    }
+    if !req.query.is_empty() {
+        event["queryStringParameters"] = json!(req.query);
+    }
+    if !ctx.path_parameters.is_empty() {
+        event["pathParameters"] = json!(ctx.path_parameters);
+    }
+    if !ctx.stage_variables.is_empty() {
+        event["stageVariables"] = json!(ctx.stage_variables);
+    }
+    if !req.body.is_empty() {
+        event["body"] = Value::String(body);
+    }
+    event
+}
+
+/// Maps a payload format 2.0 Lambda result to the HTTP response. A result
+/// with a `statusCode` is a full response; anything else is a 200 JSON body.
+pub fn proxy_response(result: &Value) -> ExecuteResponse {
+    if result.get("statusCode").is_none() {
+        let body = match result {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        return ExecuteResponse {
+            status: 200,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: body.into_bytes(),
+        };
+    }
+    let status = result["statusCode"].as_u64().and_then(|s| u16::try_from(s).ok()).unwrap_or(500);
+    let mut headers: Vec<(String, String)> = result["headers"]
+        .as_object()
+        .map(|h| {
+            h.iter()
+                .map(|(k, v)| (k.to_ascii_lowercase(), v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    for cookie in result["cookies"].as_array().into_iter().flatten().filter_map(Value::as_str) {
+        headers.push(("set-cookie".to_string(), cookie.to_string()));
+    }
+    let body = result["body"].as_str().unwrap_or_default();
+    let body = if result["isBase64Encoded"].as_bool().unwrap_or(false) {
+        base64::engine::general_purpose::STANDARD.decode(body).unwrap_or_default()
+    } else {
+        body.as_bytes().to_vec()
+    };
+    ExecuteResponse { status, headers, body }
+}
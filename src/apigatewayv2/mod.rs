@@ -0,0 +1,4 @@
+pub mod execute;
+pub mod server;
+pub mod state;
+pub mod types;
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, get, post};
+use axum::{Json, Router};
+
+use super::state::ApiGatewayV2State;
+use super::types::*;
+use crate::apigateway::error::ApiGatewayError;
+use crate::apigateway::execute::ExecuteRequest;
+
+fn json_response<T: serde::Serialize>(status: StatusCode, value: &T) -> Response {
+    (status, Json(value)).into_response()
+}
+
+// --- API handlers ---
+
+async fn create_api_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Json(req): Json<CreateApiRequest>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.create_api(req).await?;
+    Ok(json_response(StatusCode::CREATED, &resp))
+}
+
+async fn get_apis_handler(State(state): State<Arc<ApiGatewayV2State>>) -> Response {
+    json_response(StatusCode::OK, &state.get_apis().await)
+}
+
+async fn get_api_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_api(&api_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn delete_api_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+) -> Result<Response, ApiGatewayError> {
+    state.delete_api(&api_id).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// --- Route handlers ---
+
+async fn create_route_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+    Json(req): Json<CreateRouteRequest>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.create_route(&api_id, req).await?;
+    Ok(json_response(StatusCode::CREATED, &resp))
+}
+
+async fn get_routes_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_routes(&api_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn get_route_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path((api_id, route_id)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_route(&api_id, &route_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn delete_route_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path((api_id, route_id)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    state.delete_route(&api_id, &route_id).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// --- Integration handlers ---
+
+async fn create_integration_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+    Json(req): Json<CreateIntegrationRequest>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.create_integration(&api_id, req).await?;
+    Ok(json_response(StatusCode::CREATED, &resp))
+}
+
+async fn get_integrations_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_integrations(&api_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn get_integration_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path((api_id, integration_id)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_integration(&api_id, &integration_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn delete_integration_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path((api_id, integration_id)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    state.delete_integration(&api_id, &integration_id).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// --- Stage handlers ---
+
+async fn create_stage_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+    Json(req): Json<CreateStageRequest>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.create_stage(&api_id, req).await?;
+    Ok(json_response(StatusCode::CREATED, &resp))
+}
+
+async fn get_stages_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(api_id): Path<String>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_stages(&api_id).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn get_stage_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path((api_id, stage_name)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    let resp = state.get_stage(&api_id, &stage_name).await?;
+    Ok(json_response(StatusCode::OK, &resp))
+}
+
+async fn delete_stage_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path((api_id, stage_name)): Path<(String, String)>,
+) -> Result<Response, ApiGatewayError> {
+    state.delete_stage(&api_id, &stage_name).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// --- Execution handler ---
+
+async fn execute_handler(
+    State(state): State<Arc<ApiGatewayV2State>>,
+    Path(params): Path<HashMap<String, String>>,
+    method: Method,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    body: axum::body::Bytes,
+) -> Response {
+    let req = ExecuteRequest {
+        http_method: method.as_str().to_string(),
+        path: format!("/{}", params.get("path").map(String::as_str).unwrap_or_default()),
+        headers: headers
+            .iter()
+            .filter_map(|(k, v)| Some((k.as_str().to_string(), v.to_str().ok()?.to_string())))
+            .collect(),
+        query,
+        body: body.to_vec(),
+    };
+    let resp = state.execute(&params["api_id"], &params["stage"], req).await;
+    let mut response = (StatusCode::from_u16(resp.status).unwrap_or(StatusCode::OK), resp.body).into_response();
+    for (name, value) in resp.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            // Appended so that several `set-cookie` headers survive.
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
+
+pub fn create_router(state: Arc<ApiGatewayV2State>) -> Router {
+    Router::new()
+        // APIs
+        .route("/v2/apis", post(create_api_handler).get(get_apis_handler))
+        .route("/v2/apis/{api_id}", get(get_api_handler).delete(delete_api_handler))
+        // Routes
+        .route(
+            "/v2/apis/{api_id}/routes",
+            post(create_route_handler).get(get_routes_handler),
+        )
+        .route(
+            "/v2/apis/{api_id}/routes/{route_id}",
+            get(get_route_handler).delete(delete_route_handler),
+        )
+        // Integrations
+        .route(
+            "/v2/apis/{api_id}/integrations",
+            post(create_integration_handler).get(get_integrations_handler),
+        )
+        .route(
+            "/v2/apis/{api_id}/integrations/{integration_id}",
+            get(get_integration_handler).delete(delete_integration_handler),
+        )
+        // Stages
+        .route(
+            "/v2/apis/{api_id}/stages",
+            post(create_stage_handler).get(get_stages_handler),
+        )
+        .route(
+            "/v2/apis/{api_id}/stages/{stage_name}",
+            get(get_stage_handler).delete(delete_stage_handler),
+        )
+        // Invocation of HTTP APIs
+        .route("/apis/{api_id}/{stage}/_user_request_", any(execute_handler))
+        .route("/apis/{api_id}/{stage}/_user_request_/", any(execute_handler))
+        .route("/apis/{api_id}/{stage}/_user_request_/{*path}", any(execute_handler))
+        .merge(crate::healthcheck::router("apigatewayv2"))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn make_app() -> Router {
+        create_router(Arc::new(ApiGatewayV2State::new("123456789012".to_string(), "us-east-1".to_string())))
+    }
+
+    #[tokio::test]
+    async fn test_create_api() {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/v2/apis")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"name":"items","protocolType":"HTTP"}"#))
+            .unwrap();
+        let resp = make_app().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let api: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(api["protocolType"], "HTTP");
+        assert!(api["apiId"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_lambda_proxy_with_stubbed_function_through_routers() {
+        use serde_json::json;
+
+        let lambda = Arc::new(crate::lambda::state::LambdaState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        ));
+        let lambda_app = crate::lambda::server::create_router(lambda.clone());
+        crate::lambda::server::create_stubbed_function(&lambda_app, "items", json!({ "mode": "echo" })).await;
+        let bus = Arc::new(crate::bus::ServiceBus::new());
+        bus.attach_lambda(lambda);
+        let state = ApiGatewayV2State::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let app = create_router(Arc::new(state));
+        let send = |method: &str, uri: String, body: Option<serde_json::Value>| {
+            let req = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap();
+            let resp = app.clone().oneshot(req);
+            async move {
+                let resp = resp.await.unwrap();
+                let status = resp.status();
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default())
+            }
+        };
+
+        let (_, api) = send("POST", "/v2/apis".to_string(), Some(json!({ "name": "items", "protocolType": "HTTP" }))).await;
+        let api_id = api["apiId"].as_str().unwrap();
+        let (_, integration) = send("POST", format!("/v2/apis/{api_id}/integrations"), Some(json!({
+            "integrationType": "AWS_PROXY",
+            "integrationUri": "arn:aws:lambda:us-east-1:123456789012:function:items",
+        })))
+        .await;
+        let target = format!("integrations/{}", integration["integrationId"].as_str().unwrap());
+        let (status, _) = send("POST", format!("/v2/apis/{api_id}/routes"), Some(json!({
+            "routeKey": "GET /items/{id}",
+            "target": target,
+        })))
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = send("POST", format!("/v2/apis/{api_id}/stages"), Some(json!({ "stageName": "prod" }))).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // An echo stub returns the event, which has no statusCode and so is the JSON body.
+        let (status, event) = send("GET", format!("/apis/{api_id}/prod/_user_request_/items/42?q=x"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(event["routeKey"], "GET /items/{id}");
+        assert_eq!(event["rawPath"], "/prod/items/42");
+        assert_eq!(event["pathParameters"]["id"], "42");
+        assert_eq!(event["queryStringParameters"]["q"], "x");
+
+        let stub = json!({ "mode": "return", "result": { "statusCode": 201, "body": "{\"created\":true}" } });
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_aws/lambda/functions/items/stub")
+            .body(Body::from(stub.to_string()))
+            .unwrap();
+        assert!(lambda_app.oneshot(req).await.unwrap().status().is_success());
+        let (status, body) = send("GET", format!("/apis/{api_id}/prod/_user_request_/items/42"), None).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body, json!({ "created": true }));
+    }
+
+    #[tokio::test]
+    async fn test_get_unknown_api() {
+        let req = Request::builder().uri("/v2/apis/missing").body(Body::empty()).unwrap();
+        let resp = make_app().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_execute_unknown_api() {
+        let req = Request::builder().uri("/apis/missing/prod/_user_request_/items").body(Body::empty()).unwrap();
+        let resp = make_app().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
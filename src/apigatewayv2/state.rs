@@ -0,0 +1,573 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Map;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::execute::{self, RouteContext};
+use super::types::*;
+use crate::apigateway::error::ApiGatewayError;
+use crate::apigateway::execute::{self as rest, ExecuteRequest, ExecuteResponse, InvocationContext};
+use crate::bus::ServiceBus;
+
+fn now() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn short_id() -> String {
+    Uuid::new_v4()
+        .to_string()
+        .replace('-', "")
+        .chars()
+        .take(7)
+        .collect()
+}
+
+struct HttpApi {
+    api: Api,
+    routes: HashMap<String, Route>,
+    integrations: HashMap<String, Integration>,
+    stages: HashMap<String, Stage>,
+}
+
+struct ApiGatewayV2StateInner {
+    apis: HashMap<String, HttpApi>,
+    account_id: String,
+    region: String,
+}
+
+pub struct ApiGatewayV2State {
+    inner: Arc<Mutex<ApiGatewayV2StateInner>>,
+    bus: Arc<ServiceBus>,
+}
+
+impl ApiGatewayV2State {
+    pub fn new(account_id: String, region: String) -> Self {
+        ApiGatewayV2State {
+            inner: Arc::new(Mutex::new(ApiGatewayV2StateInner {
+                apis: HashMap::new(),
+                account_id,
+                region,
+            })),
+            bus: Arc::new(ServiceBus::new()),
+        }
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Invokes Lambda integrations through the shared service bus.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    // --- APIs ---
+
+    pub async fn create_api(&self, req: CreateApiRequest) -> Result<Api, ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        if req.name.is_empty() {
+            return Err(ApiGatewayError::BadRequestException("Name is required".to_string()));
+        }
+        let route_selection_expression = match (req.protocol_type.as_str(), req.route_selection_expression) {
+            ("HTTP", expression) => expression.unwrap_or_else(|| "${request.method} ${request.path}".to_string()),
+            ("WEBSOCKET", Some(expression)) => expression,
+            ("WEBSOCKET", None) => {
+                return Err(ApiGatewayError::BadRequestException(
+                    "RouteSelectionExpression is required for WEBSOCKET APIs".to_string(),
+                ))
+            }
+            (other, _) => {
+                return Err(ApiGatewayError::BadRequestException(format!(
+                    "Invalid protocol type {other}, must be HTTP or WEBSOCKET"
+                )))
+            }
+        };
+        let api_id = short_id();
+        let scheme = if req.protocol_type == "HTTP" { "https" } else { "wss" };
+        let api = Api {
+            api_endpoint: format!("{scheme}://{api_id}.execute-api.{}.amazonaws.com", state.region),
+            api_id: api_id.clone(),
+            name: req.name,
+            protocol_type: req.protocol_type,
+            route_selection_expression,
+            description: req.description,
+            created_date: now(),
+            tags: req.tags.unwrap_or_default(),
+        };
+        state.apis.insert(api_id, HttpApi {
+            api: api.clone(),
+            routes: HashMap::new(),
+            integrations: HashMap::new(),
+            stages: HashMap::new(),
+        });
+        Ok(api)
+    }
+
+    pub async fn get_apis(&self) -> GetApisResponse {
+        let state = self.inner.lock().await;
+        let mut items: Vec<Api> = state.apis.values().map(|a| a.api.clone()).collect();
+        items.sort_by(|a, b| a.created_date.cmp(&b.created_date).then_with(|| a.api_id.cmp(&b.api_id)));
+        GetApisResponse { items }
+    }
+
+    pub async fn get_api(&self, api_id: &str) -> Result<Api, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        Ok(get_api(&state.apis, api_id)?.api.clone())
+    }
+
+    pub async fn delete_api(&self, api_id: &str) -> Result<(), ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        state.apis.remove(api_id).ok_or_else(|| api_not_found(api_id))?;
+        Ok(())
+    }
+
+    // --- Routes ---
+
+    pub async fn create_route(&self, api_id: &str, req: CreateRouteRequest) -> Result<Route, ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, api_id)?;
+        if api.api.protocol_type == "HTTP"
+            && req.route_key != "$default"
+            && execute::parse_route_key(&req.route_key).is_none()
+        {
+            return Err(ApiGatewayError::BadRequestException(format!(
+                "Unable to parse route key {}; expected $default or an HTTP method and a path starting with /",
+                req.route_key
+            )));
+        }
+        if api.routes.values().any(|r| r.route_key == req.route_key) {
+            return Err(ApiGatewayError::ConflictException(format!(
+                "Route with key {} already exists for this API",
+                req.route_key
+            )));
+        }
+        if let Some(target) = &req.target {
+            target_integration(api, target)?;
+        }
+        let route = Route {
+            route_id: short_id(),
+            route_key: req.route_key,
+            target: req.target,
+            authorization_type: req.authorization_type.unwrap_or_else(|| "NONE".to_string()),
+            api_key_required: req.api_key_required.unwrap_or(false),
+        };
+        api.routes.insert(route.route_id.clone(), route.clone());
+        Ok(route)
+    }
+
+    pub async fn get_routes(&self, api_id: &str) -> Result<GetRoutesResponse, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, api_id)?;
+        let mut items: Vec<Route> = api.routes.values().cloned().collect();
+        items.sort_by(|a, b| a.route_key.cmp(&b.route_key));
+        Ok(GetRoutesResponse { items })
+    }
+
+    pub async fn get_route(&self, api_id: &str, route_id: &str) -> Result<Route, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, api_id)?;
+        api.routes.get(route_id).cloned().ok_or_else(|| route_not_found(route_id))
+    }
+
+    pub async fn delete_route(&self, api_id: &str, route_id: &str) -> Result<(), ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, api_id)?;
+        api.routes.remove(route_id).ok_or_else(|| route_not_found(route_id))?;
+        Ok(())
+    }
+
+    // --- Integrations ---
+
+    pub async fn create_integration(
+        &self,
+        api_id: &str,
+        req: CreateIntegrationRequest,
+    ) -> Result<Integration, ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, api_id)?;
+        let http = api.api.protocol_type == "HTTP";
+        let allowed: &[&str] = if http {
+            &["AWS_PROXY", "HTTP_PROXY"]
+        } else {
+            &["AWS", "AWS_PROXY", "HTTP", "HTTP_PROXY", "MOCK"]
+        };
+        if !allowed.contains(&req.integration_type.as_str()) {
+            return Err(ApiGatewayError::BadRequestException(format!(
+                "Unsupported integration type {} for {} APIs",
+                req.integration_type, api.api.protocol_type
+            )));
+        }
+        if req.integration_type != "MOCK" && req.integration_uri.is_none() {
+            return Err(ApiGatewayError::BadRequestException(
+                "IntegrationUri is required for this integration type".to_string(),
+            ));
+        }
+        let payload_format_version = req
+            .payload_format_version
+            .unwrap_or_else(|| if http { "2.0" } else { "1.0" }.to_string());
+        if !matches!(payload_format_version.as_str(), "1.0" | "2.0") {
+            return Err(ApiGatewayError::BadRequestException(format!(
+                "Invalid payload format version {payload_format_version}, must be 1.0 or 2.0"
+            )));
+        }
+        let integration = Integration {
+            integration_id: short_id(),
+            integration_type: req.integration_type,
+            integration_uri: req.integration_uri,
+            integration_method: req.integration_method,
+            payload_format_version,
+            timeout_in_millis: req.timeout_in_millis.unwrap_or(30000),
+            description: req.description,
+        };
+        api.integrations.insert(integration.integration_id.clone(), integration.clone());
+        Ok(integration)
+    }
+
+    pub async fn get_integrations(&self, api_id: &str) -> Result<GetIntegrationsResponse, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, api_id)?;
+        let mut items: Vec<Integration> = api.integrations.values().cloned().collect();
+        items.sort_by(|a, b| a.integration_id.cmp(&b.integration_id));
+        Ok(GetIntegrationsResponse { items })
+    }
+
+    pub async fn get_integration(&self, api_id: &str, integration_id: &str) -> Result<Integration, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, api_id)?;
+        api.integrations.get(integration_id).cloned().ok_or_else(|| integration_not_found(integration_id))
+    }
+
+    pub async fn delete_integration(&self, api_id: &str, integration_id: &str) -> Result<(), ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, api_id)?;
+        api.integrations.remove(integration_id).ok_or_else(|| integration_not_found(integration_id))?;
+        Ok(())
+    }
+
+    // --- Stages ---
+
+    pub async fn create_stage(&self, api_id: &str, req: CreateStageRequest) -> Result<Stage, ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, api_id)?;
+        if req.stage_name.is_empty() {
+            return Err(ApiGatewayError::BadRequestException("StageName is required".to_string()));
+        }
+        if api.stages.contains_key(&req.stage_name) {
+            return Err(ApiGatewayError::ConflictException(format!(
+                "Stage {} already exists",
+                req.stage_name
+            )));
+        }
+        let created = now();
+        let stage = Stage {
+            stage_name: req.stage_name,
+            auto_deploy: req.auto_deploy.unwrap_or(false),
+            stage_variables: req.stage_variables.unwrap_or_default(),
+            description: req.description,
+            created_date: created.clone(),
+            last_updated_date: created,
+        };
+        api.stages.insert(stage.stage_name.clone(), stage.clone());
+        Ok(stage)
+    }
+
+    pub async fn get_stages(&self, api_id: &str) -> Result<GetStagesResponse, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, api_id)?;
+        let mut items: Vec<Stage> = api.stages.values().cloned().collect();
+        items.sort_by(|a, b| a.stage_name.cmp(&b.stage_name));
+        Ok(GetStagesResponse { items })
+    }
+
+    pub async fn get_stage(&self, api_id: &str, stage_name: &str) -> Result<Stage, ApiGatewayError> {
+        let state = self.inner.lock().await;
+        let api = get_api(&state.apis, api_id)?;
+        api.stages.get(stage_name).cloned().ok_or_else(|| stage_not_found(stage_name))
+    }
+
+    pub async fn delete_stage(&self, api_id: &str, stage_name: &str) -> Result<(), ApiGatewayError> {
+        let mut state = self.inner.lock().await;
+        let api = get_api_mut(&mut state.apis, api_id)?;
+        api.stages.remove(stage_name).ok_or_else(|| stage_not_found(stage_name))?;
+        Ok(())
+    }
+
+    // --- Execution ---
+
+    /// Runs a request against a stage of an HTTP API: selects the route and
+    /// calls its Lambda proxy integration. Routes take effect immediately on
+    /// every stage; there are no deployments.
+    pub async fn execute(&self, api_id: &str, stage_name: &str, req: ExecuteRequest) -> ExecuteResponse {
+        let (account_id, region, stage_variables, matched) = {
+            let state = self.inner.lock().await;
+            let Some(api) = state.apis.get(api_id).filter(|api| api.api.protocol_type == "HTTP") else {
+                return ExecuteResponse::message(404, "Not Found");
+            };
+            let Some(stage) = api.stages.get(stage_name) else {
+                return ExecuteResponse::message(404, "Not Found");
+            };
+            let matched = execute::match_route(&api.routes, &req).map(|(route, params)| {
+                let integration = route.target.as_deref().and_then(|t| target_integration(api, t).ok()).cloned();
+                (route.route_key.clone(), params, integration)
+            });
+            (state.account_id.clone(), state.region.clone(), stage.stage_variables.clone(), matched)
+        };
+        let Some((route_key, path_parameters, integration)) = matched else {
+            return ExecuteResponse::message(404, "Not Found");
+        };
+        let Some(integration) = integration else {
+            return ExecuteResponse::message(500, "Internal Server Error");
+        };
+        let Some(uri) = integration.integration_uri.as_deref().filter(|_| integration.integration_type == "AWS_PROXY")
+        else {
+            return ExecuteResponse::message(
+                500,
+                &format!("Integration type {} is not supported", integration.integration_type),
+            );
+        };
+        let function = rest::lambda_arn(uri).unwrap_or(uri);
+
+        if integration.payload_format_version == "1.0" {
+            let method_arn = rest::method_arn(&region, &account_id, api_id, stage_name, &req.http_method, &req.path);
+            let resource_path = execute::parse_route_key(&route_key).map_or("$default", |(_, path)| path);
+            let ctx = InvocationContext {
+                api_id,
+                account_id: &account_id,
+                stage: stage_name,
+                resource_path,
+                method_arn: &method_arn,
+                path_parameters: &path_parameters,
+                stage_variables: &stage_variables,
+            };
+            let event = rest::proxy_event(&ctx, &req, &Map::new());
+            return match self.bus.invoke_lambda(function, event).await {
+                Ok(result) => rest::proxy_response(&result),
+                Err(_) => ExecuteResponse::message(500, "Internal Server Error"),
+            };
+        }
+
+        let ctx = RouteContext {
+            api_id,
+            account_id: &account_id,
+            region: &region,
+            stage: stage_name,
+            route_key: &route_key,
+            path_parameters: &path_parameters,
+            stage_variables: &stage_variables,
+        };
+        match self.bus.invoke_lambda(function, execute::proxy_event(&ctx, &req)).await {
+            Ok(result) => execute::proxy_response(&result),
+            Err(_) => ExecuteResponse::message(500, "Internal Server Error"),
+        }
+    }
+}
+
+// --- Helpers ---
+
+fn api_not_found(api_id: &str) -> ApiGatewayError {
+    ApiGatewayError::NotFoundException(format!("Invalid API identifier specified {api_id}"))
+}
+
+fn route_not_found(route_id: &str) -> ApiGatewayError {
+    ApiGatewayError::NotFoundException(format!("Invalid Route identifier specified {route_id}"))
+}
+
+fn integration_not_found(integration_id: &str) -> ApiGatewayError {
+    ApiGatewayError::NotFoundException(format!("Invalid Integration identifier specified {integration_id}"))
+}
+
+fn stage_not_found(stage_name: &str) -> ApiGatewayError {
+    ApiGatewayError::NotFoundException(format!("Invalid stage identifier specified {stage_name}"))
+}
+
+fn get_api<'a>(apis: &'a HashMap<String, HttpApi>, api_id: &str) -> Result<&'a HttpApi, ApiGatewayError> {
+    apis.get(api_id).ok_or_else(|| api_not_found(api_id))
+}
+
+fn get_api_mut<'a>(apis: &'a mut HashMap<String, HttpApi>, api_id: &str) -> Result<&'a mut HttpApi, ApiGatewayError> {
+    apis.get_mut(api_id).ok_or_else(|| api_not_found(api_id))
+}
+
+/// Resolves a route target of the form `integrations/{integrationId}`.
+fn target_integration<'a>(api: &'a HttpApi, target: &str) -> Result<&'a Integration, ApiGatewayError> {
+    target
+        .strip_prefix("integrations/")
+        .and_then(|id| api.integrations.get(id))
+        .ok_or_else(|| ApiGatewayError::BadRequestException(format!("Invalid target {target}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lambda::state::{FunctionHandler, LambdaState};
+    use serde_json::{json, Value};
+
+    fn make_state() -> ApiGatewayV2State {
+        ApiGatewayV2State::new("123456789012".to_string(), "us-east-1".to_string())
+    }
+
+    async fn create_http_api(state: &ApiGatewayV2State) -> String {
+        state
+            .create_api(CreateApiRequest {
+                name: "items".to_string(),
+                protocol_type: "HTTP".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .api_id
+    }
+
+    #[tokio::test]
+    async fn test_create_api_validates_protocol() {
+        let state = make_state();
+        let api = state.get_api(&create_http_api(&state).await).await.unwrap();
+        assert_eq!(api.route_selection_expression, "${request.method} ${request.path}");
+        let err = state
+            .create_api(CreateApiRequest {
+                name: "chat".to_string(),
+                protocol_type: "WEBSOCKET".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(err, Err(ApiGatewayError::BadRequestException(_))));
+        let err = state
+            .create_api(CreateApiRequest {
+                name: "soap".to_string(),
+                protocol_type: "SOAP".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(err, Err(ApiGatewayError::BadRequestException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_route_rejects_bad_and_duplicate_keys() {
+        let state = make_state();
+        let api_id = create_http_api(&state).await;
+        let route = |key: &str| CreateRouteRequest { route_key: key.to_string(), ..Default::default() };
+        state.create_route(&api_id, route("GET /items/{id}")).await.unwrap();
+        assert!(matches!(
+            state.create_route(&api_id, route("GET /items/{id}")).await,
+            Err(ApiGatewayError::ConflictException(_))
+        ));
+        assert!(matches!(
+            state.create_route(&api_id, route("FETCH /items")).await,
+            Err(ApiGatewayError::BadRequestException(_))
+        ));
+        assert!(matches!(
+            state
+                .create_route(&api_id, CreateRouteRequest { target: Some("integrations/nope".to_string()), ..route("GET /x") })
+                .await,
+            Err(ApiGatewayError::BadRequestException(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_api_invokes_lambda_end_to_end() {
+        let lambda = Arc::new(LambdaState::new("123456789012".to_string(), "us-east-1".to_string()));
+        lambda
+            .create_function(serde_json::from_value(json!({
+                "FunctionName": "items",
+                "Role": "arn:aws:iam::123456789012:role/r",
+                "Code": {}
+            }))
+            .unwrap())
+            .await
+            .unwrap();
+        let handler: FunctionHandler = Arc::new(|event| {
+            Box::pin(async move {
+                Ok(json!({
+                    "statusCode": 201,
+                    "headers": {"Content-Type": "application/json"},
+                    "cookies": ["session=abc"],
+                    "body": json!({
+                        "version": event["version"],
+                        "routeKey": event["routeKey"],
+                        "rawPath": event["rawPath"],
+                        "id": event["pathParameters"]["id"],
+                        "q": event["queryStringParameters"]["q"],
+                        "method": event["requestContext"]["http"]["method"],
+                    }).to_string(),
+                }))
+            })
+        });
+        lambda.set_handler("items", handler).await.unwrap();
+        let bus = Arc::new(ServiceBus::new());
+        bus.attach_lambda(lambda);
+        let state = make_state().with_bus(bus);
+
+        let api_id = create_http_api(&state).await;
+        let integration = state
+            .create_integration(&api_id, CreateIntegrationRequest {
+                integration_type: "AWS_PROXY".to_string(),
+                integration_uri: Some("arn:aws:lambda:us-east-1:123456789012:function:items".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(integration.payload_format_version, "2.0");
+        state
+            .create_route(&api_id, CreateRouteRequest {
+                route_key: "GET /items/{id}".to_string(),
+                target: Some(format!("integrations/{}", integration.integration_id)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .create_stage(&api_id, CreateStageRequest { stage_name: "prod".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+
+        let resp = state
+            .execute(&api_id, "prod", ExecuteRequest {
+                http_method: "GET".to_string(),
+                path: "/items/42".to_string(),
+                query: HashMap::from([("q".to_string(), "x".to_string())]),
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(resp.status, 201);
+        assert!(resp.headers.contains(&("set-cookie".to_string(), "session=abc".to_string())));
+        let body: Value = serde_json::from_slice(&resp.body).unwrap();
+        assert_eq!(body["version"], "2.0");
+        assert_eq!(body["routeKey"], "GET /items/{id}");
+        assert_eq!(body["rawPath"], "/prod/items/42");
+        assert_eq!(body["id"], "42");
+        assert_eq!(body["q"], "x");
+        assert_eq!(body["method"], "GET");
+
+        let unmatched = state
+            .execute(&api_id, "prod", ExecuteRequest {
+                http_method: "POST".to_string(),
+                path: "/items/42".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(unmatched.status, 404);
+        let no_stage = state
+            .execute(&api_id, "dev", ExecuteRequest {
+                http_method: "GET".to_string(),
+                path: "/items/42".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(no_stage.status, 404);
+    }
+
+    #[test]
+    fn test_proxy_response_without_status_code_is_json_body() {
+        let resp = execute::proxy_response(&json!({"ok": true}));
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body, br#"{"ok":true}"#);
+        let resp = execute::proxy_response(&json!("hello"));
+        assert_eq!(resp.body, b"hello");
+    }
+}
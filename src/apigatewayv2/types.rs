@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+// --- APIs ---
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiRequest {
+    pub name: String,
+    pub protocol_type: String,
+    pub route_selection_expression: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Api {
+    pub api_id: String,
+    pub name: String,
+    pub protocol_type: String,
+    pub api_endpoint: String,
+    pub route_selection_expression: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_date: String,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetApisResponse {
+    pub items: Vec<Api>,
+}
+
+// --- Routes ---
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRouteRequest {
+    pub route_key: String,
+    pub target: Option<String>,
+    pub authorization_type: Option<String>,
+    pub api_key_required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    pub route_id: String,
+    pub route_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub authorization_type: String,
+    pub api_key_required: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRoutesResponse {
+    pub items: Vec<Route>,
+}
+
+// --- Integrations ---
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateIntegrationRequest {
+    pub integration_type: String,
+    pub integration_uri: Option<String>,
+    pub integration_method: Option<String>,
+    pub payload_format_version: Option<String>,
+    pub timeout_in_millis: Option<i64>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Integration {
+    pub integration_id: String,
+    pub integration_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integration_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integration_method: Option<String>,
+    pub payload_format_version: String,
+    pub timeout_in_millis: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetIntegrationsResponse {
+    pub items: Vec<Integration>,
+}
+
+// --- Stages ---
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStageRequest {
+    pub stage_name: String,
+    pub auto_deploy: Option<bool>,
+    pub stage_variables: Option<HashMap<String, String>>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stage {
+    pub stage_name: String,
+    pub auto_deploy: bool,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub stage_variables: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_date: String,
+    pub last_updated_date: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetStagesResponse {
+    pub items: Vec<Stage>,
+}
@@ -1,6 +1,8 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 
+use crate::aws_error::{error_response, AwsError, Protocol};
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum AppfabricError {
@@ -40,18 +42,23 @@ impl AppfabricError {
     }
 }
 
+impl AwsError for AppfabricError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        AppfabricError::message(self)
+    }
+}
+
 impl IntoResponse for AppfabricError {
-    #[allow(dead_code)]
     fn into_response(self) -> Response {
-        let body = serde_json::json!({
-            "Message": self.message(),
-        });
-        (
-            self.status_code(),
-            [("x-amzn-ErrorType", self.error_code())],
-            axum::Json(body),
-        )
-            .into_response()
+        error_response(&self, Protocol::RestJson)
     }
 }
 
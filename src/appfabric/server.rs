@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<AppfabricState>) -> Router {
     Router::new()
         .route("/app-bundles", post(create_app_bundle_handler).get(list_app_bundles_handler))
         .route("/app-bundles/{name}", get(get_app_bundle_handler).delete(delete_app_bundle_handler))
+        .merge(crate::healthcheck::router("appfabric"))
         .with_state(state)
 }
 
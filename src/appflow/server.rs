@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<AppflowState>) -> Router {
     Router::new()
         .route("/flows", post(create_flow_handler).get(list_flows_handler))
         .route("/flows/{name}", get(get_flow_handler).delete(delete_flow_handler))
+        .merge(crate::healthcheck::router("appflow"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<AppmeshState>) -> Router {
     Router::new()
         .route("/meshes", post(create_mesh_handler).get(list_meshs_handler))
         .route("/meshes/{name}", get(get_mesh_handler).delete(delete_mesh_handler))
+        .merge(crate::healthcheck::router("appmesh"))
         .with_state(state)
 }
 
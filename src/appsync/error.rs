@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum AppSyncError {
@@ -37,17 +38,23 @@ impl AppSyncError {
     }
 }
 
+impl AwsError for AppSyncError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        AppSyncError::message(self)
+    }
+}
+
 impl IntoResponse for AppSyncError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "message": self.message(),
-        });
-        let mut resp = (self.status_code(), axum::Json(body)).into_response();
-        resp.headers_mut().insert(
-            "x-amzn-ErrorType",
-            axum::http::HeaderValue::from_str(self.error_code()).unwrap(),
-        );
-        resp
+        error_response(&self, Protocol::RestJson)
     }
 }
 
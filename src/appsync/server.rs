@@ -280,6 +280,7 @@ pub fn create_router(state: Arc<AppSyncState>) -> Router {
         .route("/v1/tags/{resourceArn}", post(tag_resource))
         .route("/v1/tags/{resourceArn}", delete(untag_resource))
         .route("/v1/tags/{resourceArn}", get(list_tags_for_resource))
+        .merge(crate::healthcheck::router("appsync"))
         .with_state(state)
 }
 
@@ -36,6 +36,13 @@ impl AppSyncState {
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
     fn generate_id() -> String {
         let hex = Uuid::new_v4().to_string().replace('-', "");
         hex[..26].to_string()
@@ -0,0 +1,89 @@
+//! ARNs passed to one service that name a resource of another.
+//!
+//! A server simulates a single account in a single region, so an ARN for
+//! another account or region is almost always a typo. Services check such
+//! ARNs with [`check_local`] and report a mismatch as their own validation
+//! error, unless `--allow-cross-account` is set to simulate several
+//! accounts against one server.
+
+/// The parts of `arn:partition:service:region:account-id:resource`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arn<'a> {
+    pub partition: &'a str,
+    pub service: &'a str,
+    pub region: &'a str,
+    pub account_id: &'a str,
+    pub resource: &'a str,
+}
+
+impl<'a> Arn<'a> {
+    /// Splits `arn` into its parts. The resource may itself contain `:`.
+    pub fn parse(arn: &'a str) -> Option<Self> {
+        let mut parts = arn.splitn(6, ':');
+        if parts.next()? != "arn" {
+            return None;
+        }
+        let arn = Arn {
+            partition: parts.next()?,
+            service: parts.next()?,
+            region: parts.next()?,
+            account_id: parts.next()?,
+            resource: parts.next()?,
+        };
+        (!arn.partition.is_empty() && !arn.service.is_empty() && !arn.resource.is_empty()).then_some(arn)
+    }
+}
+
+/// Parses `arn` and, unless `allow_cross_account`, checks that it names
+/// `account_id` and `region`. Global resources, whose ARNs have no region
+/// or account, always pass those checks. The error is a message for the
+/// calling service's validation error.
+pub fn check_local<'a>(
+    arn: &'a str,
+    account_id: &str,
+    region: &str,
+    allow_cross_account: bool,
+) -> Result<Arn<'a>, String> {
+    let parsed = Arn::parse(arn).ok_or_else(|| format!("{arn} is not a valid ARN"))?;
+    if allow_cross_account {
+        return Ok(parsed);
+    }
+    if !parsed.account_id.is_empty() && parsed.account_id != account_id {
+        return Err(format!(
+            "{arn} belongs to account {}, not {account_id}; start the server with --allow-cross-account to allow this",
+            parsed.account_id
+        ));
+    }
+    if !parsed.region.is_empty() && parsed.region != region {
+        return Err(format!(
+            "{arn} is in region {}, not {region}; start the server with --allow-cross-account to allow this",
+            parsed.region
+        ));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_colons_in_resource() {
+        let arn = Arn::parse("arn:aws:logs:us-east-1:123456789012:log-group:app:*").unwrap();
+        assert_eq!(arn.service, "logs");
+        assert_eq!(arn.resource, "log-group:app:*");
+        assert!(Arn::parse("not-an-arn").is_none());
+        assert!(Arn::parse("arn:aws:sqs:us-east-1").is_none());
+    }
+
+    #[test]
+    fn test_check_local_rejects_other_accounts_and_regions() {
+        let check = |arn, allow| check_local(arn, "123456789012", "us-east-1", allow);
+        assert!(check("arn:aws:sqs:us-east-1:123456789012:q", false).is_ok());
+        assert!(check("arn:aws:s3:::bucket", false).is_ok());
+        assert!(check("arn:aws:sqs:us-west-2:123456789012:q", false).unwrap_err().contains("us-west-2"));
+        assert!(check("arn:aws:sqs:us-east-1:210987654321:q", false).unwrap_err().contains("210987654321"));
+        assert!(check("arn:aws:sqs:us-west-2:210987654321:q", true).is_ok());
+        assert!(check("queue-name", true).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 
+use crate::aws_error::{error_response, AwsError, Protocol};
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum AutoscalingError {
@@ -40,22 +42,23 @@ impl AutoscalingError {
     }
 }
 
+impl AwsError for AutoscalingError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        AutoscalingError::message(self)
+    }
+}
+
 impl IntoResponse for AutoscalingError {
-    #[allow(dead_code)]
     fn into_response(self) -> Response {
-        let body = format!(
-            r#"<ErrorResponse xmlns="https://autoscaling.amazonaws.com/doc/2012-10-01/">
-  <Error>
-    <Code>{}</Code>
-    <Message>{}</Message>
-  </Error>
-  <RequestId>{}</RequestId>
-</ErrorResponse>"#,
-            self.error_code(),
-            self.message(),
-            uuid::Uuid::new_v4(),
-        );
-        (self.status_code(), [("content-type", "text/xml")], body).into_response()
+        error_response(&self, Protocol::Query("https://autoscaling.amazonaws.com/doc/2012-10-01/"))
     }
 }
 
@@ -78,6 +78,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<AutoscalingState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("autoscaling"))
         .with_state(state)
 }
 
@@ -0,0 +1,184 @@
+//! The error contract shared by every service's error enum, and the one
+//! place that turns an error into the body its wire protocol expects.
+
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde_json::json;
+
+pub trait AwsError {
+    /// The AWS error code, e.g. `ResourceNotFoundException`.
+    fn code(&self) -> &str;
+    fn http_status(&self) -> StatusCode;
+    fn message(&self) -> &str;
+    /// Whether the caller (`Sender`) rather than the service (`Receiver`)
+    /// is at fault.
+    fn sender_fault(&self) -> bool {
+        !self.http_status().is_server_error()
+    }
+}
+
+/// How a service reports errors on the wire.
+#[derive(Debug, Clone, Copy)]
+pub enum Protocol {
+    /// `awsJson`: the code in the body's `__type`.
+    Json,
+    /// `awsJson` with a namespaced `__type`, e.g. `com.amazonaws.sqs#`.
+    NamespacedJson(&'static str),
+    /// `restJson`: the code in the `x-amzn-ErrorType` header.
+    RestJson,
+    /// `awsQuery`: an XML `ErrorResponse` in the service's namespace.
+    Query(&'static str),
+    /// The EC2 query dialect: `Response/Errors/Error`.
+    Ec2,
+    /// `restXml`: an S3-style `Error` document.
+    RestXml,
+}
+
+pub fn error_response(err: &impl AwsError, protocol: Protocol) -> Response {
+    let status = err.http_status();
+    let code = err.code();
+    let message = err.message();
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let mut resp = match protocol {
+        Protocol::Json => (status, axum::Json(json!({ "__type": code, "message": message }))).into_response(),
+        Protocol::NamespacedJson(namespace) => {
+            let body = json!({ "__type": format!("{namespace}{code}"), "message": message });
+            (status, axum::Json(body)).into_response()
+        }
+        Protocol::RestJson => {
+            let mut resp = (status, axum::Json(json!({ "message": message }))).into_response();
+            if let Ok(value) = HeaderValue::from_str(code) {
+                resp.headers_mut().insert("x-amzn-ErrorType", value);
+            }
+            resp
+        }
+        Protocol::Query(namespace) => {
+            let fault = if err.sender_fault() { "Sender" } else { "Receiver" };
+            let body = format!(
+                "<ErrorResponse xmlns=\"{namespace}\">\n  <Error>\n    <Type>{fault}</Type>\n    <Code>{}</Code>\n    \
+                 <Message>{}</Message>\n  </Error>\n  <RequestId>{request_id}</RequestId>\n</ErrorResponse>",
+                xml_escape(code),
+                xml_escape(message),
+            );
+            (status, [(CONTENT_TYPE, "text/xml")], body).into_response()
+        }
+        Protocol::Ec2 => {
+            let body = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Response><Errors><Error><Code>{}</Code>\
+                 <Message>{}</Message></Error></Errors><RequestID>{request_id}</RequestID></Response>",
+                xml_escape(code),
+                xml_escape(message),
+            );
+            (status, [(CONTENT_TYPE, "text/xml")], body).into_response()
+        }
+        Protocol::RestXml => {
+            let body = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Error>\n  <Code>{}</Code>\n  <Message>{}</Message>\n  \
+                 <RequestId>{request_id}</RequestId>\n</Error>",
+                xml_escape(code),
+                xml_escape(message),
+            );
+            (status, [(CONTENT_TYPE, "application/xml")], body).into_response()
+        }
+    };
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        resp.headers_mut().insert("x-amzn-RequestId", value);
+    }
+    resp
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apigateway::error::ApiGatewayError;
+    use crate::cloudwatchlogs::error::CwlError;
+    use crate::cognito::error::CognitoError;
+    use crate::dynamodb::error::DynamoDbError;
+    use crate::kinesis::error::KinesisError;
+    use crate::lambda::error::LambdaError;
+    use crate::s3::error::S3Error;
+    use crate::secretsmanager::error::SecretsManagerError;
+    use crate::ses::error::SesError;
+    use crate::sns::error::SnsError;
+    use crate::sqs::error::SqsError;
+    use crate::ssm::error::SsmError;
+    use crate::stepfunctions::error::SfnError;
+
+    fn check(err: &dyn AwsError, status: u16, code: &str) {
+        assert_eq!(err.http_status().as_u16(), status, "{code}");
+        assert_eq!(err.code(), code);
+    }
+
+    #[test]
+    fn test_representative_errors_match_aws() {
+        let m = || "m".to_string();
+        check(&SqsError::QueueDoesNotExist(m()), 400, "QueueDoesNotExist");
+        check(&DynamoDbError::ResourceNotFoundException(m()), 400, "ResourceNotFoundException");
+        check(&DynamoDbError::ConditionalCheckFailedException(m()), 400, "ConditionalCheckFailedException");
+        check(&S3Error::NoSuchKey(m()), 404, "NoSuchKey");
+        check(&S3Error::BucketNotEmpty(m()), 409, "BucketNotEmpty");
+        check(&LambdaError::ResourceNotFoundException(m()), 404, "ResourceNotFoundException");
+        check(&LambdaError::ResourceConflictException(m()), 409, "ResourceConflictException");
+        check(&SnsError::NotFound(m()), 404, "NotFound");
+        check(&CognitoError::UserNotFoundException(m()), 400, "UserNotFoundException");
+        check(&SecretsManagerError::ResourceNotFoundException(m()), 400, "ResourceNotFoundException");
+        check(&KinesisError::ResourceNotFoundException(m()), 400, "ResourceNotFoundException");
+        check(&SfnError::StateMachineDoesNotExist(m()), 400, "StateMachineDoesNotExist");
+        check(&SsmError::ParameterNotFound(m()), 400, "ParameterNotFound");
+        check(&CwlError::ResourceNotFoundException(m()), 400, "ResourceNotFoundException");
+        check(&ApiGatewayError::NotFoundException(m()), 404, "NotFoundException");
+        check(&SesError::NotFoundException(m()), 404, "NotFoundException");
+    }
+
+    async fn read_body(resp: Response) -> String {
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_json_bodies() {
+        let resp = error_response(&DynamoDbError::ResourceNotFoundException("gone".into()), Protocol::Json);
+        assert!(resp.headers().contains_key("x-amzn-RequestId"));
+        let body: serde_json::Value = serde_json::from_str(&read_body(resp).await).unwrap();
+        assert_eq!(body["__type"], "ResourceNotFoundException");
+        assert_eq!(body["message"], "gone");
+
+        let err = SqsError::QueueDoesNotExist("gone".into());
+        let resp = error_response(&err, Protocol::NamespacedJson("com.amazonaws.sqs#"));
+        let body: serde_json::Value = serde_json::from_str(&read_body(resp).await).unwrap();
+        assert_eq!(body["__type"], "com.amazonaws.sqs#QueueDoesNotExist");
+
+        let resp = error_response(&LambdaError::ResourceNotFoundException("gone".into()), Protocol::RestJson);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(resp.headers()["x-amzn-ErrorType"], "ResourceNotFoundException");
+    }
+
+    #[tokio::test]
+    async fn test_xml_bodies() {
+        let resp = error_response(&SnsError::NotFound("a <b>".into()), Protocol::Query("http://sns/"));
+        let xml = read_body(resp).await;
+        assert!(xml.contains("<ErrorResponse xmlns=\"http://sns/\">"), "{xml}");
+        assert!(xml.contains("<Type>Sender</Type>"), "{xml}");
+        assert!(xml.contains("<Code>NotFound</Code>"), "{xml}");
+        assert!(xml.contains("<Message>a &lt;b&gt;</Message>"), "{xml}");
+
+        let resp = error_response(&S3Error::NoSuchKey("k".into()), Protocol::RestXml);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let xml = read_body(resp).await;
+        assert!(xml.contains("<Error>\n  <Code>NoSuchKey</Code>"), "{xml}");
+
+        let resp = error_response(&S3Error::NoSuchKey("k".into()), Protocol::Ec2);
+        let xml = read_body(resp).await;
+        assert!(xml.contains("<Response><Errors><Error><Code>NoSuchKey</Code>"), "{xml}");
+        assert!(xml.contains("<RequestID>"), "{xml}");
+    }
+}
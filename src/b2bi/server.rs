@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<B2biState>) -> Router {
     Router::new()
         .route("/profiles", post(create_profile_handler).get(list_profiles_handler))
         .route("/profiles/{name}", get(get_profile_handler).delete(delete_profile_handler))
+        .merge(crate::healthcheck::router("b2bi"))
         .with_state(state)
 }
 
@@ -78,6 +78,7 @@ pub fn create_router(state: Arc<BackupState>) -> Router {
         .route("/backup-vaults/{name}", get(get_backup_vault_handler).delete(delete_backup_vault_handler))
         .route("/backup-plans", post(create_backup_plan_handler).get(list_backup_plans_handler))
         .route("/backup-plans/{name}", get(get_backup_plan_handler).delete(delete_backup_plan_handler))
+        .merge(crate::healthcheck::router("backup"))
         .with_state(state)
 }
 
@@ -78,6 +78,7 @@ pub fn create_router(state: Arc<BatchState>) -> Router {
         .route("/compute-environments/{name}", get(get_compute_environment_handler).delete(delete_compute_environment_handler))
         .route("/job-queues", post(create_job_queue_handler).get(list_job_queues_handler))
         .route("/job-queues/{name}", get(get_job_queue_handler).delete(delete_job_queue_handler))
+        .merge(crate::healthcheck::router("batch"))
         .with_state(state)
 }
 
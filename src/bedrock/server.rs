@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<BedrockState>) -> Router {
     Router::new()
         .route("/model-customization-jobs", post(create_model_customization_job_handler).get(list_model_customization_jobs_handler))
         .route("/model-customization-jobs/{name}", get(get_model_customization_job_handler).delete(delete_model_customization_job_handler))
+        .merge(crate::healthcheck::router("bedrock"))
         .with_state(state)
 }
 
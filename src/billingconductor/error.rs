@@ -1,6 +1,8 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 
+use crate::aws_error::{error_response, AwsError, Protocol};
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum BillingconductorError {
@@ -40,14 +42,23 @@ impl BillingconductorError {
     }
 }
 
+impl AwsError for BillingconductorError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        BillingconductorError::message(self)
+    }
+}
+
 impl IntoResponse for BillingconductorError {
-    #[allow(dead_code)]
     fn into_response(self) -> Response {
-        let body = serde_json::json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -55,6 +55,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<BillingconductorState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("billingconductor"))
         .with_state(state)
 }
 
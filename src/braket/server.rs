@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<BraketState>) -> Router {
     Router::new()
         .route("/quantum-tasks", post(create_quantum_task_handler).get(list_quantum_tasks_handler))
         .route("/quantum-tasks/{name}", get(get_quantum_task_handler).delete(delete_quantum_task_handler))
+        .merge(crate::healthcheck::router("braket"))
         .with_state(state)
 }
 
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::dynamodb::state::DynamoDbState;
+use crate::lambda::state::LambdaState;
+use crate::memorydb::state::MemoryDbState;
+use crate::s3::state::S3State;
+use crate::sns::state::SnsState;
+use crate::sns::types::PublishRequest;
+use crate::sqs::state::SqsState;
+use crate::sqs::types::{MessageAttributeValue, SendMessageRequest};
+
+/// Cross-service delivery hook shared by services that fan out to other
+/// services (EventBridge targets, SNS subscriptions, ...). Handles are
+/// attached after construction so services can reference each other
+/// regardless of the order they are built in `main`.
+#[derive(Default)]
+pub struct ServiceBus {
+    sqs: OnceLock<Arc<SqsState>>,
+    lambda: OnceLock<Arc<LambdaState>>,
+    s3: OnceLock<Arc<S3State>>,
+    sns: OnceLock<Arc<SnsState>>,
+    dynamodb: OnceLock<Arc<DynamoDbState>>,
+    memorydb: OnceLock<Arc<MemoryDbState>>,
+}
+
+impl ServiceBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn attach_sqs(&self, sqs: Arc<SqsState>) {
+        let _ = self.sqs.set(sqs);
+    }
+
+    pub fn attach_lambda(&self, lambda: Arc<LambdaState>) {
+        let _ = self.lambda.set(lambda);
+    }
+
+    pub fn attach_s3(&self, s3: Arc<S3State>) {
+        let _ = self.s3.set(s3);
+    }
+
+    pub fn attach_sns(&self, sns: Arc<SnsState>) {
+        let _ = self.sns.set(sns);
+    }
+
+    pub fn attach_dynamodb(&self, dynamodb: Arc<DynamoDbState>) {
+        let _ = self.dynamodb.set(dynamodb);
+    }
+
+    pub fn attach_memorydb(&self, memorydb: Arc<MemoryDbState>) {
+        let _ = self.memorydb.set(memorydb);
+    }
+
+    /// ARNs and tags of every tagged resource in the attached services, for
+    /// the Resource Groups Tagging API.
+    pub async fn tagged_resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let mut resources = Vec::new();
+        if let Some(sqs) = self.sqs.get() {
+            resources.extend(sqs.tagged_resources().await);
+        }
+        if let Some(sns) = self.sns.get() {
+            resources.extend(sns.tagged_resources().await);
+        }
+        if let Some(lambda) = self.lambda.get() {
+            resources.extend(lambda.tagged_resources().await);
+        }
+        if let Some(s3) = self.s3.get() {
+            resources.extend(s3.tagged_resources().await);
+        }
+        if let Some(dynamodb) = self.dynamodb.get() {
+            resources.extend(dynamodb.tagged_resources().await);
+        }
+        if let Some(memorydb) = self.memorydb.get() {
+            resources.extend(memorydb.tagged_resources().await);
+        }
+        resources
+    }
+
+    /// Publishes `message` to the SNS topic `topic_arn`, which fans it out
+    /// to the topic's subscribers. Returns the SNS message ID.
+    pub async fn publish_sns(
+        &self,
+        topic_arn: &str,
+        subject: Option<String>,
+        message: String,
+    ) -> Result<String, String> {
+        let sns = self
+            .sns
+            .get()
+            .ok_or_else(|| "SNS is not available".to_string())?;
+        sns.publish(PublishRequest {
+            topic_arn: Some(topic_arn.to_string()),
+            subject,
+            message,
+            ..Default::default()
+        })
+        .await
+        .map(|resp| resp.message_id)
+        .map_err(|e| format!("{e:?}"))
+    }
+
+    /// Writes an object to the bucket identified by `bucket_arn`
+    /// (`arn:aws:s3:::bucket`).
+    pub async fn put_s3_object(
+        &self,
+        bucket_arn: &str,
+        key: String,
+        body: Vec<u8>,
+        content_type: Option<String>,
+    ) -> Result<(), String> {
+        let bucket = bucket_arn
+            .strip_prefix("arn:aws:s3:::")
+            .ok_or_else(|| format!("Invalid bucket ARN: {bucket_arn}"))?;
+        let s3 = self
+            .s3
+            .get()
+            .ok_or_else(|| "S3 is not available".to_string())?;
+        s3.put_object(bucket, key, body, content_type, HashMap::new())
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("{e:?}"))
+    }
+
+    /// Synchronously invokes the Lambda function identified by `arn` (or a
+    /// bare function name) and returns its result. API and function errors
+    /// are both reported as the error string.
+    pub async fn invoke_lambda(
+        &self,
+        arn: &str,
+        payload: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let lambda = self
+            .lambda
+            .get()
+            .ok_or_else(|| "Lambda is not available".to_string())?;
+        let function_name = arn
+            .rsplit_once(":function:")
+            .map(|(_, name)| name)
+            .unwrap_or(arn);
+        // Drop any version or alias qualifier.
+        let function_name = function_name.split(':').next().unwrap_or(function_name);
+        lambda
+            .invoke_with_payload(function_name, payload)
+            .await
+            .map_err(|e| format!("{e:?}"))?
+    }
+
+    /// Delivers `payload` to the resource identified by `arn`. Returns a
+    /// human-readable reason on failure so callers can record it.
+    pub async fn deliver(&self, arn: &str, payload: &str) -> Result<(), String> {
+        self.deliver_with_attributes(arn, payload, HashMap::new()).await
+    }
+
+    /// Like [`deliver`](Self::deliver), additionally attaching string
+    /// message attributes where the target supports them (SQS).
+    pub async fn deliver_with_attributes(
+        &self,
+        arn: &str,
+        payload: &str,
+        attributes: HashMap<String, String>,
+    ) -> Result<(), String> {
+        let parts: Vec<&str> = arn.splitn(6, ':').collect();
+        if parts.len() != 6 || parts[0] != "arn" {
+            return Err(format!("Invalid target ARN: {arn}"));
+        }
+        match parts[2] {
+            "sqs" => {
+                let sqs = self
+                    .sqs
+                    .get()
+                    .ok_or_else(|| "SQS is not available".to_string())?;
+                // SqsState resolves queues by the last path segment of the
+                // URL, so the bare queue name is accepted as a URL.
+                sqs.send_message(SendMessageRequest {
+                    queue_url: parts[5].to_string(),
+                    message_body: payload.to_string(),
+                    message_attributes: (!attributes.is_empty()).then(|| {
+                        attributes
+                            .into_iter()
+                            .map(|(k, v)| {
+                                (k, MessageAttributeValue {
+                                    data_type: "String".to_string(),
+                                    string_value: Some(v),
+                                    binary_value: None,
+                                })
+                            })
+                            .collect()
+                    }),
+                    ..Default::default()
+                })
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("{e:?}"))
+            }
+            "lambda" => {
+                // The function gets the payload as its event; a function
+                // error fails the delivery like a missing function does.
+                let event = serde_json::from_str(payload)
+                    .unwrap_or_else(|_| serde_json::Value::String(payload.to_string()));
+                self.invoke_lambda(arn, event).await.map(|_| ())
+            }
+            other => Err(format!("Unsupported target service: {other}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqs::types::{CreateQueueRequest, ReceiveMessageRequest};
+
+    #[tokio::test]
+    async fn test_deliver_to_sqs() {
+        let sqs = Arc::new(SqsState::new("123456789012".into(), "us-east-1".into(), 9324));
+        let url = sqs
+            .create_queue(CreateQueueRequest {
+                queue_name: "target".into(),
+                attributes: None,
+                tags: None,
+            })
+            .await
+            .unwrap()
+            .queue_url;
+        let bus = ServiceBus::new();
+        bus.attach_sqs(sqs.clone());
+        bus.deliver("arn:aws:sqs:us-east-1:123456789012:target", "hello")
+            .await
+            .unwrap();
+        let msgs = sqs
+            .receive_message(ReceiveMessageRequest {
+                queue_url: url,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .messages
+            .unwrap_or_default();
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].body, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_deliver_without_attached_service() {
+        let bus = ServiceBus::new();
+        assert!(bus
+            .deliver("arn:aws:sqs:us-east-1:123456789012:q", "x")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_invalid_arn() {
+        let bus = ServiceBus::new();
+        assert!(bus.deliver("not-an-arn", "x").await.is_err());
+    }
+}
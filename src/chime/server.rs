@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<ChimeState>) -> Router {
     Router::new()
         .route("/accounts", post(create_account_handler).get(list_accounts_handler))
         .route("/accounts/{name}", get(get_account_handler).delete(delete_account_handler))
+        .merge(crate::healthcheck::router("chime"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<CleanroomsState>) -> Router {
     Router::new()
         .route("/collaborations", post(create_collaboration_handler).get(list_collaborations_handler))
         .route("/collaborations/{name}", get(get_collaboration_handler).delete(delete_collaboration_handler))
+        .merge(crate::healthcheck::router("cleanrooms"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<CloudfrontState>) -> Router {
     Router::new()
         .route("/distributions", post(create_distribution_handler).get(list_distributions_handler))
         .route("/distributions/{name}", get(get_distribution_handler).delete(delete_distribution_handler))
+        .merge(crate::healthcheck::router("cloudfront"))
         .with_state(state)
 }
 
@@ -78,6 +78,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<CloudwatchState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("cloudwatch"))
         .with_state(state)
 }
 
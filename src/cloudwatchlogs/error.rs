@@ -1,12 +1,14 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum CwlError {
     ResourceNotFoundException(String),
     ResourceAlreadyExistsException(String),
     InvalidParameterException(String),
+    MalformedQueryException(String),
     InvalidAction(String),
 }
 
@@ -16,6 +18,7 @@ impl CwlError {
             CwlError::ResourceNotFoundException(_) => "ResourceNotFoundException",
             CwlError::ResourceAlreadyExistsException(_) => "ResourceAlreadyExistsException",
             CwlError::InvalidParameterException(_) => "InvalidParameterException",
+            CwlError::MalformedQueryException(_) => "MalformedQueryException",
             CwlError::InvalidAction(_) => "InvalidAction",
         }
     }
@@ -32,18 +35,29 @@ impl CwlError {
             CwlError::ResourceNotFoundException(m)
             | CwlError::ResourceAlreadyExistsException(m)
             | CwlError::InvalidParameterException(m)
+            | CwlError::MalformedQueryException(m)
             | CwlError::InvalidAction(m) => m,
         }
     }
 }
 
+impl AwsError for CwlError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        CwlError::message(self)
+    }
+}
+
 impl IntoResponse for CwlError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -67,6 +81,11 @@ mod tests {
         assert_eq!(err.error_code(), "InvalidParameterException");
     }
     #[test]
+    fn test_malformedqueryexception_error_code() {
+        let err = CwlError::MalformedQueryException("test".to_string());
+        assert_eq!(err.error_code(), "MalformedQueryException");
+    }
+    #[test]
     fn test_invalidaction_error_code() {
         let err = CwlError::InvalidAction("test".to_string());
         assert_eq!(err.error_code(), "InvalidAction");
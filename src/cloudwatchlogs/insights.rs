@@ -0,0 +1,1078 @@
+//! A subset of the CloudWatch Logs Insights query language:
+//!
+//! ```text
+//! fields @timestamp, @message, level
+//!     | filter level = "error" and latency > 100
+//!     | stats count(*) as errors, avg(latency) by service, bin(5m)
+//!     | sort errors desc
+//!     | limit 20
+//! ```
+//!
+//! Commands are `fields`, `display`, `filter`, `stats`, `sort` and `limit`.
+//! Expressions support comparisons, `and`/`or`/`not`, arithmetic,
+//! `[not] like "substring"`, `like /regex/`, `=~ /regex/`, `[not] in [...]`
+//! and a handful of scalar functions. `stats` supports `count`,
+//! `count_distinct`, `sum`, `avg`, `min` and `max`, grouped by any
+//! expressions including `bin(period)`. Events carry `@timestamp`,
+//! `@message`, `@logStream`, `@log` and `@ingestionTime`, plus every field
+//! discovered in a JSON message, with nested keys joined by dots.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+use serde_json::{Map, Number, Value};
+
+use super::error::CwlError;
+
+/// Rows returned when neither the query nor the request sets a limit.
+pub const DEFAULT_LIMIT: usize = 1000;
+
+/// One log event as seen by a query.
+pub struct Event<'a> {
+    /// `<account-id>:<log-group-name>`, the value of `@log`.
+    pub log: &'a str,
+    pub log_stream: &'a str,
+    pub timestamp: i64,
+    pub ingestion_time: i64,
+    pub message: &'a str,
+}
+
+/// The rows a query produced as `(field, value)` pairs, and how much input
+/// it read.
+pub struct QueryOutput {
+    pub rows: Vec<Vec<(String, String)>>,
+    pub records_matched: u64,
+    pub records_scanned: u64,
+    pub bytes_scanned: u64,
+}
+
+/// Parses a query string, rejecting anything outside the supported subset.
+pub fn parse(query: &str) -> Result<Query, CwlError> {
+    Parser::new(query)?.query()
+}
+
+fn query_error(message: impl Into<String>) -> CwlError {
+    CwlError::MalformedQueryException(message.into())
+}
+
+type Row = HashMap<String, Value>;
+
+fn event_row(event: &Event) -> Row {
+    let mut row = Row::new();
+    if let Ok(Value::Object(fields)) = serde_json::from_str::<Value>(event.message) {
+        flatten("", &fields, &mut row);
+    }
+    row.insert("@timestamp".into(), Value::from(event.timestamp));
+    row.insert("@ingestionTime".into(), Value::from(event.ingestion_time));
+    row.insert("@message".into(), Value::from(event.message));
+    row.insert("@logStream".into(), Value::from(event.log_stream));
+    row.insert("@log".into(), Value::from(event.log));
+    row
+}
+
+/// Discovers JSON fields the way Insights does: `{"a": {"b": [1]}}` yields
+/// `a.b.0`.
+fn flatten(prefix: &str, fields: &Map<String, Value>, row: &mut Row) {
+    for (key, value) in fields {
+        let name = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+        match value {
+            Value::Null => {}
+            Value::Object(inner) => flatten(&name, inner, row),
+            Value::Array(items) => {
+                let indexed = items.iter().enumerate().map(|(i, v)| (i.to_string(), v.clone())).collect();
+                flatten(&name, &indexed, row);
+            }
+            other => {
+                row.insert(name, other.clone());
+            }
+        }
+    }
+}
+
+// --- Parsing ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A field name or keyword, and whether it was backtick-quoted.
+    Ident(String, bool),
+    Str(String),
+    Num(Value),
+    /// A period such as `5m`, in milliseconds.
+    Duration(i64),
+    Regex(String),
+    Sym(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(s, _) => s.clone(),
+            Token::Str(s) => format!("\"{s}\""),
+            Token::Num(v) => v.to_string(),
+            Token::Duration(ms) => format!("{ms}ms"),
+            Token::Regex(r) => format!("/{r}/"),
+            Token::Sym(s) => s.to_string(),
+        }
+    }
+}
+
+struct Spanned {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+fn duration_unit(unit: &str) -> Option<i64> {
+    Some(match unit {
+        "ms" => 1,
+        "s" | "sec" => 1_000,
+        "m" | "min" => 60_000,
+        "h" | "hr" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 7 * 86_400_000,
+        _ => return None,
+    })
+}
+
+fn tokenize(chars: &[char]) -> Result<Vec<Spanned>, CwlError> {
+    const PAIRS: &[&str] = &["<=", ">=", "!=", "==", "=~"];
+    const SINGLES: &[&str] = &["|", "(", ")", ",", "[", "]", "=", "<", ">", "+", "-", "*", "/", "%"];
+    let mut tokens: Vec<Spanned> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let regex_allowed = match tokens.last().map(|t| &t.token) {
+            Some(Token::Sym("=~")) => true,
+            Some(Token::Ident(kw, false)) => kw.eq_ignore_ascii_case("like"),
+            _ => false,
+        };
+        let token = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c == '"' || c == '\'' || c == '`' || (c == '/' && regex_allowed) {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(query_error(format!("Unterminated literal starting at position {start}"))),
+                    Some('\\') if c != '/' && chars.get(i + 1).is_some() => {
+                        s.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some('\\') if chars.get(i + 1) == Some(&'/') => {
+                        s.push('/');
+                        i += 2;
+                    }
+                    Some(&ch) if ch == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&ch) => {
+                        s.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            match c {
+                '`' => Token::Ident(s, true),
+                '/' => Token::Regex(s),
+                _ => Token::Str(s),
+            }
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let s: String = chars[start..i].iter().collect();
+            let unit_start = i;
+            while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                i += 1;
+            }
+            if unit_start < i {
+                let unit: String = chars[unit_start..i].iter().collect();
+                match (s.parse::<i64>(), duration_unit(&unit)) {
+                    (Ok(n), Some(ms)) if n > 0 => Token::Duration(n * ms),
+                    _ => return Err(query_error(format!("Invalid time period: {s}{unit}"))),
+                }
+            } else {
+                match s.parse::<i64>() {
+                    Ok(n) => Token::Num(Value::from(n)),
+                    Err(_) => s
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .map(|n| Token::Num(Value::Number(n)))
+                        .ok_or_else(|| query_error(format!("Invalid number: {s}")))?,
+                }
+            }
+        } else if c.is_alphabetic() || c == '_' || c == '@' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '@' | '.' | '$')) {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect(), false)
+        } else {
+            let pair: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let sym = PAIRS
+                .iter()
+                .find(|s| **s == pair)
+                .or_else(|| SINGLES.iter().find(|s| s.starts_with(c)))
+                .ok_or_else(|| query_error(format!("Unexpected character '{c}' in query")))?;
+            i += sym.len();
+            Token::Sym(sym)
+        };
+        tokens.push(Spanned { token, start, end: i });
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Agg {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+#[derive(Debug)]
+enum Pattern {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn matches(&self, s: &str) -> bool {
+        match self {
+            Pattern::Substring(needle) => s.contains(needle.as_str()),
+            Pattern::Regex(re) => re.is_match(s),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Expr {
+    Literal(Value),
+    Field(String),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Like { expr: Box<Expr>, pattern: Pattern, negated: bool },
+    In { expr: Box<Expr>, list: Vec<Expr>, negated: bool },
+    /// `bin(period)`: `@timestamp` rounded down to a multiple of `period`.
+    Bin(i64),
+    Call(String, Vec<Expr>),
+    /// An aggregate, named by its source text so later commands can refer
+    /// to the column it produced, e.g. `sort count(*) desc`.
+    Aggregate { agg: Agg, arg: Option<Box<Expr>>, name: String },
+}
+
+impl Expr {
+    /// Whether the expression yields an epoch-millisecond timestamp, which
+    /// results render as a date.
+    fn is_time(&self, time_fields: &HashSet<String>) -> bool {
+        match self {
+            Expr::Field(name) => time_fields.contains(name),
+            Expr::Bin(_) => true,
+            Expr::Aggregate { agg: Agg::Min | Agg::Max, arg: Some(arg), .. } => arg.is_time(time_fields),
+            _ => false,
+        }
+    }
+
+    fn contains_aggregate(&self) -> bool {
+        match self {
+            Expr::Aggregate { .. } => true,
+            Expr::Neg(e) | Expr::Not(e) => e.contains_aggregate(),
+            Expr::Binary(_, l, r) => l.contains_aggregate() || r.contains_aggregate(),
+            Expr::Like { expr, .. } => expr.contains_aggregate(),
+            Expr::In { expr, list, .. } => expr.contains_aggregate() || list.iter().any(Expr::contains_aggregate),
+            Expr::Call(_, args) => args.iter().any(Expr::contains_aggregate),
+            Expr::Literal(_) | Expr::Field(_) | Expr::Bin(_) => false,
+        }
+    }
+}
+
+/// An expression and the column name it is output under.
+#[derive(Debug)]
+struct Named {
+    expr: Expr,
+    name: String,
+}
+
+#[derive(Debug)]
+enum Command {
+    Fields(Vec<Named>),
+    Display(Vec<String>),
+    Filter(Expr),
+    Stats { aggregates: Vec<Named>, by: Vec<Named> },
+    Sort(Vec<(Expr, bool)>),
+    Limit(usize),
+}
+
+/// A parsed query, ready to run over any set of events.
+#[derive(Debug)]
+pub struct Query {
+    commands: Vec<Command>,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(query: &str) -> Result<Self, CwlError> {
+        let chars: Vec<char> = query.chars().collect();
+        let tokens = tokenize(&chars)?;
+        Ok(Self { chars, tokens, pos: 0 })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn is_kw(&self, kw: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s, false)) if s.eq_ignore_ascii_case(kw))
+    }
+
+    fn eat_kw(&mut self, kw: &str) -> bool {
+        let found = self.is_kw(kw);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_sym(&mut self, sym: &'static str) -> bool {
+        let found = self.peek() == Some(&Token::Sym(sym));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_sym(&mut self, sym: &'static str) -> Result<(), CwlError> {
+        if self.eat_sym(sym) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{sym}'")))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> CwlError {
+        match self.peek() {
+            Some(token) => query_error(format!("Expected {expected} but found {}", token.describe())),
+            None => query_error(format!("Expected {expected} but reached the end of the query")),
+        }
+    }
+
+    fn at_command_end(&self) -> bool {
+        matches!(self.peek(), None | Some(Token::Sym("|")))
+    }
+
+    /// The query text covered by tokens `from..self.pos`.
+    fn source(&self, from: usize) -> String {
+        let start = self.tokens[from].start;
+        let end = self.tokens[self.pos - 1].end;
+        self.chars[start..end].iter().collect()
+    }
+
+    fn ident(&mut self) -> Result<String, CwlError> {
+        match self.peek().cloned() {
+            Some(Token::Ident(name, _)) => {
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.unexpected("a field name")),
+        }
+    }
+
+    fn query(mut self) -> Result<Query, CwlError> {
+        let mut commands = Vec::new();
+        loop {
+            commands.push(self.command()?);
+            if self.peek().is_none() {
+                break;
+            }
+            self.expect_sym("|")?;
+        }
+        Ok(Query { commands })
+    }
+
+    fn command(&mut self) -> Result<Command, CwlError> {
+        let name = self.ident()?.to_ascii_lowercase();
+        let command = match name.as_str() {
+            "fields" => Command::Fields(self.named_list()?),
+            "display" => {
+                let mut names = vec![self.ident()?];
+                while self.eat_sym(",") {
+                    names.push(self.ident()?);
+                }
+                Command::Display(names)
+            }
+            "filter" => {
+                let expr = self.expr()?;
+                if expr.contains_aggregate() {
+                    return Err(query_error("Aggregate functions are only allowed in stats"));
+                }
+                Command::Filter(expr)
+            }
+            "stats" => {
+                let aggregates = self.named_list()?;
+                if let Some(item) = aggregates.iter().find(|i| !matches!(i.expr, Expr::Aggregate { .. })) {
+                    return Err(query_error(format!("stats expects aggregate functions but found {}", item.name)));
+                }
+                let by = if self.eat_kw("by") { self.named_list()? } else { Vec::new() };
+                if by.iter().any(|b| b.expr.contains_aggregate()) {
+                    return Err(query_error("Aggregate functions are not allowed in stats by"));
+                }
+                Command::Stats { aggregates, by }
+            }
+            "sort" => {
+                let mut keys = Vec::new();
+                loop {
+                    let expr = self.expr()?;
+                    let desc = if self.eat_kw("desc") {
+                        true
+                    } else {
+                        self.eat_kw("asc");
+                        false
+                    };
+                    keys.push((expr, desc));
+                    if !self.eat_sym(",") {
+                        break;
+                    }
+                }
+                Command::Sort(keys)
+            }
+            "limit" => match self.peek().cloned() {
+                Some(Token::Num(Value::Number(n))) if n.as_u64().is_some_and(|n| n > 0) => {
+                    self.pos += 1;
+                    Command::Limit(n.as_u64().unwrap_or_default() as usize)
+                }
+                _ => return Err(self.unexpected("a positive row limit")),
+            },
+            other => return Err(query_error(format!("Unsupported query command: {other}"))),
+        };
+        if !self.at_command_end() {
+            return Err(self.unexpected("'|' or the end of the query"));
+        }
+        Ok(command)
+    }
+
+    fn named_list(&mut self) -> Result<Vec<Named>, CwlError> {
+        let mut items = Vec::new();
+        loop {
+            let from = self.pos;
+            let expr = self.expr()?;
+            let name = if self.eat_kw("as") { self.ident()? } else { self.source(from) };
+            let name = match &expr {
+                Expr::Field(field) if from + 1 == self.pos => field.clone(),
+                _ => name,
+            };
+            items.push(Named { expr, name });
+            if !self.eat_sym(",") {
+                return Ok(items);
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, CwlError> {
+        let mut left = self.and()?;
+        while self.eat_kw("or") {
+            left = Expr::Binary(BinOp::Or, Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, CwlError> {
+        let mut left = self.not()?;
+        while self.eat_kw("and") {
+            left = Expr::Binary(BinOp::And, Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, CwlError> {
+        if self.eat_kw("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.predicate()
+    }
+
+    fn predicate(&mut self) -> Result<Expr, CwlError> {
+        let left = self.additive()?;
+        for (sym, op) in [
+            ("=", BinOp::Eq),
+            ("==", BinOp::Eq),
+            ("!=", BinOp::Ne),
+            ("<", BinOp::Lt),
+            ("<=", BinOp::Le),
+            (">", BinOp::Gt),
+            (">=", BinOp::Ge),
+        ] {
+            if self.eat_sym(sym) {
+                return Ok(Expr::Binary(op, Box::new(left), Box::new(self.additive()?)));
+            }
+        }
+        if self.eat_sym("=~") {
+            return Ok(Expr::Like { expr: Box::new(left), pattern: self.pattern()?, negated: false });
+        }
+        let negated = self.eat_kw("not");
+        if self.eat_kw("like") {
+            return Ok(Expr::Like { expr: Box::new(left), pattern: self.pattern()?, negated });
+        }
+        if self.eat_kw("in") {
+            self.expect_sym("[")?;
+            let mut list = Vec::new();
+            if !self.eat_sym("]") {
+                loop {
+                    list.push(self.additive()?);
+                    if !self.eat_sym(",") {
+                        break;
+                    }
+                }
+                self.expect_sym("]")?;
+            }
+            return Ok(Expr::In { expr: Box::new(left), list, negated });
+        }
+        if negated {
+            return Err(self.unexpected("'like' or 'in'"));
+        }
+        Ok(left)
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, CwlError> {
+        match self.peek().cloned() {
+            Some(Token::Str(s)) => {
+                self.pos += 1;
+                Ok(Pattern::Substring(s))
+            }
+            Some(Token::Regex(r)) => {
+                self.pos += 1;
+                Regex::new(&r)
+                    .map(Pattern::Regex)
+                    .map_err(|e| query_error(format!("Invalid regular expression /{r}/: {e}")))
+            }
+            _ => Err(self.unexpected("a string or /regex/")),
+        }
+    }
+
+    fn additive(&mut self) -> Result<Expr, CwlError> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = if self.eat_sym("+") {
+                BinOp::Add
+            } else if self.eat_sym("-") {
+                BinOp::Sub
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, CwlError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat_sym("*") {
+                BinOp::Mul
+            } else if self.eat_sym("/") {
+                BinOp::Div
+            } else if self.eat_sym("%") {
+                BinOp::Mod
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, CwlError> {
+        if self.eat_sym("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, CwlError> {
+        let from = self.pos;
+        match self.peek().cloned() {
+            Some(Token::Num(v)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(v))
+            }
+            Some(Token::Str(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(Value::String(s)))
+            }
+            Some(Token::Sym("(")) => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                self.expect_sym(")")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name, quoted)) => {
+                self.pos += 1;
+                if quoted || !self.eat_sym("(") {
+                    return Ok(Expr::Field(name));
+                }
+                self.call(name.to_ascii_lowercase(), from)
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    /// A function call whose `(` has been consumed.
+    fn call(&mut self, name: String, from: usize) -> Result<Expr, CwlError> {
+        if name == "bin" {
+            let period = match self.peek().cloned() {
+                Some(Token::Duration(ms)) => ms,
+                _ => return Err(self.unexpected("a time period such as 5m")),
+            };
+            self.pos += 1;
+            self.expect_sym(")")?;
+            return Ok(Expr::Bin(period));
+        }
+        let agg = match name.as_str() {
+            "count" => Some(Agg::Count),
+            "count_distinct" => Some(Agg::CountDistinct),
+            "sum" => Some(Agg::Sum),
+            "avg" => Some(Agg::Avg),
+            "min" => Some(Agg::Min),
+            "max" => Some(Agg::Max),
+            _ => None,
+        };
+        if let Some(agg) = agg {
+            let arg = if agg == Agg::Count && (self.eat_sym("*") || self.peek() == Some(&Token::Sym(")"))) {
+                None
+            } else {
+                Some(Box::new(self.expr()?))
+            };
+            self.expect_sym(")")?;
+            return Ok(Expr::Aggregate { agg, arg, name: self.source(from) });
+        }
+        const SCALARS: &[&str] = &[
+            "ispresent", "isempty", "isblank", "strlen", "tolower", "toupper", "trim", "concat", "coalesce", "abs",
+            "ceil", "floor",
+        ];
+        if !SCALARS.contains(&name.as_str()) {
+            return Err(query_error(format!("Unsupported function: {name}")));
+        }
+        let mut args = Vec::new();
+        if !self.eat_sym(")") {
+            loop {
+                args.push(self.expr()?);
+                if !self.eat_sym(",") {
+                    break;
+                }
+            }
+            self.expect_sym(")")?;
+        }
+        Ok(Expr::Call(name, args))
+    }
+}
+
+// --- Evaluation ---
+
+fn num(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok().filter(|f| f.is_finite()),
+        _ => None,
+    }
+}
+
+fn float(f: f64) -> Value {
+    Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null)
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn truth(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) if s.eq_ignore_ascii_case("true") => Some(true),
+        Value::String(s) if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+/// Orders two values numerically when both are numbers, else as text.
+/// `None` when either side is missing.
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    if left.is_null() || right.is_null() {
+        return None;
+    }
+    match (num(left), num(right)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b),
+        _ => Some(text(left)?.cmp(&text(right)?)),
+    }
+}
+
+fn eval(expr: &Expr, row: &Row) -> Value {
+    match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Field(name) => row.get(name).cloned().unwrap_or(Value::Null),
+        Expr::Aggregate { name, .. } => row.get(name).cloned().unwrap_or(Value::Null),
+        Expr::Neg(inner) => num(&eval(inner, row)).map(|n| float(-n)).unwrap_or(Value::Null),
+        Expr::Not(inner) => truth(&eval(inner, row)).map(|b| Value::Bool(!b)).unwrap_or(Value::Null),
+        Expr::Binary(op @ (BinOp::And | BinOp::Or), left, right) => {
+            let left = truth(&eval(left, row)) == Some(true);
+            let right = truth(&eval(right, row)) == Some(true);
+            Value::Bool(if *op == BinOp::And { left && right } else { left || right })
+        }
+        Expr::Binary(op, left, right) => binary(*op, &eval(left, row), &eval(right, row)),
+        Expr::Like { expr, pattern, negated } => match text(&eval(expr, row)) {
+            Some(s) => Value::Bool(pattern.matches(&s) != *negated),
+            None => Value::Null,
+        },
+        Expr::In { expr, list, negated } => {
+            let value = eval(expr, row);
+            if value.is_null() {
+                return Value::Null;
+            }
+            let found = list.iter().any(|item| compare(&value, &eval(item, row)) == Some(Ordering::Equal));
+            Value::Bool(found != *negated)
+        }
+        Expr::Bin(period) => match row.get("@timestamp").and_then(Value::as_i64) {
+            Some(ts) => Value::from(ts - ts.rem_euclid(*period)),
+            None => Value::Null,
+        },
+        Expr::Call(name, args) => call(name, args.iter().map(|a| eval(a, row)).collect()),
+    }
+}
+
+fn binary(op: BinOp, left: &Value, right: &Value) -> Value {
+    let ordering = |accept: fn(Ordering) -> bool| {
+        compare(left, right).map(|o| Value::Bool(accept(o))).unwrap_or(Value::Null)
+    };
+    match op {
+        BinOp::Eq => ordering(|o| o == Ordering::Equal),
+        BinOp::Ne => ordering(|o| o != Ordering::Equal),
+        BinOp::Lt => ordering(|o| o == Ordering::Less),
+        BinOp::Le => ordering(|o| o != Ordering::Greater),
+        BinOp::Gt => ordering(|o| o == Ordering::Greater),
+        BinOp::Ge => ordering(|o| o != Ordering::Less),
+        BinOp::And | BinOp::Or => Value::Null,
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod => {
+            let (Some(a), Some(b)) = (num(left), num(right)) else {
+                return Value::Null;
+            };
+            float(match op {
+                BinOp::Add => a + b,
+                BinOp::Sub => a - b,
+                BinOp::Mul => a * b,
+                BinOp::Div if b == 0.0 => return Value::Null,
+                BinOp::Div => a / b,
+                BinOp::Mod if b == 0.0 => return Value::Null,
+                _ => a % b,
+            })
+        }
+    }
+}
+
+fn call(name: &str, args: Vec<Value>) -> Value {
+    let first = args.first().cloned().unwrap_or(Value::Null);
+    let first_text = text(&first);
+    match name {
+        "ispresent" => Value::Bool(!first.is_null()),
+        "isempty" => Value::Bool(first_text.is_none_or(|s| s.is_empty())),
+        "isblank" => Value::Bool(first_text.is_none_or(|s| s.trim().is_empty())),
+        "strlen" => first_text.map(|s| Value::from(s.chars().count())).unwrap_or(Value::Null),
+        "tolower" => first_text.map(|s| Value::from(s.to_lowercase())).unwrap_or(Value::Null),
+        "toupper" => first_text.map(|s| Value::from(s.to_uppercase())).unwrap_or(Value::Null),
+        "trim" => first_text.map(|s| Value::from(s.trim())).unwrap_or(Value::Null),
+        "concat" => Value::from(args.iter().filter_map(text).collect::<String>()),
+        "coalesce" => args.into_iter().find(|v| !v.is_null()).unwrap_or(Value::Null),
+        "abs" => num(&first).map(|n| float(n.abs())).unwrap_or(Value::Null),
+        "ceil" => num(&first).map(|n| float(n.ceil())).unwrap_or(Value::Null),
+        "floor" => num(&first).map(|n| float(n.floor())).unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+#[derive(Default)]
+struct Accumulator {
+    count: u64,
+    sum: f64,
+    distinct: HashSet<String>,
+    best: Option<Value>,
+}
+
+impl Accumulator {
+    fn add(&mut self, agg: Agg, arg: Option<&Expr>, row: &Row) {
+        let value = match arg {
+            Some(arg) => eval(arg, row),
+            None => {
+                self.count += 1;
+                return;
+            }
+        };
+        if value.is_null() {
+            return;
+        }
+        match agg {
+            Agg::Count => {}
+            Agg::CountDistinct => {
+                self.distinct.insert(text(&value).unwrap_or_default());
+            }
+            Agg::Sum | Agg::Avg => match num(&value) {
+                Some(n) => self.sum += n,
+                None => return,
+            },
+            Agg::Min | Agg::Max => {
+                let wanted = if agg == Agg::Min { Ordering::Less } else { Ordering::Greater };
+                if self.best.as_ref().is_none_or(|best| compare(&value, best) == Some(wanted)) {
+                    self.best = Some(value);
+                }
+            }
+        }
+        self.count += 1;
+    }
+
+    fn finish(self, agg: Agg) -> Value {
+        match agg {
+            Agg::Count => Value::from(self.count),
+            Agg::CountDistinct => Value::from(self.distinct.len()),
+            _ if self.count == 0 => Value::Null,
+            Agg::Sum => float(self.sum),
+            Agg::Avg => float(self.sum / self.count as f64),
+            Agg::Min | Agg::Max => self.best.unwrap_or(Value::Null),
+        }
+    }
+}
+
+fn stats(rows: Vec<Row>, aggregates: &[Named], by: &[Named]) -> Vec<Row> {
+    let mut groups: Vec<(Vec<Value>, Vec<Accumulator>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for row in &rows {
+        let keys: Vec<Value> = by.iter().map(|b| eval(&b.expr, row)).collect();
+        let slot = *index.entry(Value::Array(keys.clone()).to_string()).or_insert_with(|| {
+            groups.push((keys, aggregates.iter().map(|_| Accumulator::default()).collect()));
+            groups.len() - 1
+        });
+        for (item, acc) in aggregates.iter().zip(groups[slot].1.iter_mut()) {
+            if let Expr::Aggregate { agg, arg, .. } = &item.expr {
+                acc.add(*agg, arg.as_deref(), row);
+            }
+        }
+    }
+    // An ungrouped stats over no rows still reports a single row of zeros.
+    if groups.is_empty() && by.is_empty() {
+        groups.push((Vec::new(), aggregates.iter().map(|_| Accumulator::default()).collect()));
+    }
+    groups
+        .into_iter()
+        .map(|(keys, accumulators)| {
+            let mut row: Row = by.iter().map(|b| b.name.clone()).zip(keys).collect();
+            for (item, acc) in aggregates.iter().zip(accumulators) {
+                if let Expr::Aggregate { agg, .. } = &item.expr {
+                    row.insert(item.name.clone(), acc.finish(*agg));
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+/// Renders a value the way Insights does: whole numbers without a
+/// fraction, timestamps as `YYYY-MM-DD HH:MM:SS.sss` in UTC.
+fn render(value: &Value, is_time: bool) -> Option<String> {
+    if is_time {
+        if let Some(ms) = num(value) {
+            return chrono::DateTime::from_timestamp_millis(ms as i64)
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string());
+        }
+    }
+    match value {
+        Value::Number(n) if n.as_i64().is_none() => {
+            let f = n.as_f64()?;
+            Some(if f.fract() == 0.0 && f.abs() < 1e15 { format!("{}", f as i64) } else { f.to_string() })
+        }
+        other => text(other),
+    }
+}
+
+impl Query {
+    /// Runs the query over `events`, returning at most `limit` rows.
+    pub fn run(&self, events: &[Event], limit: usize) -> QueryOutput {
+        let records_scanned = events.len() as u64;
+        let bytes_scanned = events.iter().map(|e| e.message.len() as u64).sum();
+        let mut rows: Vec<Row> = events.iter().map(event_row).collect();
+        let mut time_fields: HashSet<String> = ["@timestamp", "@ingestionTime"].iter().map(|s| s.to_string()).collect();
+        let mut display: Vec<String> = Vec::new();
+        let mut records_matched = None;
+        for command in &self.commands {
+            match command {
+                Command::Fields(items) => {
+                    for row in rows.iter_mut() {
+                        for item in items {
+                            let value = eval(&item.expr, row);
+                            row.insert(item.name.clone(), value);
+                        }
+                    }
+                    for item in items {
+                        if item.expr.is_time(&time_fields) {
+                            time_fields.insert(item.name.clone());
+                        }
+                        if !display.contains(&item.name) {
+                            display.push(item.name.clone());
+                        }
+                    }
+                }
+                Command::Display(names) => display = names.clone(),
+                Command::Filter(expr) => rows.retain(|row| truth(&eval(expr, row)) == Some(true)),
+                Command::Stats { aggregates, by } => {
+                    records_matched.get_or_insert(rows.len() as u64);
+                    let produced: Vec<String> = by.iter().chain(aggregates).map(|i| i.name.clone()).collect();
+                    let times: Vec<String> = by
+                        .iter()
+                        .chain(aggregates)
+                        .filter(|i| i.expr.is_time(&time_fields))
+                        .map(|i| i.name.clone())
+                        .collect();
+                    rows = stats(std::mem::take(&mut rows), aggregates, by);
+                    time_fields = times.into_iter().collect();
+                    display = produced;
+                }
+                Command::Sort(keys) => rows.sort_by(|a, b| {
+                    for (expr, desc) in keys {
+                        let (left, right) = (eval(expr, a), eval(expr, b));
+                        // Rows missing the field sort last in either direction.
+                        let ordering = match (left.is_null(), right.is_null()) {
+                            (true, true) => Ordering::Equal,
+                            (true, false) => return Ordering::Greater,
+                            (false, true) => return Ordering::Less,
+                            _ => compare(&left, &right).unwrap_or(Ordering::Equal),
+                        };
+                        let ordering = if *desc { ordering.reverse() } else { ordering };
+                        if ordering != Ordering::Equal {
+                            return ordering;
+                        }
+                    }
+                    Ordering::Equal
+                }),
+                Command::Limit(n) => rows.truncate(*n),
+            }
+        }
+        let records_matched = records_matched.unwrap_or(rows.len() as u64);
+        if display.is_empty() {
+            display = vec!["@timestamp".to_string(), "@message".to_string()];
+        }
+        rows.truncate(limit);
+        let rows = rows
+            .iter()
+            .map(|row| {
+                display
+                    .iter()
+                    .filter_map(|name| {
+                        let value = render(row.get(name)?, time_fields.contains(name))?;
+                        Some((name.clone(), value))
+                    })
+                    .collect()
+            })
+            .collect();
+        QueryOutput { rows, records_matched, records_scanned, bytes_scanned }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(messages: &[&str]) -> Vec<(i64, String)> {
+        messages.iter().enumerate().map(|(i, m)| (1_700_000_000_000 + i as i64 * 60_000, m.to_string())).collect()
+    }
+
+    fn run(query: &str, events: &[(i64, String)]) -> Vec<Vec<(String, String)>> {
+        let events: Vec<Event> = events
+            .iter()
+            .map(|(ts, m)| Event {
+                log: "123456789012:app",
+                log_stream: "s1",
+                timestamp: *ts,
+                ingestion_time: *ts,
+                message: m,
+            })
+            .collect();
+        parse(query).unwrap().run(&events, DEFAULT_LIMIT).rows
+    }
+
+    fn pairs(row: &[(&str, &str)]) -> Vec<(String, String)> {
+        row.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_fields_filter_sort_limit() {
+        let data = events(&[
+            r#"{"level":"info","latency":12,"req":{"path":"/a"}}"#,
+            r#"{"level":"error","latency":250,"req":{"path":"/b"}}"#,
+            r#"{"level":"warn","latency":90,"req":{"path":"/c"}}"#,
+            "plain text error line",
+        ]);
+        let rows = run("fields req.path, latency | filter latency > 50 | sort latency desc | limit 1", &data);
+        assert_eq!(rows, vec![pairs(&[("req.path", "/b"), ("latency", "250")])]);
+
+        let rows = run("filter @message like /(?i)ERROR/ and not ispresent(level) | fields @message", &data);
+        assert_eq!(rows, vec![pairs(&[("@message", "plain text error line")])]);
+
+        let rows = run("fields @timestamp | filter level in [\"warn\"]", &data);
+        assert_eq!(rows, vec![pairs(&[("@timestamp", "2023-11-14 22:15:20.000")])]);
+    }
+
+    #[test]
+    fn test_stats_aggregates_and_bin() {
+        let data = events(&[
+            r#"{"level":"info","latency":10}"#,
+            r#"{"level":"info","latency":20}"#,
+            r#"{"level":"error","latency":35}"#,
+        ]);
+        let rows = run("stats count(*) as n, avg(latency), max(latency) by level | sort n desc", &data);
+        assert_eq!(
+            rows,
+            vec![
+                pairs(&[("level", "info"), ("n", "2"), ("avg(latency)", "15"), ("max(latency)", "20")]),
+                pairs(&[("level", "error"), ("n", "1"), ("avg(latency)", "35"), ("max(latency)", "35")]),
+            ]
+        );
+
+        let rows = run("stats count() by bin(1h)", &data);
+        assert_eq!(rows, vec![pairs(&[("bin(1h)", "2023-11-14 22:00:00.000"), ("count()", "3")])]);
+    }
+
+    #[test]
+    fn test_malformed_queries() {
+        for query in ["", "fields", "select *", "stats latency by level", "filter count() > 1", "limit 0", "bin(5x)"] {
+            assert!(
+                matches!(parse(query), Err(CwlError::MalformedQueryException(_))),
+                "{query} should be rejected"
+            );
+        }
+    }
+}
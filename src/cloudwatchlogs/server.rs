@@ -77,6 +77,9 @@ async fn handle_request(
         "ListTagsForResource" => {
             dispatch!(state, body, ListTagsForResourceRequest, list_tags_for_resource)
         }
+        "StartQuery" => dispatch!(state, body, StartQueryRequest, start_query),
+        "GetQueryResults" => dispatch!(state, body, GetQueryResultsRequest, get_query_results),
+        "StopQuery" => dispatch!(state, body, StopQueryRequest, stop_query),
         _ => Err(CwlError::InvalidAction(format!("Unknown action: {action}"))),
     }
 }
@@ -84,6 +87,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<CwlState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("cloudwatchlogs"))
         .with_state(state)
 }
 
@@ -5,8 +5,17 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use super::error::CwlError;
+use super::insights::{self, Event};
 use super::types::*;
 
+/// Values accepted by `PutRetentionPolicy`.
+const RETENTION_DAYS: &[i64] = &[
+    1, 3, 5, 7, 14, 30, 60, 90, 120, 150, 180, 365, 400, 545, 731, 1096, 1827, 2192, 2557, 2922,
+    3288, 3653,
+];
+
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
 struct StoredLogEvent {
     timestamp: i64,
     message: String,
@@ -31,8 +40,17 @@ struct LogGroupData {
     streams: HashMap<String, LogStreamData>,
 }
 
+/// A Logs Insights query. Queries run to completion in `StartQuery`, so
+/// results are ready by the first `GetQueryResults`.
+struct QueryData {
+    results: Vec<Vec<ResultField>>,
+    statistics: QueryStatistics,
+    status: String,
+}
+
 struct CwlStateInner {
     log_groups: HashMap<String, LogGroupData>,
+    queries: HashMap<String, QueryData>,
     account_id: String,
     region: String,
 }
@@ -46,12 +64,52 @@ impl CwlState {
         CwlState {
             inner: Arc::new(Mutex::new(CwlStateInner {
                 log_groups: HashMap::new(),
+                queries: HashMap::new(),
                 account_id,
                 region,
             })),
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Periodically purges events that have aged out of their group's
+    /// retention period.
+    pub fn start_retention_enforcement(self: &Arc<Self>, interval: std::time::Duration) {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                state.enforce_retention().await;
+            }
+        });
+    }
+
+    /// Drops events older than each group's retention from all of its
+    /// streams. Returns the number of events removed.
+    pub async fn enforce_retention(&self) -> usize {
+        let mut state = self.inner.lock().await;
+        let now = Self::now_ms();
+        let mut removed = 0;
+        for group in state.log_groups.values_mut() {
+            let Some(days) = group.retention_in_days else {
+                continue;
+            };
+            let cutoff = now - days * MS_PER_DAY;
+            for stream in group.streams.values_mut() {
+                let before = stream.events.len();
+                stream.events.retain(|e| e.timestamp >= cutoff);
+                removed += before - stream.events.len();
+            }
+        }
+        removed
+    }
+
     fn now_ms() -> i64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -94,6 +152,17 @@ impl CwlState {
     }
 
     pub async fn describe_log_groups(&self, req: DescribeLogGroupsRequest) -> Result<DescribeLogGroupsResponse, CwlError> {
+        if req.log_group_name_prefix.is_some() && req.log_group_name_pattern.is_some() {
+            return Err(CwlError::InvalidParameterException(
+                "LogGroupNamePrefix and LogGroupNamePattern are mutually exclusive".to_string(),
+            ));
+        }
+        let limit = req.limit.unwrap_or(50);
+        if !(1..=50).contains(&limit) {
+            return Err(CwlError::InvalidParameterException(format!(
+                "limit must be between 1 and 50, got {limit}"
+            )));
+        }
         let state = self.inner.lock().await;
         let mut groups: Vec<LogGroup> = state.log_groups.values()
             .filter(|g| {
@@ -103,6 +172,10 @@ impl CwlState {
                 && req.log_group_name_pattern.as_ref()
                     .map(|p| g.log_group_name.contains(p.as_str()))
                     .unwrap_or(true)
+                // The token is the name of the last group already returned.
+                && req.next_token.as_ref()
+                    .map(|t| g.log_group_name.as_str() > t.as_str())
+                    .unwrap_or(true)
             })
             .map(|g| LogGroup {
                 log_group_name: g.log_group_name.clone(),
@@ -116,12 +189,16 @@ impl CwlState {
             })
             .collect();
         groups.sort_by(|a, b| a.log_group_name.cmp(&b.log_group_name));
-        let limit = req.limit.unwrap_or(50);
         let has_more = groups.len() > limit;
         groups.truncate(limit);
+        let next_token = if has_more {
+            groups.last().map(|g| g.log_group_name.clone())
+        } else {
+            None
+        };
         Ok(DescribeLogGroupsResponse {
             log_groups: groups,
-            next_token: if has_more { Some("next".to_string()) } else { None },
+            next_token,
         })
     }
 
@@ -297,6 +374,11 @@ impl CwlState {
     }
 
     pub async fn put_retention_policy(&self, req: PutRetentionPolicyRequest) -> Result<(), CwlError> {
+        if !RETENTION_DAYS.contains(&req.retention_in_days) {
+            return Err(CwlError::InvalidParameterException(format!(
+                "Invalid retentionInDays value: {}", req.retention_in_days
+            )));
+        }
         let mut state = self.inner.lock().await;
         let group = state.log_groups.get_mut(&req.log_group_name)
             .ok_or_else(|| CwlError::ResourceNotFoundException(format!(
@@ -377,6 +459,93 @@ impl CwlState {
         }
         Err(CwlError::ResourceNotFoundException(format!("Resource not found: {}", req.resource_arn)))
     }
+
+    pub async fn start_query(&self, req: StartQueryRequest) -> Result<StartQueryResponse, CwlError> {
+        let mut names: Vec<String> = req.log_group_name.into_iter().collect();
+        names.extend(req.log_group_names.unwrap_or_default());
+        for identifier in req.log_group_identifiers.unwrap_or_default() {
+            // Identifiers may be names or ARNs, with or without the `:*` suffix.
+            let name = match identifier.split_once(":log-group:") {
+                Some((_, name)) => name.strip_suffix(":*").unwrap_or(name).to_string(),
+                None => identifier,
+            };
+            names.push(name);
+        }
+        if names.is_empty() {
+            return Err(CwlError::InvalidParameterException(
+                "One of logGroupName, logGroupNames or logGroupIdentifiers is required".into(),
+            ));
+        }
+        if req.end_time < req.start_time {
+            return Err(CwlError::InvalidParameterException("endTime must not be earlier than startTime".into()));
+        }
+        let limit = req.limit.unwrap_or(insights::DEFAULT_LIMIT);
+        if !(1..=10000).contains(&limit) {
+            return Err(CwlError::InvalidParameterException("limit must be between 1 and 10000".into()));
+        }
+        let query = insights::parse(&req.query_string)?;
+
+        let mut state = self.inner.lock().await;
+        let (start_ms, end_ms) = (req.start_time * 1000, req.end_time * 1000 + 999);
+        let logs: Vec<String> = names.iter().map(|name| format!("{}:{name}", state.account_id)).collect();
+        let mut events = Vec::new();
+        for (name, log) in names.iter().zip(&logs) {
+            let group = state.log_groups.get(name).ok_or_else(|| {
+                CwlError::ResourceNotFoundException(format!("Log group '{name}' does not exist"))
+            })?;
+            for stream in group.streams.values() {
+                events.extend(stream.events.iter().filter(|e| e.timestamp >= start_ms && e.timestamp <= end_ms).map(|e| {
+                    Event {
+                        log,
+                        log_stream: &stream.log_stream_name,
+                        timestamp: e.timestamp,
+                        ingestion_time: e.ingestion_time,
+                        message: &e.message,
+                    }
+                }));
+            }
+        }
+        events.sort_by_key(|e| e.timestamp);
+        let output = query.run(&events, limit);
+
+        let query_id = Uuid::new_v4().to_string();
+        state.queries.insert(query_id.clone(), QueryData {
+            results: output
+                .rows
+                .into_iter()
+                .map(|row| row.into_iter().map(|(field, value)| ResultField { field, value }).collect())
+                .collect(),
+            statistics: QueryStatistics {
+                records_matched: output.records_matched as f64,
+                records_scanned: output.records_scanned as f64,
+                bytes_scanned: output.bytes_scanned as f64,
+            },
+            status: "Complete".to_string(),
+        });
+        Ok(StartQueryResponse { query_id })
+    }
+
+    pub async fn get_query_results(&self, req: GetQueryResultsRequest) -> Result<GetQueryResultsResponse, CwlError> {
+        let state = self.inner.lock().await;
+        let query = state.queries.get(&req.query_id).ok_or_else(|| {
+            CwlError::ResourceNotFoundException(format!("Query does not exist: {}", req.query_id))
+        })?;
+        Ok(GetQueryResultsResponse {
+            results: query.results.clone(),
+            statistics: query.statistics.clone(),
+            status: query.status.clone(),
+        })
+    }
+
+    /// Queries finish inside `StartQuery`, so there is never a running one to
+    /// cancel and `success` is always false.
+    pub async fn stop_query(&self, req: StopQueryRequest) -> Result<StopQueryResponse, CwlError> {
+        let state = self.inner.lock().await;
+        if !state.queries.contains_key(&req.query_id) {
+            return Err(CwlError::ResourceNotFoundException(format!("Query does not exist: {}", req.query_id)));
+        }
+        Ok(StopQueryResponse { success: false })
+    }
 }
 
 
@@ -1136,6 +1305,101 @@ mod tests {
         assert!(result.next_token.is_some());
     }
 
+    #[tokio::test]
+    async fn test_describe_log_groups_paginates_with_next_token() {
+        let state = make_state();
+        for i in 0..5 {
+            state.create_log_group(CreateLogGroupRequest {
+                log_group_name: format!("/app/group-{}", i),
+                ..Default::default()
+            }).await.unwrap();
+        }
+        state.create_log_group(CreateLogGroupRequest {
+            log_group_name: "/other".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+
+        let mut names = Vec::new();
+        let mut next_token = None;
+        loop {
+            let page = state.describe_log_groups(DescribeLogGroupsRequest {
+                log_group_name_prefix: Some("/app/".to_string()),
+                limit: Some(2),
+                next_token,
+                ..Default::default()
+            }).await.unwrap();
+            names.extend(page.log_groups.into_iter().map(|g| g.log_group_name));
+            next_token = page.next_token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+        let expected: Vec<String> = (0..5).map(|i| format!("/app/group-{}", i)).collect();
+        assert_eq!(names, expected);
+    }
+
+    #[tokio::test]
+    async fn test_describe_log_groups_rejects_prefix_with_pattern() {
+        let state = make_state();
+        let result = state.describe_log_groups(DescribeLogGroupsRequest {
+            log_group_name_prefix: Some("/app".to_string()),
+            log_group_name_pattern: Some("app".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(CwlError::InvalidParameterException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_put_retention_policy_rejects_invalid_days() {
+        let state = make_state();
+        setup_group_and_stream(&state, "g1", "s1").await;
+        let result = state.put_retention_policy(PutRetentionPolicyRequest {
+            log_group_name: "g1".to_string(),
+            retention_in_days: 2,
+        }).await;
+        assert!(matches!(result, Err(CwlError::InvalidParameterException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_retention_purges_expired_events() {
+        let state = make_state();
+        setup_group_and_stream(&state, "g1", "s1").await;
+        setup_group_and_stream(&state, "unbounded", "s1").await;
+        let now = CwlState::now_ms();
+        for group in ["g1", "unbounded"] {
+            state.put_log_events(PutLogEventsRequest {
+                log_group_name: group.to_string(),
+                log_stream_name: "s1".to_string(),
+                log_events: vec![
+                    InputLogEvent { timestamp: now - 3 * MS_PER_DAY, message: "old".to_string() },
+                    InputLogEvent { timestamp: now - 2 * MS_PER_DAY, message: "older than a day".to_string() },
+                    InputLogEvent { timestamp: now - 60_000, message: "recent".to_string() },
+                ],
+            }).await.unwrap();
+        }
+        state.put_retention_policy(PutRetentionPolicyRequest {
+            log_group_name: "g1".to_string(),
+            retention_in_days: 1,
+        }).await.unwrap();
+
+        assert_eq!(state.enforce_retention().await, 2);
+
+        let events = state.get_log_events(GetLogEventsRequest {
+            log_group_name: "g1".to_string(),
+            log_stream_name: "s1".to_string(),
+            ..Default::default()
+        }).await.unwrap().events;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].message, "recent");
+
+        let untouched = state.get_log_events(GetLogEventsRequest {
+            log_group_name: "unbounded".to_string(),
+            log_stream_name: "s1".to_string(),
+            ..Default::default()
+        }).await.unwrap().events;
+        assert_eq!(untouched.len(), 3);
+    }
+
     #[tokio::test]
     async fn test_describe_log_streams_with_limit() {
         let state = make_state();
@@ -1200,4 +1464,79 @@ mod tests {
         }).await.unwrap();
         assert_eq!(result.events.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_insights_stats_count_by_level() {
+        let state = make_state();
+        setup_group_and_stream(&state, "app", "s1").await;
+        state.create_log_stream(CreateLogStreamRequest {
+            log_group_name: "app".to_string(),
+            log_stream_name: "s2".to_string(),
+        }).await.unwrap();
+        let events = |levels: &[&str], base: i64| -> Vec<InputLogEvent> {
+            levels
+                .iter()
+                .enumerate()
+                .map(|(i, level)| InputLogEvent {
+                    timestamp: base + i as i64,
+                    message: format!(r#"{{"level":"{level}","msg":"event {i}"}}"#),
+                })
+                .collect()
+        };
+        for (stream, levels) in [("s1", vec!["info", "error", "info"]), ("s2", vec!["warn", "info", "error"])] {
+            state.put_log_events(PutLogEventsRequest {
+                log_group_name: "app".to_string(),
+                log_stream_name: stream.to_string(),
+                log_events: events(&levels, 1_700_000_000_000),
+            }).await.unwrap();
+        }
+        // Outside the queried time range.
+        state.put_log_events(PutLogEventsRequest {
+            log_group_name: "app".to_string(),
+            log_stream_name: "s1".to_string(),
+            log_events: events(&["debug"], 1_600_000_000_000),
+        }).await.unwrap();
+
+        let started = state.start_query(StartQueryRequest {
+            log_group_names: Some(vec!["app".to_string()]),
+            start_time: 1_699_999_999,
+            end_time: 1_700_000_001,
+            query_string: "stats count() by level | sort level".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let results = state.get_query_results(GetQueryResultsRequest { query_id: started.query_id.clone() }).await.unwrap();
+        assert_eq!(results.status, "Complete");
+        let counts: Vec<(String, String)> = results
+            .results
+            .iter()
+            .map(|row| {
+                assert_eq!(row[0].field, "level");
+                assert_eq!(row[1].field, "count()");
+                (row[0].value.clone(), row[1].value.clone())
+            })
+            .collect();
+        assert_eq!(counts, vec![
+            ("error".to_string(), "2".to_string()),
+            ("info".to_string(), "3".to_string()),
+            ("warn".to_string(), "1".to_string()),
+        ]);
+        assert_eq!(results.statistics.records_scanned, 6.0);
+        assert_eq!(results.statistics.records_matched, 6.0);
+
+        let stopped = state.stop_query(StopQueryRequest { query_id: started.query_id }).await.unwrap();
+        assert!(!stopped.success);
+
+        let err = state.start_query(StartQueryRequest {
+            log_group_name: Some("app".to_string()),
+            query_string: "stats level".to_string(),
+            ..Default::default()
+        }).await.unwrap_err();
+        assert!(matches!(err, CwlError::MalformedQueryException(_)));
+        let err = state.start_query(StartQueryRequest {
+            log_group_name: Some("missing".to_string()),
+            query_string: "fields @message".to_string(),
+            ..Default::default()
+        }).await.unwrap_err();
+        assert!(matches!(err, CwlError::ResourceNotFoundException(_)));
+    }
 }
@@ -23,6 +23,8 @@ pub struct DescribeLogGroupsRequest {
     pub log_group_name_pattern: Option<String>,
     #[serde(rename = "limit")]
     pub limit: Option<usize>,
+    #[serde(rename = "nextToken")]
+    pub next_token: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -282,3 +284,73 @@ pub struct ListTagsForResourceResponse {
     #[serde(rename = "tags")]
     pub tags: HashMap<String, String>,
 }
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StartQueryRequest {
+    #[serde(rename = "logGroupName")]
+    pub log_group_name: Option<String>,
+    #[serde(rename = "logGroupNames")]
+    pub log_group_names: Option<Vec<String>>,
+    #[serde(rename = "logGroupIdentifiers")]
+    pub log_group_identifiers: Option<Vec<String>>,
+    #[serde(rename = "startTime")]
+    pub start_time: i64,
+    #[serde(rename = "endTime")]
+    pub end_time: i64,
+    #[serde(rename = "queryString")]
+    pub query_string: String,
+    #[serde(rename = "limit")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartQueryResponse {
+    #[serde(rename = "queryId")]
+    pub query_id: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetQueryResultsRequest {
+    #[serde(rename = "queryId")]
+    pub query_id: String,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ResultField {
+    #[serde(rename = "field")]
+    pub field: String,
+    #[serde(rename = "value")]
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct QueryStatistics {
+    #[serde(rename = "recordsMatched")]
+    pub records_matched: f64,
+    #[serde(rename = "recordsScanned")]
+    pub records_scanned: f64,
+    #[serde(rename = "bytesScanned")]
+    pub bytes_scanned: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetQueryResultsResponse {
+    #[serde(rename = "results")]
+    pub results: Vec<Vec<ResultField>>,
+    #[serde(rename = "statistics")]
+    pub statistics: QueryStatistics,
+    #[serde(rename = "status")]
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StopQueryRequest {
+    #[serde(rename = "queryId")]
+    pub query_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StopQueryResponse {
+    #[serde(rename = "success")]
+    pub success: bool,
+}
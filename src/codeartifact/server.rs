@@ -78,6 +78,7 @@ pub fn create_router(state: Arc<CodeartifactState>) -> Router {
         .route("/domains/{name}", get(get_domain_handler).delete(delete_domain_handler))
         .route("/repositories", post(create_repository_handler).get(list_repositorys_handler))
         .route("/repositories/{name}", get(get_repository_handler).delete(delete_repository_handler))
+        .merge(crate::healthcheck::router("codeartifact"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<CodecatalystState>) -> Router {
     Router::new()
         .route("/projects", post(create_project_handler).get(list_projects_handler))
         .route("/projects/{name}", get(get_project_handler).delete(delete_project_handler))
+        .merge(crate::healthcheck::router("codecatalyst"))
         .with_state(state)
 }
 
@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum CognitoError {
@@ -51,13 +52,23 @@ impl CognitoError {
     }
 }
 
+impl AwsError for CognitoError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        CognitoError::message(self)
+    }
+}
+
 impl IntoResponse for CognitoError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": format!("#{}", self.error_code()),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::NamespacedJson("#"))
     }
 }
 
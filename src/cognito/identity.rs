@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use super::types::CognitoIdentityProviderType;
+
+#[derive(Debug, Clone)]
+pub struct IdentityPool {
+    pub id: String,
+    pub name: String,
+    pub allow_unauthenticated_identities: bool,
+    pub allow_classic_flow: Option<bool>,
+    pub supported_login_providers: HashMap<String, String>,
+    pub developer_provider_name: Option<String>,
+    pub cognito_identity_providers: Vec<CognitoIdentityProviderType>,
+    pub tags: HashMap<String, String>,
+    pub identities: HashMap<String, Identity>,
+    /// Identity id of each `provider/subject` login seen by `GetId`.
+    pub logins: HashMap<String, String>,
+}
+
+impl IdentityPool {
+    pub fn new(id: String, name: String, allow_unauthenticated_identities: bool) -> Self {
+        IdentityPool {
+            id,
+            name,
+            allow_unauthenticated_identities,
+            allow_classic_flow: None,
+            supported_login_providers: HashMap::new(),
+            developer_provider_name: None,
+            cognito_identity_providers: Vec::new(),
+            tags: HashMap::new(),
+            identities: HashMap::new(),
+            logins: HashMap::new(),
+        }
+    }
+
+    /// Whether logins from `provider` are configured on this pool.
+    pub fn accepts_provider(&self, provider: &str) -> bool {
+        self.supported_login_providers.contains_key(provider)
+            || self
+                .cognito_identity_providers
+                .iter()
+                .any(|p| p.provider_name == provider)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Identity {
+    /// Keys of the `provider/subject` logins linked to this identity; empty
+    /// for an unauthenticated identity.
+    pub logins: Vec<String>,
+}
+
+impl Identity {
+    pub fn new() -> Self {
+        Identity {
+            logins: Vec::new(),
+        }
+    }
+}
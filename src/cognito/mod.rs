@@ -1,4 +1,5 @@
 pub mod error;
+pub mod identity;
 pub mod pool;
 pub mod server;
 pub mod state;
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
-use super::types::AttributeType;
+use super::types::{
+    AccountRecoverySettingType, AdminCreateUserConfigType, AttributeType, EmailConfigurationType, LambdaConfigType,
+    PasswordPolicyType, SchemaAttributeType, SmsConfigurationType, TokenValiditySettings, TokenValidityUnitsType,
+    UserPoolPolicyType, UsernameConfigurationType, VerificationMessageTemplateType,
+};
 
 fn now() -> f64 {
     std::time::SystemTime::now()
@@ -19,6 +23,21 @@ pub struct UserPool {
     pub last_modified_date: f64,
     pub auto_verified_attributes: Vec<String>,
     pub username_attributes: Vec<String>,
+    pub alias_attributes: Vec<String>,
+    pub mfa_configuration: String,
+    pub policies: UserPoolPolicyType,
+    pub schema: Vec<SchemaAttributeType>,
+    pub email_configuration: Option<EmailConfigurationType>,
+    pub sms_configuration: Option<SmsConfigurationType>,
+    pub lambda_config: Option<LambdaConfigType>,
+    pub admin_create_user_config: Option<AdminCreateUserConfigType>,
+    pub verification_message_template: Option<VerificationMessageTemplateType>,
+    pub account_recovery_setting: Option<AccountRecoverySettingType>,
+    pub username_configuration: Option<UsernameConfigurationType>,
+    pub email_verification_message: Option<String>,
+    pub email_verification_subject: Option<String>,
+    pub sms_verification_message: Option<String>,
+    pub sms_authentication_message: Option<String>,
     pub tags: HashMap<String, String>,
     pub estimated_number_of_users: i64,
     pub users: HashMap<String, User>,
@@ -41,6 +60,21 @@ impl UserPool {
             last_modified_date: ts,
             auto_verified_attributes: Vec::new(),
             username_attributes: Vec::new(),
+            alias_attributes: Vec::new(),
+            mfa_configuration: "OFF".to_string(),
+            policies: default_policies(),
+            schema: Vec::new(),
+            email_configuration: None,
+            sms_configuration: None,
+            lambda_config: None,
+            admin_create_user_config: None,
+            verification_message_template: None,
+            account_recovery_setting: None,
+            username_configuration: None,
+            email_verification_message: None,
+            email_verification_subject: None,
+            sms_verification_message: None,
+            sms_authentication_message: None,
             tags: HashMap::new(),
             estimated_number_of_users: 0,
             users: HashMap::new(),
@@ -50,6 +84,20 @@ impl UserPool {
     }
 }
 
+/// The password policy a pool gets when `CreateUserPool` sets none.
+fn default_policies() -> UserPoolPolicyType {
+    UserPoolPolicyType {
+        password_policy: Some(PasswordPolicyType {
+            minimum_length: Some(8),
+            require_uppercase: Some(true),
+            require_lowercase: Some(true),
+            require_numbers: Some(true),
+            require_symbols: Some(true),
+            temporary_password_validity_days: Some(7),
+        }),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct User {
     pub username: String,
@@ -99,6 +147,24 @@ pub struct UserPoolClient {
     pub supported_identity_providers: Vec<String>,
     pub prevent_user_existence_errors: String,
     pub enable_token_revocation: bool,
+    pub access_token_validity: i64,
+    pub id_token_validity: i64,
+    pub refresh_token_validity: i64,
+    /// Always has all three units set.
+    pub token_validity_units: TokenValidityUnitsType,
+    /// Minutes.
+    pub auth_session_validity: i64,
+}
+
+/// Seconds in a token validity unit.
+fn unit_seconds(unit: &str) -> Option<i64> {
+    match unit {
+        "seconds" => Some(1),
+        "minutes" => Some(60),
+        "hours" => Some(3600),
+        "days" => Some(86_400),
+        _ => None,
+    }
 }
 
 impl UserPoolClient {
@@ -119,7 +185,69 @@ impl UserPoolClient {
             supported_identity_providers: Vec::new(),
             prevent_user_existence_errors: "LEGACY".to_string(),
             enable_token_revocation: false,
+            access_token_validity: 60,
+            id_token_validity: 60,
+            refresh_token_validity: 30,
+            token_validity_units: TokenValidityUnitsType {
+                access_token: Some("minutes".to_string()),
+                id_token: Some("minutes".to_string()),
+                refresh_token: Some("days".to_string()),
+            },
+            auth_session_validity: 3,
+        }
+    }
+
+    /// Lifetime of the access tokens issued to this client, the
+    /// `ExpiresIn` of an authentication result.
+    pub fn access_token_seconds(&self) -> i64 {
+        self.access_token_validity * self.token_validity_units.access_token.as_deref().and_then(unit_seconds).unwrap_or(60)
+    }
+
+    /// Applies the settings given, keeping the rest, if the result is within
+    /// Cognito's limits: access and ID tokens from 5 minutes to a day,
+    /// refresh tokens from an hour to 10 years and sessions from 3 to 15
+    /// minutes. Otherwise nothing changes.
+    pub fn set_token_validity(&mut self, settings: TokenValiditySettings) -> Result<(), String> {
+        let units = settings.token_validity_units.unwrap_or_default();
+        let unit = |given: Option<String>, current: &Option<String>| -> Result<Option<String>, String> {
+            match given {
+                Some(u) if unit_seconds(&u).is_none() => Err(format!(
+                    "Invalid token validity unit '{u}'; must be one of seconds, minutes, hours, days"
+                )),
+                Some(u) => Ok(Some(u)),
+                None => Ok(current.clone()),
+            }
+        };
+        let units = TokenValidityUnitsType {
+            access_token: unit(units.access_token, &self.token_validity_units.access_token)?,
+            id_token: unit(units.id_token, &self.token_validity_units.id_token)?,
+            refresh_token: unit(units.refresh_token, &self.token_validity_units.refresh_token)?,
+        };
+        let seconds = |validity: i64, unit: &Option<String>| validity * unit.as_deref().and_then(unit_seconds).unwrap_or(0);
+        let access = settings.access_token_validity.unwrap_or(self.access_token_validity);
+        let id = settings.id_token_validity.unwrap_or(self.id_token_validity);
+        let refresh = settings.refresh_token_validity.unwrap_or(self.refresh_token_validity);
+        let session = settings.auth_session_validity.unwrap_or(self.auth_session_validity);
+        if !(300..=86_400).contains(&seconds(access, &units.access_token)) {
+            return Err("Invalid range for token validity: AccessTokenValidity must be between 5 minutes and 1 day".into());
+        }
+        if !(300..=86_400).contains(&seconds(id, &units.id_token)) {
+            return Err("Invalid range for token validity: IdTokenValidity must be between 5 minutes and 1 day".into());
+        }
+        if !(3600..=3650 * 86_400).contains(&seconds(refresh, &units.refresh_token)) {
+            return Err(
+                "Invalid range for token validity: RefreshTokenValidity must be between 1 hour and 3650 days".into(),
+            );
+        }
+        if !(3..=15).contains(&session) {
+            return Err("AuthSessionValidity must be between 3 and 15 minutes".into());
         }
+        self.access_token_validity = access;
+        self.id_token_validity = id;
+        self.refresh_token_validity = refresh;
+        self.token_validity_units = units;
+        self.auth_session_validity = session;
+        Ok(())
     }
 }
 
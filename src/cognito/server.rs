@@ -4,7 +4,7 @@ use axum::body::Bytes;
 use axum::extract::State;
 use axum::http::HeaderMap;
 use axum::response::IntoResponse;
-use axum::routing::post;
+use axum::routing::{get, post};
 use axum::{Json, Router};
 
 use super::error::CognitoError;
@@ -39,6 +39,10 @@ async fn handle_request(
         .and_then(|v| v.to_str().ok())
         .ok_or_else(|| CognitoError::InvalidAction("Missing X-Amz-Target header".into()))?;
 
+    if let Some(action) = target.strip_prefix("AWSCognitoIdentityService.") {
+        return handle_identity_request(&state, action, body).await;
+    }
+
     let action = target
         .strip_prefix("AWSCognitoIdentityProviderService.")
         .ok_or_else(|| CognitoError::InvalidAction(format!("Invalid target: {target}")))?;
@@ -192,9 +196,48 @@ async fn handle_request(
     }
 }
 
+/// Identity pool (`cognito-identity`) actions, served alongside user pools.
+async fn handle_identity_request(
+    state: &CognitoState,
+    action: &str,
+    body: Bytes,
+) -> Result<axum::response::Response, CognitoError> {
+    match action {
+        "CreateIdentityPool" => {
+            dispatch!(state, body, CreateIdentityPoolRequest, create_identity_pool)
+        }
+        "DescribeIdentityPool" => {
+            dispatch!(state, body, DescribeIdentityPoolRequest, describe_identity_pool)
+        }
+        "DeleteIdentityPool" => {
+            dispatch_empty!(state, body, DeleteIdentityPoolRequest, delete_identity_pool)
+        }
+        "GetId" => dispatch!(state, body, GetIdRequest, get_id),
+        "GetCredentialsForIdentity" => {
+            dispatch!(
+                state,
+                body,
+                GetCredentialsForIdentityRequest,
+                get_credentials_for_identity
+            )
+        }
+        "GetOpenIdToken" => dispatch!(state, body, GetOpenIdTokenRequest, get_open_id_token),
+        _ => Err(CognitoError::InvalidAction(format!(
+            "Unknown action: {action}"
+        ))),
+    }
+}
+
+// GET /_aws/cognito/messages — invitations captured instead of delivered
+async fn list_sent_messages(State(state): State<Arc<CognitoState>>) -> axum::response::Response {
+    Json(serde_json::json!({ "messages": state.sent_messages().await })).into_response()
+}
+
 pub fn create_router(state: Arc<CognitoState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .route("/_aws/cognito/messages", get(list_sent_messages))
+        .merge(crate::healthcheck::router("cognito"))
         .with_state(state)
 }
 
@@ -235,6 +278,23 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
     #[tokio::test]
+    async fn test_identity_service_target() {
+        let state = Arc::new(CognitoState::new("123456789012".to_string(), "us-east-1".to_string()));
+        let app = create_router(state);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/x-amz-json-1.1")
+            .header("x-amz-target", "AWSCognitoIdentityService.CreateIdentityPool")
+            .body(Body::from(r#"{"IdentityPoolName":"app","AllowUnauthenticatedIdentities":true}"#))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let pool: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(pool["IdentityPoolId"].as_str().unwrap().starts_with("us-east-1:"));
+    }
+    #[tokio::test]
     async fn test_listuserpools_ok() {
         let state = Arc::new(CognitoState::new("123456789012".to_string(), "us-east-1".to_string()));
         let app = create_router(state);
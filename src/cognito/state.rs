@@ -5,6 +5,7 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use super::error::CognitoError;
+use super::identity::{Identity, IdentityPool};
 use super::pool::{Group, User, UserPool, UserPoolClient};
 use super::types::*;
 
@@ -21,9 +22,11 @@ fn fake_token(kind: &str, username: &str, pool_id: &str) -> String {
 
 struct CognitoStateInner {
     pools: HashMap<String, UserPool>,
+    identity_pools: HashMap<String, IdentityPool>,
     account_id: String,
     region: String,
     pool_counter: u64,
+    sent_messages: Vec<SentMessage>,
 }
 
 pub struct CognitoState {
@@ -35,13 +38,22 @@ impl CognitoState {
         CognitoState {
             inner: Arc::new(Mutex::new(CognitoStateInner {
                 pools: HashMap::new(),
+                identity_pools: HashMap::new(),
                 account_id,
                 region,
                 pool_counter: 0,
+                sent_messages: Vec::new(),
             })),
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
     // --- User Pool ---
 
     pub async fn create_user_pool(
@@ -57,12 +69,37 @@ impl CognitoState {
             .take(9)
             .collect::<String>();
         let id = format!("{}_{}", state.region, suffix);
+        if !req.alias_attributes.is_empty() && !req.username_attributes.is_empty() {
+            return Err(CognitoError::InvalidParameterException(
+                "Only one of AliasAttributes and UsernameAttributes can be set in a user pool.".to_string(),
+            ));
+        }
+        validate_pool_config(req.mfa_configuration.as_deref(), req.policies.as_ref())?;
         let mut pool = UserPool::new(id.clone(), req.pool_name, &state.region, &state.account_id);
         pool.auto_verified_attributes = req.auto_verified_attributes;
         pool.username_attributes = req.username_attributes;
+        pool.alias_attributes = req.alias_attributes;
+        pool.schema = schema_attributes(req.schema)?;
         if let Some(tags) = req.user_pool_tags {
             pool.tags = tags;
         }
+        if let Some(mfa) = req.mfa_configuration {
+            pool.mfa_configuration = mfa;
+        }
+        if let Some(policies) = req.policies {
+            pool.policies = policies;
+        }
+        pool.email_configuration = req.email_configuration;
+        pool.sms_configuration = req.sms_configuration;
+        pool.lambda_config = req.lambda_config;
+        pool.admin_create_user_config = req.admin_create_user_config;
+        pool.verification_message_template = req.verification_message_template;
+        pool.account_recovery_setting = req.account_recovery_setting;
+        pool.username_configuration = req.username_configuration;
+        pool.email_verification_message = req.email_verification_message;
+        pool.email_verification_subject = req.email_verification_subject;
+        pool.sms_verification_message = req.sms_verification_message;
+        pool.sms_authentication_message = req.sms_authentication_message;
         let resp = CreateUserPoolResponse {
             user_pool: pool_to_type(&pool),
         };
@@ -130,6 +167,7 @@ impl CognitoState {
     }
 
     pub async fn update_user_pool(&self, req: UpdateUserPoolRequest) -> Result<(), CognitoError> {
+        validate_pool_config(req.mfa_configuration.as_deref(), req.policies.as_ref())?;
         let mut state = self.inner.lock().await;
         let pool = get_pool_mut(&mut state.pools, &req.user_pool_id)?;
         if let Some(tags) = req.user_pool_tags {
@@ -138,6 +176,42 @@ impl CognitoState {
         if let Some(attrs) = req.auto_verified_attributes {
             pool.auto_verified_attributes = attrs;
         }
+        if let Some(mfa) = req.mfa_configuration {
+            pool.mfa_configuration = mfa;
+        }
+        if let Some(policies) = req.policies {
+            pool.policies = policies;
+        }
+        if let Some(config) = req.email_configuration {
+            pool.email_configuration = Some(config);
+        }
+        if let Some(config) = req.sms_configuration {
+            pool.sms_configuration = Some(config);
+        }
+        if let Some(config) = req.lambda_config {
+            pool.lambda_config = Some(config);
+        }
+        if let Some(config) = req.admin_create_user_config {
+            pool.admin_create_user_config = Some(config);
+        }
+        if let Some(template) = req.verification_message_template {
+            pool.verification_message_template = Some(template);
+        }
+        if let Some(setting) = req.account_recovery_setting {
+            pool.account_recovery_setting = Some(setting);
+        }
+        if let Some(message) = req.email_verification_message {
+            pool.email_verification_message = Some(message);
+        }
+        if let Some(subject) = req.email_verification_subject {
+            pool.email_verification_subject = Some(subject);
+        }
+        if let Some(message) = req.sms_verification_message {
+            pool.sms_verification_message = Some(message);
+        }
+        if let Some(message) = req.sms_authentication_message {
+            pool.sms_authentication_message = Some(message);
+        }
         pool.last_modified_date = now();
         Ok(())
     }
@@ -148,46 +222,70 @@ impl CognitoState {
         &self,
         req: AdminCreateUserRequest,
     ) -> Result<AdminCreateUserResponse, CognitoError> {
-        let mut state = self.inner.lock().await;
-        let pool = get_pool_mut(&mut state.pools, &req.user_pool_id)?;
-
-        if pool.users.contains_key(&req.username) {
-            return Err(CognitoError::UsernameExistsException(format!(
-                "User already exists: {}",
-                req.username
+        let action = req.message_action.as_deref();
+        if action.is_some_and(|a| a != "RESEND" && a != "SUPPRESS") {
+            return Err(CognitoError::InvalidParameterException(format!(
+                "Invalid MessageAction: {}",
+                action.unwrap_or_default()
+            )));
+        }
+        if let Some(medium) = req.desired_delivery_mediums.iter().find(|m| *m != "EMAIL" && *m != "SMS") {
+            return Err(CognitoError::InvalidParameterException(format!(
+                "Invalid DesiredDeliveryMediums value: {medium}"
             )));
         }
+        let mut state = self.inner.lock().await;
+        let pool = get_pool_mut(&mut state.pools, &req.user_pool_id)?;
 
-        let attrs: Vec<AttributeType> = req
-            .user_attributes
-            .into_iter()
-            .map(|a| AttributeType {
-                name: a.name,
-                value: a.value,
-            })
-            .collect();
+        let user = if action == Some("RESEND") {
+            let user = pool.users.get_mut(&req.username).ok_or_else(|| {
+                CognitoError::UserNotFoundException(format!("User does not exist: {}", req.username))
+            })?;
+            if let Some(password) = req.temporary_password {
+                user.password = Some(password);
+                user.user_last_modified_date = now();
+            }
+            user.clone()
+        } else {
+            if pool.users.contains_key(&req.username) {
+                return Err(CognitoError::UsernameExistsException(format!(
+                    "User already exists: {}",
+                    req.username
+                )));
+            }
+            let mut attrs = req.user_attributes;
+            username_attribute(pool, &req.username, &mut attrs)?;
+            if let Some(missing) = pool
+                .schema
+                .iter()
+                .filter(|a| a.required == Some(true))
+                .find(|a| !attrs.iter().any(|u| u.name == a.name))
+            {
+                return Err(CognitoError::InvalidParameterException(format!(
+                    "Attributes did not conform to the schema: {}: The attribute is required",
+                    missing.name
+                )));
+            }
+            // Without a TemporaryPassword, Cognito generates one.
+            let password = req.temporary_password.unwrap_or_else(|| temporary_password(pool));
+            let user = User::new(req.username.clone(), attrs, Some(password));
+            pool.users.insert(req.username.clone(), user.clone());
+            pool.estimated_number_of_users = pool.users.len() as i64;
+            user
+        };
 
-        let user = User::new(req.username.clone(), attrs.clone(), req.temporary_password);
-        let resp = AdminCreateUserResponse {
-            user: UserType {
-                username: user.username.clone(),
-                attributes: user
-                    .attributes
-                    .iter()
-                    .map(|a| AttributeType {
-                        name: a.name.clone(),
-                        value: a.value.clone(),
-                    })
-                    .collect(),
-                user_create_date: user.user_create_date,
-                user_last_modified_date: user.user_last_modified_date,
-                enabled: user.enabled,
-                user_status: user.user_status.clone(),
-            },
+        let messages = if action == Some("SUPPRESS") {
+            Vec::new()
+        } else {
+            invitations(pool, &user, &req.desired_delivery_mediums)?
         };
-        pool.users.insert(req.username, user);
-        pool.estimated_number_of_users = pool.users.len() as i64;
-        Ok(resp)
+        state.sent_messages.extend(messages);
+        Ok(AdminCreateUserResponse { user: user_to_type(&user) })
+    }
+
+    /// Every message that would have been delivered, oldest first.
+    pub async fn sent_messages(&self) -> Vec<SentMessage> {
+        self.inner.lock().await.sent_messages.clone()
     }
 
     pub async fn admin_delete_user(
@@ -412,6 +510,7 @@ impl CognitoState {
         if let Some(e) = req.enable_token_revocation {
             client.enable_token_revocation = e;
         }
+        client.set_token_validity(req.token_validity).map_err(CognitoError::InvalidParameterException)?;
 
         let resp = CreateUserPoolClientResponse {
             user_pool_client: client_to_type(&client),
@@ -504,6 +603,7 @@ impl CognitoState {
                 "User pool client {} not found.",
                 req.client_id
             )))?;
+        client.set_token_validity(req.token_validity).map_err(CognitoError::InvalidParameterException)?;
         if let Some(name) = req.client_name {
             client.client_name = name;
         }
@@ -735,7 +835,7 @@ impl CognitoState {
         let state = self.inner.lock().await;
 
         // Find pool that owns this client
-        let (pool, _client) = state
+        let (pool, client) = state
             .pools
             .values()
             .find_map(|p| p.clients.get(&req.client_id).map(|c| (p, c)))
@@ -769,7 +869,7 @@ impl CognitoState {
                 Ok(InitiateAuthResponse {
                     authentication_result: Some(AuthenticationResultType {
                         access_token: fake_token("access", &username, &pool.id),
-                        expires_in: 3600,
+                        expires_in: client.access_token_seconds(),
                         token_type: "Bearer".to_string(),
                         refresh_token: fake_token("refresh", &username, &pool.id),
                         id_token: fake_token("id", &username, &pool.id),
@@ -781,7 +881,7 @@ impl CognitoState {
             "REFRESH_TOKEN_AUTH" | "REFRESH_TOKEN" => Ok(InitiateAuthResponse {
                 authentication_result: Some(AuthenticationResultType {
                     access_token: fake_token("access", "refresh-user", &pool.id),
-                    expires_in: 3600,
+                    expires_in: client.access_token_seconds(),
                     token_type: "Bearer".to_string(),
                     refresh_token: fake_token("refresh", "refresh-user", &pool.id),
                     id_token: fake_token("id", "refresh-user", &pool.id),
@@ -803,12 +903,9 @@ impl CognitoState {
         let state = self.inner.lock().await;
         let pool = get_pool(&state.pools, &req.user_pool_id)?;
 
-        if !pool.clients.contains_key(&req.client_id) {
-            return Err(CognitoError::ResourceNotFoundException(format!(
-                "User pool client {} not found.",
-                req.client_id
-            )));
-        }
+        let client = pool.clients.get(&req.client_id).ok_or_else(|| {
+            CognitoError::ResourceNotFoundException(format!("User pool client {} not found.", req.client_id))
+        })?;
 
         match req.auth_flow.as_str() {
             "ADMIN_USER_PASSWORD_AUTH" | "ADMIN_NO_SRP_AUTH" => {
@@ -832,7 +929,7 @@ impl CognitoState {
                 Ok(InitiateAuthResponse {
                     authentication_result: Some(AuthenticationResultType {
                         access_token: fake_token("access", &username, &pool.id),
-                        expires_in: 3600,
+                        expires_in: client.access_token_seconds(),
                         token_type: "Bearer".to_string(),
                         refresh_token: fake_token("refresh", &username, &pool.id),
                         id_token: fake_token("id", &username, &pool.id),
@@ -997,10 +1094,212 @@ impl CognitoState {
         user.user_last_modified_date = now();
         Ok(())
     }
+
+    // --- Identity Pools ---
+
+    pub async fn create_identity_pool(
+        &self,
+        req: CreateIdentityPoolRequest,
+    ) -> Result<IdentityPoolType, CognitoError> {
+        let mut state = self.inner.lock().await;
+        if req.identity_pool_name.is_empty() || req.identity_pool_name.len() > 128 {
+            return Err(CognitoError::InvalidParameterException(
+                "IdentityPoolName must be between 1 and 128 characters.".to_string(),
+            ));
+        }
+        let id = format!("{}:{}", state.region, Uuid::new_v4());
+        let mut pool = IdentityPool::new(id.clone(), req.identity_pool_name, req.allow_unauthenticated_identities);
+        pool.allow_classic_flow = req.allow_classic_flow;
+        pool.supported_login_providers = req.supported_login_providers.unwrap_or_default();
+        pool.developer_provider_name = req.developer_provider_name;
+        pool.cognito_identity_providers = req.cognito_identity_providers.unwrap_or_default();
+        pool.tags = req.identity_pool_tags.unwrap_or_default();
+        let resp = identity_pool_to_type(&pool);
+        state.identity_pools.insert(id, pool);
+        Ok(resp)
+    }
+
+    pub async fn describe_identity_pool(
+        &self,
+        req: DescribeIdentityPoolRequest,
+    ) -> Result<IdentityPoolType, CognitoError> {
+        let state = self.inner.lock().await;
+        let pool = get_identity_pool(&state.identity_pools, &req.identity_pool_id)?;
+        Ok(identity_pool_to_type(pool))
+    }
+
+    pub async fn delete_identity_pool(&self, req: DeleteIdentityPoolRequest) -> Result<(), CognitoError> {
+        let mut state = self.inner.lock().await;
+        if state.identity_pools.remove(&req.identity_pool_id).is_none() {
+            return Err(CognitoError::ResourceNotFoundException(format!(
+                "IdentityPool '{}' not found.",
+                req.identity_pool_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the identity linked to the given logins, creating one on
+    /// first use. Without logins a new unauthenticated identity is issued.
+    pub async fn get_id(&self, req: GetIdRequest) -> Result<GetIdResponse, CognitoError> {
+        let mut state = self.inner.lock().await;
+        let state = &mut *state;
+        let pool = state.identity_pools.get_mut(&req.identity_pool_id).ok_or_else(|| {
+            CognitoError::ResourceNotFoundException(format!(
+                "IdentityPool '{}' not found.",
+                req.identity_pool_id
+            ))
+        })?;
+        let logins = req.logins.unwrap_or_default();
+        if logins.is_empty() && !pool.allow_unauthenticated_identities {
+            return Err(CognitoError::NotAuthorizedException(
+                "Unauthenticated access is not supported for this identity pool.".to_string(),
+            ));
+        }
+        let keys = login_keys(&logins, pool, &state.pools)?;
+        let existing = keys.iter().find_map(|key| pool.logins.get(key)).cloned();
+        let identity_id = existing.unwrap_or_else(|| format!("{}:{}", state.region, Uuid::new_v4()));
+        let identity = pool
+            .identities
+            .entry(identity_id.clone())
+            .or_insert_with(Identity::new);
+        for key in keys {
+            if !identity.logins.contains(&key) {
+                identity.logins.push(key.clone());
+            }
+            pool.logins.insert(key, identity_id.clone());
+        }
+        Ok(GetIdResponse { identity_id })
+    }
+
+    /// Issues stub temporary credentials; they are not checked by any
+    /// other service.
+    pub async fn get_credentials_for_identity(
+        &self,
+        req: GetCredentialsForIdentityRequest,
+    ) -> Result<GetCredentialsForIdentityResponse, CognitoError> {
+        let state = self.inner.lock().await;
+        authorize_identity(&state, &req.identity_id, &req.logins.unwrap_or_default())?;
+        let random = || Uuid::new_v4().simple().to_string().to_uppercase();
+        Ok(GetCredentialsForIdentityResponse {
+            identity_id: req.identity_id,
+            credentials: CredentialsType {
+                access_key_id: format!("ASIA{}", &random()[..16]),
+                secret_key: format!("{}{}", random(), &random()[..8]),
+                session_token: format!("{}{}", random(), random()),
+                expiration: (now() + 3600.0).floor(),
+            },
+        })
+    }
+
+    pub async fn get_open_id_token(
+        &self,
+        req: GetOpenIdTokenRequest,
+    ) -> Result<GetOpenIdTokenResponse, CognitoError> {
+        let state = self.inner.lock().await;
+        authorize_identity(&state, &req.identity_id, &req.logins.unwrap_or_default())?;
+        Ok(GetOpenIdTokenResponse {
+            token: format!("openid-{}-{}", req.identity_id, Uuid::new_v4()),
+            identity_id: req.identity_id,
+        })
+    }
 }
 
 // --- Helpers ---
 
+fn get_identity_pool<'a>(
+    pools: &'a HashMap<String, IdentityPool>,
+    pool_id: &str,
+) -> Result<&'a IdentityPool, CognitoError> {
+    pools
+        .get(pool_id)
+        .ok_or_else(|| CognitoError::ResourceNotFoundException(format!(
+            "IdentityPool '{}' not found.",
+            pool_id
+        )))
+}
+
+/// Resolves each login to a `provider/subject` key. A user pool id token
+/// names its user, so every token issued to that user maps to the same
+/// identity; tokens from other providers are their own subject.
+fn login_keys(
+    logins: &HashMap<String, String>,
+    pool: &IdentityPool,
+    user_pools: &HashMap<String, UserPool>,
+) -> Result<Vec<String>, CognitoError> {
+    let mut keys = Vec::new();
+    for (provider, token) in logins {
+        if !pool.accepts_provider(provider) {
+            return Err(CognitoError::NotAuthorizedException(format!(
+                "Invalid login token. Provider {provider} is not configured for this identity pool."
+            )));
+        }
+        let subject = match provider.split_once(".amazonaws.com/") {
+            Some((host, user_pool_id)) if host.starts_with("cognito-idp.") => {
+                let username = token
+                    .strip_prefix("id-")
+                    .and_then(|rest| rest.rfind(&format!("-{user_pool_id}-")).map(|end| &rest[..end]))
+                    .filter(|username| {
+                        user_pools
+                            .get(user_pool_id)
+                            .is_some_and(|user_pool| user_pool.users.contains_key(*username))
+                    })
+                    .ok_or_else(|| CognitoError::NotAuthorizedException("Invalid login token.".to_string()))?;
+                username.to_string()
+            }
+            _ => token.clone(),
+        };
+        keys.push(format!("{provider}/{subject}"));
+    }
+    keys.sort();
+    Ok(keys)
+}
+
+/// Checks that `logins` may act as `identity_id`: an authenticated identity
+/// needs one of its own logins, an unauthenticated one needs a pool that
+/// still allows guests.
+fn authorize_identity(
+    state: &CognitoStateInner,
+    identity_id: &str,
+    logins: &HashMap<String, String>,
+) -> Result<(), CognitoError> {
+    let (pool, identity) = state
+        .identity_pools
+        .values()
+        .find_map(|pool| pool.identities.get(identity_id).map(|identity| (pool, identity)))
+        .ok_or_else(|| {
+            CognitoError::ResourceNotFoundException(format!("Identity '{identity_id}' not found."))
+        })?;
+    if identity.logins.is_empty() {
+        if !pool.allow_unauthenticated_identities {
+            return Err(CognitoError::NotAuthorizedException(
+                "Unauthenticated access is not supported for this identity pool.".to_string(),
+            ));
+        }
+        return Ok(());
+    }
+    let keys = login_keys(logins, pool, &state.pools)?;
+    if !keys.iter().any(|key| identity.logins.contains(key)) {
+        return Err(CognitoError::NotAuthorizedException(format!(
+            "Access to Identity '{identity_id}' is forbidden."
+        )));
+    }
+    Ok(())
+}
+
+fn identity_pool_to_type(pool: &IdentityPool) -> IdentityPoolType {
+    IdentityPoolType {
+        identity_pool_id: pool.id.clone(),
+        identity_pool_name: pool.name.clone(),
+        allow_unauthenticated_identities: pool.allow_unauthenticated_identities,
+        allow_classic_flow: pool.allow_classic_flow,
+        supported_login_providers: pool.supported_login_providers.clone(),
+        developer_provider_name: pool.developer_provider_name.clone(),
+        cognito_identity_providers: pool.cognito_identity_providers.clone(),
+        identity_pool_tags: pool.tags.clone(),
+    }
+}
+
 fn get_pool<'a>(
     pools: &'a HashMap<String, UserPool>,
     pool_id: &str,
@@ -1036,9 +1335,159 @@ fn pool_to_type(pool: &UserPool) -> UserPoolType {
         estimated_number_of_users: pool.estimated_number_of_users,
         auto_verified_attributes: pool.auto_verified_attributes.clone(),
         username_attributes: pool.username_attributes.clone(),
+        alias_attributes: pool.alias_attributes.clone(),
+        mfa_configuration: pool.mfa_configuration.clone(),
+        policies: pool.policies.clone(),
+        schema_attributes: pool.schema.clone(),
+        email_configuration: pool.email_configuration.clone(),
+        sms_configuration: pool.sms_configuration.clone(),
+        lambda_config: pool.lambda_config.clone(),
+        admin_create_user_config: pool.admin_create_user_config.clone(),
+        verification_message_template: pool.verification_message_template.clone(),
+        account_recovery_setting: pool.account_recovery_setting.clone(),
+        username_configuration: pool.username_configuration.clone(),
+        email_verification_message: pool.email_verification_message.clone(),
+        email_verification_subject: pool.email_verification_subject.clone(),
+        sms_verification_message: pool.sms_verification_message.clone(),
+        sms_authentication_message: pool.sms_authentication_message.clone(),
+        user_pool_tags: pool.tags.clone(),
     }
 }
 
+/// With `UsernameAttributes` set, the username must be one of them: it is
+/// recorded as that attribute unless one was given.
+fn username_attribute(pool: &UserPool, username: &str, attrs: &mut Vec<AttributeType>) -> Result<(), CognitoError> {
+    if pool.username_attributes.is_empty() {
+        return Ok(());
+    }
+    let allows = |name: &str| pool.username_attributes.iter().any(|a| a == name);
+    let name = if allows("email") && username.contains('@') {
+        "email"
+    } else if allows("phone_number") && username.starts_with('+') {
+        "phone_number"
+    } else {
+        let expected = if pool.username_attributes.len() > 1 {
+            "either an email or a phone number"
+        } else if allows("email") {
+            "an email"
+        } else {
+            "a phone number"
+        };
+        return Err(CognitoError::InvalidParameterException(format!("Username should be {expected}.")));
+    };
+    if !attrs.iter().any(|a| a.name == name) {
+        attrs.push(AttributeType { name: name.to_string(), value: username.to_string() });
+    }
+    Ok(())
+}
+
+/// A temporary password that satisfies the pool's password policy.
+fn temporary_password(pool: &UserPool) -> String {
+    let length = pool.policies.password_policy.as_ref().and_then(|p| p.minimum_length).unwrap_or(8).max(12);
+    let random = Uuid::new_v4().simple().to_string();
+    format!("Aa1!{random}").chars().take(length as usize).collect()
+}
+
+/// The invitation for each delivery medium the user has an address for,
+/// from the pool's `InviteMessageTemplate` or Cognito's default. Mediums
+/// default to SMS; explicitly requested mediums must be deliverable.
+fn invitations(pool: &UserPool, user: &User, mediums: &[String]) -> Result<Vec<SentMessage>, CognitoError> {
+    let requested = !mediums.is_empty();
+    let mediums = if requested { mediums.to_vec() } else { vec!["SMS".to_string()] };
+    let template = pool.admin_create_user_config.as_ref().and_then(|c| c.invite_message_template.as_ref());
+    let fill = |text: &str| {
+        text.replace("{username}", &user.username)
+            .replace("{####}", user.password.as_deref().unwrap_or_default())
+    };
+    let default_message = "Your username is {username} and temporary password is {####}.";
+    let mut messages = Vec::new();
+    for medium in mediums {
+        let attribute = if medium == "EMAIL" { "email" } else { "phone_number" };
+        let Some(destination) = user.attributes.iter().find(|a| a.name == attribute) else {
+            continue;
+        };
+        let (subject, message) = if medium == "EMAIL" {
+            let subject = template.and_then(|t| t.email_subject.as_deref()).unwrap_or("Your temporary password");
+            let message = template.and_then(|t| t.email_message.as_deref()).unwrap_or(default_message);
+            (Some(fill(subject)), fill(message))
+        } else {
+            (None, fill(template.and_then(|t| t.s_m_s_message.as_deref()).unwrap_or(default_message)))
+        };
+        messages.push(SentMessage {
+            user_pool_id: pool.id.clone(),
+            username: user.username.clone(),
+            kind: "Invitation".to_string(),
+            delivery_medium: medium,
+            destination: destination.value.clone(),
+            subject,
+            message,
+            timestamp: now(),
+        });
+    }
+    if requested && messages.is_empty() {
+        return Err(CognitoError::InvalidParameterException(
+            "User has no attribute matching desired delivery mediums".to_string(),
+        ));
+    }
+    Ok(messages)
+}
+
+/// Attributes every user pool has; any other schema attribute is custom.
+const STANDARD_ATTRIBUTES: &[&str] = &[
+    "sub", "name", "given_name", "family_name", "middle_name", "nickname", "preferred_username", "profile",
+    "picture", "website", "email", "email_verified", "gender", "birthdate", "zoneinfo", "locale", "phone_number",
+    "phone_number_verified", "address", "updated_at",
+];
+
+fn validate_pool_config(mfa: Option<&str>, policies: Option<&UserPoolPolicyType>) -> Result<(), CognitoError> {
+    if let Some(mfa) = mfa {
+        if !["OFF", "ON", "OPTIONAL"].contains(&mfa) {
+            return Err(CognitoError::InvalidParameterException(format!(
+                "1 validation error detected: Value '{mfa}' at 'mfaConfiguration' failed to satisfy constraint: \
+                 Member must satisfy enum value set: [OFF, ON, OPTIONAL]"
+            )));
+        }
+    }
+    if let Some(policy) = policies.and_then(|p| p.password_policy.as_ref()) {
+        if policy.minimum_length.is_some_and(|len| !(6..=99).contains(&len)) {
+            return Err(CognitoError::InvalidParameterException(
+                "Password policy MinimumLength must be between 6 and 99.".to_string(),
+            ));
+        }
+        if policy.temporary_password_validity_days.is_some_and(|days| days > 365) {
+            return Err(CognitoError::InvalidParameterException(
+                "Password policy TemporaryPasswordValidityDays must be at most 365.".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Normalizes `CreateUserPool`'s `Schema`: custom attributes are reported
+/// with a `custom:` prefix, and each name may appear only once.
+fn schema_attributes(schema: Vec<SchemaAttributeType>) -> Result<Vec<SchemaAttributeType>, CognitoError> {
+    let mut out: Vec<SchemaAttributeType> = Vec::with_capacity(schema.len());
+    for mut attr in schema {
+        if attr.name.is_empty() || attr.name.len() > 20 {
+            return Err(CognitoError::InvalidParameterException(format!(
+                "Invalid schema attribute name: {}",
+                attr.name
+            )));
+        }
+        if !STANDARD_ATTRIBUTES.contains(&attr.name.as_str()) && !attr.name.starts_with("custom:") {
+            attr.name = format!("custom:{}", attr.name);
+        }
+        if out.iter().any(|a| a.name == attr.name) {
+            return Err(CognitoError::InvalidParameterException(format!(
+                "Duplicate schema attribute: {}",
+                attr.name
+            )));
+        }
+        out.push(attr);
+    }
+    Ok(out)
+}
+
 fn user_to_type(user: &User) -> UserType {
     UserType {
         username: user.username.clone(),
@@ -1072,6 +1521,11 @@ fn client_to_type(client: &UserPoolClient) -> UserPoolClientType {
         logout_ur_ls: client.logout_urls.clone(),
         prevent_user_existence_errors: client.prevent_user_existence_errors.clone(),
         enable_token_revocation: client.enable_token_revocation,
+        access_token_validity: client.access_token_validity,
+        id_token_validity: client.id_token_validity,
+        refresh_token_validity: client.refresh_token_validity,
+        token_validity_units: client.token_validity_units.clone(),
+        auth_session_validity: client.auth_session_validity,
     }
 }
 
@@ -1160,6 +1614,85 @@ mod tests {
         assert_eq!(result.unwrap().user_pool.name, "test-pool");
     }
 
+    #[tokio::test]
+    async fn test_describe_user_pool_round_trips_configuration() {
+        let state = make_state();
+        let policy = PasswordPolicyType {
+            minimum_length: Some(12),
+            require_uppercase: Some(true),
+            require_lowercase: Some(true),
+            require_numbers: Some(false),
+            require_symbols: Some(false),
+            temporary_password_validity_days: Some(3),
+        };
+        let req = CreateUserPoolRequest {
+            pool_name: "configured".to_string(),
+            mfa_configuration: Some("OPTIONAL".to_string()),
+            policies: Some(UserPoolPolicyType { password_policy: Some(policy.clone()) }),
+            schema: vec![SchemaAttributeType {
+                name: "tier".to_string(),
+                attribute_data_type: Some("String".to_string()),
+                mutable: Some(true),
+                ..Default::default()
+            }],
+            lambda_config: Some(LambdaConfigType {
+                pre_sign_up: Some("arn:aws:lambda:us-east-1:123456789012:function:pre".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let pool_id = state.create_user_pool(req).await.unwrap().user_pool.id;
+
+        let pool = state
+            .describe_user_pool(DescribeUserPoolRequest { user_pool_id: pool_id.clone() })
+            .await
+            .unwrap()
+            .user_pool;
+        assert_eq!(pool.mfa_configuration, "OPTIONAL");
+        assert_eq!(pool.policies.password_policy, Some(policy));
+        assert_eq!(pool.schema_attributes[0].name, "custom:tier");
+        let lambda = pool.lambda_config.unwrap();
+        assert_eq!(lambda.pre_sign_up.as_deref(), Some("arn:aws:lambda:us-east-1:123456789012:function:pre"));
+
+        let json = serde_json::to_value(
+            state.describe_user_pool(DescribeUserPoolRequest { user_pool_id: pool_id.clone() }).await.unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json["UserPool"]["Policies"]["PasswordPolicy"]["MinimumLength"], 12);
+        assert_eq!(json["UserPool"]["SchemaAttributes"][0]["AttributeDataType"], "String");
+
+        let req = UpdateUserPoolRequest {
+            user_pool_id: pool_id.clone(),
+            mfa_configuration: Some("OFF".to_string()),
+            ..Default::default()
+        };
+        state.update_user_pool(req).await.unwrap();
+        let pool = state.describe_user_pool(DescribeUserPoolRequest { user_pool_id: pool_id }).await.unwrap().user_pool;
+        assert_eq!(pool.mfa_configuration, "OFF");
+        assert_eq!(pool.policies.password_policy.unwrap().minimum_length, Some(12));
+
+        let req = CreateUserPoolRequest {
+            pool_name: "bad".to_string(),
+            mfa_configuration: Some("SOMETIMES".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            state.create_user_pool(req).await,
+            Err(CognitoError::InvalidParameterException(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_default_password_policy() {
+        let state = make_state();
+        let pool_id = create_pool(&state).await;
+        let pool = state.describe_user_pool(DescribeUserPoolRequest { user_pool_id: pool_id }).await.unwrap().user_pool;
+        assert_eq!(pool.mfa_configuration, "OFF");
+        let policy = pool.policies.password_policy.unwrap();
+        assert_eq!(policy.minimum_length, Some(8));
+        assert_eq!(policy.temporary_password_validity_days, Some(7));
+    }
+
     #[tokio::test]
     async fn test_describe_user_pool_not_found() {
         let state = make_state();
@@ -1276,6 +1809,94 @@ mod tests {
         assert!(state.admin_set_user_password(req).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_admin_create_user_invitations() {
+        let state = make_state();
+        let req = CreateUserPoolRequest {
+            pool_name: "invites".to_string(),
+            admin_create_user_config: Some(AdminCreateUserConfigType {
+                invite_message_template: Some(MessageTemplateType {
+                    email_subject: Some("Welcome".to_string()),
+                    email_message: Some("Hi {username}, use {####}".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let pool_id = state.create_user_pool(req).await.unwrap().user_pool.id;
+        let email = || vec![AttributeType { name: "email".to_string(), value: "u1@example.com".to_string() }];
+
+        let resp = state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: pool_id.clone(),
+            username: "u1".to_string(),
+            user_attributes: email(),
+            temporary_password: Some("Temp#1234".to_string()),
+            desired_delivery_mediums: vec!["EMAIL".to_string()],
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(resp.user.user_status, "FORCE_CHANGE_PASSWORD");
+        let sent = state.sent_messages().await;
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].destination, "u1@example.com");
+        assert_eq!(sent[0].subject.as_deref(), Some("Welcome"));
+        assert_eq!(sent[0].message, "Hi u1, use Temp#1234");
+
+        let resp = state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: pool_id.clone(),
+            username: "u2".to_string(),
+            user_attributes: email(),
+            message_action: Some("SUPPRESS".to_string()),
+            desired_delivery_mediums: vec!["EMAIL".to_string()],
+            ..Default::default()
+        }).await.unwrap();
+        // A generated temporary password still forces a change.
+        assert_eq!(resp.user.user_status, "FORCE_CHANGE_PASSWORD");
+        assert_eq!(state.sent_messages().await.len(), 1);
+
+        state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: pool_id.clone(),
+            username: "u2".to_string(),
+            message_action: Some("RESEND".to_string()),
+            desired_delivery_mediums: vec!["EMAIL".to_string()],
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(state.sent_messages().await.len(), 2);
+
+        let err = state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: pool_id,
+            username: "u3".to_string(),
+            desired_delivery_mediums: vec!["SMS".to_string()],
+            ..Default::default()
+        }).await;
+        assert!(matches!(err, Err(CognitoError::InvalidParameterException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_admin_create_user_username_attributes() {
+        let state = make_state();
+        let req = CreateUserPoolRequest {
+            pool_name: "by-email".to_string(),
+            username_attributes: vec!["email".to_string()],
+            ..Default::default()
+        };
+        let pool_id = state.create_user_pool(req).await.unwrap().user_pool.id;
+        let err = state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: pool_id.clone(),
+            username: "not-an-email".to_string(),
+            ..Default::default()
+        }).await;
+        assert!(matches!(err, Err(CognitoError::InvalidParameterException(_))));
+
+        let resp = state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: pool_id,
+            username: "a@example.com".to_string(),
+            message_action: Some("SUPPRESS".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert!(resp.user.attributes.iter().any(|a| a.name == "email" && a.value == "a@example.com"));
+    }
+
     #[tokio::test]
     async fn test_list_users() {
         let state = make_state();
@@ -1347,6 +1968,64 @@ mod tests {
         assert_eq!(result.user_pool_clients.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_client_token_validity_sets_expires_in() {
+        let state = make_state();
+        let pool_id = create_pool(&state).await;
+        let client_id = state.create_user_pool_client(CreateUserPoolClientRequest {
+            user_pool_id: pool_id.clone(),
+            client_name: "app".to_string(),
+            token_validity: TokenValiditySettings {
+                access_token_validity: Some(15),
+                token_validity_units: Some(TokenValidityUnitsType {
+                    access_token: Some("minutes".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }).await.unwrap().user_pool_client.client_id;
+
+        let client = state.describe_user_pool_client(DescribeUserPoolClientRequest {
+            user_pool_id: pool_id.clone(),
+            client_id: client_id.clone(),
+        }).await.unwrap().user_pool_client;
+        assert_eq!(client.access_token_validity, 15);
+        assert_eq!(client.token_validity_units.access_token.as_deref(), Some("minutes"));
+        assert_eq!(client.refresh_token_validity, 30);
+        assert_eq!(client.token_validity_units.refresh_token.as_deref(), Some("days"));
+        assert_eq!(client.auth_session_validity, 3);
+
+        state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: pool_id.clone(),
+            username: "alice".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let auth_parameters = Some(HashMap::from([("USERNAME".to_string(), "alice".to_string())]));
+        let result = state.initiate_auth(InitiateAuthRequest {
+            auth_flow: "USER_PASSWORD_AUTH".to_string(),
+            client_id: client_id.clone(),
+            auth_parameters: auth_parameters.clone(),
+        }).await.unwrap().authentication_result.unwrap();
+        assert_eq!(result.expires_in, 900);
+        let result = state.admin_initiate_auth(AdminInitiateAuthRequest {
+            user_pool_id: pool_id.clone(),
+            client_id: client_id.clone(),
+            auth_flow: "ADMIN_NO_SRP_AUTH".to_string(),
+            auth_parameters,
+        }).await.unwrap().authentication_result.unwrap();
+        assert_eq!(result.expires_in, 900);
+
+        // Out of range: access tokens live at least 5 minutes.
+        let err = state.update_user_pool_client(UpdateUserPoolClientRequest {
+            user_pool_id: pool_id,
+            client_id,
+            token_validity: TokenValiditySettings { access_token_validity: Some(4), ..Default::default() },
+            ..Default::default()
+        }).await;
+        assert!(matches!(err, Err(CognitoError::InvalidParameterException(_))));
+    }
+
     #[tokio::test]
     async fn test_admin_add_remove_user_to_group() {
         let state = make_state();
@@ -1378,4 +2057,76 @@ mod tests {
             group_name: "g1".to_string(),
         }).await.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_identity_pool_federates_user_pool_login() {
+        let state = make_state();
+        let user_pool_id = create_pool(&state).await;
+        let client_id = state.create_user_pool_client(CreateUserPoolClientRequest {
+            user_pool_id: user_pool_id.clone(),
+            client_name: "web".to_string(),
+            ..Default::default()
+        }).await.unwrap().user_pool_client.client_id;
+        state.admin_create_user(AdminCreateUserRequest {
+            user_pool_id: user_pool_id.clone(),
+            username: "alice".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let sign_in = || async {
+            state.initiate_auth(InitiateAuthRequest {
+                auth_flow: "USER_PASSWORD_AUTH".to_string(),
+                client_id: client_id.clone(),
+                auth_parameters: Some(HashMap::from([("USERNAME".to_string(), "alice".to_string())])),
+            }).await.unwrap().authentication_result.unwrap().id_token
+        };
+        let provider = format!("cognito-idp.us-east-1.amazonaws.com/{user_pool_id}");
+
+        let identity_pool = state.create_identity_pool(CreateIdentityPoolRequest {
+            identity_pool_name: "app".to_string(),
+            cognito_identity_providers: Some(vec![CognitoIdentityProviderType {
+                provider_name: provider.clone(),
+                client_id: client_id.clone(),
+                server_side_token_check: None,
+            }]),
+            ..Default::default()
+        }).await.unwrap();
+        let get_id = |token: String| GetIdRequest {
+            identity_pool_id: identity_pool.identity_pool_id.clone(),
+            logins: Some(HashMap::from([(provider.clone(), token)])),
+        };
+        let identity_id = state.get_id(get_id(sign_in().await)).await.unwrap().identity_id;
+        // A later sign-in by the same user maps to the same identity.
+        assert_eq!(state.get_id(get_id(sign_in().await)).await.unwrap().identity_id, identity_id);
+        assert!(matches!(
+            state.get_id(get_id("forged".to_string())).await,
+            Err(CognitoError::NotAuthorizedException(_))
+        ));
+
+        let creds = state.get_credentials_for_identity(GetCredentialsForIdentityRequest {
+            identity_id: identity_id.clone(),
+            logins: get_id(sign_in().await).logins,
+        }).await.unwrap();
+        assert_eq!(creds.identity_id, identity_id);
+        assert!(creds.credentials.access_key_id.starts_with("ASIA"));
+        assert!(!creds.credentials.session_token.is_empty());
+
+        // An authenticated identity cannot be used without its login, and the
+        // pool does not allow unauthenticated identities.
+        assert!(matches!(
+            state.get_credentials_for_identity(GetCredentialsForIdentityRequest {
+                identity_id: identity_id.clone(),
+                logins: None,
+            }).await,
+            Err(CognitoError::NotAuthorizedException(_))
+        ));
+        assert!(matches!(
+            state.get_id(GetIdRequest { identity_pool_id: identity_pool.identity_pool_id.clone(), logins: None }).await,
+            Err(CognitoError::NotAuthorizedException(_))
+        ));
+        let token = state.get_open_id_token(GetOpenIdTokenRequest {
+            identity_id: identity_id.clone(),
+            logins: get_id(sign_in().await).logins,
+        }).await.unwrap();
+        assert_eq!(token.identity_id, identity_id);
+    }
 }
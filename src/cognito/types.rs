@@ -25,6 +25,220 @@ pub struct UserPoolType {
     pub auto_verified_attributes: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub username_attributes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alias_attributes: Vec<String>,
+    pub mfa_configuration: String,
+    pub policies: UserPoolPolicyType,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub schema_attributes: Vec<SchemaAttributeType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_configuration: Option<EmailConfigurationType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sms_configuration: Option<SmsConfigurationType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lambda_config: Option<LambdaConfigType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admin_create_user_config: Option<AdminCreateUserConfigType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification_message_template: Option<VerificationMessageTemplateType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_recovery_setting: Option<AccountRecoverySettingType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username_configuration: Option<UsernameConfigurationType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verification_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verification_subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sms_verification_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sms_authentication_message: Option<String>,
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub user_pool_tags: std::collections::HashMap<String, String>,
+}
+
+// --- UserPool configuration blocks ---
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct UserPoolPolicyType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_policy: Option<PasswordPolicyType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PasswordPolicyType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum_length: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_uppercase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_lowercase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_numbers: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_symbols: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temporary_password_validity_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SchemaAttributeType {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribute_data_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub developer_only_attribute: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_attribute_constraints: Option<NumberAttributeConstraintsType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub string_attribute_constraints: Option<StringAttributeConstraintsType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NumberAttributeConstraintsType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct StringAttributeConstraintsType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EmailConfigurationType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_arn: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to_email_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_sending_account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub configuration_set: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SmsConfigurationType {
+    pub sns_caller_arn: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sns_region: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LambdaConfigType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_sign_up: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_confirmation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_authentication: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_authentication: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub define_auth_challenge: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub create_auth_challenge: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_auth_challenge_response: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_token_generation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_migration: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_s_m_s_sender: Option<CustomSenderLambdaType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_email_sender: Option<CustomSenderLambdaType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k_m_s_key_i_d: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomSenderLambdaType {
+    pub lambda_version: String,
+    pub lambda_arn: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AdminCreateUserConfigType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_admin_create_user_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unused_account_validity_days: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invite_message_template: Option<MessageTemplateType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MessageTemplateType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s_m_s_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_subject: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VerificationMessageTemplateType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sms_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_message_by_link: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_subject_by_link: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_email_option: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccountRecoverySettingType {
+    #[serde(default)]
+    pub recovery_mechanisms: Vec<RecoveryOptionType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecoveryOptionType {
+    pub priority: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct UsernameConfigurationType {
+    pub case_sensitive: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -49,6 +263,36 @@ pub struct CreateUserPoolRequest {
     pub username_attributes: Vec<String>,
     #[serde(default)]
     pub user_pool_tags: Option<std::collections::HashMap<String, String>>,
+    #[serde(default)]
+    pub alias_attributes: Vec<String>,
+    #[serde(default)]
+    pub mfa_configuration: Option<String>,
+    #[serde(default)]
+    pub policies: Option<UserPoolPolicyType>,
+    #[serde(default)]
+    pub schema: Vec<SchemaAttributeType>,
+    #[serde(default)]
+    pub email_configuration: Option<EmailConfigurationType>,
+    #[serde(default)]
+    pub sms_configuration: Option<SmsConfigurationType>,
+    #[serde(default)]
+    pub lambda_config: Option<LambdaConfigType>,
+    #[serde(default)]
+    pub admin_create_user_config: Option<AdminCreateUserConfigType>,
+    #[serde(default)]
+    pub verification_message_template: Option<VerificationMessageTemplateType>,
+    #[serde(default)]
+    pub account_recovery_setting: Option<AccountRecoverySettingType>,
+    #[serde(default)]
+    pub username_configuration: Option<UsernameConfigurationType>,
+    #[serde(default)]
+    pub email_verification_message: Option<String>,
+    #[serde(default)]
+    pub email_verification_subject: Option<String>,
+    #[serde(default)]
+    pub sms_verification_message: Option<String>,
+    #[serde(default)]
+    pub sms_authentication_message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -108,6 +352,30 @@ pub struct UpdateUserPoolRequest {
     pub user_pool_tags: Option<std::collections::HashMap<String, String>>,
     #[serde(default)]
     pub auto_verified_attributes: Option<Vec<String>>,
+    #[serde(default)]
+    pub mfa_configuration: Option<String>,
+    #[serde(default)]
+    pub policies: Option<UserPoolPolicyType>,
+    #[serde(default)]
+    pub email_configuration: Option<EmailConfigurationType>,
+    #[serde(default)]
+    pub sms_configuration: Option<SmsConfigurationType>,
+    #[serde(default)]
+    pub lambda_config: Option<LambdaConfigType>,
+    #[serde(default)]
+    pub admin_create_user_config: Option<AdminCreateUserConfigType>,
+    #[serde(default)]
+    pub verification_message_template: Option<VerificationMessageTemplateType>,
+    #[serde(default)]
+    pub account_recovery_setting: Option<AccountRecoverySettingType>,
+    #[serde(default)]
+    pub email_verification_message: Option<String>,
+    #[serde(default)]
+    pub email_verification_subject: Option<String>,
+    #[serde(default)]
+    pub sms_verification_message: Option<String>,
+    #[serde(default)]
+    pub sms_authentication_message: Option<String>,
 }
 
 // --- User types ---
@@ -134,6 +402,10 @@ pub struct AdminCreateUserRequest {
     pub user_attributes: Vec<AttributeType>,
     #[serde(default)]
     pub temporary_password: Option<String>,
+    #[serde(default)]
+    pub message_action: Option<String>,
+    #[serde(default)]
+    pub desired_delivery_mediums: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -142,6 +414,22 @@ pub struct AdminCreateUserResponse {
     pub user: UserType,
 }
 
+/// A message Cognito would have delivered to a user, kept in memory for
+/// inspection instead.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SentMessage {
+    pub user_pool_id: String,
+    pub username: String,
+    pub kind: String,
+    pub delivery_medium: String,
+    pub destination: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub message: String,
+    pub timestamp: f64,
+}
+
 // --- AdminDeleteUser ---
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -254,6 +542,42 @@ pub struct UserPoolClientType {
     pub logout_ur_ls: Vec<String>,
     pub prevent_user_existence_errors: String,
     pub enable_token_revocation: bool,
+    pub access_token_validity: i64,
+    pub id_token_validity: i64,
+    pub refresh_token_validity: i64,
+    pub token_validity_units: TokenValidityUnitsType,
+    pub auth_session_validity: i64,
+}
+
+/// The unit of each token validity: `seconds`, `minutes`, `hours` or
+/// `days`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TokenValidityUnitsType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+/// Token and session lifetimes, set by `CreateUserPoolClient` and
+/// `UpdateUserPoolClient`.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct TokenValiditySettings {
+    #[serde(default)]
+    pub access_token_validity: Option<i64>,
+    #[serde(default)]
+    pub id_token_validity: Option<i64>,
+    #[serde(default)]
+    pub refresh_token_validity: Option<i64>,
+    #[serde(default)]
+    pub token_validity_units: Option<TokenValidityUnitsType>,
+    /// Minutes an authentication session token stays valid.
+    #[serde(default)]
+    pub auth_session_validity: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -289,6 +613,8 @@ pub struct CreateUserPoolClientRequest {
     pub prevent_user_existence_errors: Option<String>,
     #[serde(default)]
     pub enable_token_revocation: Option<bool>,
+    #[serde(flatten)]
+    pub token_validity: TokenValiditySettings,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -364,6 +690,8 @@ pub struct UpdateUserPoolClientRequest {
     pub prevent_user_existence_errors: Option<String>,
     #[serde(default)]
     pub enable_token_revocation: Option<bool>,
+    #[serde(flatten)]
+    pub token_validity: TokenValiditySettings,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -620,3 +948,106 @@ pub struct AdminUpdateUserAttributesRequest {
     pub username: String,
     pub user_attributes: Vec<AttributeType>,
 }
+
+// --- Identity pools ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CognitoIdentityProviderType {
+    pub provider_name: String,
+    pub client_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_side_token_check: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreateIdentityPoolRequest {
+    pub identity_pool_name: String,
+    pub allow_unauthenticated_identities: bool,
+    pub allow_classic_flow: Option<bool>,
+    pub supported_login_providers: Option<std::collections::HashMap<String, String>>,
+    pub developer_provider_name: Option<String>,
+    pub cognito_identity_providers: Option<Vec<CognitoIdentityProviderType>>,
+    pub identity_pool_tags: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct IdentityPoolType {
+    pub identity_pool_id: String,
+    pub identity_pool_name: String,
+    pub allow_unauthenticated_identities: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_classic_flow: Option<bool>,
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub supported_login_providers: std::collections::HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub developer_provider_name: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cognito_identity_providers: Vec<CognitoIdentityProviderType>,
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub identity_pool_tags: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeIdentityPoolRequest {
+    pub identity_pool_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteIdentityPoolRequest {
+    pub identity_pool_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetIdRequest {
+    pub identity_pool_id: String,
+    pub logins: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetIdResponse {
+    pub identity_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetCredentialsForIdentityRequest {
+    pub identity_id: String,
+    pub logins: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CredentialsType {
+    pub access_key_id: String,
+    pub secret_key: String,
+    pub session_token: String,
+    pub expiration: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetCredentialsForIdentityResponse {
+    pub identity_id: String,
+    pub credentials: CredentialsType,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetOpenIdTokenRequest {
+    pub identity_id: String,
+    pub logins: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetOpenIdTokenResponse {
+    pub identity_id: String,
+    pub token: String,
+}
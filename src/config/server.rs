@@ -110,6 +110,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<ConfigState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("config"))
         .with_state(state)
 }
 
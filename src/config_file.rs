@@ -0,0 +1,284 @@
+//! Startup settings from a TOML or YAML file, given with `--config <file>`.
+//!
+//! The file is translated into the same flags the command line takes, so
+//! everything it sets ends up in `Args`. Flags given on the command line win
+//! over the file.
+//!
+//! ```toml
+//! region = "eu-west-1"
+//!
+//! [services.sqs]
+//! port = 19324
+//!
+//! [services.dynamodb]
+//! port = 18000
+//! enforce_throughput = true
+//!
+//! [seeds]
+//! dynamodb = "fixtures/dynamodb"
+//!
+//! [faults.sqs]
+//! latency_ms = 200
+//! error_rate = 0.1
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub region: Option<String>,
+    pub account_id: Option<String>,
+    pub admin_port: Option<u16>,
+    pub log_level: Option<String>,
+    pub log_bodies: Option<bool>,
+    pub allow_cross_account: Option<bool>,
+    /// Per-service port, enabled flag and service-specific options, keyed
+    /// by the name used in `--<service>-port`.
+    #[serde(default)]
+    pub services: BTreeMap<String, ServiceConfig>,
+    /// Fixture directory per service, relative to the config file.
+    #[serde(default)]
+    pub seeds: BTreeMap<String, PathBuf>,
+    #[serde(default)]
+    pub faults: BTreeMap<String, FaultSection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ServiceConfig {
+    pub port: Option<u16>,
+    /// Setting `enabled = true` on any service runs only the services
+    /// enabled in the file; `enabled = false` leaves a service out.
+    pub enabled: Option<bool>,
+    /// Any other key becomes `--<service>-<key>`, e.g. `enforce_throughput`
+    /// under `dynamodb` is `--dynamodb-enforce-throughput`.
+    #[serde(flatten)]
+    pub options: BTreeMap<String, OptionValue>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultSection {
+    pub latency_ms: Option<u64>,
+    pub error_rate: Option<f64>,
+    pub error_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OptionValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    List(Vec<String>),
+}
+
+impl ConfigFile {
+    /// Parses `path` as YAML for `.yaml`/`.yml` files and as TOML otherwise.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let mut config: Self = match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?,
+            _ => toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?,
+        };
+        let base = path.parent().unwrap_or(Path::new(""));
+        for dir in config.seeds.values_mut() {
+            *dir = base.join(&*dir);
+        }
+        Ok(config)
+    }
+
+    /// The flags this file stands for. `services` lists the valid service
+    /// names and is the order in which enabled services are listed.
+    pub fn to_args(&self, services: &[String]) -> Result<Vec<(String, Option<String>)>, String> {
+        let known = |service: &str, section: &str| {
+            if services.iter().any(|s| s == service) {
+                Ok(())
+            } else {
+                Err(format!("unknown service '{service}' in [{section}]"))
+            }
+        };
+        let mut flags: Vec<(String, Option<String>)> = Vec::new();
+        let mut set = |name: &str, value: Option<String>| flags.push((format!("--{}", name.replace('_', "-")), value));
+
+        if let Some(region) = &self.region {
+            set("region", Some(region.clone()));
+        }
+        if let Some(account_id) = &self.account_id {
+            set("account_id", Some(account_id.clone()));
+        }
+        if let Some(port) = self.admin_port {
+            set("admin_port", Some(port.to_string()));
+        }
+        if let Some(level) = &self.log_level {
+            set("log_level", Some(level.clone()));
+        }
+        if self.log_bodies == Some(true) {
+            set("log_bodies", None);
+        }
+        if self.allow_cross_account == Some(true) {
+            set("allow_cross_account", None);
+        }
+
+        for (service, config) in &self.services {
+            known(service, "services")?;
+            if let Some(port) = config.port {
+                set(&format!("{service}_port"), Some(port.to_string()));
+            }
+            for (key, value) in &config.options {
+                let name = format!("{service}_{key}");
+                match value {
+                    OptionValue::Bool(true) => set(&name, None),
+                    OptionValue::Bool(false) => {}
+                    OptionValue::Int(n) => set(&name, Some(n.to_string())),
+                    OptionValue::Float(n) => set(&name, Some(n.to_string())),
+                    OptionValue::Str(s) => set(&name, Some(s.clone())),
+                    OptionValue::List(items) => set(&name, Some(items.join(","))),
+                }
+            }
+        }
+        if self.services.values().any(|c| c.enabled.is_some()) {
+            let only = self.services.values().any(|c| c.enabled == Some(true));
+            let enabled: Vec<&str> = services
+                .iter()
+                .filter(|s| match self.services.get(*s).and_then(|c| c.enabled) {
+                    Some(enabled) => enabled,
+                    None => !only,
+                })
+                .map(String::as_str)
+                .collect();
+            set("services", Some(enabled.join(",")));
+        }
+
+        for (service, dir) in &self.seeds {
+            known(service, "seeds")?;
+            set(&format!("{service}_seed"), Some(dir.display().to_string()));
+        }
+
+        for (service, fault) in &self.faults {
+            known(service, "faults")?;
+            if let Some(ms) = fault.latency_ms {
+                set(&format!("{service}_fault_latency_ms"), Some(ms.to_string()));
+            }
+            if let Some(rate) = fault.error_rate {
+                set(&format!("{service}_fault_error_rate"), Some(rate.to_string()));
+            }
+            if let Some(code) = &fault.error_code {
+                set(&format!("{service}_fault_error_code"), Some(code.clone()));
+            }
+        }
+        Ok(flags)
+    }
+}
+
+/// Expands `--config <file>` into the flags the file sets, placed ahead of
+/// the command-line flags. A flag given on the command line replaces the
+/// file's value rather than repeating it.
+pub fn expand_args(argv: Vec<String>, services: &[String]) -> Result<Vec<String>, String> {
+    let path = argv.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix("--config=") {
+        Some(path) => Some(Ok(path.to_string())),
+        None if arg == "--config" => Some(argv.get(i + 1).cloned().ok_or_else(|| "missing value for --config".to_string())),
+        None => None,
+    });
+    let Some(path) = path.transpose()? else {
+        return Ok(argv);
+    };
+    let config = ConfigFile::load(Path::new(&path))?;
+    let given = |flag: &str| argv.iter().any(|a| a == flag || a.strip_prefix(flag).is_some_and(|rest| rest.starts_with('=')));
+
+    let mut expanded: Vec<String> = argv.first().cloned().into_iter().collect();
+    for (flag, value) in config.to_args(services)? {
+        if given(&flag) {
+            continue;
+        }
+        expanded.push(flag);
+        expanded.extend(value);
+    }
+    expanded.extend(argv.into_iter().skip(1));
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::Args;
+
+    fn write_config(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn parse(argv: &[&str]) -> Args {
+        let argv = argv.iter().map(|s| s.to_string()).collect();
+        let services = crate::service_names();
+        let argv = expand_args(argv, &services).unwrap();
+        let (argv, _) = crate::faults::extract_args(argv, &services).unwrap();
+        Args::parse_from(argv)
+    }
+
+    #[test]
+    fn test_toml_config_enables_two_services_on_custom_ports() {
+        let path = write_config(
+            "services.toml",
+            r#"
+region = "eu-west-1"
+
+[services.sqs]
+port = 19324
+enabled = true
+
+[services.dynamodb]
+port = 18000
+enabled = true
+enforce_throughput = true
+
+[seeds]
+dynamodb = "fixtures"
+
+[faults.sqs]
+latency_ms = 25
+"#,
+        );
+        let args = parse(&["bin", "--config", path.to_str().unwrap()]);
+        assert_eq!(args.region, "eu-west-1");
+        assert_eq!(args.sqs_port, 19324);
+        assert_eq!(args.dynamodb_port, 18000);
+        assert!(args.dynamodb_enforce_throughput);
+        assert_eq!(args.dynamodb_seed, Some(path.parent().unwrap().join("fixtures")));
+        assert_eq!(args.services, vec!["dynamodb", "sqs"]);
+        assert!(args.is_enabled("sqs") && args.is_enabled("dynamodb"));
+        assert!(!args.is_enabled("s3"));
+        assert_eq!(args.s3_port, 9000);
+
+        let services = crate::service_names();
+        let argv = vec!["bin".to_string(), format!("--config={}", path.display())];
+        let (_, faults) = crate::faults::extract_args(expand_args(argv, &services).unwrap(), &services).unwrap();
+        assert_eq!(faults["sqs"].latency_ms, 25);
+    }
+
+    #[test]
+    fn test_yaml_config_is_overridden_by_command_line() {
+        let path = write_config(
+            "services.yaml",
+            "account_id: \"111122223333\"\nservices:\n  sqs:\n    port: 19324\n  s3:\n    enabled: false\n",
+        );
+        let args = parse(&["bin", "--config", path.to_str().unwrap(), "--sqs-port", "29324"]);
+        assert_eq!(args.account_id, "111122223333");
+        assert_eq!(args.sqs_port, 29324);
+        assert!(args.is_enabled("sqs"));
+        assert!(!args.is_enabled("s3"));
+
+        let path = write_config("bad.toml", "[services.s4]\nport = 1\n");
+        let argv = vec!["bin".to_string(), "--config".to_string(), path.display().to_string()];
+        assert!(expand_args(argv, &crate::service_names()).unwrap_err().contains("unknown service 's4'"));
+    }
+}
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<ConnectState>) -> Router {
     Router::new()
         .route("/instances", post(create_instance_handler).get(list_instances_handler))
         .route("/instances/{name}", get(get_instance_handler).delete(delete_instance_handler))
+        .merge(crate::healthcheck::router("connect"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<ControltowerState>) -> Router {
     Router::new()
         .route("/landing-zones", post(create_landing_zone_handler).get(list_landing_zones_handler))
         .route("/landing-zones/{name}", get(get_landing_zone_handler).delete(delete_landing_zone_handler))
+        .merge(crate::healthcheck::router("controltower"))
         .with_state(state)
 }
 
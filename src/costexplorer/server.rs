@@ -55,6 +55,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<CostexplorerState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("costexplorer"))
         .with_state(state)
 }
 
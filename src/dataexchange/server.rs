@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<DataexchangeState>) -> Router {
     Router::new()
         .route("/data-sets", post(create_data_set_handler).get(list_data_sets_handler))
         .route("/data-sets/{name}", get(get_data_set_handler).delete(delete_data_set_handler))
+        .merge(crate::healthcheck::router("dataexchange"))
         .with_state(state)
 }
 
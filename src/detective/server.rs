@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<DetectiveState>) -> Router {
     Router::new()
         .route("/graphs", post(create_graph_handler).get(list_graphs_handler))
         .route("/graphs/{name}", get(get_graph_handler).delete(delete_graph_handler))
+        .merge(crate::healthcheck::router("detective"))
         .with_state(state)
 }
 
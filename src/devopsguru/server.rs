@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<DevopsguruState>) -> Router {
     Router::new()
         .route("/insights", post(create_insight_handler).get(list_insights_handler))
         .route("/insights/{name}", get(get_insight_handler).delete(delete_insight_handler))
+        .merge(crate::healthcheck::router("devopsguru"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<DRSState>) -> Router {
     Router::new()
         .route("/source-servers", post(create_source_server_handler).get(list_source_servers_handler))
         .route("/source-servers/{name}", get(get_source_server_handler).delete(delete_source_server_handler))
+        .merge(crate::healthcheck::router("drs"))
         .with_state(state)
 }
 
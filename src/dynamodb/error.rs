@@ -1,6 +1,8 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
+use crate::pagination::InvalidToken;
 
 #[derive(Debug, Clone)]
 pub enum DynamoDbError {
@@ -43,13 +45,29 @@ impl DynamoDbError {
     }
 }
 
+impl From<InvalidToken> for DynamoDbError {
+    fn from(_: InvalidToken) -> Self {
+        DynamoDbError::ValidationException("Invalid NextToken value.".to_string())
+    }
+}
+
+impl AwsError for DynamoDbError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        DynamoDbError::message(self)
+    }
+}
+
 impl IntoResponse for DynamoDbError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": format!("com.amazonaws.dynamodb.v20120810#{}", self.error_code()),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::NamespacedJson("com.amazonaws.dynamodb.v20120810#"))
     }
 }
 
@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
 use axum::body::Bytes;
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::http::HeaderMap;
 use axum::response::IntoResponse;
-use axum::routing::post;
+use axum::routing::{get, post};
 use axum::{Json, Router};
 
 use super::error::DynamoDbError;
@@ -72,9 +72,32 @@ async fn handle_request(
     }
 }
 
+// GET /_debug/tables/{name}/items — dump a table as a fixture; not part of
+// the AWS API.
+async fn export_items(
+    State(state): State<Arc<DynamoDbState>>,
+    Path(name): Path<String>,
+) -> Result<axum::response::Response, DynamoDbError> {
+    Ok(Json(state.export_table(&name).await?).into_response())
+}
+
+// PUT /_debug/tables/{name}/items — bulk-load a fixture.
+async fn import_items(
+    State(state): State<Arc<DynamoDbState>>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Result<axum::response::Response, DynamoDbError> {
+    let fixture: TableFixture = serde_json::from_slice(&body)
+        .map_err(|e| DynamoDbError::SerializationException(e.to_string()))?;
+    let count = state.import_table(&name, fixture).await?;
+    Ok(Json(serde_json::json!({ "ItemCount": count })).into_response())
+}
+
 pub fn create_router(state: Arc<DynamoDbState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .route("/_debug/tables/{name}/items", get(export_items).put(import_items))
+        .merge(crate::healthcheck::router("dynamodb"))
         .with_state(state)
 }
 
@@ -157,4 +180,32 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_debug_items_round_trip() {
+        let state = Arc::new(DynamoDbState::new("123456789012".to_string(), "us-east-1".to_string()));
+        let app = create_router(state.clone());
+        let fixture = serde_json::json!({
+            "KeySchema": [{ "AttributeName": "pk", "KeyType": "HASH" }],
+            "AttributeDefinitions": [{ "AttributeName": "pk", "AttributeType": "S" }],
+            "Items": [{ "pk": { "S": "a" }, "n": { "N": "1" } }, { "pk": { "S": "b" } }]
+        });
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_debug/tables/fixtures/items")
+            .body(Body::from(fixture.to_string()))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+
+        let req = Request::builder().uri("/_debug/tables/fixtures/items").body(Body::empty()).unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let dump: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(dump["Items"].as_array().unwrap().len(), 2);
+        assert_eq!(dump["KeySchema"][0]["AttributeName"], "pk");
+
+        let req = Request::builder().uri("/_debug/tables/missing/items").body(Body::empty()).unwrap();
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::BAD_REQUEST);
+    }
 }
@@ -6,6 +6,9 @@ use serde_json::Value;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+use crate::aws_error::AwsError;
+use crate::pagination;
+
 use super::error::DynamoDbError;
 use super::table::Table;
 use super::types::*;
@@ -16,8 +19,13 @@ struct DynamoDbStateInner {
     region: String,
 }
 
+/// The most writes one `BatchWriteItem` call may carry.
+const MAX_BATCH_WRITE_ITEMS: usize = 25;
+const TAGS_PER_PAGE: usize = 10;
+
 pub struct DynamoDbState {
     inner: Arc<Mutex<DynamoDbStateInner>>,
+    enforce_throughput: bool,
 }
 
 impl DynamoDbState {
@@ -28,9 +36,35 @@ impl DynamoDbState {
                 account_id,
                 region,
             })),
+            enforce_throughput: false,
         }
     }
 
+    /// Limits each `BatchWriteItem` call to a provisioned table's
+    /// `WriteCapacityUnits` writes, returning the rest as unprocessed items.
+    pub fn with_throughput_enforcement(mut self, enforce: bool) -> Self {
+        self.enforce_throughput = enforce;
+        self
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// ARNs and tags of every table that carries at least one tag.
+    pub async fn tagged_resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let state = self.inner.lock().await;
+        state
+            .tables
+            .values()
+            .filter(|table| !table.tags.is_empty())
+            .map(|table| (table.table_arn.clone(), table.tags.clone()))
+            .collect()
+    }
+
     fn now_epoch() -> f64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -246,6 +280,7 @@ impl DynamoDbState {
                 )));
             }
         }
+        table.validate_item(&req.item)?;
 
         // Evaluate condition expression
         if let Some(ref cond_expr) = req.condition_expression {
@@ -259,7 +294,7 @@ impl DynamoDbState {
                 cond_expr,
                 req.expression_attribute_names.as_ref(),
                 req.expression_attribute_values.as_ref(),
-            ) {
+            )? {
                 return Err(DynamoDbError::ConditionalCheckFailedException(
                     "The conditional request failed".into(),
                 ));
@@ -335,7 +370,7 @@ impl DynamoDbState {
                 cond_expr,
                 req.expression_attribute_names.as_ref(),
                 req.expression_attribute_values.as_ref(),
-            ) {
+            )? {
                 return Err(DynamoDbError::ConditionalCheckFailedException(
                     "The conditional request failed".into(),
                 ));
@@ -364,6 +399,7 @@ impl DynamoDbState {
                 req.table_name
             ))
         })?;
+        table.validate_key(&req.key)?;
 
         // Evaluate condition expression
         if let Some(ref cond_expr) = req.condition_expression {
@@ -377,7 +413,7 @@ impl DynamoDbState {
                 cond_expr,
                 req.expression_attribute_names.as_ref(),
                 req.expression_attribute_values.as_ref(),
-            ) {
+            )? {
                 return Err(DynamoDbError::ConditionalCheckFailedException(
                     "The conditional request failed".into(),
                 ));
@@ -385,47 +421,31 @@ impl DynamoDbState {
         }
 
         let idx = table.find_item_index(&req.key);
-        let existed = idx.is_some();
+        let old_item = idx.map(|i| table.items[i].clone());
 
-        // For UpdateItem, if the item doesn't exist, create it from the key
-        let item_idx = match idx {
-            Some(i) => i,
-            None => {
-                let mut new_item = req.key.clone();
-                // Ensure hash and range keys are set
-                let hash_key = table.hash_key_name().to_string();
-                if !new_item.contains_key(&hash_key) {
-                    if let Some(v) = req.key.get(&hash_key) {
-                        new_item.insert(hash_key, v.clone());
-                    }
-                }
-                if let Some(rk) = table.range_key_name().map(|s| s.to_string()) {
-                    if !new_item.contains_key(&rk) {
-                        if let Some(v) = req.key.get(&rk) {
-                            new_item.insert(rk, v.clone());
-                        }
-                    }
-                }
-                table.items.push(new_item);
-                table.items.len() - 1
-            }
-        };
-
-        let old_item = if existed {
-            Some(table.items[item_idx].clone())
-        } else {
-            None
-        };
-
-        // Apply update expression
+        // For UpdateItem, if the item doesn't exist, create it from the key.
+        // The update is applied to a copy so that an update that fails or
+        // outgrows the item size limit leaves the table unchanged.
+        let mut new_item = old_item.clone().unwrap_or_else(|| req.key.clone());
         if let Some(ref expr) = req.update_expression {
             apply_update_expression(
-                &mut table.items[item_idx],
+                &mut new_item,
                 expr,
                 req.expression_attribute_names.as_ref(),
                 req.expression_attribute_values.as_ref(),
             )?;
         }
+        table.validate_item(&new_item)?;
+        let item_idx = match idx {
+            Some(i) => {
+                table.items[i] = new_item;
+                i
+            }
+            None => {
+                table.items.push(new_item);
+                table.items.len() - 1
+            }
+        };
 
         let attributes = match req.return_values.as_deref() {
             Some("ALL_NEW") => Some(table.items[item_idx].clone()),
@@ -526,17 +546,18 @@ impl DynamoDbState {
 
         // Apply filter expression
         if let Some(ref filter_expr) = req.filter_expression {
-            matched_items = matched_items
-                .into_iter()
-                .filter(|item| {
-                    evaluate_filter_expression(
-                        item,
-                        filter_expr,
-                        req.expression_attribute_names.as_ref(),
-                        req.expression_attribute_values.as_ref(),
-                    )
-                })
-                .collect();
+            let mut kept = Vec::with_capacity(matched_items.len());
+            for item in matched_items {
+                if evaluate_filter_expression(
+                    &item,
+                    filter_expr,
+                    req.expression_attribute_names.as_ref(),
+                    req.expression_attribute_values.as_ref(),
+                )? {
+                    kept.push(item);
+                }
+            }
+            matched_items = kept;
         }
 
         // Apply pagination
@@ -611,19 +632,18 @@ impl DynamoDbState {
 
         // Apply filter expression using iterators to avoid cloning all items
         let mut items: Vec<Item> = if let Some(ref filter_expr) = req.filter_expression {
-            table
-                .items
-                .iter()
-                .filter(|item| {
-                    evaluate_filter_expression(
-                        item,
-                        filter_expr,
-                        req.expression_attribute_names.as_ref(),
-                        req.expression_attribute_values.as_ref(),
-                    )
-                })
-                .cloned()
-                .collect()
+            let mut items = Vec::new();
+            for item in &table.items {
+                if evaluate_filter_expression(
+                    item,
+                    filter_expr,
+                    req.expression_attribute_names.as_ref(),
+                    req.expression_attribute_values.as_ref(),
+                )? {
+                    items.push(item.clone());
+                }
+            }
+            items
         } else {
             table.items.clone()
         };
@@ -723,39 +743,92 @@ impl DynamoDbState {
         &self,
         req: BatchWriteItemRequest,
     ) -> Result<BatchWriteItemResponse, DynamoDbError> {
+        let total: usize = req.request_items.values().map(Vec::len).sum();
+        if total == 0 {
+            return Err(DynamoDbError::ValidationException(
+                "1 validation error detected: Value at 'requestItems' failed to satisfy constraint: \
+                 Member must have length greater than or equal to 1"
+                    .into(),
+            ));
+        }
+        if total > MAX_BATCH_WRITE_ITEMS {
+            return Err(DynamoDbError::ValidationException(format!(
+                "1 validation error detected: Value at 'requestItems' failed to satisfy constraint: \
+                 Map value must satisfy constraint: Member must have length less than or equal to \
+                 {MAX_BATCH_WRITE_ITEMS}"
+            )));
+        }
+
         let mut inner = self.inner.lock().await;
 
+        // Validate the whole request before writing anything.
         for (table_name, write_requests) in &req.request_items {
-            let table = inner.tables.get_mut(table_name).ok_or_else(|| {
+            let table = inner.tables.get(table_name).ok_or_else(|| {
                 DynamoDbError::ResourceNotFoundException(format!(
                     "Requested resource not found: Table: {} not found",
                     table_name
                 ))
             })?;
-
+            let key_names: Vec<&str> =
+                table.key_schema.iter().map(|k| k.attribute_name.as_str()).collect();
+            let mut seen = std::collections::HashSet::new();
             for write_req in write_requests {
-                if let Some(ref put) = write_req.put_request {
-                    match table.find_item_index(&put.item) {
-                        Some(idx) => {
-                            table.items[idx] = put.item.clone();
-                        }
-                        None => {
-                            table.items.push(put.item.clone());
-                        }
+                let key_source = match (&write_req.put_request, &write_req.delete_request) {
+                    (Some(put), None) => &put.item,
+                    (None, Some(delete)) => &delete.key,
+                    _ => {
+                        return Err(DynamoDbError::ValidationException(
+                            "A write request must contain exactly one of PutRequest or DeleteRequest".into(),
+                        ))
                     }
+                };
+                if let Some(missing) = key_names.iter().find(|k| !key_source.contains_key(**k)) {
+                    return Err(DynamoDbError::ValidationException(format!(
+                        "One or more parameter values are not valid. Missing the key {missing} in the item"
+                    )));
+                }
+                match &write_req.put_request {
+                    Some(put) => table.validate_item(&put.item)?,
+                    None => table.validate_key(key_source)?,
+                }
+                if !seen.insert(table.build_primary_key(key_source)) {
+                    return Err(DynamoDbError::ValidationException(
+                        "Provided list of item keys contains duplicates".into(),
+                    ));
                 }
+            }
+        }
 
-                if let Some(ref delete) = write_req.delete_request {
+        let mut unprocessed_items = HashMap::new();
+        for (table_name, write_requests) in req.request_items {
+            let Some(table) = inner.tables.get_mut(&table_name) else {
+                continue;
+            };
+            let capacity = if self.enforce_throughput && table.billing_mode == "PROVISIONED" {
+                table.provisioned_throughput.write_capacity_units.max(0) as usize
+            } else {
+                usize::MAX
+            };
+            let mut write_requests = write_requests.into_iter();
+            for write_req in write_requests.by_ref().take(capacity) {
+                if let Some(put) = write_req.put_request {
+                    match table.find_item_index(&put.item) {
+                        Some(idx) => table.items[idx] = put.item,
+                        None => table.items.push(put.item),
+                    }
+                } else if let Some(delete) = write_req.delete_request {
                     if let Some(idx) = table.find_item_index(&delete.key) {
                         table.items.remove(idx);
                     }
                 }
             }
+            let rest: Vec<WriteRequest> = write_requests.collect();
+            if !rest.is_empty() {
+                unprocessed_items.insert(table_name, serde_json::to_value(rest).unwrap_or_default());
+            }
         }
 
-        Ok(BatchWriteItemResponse {
-            unprocessed_items: HashMap::new(),
-        })
+        Ok(BatchWriteItemResponse { unprocessed_items })
     }
 
     // --- Tag operations ---
@@ -792,7 +865,7 @@ impl DynamoDbState {
 
         let table = find_table_by_arn(&inner.tables, &req.resource_arn)?;
 
-        let tags: Vec<Tag> = table
+        let mut tags: Vec<Tag> = table
             .tags
             .iter()
             .map(|(k, v)| Tag {
@@ -800,12 +873,104 @@ impl DynamoDbState {
                 value: v.clone(),
             })
             .collect();
+        tags.sort_by(|a, b| a.key.cmp(&b.key));
 
-        Ok(ListTagsOfResourceResponse {
-            tags,
-            next_token: None,
+        let listing = format!("dynamodb:ListTagsOfResource:{}", req.resource_arn);
+        let (tags, next_token) =
+            pagination::paginate(tags, |t| t.key.as_str(), req.next_token.as_deref(), &listing, TAGS_PER_PAGE)?;
+
+        Ok(ListTagsOfResourceResponse { tags, next_token })
+    }
+
+    // --- Fixtures (non-AWS) ---
+
+    /// Dumps a table's definition and every item.
+    pub async fn export_table(&self, table_name: &str) -> Result<TableFixture, DynamoDbError> {
+        let inner = self.inner.lock().await;
+        let table = inner.tables.get(table_name).ok_or_else(|| {
+            DynamoDbError::ResourceNotFoundException(format!(
+                "Requested resource not found: Table: {table_name} not found"
+            ))
+        })?;
+        Ok(TableFixture {
+            key_schema: Some(table.key_schema.clone()),
+            attribute_definitions: Some(table.attribute_definitions.clone()),
+            billing_mode: Some(table.billing_mode.clone()),
+            items: table.items.clone(),
         })
     }
+
+    /// Bulk-loads items, creating the table from the fixture's definition
+    /// if it does not exist. Items replace any with the same primary key;
+    /// capacity is not consumed. Returns the number of items loaded.
+    pub async fn import_table(&self, table_name: &str, fixture: TableFixture) -> Result<usize, DynamoDbError> {
+        let exists = self.inner.lock().await.tables.contains_key(table_name);
+        if !exists {
+            let key_schema = fixture.key_schema.ok_or_else(|| {
+                DynamoDbError::ResourceNotFoundException(format!(
+                    "Requested resource not found: Table: {table_name} not found, and the fixture has no KeySchema"
+                ))
+            })?;
+            self.create_table(CreateTableRequest {
+                table_name: table_name.to_string(),
+                key_schema,
+                attribute_definitions: fixture.attribute_definitions.unwrap_or_default(),
+                billing_mode: fixture.billing_mode.or_else(|| Some("PAY_PER_REQUEST".to_string())),
+                ..Default::default()
+            })
+            .await?;
+        }
+
+        let mut inner = self.inner.lock().await;
+        let table = inner.tables.get_mut(table_name).ok_or_else(|| {
+            DynamoDbError::ResourceNotFoundException(format!(
+                "Requested resource not found: Table: {table_name} not found"
+            ))
+        })?;
+        let keys: Vec<String> =
+            table.key_schema.iter().map(|k| k.attribute_name.clone()).collect();
+        if let Some(missing) = fixture
+            .items
+            .iter()
+            .find_map(|item| keys.iter().find(|k| !item.contains_key(*k)))
+        {
+            return Err(DynamoDbError::ValidationException(format!(
+                "One or more parameter values are not valid. Missing the key {missing} in the item"
+            )));
+        }
+        let count = fixture.items.len();
+        for item in fixture.items {
+            match table.find_item_index(&item) {
+                Some(idx) => table.items[idx] = item,
+                None => table.items.push(item),
+            }
+        }
+        Ok(count)
+    }
+
+    /// Loads every `<table>.json` fixture in `dir`, in file name order.
+    pub async fn seed_from_dir(&self, dir: &std::path::Path) -> Result<(), String> {
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        let mut paths: Vec<_> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let Some(table_name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let contents = std::fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+            let fixture: TableFixture =
+                serde_json::from_slice(&contents).map_err(|e| format!("{}: {e}", path.display()))?;
+            let count = self
+                .import_table(table_name, fixture)
+                .await
+                .map_err(|e| format!("{}: {}", path.display(), AwsError::message(&e)))?;
+            tracing::info!(table = table_name, items = count, "seeded DynamoDB table");
+        }
+        Ok(())
+    }
 }
 
 // --- Helper functions ---
@@ -939,26 +1104,72 @@ fn parse_key_condition_expression(
 
 fn split_by_and(expr: &str) -> Vec<&str> {
     let mut parts = Vec::new();
-    let mut last = 0;
+    let mut rest = expr;
+    while let Some((start, end)) = find_keyword(rest, "AND") {
+        parts.push(&rest[..start]);
+        rest = &rest[end..];
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Finds a keyword such as `AND`, `OR` or `BETWEEN` outside parentheses,
+/// in any letter case and only as a whole word, so `#brand` or `android`
+/// never match `AND`. The `AND` that belongs to a `BETWEEN` is skipped.
+/// Returns the keyword's byte range.
+fn find_keyword(expr: &str, keyword: &str) -> Option<(usize, usize)> {
     let bytes = expr.as_bytes();
-    let len = bytes.len();
-
-    let mut i = 0;
-    while i < len {
-        // Check for " AND " (fully case-insensitive)
-        if i + 5 <= len {
-            let slice = &expr[i..i + 5];
-            if slice.to_lowercase() == " and " {
-                parts.push(&expr[last..i]);
-                last = i + 5;
-                i = last;
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'#' | b':' | b'.');
+    let word_at = |i: usize, word: &[u8]| {
+        bytes.len() >= i + word.len()
+            && bytes[i..i + word.len()].eq_ignore_ascii_case(word)
+            && bytes.get(i + word.len()).is_none_or(|&b| !is_word(b))
+    };
+    let mut depth = 0usize;
+    let mut in_between = false;
+    for i in 0..bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth > 0 || (i > 0 && is_word(bytes[i - 1])) {
+            continue;
+        }
+        if word_at(i, keyword.as_bytes()) {
+            if in_between && keyword.eq_ignore_ascii_case("AND") {
+                in_between = false;
                 continue;
             }
+            return Some((i, i + keyword.len()));
+        }
+        if word_at(i, b"BETWEEN") {
+            in_between = true;
         }
-        i += 1;
     }
-    parts.push(&expr[last..]);
-    parts
+    None
+}
+
+/// Returns the arguments of `expr` if it is exactly one call to `func_name`,
+/// e.g. `size(#tags)`.
+fn function_call<'a>(expr: &'a str, func_name: &str) -> Option<&'a str> {
+    let expr = expr.trim();
+    let rest = expr
+        .get(..func_name.len())
+        .filter(|name| name.eq_ignore_ascii_case(func_name))
+        .map(|_| expr[func_name.len()..].trim_start())?;
+    let inner = rest.strip_prefix('(')?.strip_suffix(')')?;
+    // Reject `f(a) = g(b)`, whose outer parentheses are not one group.
+    let mut depth = 0usize;
+    for b in inner.bytes() {
+        match b {
+            b'(' => depth += 1,
+            b')' if depth == 0 => return None,
+            b')' => depth -= 1,
+            _ => {}
+        }
+    }
+    Some(inner)
 }
 
 fn parse_single_condition(
@@ -1081,15 +1292,11 @@ fn extract_function_args<'a>(expr: &'a str, func_name: &str) -> Option<&'a str>
 }
 
 fn find_between<'a>(expr: &'a str) -> Option<(&'a str, &'a str, &'a str)> {
-    let lower = expr.to_lowercase();
-    let between_pos = lower.find(" between ")?;
-    let attr = &expr[..between_pos];
-    let rest = &expr[between_pos + 9..]; // " between " is 9 chars
-    let lower_rest = rest.to_lowercase();
-    let and_pos = lower_rest.find(" and ")?;
-    let val1 = &rest[..and_pos];
-    let val2 = &rest[and_pos + 5..];
-    Some((attr, val1, val2))
+    let (start, end) = find_keyword(expr, "BETWEEN")?;
+    let attr = &expr[..start];
+    let rest = &expr[end..];
+    let (and_start, and_end) = find_keyword(rest, "AND")?;
+    Some((attr, &rest[..and_start], &rest[and_end..]))
 }
 
 fn evaluate_key_conditions(item: &Item, conditions: &[KeyCondition]) -> bool {
@@ -1219,161 +1426,153 @@ fn evaluate_filter_expression(
     expr: &str,
     names: Option<&HashMap<String, String>>,
     values: Option<&HashMap<String, Value>>,
-) -> bool {
+) -> Result<bool, DynamoDbError> {
     // Support basic comparison expressions: attr = val, attr <> val, attr < val, etc.
-    // Also support AND / OR and NOT, attribute_exists(), attribute_not_exists(), begins_with()
+    // Also support AND / OR and NOT, IN, attribute_exists(), attribute_not_exists(),
+    // attribute_type(), begins_with(), contains() and size()
 
     // Parse and evaluate recursively
     evaluate_expr(item, expr.trim(), names, values)
 }
 
+/// Most operands an `IN` list may hold.
+const MAX_IN_OPERANDS: usize = 100;
+
+/// The type descriptors accepted by `attribute_type()`.
+const ATTRIBUTE_TYPES: [&str; 10] = ["S", "SS", "N", "NS", "B", "BS", "BOOL", "NULL", "L", "M"];
+
 fn evaluate_expr(
     item: &Item,
     expr: &str,
     names: Option<&HashMap<String, String>>,
     values: Option<&HashMap<String, Value>>,
-) -> bool {
+) -> Result<bool, DynamoDbError> {
     let expr = expr.trim();
 
-    // Handle OR (lower precedence than AND)
-    if let Some((left, right)) = split_logical_op(expr, " OR ", " or ") {
-        return evaluate_expr(item, left, names, values)
-            || evaluate_expr(item, right, names, values);
+    // Handle OR (lower precedence than AND). Both sides are evaluated so a
+    // malformed operand is reported whatever the other side returns.
+    if let Some((left, right)) = split_logical_op(expr, "OR") {
+        let left = evaluate_expr(item, left, names, values)?;
+        let right = evaluate_expr(item, right, names, values)?;
+        return Ok(left || right);
     }
 
     // Handle AND
-    if let Some((left, right)) = split_logical_op(expr, " AND ", " and ") {
-        return evaluate_expr(item, left, names, values)
-            && evaluate_expr(item, right, names, values);
+    if let Some((left, right)) = split_logical_op(expr, "AND") {
+        let left = evaluate_expr(item, left, names, values)?;
+        let right = evaluate_expr(item, right, names, values)?;
+        return Ok(left && right);
     }
 
     // Handle NOT
-    let lower = expr.to_lowercase();
-    if lower.starts_with("not ") {
-        return !evaluate_expr(item, &expr[4..], names, values);
+    if expr.get(..3).is_some_and(|kw| kw.eq_ignore_ascii_case("not"))
+        && expr[3..].starts_with(|c: char| c.is_whitespace() || c == '(')
+    {
+        return Ok(!evaluate_expr(item, &expr[3..], names, values)?);
     }
 
     // Handle parenthesized expressions
-    if expr.starts_with('(') && expr.ends_with(')') {
-        return evaluate_expr(item, &expr[1..expr.len() - 1], names, values);
+    if let Some(inner) = function_call(expr, "") {
+        return evaluate_expr(item, inner, names, values);
     }
 
     // Handle functions
-    if let Some(inner) = extract_function_args(expr, "attribute_exists") {
+    if let Some(inner) = function_call(expr, "attribute_exists") {
         let attr = resolve_name(inner.trim(), names);
-        return item.contains_key(&attr);
+        return Ok(item.contains_key(&attr));
     }
 
-    if let Some(inner) = extract_function_args(expr, "attribute_not_exists") {
+    if let Some(inner) = function_call(expr, "attribute_not_exists") {
         let attr = resolve_name(inner.trim(), names);
-        return !item.contains_key(&attr);
+        return Ok(!item.contains_key(&attr));
     }
 
-    if let Some(inner) = extract_function_args(expr, "begins_with") {
-        let args: Vec<&str> = inner.splitn(2, ',').collect();
-        if args.len() == 2 {
-            let attr = resolve_name(args[0].trim(), names);
-            let val = resolve_value(args[1].trim(), values);
-            if let Some(item_val) = item.get(&attr) {
-                if let Some(cmp_val) = val {
-                    let item_str = extract_string_value(item_val).unwrap_or_default();
-                    let prefix_str = extract_string_value(cmp_val).unwrap_or_default();
-                    return item_str.starts_with(&prefix_str);
-                }
-            }
-            return false;
+    if let Some(inner) = function_call(expr, "attribute_type") {
+        if let Some((attr, type_token)) = inner.split_once(',') {
+            let expected = resolve_operand(item, type_token, names, values)
+                .as_ref()
+                .and_then(extract_string_value)
+                .filter(|t| ATTRIBUTE_TYPES.contains(&t.as_str()))
+                .ok_or_else(|| {
+                    DynamoDbError::ValidationException(format!(
+                        "Invalid expression: Invalid type for attribute_type: {}",
+                        type_token.trim()
+                    ))
+                })?;
+            let item_val = resolve_operand(item, attr, names, values);
+            return Ok(item_val
+                .as_ref()
+                .and_then(|v| v.as_object())
+                .is_some_and(|v| v.contains_key(&expected)));
         }
     }
 
-    if let Some(inner) = extract_function_args(expr, "contains") {
-        let args: Vec<&str> = inner.splitn(2, ',').collect();
-        if args.len() == 2 {
-            let attr = resolve_name(args[0].trim(), names);
-            let val = resolve_value(args[1].trim(), values);
-            if let Some(item_val) = item.get(&attr) {
-                if let Some(cmp_val) = val {
-                    let item_str = extract_string_value(item_val).unwrap_or_default();
-                    let search_str = extract_string_value(cmp_val).unwrap_or_default();
-                    return item_str.contains(&search_str);
-                }
+    if let Some(inner) = function_call(expr, "begins_with") {
+        if let Some((attr, prefix)) = inner.split_once(',') {
+            let item_val = resolve_operand(item, attr, names, values);
+            let prefix = resolve_operand(item, prefix, names, values);
+            if let (Some(item_val), Some(prefix)) = (item_val, prefix) {
+                let item_str = extract_string_value(&item_val).unwrap_or_default();
+                let prefix_str = extract_string_value(&prefix).unwrap_or_default();
+                return Ok(item_str.starts_with(&prefix_str));
             }
-            return false;
-        }
-    }
-
-    // Handle size(attr) comparisons: size(attr) op val
-    if let Some(inner) = extract_function_args(expr, "size") {
-        // expr is something like "size(attr) >= :val" but extract_function_args matched the size(...) part.
-        // We need to re-parse from the full expr to get the operator and value.
-        // Find the closing paren of size(...)
-        let lower = expr.to_lowercase();
-        if let Some(size_start) = lower.find("size(") {
-            let after_size = &expr[size_start + 5..];
-            // Find matching close paren
-            let mut depth = 1;
-            let mut close_pos = None;
-            for (j, ch) in after_size.char_indices() {
-                match ch {
-                    '(' => depth += 1,
-                    ')' => {
-                        depth -= 1;
-                        if depth == 0 {
-                            close_pos = Some(size_start + 5 + j + 1);
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if let Some(cp) = close_pos {
-                let remainder = expr[cp..].trim();
-                let size_operators: &[(&str, fn(i64, i64) -> bool)] = &[
-                    ("<>", |a, b| a != b),
-                    ("<=", |a, b| a <= b),
-                    (">=", |a, b| a >= b),
-                    ("=", |a, b| a == b),
-                    ("<", |a, b| a < b),
-                    (">", |a, b| a > b),
-                ];
-                for (op_str, op_fn) in size_operators {
-                    if remainder.starts_with(op_str) {
-                        let val_str = remainder[op_str.len()..].trim();
-                        let attr = resolve_name(inner.trim(), names);
-                        let attr_size = if let Some(item_val) = item.get(&attr) {
-                            attribute_size(item_val)
-                        } else {
-                            0
-                        };
-                        // The comparison value should be a number
-                        if let Some(cmp_val) = resolve_value(val_str, values) {
-                            if let Some(n_str) = cmp_val.get("N").and_then(|v| v.as_str()) {
-                                if let Ok(n) = n_str.parse::<i64>() {
-                                    return op_fn(attr_size, n);
-                                }
-                            }
-                        }
-                        return false;
-                    }
-                }
+            return Ok(false);
+        }
+    }
+
+    if let Some(inner) = function_call(expr, "contains") {
+        if let Some((attr, operand)) = inner.split_once(',') {
+            let item_val = resolve_operand(item, attr, names, values);
+            let operand = resolve_operand(item, operand, names, values);
+            if let (Some(item_val), Some(operand)) = (item_val, operand) {
+                return Ok(attribute_contains(&item_val, &operand));
             }
+            return Ok(false);
         }
     }
 
+    // Handle IN: operand IN (val1, val2, ...)
+    if let Some((start, end)) = find_keyword(expr, "IN") {
+        let list = function_call(&expr[end..], "").ok_or_else(|| {
+            DynamoDbError::ValidationException(format!(
+                "Invalid expression: Syntax error; token: \"{}\"",
+                expr[end..].trim()
+            ))
+        })?;
+        let candidates: Vec<&str> = list.split(',').map(str::trim).collect();
+        if candidates.iter().any(|c| c.is_empty()) {
+            return Err(DynamoDbError::ValidationException(format!(
+                "Invalid expression: Syntax error; token: \"({list})\""
+            )));
+        }
+        if candidates.len() > MAX_IN_OPERANDS {
+            return Err(DynamoDbError::ValidationException(format!(
+                "Invalid expression: The IN operator is provided with too many operands; number of operands: {}",
+                candidates.len()
+            )));
+        }
+        let Some(item_val) = resolve_operand(item, &expr[..start], names, values) else {
+            return Ok(false);
+        };
+        return Ok(candidates.iter().any(|c| {
+            resolve_operand(item, c, names, values).is_some_and(|v| attribute_value_equals(&item_val, &v))
+        }));
+    }
+
     // Handle BETWEEN: attr BETWEEN val1 AND val2
     if let Some((attr_str, val1_str, val2_str)) = find_between(expr) {
-        let attr = resolve_name(attr_str.trim(), names);
-        if let Some(item_val) = item.get(&attr) {
+        if let Some(item_val) = resolve_operand(item, attr_str, names, values) {
             if let (Some(low), Some(high)) = (
-                resolve_value(val1_str.trim(), values),
-                resolve_value(val2_str.trim(), values),
+                resolve_operand(item, val1_str, names, values),
+                resolve_operand(item, val2_str, names, values),
             ) {
-                let cmp_low = compare_attribute_values(Some(item_val), Some(low));
-                let cmp_high = compare_attribute_values(Some(item_val), Some(high));
-                return matches!(cmp_low, std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
-                    && matches!(cmp_high, std::cmp::Ordering::Less | std::cmp::Ordering::Equal);
+                let cmp_low = compare_attribute_values(Some(&item_val), Some(&low));
+                let cmp_high = compare_attribute_values(Some(&item_val), Some(&high));
+                return Ok(matches!(cmp_low, std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
+                    && matches!(cmp_high, std::cmp::Ordering::Less | std::cmp::Ordering::Equal));
             }
         }
-        return false;
+        return Ok(false);
     }
 
     // Simple comparison operators
@@ -1402,21 +1601,20 @@ fn evaluate_expr(
 
     for (op_str, op_fn) in operators {
         if let Some(pos) = find_operator_pos(expr, op_str) {
-            let attr_str = expr[..pos].trim();
-            let val_str = expr[pos + op_str.len()..].trim();
-
-            let attr = resolve_name(attr_str, names);
-            if let Some(item_val) = item.get(&attr) {
-                if let Some(cmp_val) = resolve_value(val_str, values) {
-                    return op_fn(item_val, cmp_val);
-                }
+            let left = resolve_operand(item, &expr[..pos], names, values);
+            let right = resolve_operand(item, &expr[pos + op_str.len()..], names, values);
+            if let (Some(left), Some(right)) = (left, right) {
+                return Ok(op_fn(&left, &right));
             }
-            return false;
+            return Ok(false);
         }
     }
 
-    // Default: if we can't parse, reject the item to avoid silent data leaks
-    false
+    // Anything else is malformed; matching or skipping every item would
+    // silently return wrong results.
+    Err(DynamoDbError::ValidationException(format!(
+        "Invalid expression: Syntax error; token: \"{expr}\""
+    )))
 }
 
 fn find_operator_pos(expr: &str, op: &str) -> Option<usize> {
@@ -1444,38 +1642,48 @@ fn find_operator_pos(expr: &str, op: &str) -> Option<usize> {
     None
 }
 
-fn split_logical_op<'a>(
-    expr: &'a str,
-    op_upper: &str,
-    _op_lower: &str,
-) -> Option<(&'a str, &'a str)> {
-    let mut paren_depth = 0;
-    let bytes = expr.as_bytes();
-    let op_len = op_upper.len();
-    let op_upper_lower = op_upper.to_lowercase();
+fn split_logical_op<'a>(expr: &'a str, keyword: &str) -> Option<(&'a str, &'a str)> {
+    let (start, end) = find_keyword(expr, keyword)?;
+    Some((&expr[..start], &expr[end..]))
+}
 
-    if expr.len() < op_len {
-        return None;
+/// Resolves one side of a comparison: a `:value` placeholder, `size(path)`,
+/// or an attribute path (possibly via `#name` placeholders). Returns `None`
+/// if the attribute is absent.
+fn resolve_operand(
+    item: &Item,
+    token: &str,
+    names: Option<&HashMap<String, String>>,
+    values: Option<&HashMap<String, Value>>,
+) -> Option<Value> {
+    let token = token.trim();
+    if let Some(inner) = function_call(token, "size") {
+        let attr = resolve_name(inner.trim(), names);
+        return item
+            .get(&attr)
+            .map(|v| serde_json::json!({ "N": attribute_size(v).to_string() }));
     }
+    if token.starts_with(':') {
+        return resolve_value(token, values).cloned();
+    }
+    item.get(&resolve_name(token, names)).cloned()
+}
 
-    for i in 0..=(expr.len() - op_len) {
-        match bytes[i] {
-            b'(' => paren_depth += 1,
-            b')' => {
-                if paren_depth > 0 {
-                    paren_depth -= 1;
-                }
-            }
-            _ => {}
-        }
-        if paren_depth == 0 && i + op_len <= expr.len() {
-            let slice = &expr[i..i + op_len];
-            if slice.to_lowercase() == op_upper_lower {
-                return Some((&expr[..i], &expr[i + op_len..]));
-            }
+/// `contains()`: a substring of a string, or a member of a set or list.
+fn attribute_contains(haystack: &Value, needle: &Value) -> bool {
+    if let Some(s) = haystack.get("S").and_then(|v| v.as_str()) {
+        return extract_string_value(needle).is_some_and(|n| s.contains(&n));
+    }
+    for set in ["SS", "NS", "BS"] {
+        if let Some(members) = haystack.get(set).and_then(|v| v.as_array()) {
+            let needle = extract_string_value(needle).unwrap_or_default();
+            return members.iter().any(|m| m.as_str() == Some(needle.as_str()));
         }
     }
-    None
+    if let Some(list) = haystack.get("L").and_then(|v| v.as_array()) {
+        return list.contains(needle);
+    }
+    false
 }
 
 // --- UpdateExpression parsing ---
@@ -1942,6 +2150,22 @@ mod tests {
         assert_eq!(result.unwrap().table.table_name, "desc");
     }
 
+    #[tokio::test]
+    async fn test_describe_table_reports_item_count_and_size() {
+        let state = make_state();
+        state.create_table(make_create_table_req("counted")).await.unwrap();
+        for n in 0..3 {
+            let mut item = HashMap::new();
+            item.insert("pk".to_string(), serde_json::json!({"S": format!("key{n}")}));
+            item.insert("n".to_string(), serde_json::json!({"N": "12345"}));
+            state.put_item(PutItemRequest { table_name: "counted".to_string(), item, ..Default::default() }).await.unwrap();
+        }
+        let table = state.describe_table(DescribeTableRequest { table_name: "counted".to_string() }).await.unwrap().table;
+        assert_eq!(table.item_count, 3);
+        // "pk" + "keyN" = 6 bytes, "n" + five significant digits = 1 + 4 bytes.
+        assert_eq!(table.table_size_bytes, 3 * 11);
+    }
+
     #[tokio::test]
     async fn test_describe_table_not_found() {
         let state = make_state();
@@ -2036,6 +2260,70 @@ mod tests {
         assert!(state.put_item(req).await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_oversized_items_are_rejected() {
+        let state = make_state();
+        state.create_table(make_create_table_req("items")).await.unwrap();
+        let item = |pk: &str, data: String| {
+            HashMap::from([
+                ("pk".to_string(), serde_json::json!({"S": pk})),
+                ("data".to_string(), serde_json::json!({"S": data})),
+            ])
+        };
+        let put = |item| state.put_item(PutItemRequest { table_name: "items".to_string(), item, ..Default::default() });
+
+        let err = put(item("big", "x".repeat(crate::dynamodb::table::MAX_ITEM_SIZE))).await.unwrap_err();
+        assert!(matches!(&err, DynamoDbError::ValidationException(m) if m.contains("Item size has exceeded")));
+        put(item("small", "x".repeat(1000))).await.unwrap();
+
+        // An update that would outgrow the limit leaves the item unchanged.
+        let result = state.update_item(UpdateItemRequest {
+            table_name: "items".to_string(),
+            key: HashMap::from([("pk".to_string(), serde_json::json!({"S": "small"}))]),
+            update_expression: Some("SET #d = :d".to_string()),
+            expression_attribute_names: Some(HashMap::from([("#d".to_string(), "data".to_string())])),
+            expression_attribute_values: Some(HashMap::from([(
+                ":d".to_string(),
+                serde_json::json!({"S": "x".repeat(crate::dynamodb::table::MAX_ITEM_SIZE)}),
+            )])),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(DynamoDbError::ValidationException(_))));
+        let stored = state.get_item(GetItemRequest {
+            table_name: "items".to_string(),
+            key: HashMap::from([("pk".to_string(), serde_json::json!({"S": "small"}))]),
+            ..Default::default()
+        }).await.unwrap().item.unwrap();
+        assert_eq!(stored["data"]["S"].as_str().unwrap().len(), 1000);
+
+        let err = put(item(&"k".repeat(2049), String::new())).await.unwrap_err();
+        assert!(matches!(&err, DynamoDbError::ValidationException(m) if m.contains("Size of hashkey")));
+    }
+
+    #[tokio::test]
+    async fn test_empty_partition_key_string_is_rejected() {
+        let state = make_state();
+        state.create_table(make_create_table_req("items")).await.unwrap();
+        let empty_key = HashMap::from([("pk".to_string(), serde_json::json!({"S": ""}))]);
+
+        let err = state.put_item(PutItemRequest {
+            table_name: "items".to_string(),
+            item: empty_key.clone(),
+            ..Default::default()
+        }).await.unwrap_err();
+        assert!(matches!(&err, DynamoDbError::ValidationException(m) if m.contains("empty string value. Key: pk")));
+
+        let err = state.batch_write_item(BatchWriteItemRequest {
+            request_items: HashMap::from([(
+                "items".to_string(),
+                vec![WriteRequest { put_request: Some(PutRequest { item: empty_key }), delete_request: None }],
+            )]),
+        }).await.unwrap_err();
+        assert!(matches!(err, DynamoDbError::ValidationException(_)));
+        let scanned = state.scan(ScanRequest { table_name: "items".to_string(), ..Default::default() }).await.unwrap();
+        assert_eq!(scanned.count, 0);
+    }
+
     #[tokio::test]
     async fn test_put_item_return_old() {
         let state = make_state();
@@ -2226,7 +2514,7 @@ mod tests {
             tags: vec![Tag { key: "env".to_string(), value: "test".to_string() }],
         }).await.unwrap();
 
-        let tags = state.list_tags_of_resource(ListTagsOfResourceRequest { resource_arn: arn.clone() }).await.unwrap();
+        let tags = state.list_tags_of_resource(ListTagsOfResourceRequest { resource_arn: arn.clone(), next_token: None }).await.unwrap();
         assert_eq!(tags.tags.len(), 1);
 
         state.untag_resource(UntagResourceRequest {
@@ -2234,7 +2522,7 @@ mod tests {
             tag_keys: vec!["env".to_string()],
         }).await.unwrap();
 
-        let tags = state.list_tags_of_resource(ListTagsOfResourceRequest { resource_arn: arn }).await.unwrap();
+        let tags = state.list_tags_of_resource(ListTagsOfResourceRequest { resource_arn: arn, next_token: None }).await.unwrap();
         assert!(tags.tags.is_empty());
     }
 
@@ -2619,6 +2907,74 @@ mod tests {
         assert_eq!(scan.count, 1);
     }
 
+    fn put_pk(pk: &str) -> WriteRequest {
+        let item = HashMap::from([("pk".to_string(), serde_json::json!({"S": pk}))]);
+        WriteRequest { put_request: Some(PutRequest { item }), delete_request: None }
+    }
+
+    #[tokio::test]
+    async fn test_batch_write_item_limit() {
+        let state = make_state();
+        state.create_table(make_create_table_req("bw25")).await.unwrap();
+        state.create_table(make_create_table_req("bw25b")).await.unwrap();
+
+        let writes = |n: usize, prefix: &str| (0..n).map(|i| put_pk(&format!("{prefix}{i}"))).collect::<Vec<_>>();
+        let request_items = HashMap::from([("bw25".to_string(), writes(25, "k"))]);
+        state.batch_write_item(BatchWriteItemRequest { request_items }).await.unwrap();
+
+        // The limit counts writes across every table in the request.
+        let request_items =
+            HashMap::from([("bw25".to_string(), writes(20, "x")), ("bw25b".to_string(), writes(6, "y"))]);
+        let result = state.batch_write_item(BatchWriteItemRequest { request_items }).await;
+        assert!(matches!(result, Err(DynamoDbError::ValidationException(_))));
+        let scan = state.scan(ScanRequest { table_name: "bw25b".to_string(), ..Default::default() }).await.unwrap();
+        assert_eq!(scan.count, 0);
+
+        let request_items = HashMap::new();
+        let result = state.batch_write_item(BatchWriteItemRequest { request_items }).await;
+        assert!(matches!(result, Err(DynamoDbError::ValidationException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_batch_write_rejects_duplicate_keys() {
+        let state = make_state();
+        state.create_table(make_create_table_req("bwdup")).await.unwrap();
+        let delete_k1 = WriteRequest {
+            put_request: None,
+            delete_request: Some(DeleteRequest {
+                key: HashMap::from([("pk".to_string(), serde_json::json!({"S": "k1"}))]),
+            }),
+        };
+        for writes in [vec![put_pk("k1"), put_pk("k2"), put_pk("k1")], vec![put_pk("k1"), delete_k1]] {
+            let request_items = HashMap::from([("bwdup".to_string(), writes)]);
+            let result = state.batch_write_item(BatchWriteItemRequest { request_items }).await;
+            match result {
+                Err(DynamoDbError::ValidationException(msg)) => assert!(msg.contains("duplicates"), "{msg}"),
+                other => panic!("expected ValidationException, got {other:?}"),
+            }
+        }
+        let scan = state.scan(ScanRequest { table_name: "bwdup".to_string(), ..Default::default() }).await.unwrap();
+        assert_eq!(scan.count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_write_returns_unprocessed_items_over_capacity() {
+        let state = make_state().with_throughput_enforcement(true);
+        let mut req = make_create_table_req("bwcap");
+        req.billing_mode = Some("PROVISIONED".to_string());
+        req.provisioned_throughput = Some(ProvisionedThroughput { read_capacity_units: 5, write_capacity_units: 3 });
+        state.create_table(req).await.unwrap();
+
+        let writes = (0..5).map(|i| put_pk(&format!("k{i}"))).collect();
+        let request_items = HashMap::from([("bwcap".to_string(), writes)]);
+        let result = state.batch_write_item(BatchWriteItemRequest { request_items }).await.unwrap();
+        let unprocessed = result.unprocessed_items["bwcap"].as_array().unwrap();
+        assert_eq!(unprocessed.len(), 2);
+        assert_eq!(unprocessed[0]["PutRequest"]["Item"]["pk"]["S"], "k3");
+        let scan = state.scan(ScanRequest { table_name: "bwcap".to_string(), ..Default::default() }).await.unwrap();
+        assert_eq!(scan.count, 3);
+    }
+
     #[tokio::test]
     async fn test_batch_write_table_not_found() {
         let state = make_state();
@@ -2760,6 +3116,7 @@ mod tests {
         let state = make_state();
         let result = state.list_tags_of_resource(ListTagsOfResourceRequest {
             resource_arn: "arn:aws:dynamodb:us-east-1:123456789012:table/nope".to_string(),
+            next_token: None,
         }).await;
         assert!(result.is_err());
     }
@@ -2831,4 +3188,192 @@ mod tests {
         assert_eq!(result.responses.get("t1").unwrap().len(), 1);
         assert_eq!(result.responses.get("t2").unwrap().len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_seed_from_dir_creates_table_and_items() {
+        let dir = std::env::temp_dir().join(format!("dynamodb-seed-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let fixture = serde_json::json!({
+            "KeySchema": [
+                { "AttributeName": "pk", "KeyType": "HASH" },
+                { "AttributeName": "sk", "KeyType": "RANGE" }
+            ],
+            "AttributeDefinitions": [
+                { "AttributeName": "pk", "AttributeType": "S" },
+                { "AttributeName": "sk", "AttributeType": "N" }
+            ],
+            "Items": [
+                { "pk": { "S": "user#1" }, "sk": { "N": "1" }, "name": { "S": "Ada" } },
+                { "pk": { "S": "user#1" }, "sk": { "N": "2" }, "name": { "S": "Grace" } }
+            ]
+        });
+        std::fs::write(dir.join("users.json"), fixture.to_string()).unwrap();
+        std::fs::write(dir.join("README.txt"), "not a fixture").unwrap();
+
+        let state = make_state();
+        state.seed_from_dir(&dir).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let key: Item = serde_json::from_value(serde_json::json!({ "pk": { "S": "user#1" }, "sk": { "N": "2" } })).unwrap();
+        let resp = state
+            .get_item(GetItemRequest { table_name: "users".to_string(), key, ..Default::default() })
+            .await
+            .unwrap();
+        let name: Item = serde_json::from_value(serde_json::json!({ "name": { "S": "Grace" } })).unwrap();
+        assert_eq!(resp.item.unwrap().get("name"), name.get("name"));
+
+        // Loading into an existing table needs no definition, and an item
+        // missing its key is rejected.
+        let fixture: TableFixture =
+            serde_json::from_value(serde_json::json!({ "Items": [{ "sk": { "N": "3" } }] })).unwrap();
+        assert!(matches!(
+            state.import_table("users", fixture).await,
+            Err(DynamoDbError::ValidationException(_))
+        ));
+        let fixture: TableFixture = serde_json::from_value(serde_json::json!({ "Items": [] })).unwrap();
+        assert!(matches!(
+            state.import_table("other", fixture).await,
+            Err(DynamoDbError::ResourceNotFoundException(_))
+        ));
+    }
+
+    fn filter_item() -> Item {
+        serde_json::from_value(serde_json::json!({
+            "pk": { "S": "device#1" },
+            "android": { "S": "14" },
+            "brand": { "S": "pixel" },
+            "tags": { "SS": ["a", "b", "c"] },
+            "parts": { "L": [{ "S": "x" }, { "S": "y" }] },
+            "n": { "N": "5" }
+        }))
+        .unwrap()
+    }
+
+    fn filter_values() -> HashMap<String, Value> {
+        serde_json::from_value(serde_json::json!({
+            ":v": { "S": "14" },
+            ":b": { "S": "pixel" },
+            ":two": { "N": "2" },
+            ":lo": { "N": "1" },
+            ":hi": { "N": "9" },
+            ":tag": { "S": "b" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_split_by_and_is_case_insensitive() {
+        assert_eq!(split_by_and("pk = :p AnD sk > :s"), vec!["pk = :p ", " sk > :s"]);
+        assert_eq!(split_by_and("pk = :p and\tsk > :s").len(), 2);
+        // The AND of a BETWEEN is part of that condition.
+        assert_eq!(split_by_and("pk = :p AND sk BETWEEN :a aNd :b"), vec!["pk = :p ", " sk BETWEEN :a aNd :b"]);
+    }
+
+    #[test]
+    fn test_filter_mixed_case_logical_operators() {
+        let item = filter_item();
+        let values = filter_values();
+        assert!(evaluate_filter_expression(&item, "android = :v AnD brand = :b", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "android = :b oR brand = :b", None, Some(&values)).unwrap());
+        assert!(!evaluate_filter_expression(&item, "Not brand = :b", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "n BeTwEeN :lo AND :hi and brand = :b", None, Some(&values)).unwrap());
+    }
+
+    #[test]
+    fn test_filter_attribute_names_containing_keywords() {
+        let item = filter_item();
+        let values = filter_values();
+        // `android` and `brand` contain "and" but are not the AND keyword.
+        assert!(evaluate_filter_expression(&item, "android = :v", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "brand = :b", None, Some(&values)).unwrap());
+        assert!(!evaluate_filter_expression(&item, "android = :b", None, Some(&values)).unwrap());
+        let names = HashMap::from([("#and".to_string(), "android".to_string())]);
+        assert!(evaluate_filter_expression(&item, "#and = :v and brand = :b", Some(&names), Some(&values)).unwrap());
+    }
+
+    #[test]
+    fn test_filter_size_function() {
+        let item = filter_item();
+        let values = filter_values();
+        assert!(evaluate_filter_expression(&item, "size(tags) > :two", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "SIZE(parts) = :two", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "size(brand) > size(parts)", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, ":two < size(tags)", None, Some(&values)).unwrap());
+        assert!(!evaluate_filter_expression(&item, "size(missing) > :two", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "contains(tags, :tag) AND size(tags) <= :hi", None, Some(&values)).unwrap());
+    }
+
+    #[test]
+    fn test_filter_in_operator() {
+        let item = filter_item();
+        let values = filter_values();
+        assert!(evaluate_filter_expression(&item, "brand IN (:v, :b)", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "n in (:two,:lo,:hi) OR brand IN (:b)", None, Some(&values)).unwrap());
+        assert!(!evaluate_filter_expression(&item, "android IN (:b, :tag)", None, Some(&values)).unwrap());
+        assert!(!evaluate_filter_expression(&item, "missing IN (:b)", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "NOT brand IN (:v)", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "size(parts) IN (:two)", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "brand IN :b", None, Some(&values)).is_err());
+        assert!(evaluate_filter_expression(&item, "brand IN (:b, )", None, Some(&values)).is_err());
+    }
+
+    #[test]
+    fn test_filter_attribute_type_function() {
+        let item = filter_item();
+        let values: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            ":ss": { "S": "SS" },
+            ":s": { "S": "S" },
+            ":n": { "S": "N" },
+            ":bad": { "S": "STRING" }
+        }))
+        .unwrap();
+        let names = HashMap::from([("#t".to_string(), "tags".to_string())]);
+        assert!(evaluate_filter_expression(&item, "attribute_type(#t, :ss)", Some(&names), Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "attribute_type(n, :n) AND attribute_type(brand, :s)", None, Some(&values)).unwrap());
+        assert!(!evaluate_filter_expression(&item, "attribute_type(brand, :n)", None, Some(&values)).unwrap());
+        assert!(!evaluate_filter_expression(&item, "attribute_type(missing, :s)", None, Some(&values)).unwrap());
+        assert!(matches!(
+            evaluate_filter_expression(&item, "attribute_type(brand, :bad)", None, Some(&values)),
+            Err(DynamoDbError::ValidationException(_))
+        ));
+    }
+
+    #[test]
+    fn test_filter_size_comparisons() {
+        let item = filter_item();
+        let values = filter_values();
+        assert!(evaluate_filter_expression(&item, "size(tags) <> :two AND size(parts) >= :two", None, Some(&values)).unwrap());
+        assert!(evaluate_filter_expression(&item, "size(brand) BETWEEN :lo AND :hi", None, Some(&values)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_filter_is_validation_error() {
+        let item = filter_item();
+        let values = filter_values();
+        // Malformed on either side of AND/OR is reported, not short-circuited.
+        for expr in ["brand", "brand LIKE :b", "brand = :b OR nonsense", "attribute_exists(pk) AND begins_with(brand)"] {
+            assert!(
+                matches!(evaluate_filter_expression(&item, expr, None, Some(&values)), Err(DynamoDbError::ValidationException(_))),
+                "{expr}"
+            );
+        }
+
+        let state = make_state();
+        state.create_table(make_create_table_req("items")).await.unwrap();
+        let item = HashMap::from([("pk".to_string(), serde_json::json!({"S": "k1"}))]);
+        state.put_item(PutItemRequest { table_name: "items".to_string(), item: item.clone(), ..Default::default() }).await.unwrap();
+        let req = ScanRequest {
+            table_name: "items".to_string(),
+            filter_expression: Some("pk CONTAINS k".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(state.scan(req).await, Err(DynamoDbError::ValidationException(_))));
+        let req = PutItemRequest {
+            table_name: "items".to_string(),
+            item,
+            condition_expression: Some("pk is missing".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(state.put_item(req).await, Err(DynamoDbError::ValidationException(_))));
+    }
 }
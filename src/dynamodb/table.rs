@@ -1,7 +1,16 @@
 use std::collections::HashMap;
 
+use base64::Engine;
+use serde_json::Value;
+
+use super::error::DynamoDbError;
 use super::types::*;
 
+/// Largest item DynamoDB stores, by [`item_size`].
+pub const MAX_ITEM_SIZE: usize = 400 * 1024;
+const MAX_HASH_KEY_SIZE: usize = 2048;
+const MAX_RANGE_KEY_SIZE: usize = 1024;
+
 #[derive(Debug, Clone)]
 pub struct Table {
     pub table_name: String,
@@ -54,6 +63,53 @@ impl Table {
         })
     }
 
+    /// Rejects empty string or binary key values and keys over the size
+    /// limits: 2048 bytes for the partition key, 1024 for the sort key.
+    /// Key attributes missing from `key` are not checked here.
+    pub fn validate_key(&self, key: &Item) -> Result<(), DynamoDbError> {
+        for element in &self.key_schema {
+            let Some(value) = key.get(&element.attribute_name) else {
+                continue;
+            };
+            for kind in ["S", "B"] {
+                if value.get(kind).and_then(Value::as_str) == Some("") {
+                    let kind = if kind == "S" { "string" } else { "binary" };
+                    return Err(DynamoDbError::ValidationException(format!(
+                        "One or more parameter values are not valid. The AttributeValue for a key attribute \
+                         cannot contain an empty {kind} value. Key: {}",
+                        element.attribute_name
+                    )));
+                }
+            }
+            let size = value_size(value);
+            if element.key_type == "HASH" && size > MAX_HASH_KEY_SIZE {
+                return Err(DynamoDbError::ValidationException(format!(
+                    "One or more parameter values were invalid: Size of hashkey has exceeded the maximum size \
+                     limit of {MAX_HASH_KEY_SIZE} bytes"
+                )));
+            }
+            if element.key_type == "RANGE" && size > MAX_RANGE_KEY_SIZE {
+                return Err(DynamoDbError::ValidationException(format!(
+                    "One or more parameter values were invalid: Aggregated size of all range keys has exceeded \
+                     the size limit of {MAX_RANGE_KEY_SIZE} bytes"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks an item about to be written: its key as in
+    /// [`Table::validate_key`], and its size against [`MAX_ITEM_SIZE`].
+    pub fn validate_item(&self, item: &Item) -> Result<(), DynamoDbError> {
+        self.validate_key(item)?;
+        if item_size(item) > MAX_ITEM_SIZE {
+            return Err(DynamoDbError::ValidationException(
+                "Item size has exceeded the maximum allowed size".into(),
+            ));
+        }
+        Ok(())
+    }
+
     pub fn to_description(&self) -> TableDescription {
         let billing_mode_summary = if self.billing_mode == "PAY_PER_REQUEST" {
             Some(BillingModeSummary {
@@ -75,7 +131,45 @@ impl Table {
             provisioned_throughput: self.provisioned_throughput.clone(),
             billing_mode_summary,
             item_count: self.items.len() as i64,
-            table_size_bytes: 0,
+            table_size_bytes: self.items.iter().map(item_size).sum::<usize>() as i64,
         }
     }
 }
+
+/// The size DynamoDB bills for an item: the UTF-8 length of every
+/// attribute name plus the size of its value.
+pub fn item_size(item: &Item) -> usize {
+    item.iter().map(|(name, value)| name.len() + value_size(value)).sum()
+}
+
+/// Size of an attribute value, following the DynamoDB item size rules:
+/// numbers take one byte per two significant digits plus one, and lists
+/// and maps carry three bytes of overhead plus one per element.
+fn value_size(value: &Value) -> usize {
+    let Some((kind, inner)) = value.as_object().and_then(|o| o.iter().next()) else {
+        return 0;
+    };
+    match (kind.as_str(), inner) {
+        ("S", Value::String(s)) => s.len(),
+        ("N", Value::String(n)) => number_size(n),
+        ("B", Value::String(b)) => binary_size(b),
+        ("SS", Value::Array(v)) => v.iter().filter_map(Value::as_str).map(str::len).sum(),
+        ("NS", Value::Array(v)) => v.iter().filter_map(Value::as_str).map(number_size).sum(),
+        ("BS", Value::Array(v)) => v.iter().filter_map(Value::as_str).map(binary_size).sum(),
+        ("L", Value::Array(v)) => 3 + v.iter().map(|e| 1 + value_size(e)).sum::<usize>(),
+        ("M", Value::Object(m)) => 3 + m.iter().map(|(k, e)| 1 + k.len() + value_size(e)).sum::<usize>(),
+        ("BOOL", _) | ("NULL", _) => 1,
+        _ => 0,
+    }
+}
+
+fn number_size(n: &str) -> usize {
+    let mantissa = n.trim_start_matches(['-', '+']).split(['e', 'E']).next().unwrap_or_default();
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let significant = digits.trim_start_matches('0').trim_end_matches('0').len().max(1);
+    significant.div_ceil(2) + 1
+}
+
+fn binary_size(b: &str) -> usize {
+    base64::engine::general_purpose::STANDARD.decode(b).map(|d| d.len()).unwrap_or(b.len())
+}
@@ -370,21 +370,21 @@ pub struct BatchWriteItemRequest {
     pub request_items: HashMap<String, Vec<WriteRequest>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WriteRequest {
-    #[serde(rename = "PutRequest", default)]
+    #[serde(rename = "PutRequest", default, skip_serializing_if = "Option::is_none")]
     pub put_request: Option<PutRequest>,
-    #[serde(rename = "DeleteRequest", default)]
+    #[serde(rename = "DeleteRequest", default, skip_serializing_if = "Option::is_none")]
     pub delete_request: Option<DeleteRequest>,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PutRequest {
     #[serde(rename = "Item")]
     pub item: Item,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DeleteRequest {
     #[serde(rename = "Key")]
     pub key: Item,
@@ -430,6 +430,8 @@ pub struct UntagResourceRequest {
 pub struct ListTagsOfResourceRequest {
     #[serde(rename = "ResourceArn")]
     pub resource_arn: String,
+    #[serde(rename = "NextToken")]
+    pub next_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -439,3 +441,21 @@ pub struct ListTagsOfResourceResponse {
     #[serde(rename = "NextToken", skip_serializing_if = "Option::is_none")]
     pub next_token: Option<String>,
 }
+
+// --- Fixtures (non-AWS) ---
+
+/// A table's items, and optionally its definition, as dumped by
+/// `GET /_debug/tables/{name}/items` and loaded by the `PUT` on the same
+/// path or a `--dynamodb-seed` file. The definition is needed only when the
+/// table does not exist yet.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TableFixture {
+    #[serde(rename = "KeySchema", default, skip_serializing_if = "Option::is_none")]
+    pub key_schema: Option<Vec<KeySchemaElement>>,
+    #[serde(rename = "AttributeDefinitions", default, skip_serializing_if = "Option::is_none")]
+    pub attribute_definitions: Option<Vec<AttributeDefinition>>,
+    #[serde(rename = "BillingMode", default, skip_serializing_if = "Option::is_none")]
+    pub billing_mode: Option<String>,
+    #[serde(rename = "Items", default)]
+    pub items: Vec<Item>,
+}
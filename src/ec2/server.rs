@@ -114,6 +114,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<EC2State>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("ec2"))
         .with_state(state)
 }
 
@@ -34,6 +34,13 @@ impl EC2State {
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
     #[allow(dead_code)]
     pub async fn create_instance(&self, name: String) -> Result<InstanceInfo, EC2Error> {
         let mut state = self.inner.lock().await;
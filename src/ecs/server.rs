@@ -63,6 +63,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<ECSState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("ecs"))
         .with_state(state)
 }
 
@@ -242,6 +242,7 @@ pub fn create_router(state: Arc<EfsState>) -> Router {
             "/2015-02-01/file-systems/{FileSystemId}/lifecycle-configuration",
             get(describe_lifecycle_configuration),
         )
+        .merge(crate::healthcheck::router("efs"))
         .with_state(state)
 }
 
@@ -40,6 +40,13 @@ impl EfsState {
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
     fn generate_fs_id() -> String {
         let hex = Uuid::new_v4().to_string().replace('-', "");
         format!("fs-{}", &hex[..17])
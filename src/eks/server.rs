@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<EKSState>) -> Router {
     Router::new()
         .route("/clusters", post(create_cluster_handler).get(list_clusters_handler))
         .route("/clusters/{name}", get(get_cluster_handler).delete(delete_cluster_handler))
+        .merge(crate::healthcheck::router("eks"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<ElastictranscoderState>) -> Router {
     Router::new()
         .route("/pipelines", post(create_pipeline_handler).get(list_pipelines_handler))
         .route("/pipelines/{name}", get(get_pipeline_handler).delete(delete_pipeline_handler))
+        .merge(crate::healthcheck::router("elastictranscoder"))
         .with_state(state)
 }
 
@@ -96,6 +96,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<ELBState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("elb"))
         .with_state(state)
 }
 
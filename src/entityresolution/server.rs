@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<EntityresolutionState>) -> Router {
     Router::new()
         .route("/matching-workflows", post(create_matching_workflow_handler).get(list_matching_workflows_handler))
         .route("/matching-workflows/{name}", get(get_matching_workflow_handler).delete(delete_matching_workflow_handler))
+        .merge(crate::healthcheck::router("entityresolution"))
         .with_state(state)
 }
 
@@ -1,12 +1,16 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
+use crate::pagination::InvalidToken;
 
 #[derive(Debug, Clone)]
 pub enum EventBridgeError {
     ResourceNotFoundException(String),
     ResourceAlreadyExistsException(String),
     InvalidAction(String),
+    InvalidEventPatternException(String),
+    ValidationException(String),
 }
 
 impl EventBridgeError {
@@ -15,6 +19,8 @@ impl EventBridgeError {
             EventBridgeError::ResourceNotFoundException(_) => "ResourceNotFoundException",
             EventBridgeError::ResourceAlreadyExistsException(_) => "ResourceAlreadyExistsException",
             EventBridgeError::InvalidAction(_) => "InvalidAction",
+            EventBridgeError::InvalidEventPatternException(_) => "InvalidEventPatternException",
+            EventBridgeError::ValidationException(_) => "ValidationException",
         }
     }
 
@@ -29,18 +35,36 @@ impl EventBridgeError {
         match self {
             EventBridgeError::ResourceNotFoundException(m)
             | EventBridgeError::ResourceAlreadyExistsException(m)
-            | EventBridgeError::InvalidAction(m) => m,
+            | EventBridgeError::InvalidAction(m)
+            | EventBridgeError::InvalidEventPatternException(m)
+            | EventBridgeError::ValidationException(m) => m,
         }
     }
 }
 
+impl From<InvalidToken> for EventBridgeError {
+    fn from(_: InvalidToken) -> Self {
+        EventBridgeError::ValidationException("Invalid NextToken value.".to_string())
+    }
+}
+
+impl AwsError for EventBridgeError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        EventBridgeError::message(self)
+    }
+}
+
 impl IntoResponse for EventBridgeError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -64,6 +88,16 @@ mod tests {
         assert_eq!(err.error_code(), "InvalidAction");
     }
     #[test]
+    fn test_invalideventpatternexception_error_code() {
+        let err = EventBridgeError::InvalidEventPatternException("test".to_string());
+        assert_eq!(err.error_code(), "InvalidEventPatternException");
+    }
+    #[test]
+    fn test_validationexception_error_code() {
+        let err = EventBridgeError::ValidationException("test".to_string());
+        assert_eq!(err.error_code(), "ValidationException");
+    }
+    #[test]
     fn test_message() {
         let err = EventBridgeError::ResourceNotFoundException("hello".to_string());
         assert_eq!(err.message(), "hello");
@@ -1,4 +1,5 @@
 pub mod error;
+pub mod pattern;
 pub mod server;
 pub mod state;
 pub mod types;
@@ -0,0 +1,140 @@
+use serde_json::Value;
+
+use super::error::EventBridgeError;
+
+/// Parses an event pattern, rejecting anything that is not a JSON object.
+pub fn parse(pattern: &str) -> Result<Value, EventBridgeError> {
+    let value: Value = serde_json::from_str(pattern).map_err(|e| {
+        EventBridgeError::InvalidEventPatternException(format!("Event pattern is not valid JSON: {e}"))
+    })?;
+    if !value.is_object() {
+        return Err(EventBridgeError::InvalidEventPatternException(
+            "Event pattern must be a JSON object".to_string(),
+        ));
+    }
+    Ok(value)
+}
+
+/// Returns true if `event` satisfies every field of `pattern`.
+pub fn matches(pattern: &Value, event: &Value) -> bool {
+    match (pattern, event) {
+        (Value::Object(p), Value::Object(e)) => {
+            p.iter().all(|(key, expected)| match_field(expected, e.get(key)))
+        }
+        _ => false,
+    }
+}
+
+fn match_field(expected: &Value, actual: Option<&Value>) -> bool {
+    match expected {
+        Value::Object(_) => match actual {
+            Some(v @ Value::Object(_)) => matches(expected, v),
+            _ => false,
+        },
+        Value::Array(matchers) => {
+            let Some(actual) = actual else {
+                return matchers
+                    .iter()
+                    .any(|m| m.get("exists") == Some(&Value::Bool(false)));
+            };
+            let values: Vec<&Value> = match actual {
+                Value::Array(items) => items.iter().collect(),
+                other => vec![other],
+            };
+            matchers
+                .iter()
+                .any(|m| values.iter().any(|v| match_value(m, v)))
+        }
+        _ => false,
+    }
+}
+
+fn match_value(matcher: &Value, value: &Value) -> bool {
+    let Value::Object(rule) = matcher else {
+        return matcher == value;
+    };
+    rule.iter().all(|(op, arg)| match op.as_str() {
+        "exists" => arg.as_bool().unwrap_or(false),
+        "prefix" => match (arg, value) {
+            (Value::String(p), Value::String(s)) => s.starts_with(p.as_str()),
+            _ => false,
+        },
+        "suffix" => match (arg, value) {
+            (Value::String(p), Value::String(s)) => s.ends_with(p.as_str()),
+            _ => false,
+        },
+        "equals-ignore-case" => match (arg, value) {
+            (Value::String(p), Value::String(s)) => p.eq_ignore_ascii_case(s),
+            _ => false,
+        },
+        "anything-but" => match arg {
+            Value::Array(excluded) => !excluded.contains(value),
+            Value::Object(_) => !match_value(arg, value),
+            other => other != value,
+        },
+        "numeric" => match (arg, value.as_f64()) {
+            (Value::Array(conds), Some(n)) => match_numeric(conds, n),
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+fn match_numeric(conds: &[Value], n: f64) -> bool {
+    conds.chunks(2).all(|pair| {
+        let (Some(op), Some(bound)) = (pair[0].as_str(), pair.get(1).and_then(Value::as_f64)) else {
+            return false;
+        };
+        match op {
+            "=" => n == bound,
+            ">" => n > bound,
+            ">=" => n >= bound,
+            "<" => n < bound,
+            "<=" => n <= bound,
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event() -> Value {
+        json!({
+            "source": "orders",
+            "detail-type": "OrderPlaced",
+            "detail": {"amount": 42, "region": "eu-west-1", "tags": ["a", "b"]}
+        })
+    }
+
+    #[test]
+    fn test_exact_match() {
+        assert!(matches(&json!({"source": ["orders"]}), &event()));
+        assert!(!matches(&json!({"source": ["billing"]}), &event()));
+    }
+
+    #[test]
+    fn test_nested_and_array_values() {
+        assert!(matches(&json!({"detail": {"tags": ["b"]}}), &event()));
+        assert!(!matches(&json!({"detail": {"tags": ["c"]}}), &event()));
+    }
+
+    #[test]
+    fn test_content_filters() {
+        assert!(matches(&json!({"detail": {"region": [{"prefix": "eu-"}]}}), &event()));
+        assert!(matches(&json!({"detail": {"amount": [{"numeric": [">", 10, "<=", 42]}]}}), &event()));
+        assert!(!matches(&json!({"detail": {"amount": [{"numeric": ["<", 10]}]}}), &event()));
+        assert!(matches(&json!({"source": [{"anything-but": ["billing"]}]}), &event()));
+        assert!(matches(&json!({"detail": {"missing": [{"exists": false}]}}), &event()));
+        assert!(!matches(&json!({"detail": {"missing": [{"exists": true}]}}), &event()));
+    }
+
+    #[test]
+    fn test_parse_rejects_non_object() {
+        assert!(parse("[1]").is_err());
+        assert!(parse("{not json").is_err());
+        assert!(parse("{\"source\":[\"x\"]}").is_ok());
+    }
+}
@@ -54,11 +54,21 @@ async fn handle_request(
         "DeleteRule" => dispatch_empty!(state, body, DeleteRuleRequest, delete_rule),
         "DescribeRule" => dispatch!(state, body, DescribeRuleRequest, describe_rule),
         "ListRules" => dispatch!(state, body, ListRulesRequest, list_rules),
+        "EnableRule" => dispatch_empty!(state, body, EnableRuleRequest, enable_rule),
+        "DisableRule" => dispatch_empty!(state, body, DisableRuleRequest, disable_rule),
+        "TestEventPattern" => dispatch!(state, body, TestEventPatternRequest, test_event_pattern),
         "PutTargets" => dispatch!(state, body, PutTargetsRequest, put_targets),
         "RemoveTargets" => dispatch!(state, body, RemoveTargetsRequest, remove_targets),
         "ListTargetsByRule" => {
             dispatch!(state, body, ListTargetsByRuleRequest, list_targets_by_rule)
         }
+        "CreateArchive" => dispatch!(state, body, CreateArchiveRequest, create_archive),
+        "DeleteArchive" => dispatch_empty!(state, body, DeleteArchiveRequest, delete_archive),
+        "DescribeArchive" => dispatch!(state, body, DescribeArchiveRequest, describe_archive),
+        "ListArchives" => dispatch!(state, body, ListArchivesRequest, list_archives),
+        "StartReplay" => dispatch!(state, body, StartReplayRequest, start_replay),
+        "DescribeReplay" => dispatch!(state, body, DescribeReplayRequest, describe_replay),
+        "ListReplays" => dispatch!(state, body, ListReplaysRequest, list_replays),
         "TagResource" => dispatch_empty!(state, body, TagResourceRequest, tag_resource),
         "UntagResource" => dispatch_empty!(state, body, UntagResourceRequest, untag_resource),
         "ListTagsForResource" => {
@@ -71,6 +81,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<EventBridgeState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("eventbridge"))
         .with_state(state)
 }
 
@@ -82,6 +93,92 @@ mod tests {
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
 
+    #[tokio::test]
+    async fn test_lambda_target_with_stubbed_function_through_routers() {
+        use serde_json::json;
+
+        let sqs = Arc::new(crate::sqs::state::SqsState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+            9324,
+        ));
+        let url = sqs.create_queue(crate::sqs::types::CreateQueueRequest {
+            queue_name: "audit-dlq".to_string(),
+            attributes: None,
+            tags: None,
+        }).await.unwrap().queue_url;
+        let lambda = Arc::new(crate::lambda::state::LambdaState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        ));
+        let lambda_app = crate::lambda::server::create_router(lambda.clone());
+        crate::lambda::server::create_stubbed_function(&lambda_app, "audit", json!({ "mode": "echo" })).await;
+        let bus = Arc::new(crate::bus::ServiceBus::new());
+        bus.attach_sqs(sqs.clone());
+        bus.attach_lambda(lambda);
+        let state = EventBridgeState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let app = create_router(Arc::new(state));
+        let call = |action: &str, body: serde_json::Value| {
+            let app = app.clone();
+            let req = Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/x-amz-json-1.1")
+                .header("x-amz-target", format!("AWSEvents.{action}"))
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let action = action.to_string();
+            async move {
+                let resp = app.oneshot(req).await.unwrap();
+                assert_eq!(resp.status(), StatusCode::OK, "{action}");
+            }
+        };
+        let put_event = |id: &str| json!({
+            "Entries": [{
+                "Source": "orders",
+                "DetailType": "OrderPlaced",
+                "Detail": format!("{{\"id\":\"{id}\"}}"),
+            }]
+        });
+
+        call("PutRule", json!({ "Name": "orders", "EventPattern": r#"{"source":["orders"]}"# })).await;
+        call("PutTargets", json!({
+            "Rule": "orders",
+            "Targets": [{
+                "Id": "fn",
+                "Arn": "arn:aws:lambda:us-east-1:123456789012:function:audit",
+                "RetryPolicy": { "MaximumRetryAttempts": 0 },
+                "DeadLetterConfig": { "Arn": "arn:aws:sqs:us-east-1:123456789012:audit-dlq" },
+            }]
+        })).await;
+        // The echo stub succeeds, so nothing is dead-lettered for "accepted".
+        call("PutEvents", put_event("accepted")).await;
+        let stub = json!({ "mode": "error", "errorMessage": "boom" });
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_aws/lambda/functions/audit/stub")
+            .body(Body::from(stub.to_string()))
+            .unwrap();
+        assert!(lambda_app.clone().oneshot(req).await.unwrap().status().is_success());
+        call("PutEvents", put_event("rejected")).await;
+
+        let mut messages = Vec::new();
+        for _ in 0..50 {
+            messages = sqs.receive_message(crate::sqs::types::ReceiveMessageRequest {
+                queue_url: url.clone(),
+                max_number_of_messages: Some(10),
+                ..Default::default()
+            }).await.unwrap().messages.unwrap_or_default();
+            if !messages.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(messages.len(), 1);
+        let event: serde_json::Value = serde_json::from_str(&messages[0].body).unwrap();
+        assert_eq!(event["detail"]["id"], "rejected");
+    }
+
     #[tokio::test]
     async fn test_missing_target_header() {
         let state = Arc::new(EventBridgeState::new("123456789012".to_string(), "us-east-1".to_string()));
@@ -167,4 +264,19 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_listarchives_action() {
+        let state = Arc::new(EventBridgeState::new("123456789012".to_string(), "us-east-1".to_string()));
+        let app = create_router(state);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/x-amz-json-1.1")
+            .header("x-amz-target", "AWSEvents.ListArchives")
+            .body(Body::from("{}"))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
 }
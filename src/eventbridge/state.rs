@@ -1,10 +1,16 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use serde_json::Value;
 use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
 use uuid::Uuid;
 
+use crate::bus::ServiceBus;
+use crate::pagination;
+
 use super::error::EventBridgeError;
+use super::pattern;
 use super::types::*;
 
 struct EventBusData {
@@ -26,14 +32,225 @@ struct RuleData {
     tags: HashMap<String, String>,
 }
 
+struct ArchiveData {
+    name: String,
+    arn: String,
+    event_source_arn: String,
+    bus_name: String,
+    description: Option<String>,
+    event_pattern: Option<String>,
+    retention_days: u32,
+    state: String,
+    creation_time: f64,
+    events: Vec<ArchivedEvent>,
+}
+
+impl ArchiveData {
+    fn size_bytes(&self) -> u64 {
+        self.events.iter().map(|e| e.event.to_string().len() as u64).sum()
+    }
+}
+
+struct ArchivedEvent {
+    time: f64,
+    event: Value,
+}
+
+struct ReplayData {
+    name: String,
+    arn: String,
+    description: Option<String>,
+    event_source_arn: String,
+    destination: ReplayDestination,
+    event_start_time: f64,
+    event_end_time: f64,
+    event_last_replayed_time: Option<f64>,
+    replay_start_time: f64,
+    replay_end_time: Option<f64>,
+    state: String,
+}
+
+/// A single target invocation produced by routing an event through the rules.
+struct Delivery {
+    rule_arn: String,
+    target_arn: String,
+    payload: String,
+    event: String,
+    retry_policy: Option<RetryPolicy>,
+    dead_letter_arn: Option<String>,
+}
+
+const DEFAULT_MAX_RETRY_ATTEMPTS: u32 = 185;
+const DEFAULT_MAX_EVENT_AGE_SECS: u32 = 86_400;
+/// Backoff between retries starts here and doubles up to `RETRY_MAX_DELAY`.
+/// Both are far shorter than AWS so failure paths can be exercised in tests.
+const RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+const RETRY_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(30);
+
 struct EventBridgeStateInner {
     buses: HashMap<String, EventBusData>,
+    archives: HashMap<String, ArchiveData>,
+    replays: HashMap<String, ReplayData>,
+    /// Background retries of failed target deliveries.
+    retries: Vec<AbortHandle>,
     account_id: String,
     region: String,
 }
 
 pub struct EventBridgeState {
     inner: Arc<Mutex<EventBridgeStateInner>>,
+    bus: Arc<ServiceBus>,
+    allow_cross_account: bool,
+}
+
+fn now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or(std::time::Duration::from_secs(0))
+        .as_secs_f64()
+}
+
+fn format_event_time(epoch: f64) -> String {
+    chrono::DateTime::from_timestamp(epoch as i64, 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string()
+}
+
+/// Resolves an event bus by name or ARN to its name.
+fn bus_name_from(name_or_arn: &str) -> &str {
+    name_or_arn
+        .strip_prefix("arn:")
+        .and_then(|rest| rest.rsplit("event-bus/").next())
+        .unwrap_or(name_or_arn)
+}
+
+/// Builds the payload a target receives, honouring `Input`/`InputPath`.
+fn target_payload(target: &Target, event: &Value) -> String {
+    if let Some(ref input) = target.input {
+        return input.clone();
+    }
+    if let Some(ref path) = target.input_path {
+        let selected = path
+            .trim_start_matches('$')
+            .split('.')
+            .filter(|s| !s.is_empty())
+            .try_fold(event, |v, key| v.get(key));
+        return selected.map(Value::to_string).unwrap_or_else(|| "null".to_string());
+    }
+    event.to_string()
+}
+
+/// Retries a failed delivery with exponential backoff until it succeeds or
+/// the retry policy is exhausted, then forwards the original event to the
+/// target's dead-letter queue, if one is configured.
+async fn retry_delivery(bus: Arc<ServiceBus>, delivery: Delivery, mut last_error: String) {
+    let policy = delivery.retry_policy.clone().unwrap_or_default();
+    let max_attempts = policy.maximum_retry_attempts.unwrap_or(DEFAULT_MAX_RETRY_ATTEMPTS);
+    let max_age = std::time::Duration::from_secs(
+        policy.maximum_event_age_in_seconds.unwrap_or(DEFAULT_MAX_EVENT_AGE_SECS) as u64,
+    );
+    let started = std::time::Instant::now();
+    let mut delay = RETRY_BASE_DELAY;
+    let mut attempts = 0;
+    let mut exhausted = "MaximumRetryAttemptsExhausted";
+    while attempts < max_attempts {
+        if started.elapsed() + delay > max_age {
+            exhausted = "MaximumEventAgeExhausted";
+            break;
+        }
+        tokio::time::sleep(delay).await;
+        attempts += 1;
+        match bus.deliver(&delivery.target_arn, &delivery.payload).await {
+            Ok(()) => return,
+            Err(reason) => last_error = reason,
+        }
+        delay = (delay * 2).min(RETRY_MAX_DELAY);
+    }
+    let Some(ref dlq_arn) = delivery.dead_letter_arn else {
+        return;
+    };
+    let attributes = HashMap::from([
+        ("RULE_ARN".to_string(), delivery.rule_arn.clone()),
+        ("TARGET_ARN".to_string(), delivery.target_arn.clone()),
+        ("ERROR_MESSAGE".to_string(), last_error),
+        ("EXHAUSTED_RETRY_CONDITION".to_string(), exhausted.to_string()),
+        ("RETRY_ATTEMPTS".to_string(), attempts.to_string()),
+    ]);
+    let _ = bus.deliver_with_attributes(dlq_arn, &delivery.event, attributes).await;
+}
+
+fn validate_retry_policy(policy: Option<&RetryPolicy>) -> Result<(), EventBridgeError> {
+    let Some(policy) = policy else {
+        return Ok(());
+    };
+    if policy.maximum_retry_attempts.is_some_and(|n| n > DEFAULT_MAX_RETRY_ATTEMPTS) {
+        return Err(EventBridgeError::ValidationException(format!(
+            "MaximumRetryAttempts must be between 0 and {}", DEFAULT_MAX_RETRY_ATTEMPTS
+        )));
+    }
+    if policy.maximum_event_age_in_seconds.is_some_and(|n| !(60..=DEFAULT_MAX_EVENT_AGE_SECS).contains(&n)) {
+        return Err(EventBridgeError::ValidationException(format!(
+            "MaximumEventAgeInSeconds must be between 60 and {}", DEFAULT_MAX_EVENT_AGE_SECS
+        )));
+    }
+    Ok(())
+}
+
+impl EventBridgeStateInner {
+    /// Matches `event` against the enabled rules of `bus_name`, optionally
+    /// restricted to `rule_arns`, and returns the resulting target deliveries.
+    fn route(&self, bus_name: &str, event: &Value, rule_arns: Option<&[String]>) -> Vec<Delivery> {
+        let Some(bus) = self.buses.get(bus_name) else {
+            return Vec::new();
+        };
+        let mut deliveries = Vec::new();
+        for rule in bus.rules.values() {
+            if rule.state != "ENABLED" {
+                continue;
+            }
+            if rule_arns.is_some_and(|arns| !arns.contains(&rule.arn)) {
+                continue;
+            }
+            let Some(ref raw) = rule.event_pattern else {
+                continue;
+            };
+            let matched = pattern::parse(raw)
+                .map(|p| pattern::matches(&p, event))
+                .unwrap_or(false);
+            if !matched {
+                continue;
+            }
+            for target in rule.targets.values() {
+                deliveries.push(Delivery {
+                    rule_arn: rule.arn.clone(),
+                    target_arn: target.arn.clone(),
+                    payload: target_payload(target, event),
+                    event: event.to_string(),
+                    retry_policy: target.retry_policy.clone(),
+                    dead_letter_arn: target.dead_letter_config.as_ref().and_then(|c| c.arn.clone()),
+                });
+            }
+        }
+        deliveries
+    }
+
+    fn archive_event(&mut self, bus_name: &str, time: f64, event: &Value) {
+        for archive in self.archives.values_mut() {
+            if archive.bus_name != bus_name || archive.state != "ENABLED" {
+                continue;
+            }
+            let matched = match archive.event_pattern {
+                Some(ref raw) => pattern::parse(raw)
+                    .map(|p| pattern::matches(&p, event))
+                    .unwrap_or(false),
+                None => true,
+            };
+            if matched {
+                archive.events.push(ArchivedEvent { time, event: event.clone() });
+            }
+        }
+    }
 }
 
 impl EventBridgeState {
@@ -49,9 +266,50 @@ impl EventBridgeState {
         EventBridgeState {
             inner: Arc::new(Mutex::new(EventBridgeStateInner {
                 buses,
+                archives: HashMap::new(),
+                replays: HashMap::new(),
+                retries: Vec::new(),
                 account_id,
                 region,
             })),
+            bus: Arc::new(ServiceBus::new()),
+            allow_cross_account: false,
+        }
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        for retry in &state.retries {
+            retry.abort();
+        }
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Routes target deliveries through `bus` instead of a detached one.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Accepts target and dead-letter queue ARNs of other accounts and
+    /// regions.
+    pub fn with_cross_account(mut self, allow: bool) -> Self {
+        self.allow_cross_account = allow;
+        self
+    }
+
+    /// Makes the first delivery attempt inline; failed deliveries are
+    /// retried in the background according to the target's retry policy.
+    async fn dispatch(&self, deliveries: Vec<Delivery>) {
+        for delivery in deliveries {
+            if let Err(reason) = self.bus.deliver(&delivery.target_arn, &delivery.payload).await {
+                let mut state = self.inner.lock().await;
+                state.retries.retain(|retry| !retry.is_finished());
+                let retry = tokio::spawn(retry_delivery(self.bus.clone(), delivery, reason));
+                state.retries.push(retry.abort_handle());
+            }
         }
     }
 
@@ -104,7 +362,7 @@ impl EventBridgeState {
         req: DescribeEventBusRequest,
     ) -> Result<DescribeEventBusResponse, EventBridgeError> {
         let state = self.inner.lock().await;
-        let bus_name = req.name.as_deref().unwrap_or("default");
+        let bus_name = bus_name_from(req.name.as_deref().unwrap_or("default"));
         let bus = state.buses.get(bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
@@ -142,25 +400,54 @@ impl EventBridgeState {
         &self,
         req: PutEventsRequest,
     ) -> Result<PutEventsResponse, EventBridgeError> {
-        let state = self.inner.lock().await;
+        let mut state = self.inner.lock().await;
         let mut results = Vec::with_capacity(req.entries.len());
+        let mut deliveries = Vec::new();
         for entry in req.entries {
-            let event_id = Uuid::new_v4().to_string();
-            let bus_name = entry.event_bus_name.as_deref().unwrap_or("default");
-            if state.buses.contains_key(bus_name) {
-                results.push(PutEventsResultEntry {
-                    event_id: Some(event_id),
-                    error_code: None,
-                    error_message: None,
-                });
-            } else {
+            let bus_name = bus_name_from(entry.event_bus_name.as_deref().unwrap_or("default")).to_string();
+            if !state.buses.contains_key(&bus_name) {
                 results.push(PutEventsResultEntry {
                     event_id: None,
                     error_code: Some("ResourceNotFoundException".to_string()),
                     error_message: Some(format!("Event bus {} not found", bus_name)),
                 });
+                continue;
             }
+            let detail = match entry.detail.as_deref().map(serde_json::from_str::<Value>) {
+                Some(Ok(v @ Value::Object(_))) => v,
+                Some(_) => {
+                    results.push(PutEventsResultEntry {
+                        event_id: None,
+                        error_code: Some("MalformedDetail".to_string()),
+                        error_message: Some("Detail is malformed.".to_string()),
+                    });
+                    continue;
+                }
+                None => Value::Object(Default::default()),
+            };
+            let event_id = Uuid::new_v4().to_string();
+            let time = entry.time.unwrap_or_else(now);
+            let event = serde_json::json!({
+                "version": "0",
+                "id": event_id,
+                "detail-type": entry.detail_type.unwrap_or_default(),
+                "source": entry.source.unwrap_or_default(),
+                "account": state.account_id,
+                "time": format_event_time(time),
+                "region": state.region,
+                "resources": entry.resources.unwrap_or_default(),
+                "detail": detail,
+            });
+            state.archive_event(&bus_name, time, &event);
+            deliveries.extend(state.route(&bus_name, &event, None));
+            results.push(PutEventsResultEntry {
+                event_id: Some(event_id),
+                error_code: None,
+                error_message: None,
+            });
         }
+        drop(state);
+        self.dispatch(deliveries).await;
         let failed = results.iter().filter(|r| r.error_code.is_some()).count() as u32;
         Ok(PutEventsResponse {
             failed_entry_count: failed,
@@ -170,19 +457,24 @@ impl EventBridgeState {
 
     pub async fn put_rule(&self, req: PutRuleRequest) -> Result<PutRuleResponse, EventBridgeError> {
         let mut state = self.inner.lock().await;
-        let bus_name = req.event_bus_name.as_deref().unwrap_or("default").to_string();
+        let bus_name = bus_name_from(req.event_bus_name.as_deref().unwrap_or("default")).to_string();
+        if let Some(ref raw) = req.event_pattern {
+            pattern::parse(raw)?;
+        }
+        let arn = format!(
+            "arn:aws:events:{}:{}:rule/{}/{}",
+            state.region, state.account_id, bus_name, req.name
+        );
         let bus = state.buses.get_mut(&bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
             )))?;
-        let arn = format!(
-            "arn:aws:events:{}:{}:rule/{}/{}",
-            "us-east-1", "000000000000", bus_name, req.name
-        );
         let mut tags = HashMap::new();
         if let Some(t) = req.tags {
             for tag in t { tags.insert(tag.key, tag.value); }
         }
+        // Updating an existing rule keeps its targets, as in AWS.
+        let targets = bus.rules.remove(&req.name).map(|r| r.targets).unwrap_or_default();
         bus.rules.insert(req.name.clone(), RuleData {
             name: req.name,
             arn: arn.clone(),
@@ -191,7 +483,7 @@ impl EventBridgeState {
             schedule_expression: req.schedule_expression,
             state: req.state.unwrap_or_else(|| "ENABLED".to_string()),
             description: req.description,
-            targets: HashMap::new(),
+            targets,
             tags,
         });
         Ok(PutRuleResponse { rule_arn: arn })
@@ -199,7 +491,7 @@ impl EventBridgeState {
 
     pub async fn delete_rule(&self, req: DeleteRuleRequest) -> Result<(), EventBridgeError> {
         let mut state = self.inner.lock().await;
-        let bus_name = req.event_bus_name.as_deref().unwrap_or("default");
+        let bus_name = bus_name_from(req.event_bus_name.as_deref().unwrap_or("default"));
         let bus = state.buses.get_mut(bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
@@ -217,7 +509,7 @@ impl EventBridgeState {
         req: DescribeRuleRequest,
     ) -> Result<DescribeRuleResponse, EventBridgeError> {
         let state = self.inner.lock().await;
-        let bus_name = req.event_bus_name.as_deref().unwrap_or("default");
+        let bus_name = bus_name_from(req.event_bus_name.as_deref().unwrap_or("default"));
         let bus = state.buses.get(bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
@@ -242,7 +534,7 @@ impl EventBridgeState {
         req: ListRulesRequest,
     ) -> Result<ListRulesResponse, EventBridgeError> {
         let state = self.inner.lock().await;
-        let bus_name = req.event_bus_name.as_deref().unwrap_or("default");
+        let bus_name = bus_name_from(req.event_bus_name.as_deref().unwrap_or("default"));
         let bus = state.buses.get(bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
@@ -250,7 +542,10 @@ impl EventBridgeState {
         let mut rules: Vec<Rule> = bus.rules.values().map(|r| Rule {
             name: r.name.clone(),
             arn: r.arn.clone(),
+            event_pattern: r.event_pattern.clone(),
+            schedule_expression: r.schedule_expression.clone(),
             state: r.state.clone(),
+            description: r.description.clone(),
             event_bus_name: r.event_bus_name.clone(),
         }).collect();
         if let Some(ref prefix) = req.name_prefix {
@@ -266,9 +561,68 @@ impl EventBridgeState {
         })
     }
 
+    pub async fn enable_rule(&self, req: EnableRuleRequest) -> Result<(), EventBridgeError> {
+        self.set_rule_state(&req.name, req.event_bus_name.as_deref(), "ENABLED").await
+    }
+
+    pub async fn disable_rule(&self, req: DisableRuleRequest) -> Result<(), EventBridgeError> {
+        self.set_rule_state(&req.name, req.event_bus_name.as_deref(), "DISABLED").await
+    }
+
+    async fn set_rule_state(
+        &self,
+        name: &str,
+        event_bus_name: Option<&str>,
+        rule_state: &str,
+    ) -> Result<(), EventBridgeError> {
+        let mut state = self.inner.lock().await;
+        let bus_name = bus_name_from(event_bus_name.unwrap_or("default"));
+        let bus = state.buses.get_mut(bus_name)
+            .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
+                "Event bus {} not found", bus_name
+            )))?;
+        let rule = bus.rules.get_mut(name)
+            .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
+                "Rule {} not found", name
+            )))?;
+        rule.state = rule_state.to_string();
+        Ok(())
+    }
+
+    /// Evaluates a pattern against an event without touching any bus.
+    pub async fn test_event_pattern(
+        &self,
+        req: TestEventPatternRequest,
+    ) -> Result<TestEventPatternResponse, EventBridgeError> {
+        let pattern = pattern::parse(&req.event_pattern)?;
+        let event: Value = serde_json::from_str(&req.event)
+            .ok()
+            .filter(Value::is_object)
+            .ok_or_else(|| EventBridgeError::ValidationException(
+                "Parameter Event is not valid.".to_string(),
+            ))?;
+        const REQUIRED: [&str; 7] = ["id", "account", "source", "time", "region", "resources", "detail-type"];
+        if let Some(missing) = REQUIRED.iter().find(|f| event.get(**f).is_none()) {
+            return Err(EventBridgeError::ValidationException(format!(
+                "Parameter Event is not valid: missing field '{missing}'."
+            )));
+        }
+        Ok(TestEventPatternResponse {
+            result: pattern::matches(&pattern, &event),
+        })
+    }
+
     pub async fn put_targets(&self, req: PutTargetsRequest) -> Result<PutTargetsResponse, EventBridgeError> {
         let mut state = self.inner.lock().await;
-        let bus_name = req.event_bus_name.as_deref().unwrap_or("default");
+        for target in &req.targets {
+            validate_retry_policy(target.retry_policy.as_ref())?;
+            let dead_letter_arn = target.dead_letter_config.as_ref().and_then(|c| c.arn.as_ref());
+            for arn in std::iter::once(&target.arn).chain(dead_letter_arn) {
+                crate::arn::check_local(arn, &state.account_id, &state.region, self.allow_cross_account)
+                    .map_err(EventBridgeError::ValidationException)?;
+            }
+        }
+        let bus_name = bus_name_from(req.event_bus_name.as_deref().unwrap_or("default"));
         let bus = state.buses.get_mut(bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
@@ -288,7 +642,7 @@ impl EventBridgeState {
 
     pub async fn remove_targets(&self, req: RemoveTargetsRequest) -> Result<RemoveTargetsResponse, EventBridgeError> {
         let mut state = self.inner.lock().await;
-        let bus_name = req.event_bus_name.as_deref().unwrap_or("default");
+        let bus_name = bus_name_from(req.event_bus_name.as_deref().unwrap_or("default"));
         let bus = state.buses.get_mut(bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
@@ -311,7 +665,7 @@ impl EventBridgeState {
         req: ListTargetsByRuleRequest,
     ) -> Result<ListTargetsByRuleResponse, EventBridgeError> {
         let state = self.inner.lock().await;
-        let bus_name = req.event_bus_name.as_deref().unwrap_or("default");
+        let bus_name = bus_name_from(req.event_bus_name.as_deref().unwrap_or("default"));
         let bus = state.buses.get(bus_name)
             .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
                 "Event bus {} not found", bus_name
@@ -325,6 +679,240 @@ impl EventBridgeState {
         Ok(ListTargetsByRuleResponse { targets, next_token: None })
     }
 
+    pub async fn create_archive(
+        &self,
+        req: CreateArchiveRequest,
+    ) -> Result<CreateArchiveResponse, EventBridgeError> {
+        let mut state = self.inner.lock().await;
+        if state.archives.contains_key(&req.archive_name) {
+            return Err(EventBridgeError::ResourceAlreadyExistsException(format!(
+                "Archive {} already exists", req.archive_name
+            )));
+        }
+        let bus_name = state.buses.values()
+            .find(|b| b.arn == req.event_source_arn)
+            .map(|b| b.name.clone())
+            .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
+                "Event bus {} not found", req.event_source_arn
+            )))?;
+        if let Some(ref raw) = req.event_pattern {
+            pattern::parse(raw)?;
+        }
+        let arn = format!(
+            "arn:aws:events:{}:{}:archive/{}",
+            state.region, state.account_id, req.archive_name
+        );
+        let creation_time = now();
+        state.archives.insert(req.archive_name.clone(), ArchiveData {
+            name: req.archive_name,
+            arn: arn.clone(),
+            event_source_arn: req.event_source_arn,
+            bus_name,
+            description: req.description,
+            event_pattern: req.event_pattern,
+            retention_days: req.retention_days.unwrap_or(0),
+            state: "ENABLED".to_string(),
+            creation_time,
+            events: Vec::new(),
+        });
+        Ok(CreateArchiveResponse {
+            archive_arn: arn,
+            state: "ENABLED".to_string(),
+            creation_time,
+        })
+    }
+
+    pub async fn delete_archive(&self, req: DeleteArchiveRequest) -> Result<(), EventBridgeError> {
+        let mut state = self.inner.lock().await;
+        if state.archives.remove(&req.archive_name).is_none() {
+            return Err(EventBridgeError::ResourceNotFoundException(format!(
+                "Archive {} not found", req.archive_name
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn describe_archive(
+        &self,
+        req: DescribeArchiveRequest,
+    ) -> Result<DescribeArchiveResponse, EventBridgeError> {
+        let state = self.inner.lock().await;
+        let archive = state.archives.get(&req.archive_name)
+            .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
+                "Archive {} not found", req.archive_name
+            )))?;
+        Ok(DescribeArchiveResponse {
+            archive_arn: archive.arn.clone(),
+            archive_name: archive.name.clone(),
+            event_source_arn: archive.event_source_arn.clone(),
+            description: archive.description.clone(),
+            event_pattern: archive.event_pattern.clone(),
+            state: archive.state.clone(),
+            retention_days: archive.retention_days,
+            size_bytes: archive.size_bytes(),
+            event_count: archive.events.len() as u64,
+            creation_time: archive.creation_time,
+        })
+    }
+
+    pub async fn list_archives(
+        &self,
+        req: ListArchivesRequest,
+    ) -> Result<ListArchivesResponse, EventBridgeError> {
+        let state = self.inner.lock().await;
+        let mut archives: Vec<Archive> = state.archives.values()
+            .filter(|a| req.name_prefix.as_ref().is_none_or(|p| a.name.starts_with(p.as_str())))
+            .filter(|a| req.event_source_arn.as_ref().is_none_or(|arn| &a.event_source_arn == arn))
+            .filter(|a| req.state.as_ref().is_none_or(|st| &a.state == st))
+            .map(|a| Archive {
+                archive_name: a.name.clone(),
+                event_source_arn: a.event_source_arn.clone(),
+                state: a.state.clone(),
+                retention_days: a.retention_days,
+                size_bytes: a.size_bytes(),
+                event_count: a.events.len() as u64,
+                creation_time: a.creation_time,
+            })
+            .collect();
+        archives.sort_by(|a, b| a.archive_name.cmp(&b.archive_name));
+        let listing = format!(
+            "events:ListArchives:{}:{}:{}",
+            req.name_prefix.as_deref().unwrap_or_default(),
+            req.event_source_arn.as_deref().unwrap_or_default(),
+            req.state.as_deref().unwrap_or_default(),
+        );
+        let limit = req.limit.unwrap_or(100);
+        let (archives, next_token) =
+            pagination::paginate(archives, |a| a.archive_name.as_str(), req.next_token.as_deref(), &listing, limit)?;
+        Ok(ListArchivesResponse { archives, next_token })
+    }
+
+    /// Re-emits archived events within the requested time window back
+    /// through the destination bus's rules. Replays run to completion
+    /// before the call returns.
+    pub async fn start_replay(
+        &self,
+        req: StartReplayRequest,
+    ) -> Result<StartReplayResponse, EventBridgeError> {
+        let mut state = self.inner.lock().await;
+        if state.replays.contains_key(&req.replay_name) {
+            return Err(EventBridgeError::ResourceAlreadyExistsException(format!(
+                "Replay {} already exists", req.replay_name
+            )));
+        }
+        if req.event_end_time < req.event_start_time {
+            return Err(EventBridgeError::InvalidAction(
+                "EventEndTime must be after EventStartTime".to_string(),
+            ));
+        }
+        let archive = state.archives.values()
+            .find(|a| a.arn == req.event_source_arn)
+            .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
+                "Archive {} not found", req.event_source_arn
+            )))?;
+        let dest_bus = bus_name_from(&req.destination.arn).to_string();
+        if dest_bus != archive.bus_name {
+            return Err(EventBridgeError::InvalidAction(
+                "Replay destination must be the archive's source event bus".to_string(),
+            ));
+        }
+        let mut replayed: Vec<Value> = Vec::new();
+        let mut last_replayed: Option<f64> = None;
+        for archived in &archive.events {
+            if archived.time >= req.event_start_time && archived.time <= req.event_end_time {
+                replayed.push(archived.event.clone());
+                last_replayed = Some(last_replayed.map_or(archived.time, |t| t.max(archived.time)));
+            }
+        }
+        let mut deliveries = Vec::new();
+        for event in &mut replayed {
+            if let Value::Object(ref mut map) = event {
+                map.insert("replay-name".to_string(), Value::String(req.replay_name.clone()));
+            }
+            deliveries.extend(state.route(&dest_bus, event, req.destination.filter_arns.as_deref()));
+        }
+        let arn = format!(
+            "arn:aws:events:{}:{}:replay/{}",
+            state.region, state.account_id, req.replay_name
+        );
+        let replay_start_time = now();
+        state.replays.insert(req.replay_name.clone(), ReplayData {
+            name: req.replay_name,
+            arn: arn.clone(),
+            description: req.description,
+            event_source_arn: req.event_source_arn,
+            destination: req.destination,
+            event_start_time: req.event_start_time,
+            event_end_time: req.event_end_time,
+            event_last_replayed_time: last_replayed,
+            replay_start_time,
+            replay_end_time: Some(now()),
+            state: "COMPLETED".to_string(),
+        });
+        drop(state);
+        self.dispatch(deliveries).await;
+        Ok(StartReplayResponse {
+            replay_arn: arn,
+            state: "COMPLETED".to_string(),
+            replay_start_time,
+        })
+    }
+
+    pub async fn describe_replay(
+        &self,
+        req: DescribeReplayRequest,
+    ) -> Result<DescribeReplayResponse, EventBridgeError> {
+        let state = self.inner.lock().await;
+        let replay = state.replays.get(&req.replay_name)
+            .ok_or_else(|| EventBridgeError::ResourceNotFoundException(format!(
+                "Replay {} not found", req.replay_name
+            )))?;
+        Ok(DescribeReplayResponse {
+            replay_name: replay.name.clone(),
+            replay_arn: replay.arn.clone(),
+            description: replay.description.clone(),
+            state: replay.state.clone(),
+            event_source_arn: replay.event_source_arn.clone(),
+            destination: replay.destination.clone(),
+            event_start_time: replay.event_start_time,
+            event_end_time: replay.event_end_time,
+            event_last_replayed_time: replay.event_last_replayed_time,
+            replay_start_time: replay.replay_start_time,
+            replay_end_time: replay.replay_end_time,
+        })
+    }
+
+    pub async fn list_replays(
+        &self,
+        req: ListReplaysRequest,
+    ) -> Result<ListReplaysResponse, EventBridgeError> {
+        let state = self.inner.lock().await;
+        let mut replays: Vec<Replay> = state.replays.values()
+            .filter(|r| req.name_prefix.as_ref().is_none_or(|p| r.name.starts_with(p.as_str())))
+            .filter(|r| req.event_source_arn.as_ref().is_none_or(|arn| &r.event_source_arn == arn))
+            .filter(|r| req.state.as_ref().is_none_or(|st| &r.state == st))
+            .map(|r| Replay {
+                replay_name: r.name.clone(),
+                event_source_arn: r.event_source_arn.clone(),
+                state: r.state.clone(),
+                event_start_time: r.event_start_time,
+                event_end_time: r.event_end_time,
+                replay_start_time: r.replay_start_time,
+            })
+            .collect();
+        replays.sort_by(|a, b| a.replay_name.cmp(&b.replay_name));
+        let listing = format!(
+            "events:ListReplays:{}:{}:{}",
+            req.name_prefix.as_deref().unwrap_or_default(),
+            req.event_source_arn.as_deref().unwrap_or_default(),
+            req.state.as_deref().unwrap_or_default(),
+        );
+        let limit = req.limit.unwrap_or(100);
+        let (replays, next_token) =
+            pagination::paginate(replays, |a| a.replay_name.as_str(), req.next_token.as_deref(), &listing, limit)?;
+        Ok(ListReplaysResponse { replays, next_token })
+    }
+
     pub async fn tag_resource(&self, req: TagResourceRequest) -> Result<(), EventBridgeError> {
         let mut state = self.inner.lock().await;
         // Find the bus or rule by ARN and tag it
@@ -668,8 +1256,8 @@ mod tests {
             rule: "my-rule".to_string(),
             event_bus_name: None,
             targets: vec![
-                Target { id: "t1".to_string(), arn: "arn:aws:lambda:us-east-1:123:function:f1".to_string(), role_arn: None, input: None, input_path: None },
-                Target { id: "t2".to_string(), arn: "arn:aws:sqs:us-east-1:123:queue1".to_string(), role_arn: None, input: None, input_path: None },
+                Target { id: "t1".to_string(), arn: "arn:aws:lambda:us-east-1:123456789012:function:f1".to_string(), role_arn: None, input: None, input_path: None, retry_policy: None, dead_letter_config: None },
+                Target { id: "t2".to_string(), arn: "arn:aws:sqs:us-east-1:123456789012:queue1".to_string(), role_arn: None, input: None, input_path: None, retry_policy: None, dead_letter_config: None },
             ],
         }).await.unwrap();
         let result = state.list_targets_by_rule(ListTargetsByRuleRequest {
@@ -708,7 +1296,7 @@ mod tests {
         state.put_targets(PutTargetsRequest {
             rule: "my-rule".to_string(),
             event_bus_name: None,
-            targets: vec![Target { id: "t1".to_string(), arn: "arn".to_string(), role_arn: None, input: None, input_path: None }],
+            targets: vec![Target { id: "t1".to_string(), arn: "arn:aws:sqs:us-east-1:123456789012:queue1".to_string(), role_arn: None, input: None, input_path: None, retry_policy: None, dead_letter_config: None }],
         }).await.unwrap();
         state.remove_targets(RemoveTargetsRequest {
             rule: "my-rule".to_string(),
@@ -770,7 +1358,7 @@ mod tests {
     async fn test_put_events_success() {
         let state = make_state();
         let result = state.put_events(PutEventsRequest {
-            entries: vec![PutEventsRequestEntry { event_bus_name: None }],
+            entries: vec![PutEventsRequestEntry { event_bus_name: None, ..Default::default() }],
         }).await.unwrap();
         assert_eq!(result.failed_entry_count, 0);
         assert_eq!(result.entries.len(), 1);
@@ -781,7 +1369,7 @@ mod tests {
     async fn test_put_events_nonexistent_bus() {
         let state = make_state();
         let result = state.put_events(PutEventsRequest {
-            entries: vec![PutEventsRequestEntry { event_bus_name: Some("nope".to_string()) }],
+            entries: vec![PutEventsRequestEntry { event_bus_name: Some("nope".to_string()), ..Default::default() }],
         }).await.unwrap();
         assert_eq!(result.failed_entry_count, 1);
         assert!(result.entries[0].error_code.is_some());
@@ -792,8 +1380,8 @@ mod tests {
         let state = make_state();
         let result = state.put_events(PutEventsRequest {
             entries: vec![
-                PutEventsRequestEntry { event_bus_name: None },
-                PutEventsRequestEntry { event_bus_name: Some("nope".to_string()) },
+                PutEventsRequestEntry { event_bus_name: None, ..Default::default() },
+                PutEventsRequestEntry { event_bus_name: Some("nope".to_string()), ..Default::default() },
             ],
         }).await.unwrap();
         assert_eq!(result.failed_entry_count, 1);
@@ -880,4 +1468,530 @@ mod tests {
         }).await;
         assert!(result.is_err());
     }
+
+    // --- Archives and replays ---
+
+    async fn make_state_with_queue(queue: &str) -> (EventBridgeState, Arc<crate::sqs::state::SqsState>, String) {
+        let sqs = Arc::new(crate::sqs::state::SqsState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+            9324,
+        ));
+        let url = sqs.create_queue(crate::sqs::types::CreateQueueRequest {
+            queue_name: queue.to_string(),
+            attributes: None,
+            tags: None,
+        }).await.unwrap().queue_url;
+        let bus = Arc::new(ServiceBus::new());
+        bus.attach_sqs(sqs.clone());
+        (make_state().with_bus(bus), sqs, url)
+    }
+
+    async fn drain_queue(sqs: &crate::sqs::state::SqsState, url: &str) -> Vec<Value> {
+        sqs.receive_message(crate::sqs::types::ReceiveMessageRequest {
+            queue_url: url.to_string(),
+            max_number_of_messages: Some(10),
+            ..Default::default()
+        }).await.unwrap().messages.unwrap_or_default()
+            .into_iter()
+            .map(|m| serde_json::from_str(&m.body).unwrap())
+            .collect()
+    }
+
+    fn order_event(bus: &str, id: &str, time: f64) -> PutEventsRequestEntry {
+        PutEventsRequestEntry {
+            event_bus_name: Some(bus.to_string()),
+            source: Some("orders".to_string()),
+            detail_type: Some("OrderPlaced".to_string()),
+            detail: Some(format!("{{\"id\":\"{id}\"}}")),
+            resources: None,
+            time: Some(time),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_events_routes_to_rule_on_named_bus() {
+        let (state, sqs, url) = make_state_with_queue("target").await;
+        state.create_event_bus(CreateEventBusRequest { name: "orders-bus".to_string(), tags: None }).await.unwrap();
+        state.put_rule(PutRuleRequest {
+            name: "orders".to_string(),
+            event_bus_name: Some("orders-bus".to_string()),
+            event_pattern: Some("{\"source\":[\"orders\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        state.put_targets(PutTargetsRequest {
+            rule: "orders".to_string(),
+            event_bus_name: Some("orders-bus".to_string()),
+            targets: vec![Target {
+                id: "q".to_string(),
+                arn: "arn:aws:sqs:us-east-1:123456789012:target".to_string(),
+                role_arn: None,
+                input: None,
+                input_path: None,
+                retry_policy: None,
+                dead_letter_config: None,
+            }],
+        }).await.unwrap();
+
+        // Same event on the default bus must not reach the rule.
+        state.put_events(PutEventsRequest { entries: vec![order_event("default", "1", now())] }).await.unwrap();
+        assert!(drain_queue(&sqs, &url).await.is_empty());
+
+        state.put_events(PutEventsRequest { entries: vec![order_event("orders-bus", "2", now())] }).await.unwrap();
+        let events = drain_queue(&sqs, &url).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["detail"]["id"], "2");
+        assert_eq!(events[0]["source"], "orders");
+    }
+
+    #[tokio::test]
+    async fn test_rule_and_target_operations_accept_a_bus_arn() {
+        let (state, sqs, url) = make_state_with_queue("target").await;
+        let bus_arn = state
+            .create_event_bus(CreateEventBusRequest { name: "orders-bus".to_string(), tags: None })
+            .await
+            .unwrap()
+            .event_bus_arn;
+        let on_bus = || Some(bus_arn.clone());
+        state.put_rule(PutRuleRequest {
+            name: "orders".to_string(),
+            event_bus_name: on_bus(),
+            event_pattern: Some("{\"source\":[\"orders\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        state.put_targets(PutTargetsRequest {
+            rule: "orders".to_string(),
+            event_bus_name: on_bus(),
+            targets: vec![Target {
+                id: "q".to_string(),
+                arn: "arn:aws:sqs:us-east-1:123456789012:target".to_string(),
+                ..Default::default()
+            }],
+        }).await.unwrap();
+
+        let bus = state.describe_event_bus(DescribeEventBusRequest { name: on_bus() }).await.unwrap();
+        assert_eq!(bus.name, "orders-bus");
+        let rule = state.describe_rule(DescribeRuleRequest { name: "orders".to_string(), event_bus_name: on_bus() }).await.unwrap();
+        assert_eq!(rule.event_bus_name, "orders-bus");
+        let rules = state.list_rules(ListRulesRequest { event_bus_name: on_bus(), ..Default::default() }).await.unwrap();
+        assert_eq!(rules.rules.len(), 1);
+        let targets = state.list_targets_by_rule(ListTargetsByRuleRequest {
+            rule: "orders".to_string(),
+            event_bus_name: on_bus(),
+        }).await.unwrap();
+        assert_eq!(targets.targets.len(), 1);
+        // Nothing landed on the default bus.
+        assert!(state.list_rules(ListRulesRequest::default()).await.unwrap().rules.is_empty());
+
+        state.put_events(PutEventsRequest { entries: vec![order_event(&bus_arn, "1", now())] }).await.unwrap();
+        assert_eq!(drain_queue(&sqs, &url).await.len(), 1);
+
+        state.disable_rule(DisableRuleRequest { name: "orders".to_string(), event_bus_name: on_bus() }).await.unwrap();
+        state.remove_targets(RemoveTargetsRequest {
+            rule: "orders".to_string(),
+            event_bus_name: on_bus(),
+            ids: vec!["q".to_string()],
+        }).await.unwrap();
+        state.delete_rule(DeleteRuleRequest { name: "orders".to_string(), event_bus_name: on_bus() }).await.unwrap();
+        assert!(state.list_rules(ListRulesRequest { event_bus_name: on_bus(), ..Default::default() }).await.unwrap().rules.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_rule_suppresses_dispatch() {
+        let (state, sqs, url) = make_state_with_queue("toggled").await;
+        state.put_rule(PutRuleRequest {
+            name: "orders".to_string(),
+            event_pattern: Some("{\"source\":[\"orders\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        state.put_targets(PutTargetsRequest {
+            rule: "orders".to_string(),
+            event_bus_name: None,
+            targets: vec![Target {
+                id: "q".to_string(),
+                arn: "arn:aws:sqs:us-east-1:123456789012:toggled".to_string(),
+                role_arn: None,
+                input: None,
+                input_path: None,
+                retry_policy: None,
+                dead_letter_config: None,
+            }],
+        }).await.unwrap();
+
+        state.disable_rule(DisableRuleRequest { name: "orders".to_string(), event_bus_name: None }).await.unwrap();
+        let rule = state.describe_rule(DescribeRuleRequest { name: "orders".to_string(), event_bus_name: None }).await.unwrap();
+        assert_eq!(rule.state, "DISABLED");
+        state.put_events(PutEventsRequest { entries: vec![order_event("default", "1", now())] }).await.unwrap();
+        assert!(drain_queue(&sqs, &url).await.is_empty());
+
+        state.enable_rule(EnableRuleRequest { name: "orders".to_string(), event_bus_name: None }).await.unwrap();
+        let listed = state.list_rules(ListRulesRequest { name_prefix: Some("ord".to_string()), ..Default::default() }).await.unwrap();
+        assert_eq!(listed.rules.len(), 1);
+        assert_eq!(listed.rules[0].state, "ENABLED");
+        assert_eq!(listed.rules[0].event_pattern.as_deref(), Some("{\"source\":[\"orders\"]}"));
+        state.put_events(PutEventsRequest { entries: vec![order_event("default", "2", now())] }).await.unwrap();
+        assert_eq!(drain_queue(&sqs, &url).await.len(), 1);
+
+        let missing = state.disable_rule(DisableRuleRequest { name: "nope".to_string(), event_bus_name: None }).await;
+        assert!(matches!(missing, Err(EventBridgeError::ResourceNotFoundException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_test_event_pattern() {
+        let state = make_state();
+        let event = serde_json::json!({
+            "id": "1", "account": "123456789012", "source": "orders", "time": "2024-01-01T00:00:00Z",
+            "region": "us-east-1", "resources": [], "detail-type": "OrderPlaced",
+            "detail": {"amount": 150},
+        }).to_string();
+        let test = |pattern: &str| TestEventPatternRequest {
+            event_pattern: pattern.to_string(),
+            event: event.clone(),
+        };
+
+        let matched = state.test_event_pattern(test(r#"{"source":["orders"],"detail":{"amount":[{"numeric":[">",100]}]}}"#)).await.unwrap();
+        assert!(matched.result);
+        let unmatched = state.test_event_pattern(test(r#"{"source":["payments"]}"#)).await.unwrap();
+        assert!(!unmatched.result);
+
+        let bad_pattern = state.test_event_pattern(test("[]")).await;
+        assert!(matches!(bad_pattern, Err(EventBridgeError::InvalidEventPatternException(_))));
+        let bad_event = state.test_event_pattern(TestEventPatternRequest {
+            event_pattern: r#"{"source":["orders"]}"#.to_string(),
+            event: r#"{"source":"orders"}"#.to_string(),
+        }).await;
+        assert!(matches!(bad_event, Err(EventBridgeError::ValidationException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_put_rule_invalid_pattern() {
+        let state = make_state();
+        let result = state.put_rule(PutRuleRequest {
+            name: "bad".to_string(),
+            event_pattern: Some("not json".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(EventBridgeError::InvalidEventPatternException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_replay_to_queue() {
+        let (state, sqs, url) = make_state_with_queue("replay-target").await;
+        let bus_arn = state.create_event_bus(CreateEventBusRequest {
+            name: "dr-bus".to_string(),
+            tags: None,
+        }).await.unwrap().event_bus_arn;
+        let archive_arn = state.create_archive(CreateArchiveRequest {
+            archive_name: "dr-archive".to_string(),
+            event_source_arn: bus_arn.clone(),
+            event_pattern: Some("{\"source\":[\"orders\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap().archive_arn;
+
+        // Events are archived before any rule exists, so nothing is delivered yet.
+        state.put_events(PutEventsRequest {
+            entries: vec![
+                order_event("dr-bus", "old", 1_000.0),
+                order_event("dr-bus", "in-range-1", 2_000.0),
+                order_event("dr-bus", "in-range-2", 2_500.0),
+            ],
+        }).await.unwrap();
+        let described = state.describe_archive(DescribeArchiveRequest {
+            archive_name: "dr-archive".to_string(),
+        }).await.unwrap();
+        assert_eq!(described.event_count, 3);
+
+        state.put_rule(PutRuleRequest {
+            name: "reprocess".to_string(),
+            event_bus_name: Some("dr-bus".to_string()),
+            event_pattern: Some("{\"detail-type\":[\"OrderPlaced\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        state.put_targets(PutTargetsRequest {
+            rule: "reprocess".to_string(),
+            event_bus_name: Some("dr-bus".to_string()),
+            targets: vec![Target {
+                id: "q".to_string(),
+                arn: "arn:aws:sqs:us-east-1:123456789012:replay-target".to_string(),
+                role_arn: None,
+                input: None,
+                input_path: None,
+                retry_policy: None,
+                dead_letter_config: None,
+            }],
+        }).await.unwrap();
+
+        let replay = state.start_replay(StartReplayRequest {
+            replay_name: "recover".to_string(),
+            description: None,
+            event_source_arn: archive_arn.clone(),
+            event_start_time: 1_500.0,
+            event_end_time: 3_000.0,
+            destination: ReplayDestination { arn: bus_arn, filter_arns: None },
+        }).await.unwrap();
+        assert_eq!(replay.state, "COMPLETED");
+
+        let mut ids: Vec<String> = drain_queue(&sqs, &url).await.iter()
+            .map(|e| {
+                assert_eq!(e["replay-name"], "recover");
+                e["detail"]["id"].as_str().unwrap_or_default().to_string()
+            })
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["in-range-1", "in-range-2"]);
+
+        let described = state.describe_replay(DescribeReplayRequest {
+            replay_name: "recover".to_string(),
+        }).await.unwrap();
+        assert_eq!(described.event_last_replayed_time, Some(2_500.0));
+        let listed = state.list_replays(ListReplaysRequest {
+            event_source_arn: Some(archive_arn),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(listed.replays.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_archive_unknown_bus() {
+        let state = make_state();
+        let result = state.create_archive(CreateArchiveRequest {
+            archive_name: "a".to_string(),
+            event_source_arn: "arn:aws:events:us-east-1:123456789012:event-bus/nope".to_string(),
+            ..Default::default()
+        }).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_replay_unknown_archive() {
+        let state = make_state();
+        let result = state.start_replay(StartReplayRequest {
+            replay_name: "r".to_string(),
+            event_source_arn: "arn:aws:events:us-east-1:123456789012:archive/nope".to_string(),
+            ..Default::default()
+        }).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_archives_pages_with_next_token() {
+        let state = make_state();
+        let bus_arn = state.describe_event_bus(DescribeEventBusRequest { name: None }).await.unwrap().arn;
+        for name in ["c", "a", "b"] {
+            state.create_archive(CreateArchiveRequest {
+                archive_name: name.to_string(),
+                event_source_arn: bus_arn.clone(),
+                ..Default::default()
+            }).await.unwrap();
+        }
+        let list = |next_token: Option<String>| state.list_archives(ListArchivesRequest {
+            limit: Some(2),
+            next_token,
+            ..Default::default()
+        });
+        let first = list(None).await.unwrap();
+        let names: Vec<&str> = first.archives.iter().map(|a| a.archive_name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        let second = list(first.next_token).await.unwrap();
+        assert_eq!(second.archives.len(), 1);
+        assert_eq!(second.archives[0].archive_name, "c");
+        assert!(second.next_token.is_none());
+
+        let result = list(Some("next".to_string())).await;
+        assert!(matches!(result, Err(EventBridgeError::ValidationException(_))));
+        // A token from one listing is not accepted by another.
+        let token = list(None).await.unwrap().next_token;
+        let result = state.list_replays(ListReplaysRequest { next_token: token, ..Default::default() }).await;
+        assert!(matches!(result, Err(EventBridgeError::ValidationException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_delete_archives() {
+        let state = make_state();
+        let bus_arn = state.describe_event_bus(DescribeEventBusRequest { name: None }).await.unwrap().arn;
+        state.create_archive(CreateArchiveRequest {
+            archive_name: "all-events".to_string(),
+            event_source_arn: bus_arn,
+            ..Default::default()
+        }).await.unwrap();
+        let listed = state.list_archives(ListArchivesRequest::default()).await.unwrap();
+        assert_eq!(listed.archives.len(), 1);
+        state.delete_archive(DeleteArchiveRequest { archive_name: "all-events".to_string() }).await.unwrap();
+        assert!(state.list_archives(ListArchivesRequest::default()).await.unwrap().archives.is_empty());
+    }
+
+    // --- Retry policy and dead-letter queues ---
+
+    #[tokio::test]
+    async fn test_failing_lambda_target_ends_up_in_dlq() {
+        let (state, sqs, url) = make_state_with_queue("rule-dlq").await;
+        // The function is never created, so every invocation fails.
+        state.bus.attach_lambda(Arc::new(crate::lambda::state::LambdaState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        )));
+        state.put_rule(PutRuleRequest {
+            name: "orders".to_string(),
+            event_pattern: Some("{\"source\":[\"orders\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        state.put_targets(PutTargetsRequest {
+            rule: "orders".to_string(),
+            event_bus_name: None,
+            targets: vec![Target {
+                id: "fn".to_string(),
+                arn: "arn:aws:lambda:us-east-1:123456789012:function:always-fails".to_string(),
+                retry_policy: Some(RetryPolicy {
+                    maximum_retry_attempts: Some(2),
+                    maximum_event_age_in_seconds: None,
+                }),
+                dead_letter_config: Some(DeadLetterConfig {
+                    arn: Some("arn:aws:sqs:us-east-1:123456789012:rule-dlq".to_string()),
+                }),
+                ..Default::default()
+            }],
+        }).await.unwrap();
+
+        state.put_events(PutEventsRequest { entries: vec![order_event("default", "lost", now())] }).await.unwrap();
+
+        let mut messages = Vec::new();
+        for _ in 0..50 {
+            messages = sqs.receive_message(crate::sqs::types::ReceiveMessageRequest {
+                queue_url: url.clone(),
+                message_attribute_names: Some(vec!["All".to_string()]),
+                ..Default::default()
+            }).await.unwrap().messages.unwrap_or_default();
+            if !messages.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(messages.len(), 1);
+        let event: Value = serde_json::from_str(&messages[0].body).unwrap();
+        assert_eq!(event["detail"]["id"], "lost");
+        let attrs = messages[0].message_attributes.as_ref().unwrap();
+        assert_eq!(attrs["RETRY_ATTEMPTS"].string_value.as_deref(), Some("2"));
+        assert_eq!(
+            attrs["EXHAUSTED_RETRY_CONDITION"].string_value.as_deref(),
+            Some("MaximumRetryAttemptsExhausted")
+        );
+        assert_eq!(
+            attrs["TARGET_ARN"].string_value.as_deref(),
+            Some("arn:aws:lambda:us-east-1:123456789012:function:always-fails")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lambda_target_function_error_is_retried_then_dead_lettered() {
+        use crate::lambda::state::{FunctionHandler, LambdaState};
+
+        let (state, sqs, url) = make_state_with_queue("fn-dlq").await;
+        let lambda = Arc::new(LambdaState::new("123456789012".to_string(), "us-east-1".to_string()));
+        lambda.create_function(serde_json::from_value(serde_json::json!({
+            "FunctionName": "handler-fails",
+            "Role": "arn:aws:iam::123456789012:role/r",
+            "Code": {}
+        })).unwrap()).await.unwrap();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let handler: FunctionHandler = {
+            let seen = seen.clone();
+            Arc::new(move |event| {
+                seen.lock().unwrap().push(event);
+                Box::pin(async { Err("boom".to_string()) })
+            })
+        };
+        lambda.set_handler("handler-fails", handler).await.unwrap();
+        state.bus.attach_lambda(lambda);
+        state.put_rule(PutRuleRequest {
+            name: "orders".to_string(),
+            event_pattern: Some("{\"source\":[\"orders\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        state.put_targets(PutTargetsRequest {
+            rule: "orders".to_string(),
+            event_bus_name: None,
+            targets: vec![Target {
+                id: "fn".to_string(),
+                arn: "arn:aws:lambda:us-east-1:123456789012:function:handler-fails".to_string(),
+                retry_policy: Some(RetryPolicy {
+                    maximum_retry_attempts: Some(1),
+                    maximum_event_age_in_seconds: None,
+                }),
+                dead_letter_config: Some(DeadLetterConfig {
+                    arn: Some("arn:aws:sqs:us-east-1:123456789012:fn-dlq".to_string()),
+                }),
+                ..Default::default()
+            }],
+        }).await.unwrap();
+
+        state.put_events(PutEventsRequest { entries: vec![order_event("default", "rejected", now())] }).await.unwrap();
+
+        let mut messages = Vec::new();
+        for _ in 0..50 {
+            messages = sqs.receive_message(crate::sqs::types::ReceiveMessageRequest {
+                queue_url: url.clone(),
+                ..Default::default()
+            }).await.unwrap().messages.unwrap_or_default();
+            if !messages.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(messages.len(), 1);
+        // The function saw the event itself, once per attempt.
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0]["detail"]["id"], "rejected");
+    }
+
+    #[tokio::test]
+    async fn test_reset_cancels_pending_retries() {
+        let (state, sqs, url) = make_state_with_queue("retry-dlq").await;
+        state.put_rule(PutRuleRequest {
+            name: "orders".to_string(),
+            event_pattern: Some("{\"source\":[\"orders\"]}".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        state.put_targets(PutTargetsRequest {
+            rule: "orders".to_string(),
+            event_bus_name: None,
+            targets: vec![Target {
+                id: "missing".to_string(),
+                arn: "arn:aws:sqs:us-east-1:123456789012:no-such-queue".to_string(),
+                retry_policy: Some(RetryPolicy {
+                    maximum_retry_attempts: Some(1),
+                    maximum_event_age_in_seconds: None,
+                }),
+                dead_letter_config: Some(DeadLetterConfig {
+                    arn: Some("arn:aws:sqs:us-east-1:123456789012:retry-dlq".to_string()),
+                }),
+                ..Default::default()
+            }],
+        }).await.unwrap();
+        state.put_events(PutEventsRequest { entries: vec![order_event("default", "lost", now())] }).await.unwrap();
+
+        state.reset().await;
+        tokio::time::sleep(RETRY_BASE_DELAY * 3).await;
+        assert!(drain_queue(&sqs, &url).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_put_targets_rejects_invalid_retry_policy() {
+        let state = make_state();
+        state.put_rule(PutRuleRequest { name: "r".to_string(), ..Default::default() }).await.unwrap();
+        let result = state.put_targets(PutTargetsRequest {
+            rule: "r".to_string(),
+            event_bus_name: None,
+            targets: vec![Target {
+                id: "t".to_string(),
+                arn: "arn:aws:sqs:us-east-1:123456789012:q".to_string(),
+                retry_policy: Some(RetryPolicy {
+                    maximum_retry_attempts: Some(500),
+                    maximum_event_age_in_seconds: None,
+                }),
+                ..Default::default()
+            }],
+        }).await;
+        assert!(matches!(result, Err(EventBridgeError::ValidationException(_))));
+    }
 }
@@ -72,6 +72,16 @@ pub struct PutEventsRequest {
 pub struct PutEventsRequestEntry {
     #[serde(rename = "EventBusName")]
     pub event_bus_name: Option<String>,
+    #[serde(rename = "Source")]
+    pub source: Option<String>,
+    #[serde(rename = "DetailType")]
+    pub detail_type: Option<String>,
+    #[serde(rename = "Detail")]
+    pub detail: Option<String>,
+    #[serde(rename = "Resources")]
+    pub resources: Option<Vec<String>>,
+    #[serde(rename = "Time")]
+    pub time: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -181,12 +191,51 @@ pub struct Rule {
     pub name: String,
     #[serde(rename = "Arn")]
     pub arn: String,
+    #[serde(rename = "EventPattern")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_pattern: Option<String>,
+    #[serde(rename = "ScheduleExpression")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_expression: Option<String>,
     #[serde(rename = "State")]
     pub state: String,
+    #[serde(rename = "Description")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     #[serde(rename = "EventBusName")]
     pub event_bus_name: String,
 }
 
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct EnableRuleRequest {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "EventBusName")]
+    pub event_bus_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DisableRuleRequest {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "EventBusName")]
+    pub event_bus_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TestEventPatternRequest {
+    #[serde(rename = "EventPattern")]
+    pub event_pattern: String,
+    #[serde(rename = "Event")]
+    pub event: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestEventPatternResponse {
+    #[serde(rename = "Result")]
+    pub result: bool,
+}
+
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct PutTargetsRequest {
     #[serde(rename = "Rule")]
@@ -240,7 +289,7 @@ pub struct ListTargetsByRuleResponse {
     pub next_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct Target {
     #[serde(rename = "Id")]
     pub id: String,
@@ -255,6 +304,29 @@ pub struct Target {
     #[serde(rename = "InputPath")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input_path: Option<String>,
+    #[serde(rename = "RetryPolicy")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicy>,
+    #[serde(rename = "DeadLetterConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_config: Option<DeadLetterConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct RetryPolicy {
+    #[serde(rename = "MaximumRetryAttempts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_retry_attempts: Option<u32>,
+    #[serde(rename = "MaximumEventAgeInSeconds")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_event_age_in_seconds: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct DeadLetterConfig {
+    #[serde(rename = "Arn")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arn: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -292,3 +364,215 @@ pub struct ListTagsForResourceResponse {
     #[serde(rename = "Tags")]
     pub tags: Vec<Tag>,
 }
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CreateArchiveRequest {
+    #[serde(rename = "ArchiveName")]
+    pub archive_name: String,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: String,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "EventPattern")]
+    pub event_pattern: Option<String>,
+    #[serde(rename = "RetentionDays")]
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateArchiveResponse {
+    #[serde(rename = "ArchiveArn")]
+    pub archive_arn: String,
+    #[serde(rename = "State")]
+    pub state: String,
+    #[serde(rename = "CreationTime")]
+    pub creation_time: f64,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DeleteArchiveRequest {
+    #[serde(rename = "ArchiveName")]
+    pub archive_name: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DescribeArchiveRequest {
+    #[serde(rename = "ArchiveName")]
+    pub archive_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DescribeArchiveResponse {
+    #[serde(rename = "ArchiveArn")]
+    pub archive_arn: String,
+    #[serde(rename = "ArchiveName")]
+    pub archive_name: String,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: String,
+    #[serde(rename = "Description")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "EventPattern")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_pattern: Option<String>,
+    #[serde(rename = "State")]
+    pub state: String,
+    #[serde(rename = "RetentionDays")]
+    pub retention_days: u32,
+    #[serde(rename = "SizeBytes")]
+    pub size_bytes: u64,
+    #[serde(rename = "EventCount")]
+    pub event_count: u64,
+    #[serde(rename = "CreationTime")]
+    pub creation_time: f64,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ListArchivesRequest {
+    #[serde(rename = "NamePrefix")]
+    pub name_prefix: Option<String>,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: Option<String>,
+    #[serde(rename = "State")]
+    pub state: Option<String>,
+    #[serde(rename = "Limit")]
+    pub limit: Option<usize>,
+    #[serde(rename = "NextToken")]
+    pub next_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListArchivesResponse {
+    #[serde(rename = "Archives")]
+    pub archives: Vec<Archive>,
+    #[serde(rename = "NextToken")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Archive {
+    #[serde(rename = "ArchiveName")]
+    pub archive_name: String,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: String,
+    #[serde(rename = "State")]
+    pub state: String,
+    #[serde(rename = "RetentionDays")]
+    pub retention_days: u32,
+    #[serde(rename = "SizeBytes")]
+    pub size_bytes: u64,
+    #[serde(rename = "EventCount")]
+    pub event_count: u64,
+    #[serde(rename = "CreationTime")]
+    pub creation_time: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ReplayDestination {
+    #[serde(rename = "Arn")]
+    pub arn: String,
+    #[serde(rename = "FilterArns")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_arns: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StartReplayRequest {
+    #[serde(rename = "ReplayName")]
+    pub replay_name: String,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: String,
+    #[serde(rename = "EventStartTime")]
+    pub event_start_time: f64,
+    #[serde(rename = "EventEndTime")]
+    pub event_end_time: f64,
+    #[serde(rename = "Destination")]
+    pub destination: ReplayDestination,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartReplayResponse {
+    #[serde(rename = "ReplayArn")]
+    pub replay_arn: String,
+    #[serde(rename = "State")]
+    pub state: String,
+    #[serde(rename = "ReplayStartTime")]
+    pub replay_start_time: f64,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DescribeReplayRequest {
+    #[serde(rename = "ReplayName")]
+    pub replay_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DescribeReplayResponse {
+    #[serde(rename = "ReplayName")]
+    pub replay_name: String,
+    #[serde(rename = "ReplayArn")]
+    pub replay_arn: String,
+    #[serde(rename = "Description")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "State")]
+    pub state: String,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: String,
+    #[serde(rename = "Destination")]
+    pub destination: ReplayDestination,
+    #[serde(rename = "EventStartTime")]
+    pub event_start_time: f64,
+    #[serde(rename = "EventEndTime")]
+    pub event_end_time: f64,
+    #[serde(rename = "EventLastReplayedTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_last_replayed_time: Option<f64>,
+    #[serde(rename = "ReplayStartTime")]
+    pub replay_start_time: f64,
+    #[serde(rename = "ReplayEndTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_end_time: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ListReplaysRequest {
+    #[serde(rename = "NamePrefix")]
+    pub name_prefix: Option<String>,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: Option<String>,
+    #[serde(rename = "State")]
+    pub state: Option<String>,
+    #[serde(rename = "Limit")]
+    pub limit: Option<usize>,
+    #[serde(rename = "NextToken")]
+    pub next_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListReplaysResponse {
+    #[serde(rename = "Replays")]
+    pub replays: Vec<Replay>,
+    #[serde(rename = "NextToken")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Replay {
+    #[serde(rename = "ReplayName")]
+    pub replay_name: String,
+    #[serde(rename = "EventSourceArn")]
+    pub event_source_arn: String,
+    #[serde(rename = "State")]
+    pub state: String,
+    #[serde(rename = "EventStartTime")]
+    pub event_start_time: f64,
+    #[serde(rename = "EventEndTime")]
+    pub event_end_time: f64,
+    #[serde(rename = "ReplayStartTime")]
+    pub replay_start_time: f64,
+}
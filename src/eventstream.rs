@@ -0,0 +1,86 @@
+//! Encoder for the `application/vnd.amazon.eventstream` framing used by
+//! streaming AWS APIs such as Kinesis `SubscribeToShard`.
+//!
+//! Each message is laid out as:
+//! total length (u32) | headers length (u32) | prelude CRC32 |
+//! headers | payload | message CRC32, all integers big-endian.
+
+pub const CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+
+const HEADER_TYPE_STRING: u8 = 7;
+
+/// Encodes an `event` message with the given `:event-type` and JSON payload.
+pub fn encode_event(event_type: &str, payload: &[u8]) -> Vec<u8> {
+    encode_message(
+        &[
+            (":event-type", event_type),
+            (":content-type", "application/json"),
+            (":message-type", "event"),
+        ],
+        payload,
+    )
+}
+
+/// Encodes a message with string-valued headers.
+pub fn encode_message(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+    let mut header_bytes = Vec::new();
+    for (name, value) in headers {
+        header_bytes.push(name.len() as u8);
+        header_bytes.extend_from_slice(name.as_bytes());
+        header_bytes.push(HEADER_TYPE_STRING);
+        header_bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        header_bytes.extend_from_slice(value.as_bytes());
+    }
+    let total_len = 12 + header_bytes.len() + payload.len() + 4;
+    let mut message = Vec::with_capacity(total_len);
+    message.extend_from_slice(&(total_len as u32).to_be_bytes());
+    message.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+    let prelude_crc = crc32(&message);
+    message.extend_from_slice(&prelude_crc.to_be_bytes());
+    message.extend_from_slice(&header_bytes);
+    message.extend_from_slice(payload);
+    let message_crc = crc32(&message);
+    message.extend_from_slice(&message_crc.to_be_bytes());
+    message
+}
+
+/// CRC-32 (IEEE 802.3), as required by the event stream checksums.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_encode_message_layout() {
+        let msg = encode_event("SubscribeToShardEvent", b"{}");
+        let total = u32::from_be_bytes(msg[0..4].try_into().unwrap()) as usize;
+        let headers_len = u32::from_be_bytes(msg[4..8].try_into().unwrap()) as usize;
+        assert_eq!(total, msg.len());
+        assert_eq!(u32::from_be_bytes(msg[8..12].try_into().unwrap()), crc32(&msg[0..8]));
+        assert_eq!(&msg[12 + headers_len..total - 4], b"{}");
+        assert_eq!(
+            u32::from_be_bytes(msg[total - 4..].try_into().unwrap()),
+            crc32(&msg[..total - 4])
+        );
+        // First header is `:event-type` with a string value.
+        assert_eq!(msg[12] as usize, ":event-type".len());
+        assert_eq!(&msg[13..24], b":event-type");
+        assert_eq!(msg[24], HEADER_TYPE_STRING);
+    }
+}
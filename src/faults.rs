@@ -0,0 +1,321 @@
+//! Per-service fault injection: added latency and randomly injected AWS
+//! errors, for exercising client retry, backoff and timeout handling.
+//!
+//! Faults are configured at startup with `--<service>-fault-latency-ms`,
+//! `--<service>-fault-error-rate` and `--<service>-fault-error-code`, and at
+//! runtime through `/_aws/faults` on the service's own port.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
+
+pub const ADMIN_PATH: &str = "/_aws/faults";
+
+const DEFAULT_ERROR_CODE: &str = "ServiceUnavailable";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultConfig {
+    /// Delay added before every request is handled.
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Fraction of requests, from 0.0 to 1.0, answered with the injected error.
+    #[serde(default)]
+    pub error_rate: f64,
+    /// The AWS error code returned; `ServiceUnavailable` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+}
+
+impl FaultConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.error_rate) {
+            return Err(format!("error rate must be between 0 and 1, got {}", self.error_rate));
+        }
+        Ok(())
+    }
+
+    fn error_code(&self) -> &str {
+        self.error_code.as_deref().unwrap_or(DEFAULT_ERROR_CODE)
+    }
+}
+
+/// Splits `--<service>-fault-*` flags out of the command line, returning the
+/// remaining arguments and the fault settings per service. `services` lists
+/// the valid service keys (the names used in `--<service>-port`).
+pub fn extract_args(
+    args: impl IntoIterator<Item = String>,
+    services: &[String],
+) -> Result<(Vec<String>, HashMap<String, FaultConfig>), String> {
+    let mut rest = Vec::new();
+    let mut faults: HashMap<String, FaultConfig> = HashMap::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let Some((service, setting)) = arg.strip_prefix("--").and_then(|a| a.split_once("-fault-")) else {
+            rest.push(arg);
+            continue;
+        };
+        let (setting, inline) = match setting.split_once('=') {
+            Some((s, v)) => (s, Some(v.to_string())),
+            None => (setting, None),
+        };
+        if !services.iter().any(|s| s == service) {
+            return Err(format!("unknown service in {arg}"));
+        }
+        let value = match inline {
+            Some(v) => v,
+            None => args.next().ok_or_else(|| format!("missing value for {arg}"))?,
+        };
+        let config = faults.entry(service.to_string()).or_default();
+        match setting {
+            "latency-ms" => {
+                config.latency_ms = value.parse().map_err(|_| format!("invalid value '{value}' for {arg}"))?;
+            }
+            "error-rate" => {
+                config.error_rate = value.parse().map_err(|_| format!("invalid value '{value}' for {arg}"))?;
+            }
+            "error-code" => config.error_code = Some(value),
+            _ => return Err(format!("unknown fault setting in {arg}")),
+        }
+        config.validate().map_err(|e| format!("{arg}: {e}"))?;
+    }
+    Ok((rest, faults))
+}
+
+#[derive(Clone)]
+struct Injector {
+    service: &'static str,
+    config: Arc<RwLock<FaultConfig>>,
+}
+
+/// Wraps a service router with the fault-injection middleware and its
+/// `/_aws/faults` admin endpoint.
+pub fn layer(router: Router, service: &'static str, config: FaultConfig) -> Router {
+    let injector = Injector { service, config: Arc::new(RwLock::new(config)) };
+    router.layer(middleware::from_fn_with_state(injector, inject))
+}
+
+async fn inject(State(injector): State<Injector>, req: Request, next: Next) -> Response {
+    match req.uri().path() {
+        ADMIN_PATH => return admin(&injector, req).await,
+        // Health checks report readiness, not injected failures.
+        crate::healthcheck::PATH => return next.run(req).await,
+        _ => {}
+    }
+    let config = injector.config.read().unwrap().clone();
+    if config.latency_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(config.latency_ms)).await;
+    }
+    if config.error_rate > 0.0 && rand::random::<f64>() < config.error_rate {
+        return injected_error(injector.service, &req, config.error_code());
+    }
+    next.run(req).await
+}
+
+// GET returns the current settings, PUT/POST replaces them and DELETE
+// clears them.
+async fn admin(injector: &Injector, req: Request) -> Response {
+    match *req.method() {
+        Method::GET => {}
+        Method::PUT | Method::POST => {
+            let Ok(bytes) = axum::body::to_bytes(req.into_body(), 64 * 1024).await else {
+                return (StatusCode::BAD_REQUEST, Json(json!({"message": "Unreadable body"}))).into_response();
+            };
+            let config: FaultConfig = match serde_json::from_slice(&bytes) {
+                Ok(c) => c,
+                Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({"message": e.to_string()}))).into_response(),
+            };
+            if let Err(e) = config.validate() {
+                return (StatusCode::BAD_REQUEST, Json(json!({"message": e}))).into_response();
+            }
+            *injector.config.write().unwrap() = config;
+        }
+        Method::DELETE => *injector.config.write().unwrap() = FaultConfig::default(),
+        _ => return StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    }
+    let config = injector.config.read().unwrap().clone();
+    Json(json!({ "service": injector.service, "faults": config })).into_response()
+}
+
+/// The HTTP status AWS uses for common retryable and server-side errors.
+fn status_for(code: &str) -> StatusCode {
+    match code {
+        "ServiceUnavailable" | "ServiceUnavailableException" | "SlowDown" => StatusCode::SERVICE_UNAVAILABLE,
+        "ThrottlingException" | "Throttling" | "RequestLimitExceeded" | "ProvisionedThroughputExceededException"
+        | "RequestThrottled" | "RequestThrottledException" => StatusCode::BAD_REQUEST,
+        "TooManyRequestsException" => StatusCode::TOO_MANY_REQUESTS,
+        "RequestTimeout" | "RequestTimeoutException" => StatusCode::REQUEST_TIMEOUT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// An injected error, reported through the same builder as real errors.
+struct InjectedFault<'a> {
+    code: &'a str,
+}
+
+impl AwsError for InjectedFault<'_> {
+    fn code(&self) -> &str {
+        self.code
+    }
+
+    fn http_status(&self) -> StatusCode {
+        status_for(self.code)
+    }
+
+    fn message(&self) -> &str {
+        "Fault injected by the local emulator"
+    }
+}
+
+/// The protocol the injected error is reported in: JSON for `x-amz-target`
+/// requests, the service's own XML dialect for query, EC2 and S3 services,
+/// and REST JSON otherwise.
+fn protocol_for(service: &str, req: &Request) -> Protocol {
+    if req.headers().contains_key("x-amz-target") {
+        return Protocol::Json;
+    }
+    match service {
+        "ec2" => Protocol::Ec2,
+        "s3" => Protocol::RestXml,
+        "sns" => Protocol::Query("http://sns.amazonaws.com/doc/2010-03-31/"),
+        "ses" => Protocol::Query("http://ses.amazonaws.com/doc/2010-12-01/"),
+        "autoscaling" => Protocol::Query("https://autoscaling.amazonaws.com/doc/2012-10-01/"),
+        "cloudformation" => Protocol::Query("https://cloudformation.amazonaws.com/doc/2012-10-01/"),
+        "cloudsearch" => Protocol::Query("https://cloudsearch.amazonaws.com/doc/2012-10-01/"),
+        "cloudwatch" => Protocol::Query("https://cloudwatch.amazonaws.com/doc/2012-10-01/"),
+        "documentdb" => Protocol::Query("https://documentdb.amazonaws.com/doc/2012-10-01/"),
+        "elasticache" => Protocol::Query("https://elasticache.amazonaws.com/doc/2012-10-01/"),
+        "elasticbeanstalk" => Protocol::Query("https://elasticbeanstalk.amazonaws.com/doc/2012-10-01/"),
+        "elb" => Protocol::Query("https://elb.amazonaws.com/doc/2012-10-01/"),
+        "iam" => Protocol::Query("https://iam.amazonaws.com/doc/2012-10-01/"),
+        "neptune" => Protocol::Query("https://neptune.amazonaws.com/doc/2012-10-01/"),
+        "rds" => Protocol::Query("https://rds.amazonaws.com/doc/2012-10-01/"),
+        "redshift" => Protocol::Query("https://redshift.amazonaws.com/doc/2012-10-01/"),
+        _ => Protocol::RestJson,
+    }
+}
+
+fn injected_error(service: &str, req: &Request, code: &str) -> Response {
+    let protocol = protocol_for(service, req);
+    let mut resp = error_response(&InjectedFault { code }, protocol);
+    // `awsJson` clients read the body, but some also check the header.
+    if let (Protocol::Json, Ok(value)) = (protocol, HeaderValue::from_str(code)) {
+        resp.headers_mut().insert("x-amzn-ErrorType", value);
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::body::Body;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::StatusCode;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::sqs::state::SqsState;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_extract_args() {
+        let services = args(&["sqs", "sns"]);
+        let (rest, faults) = extract_args(
+            args(&["bin", "--sqs-fault-latency-ms", "50", "--region", "eu-west-1", "--sqs-fault-error-rate=0.1"]),
+            &services,
+        )
+        .unwrap();
+        assert_eq!(rest, args(&["bin", "--region", "eu-west-1"]));
+        assert_eq!(faults["sqs"], FaultConfig { latency_ms: 50, error_rate: 0.1, error_code: None });
+
+        assert!(extract_args(args(&["--s4-fault-error-rate", "0.1"]), &services).is_err());
+        assert!(extract_args(args(&["--sqs-fault-error-rate", "2"]), &services).is_err());
+        assert!(extract_args(args(&["--sqs-fault-jitter", "2"]), &services).is_err());
+        assert!(extract_args(args(&["--sqs-fault-error-code"]), &services).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_full_error_rate_returns_injected_error() {
+        let state = Arc::new(SqsState::new("123456789012".to_string(), "us-east-1".to_string(), 9324));
+        let app = layer(crate::sqs::server::create_router(state), "sqs", FaultConfig::default());
+
+        let set = Request::builder()
+            .method("PUT")
+            .uri(ADMIN_PATH)
+            .body(Body::from(r#"{"errorRate": 1.0, "errorCode": "ThrottlingException"}"#))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(set).await.unwrap().status(), StatusCode::OK);
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("x-amz-target", "AmazonSQS.ListQueues")
+            .header(CONTENT_TYPE, "application/x-amz-json-1.0")
+            .body(Body::from("{}"))
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.headers()["x-amzn-ErrorType"], "ThrottlingException");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["__type"], "ThrottlingException");
+
+        // Clearing the faults restores normal handling.
+        let clear = Request::builder().method("DELETE").uri(ADMIN_PATH).body(Body::empty()).unwrap();
+        app.clone().oneshot(clear).await.unwrap();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("x-amz-target", "AmazonSQS.ListQueues")
+            .header(CONTENT_TYPE, "application/x-amz-json-1.0")
+            .body(Body::from("{}"))
+            .unwrap();
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_query_protocol_error_and_latency() {
+        let config = FaultConfig { latency_ms: 20, error_rate: 1.0, error_code: None };
+        let app = layer(Router::new(), "sns", config);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("Action=ListTopics"))
+            .unwrap();
+        let start = std::time::Instant::now();
+        let resp = app.oneshot(req).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("<Type>Receiver</Type>\n    <Code>ServiceUnavailable</Code>"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_injected_error_code_is_escaped() {
+        let config = FaultConfig { latency_ms: 0, error_rate: 1.0, error_code: Some("Slow<Down>&".to_string()) };
+        let app = layer(Router::new(), "s3", config);
+        let req = Request::builder().uri("/bucket/key").body(Body::empty()).unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("<Code>Slow&lt;Down&gt;&amp;</Code>"), "{body}");
+    }
+}
@@ -0,0 +1,392 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use super::error::FirehoseError;
+use super::stream::StoredRecord;
+use super::types::{
+    BufferingHints, DestinationEncryptionConfiguration, ExtendedS3DestinationConfiguration,
+    ExtendedS3DestinationDescription, S3DestinationDescription,
+};
+
+const DEFAULT_PREFIX: &str = "!{timestamp:yyyy/MM/dd/HH}/";
+
+/// An object ready to be written to the destination bucket.
+pub struct S3Object {
+    pub key: String,
+    pub body: Vec<u8>,
+}
+
+/// Rejects destination settings this emulator cannot honour.
+pub fn validate(config: &ExtendedS3DestinationConfiguration) -> Result<(), FirehoseError> {
+    if !config.bucket_arn.starts_with("arn:aws:s3:::") {
+        return Err(FirehoseError::InvalidArgumentException(format!(
+            "Invalid BucketARN: {}",
+            config.bucket_arn
+        )));
+    }
+    match config.compression_format.as_deref() {
+        None | Some("UNCOMPRESSED") | Some("GZIP") | Some("Snappy") => {}
+        Some(other) => {
+            return Err(FirehoseError::InvalidArgumentException(format!(
+                "Unsupported CompressionFormat: {other}"
+            )))
+        }
+    }
+    if dynamic_partitioning(config) {
+        let prefix = config.prefix.as_deref().unwrap_or_default();
+        if !prefix.contains("!{partitionKeyFromQuery:") {
+            return Err(FirehoseError::InvalidArgumentException(
+                "Prefix must use partitionKeyFromQuery when dynamic partitioning is enabled".to_string(),
+            ));
+        }
+        if extraction_query(config).is_none() {
+            return Err(FirehoseError::InvalidArgumentException(
+                "A MetadataExtraction processor is required when dynamic partitioning is enabled"
+                    .to_string(),
+            ));
+        }
+        parse_query(extraction_query(config).unwrap_or_default())?;
+    }
+    Ok(())
+}
+
+/// Size at which buffered records are flushed without waiting for the interval.
+pub fn buffer_size_bytes(config: &ExtendedS3DestinationConfiguration) -> usize {
+    let default_mb = if dynamic_partitioning(config) { 64 } else { 5 };
+    let mb = config
+        .buffering_hints
+        .as_ref()
+        .and_then(|h| h.size_in_mbs)
+        .unwrap_or(default_mb);
+    (mb as usize) * 1024 * 1024
+}
+
+pub fn buffer_interval(config: &ExtendedS3DestinationConfiguration) -> std::time::Duration {
+    let secs = config
+        .buffering_hints
+        .as_ref()
+        .and_then(|h| h.interval_in_seconds)
+        .unwrap_or(300);
+    std::time::Duration::from_secs(secs)
+}
+
+/// Describes `config` with the buffering and compression defaults it is
+/// delivered with.
+pub fn describe(
+    config: &ExtendedS3DestinationConfiguration,
+) -> (ExtendedS3DestinationDescription, S3DestinationDescription) {
+    let buffering_hints = BufferingHints {
+        size_in_mbs: Some((buffer_size_bytes(config) / (1024 * 1024)) as u64),
+        interval_in_seconds: Some(buffer_interval(config).as_secs()),
+    };
+    let compression_format = config
+        .compression_format
+        .clone()
+        .unwrap_or_else(|| "UNCOMPRESSED".to_string());
+    let encryption = DestinationEncryptionConfiguration {
+        no_encryption_config: "NoEncryption".to_string(),
+    };
+    let legacy = S3DestinationDescription {
+        role_arn: config.role_arn.clone(),
+        bucket_arn: config.bucket_arn.clone(),
+        prefix: config.prefix.clone(),
+        error_output_prefix: config.error_output_prefix.clone(),
+        buffering_hints: buffering_hints.clone(),
+        compression_format: compression_format.clone(),
+        encryption_configuration: encryption.clone(),
+    };
+    let extended = ExtendedS3DestinationDescription {
+        role_arn: config.role_arn.clone(),
+        bucket_arn: config.bucket_arn.clone(),
+        prefix: config.prefix.clone(),
+        error_output_prefix: config.error_output_prefix.clone(),
+        buffering_hints,
+        compression_format,
+        encryption_configuration: encryption,
+        processing_configuration: config.processing_configuration.clone(),
+        dynamic_partitioning_configuration: config.dynamic_partitioning_configuration.clone(),
+        s3_backup_mode: "Disabled".to_string(),
+    };
+    (extended, legacy)
+}
+
+/// Groups `records` by partition and renders one object per partition.
+/// Records whose partition keys cannot be extracted are written under the
+/// error output prefix.
+pub fn build_objects(
+    stream_name: &str,
+    version_id: &str,
+    config: &ExtendedS3DestinationConfiguration,
+    records: Vec<StoredRecord>,
+    now: DateTime<Utc>,
+) -> Vec<S3Object> {
+    let delimiter = record_delimiter(config);
+    let query = if dynamic_partitioning(config) {
+        extraction_query(config).and_then(|q| parse_query(q).ok())
+    } else {
+        None
+    };
+
+    let mut groups: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    for record in records {
+        let prefix = match query {
+            Some(ref fields) => match extract_keys(fields, &record.data) {
+                Some(keys) => render_prefix(config.prefix.as_deref().unwrap_or(DEFAULT_PREFIX), &keys, now),
+                None => error_prefix(config, now),
+            },
+            None => render_prefix(
+                config.prefix.as_deref().filter(|p| !p.is_empty()).unwrap_or(DEFAULT_PREFIX),
+                &[],
+                now,
+            ),
+        };
+        let body = groups.entry(prefix).or_default();
+        body.extend_from_slice(&record.data);
+        if let Some(ref d) = delimiter {
+            body.extend_from_slice(d);
+        }
+    }
+
+    let format = config.compression_format.as_deref().unwrap_or("UNCOMPRESSED");
+    groups
+        .into_iter()
+        .map(|(prefix, body)| S3Object {
+            key: format!(
+                "{}{}-{}-{}-{}{}",
+                prefix,
+                stream_name,
+                version_id,
+                now.format("%Y-%m-%d-%H-%M-%S"),
+                uuid::Uuid::new_v4(),
+                extension(format)
+            ),
+            body: compress(format, &body),
+        })
+        .collect()
+}
+
+fn dynamic_partitioning(config: &ExtendedS3DestinationConfiguration) -> bool {
+    config
+        .dynamic_partitioning_configuration
+        .as_ref()
+        .is_some_and(|d| d.enabled)
+}
+
+/// Returns the value of `name` on the first enabled processor of `kind`.
+fn processor_parameter<'a>(
+    config: &'a ExtendedS3DestinationConfiguration,
+    kind: &str,
+    name: &str,
+) -> Option<Option<&'a str>> {
+    let processing = config.processing_configuration.as_ref().filter(|p| p.enabled)?;
+    let processor = processing.processors.iter().find(|p| p.processor_type == kind)?;
+    Some(
+        processor
+            .parameters
+            .iter()
+            .find(|p| p.parameter_name == name)
+            .map(|p| p.parameter_value.as_str()),
+    )
+}
+
+fn extraction_query(config: &ExtendedS3DestinationConfiguration) -> Option<&str> {
+    processor_parameter(config, "MetadataExtraction", "MetadataExtractionQuery").flatten()
+}
+
+fn record_delimiter(config: &ExtendedS3DestinationConfiguration) -> Option<Vec<u8>> {
+    processor_parameter(config, "AppendDelimiterToRecord", "Delimiter").map(|d| {
+        // The delimiter is configured as an escaped string, e.g. "\\n".
+        d.unwrap_or("\\n")
+            .replace("\\n", "\n")
+            .replace("\\t", "\t")
+            .replace("\\r", "\r")
+            .into_bytes()
+    })
+}
+
+/// Parses a jq-style extraction query such as `{customer:.customer_id,region:.meta.region}`
+/// into `(partition key, JSON path)` pairs.
+fn parse_query(query: &str) -> Result<Vec<(String, Vec<String>)>, FirehoseError> {
+    let invalid = || {
+        FirehoseError::InvalidArgumentException(format!("Unsupported MetadataExtractionQuery: {query}"))
+    };
+    let body = query
+        .trim()
+        .strip_prefix('{')
+        .and_then(|q| q.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+    body.split(',')
+        .map(|field| {
+            let (name, path) = field.split_once(':').ok_or_else(invalid)?;
+            let path = path.trim().strip_prefix('.').ok_or_else(invalid)?;
+            let segments: Vec<String> = path.split('.').map(str::to_string).collect();
+            if name.trim().is_empty() || segments.iter().any(String::is_empty) {
+                return Err(invalid());
+            }
+            Ok((name.trim().to_string(), segments))
+        })
+        .collect()
+}
+
+fn extract_keys(fields: &[(String, Vec<String>)], data: &[u8]) -> Option<Vec<(String, String)>> {
+    let record: Value = serde_json::from_slice(data).ok()?;
+    fields
+        .iter()
+        .map(|(name, path)| {
+            let value = path.iter().try_fold(&record, |v, key| v.get(key))?;
+            let rendered = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => return None,
+            };
+            Some((name.clone(), rendered))
+        })
+        .collect()
+}
+
+fn error_prefix(config: &ExtendedS3DestinationConfiguration, now: DateTime<Utc>) -> String {
+    let prefix = config
+        .error_output_prefix
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .unwrap_or("!{firehose:error-output-type}/");
+    render_prefix(prefix, &[], now).replace("!{firehose:error-output-type}", "partitioning-failed")
+}
+
+/// Expands `!{partitionKeyFromQuery:key}` and `!{timestamp:pattern}` expressions.
+fn render_prefix(prefix: &str, keys: &[(String, String)], now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    let mut rest = prefix;
+    while let Some(start) = rest.find("!{") {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let expr = &rest[start + 2..start + len];
+        match expr.split_once(':') {
+            Some(("partitionKeyFromQuery", key)) => {
+                if let Some((_, v)) = keys.iter().find(|(k, _)| k == key) {
+                    out.push_str(v);
+                }
+            }
+            Some(("timestamp", pattern)) => out.push_str(&format_timestamp(pattern, now)),
+            _ => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Converts the Java-style date pattern Firehose uses into a chrono format.
+fn format_timestamp(pattern: &str, now: DateTime<Utc>) -> String {
+    let format = pattern
+        .replace("yyyy", "%Y")
+        .replace("MM", "%m")
+        .replace("dd", "%d")
+        .replace("HH", "%H")
+        .replace("mm", "%M")
+        .replace("ss", "%S");
+    now.format(&format).to_string()
+}
+
+fn extension(format: &str) -> &'static str {
+    match format {
+        "GZIP" => ".gz",
+        "Snappy" => ".snappy",
+        _ => "",
+    }
+}
+
+fn compress(format: &str, data: &[u8]) -> Vec<u8> {
+    match format {
+        "GZIP" => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            let _ = encoder.write_all(data);
+            encoder.finish().unwrap_or_default()
+        }
+        "Snappy" => {
+            let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+            let _ = encoder.write_all(data);
+            encoder.into_inner().unwrap_or_default()
+        }
+        _ => data.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firehose::types::*;
+    use std::io::Read;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-03-05T07:08:09Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn record(data: &str) -> StoredRecord {
+        StoredRecord { data: data.as_bytes().to_vec() }
+    }
+
+    #[test]
+    fn test_render_prefix() {
+        let keys = vec![("customer".to_string(), "42".to_string())];
+        assert_eq!(
+            render_prefix("c=!{partitionKeyFromQuery:customer}/!{timestamp:yyyy/MM/dd}/", &keys, now()),
+            "c=42/2024/03/05/"
+        );
+        assert_eq!(render_prefix(DEFAULT_PREFIX, &[], now()), "2024/03/05/07/");
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let fields = parse_query("{customer:.customer_id, region:.meta.region}").unwrap();
+        assert_eq!(fields[0], ("customer".to_string(), vec!["customer_id".to_string()]));
+        assert_eq!(fields[1].1, vec!["meta".to_string(), "region".to_string()]);
+        assert!(parse_query("customer:.id").is_err());
+        assert!(parse_query("{customer:id}").is_err());
+    }
+
+    #[test]
+    fn test_build_objects_plain_with_delimiter() {
+        let config = ExtendedS3DestinationConfiguration {
+            bucket_arn: "arn:aws:s3:::b".to_string(),
+            prefix: Some("raw/".to_string()),
+            processing_configuration: Some(ProcessingConfiguration {
+                enabled: true,
+                processors: vec![Processor {
+                    processor_type: "AppendDelimiterToRecord".to_string(),
+                    parameters: vec![],
+                }],
+            }),
+            ..Default::default()
+        };
+        let objects = build_objects("s", "1", &config, vec![record("{\"a\":1}"), record("{\"a\":2}")], now());
+        assert_eq!(objects.len(), 1);
+        assert!(objects[0].key.starts_with("raw/s-1-2024-03-05-07-08-09-"));
+        assert_eq!(objects[0].body, b"{\"a\":1}\n{\"a\":2}\n");
+    }
+
+    #[test]
+    fn test_snappy_round_trip() {
+        let compressed = compress("Snappy", b"hello snappy");
+        let mut out = Vec::new();
+        snap::read::FrameDecoder::new(&compressed[..]).read_to_end(&mut out).unwrap();
+        assert_eq!(out, b"hello snappy");
+        assert_eq!(extension("Snappy"), ".snappy");
+    }
+
+    #[test]
+    fn test_validate_dynamic_partitioning_requires_partition_prefix() {
+        let config = ExtendedS3DestinationConfiguration {
+            bucket_arn: "arn:aws:s3:::b".to_string(),
+            prefix: Some("static/".to_string()),
+            dynamic_partitioning_configuration: Some(DynamicPartitioningConfiguration { enabled: true }),
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum FirehoseError {
@@ -46,13 +47,23 @@ impl FirehoseError {
     }
 }
 
+impl AwsError for FirehoseError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        FirehoseError::message(self)
+    }
+}
+
 impl IntoResponse for FirehoseError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": format!("#{}", self.error_code()),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::NamespacedJson("#"))
     }
 }
 
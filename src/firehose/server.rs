@@ -86,6 +86,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<FirehoseState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("firehose"))
         .with_state(state)
 }
 
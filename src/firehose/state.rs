@@ -1,13 +1,21 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use base64::Engine;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+use super::delivery;
+use crate::bus::ServiceBus;
+
 use super::error::FirehoseError;
-use super::stream::{DeliveryStream, StoredRecord};
+use super::stream::DeliveryStream;
 use super::types::*;
 
+/// How long a new delivery stream reports `CREATING` before it becomes `ACTIVE`.
+const STREAM_CREATE_DELAY: Duration = Duration::from_millis(500);
+
 struct FirehoseStateInner {
     streams: HashMap<String, DeliveryStream>,
     account_id: String,
@@ -16,6 +24,63 @@ struct FirehoseStateInner {
 
 pub struct FirehoseState {
     inner: Arc<Mutex<FirehoseStateInner>>,
+    bus: Arc<ServiceBus>,
+}
+
+/// Drains the buffered records of `name` and writes them to its S3
+/// destination, one object per partition.
+async fn flush_stream(inner: &Mutex<FirehoseStateInner>, bus: &ServiceBus, name: &str) {
+    let (config, objects) = {
+        let mut state = inner.lock().await;
+        let Some(stream) = state.streams.get_mut(name) else {
+            return;
+        };
+        let Some(config) = stream.s3_destination.clone() else {
+            return;
+        };
+        if stream.records.is_empty() {
+            return;
+        }
+        let records = stream.take_records();
+        let objects = delivery::build_objects(name, &stream.version_id, &config, records, chrono::Utc::now());
+        (config, objects)
+    };
+    for object in objects {
+        // A missing bucket drops the batch; there is no backup destination.
+        let _ = bus
+            .put_s3_object(&config.bucket_arn, object.key, object.body, None)
+            .await;
+    }
+}
+
+/// Flushes `name` every buffering interval until the task is aborted by a
+/// delete or reset, or the stream loses its destination. The interval is re-read each cycle so
+/// `UpdateDestination` takes effect.
+async fn flush_periodically(inner: Arc<Mutex<FirehoseStateInner>>, bus: Arc<ServiceBus>, name: String) {
+    loop {
+        let interval = {
+            let state = inner.lock().await;
+            match state.streams.get(&name).and_then(|s| s.s3_destination.as_ref()) {
+                Some(config) => delivery::buffer_interval(config),
+                None => return,
+            }
+        };
+        tokio::time::sleep(interval).await;
+        flush_stream(&inner, &bus, &name).await;
+    }
+}
+
+fn buffer_full(stream: &DeliveryStream) -> bool {
+    stream
+        .s3_destination
+        .as_ref()
+        .is_some_and(|config| stream.buffered_bytes >= delivery::buffer_size_bytes(config))
+}
+
+fn decode_record(data: &str) -> Result<Vec<u8>, FirehoseError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|_| FirehoseError::InvalidArgumentException("Record data must be valid base64".to_string()))
 }
 
 impl FirehoseState {
@@ -26,7 +91,30 @@ impl FirehoseState {
                 account_id,
                 region,
             })),
+            bus: Arc::new(ServiceBus::new()),
+        }
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        for flusher in state.streams.values().filter_map(|s| s.flusher.as_ref()) {
+            flusher.abort();
         }
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Writes S3 destination output through `bus` instead of a detached one.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Delivers everything buffered for `name` without waiting for the
+    /// buffering hints to be reached.
+    pub async fn flush_delivery_stream(&self, name: &str) {
+        flush_stream(&self.inner, &self.bus, name).await;
     }
 
     pub async fn create_delivery_stream(
@@ -58,7 +146,25 @@ impl FirehoseState {
             }
         }
 
-        state.streams.insert(req.delivery_stream_name, stream);
+        if let Some(config) = req.extended_s3_destination_configuration {
+            delivery::validate(&config)?;
+            let flusher =
+                tokio::spawn(flush_periodically(self.inner.clone(), self.bus.clone(), req.delivery_stream_name.clone()));
+            stream.flusher = Some(flusher.abort_handle());
+            stream.s3_destination = Some(config);
+        }
+
+        state.streams.insert(req.delivery_stream_name.clone(), stream);
+
+        let inner = self.inner.clone();
+        let name = req.delivery_stream_name;
+        tokio::spawn(async move {
+            tokio::time::sleep(STREAM_CREATE_DELAY).await;
+            let mut state = inner.lock().await;
+            if let Some(stream) = state.streams.get_mut(&name).filter(|s| s.status == "CREATING") {
+                stream.status = "ACTIVE".to_string();
+            }
+        });
 
         Ok(CreateDeliveryStreamResponse {
             delivery_stream_arn: arn,
@@ -71,11 +177,14 @@ impl FirehoseState {
     ) -> Result<(), FirehoseError> {
         let mut state = self.inner.lock().await;
 
-        if state.streams.remove(&req.delivery_stream_name).is_none() {
+        let Some(stream) = state.streams.remove(&req.delivery_stream_name) else {
             return Err(FirehoseError::ResourceNotFoundException(format!(
                 "Delivery stream {} under account {} not found.",
                 req.delivery_stream_name, state.account_id
             )));
+        };
+        if let Some(flusher) = stream.flusher {
+            flusher.abort();
         }
 
         Ok(())
@@ -98,29 +207,34 @@ impl FirehoseState {
                     ))
                 })?;
 
+        let described = stream.s3_destination.as_ref().map(delivery::describe);
         let destinations: Vec<DestinationDescription> = stream
             .destinations
             .iter()
             .map(|d| DestinationDescription {
                 destination_id: d.destination_id.clone(),
+                extended_s3_destination_description: described.clone().map(|(extended, _)| extended),
+                s3_destination_description: described.clone().map(|(_, legacy)| legacy),
             })
             .collect();
 
-        Ok(DescribeDeliveryStreamResponse {
-            delivery_stream_description: DeliveryStreamDescription {
-                delivery_stream_name: stream.name.clone(),
-                delivery_stream_arn: stream.arn.clone(),
-                delivery_stream_status: stream.status.clone(),
-                delivery_stream_type: stream.stream_type.clone(),
-                version_id: stream.version_id.clone(),
-                create_timestamp: stream.create_timestamp,
-                last_update_timestamp: stream.last_update_timestamp,
-                destinations,
-                has_more_destinations: false,
-                delivery_stream_encryption_configuration: EncryptionConfig {
-                    status: "DISABLED".to_string(),
-                },
+        let description = DeliveryStreamDescription {
+            delivery_stream_name: stream.name.clone(),
+            delivery_stream_arn: stream.arn.clone(),
+            delivery_stream_status: stream.status.clone(),
+            delivery_stream_type: stream.stream_type.clone(),
+            version_id: stream.version_id.clone(),
+            create_timestamp: stream.create_timestamp,
+            last_update_timestamp: stream.last_update_timestamp,
+            destinations,
+            has_more_destinations: false,
+            delivery_stream_encryption_configuration: EncryptionConfig {
+                status: "DISABLED".to_string(),
             },
+        };
+
+        Ok(DescribeDeliveryStreamResponse {
+            delivery_stream_description: description,
         })
     }
 
@@ -200,6 +314,55 @@ impl FirehoseState {
             )));
         }
 
+        if let Some(update) = req.extended_s3_destination_update {
+            let had_destination = stream.s3_destination.is_some();
+            let mut config = match stream.s3_destination.clone() {
+                Some(config) => config,
+                None => ExtendedS3DestinationConfiguration {
+                    bucket_arn: update.bucket_arn.clone().ok_or_else(|| {
+                        FirehoseError::InvalidArgumentException(
+                            "BucketARN is required to add an S3 destination".to_string(),
+                        )
+                    })?,
+                    ..Default::default()
+                },
+            };
+            if let Some(role_arn) = update.role_arn {
+                config.role_arn = Some(role_arn);
+            }
+            if let Some(bucket_arn) = update.bucket_arn {
+                config.bucket_arn = bucket_arn;
+            }
+            if let Some(prefix) = update.prefix {
+                config.prefix = Some(prefix);
+            }
+            if let Some(prefix) = update.error_output_prefix {
+                config.error_output_prefix = Some(prefix);
+            }
+            if let Some(hints) = update.buffering_hints {
+                config.buffering_hints = Some(hints);
+            }
+            if let Some(format) = update.compression_format {
+                config.compression_format = Some(format);
+            }
+            if let Some(processing) = update.processing_configuration {
+                config.processing_configuration = Some(processing);
+            }
+            if let Some(partitioning) = update.dynamic_partitioning_configuration {
+                config.dynamic_partitioning_configuration = Some(partitioning);
+            }
+            delivery::validate(&config)?;
+            stream.s3_destination = Some(config);
+            if !had_destination {
+                let flusher = tokio::spawn(flush_periodically(
+                    self.inner.clone(),
+                    self.bus.clone(),
+                    req.delivery_stream_name.clone(),
+                ));
+                stream.flusher = Some(flusher.abort_handle());
+            }
+        }
+
         // Increment version
         let current_version: u64 = stream.version_id.parse().unwrap_or(1);
         stream.version_id = (current_version + 1).to_string();
@@ -229,9 +392,15 @@ impl FirehoseState {
                     ))
                 })?;
 
+        let data = decode_record(&req.record.data)?;
         let record_id = Uuid::new_v4().to_string();
 
-        stream.records.push(StoredRecord {});
+        stream.push_record(data);
+        let should_flush = buffer_full(stream);
+        drop(state);
+        if should_flush {
+            self.flush_delivery_stream(&req.delivery_stream_name).await;
+        }
 
         Ok(PutRecordResponse {
             record_id,
@@ -271,11 +440,21 @@ impl FirehoseState {
 
         let mut responses = Vec::with_capacity(req.records.len());
 
-        for _record in req.records {
+        let data = req
+            .records
+            .iter()
+            .map(|r| decode_record(&r.data))
+            .collect::<Result<Vec<_>, _>>()?;
+        for data in data {
             let record_id = Uuid::new_v4().to_string();
-            stream.records.push(StoredRecord {});
+            stream.push_record(data);
             responses.push(PutRecordBatchResponseEntry { record_id });
         }
+        let should_flush = buffer_full(stream);
+        drop(state);
+        if should_flush {
+            self.flush_delivery_stream(&req.delivery_stream_name).await;
+        }
 
         Ok(PutRecordBatchResponse {
             failed_put_count: 0,
@@ -460,6 +639,59 @@ mod tests {
         assert!(state.delete_delivery_stream(req).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_delete_and_reset_stop_the_flusher() {
+        let state = make_state();
+        let create = |name: &str| CreateDeliveryStreamRequest {
+            delivery_stream_name: name.to_string(),
+            extended_s3_destination_configuration: Some(ExtendedS3DestinationConfiguration {
+                bucket_arn: "arn:aws:s3:::logs".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let flusher = |name: &'static str| {
+            let state = &state;
+            async move { state.inner.lock().await.streams[name].flusher.clone().unwrap() }
+        };
+
+        state.create_delivery_stream(create("first")).await.unwrap();
+        let deleted = flusher("first").await;
+        let req = DeleteDeliveryStreamRequest { delivery_stream_name: "first".to_string() };
+        state.delete_delivery_stream(req).await.unwrap();
+        // Re-creating the stream starts a fresh flusher; the old one is gone.
+        state.create_delivery_stream(create("first")).await.unwrap();
+        state.create_delivery_stream(create("second")).await.unwrap();
+        let live = [flusher("first").await, flusher("second").await];
+        tokio::task::yield_now().await;
+        assert!(deleted.is_finished());
+        assert!(live.iter().all(|f| !f.is_finished()));
+
+        state.reset().await;
+        tokio::task::yield_now().await;
+        assert!(live.iter().all(|f| f.is_finished()));
+    }
+
+    #[tokio::test]
+    async fn test_buffered_bytes_track_puts_and_takes() {
+        let state = make_state();
+        create_stream(&state, "counted").await;
+        let encode = |s: &str| base64::engine::general_purpose::STANDARD.encode(s);
+        state.put_record(PutRecordRequest {
+            delivery_stream_name: "counted".to_string(),
+            record: RecordInput { data: encode("abc") },
+        }).await.unwrap();
+        state.put_record_batch(PutRecordBatchRequest {
+            delivery_stream_name: "counted".to_string(),
+            records: vec![RecordInput { data: encode("de") }, RecordInput { data: encode("fghij") }],
+        }).await.unwrap();
+        assert_eq!(state.inner.lock().await.streams["counted"].buffered_bytes, 10);
+
+        let records = state.inner.lock().await.streams.get_mut("counted").unwrap().take_records();
+        assert_eq!(records.len(), 3);
+        assert_eq!(state.inner.lock().await.streams["counted"].buffered_bytes, 0);
+    }
+
     #[tokio::test]
     async fn test_delete_delivery_stream_not_found() {
         let state = make_state();
@@ -471,7 +703,7 @@ mod tests {
     async fn test_put_record() {
         let state = make_state();
         create_stream(&state, "rec-stream").await;
-        let req = PutRecordRequest { delivery_stream_name: "rec-stream".to_string() };
+        let req = PutRecordRequest { delivery_stream_name: "rec-stream".to_string(), record: RecordInput::default() };
         let result = state.put_record(req).await;
         assert!(result.is_ok());
     }
@@ -482,13 +714,103 @@ mod tests {
         create_stream(&state, "batch-stream").await;
         let req = PutRecordBatchRequest {
             delivery_stream_name: "batch-stream".to_string(),
-            records: vec![RecordInput {}, RecordInput {}],
+            records: vec![RecordInput::default(), RecordInput::default()],
         };
         let result = state.put_record_batch(req).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().request_responses.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_dynamic_partitioning_writes_one_gzip_object_per_partition() {
+        use std::io::Read;
+
+        let s3 = Arc::new(crate::s3::state::S3State::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        ));
+        s3.create_bucket("lake".to_string(), None).await.unwrap();
+        let bus = Arc::new(ServiceBus::new());
+        bus.attach_s3(s3.clone());
+        let state = make_state().with_bus(bus);
+
+        let config: ExtendedS3DestinationConfiguration = serde_json::from_value(serde_json::json!({
+            "BucketARN": "arn:aws:s3:::lake",
+            "Prefix": "customer=!{partitionKeyFromQuery:customer}/",
+            "CompressionFormat": "GZIP",
+            "DynamicPartitioningConfiguration": {"Enabled": true},
+            "ProcessingConfiguration": {
+                "Enabled": true,
+                "Processors": [
+                    {
+                        "Type": "MetadataExtraction",
+                        "Parameters": [
+                            {"ParameterName": "MetadataExtractionQuery", "ParameterValue": "{customer:.customer_id}"},
+                            {"ParameterName": "JsonParsingEngine", "ParameterValue": "JQ-1.6"}
+                        ]
+                    },
+                    {"Type": "AppendDelimiterToRecord", "Parameters": []}
+                ]
+            }
+        }))
+        .unwrap();
+        state
+            .create_delivery_stream(CreateDeliveryStreamRequest {
+                delivery_stream_name: "partitioned".to_string(),
+                extended_s3_destination_configuration: Some(config),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let encode = |s: &str| RecordInput {
+            data: base64::engine::general_purpose::STANDARD.encode(s),
+        };
+        state
+            .put_record_batch(PutRecordBatchRequest {
+                delivery_stream_name: "partitioned".to_string(),
+                records: vec![
+                    encode(r#"{"customer_id":"a","n":1}"#),
+                    encode(r#"{"customer_id":"b","n":2}"#),
+                    encode(r#"{"customer_id":"a","n":3}"#),
+                ],
+            })
+            .await
+            .unwrap();
+        state.flush_delivery_stream("partitioned").await;
+
+        let listing = s3
+            .list_objects_v2("lake", "", None, 1000, None, None)
+            .await
+            .unwrap();
+        let keys: Vec<String> = listing.contents.iter().map(|o| o.key.clone()).collect();
+        assert_eq!(keys.len(), 2);
+        assert!(keys[0].starts_with("customer=a/partitioned-1-") && keys[0].ends_with(".gz"));
+        assert!(keys[1].starts_with("customer=b/partitioned-1-") && keys[1].ends_with(".gz"));
+
+        let object = s3.get_object("lake", &keys[0], None).await.unwrap();
+        let mut body = String::new();
+        flate2::read::GzDecoder::new(&object.body[..])
+            .read_to_string(&mut body)
+            .unwrap();
+        assert_eq!(body, "{\"customer_id\":\"a\",\"n\":1}\n{\"customer_id\":\"a\",\"n\":3}\n");
+    }
+
+    #[tokio::test]
+    async fn test_put_record_rejects_invalid_base64() {
+        let state = make_state();
+        create_stream(&state, "b64").await;
+        let result = state
+            .put_record(PutRecordRequest {
+                delivery_stream_name: "b64".to_string(),
+                record: RecordInput {
+                    data: "not base64!".to_string(),
+                },
+            })
+            .await;
+        assert!(matches!(result, Err(FirehoseError::InvalidArgumentException(_))));
+    }
+
     #[tokio::test]
     async fn test_tag_and_list_tags() {
         let state = make_state();
@@ -617,6 +939,7 @@ mod tests {
             delivery_stream_name: "nope".to_string(),
             current_delivery_stream_version_id: "1".to_string(),
             destination_id: "d1".to_string(),
+            ..Default::default()
         }).await;
         assert!(result.is_err());
     }
@@ -629,6 +952,7 @@ mod tests {
             delivery_stream_name: "my-stream".to_string(),
             current_delivery_stream_version_id: "999".to_string(),
             destination_id: "destinationId-000000000001".to_string(),
+            ..Default::default()
         }).await;
         assert!(result.is_err());
     }
@@ -641,6 +965,7 @@ mod tests {
             delivery_stream_name: "my-stream".to_string(),
             current_delivery_stream_version_id: "1".to_string(),
             destination_id: "invalid-dest".to_string(),
+            ..Default::default()
         }).await;
         assert!(result.is_err());
     }
@@ -652,6 +977,7 @@ mod tests {
         let state = make_state();
         let result = state.put_record(PutRecordRequest {
             delivery_stream_name: "nope".to_string(),
+            record: RecordInput::default(),
         }).await;
         assert!(result.is_err());
     }
@@ -674,7 +1000,7 @@ mod tests {
         let state = make_state();
         let result = state.put_record_batch(PutRecordBatchRequest {
             delivery_stream_name: "nope".to_string(),
-            records: vec![RecordInput {}],
+            records: vec![RecordInput::default()],
         }).await;
         assert!(result.is_err());
     }
@@ -782,9 +1108,76 @@ mod tests {
         }).await.unwrap();
         let desc = &result.delivery_stream_description;
         assert_eq!(desc.delivery_stream_name, "detail-stream");
-        assert_eq!(desc.delivery_stream_status, "ACTIVE");
+        assert_eq!(desc.delivery_stream_status, "CREATING");
         assert_eq!(desc.version_id, "1");
         assert!(!desc.has_more_destinations);
         assert_eq!(desc.delivery_stream_encryption_configuration.status, "DISABLED");
     }
+
+    #[tokio::test]
+    async fn test_describe_reports_status_and_updated_destination() {
+        let state = make_state();
+        state.create_delivery_stream(CreateDeliveryStreamRequest {
+            delivery_stream_name: "s3-stream".to_string(),
+            delivery_stream_type: "DirectPut".to_string(),
+            extended_s3_destination_configuration: Some(ExtendedS3DestinationConfiguration {
+                bucket_arn: "arn:aws:s3:::logs".to_string(),
+                prefix: Some("raw/".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }).await.unwrap();
+        let describe = || state.describe_delivery_stream(DescribeDeliveryStreamRequest {
+            delivery_stream_name: "s3-stream".to_string(),
+        });
+
+        let first = describe().await.unwrap().delivery_stream_description;
+        assert_eq!(first.delivery_stream_status, "CREATING");
+        assert!(first.create_timestamp > 0.0);
+        let dest = first.destinations[0].extended_s3_destination_description.clone().unwrap();
+        assert_eq!(dest.bucket_arn, "arn:aws:s3:::logs");
+        assert_eq!(dest.prefix.as_deref(), Some("raw/"));
+        assert_eq!(dest.compression_format, "UNCOMPRESSED");
+        assert_eq!(dest.buffering_hints.size_in_mbs, Some(5));
+        assert_eq!(dest.buffering_hints.interval_in_seconds, Some(300));
+
+        // Reading the status does not advance it; only time does.
+        assert_eq!(describe().await.unwrap().delivery_stream_description.delivery_stream_status, "CREATING");
+        tokio::time::sleep(STREAM_CREATE_DELAY + Duration::from_millis(100)).await;
+        let second = describe().await.unwrap().delivery_stream_description;
+        assert_eq!(second.delivery_stream_status, "ACTIVE");
+        assert_eq!(second.version_id, "1");
+
+        state.update_destination(UpdateDestinationRequest {
+            delivery_stream_name: "s3-stream".to_string(),
+            current_delivery_stream_version_id: "1".to_string(),
+            destination_id: second.destinations[0].destination_id.clone(),
+            extended_s3_destination_update: Some(ExtendedS3DestinationUpdate {
+                prefix: Some("curated/".to_string()),
+                compression_format: Some("GZIP".to_string()),
+                buffering_hints: Some(BufferingHints { size_in_mbs: Some(1), interval_in_seconds: Some(60) }),
+                ..Default::default()
+            }),
+        }).await.unwrap();
+
+        let updated = describe().await.unwrap().delivery_stream_description;
+        assert_eq!(updated.version_id, "2");
+        assert_eq!(updated.create_timestamp, first.create_timestamp);
+        let dest = updated.destinations[0].extended_s3_destination_description.clone().unwrap();
+        assert_eq!(dest.bucket_arn, "arn:aws:s3:::logs");
+        assert_eq!(dest.prefix.as_deref(), Some("curated/"));
+        assert_eq!(dest.compression_format, "GZIP");
+        assert_eq!(dest.buffering_hints.interval_in_seconds, Some(60));
+        let legacy = updated.destinations[0].s3_destination_description.clone().unwrap();
+        assert_eq!(legacy.prefix.as_deref(), Some("curated/"));
+
+        // The old version id is now stale.
+        let err = state.update_destination(UpdateDestinationRequest {
+            delivery_stream_name: "s3-stream".to_string(),
+            current_delivery_stream_version_id: "1".to_string(),
+            destination_id: updated.destinations[0].destination_id.clone(),
+            ..Default::default()
+        }).await.unwrap_err();
+        assert!(matches!(err, FirehoseError::ConcurrentModificationException(_)));
+    }
 }
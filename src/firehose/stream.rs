@@ -1,5 +1,9 @@
 use std::collections::HashMap;
 
+use tokio::task::AbortHandle;
+
+use super::types::ExtendedS3DestinationConfiguration;
+
 #[derive(Debug, Clone)]
 pub struct DeliveryStream {
     pub name: String,
@@ -12,6 +16,11 @@ pub struct DeliveryStream {
     pub destinations: Vec<Destination>,
     pub tags: HashMap<String, String>,
     pub records: Vec<StoredRecord>,
+    /// Total size of `records`, kept as they are added and taken.
+    pub buffered_bytes: usize,
+    pub s3_destination: Option<ExtendedS3DestinationConfiguration>,
+    /// The periodic flush task, aborted when the stream is deleted.
+    pub flusher: Option<AbortHandle>,
 }
 
 #[derive(Debug, Clone)]
@@ -20,7 +29,9 @@ pub struct Destination {
 }
 
 #[derive(Debug, Clone)]
-pub struct StoredRecord {}
+pub struct StoredRecord {
+    pub data: Vec<u8>,
+}
 
 impl DeliveryStream {
     pub fn new(name: String, arn: String, stream_type: String) -> Self {
@@ -32,7 +43,7 @@ impl DeliveryStream {
         DeliveryStream {
             name,
             arn,
-            status: "ACTIVE".to_string(),
+            status: "CREATING".to_string(),
             stream_type,
             create_timestamp: now,
             last_update_timestamp: now,
@@ -42,6 +53,19 @@ impl DeliveryStream {
             }],
             tags: HashMap::new(),
             records: Vec::new(),
+            buffered_bytes: 0,
+            s3_destination: None,
+            flusher: None,
         }
     }
+
+    pub fn push_record(&mut self, data: Vec<u8>) {
+        self.buffered_bytes += data.len();
+        self.records.push(StoredRecord { data });
+    }
+
+    pub fn take_records(&mut self) -> Vec<StoredRecord> {
+        self.buffered_bytes = 0;
+        std::mem::take(&mut self.records)
+    }
 }
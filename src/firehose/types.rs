@@ -10,6 +10,70 @@ pub struct CreateDeliveryStreamRequest {
     pub delivery_stream_type: String,
     #[serde(default)]
     pub tags: Option<Vec<Tag>>,
+    #[serde(default)]
+    pub extended_s3_destination_configuration: Option<ExtendedS3DestinationConfiguration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExtendedS3DestinationConfiguration {
+    #[serde(rename = "RoleARN", default)]
+    pub role_arn: Option<String>,
+    #[serde(rename = "BucketARN")]
+    pub bucket_arn: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_output_prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffering_hints: Option<BufferingHints>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processing_configuration: Option<ProcessingConfiguration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dynamic_partitioning_configuration: Option<DynamicPartitioningConfiguration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct BufferingHints {
+    #[serde(rename = "SizeInMBs", default, skip_serializing_if = "Option::is_none")]
+    pub size_in_mbs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_in_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProcessingConfiguration {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub processors: Vec<Processor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct Processor {
+    #[serde(rename = "Type")]
+    pub processor_type: String,
+    #[serde(default)]
+    pub parameters: Vec<ProcessorParameter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProcessorParameter {
+    pub parameter_name: String,
+    pub parameter_value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct DynamicPartitioningConfiguration {
+    #[serde(default)]
+    pub enabled: bool,
 }
 
 fn default_stream_type() -> String {
@@ -65,6 +129,56 @@ pub struct DeliveryStreamDescription {
 #[serde(rename_all = "PascalCase")]
 pub struct DestinationDescription {
     pub destination_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended_s3_destination_description: Option<ExtendedS3DestinationDescription>,
+    #[serde(rename = "S3DestinationDescription", skip_serializing_if = "Option::is_none")]
+    pub s3_destination_description: Option<S3DestinationDescription>,
+}
+
+/// The destination as configured, with the defaults AWS fills in.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExtendedS3DestinationDescription {
+    #[serde(rename = "RoleARN", skip_serializing_if = "Option::is_none")]
+    pub role_arn: Option<String>,
+    #[serde(rename = "BucketARN")]
+    pub bucket_arn: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_output_prefix: Option<String>,
+    pub buffering_hints: BufferingHints,
+    pub compression_format: String,
+    pub encryption_configuration: DestinationEncryptionConfiguration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processing_configuration: Option<ProcessingConfiguration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dynamic_partitioning_configuration: Option<DynamicPartitioningConfiguration>,
+    #[serde(rename = "S3BackupMode")]
+    pub s3_backup_mode: String,
+}
+
+/// The legacy view of an S3 destination, which SDKs still read.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct S3DestinationDescription {
+    #[serde(rename = "RoleARN", skip_serializing_if = "Option::is_none")]
+    pub role_arn: Option<String>,
+    #[serde(rename = "BucketARN")]
+    pub bucket_arn: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_output_prefix: Option<String>,
+    pub buffering_hints: BufferingHints,
+    pub compression_format: String,
+    pub encryption_configuration: DestinationEncryptionConfiguration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DestinationEncryptionConfiguration {
+    pub no_encryption_config: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -101,6 +215,30 @@ pub struct UpdateDestinationRequest {
     pub delivery_stream_name: String,
     pub current_delivery_stream_version_id: String,
     pub destination_id: String,
+    #[serde(default)]
+    pub extended_s3_destination_update: Option<ExtendedS3DestinationUpdate>,
+}
+
+/// Fields to change on an S3 destination; anything absent is kept.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExtendedS3DestinationUpdate {
+    #[serde(rename = "RoleARN", default)]
+    pub role_arn: Option<String>,
+    #[serde(rename = "BucketARN", default)]
+    pub bucket_arn: Option<String>,
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub error_output_prefix: Option<String>,
+    #[serde(default)]
+    pub buffering_hints: Option<BufferingHints>,
+    #[serde(default)]
+    pub compression_format: Option<String>,
+    #[serde(default)]
+    pub processing_configuration: Option<ProcessingConfiguration>,
+    #[serde(default)]
+    pub dynamic_partitioning_configuration: Option<DynamicPartitioningConfiguration>,
 }
 
 // --- PutRecord ---
@@ -109,11 +247,17 @@ pub struct UpdateDestinationRequest {
 #[serde(rename_all = "PascalCase")]
 pub struct PutRecordRequest {
     pub delivery_stream_name: String,
+    #[serde(default)]
+    pub record: RecordInput,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "PascalCase")]
-pub struct RecordInput {}
+pub struct RecordInput {
+    /// Base64-encoded record payload.
+    #[serde(default)]
+    pub data: String,
+}
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<FISState>) -> Router {
     Router::new()
         .route("/experiment-templates", post(create_experiment_template_handler).get(list_experiment_templates_handler))
         .route("/experiment-templates/{name}", get(get_experiment_template_handler).delete(delete_experiment_template_handler))
+        .merge(crate::healthcheck::router("fis"))
         .with_state(state)
 }
 
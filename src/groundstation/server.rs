@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<GroundstationState>) -> Router {
     Router::new()
         .route("/configs", post(create_config_handler).get(list_configs_handler))
         .route("/configs/{name}", get(get_config_handler).delete(delete_config_handler))
+        .merge(crate::healthcheck::router("groundstation"))
         .with_state(state)
 }
 
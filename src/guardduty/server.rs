@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<GuarddutyState>) -> Router {
     Router::new()
         .route("/detectors", post(create_detector_handler).get(list_detectors_handler))
         .route("/detectors/{name}", get(get_detector_handler).delete(delete_detector_handler))
+        .merge(crate::healthcheck::router("guardduty"))
         .with_state(state)
 }
 
@@ -0,0 +1,45 @@
+//! `GET /_health` on every service port, for harnesses that wait for the
+//! emulator to come up.
+
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::json;
+
+pub const PATH: &str = "/_health";
+
+/// A router answering `GET /_health` with `{"status":"ok","service":...}`,
+/// merged into each service's router.
+pub fn router<S: Clone + Send + Sync + 'static>(service: &'static str) -> Router<S> {
+    Router::new().route(PATH, get(move || async move { Json(json!({ "status": "ok", "service": service })) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use crate::sqs::state::SqsState;
+
+    #[tokio::test]
+    async fn test_health_reachable_immediately_after_bind() {
+        let state = Arc::new(SqsState::new("123456789012".to_string(), "us-east-1".to_string(), 9324));
+        let app = crate::sqs::server::create_router(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // No sleep: the port accepts connections as soon as it is bound.
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /_health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        let body: serde_json::Value = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["service"], "sqs");
+    }
+}
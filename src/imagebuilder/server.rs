@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<ImagebuilderState>) -> Router {
     Router::new()
         .route("/image-pipelines", post(create_image_pipeline_handler).get(list_image_pipelines_handler))
         .route("/image-pipelines/{name}", get(get_image_pipeline_handler).delete(delete_image_pipeline_handler))
+        .merge(crate::healthcheck::router("imagebuilder"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<InspectorState>) -> Router {
     Router::new()
         .route("/findings", post(create_finding_handler).get(list_findings_handler))
         .route("/findings/{name}", get(get_finding_handler).delete(delete_finding_handler))
+        .merge(crate::healthcheck::router("inspector"))
         .with_state(state)
 }
 
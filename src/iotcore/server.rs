@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<IotcoreState>) -> Router {
     Router::new()
         .route("/things", post(create_thing_handler).get(list_things_handler))
         .route("/things/{name}", get(get_thing_handler).delete(delete_thing_handler))
+        .merge(crate::healthcheck::router("iotcore"))
         .with_state(state)
 }
 
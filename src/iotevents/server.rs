@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<IoteventsState>) -> Router {
     Router::new()
         .route("/detector-models", post(create_detector_model_handler).get(list_detector_models_handler))
         .route("/detector-models/{name}", get(get_detector_model_handler).delete(delete_detector_model_handler))
+        .merge(crate::healthcheck::router("iotevents"))
         .with_state(state)
 }
 
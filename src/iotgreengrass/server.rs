@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<IotgreengrassState>) -> Router {
     Router::new()
         .route("/components", post(create_component_handler).get(list_components_handler))
         .route("/components/{name}", get(get_component_handler).delete(delete_component_handler))
+        .merge(crate::healthcheck::router("iotgreengrass"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<IotsitewiseState>) -> Router {
     Router::new()
         .route("/assets", post(create_asset_handler).get(list_assets_handler))
         .route("/assets/{name}", get(get_asset_handler).delete(delete_asset_handler))
+        .merge(crate::healthcheck::router("iotsitewise"))
         .with_state(state)
 }
 
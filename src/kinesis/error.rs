@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum KinesisError {
@@ -8,6 +9,8 @@ pub enum KinesisError {
     ResourceInUseException(String),
     InvalidArgumentException(String),
     ExpiredIteratorException(String),
+    LimitExceededException(String),
+    ProvisionedThroughputExceededException(String),
     InvalidAction(String),
 }
 
@@ -18,6 +21,8 @@ impl KinesisError {
             KinesisError::ResourceInUseException(_) => "ResourceInUseException",
             KinesisError::InvalidArgumentException(_) => "InvalidArgumentException",
             KinesisError::ExpiredIteratorException(_) => "ExpiredIteratorException",
+            KinesisError::LimitExceededException(_) => "LimitExceededException",
+            KinesisError::ProvisionedThroughputExceededException(_) => "ProvisionedThroughputExceededException",
             KinesisError::InvalidAction(_) => "InvalidAction",
         }
     }
@@ -36,18 +41,30 @@ impl KinesisError {
             | KinesisError::ResourceInUseException(m)
             | KinesisError::InvalidArgumentException(m)
             | KinesisError::ExpiredIteratorException(m)
+            | KinesisError::LimitExceededException(m)
+            | KinesisError::ProvisionedThroughputExceededException(m)
             | KinesisError::InvalidAction(m) => m,
         }
     }
 }
 
+impl AwsError for KinesisError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        KinesisError::message(self)
+    }
+}
+
 impl IntoResponse for KinesisError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -76,6 +93,16 @@ mod tests {
         assert_eq!(err.error_code(), "ExpiredIteratorException");
     }
     #[test]
+    fn test_limitexceededexception_error_code() {
+        let err = KinesisError::LimitExceededException("test".to_string());
+        assert_eq!(err.error_code(), "LimitExceededException");
+    }
+    #[test]
+    fn test_provisionedthroughputexceededexception_error_code() {
+        let err = KinesisError::ProvisionedThroughputExceededException("test".to_string());
+        assert_eq!(err.error_code(), "ProvisionedThroughputExceededException");
+    }
+    #[test]
     fn test_invalidaction_error_code() {
         let err = KinesisError::InvalidAction("test".to_string());
         assert_eq!(err.error_code(), "InvalidAction");
@@ -1,14 +1,21 @@
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::body::Bytes;
+use axum::body::{Body, Bytes};
 use axum::extract::State;
-use axum::http::HeaderMap;
+use axum::http::{header, HeaderMap};
 use axum::response::IntoResponse;
 use axum::routing::post;
 use axum::{Json, Router};
 
+use tokio::sync::{broadcast, mpsc};
+
+use crate::aws_error::AwsError;
+use crate::eventstream;
+
 use super::error::KinesisError;
-use super::state::KinesisState;
+use super::state::{KinesisState, ShardSubscription};
 use super::types::*;
 
 macro_rules! dispatch {
@@ -71,13 +78,135 @@ async fn handle_request(
         "DecreaseStreamRetentionPeriod" => {
             dispatch_empty!(state, body, DecreaseStreamRetentionPeriodRequest, decrease_stream_retention_period)
         }
+        "StartStreamEncryption" => {
+            dispatch_empty!(state, body, StartStreamEncryptionRequest, start_stream_encryption)
+        }
+        "StopStreamEncryption" => {
+            dispatch_empty!(state, body, StopStreamEncryptionRequest, stop_stream_encryption)
+        }
+        "RegisterStreamConsumer" => {
+            dispatch!(state, body, RegisterStreamConsumerRequest, register_stream_consumer)
+        }
+        "DeregisterStreamConsumer" => {
+            dispatch_empty!(state, body, DeregisterStreamConsumerRequest, deregister_stream_consumer)
+        }
+        "DescribeStreamConsumer" => {
+            dispatch!(state, body, DescribeStreamConsumerRequest, describe_stream_consumer)
+        }
+        "ListStreamConsumers" => {
+            dispatch!(state, body, ListStreamConsumersRequest, list_stream_consumers)
+        }
+        "SubscribeToShard" => {
+            let req: SubscribeToShardRequest = serde_json::from_slice(&body)
+                .map_err(|e| KinesisError::InvalidArgumentException(e.to_string()))?;
+            let subscription = state.subscribe_to_shard(req.clone()).await?;
+            Ok(subscription_response(state, req, subscription))
+        }
         _ => Err(KinesisError::InvalidAction(format!("Unknown action: {action}"))),
     }
 }
 
+/// How long a `SubscribeToShard` stream stays open, as in AWS.
+const SUBSCRIPTION_DURATION: Duration = Duration::from_secs(300);
+
+/// Streams a subscription as an event stream: an `initial-response`, the
+/// backlog (if any), then one `SubscribeToShardEvent` per pushed record.
+/// The stream ends with an exception event if the consumer is deregistered.
+fn subscription_response(
+    state: Arc<KinesisState>,
+    req: SubscribeToShardRequest,
+    mut sub: ShardSubscription,
+) -> axum::response::Response {
+    let (tx, rx) = mpsc::channel::<Vec<u8>>(16);
+    tokio::spawn(async move {
+        let event = |records: Vec<Record>, continuation: &str| {
+            let payload = serde_json::to_vec(&SubscribeToShardEvent {
+                records,
+                continuation_sequence_number: continuation.to_string(),
+                millis_behind_latest: 0,
+            })
+            .unwrap_or_default();
+            eventstream::encode_event("SubscribeToShardEvent", &payload)
+        };
+        let exception = |error: KinesisError| {
+            let payload = serde_json::json!({ "message": error.message() }).to_string();
+            eventstream::encode_message(
+                &[
+                    (":exception-type", error.code()),
+                    (":content-type", "application/json"),
+                    (":message-type", "exception"),
+                ],
+                payload.as_bytes(),
+            )
+        };
+        if tx.send(eventstream::encode_event("initial-response", b"{}")).await.is_err() {
+            return;
+        }
+        let _ = tokio::time::timeout(SUBSCRIPTION_DURATION, async {
+            loop {
+                let backlog = std::mem::take(&mut sub.backlog);
+                if tx.send(event(backlog, &sub.continuation_sequence_number)).await.is_err() {
+                    return;
+                }
+                loop {
+                    tokio::select! {
+                        _ = sub.consumer.changed() => {
+                            let error = KinesisError::ResourceNotFoundException(format!(
+                                "Consumer {} was deregistered", req.consumer_arn
+                            ));
+                            let _ = tx.send(exception(error)).await;
+                            return;
+                        }
+                        received = sub.live.recv() => match received {
+                            Ok((shard_id, record)) if shard_id == sub.shard_id => {
+                                sub.continuation_sequence_number = record.sequence_number.clone();
+                                if tx.send(event(vec![record], &sub.continuation_sequence_number)).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(broadcast::error::RecvError::Lagged(_)) => break,
+                            Err(broadcast::error::RecvError::Closed) => return,
+                        },
+                    }
+                }
+                // The feed overflowed and dropped records: subscribe again
+                // after the last record sent, reading the missed ones back
+                // from the shard as the new backlog.
+                let position_type = if sub.continuation_sequence_number.bytes().all(|b| b == b'0') {
+                    "TRIM_HORIZON"
+                } else {
+                    "AFTER_SEQUENCE_NUMBER"
+                };
+                let resume = SubscribeToShardRequest {
+                    starting_position: StartingPosition {
+                        position_type: position_type.to_string(),
+                        sequence_number: Some(sub.continuation_sequence_number.clone()),
+                        timestamp: None,
+                    },
+                    ..req.clone()
+                };
+                match state.subscribe_to_shard(resume).await {
+                    Ok(resumed) => sub = resumed,
+                    Err(error) => {
+                        let _ = tx.send(exception(error)).await;
+                        return;
+                    }
+                }
+            }
+        })
+        .await;
+    });
+    let body = Body::from_stream(futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (Ok::<_, Infallible>(chunk), rx))
+    }));
+    ([(header::CONTENT_TYPE, eventstream::CONTENT_TYPE)], body).into_response()
+}
+
 pub fn create_router(state: Arc<KinesisState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("kinesis"))
         .with_state(state)
 }
 
@@ -174,4 +303,135 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert!(resp.status().is_client_error());
     }
+
+    #[tokio::test]
+    async fn test_subscribe_to_shard_streams_pushed_records() {
+        use http_body_util::BodyExt;
+
+        let state = Arc::new(KinesisState::new("123456789012".to_string(), "us-east-1".to_string()));
+        state.create_stream(CreateStreamRequest {
+            stream_name: "efo".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let consumer_arn = state.register_stream_consumer(RegisterStreamConsumerRequest {
+            stream_arn: "arn:aws:kinesis:us-east-1:123456789012:stream/efo".to_string(),
+            consumer_name: "app".to_string(),
+        }).await.unwrap().consumer.consumer_arn;
+        let app = create_router(state.clone());
+        let body = serde_json::json!({
+            "ConsumerARN": consumer_arn,
+            "ShardId": "shardId-000000000000",
+            "StartingPosition": {"Type": "LATEST"},
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/x-amz-json-1.1")
+            .header("x-amz-target", "Kinesis_20131202.SubscribeToShard")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], eventstream::CONTENT_TYPE);
+        let mut body = resp.into_body();
+
+        let mut next_frame = async || {
+            let frame = body.frame().await.unwrap().unwrap();
+            String::from_utf8_lossy(&frame.into_data().unwrap()).into_owned()
+        };
+        assert!(next_frame().await.contains("initial-response"));
+        assert!(next_frame().await.contains("\"Records\":[]"));
+
+        state.put_record(PutRecordRequest {
+            stream_name: Some("efo".to_string()),
+            data: "cHVzaGVk".to_string(),
+            partition_key: "pk".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let pushed = next_frame().await;
+        assert!(pushed.contains("SubscribeToShardEvent"));
+        assert!(pushed.contains("cHVzaGVk"));
+    }
+
+    /// Creates stream "efo" with consumer "app" and opens a `LATEST`
+    /// subscription to its shard through the router.
+    async fn subscribe_latest(state: &Arc<KinesisState>) -> (String, Body) {
+        state.create_stream(CreateStreamRequest {
+            stream_name: "efo".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let consumer_arn = state.register_stream_consumer(RegisterStreamConsumerRequest {
+            stream_arn: "arn:aws:kinesis:us-east-1:123456789012:stream/efo".to_string(),
+            consumer_name: "app".to_string(),
+        }).await.unwrap().consumer.consumer_arn;
+        let body = serde_json::json!({
+            "ConsumerARN": consumer_arn,
+            "ShardId": "shardId-000000000000",
+            "StartingPosition": {"Type": "LATEST"},
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/x-amz-json-1.1")
+            .header("x-amz-target", "Kinesis_20131202.SubscribeToShard")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let resp = create_router(state.clone()).oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        (consumer_arn, resp.into_body())
+    }
+
+    #[tokio::test]
+    async fn test_subscription_rereads_records_it_fell_behind_on() {
+        use http_body_util::BodyExt;
+
+        let state = Arc::new(KinesisState::new("123456789012".to_string(), "us-east-1".to_string()));
+        let (_, mut body) = subscribe_latest(&state).await;
+        // Nobody reads the stream while these are put, so the live feed
+        // overflows and the subscription has to catch up from the shard.
+        let total = 1500;
+        state.put_records(PutRecordsRequest {
+            stream_name: Some("efo".to_string()),
+            stream_arn: None,
+            records: (0..total).map(|i| PutRecordsRequestEntry {
+                data: "cmVjb3Jk".to_string(),
+                partition_key: format!("pk-{i}"),
+            }).collect(),
+        }).await.unwrap();
+
+        let mut received = Vec::new();
+        while received.len() < total {
+            let frame = tokio::time::timeout(Duration::from_secs(5), body.frame()).await.unwrap().unwrap().unwrap();
+            let frame = String::from_utf8_lossy(&frame.into_data().unwrap()).into_owned();
+            received.extend(frame.match_indices("\"PartitionKey\":\"pk-").map(|(at, _)| {
+                let key = &frame[at + 16..];
+                key[..key.find('"').unwrap()].to_string()
+            }));
+        }
+        let expected: Vec<String> = (0..total).map(|i| format!("pk-{i}")).collect();
+        assert_eq!(received, expected);
+    }
+
+    #[tokio::test]
+    async fn test_deregistering_consumer_ends_its_subscriptions() {
+        use http_body_util::BodyExt;
+
+        let state = Arc::new(KinesisState::new("123456789012".to_string(), "us-east-1".to_string()));
+        let (consumer_arn, mut body) = subscribe_latest(&state).await;
+        let mut next_frame = async || {
+            let frame = body.frame().await?.unwrap();
+            Some(String::from_utf8_lossy(&frame.into_data().unwrap()).into_owned())
+        };
+        assert!(next_frame().await.unwrap().contains("initial-response"));
+        assert!(next_frame().await.unwrap().contains("\"Records\":[]"));
+
+        state.deregister_stream_consumer(DeregisterStreamConsumerRequest {
+            consumer_arn: Some(consumer_arn),
+            ..Default::default()
+        }).await.unwrap();
+        let ended = next_frame().await.unwrap();
+        assert!(ended.contains("exception"));
+        assert!(ended.contains("ResourceNotFoundException"));
+        assert!(next_frame().await.is_none());
+    }
 }
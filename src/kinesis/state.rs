@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, watch, Mutex};
 
 use super::error::KinesisError;
 use super::types::*;
@@ -12,6 +12,7 @@ struct StoredRecord {
     data: String,
     partition_key: String,
     arrival_timestamp: f64,
+    encryption_type: String,
 }
 
 struct KinesisStream {
@@ -21,9 +22,113 @@ struct KinesisStream {
     shard_count: u32,
     retention_period_hours: u32,
     created: f64,
+    encryption_type: String, // "NONE" or "KMS"
+    key_id: Option<String>,
     tags: HashMap<String, String>,
     records: Vec<StoredRecord>, // all records across all shards
     next_sequence: u64,
+    consumers: HashMap<String, ConsumerData>,
+    feed: broadcast::Sender<(String, Record)>, // (shard_id, record) for SubscribeToShard
+    shard_usage: HashMap<String, ShardUsage>,
+}
+
+/// Writes accepted by a shard in the current one-second window.
+struct ShardUsage {
+    window_start: std::time::Instant,
+    records: u32,
+    bytes: usize,
+}
+
+impl ShardUsage {
+    /// Accounts for a record of `size` bytes, returning false if it would
+    /// exceed the shard's per-second write limits.
+    fn try_admit(&mut self, size: usize) -> bool {
+        if self.window_start.elapsed() >= std::time::Duration::from_secs(1) {
+            self.window_start = std::time::Instant::now();
+            self.records = 0;
+            self.bytes = 0;
+        }
+        if self.records >= SHARD_MAX_RECORDS_PER_SEC || self.bytes + size > SHARD_MAX_BYTES_PER_SEC {
+            return false;
+        }
+        self.records += 1;
+        self.bytes += size;
+        true
+    }
+}
+
+impl KinesisStream {
+    /// Applies the per-shard write limits, if enforced, to a record headed
+    /// for `shard_id`. The record size is its decoded data plus partition key.
+    fn admit(&mut self, enforce: bool, shard_id: &str, data: &str, partition_key: &str) -> Result<(), String> {
+        if !enforce {
+            return Ok(());
+        }
+        let size = BASE64.decode(data).map(|d| d.len()).unwrap_or(data.len()) + partition_key.len();
+        let usage = self.shard_usage.entry(shard_id.to_string()).or_insert_with(|| ShardUsage {
+            window_start: std::time::Instant::now(),
+            records: 0,
+            bytes: 0,
+        });
+        if usage.try_admit(size) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Rate exceeded for shard {} in stream {} under account {}.",
+                shard_id, self.name, self.arn.split(':').nth(4).unwrap_or_default()
+            ))
+        }
+    }
+}
+
+struct ConsumerData {
+    name: String,
+    arn: String,
+    status: String,
+    created: f64,
+    // Dropped with the consumer, which ends its subscriptions.
+    active: watch::Sender<()>,
+}
+
+impl ConsumerData {
+    fn to_consumer(&self) -> Consumer {
+        Consumer {
+            consumer_name: self.name.clone(),
+            consumer_arn: self.arn.clone(),
+            consumer_status: self.status.clone(),
+            consumer_creation_timestamp: self.created,
+        }
+    }
+}
+
+impl StoredRecord {
+    fn to_record(&self) -> Record {
+        Record {
+            sequence_number: self.sequence_number.clone(),
+            approximate_arrival_timestamp: self.arrival_timestamp,
+            data: self.data.clone(),
+            partition_key: self.partition_key.clone(),
+            encryption_type: self.encryption_type.clone(),
+        }
+    }
+}
+
+const MAX_CONSUMERS_PER_STREAM: usize = 20;
+const MIN_RETENTION_HOURS: u32 = 24;
+const MAX_RETENTION_HOURS: u32 = 8760;
+const SHARD_MAX_RECORDS_PER_SEC: u32 = 1000;
+const SHARD_MAX_BYTES_PER_SEC: usize = 1024 * 1024;
+const FEED_CAPACITY: usize = 1024;
+
+/// An enhanced fan-out subscription: records already in the shard from the
+/// starting position, followed by records pushed as they are put.
+pub struct ShardSubscription {
+    pub shard_id: String,
+    pub backlog: Vec<Record>,
+    pub live: broadcast::Receiver<(String, Record)>,
+    pub continuation_sequence_number: String,
+    /// Closes when the consumer is deregistered.
+    pub consumer: watch::Receiver<()>,
 }
 
 // Shard iterator encoding: "stream_name:shard_id:sequence_position"
@@ -42,6 +147,8 @@ struct KinesisStateInner {
 
 pub struct KinesisState {
     inner: Arc<Mutex<KinesisStateInner>>,
+    enforce_shard_limits: bool,
+    allow_cross_account: bool,
 }
 
 impl KinesisState {
@@ -53,9 +160,31 @@ impl KinesisState {
                 account_id,
                 region,
             })),
+            enforce_shard_limits: false,
+            allow_cross_account: false,
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Enables the per-shard write limits (1 MB/s and 1,000 records/s).
+    /// Writes over the limit fail with `ProvisionedThroughputExceededException`.
+    pub fn with_shard_limits(mut self, enforce: bool) -> Self {
+        self.enforce_shard_limits = enforce;
+        self
+    }
+
+    /// Accepts KMS key ARNs of other accounts and regions.
+    pub fn with_cross_account(mut self, allow: bool) -> Self {
+        self.allow_cross_account = allow;
+        self
+    }
+
     fn now() -> f64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -119,11 +248,16 @@ impl KinesisState {
             arn,
             status: "ACTIVE".to_string(),
             shard_count,
-            retention_period_hours: 24,
+            retention_period_hours: MIN_RETENTION_HOURS,
             created: Self::now(),
+            encryption_type: "NONE".to_string(),
+            key_id: None,
             tags: HashMap::new(),
             records: Vec::new(),
             next_sequence: 1,
+            consumers: HashMap::new(),
+            feed: broadcast::channel(FEED_CAPACITY).0,
+            shard_usage: HashMap::new(),
         });
         let _ = mode;
         Ok(())
@@ -159,6 +293,8 @@ impl KinesisState {
                 retention_period_hours: stream.retention_period_hours,
                 stream_creation_timestamp: stream.created,
                 enhanced_monitoring: vec![],
+                encryption_type: stream.encryption_type.clone(),
+                key_id: stream.key_id.clone(),
             },
         })
     }
@@ -181,7 +317,10 @@ impl KinesisState {
                 retention_period_hours: stream.retention_period_hours,
                 stream_creation_timestamp: stream.created,
                 open_shard_count: stream.shard_count,
+                consumer_count: stream.consumers.len(),
                 enhanced_monitoring: vec![],
+                encryption_type: stream.encryption_type.clone(),
+                key_id: stream.key_id.clone(),
             },
         })
     }
@@ -209,20 +348,26 @@ impl KinesisState {
             .ok_or_else(|| KinesisError::ResourceNotFoundException("Stream not found".to_string()))?
             .to_string();
         let stream = state.streams.get_mut(&name).unwrap();
+        let shard_idx = 0u32; // simple: all records go to shard 0
+        stream
+            .admit(self.enforce_shard_limits, &format!("shardId-{:012}", shard_idx), &req.data, &req.partition_key)
+            .map_err(KinesisError::ProvisionedThroughputExceededException)?;
         let seq = stream.next_sequence;
         stream.next_sequence += 1;
-        let shard_idx = 0u32; // simple: all records go to shard 0
         let sequence_number = format!("{:049}", seq);
-        stream.records.push(StoredRecord {
+        let record = StoredRecord {
             sequence_number: sequence_number.clone(),
             data: req.data,
             partition_key: req.partition_key,
             arrival_timestamp: Self::now(),
-        });
+            encryption_type: stream.encryption_type.clone(),
+        };
+        let _ = stream.feed.send((format!("shardId-{:012}", shard_idx), record.to_record()));
+        stream.records.push(record);
         Ok(PutRecordResponse {
             shard_id: format!("shardId-{:012}", shard_idx),
             sequence_number,
-            encryption_type: "NONE".to_string(),
+            encryption_type: stream.encryption_type.clone(),
         })
     }
 
@@ -233,25 +378,42 @@ impl KinesisState {
             .to_string();
         let stream = state.streams.get_mut(&name).unwrap();
         let mut results = Vec::with_capacity(req.records.len());
+        let mut failed_record_count = 0;
         for record in req.records {
+            let shard_id = "shardId-000000000000";
+            if let Err(message) = stream.admit(self.enforce_shard_limits, shard_id, &record.data, &record.partition_key) {
+                failed_record_count += 1;
+                results.push(PutRecordsResultEntry {
+                    shard_id: None,
+                    sequence_number: None,
+                    error_code: Some("ProvisionedThroughputExceededException".to_string()),
+                    error_message: Some(message),
+                });
+                continue;
+            }
             let seq = stream.next_sequence;
             stream.next_sequence += 1;
             let sequence_number = format!("{:049}", seq);
-            stream.records.push(StoredRecord {
+            let stored = StoredRecord {
                 sequence_number: sequence_number.clone(),
                 data: record.data,
                 partition_key: record.partition_key,
                 arrival_timestamp: Self::now(),
-            });
+                encryption_type: stream.encryption_type.clone(),
+            };
+            let _ = stream.feed.send((shard_id.to_string(), stored.to_record()));
+            stream.records.push(stored);
             results.push(PutRecordsResultEntry {
-                shard_id: "shardId-000000000000".to_string(),
-                sequence_number,
+                shard_id: Some(shard_id.to_string()),
+                sequence_number: Some(sequence_number),
+                error_code: None,
+                error_message: None,
             });
         }
         Ok(PutRecordsResponse {
-            failed_record_count: 0,
+            failed_record_count,
             records: results,
-            encryption_type: "NONE".to_string(),
+            encryption_type: stream.encryption_type.clone(),
         })
     }
 
@@ -306,13 +468,7 @@ impl KinesisState {
         let limit = req.limit.unwrap_or(10000).min(10000);
         let records_slice = &stream.records[position.min(stream.records.len())..];
         let take = records_slice.len().min(limit);
-        let records: Vec<Record> = records_slice[..take].iter().map(|r| Record {
-            sequence_number: r.sequence_number.clone(),
-            approximate_arrival_timestamp: r.arrival_timestamp,
-            data: r.data.clone(),
-            partition_key: r.partition_key.clone(),
-            encryption_type: "NONE".to_string(),
-        }).collect();
+        let records: Vec<Record> = records_slice[..take].iter().map(StoredRecord::to_record).collect();
 
         let new_position = position + take;
         let total_records = stream.records.len();
@@ -408,6 +564,9 @@ impl KinesisState {
                 "New retention period must be greater than current".to_string(),
             ));
         }
+        if req.retention_period_hours > MAX_RETENTION_HOURS {
+            return Err(Self::retention_out_of_range());
+        }
         stream.retention_period_hours = req.retention_period_hours;
         Ok(())
     }
@@ -426,9 +585,251 @@ impl KinesisState {
                 "New retention period must be less than current".to_string(),
             ));
         }
+        if req.retention_period_hours < MIN_RETENTION_HOURS {
+            return Err(Self::retention_out_of_range());
+        }
         stream.retention_period_hours = req.retention_period_hours;
         Ok(())
     }
+
+    fn retention_out_of_range() -> KinesisError {
+        KinesisError::InvalidArgumentException(format!(
+            "Minimum allowed retention period is {} hours. Maximum allowed retention period is {} hours.",
+            MIN_RETENTION_HOURS, MAX_RETENTION_HOURS
+        ))
+    }
+
+    /// Records the KMS key and marks records written from now on as
+    /// encrypted. The key is not looked up in KMS.
+    pub async fn start_stream_encryption(&self, req: StartStreamEncryptionRequest) -> Result<(), KinesisError> {
+        if req.encryption_type != "KMS" {
+            return Err(KinesisError::InvalidArgumentException(format!(
+                "Invalid EncryptionType {}, must be KMS", req.encryption_type
+            )));
+        }
+        if req.key_id.is_empty() {
+            return Err(KinesisError::InvalidArgumentException("KeyId must not be empty".to_string()));
+        }
+        let mut state = self.inner.lock().await;
+        // Key IDs and aliases are local by construction; only ARNs can
+        // point elsewhere.
+        if req.key_id.starts_with("arn:") {
+            crate::arn::check_local(&req.key_id, &state.account_id, &state.region, self.allow_cross_account)
+                .map_err(KinesisError::InvalidArgumentException)?;
+        }
+        let name = Self::resolve(&state, req.stream_name.as_deref(), req.stream_arn.as_deref())
+            .ok_or_else(|| KinesisError::ResourceNotFoundException("Stream not found".to_string()))?
+            .to_string();
+        let stream = state.streams.get_mut(&name).unwrap();
+        stream.encryption_type = req.encryption_type;
+        stream.key_id = Some(req.key_id);
+        Ok(())
+    }
+
+    pub async fn stop_stream_encryption(&self, req: StopStreamEncryptionRequest) -> Result<(), KinesisError> {
+        if req.encryption_type != "KMS" {
+            return Err(KinesisError::InvalidArgumentException(format!(
+                "Invalid EncryptionType {}, must be KMS", req.encryption_type
+            )));
+        }
+        let mut state = self.inner.lock().await;
+        let name = Self::resolve(&state, req.stream_name.as_deref(), req.stream_arn.as_deref())
+            .ok_or_else(|| KinesisError::ResourceNotFoundException("Stream not found".to_string()))?
+            .to_string();
+        let stream = state.streams.get_mut(&name).unwrap();
+        stream.encryption_type = "NONE".to_string();
+        stream.key_id = None;
+        Ok(())
+    }
+
+    /// Finds the stream and consumer name owning `consumer_arn`.
+    fn find_consumer(state: &KinesisStateInner, consumer_arn: &str) -> Option<(String, String)> {
+        state.streams.iter().find_map(|(stream_name, stream)| {
+            stream.consumers.values()
+                .find(|c| c.arn == consumer_arn)
+                .map(|c| (stream_name.clone(), c.name.clone()))
+        })
+    }
+
+    /// Resolves a consumer from either its ARN or a stream ARN plus name.
+    fn resolve_consumer(
+        state: &KinesisStateInner,
+        stream_arn: Option<&str>,
+        consumer_name: Option<&str>,
+        consumer_arn: Option<&str>,
+    ) -> Result<(String, String), KinesisError> {
+        let found = match (consumer_arn, stream_arn, consumer_name) {
+            (Some(arn), _, _) => Self::find_consumer(state, arn),
+            (None, Some(stream_arn), Some(name)) => Self::resolve(state, None, Some(stream_arn))
+                .filter(|s| state.streams[*s].consumers.contains_key(name))
+                .map(|s| (s.to_string(), name.to_string())),
+            _ => {
+                return Err(KinesisError::InvalidArgumentException(
+                    "Either ConsumerARN or both StreamARN and ConsumerName must be provided".to_string(),
+                ))
+            }
+        };
+        found.ok_or_else(|| KinesisError::ResourceNotFoundException("Consumer not found".to_string()))
+    }
+
+    pub async fn register_stream_consumer(
+        &self,
+        req: RegisterStreamConsumerRequest,
+    ) -> Result<RegisterStreamConsumerResponse, KinesisError> {
+        let mut state = self.inner.lock().await;
+        let name = Self::resolve(&state, None, Some(&req.stream_arn))
+            .ok_or_else(|| KinesisError::ResourceNotFoundException("Stream not found".to_string()))?
+            .to_string();
+        let stream = state.streams.get_mut(&name).unwrap();
+        if stream.consumers.contains_key(&req.consumer_name) {
+            return Err(KinesisError::ResourceInUseException(format!(
+                "Consumer {} already exists", req.consumer_name
+            )));
+        }
+        if stream.consumers.len() >= MAX_CONSUMERS_PER_STREAM {
+            return Err(KinesisError::LimitExceededException(format!(
+                "A stream can have at most {} registered consumers", MAX_CONSUMERS_PER_STREAM
+            )));
+        }
+        let created = Self::now();
+        let consumer = ConsumerData {
+            arn: format!("{}/consumer/{}:{}", stream.arn, req.consumer_name, created as u64),
+            name: req.consumer_name.clone(),
+            status: "ACTIVE".to_string(),
+            created,
+            active: watch::channel(()).0,
+        };
+        let resp = RegisterStreamConsumerResponse { consumer: consumer.to_consumer() };
+        stream.consumers.insert(req.consumer_name, consumer);
+        Ok(resp)
+    }
+
+    pub async fn deregister_stream_consumer(
+        &self,
+        req: DeregisterStreamConsumerRequest,
+    ) -> Result<(), KinesisError> {
+        let mut state = self.inner.lock().await;
+        let (stream_name, consumer_name) = Self::resolve_consumer(
+            &state,
+            req.stream_arn.as_deref(),
+            req.consumer_name.as_deref(),
+            req.consumer_arn.as_deref(),
+        )?;
+        state.streams.get_mut(&stream_name).unwrap().consumers.remove(&consumer_name);
+        Ok(())
+    }
+
+    pub async fn describe_stream_consumer(
+        &self,
+        req: DescribeStreamConsumerRequest,
+    ) -> Result<DescribeStreamConsumerResponse, KinesisError> {
+        let state = self.inner.lock().await;
+        let (stream_name, consumer_name) = Self::resolve_consumer(
+            &state,
+            req.stream_arn.as_deref(),
+            req.consumer_name.as_deref(),
+            req.consumer_arn.as_deref(),
+        )?;
+        let stream = &state.streams[&stream_name];
+        let consumer = &stream.consumers[&consumer_name];
+        Ok(DescribeStreamConsumerResponse {
+            consumer_description: ConsumerDescription {
+                consumer_name: consumer.name.clone(),
+                consumer_arn: consumer.arn.clone(),
+                consumer_status: consumer.status.clone(),
+                consumer_creation_timestamp: consumer.created,
+                stream_arn: stream.arn.clone(),
+            },
+        })
+    }
+
+    pub async fn list_stream_consumers(
+        &self,
+        req: ListStreamConsumersRequest,
+    ) -> Result<ListStreamConsumersResponse, KinesisError> {
+        let state = self.inner.lock().await;
+        let name = Self::resolve(&state, None, Some(&req.stream_arn))
+            .ok_or_else(|| KinesisError::ResourceNotFoundException("Stream not found".to_string()))?;
+        let mut consumers: Vec<Consumer> = state.streams[name].consumers.values()
+            .map(ConsumerData::to_consumer)
+            .collect();
+        consumers.sort_by(|a, b| a.consumer_name.cmp(&b.consumer_name));
+        if let Some(ref token) = req.next_token {
+            consumers.retain(|c| &c.consumer_name > token);
+        }
+        let limit = req.max_results.unwrap_or(100);
+        let next_token = if consumers.len() > limit {
+            consumers.truncate(limit);
+            consumers.last().map(|c| c.consumer_name.clone())
+        } else {
+            None
+        };
+        Ok(ListStreamConsumersResponse { consumers, next_token })
+    }
+
+    /// Starts an enhanced fan-out subscription for a registered consumer.
+    /// Each subscription has its own cursor, independent of shard iterators
+    /// and of other consumers.
+    pub async fn subscribe_to_shard(
+        &self,
+        req: SubscribeToShardRequest,
+    ) -> Result<ShardSubscription, KinesisError> {
+        let state = self.inner.lock().await;
+        let (stream_name, consumer_name) = Self::find_consumer(&state, &req.consumer_arn)
+            .ok_or_else(|| KinesisError::ResourceNotFoundException("Consumer not found".to_string()))?;
+        let stream = &state.streams[&stream_name];
+        if !Self::make_shards(stream.shard_count).iter().any(|s| s.shard_id == req.shard_id) {
+            return Err(KinesisError::ResourceNotFoundException(format!(
+                "Shard {} not found", req.shard_id
+            )));
+        }
+        let pos = &req.starting_position;
+        let start = match pos.position_type.as_str() {
+            "TRIM_HORIZON" => 0,
+            "LATEST" => stream.records.len(),
+            "AT_SEQUENCE_NUMBER" | "AFTER_SEQUENCE_NUMBER" => {
+                let seq = pos.sequence_number.as_ref().ok_or_else(|| {
+                    KinesisError::InvalidArgumentException("SequenceNumber is required".to_string())
+                })?;
+                let found = stream.records.iter().position(|r| &r.sequence_number == seq);
+                match (pos.position_type.as_str(), found) {
+                    ("AT_SEQUENCE_NUMBER", Some(p)) => p,
+                    (_, Some(p)) => p + 1,
+                    _ => stream.records.len(),
+                }
+            }
+            "AT_TIMESTAMP" => {
+                let ts = pos.timestamp.ok_or_else(|| {
+                    KinesisError::InvalidArgumentException("Timestamp is required".to_string())
+                })?;
+                stream.records.iter().position(|r| r.arrival_timestamp >= ts).unwrap_or(stream.records.len())
+            }
+            other => {
+                return Err(KinesisError::InvalidArgumentException(format!(
+                    "Invalid StartingPosition type: {}", other
+                )))
+            }
+        };
+        // All records are currently written to the first shard.
+        let backlog: Vec<Record> = if req.shard_id == "shardId-000000000000" {
+            stream.records[start..].iter().map(StoredRecord::to_record).collect()
+        } else {
+            Vec::new()
+        };
+        let continuation_sequence_number = backlog.last()
+            .map(|r| r.sequence_number.clone())
+            .or_else(|| start.checked_sub(1).and_then(|p| stream.records.get(p)).map(|r| r.sequence_number.clone()))
+            .unwrap_or_else(|| format!("{:049}", 0u64));
+        // Subscribing while holding the lock guarantees no record falls
+        // between the backlog and the live feed.
+        Ok(ShardSubscription {
+            shard_id: req.shard_id,
+            backlog,
+            live: stream.feed.subscribe(),
+            continuation_sequence_number,
+            consumer: stream.consumers[&consumer_name].active.subscribe(),
+        })
+    }
 }
 
 
@@ -594,6 +995,43 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_stream_encryption_reported_in_summary() {
+        let state = make_state();
+        create_stream(&state, "enc-stream").await;
+        state.start_stream_encryption(StartStreamEncryptionRequest {
+            stream_name: Some("enc-stream".to_string()),
+            encryption_type: "KMS".to_string(),
+            key_id: "alias/aws/kinesis".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let summary = state.describe_stream_summary(DescribeStreamSummaryRequest {
+            stream_name: Some("enc-stream".to_string()),
+            ..Default::default()
+        }).await.unwrap().stream_description_summary;
+        assert_eq!(summary.encryption_type, "KMS");
+        assert_eq!(summary.key_id.as_deref(), Some("alias/aws/kinesis"));
+        let put = state.put_record(PutRecordRequest {
+            stream_name: Some("enc-stream".to_string()),
+            data: "dGVzdA==".to_string(),
+            partition_key: "pk".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(put.encryption_type, "KMS");
+
+        state.stop_stream_encryption(StopStreamEncryptionRequest {
+            stream_name: Some("enc-stream".to_string()),
+            encryption_type: "KMS".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let summary = state.describe_stream_summary(DescribeStreamSummaryRequest {
+            stream_name: Some("enc-stream".to_string()),
+            ..Default::default()
+        }).await.unwrap().stream_description_summary;
+        assert_eq!(summary.encryption_type, "NONE");
+        assert!(summary.key_id.is_none());
+    }
+
     #[tokio::test]
     async fn test_list_streams_multiple() {
         let state = make_state();
@@ -1027,4 +1465,182 @@ mod tests {
         assert_eq!(result.tags.len(), 5);
         assert!(result.has_more_tags);
     }
+
+    // --- Enhanced fan-out consumers ---
+
+    async fn stream_arn(state: &KinesisState, name: &str) -> String {
+        state.describe_stream_summary(DescribeStreamSummaryRequest {
+            stream_name: Some(name.to_string()),
+            ..Default::default()
+        }).await.unwrap().stream_description_summary.stream_arn
+    }
+
+    async fn put(state: &KinesisState, stream: &str, data: &str) {
+        state.put_record(PutRecordRequest {
+            stream_name: Some(stream.to_string()),
+            data: data.to_string(),
+            partition_key: "pk".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_register_list_deregister_consumer() {
+        let state = make_state();
+        create_stream(&state, "efo").await;
+        let arn = stream_arn(&state, "efo").await;
+        let consumer = state.register_stream_consumer(RegisterStreamConsumerRequest {
+            stream_arn: arn.clone(),
+            consumer_name: "app".to_string(),
+        }).await.unwrap().consumer;
+        assert!(consumer.consumer_arn.starts_with(&format!("{}/consumer/app:", arn)));
+        assert_eq!(consumer.consumer_status, "ACTIVE");
+
+        let dup = state.register_stream_consumer(RegisterStreamConsumerRequest {
+            stream_arn: arn.clone(),
+            consumer_name: "app".to_string(),
+        }).await;
+        assert!(matches!(dup, Err(KinesisError::ResourceInUseException(_))));
+
+        let listed = state.list_stream_consumers(ListStreamConsumersRequest {
+            stream_arn: arn.clone(),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(listed.consumers.len(), 1);
+
+        state.deregister_stream_consumer(DeregisterStreamConsumerRequest {
+            consumer_arn: Some(consumer.consumer_arn),
+            ..Default::default()
+        }).await.unwrap();
+        let listed = state.list_stream_consumers(ListStreamConsumersRequest {
+            stream_arn: arn,
+            ..Default::default()
+        }).await.unwrap();
+        assert!(listed.consumers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_to_shard_receives_backlog_and_pushed_records() {
+        let state = make_state();
+        create_stream(&state, "efo").await;
+        let arn = stream_arn(&state, "efo").await;
+        put(&state, "efo", "b2xk").await;
+        let consumer_arn = state.register_stream_consumer(RegisterStreamConsumerRequest {
+            stream_arn: arn,
+            consumer_name: "app".to_string(),
+        }).await.unwrap().consumer.consumer_arn;
+
+        let subscribe = |position: &str| SubscribeToShardRequest {
+            consumer_arn: consumer_arn.clone(),
+            shard_id: "shardId-000000000000".to_string(),
+            starting_position: StartingPosition {
+                position_type: position.to_string(),
+                ..Default::default()
+            },
+        };
+        let mut from_start = state.subscribe_to_shard(subscribe("TRIM_HORIZON")).await.unwrap();
+        let mut from_latest = state.subscribe_to_shard(subscribe("LATEST")).await.unwrap();
+        assert_eq!(from_start.backlog.len(), 1);
+        assert!(from_latest.backlog.is_empty());
+
+        put(&state, "efo", "bmV3").await;
+        for sub in [&mut from_start, &mut from_latest] {
+            let (shard_id, record) = sub.live.recv().await.unwrap();
+            assert_eq!(shard_id, "shardId-000000000000");
+            assert_eq!(record.data, "bmV3");
+        }
+
+        // Polling iterators are unaffected by the push subscriptions.
+        let iterator = state.get_shard_iterator(GetShardIteratorRequest {
+            stream_name: Some("efo".to_string()),
+            shard_id: "shardId-000000000000".to_string(),
+            shard_iterator_type: "TRIM_HORIZON".to_string(),
+            ..Default::default()
+        }).await.unwrap().shard_iterator;
+        let polled = state.get_records(GetRecordsRequest {
+            shard_iterator: iterator,
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(polled.records.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_to_shard_unknown_consumer() {
+        let state = make_state();
+        let result = state.subscribe_to_shard(SubscribeToShardRequest {
+            consumer_arn: "arn:aws:kinesis:us-east-1:123456789012:stream/s/consumer/c:1".to_string(),
+            shard_id: "shardId-000000000000".to_string(),
+            starting_position: StartingPosition {
+                position_type: "LATEST".to_string(),
+                ..Default::default()
+            },
+        }).await;
+        assert!(result.is_err());
+    }
+
+    // --- Shard throughput limits ---
+
+    #[tokio::test]
+    async fn test_put_records_partial_failure_over_record_rate() {
+        let state = make_state().with_shard_limits(true);
+        create_stream(&state, "limited").await;
+        let records = (0..1200).map(|i| PutRecordsRequestEntry {
+            data: BASE64.encode(format!("record-{i}")),
+            partition_key: format!("pk-{i}"),
+        }).collect();
+        let result = state.put_records(PutRecordsRequest {
+            stream_name: Some("limited".to_string()),
+            records,
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(result.records.len(), 1200);
+        assert_eq!(result.failed_record_count, 200);
+        assert!(result.records[..1000].iter().all(|r| r.error_code.is_none() && r.sequence_number.is_some()));
+        for failed in &result.records[1000..] {
+            assert_eq!(failed.error_code.as_deref(), Some("ProvisionedThroughputExceededException"));
+            assert!(failed.sequence_number.is_none());
+            assert!(failed.error_message.as_deref().unwrap().contains("shardId-000000000000"));
+        }
+
+        // A single PutRecord in the same window is throttled as a whole.
+        let over_limit = state.put_record(PutRecordRequest {
+            stream_name: Some("limited".to_string()),
+            data: BASE64.encode("one more"),
+            partition_key: "pk".to_string(),
+            ..Default::default()
+        }).await;
+        assert!(matches!(over_limit, Err(KinesisError::ProvisionedThroughputExceededException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_put_records_byte_limit() {
+        let state = make_state().with_shard_limits(true);
+        create_stream(&state, "limited").await;
+        let big = BASE64.encode(vec![b'x'; 400 * 1024]);
+        let result = state.put_records(PutRecordsRequest {
+            stream_name: Some("limited".to_string()),
+            records: (0..3).map(|_| PutRecordsRequestEntry {
+                data: big.clone(),
+                partition_key: "pk".to_string(),
+            }).collect(),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(result.failed_record_count, 1);
+        assert!(result.records[2].error_code.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_shard_limits_off_by_default() {
+        let state = make_state();
+        create_stream(&state, "unlimited").await;
+        let result = state.put_records(PutRecordsRequest {
+            stream_name: Some("unlimited".to_string()),
+            records: (0..1500).map(|i| PutRecordsRequestEntry {
+                data: BASE64.encode(format!("{i}")),
+                partition_key: "pk".to_string(),
+            }).collect(),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(result.failed_record_count, 0);
+    }
 }
@@ -58,6 +58,10 @@ pub struct StreamDescription {
     pub stream_creation_timestamp: f64,
     #[serde(rename = "EnhancedMonitoring")]
     pub enhanced_monitoring: Vec<serde_json::Value>,
+    #[serde(rename = "EncryptionType")]
+    pub encryption_type: String,
+    #[serde(rename = "KeyId", skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -117,8 +121,14 @@ pub struct StreamDescriptionSummary {
     pub stream_creation_timestamp: f64,
     #[serde(rename = "OpenShardCount")]
     pub open_shard_count: u32,
+    #[serde(rename = "ConsumerCount")]
+    pub consumer_count: usize,
     #[serde(rename = "EnhancedMonitoring")]
     pub enhanced_monitoring: Vec<serde_json::Value>,
+    #[serde(rename = "EncryptionType")]
+    pub encryption_type: String,
+    #[serde(rename = "KeyId", skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -193,9 +203,17 @@ pub struct PutRecordsResponse {
 #[derive(Debug, Serialize)]
 pub struct PutRecordsResultEntry {
     #[serde(rename = "ShardId")]
-    pub shard_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard_id: Option<String>,
     #[serde(rename = "SequenceNumber")]
-    pub sequence_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence_number: Option<String>,
+    #[serde(rename = "ErrorCode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    #[serde(rename = "ErrorMessage")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -333,3 +351,140 @@ pub struct DecreaseStreamRetentionPeriodRequest {
     #[serde(rename = "RetentionPeriodHours")]
     pub retention_period_hours: u32,
 }
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StartStreamEncryptionRequest {
+    #[serde(rename = "StreamName")]
+    pub stream_name: Option<String>,
+    #[serde(rename = "StreamARN")]
+    pub stream_arn: Option<String>,
+    #[serde(rename = "EncryptionType")]
+    pub encryption_type: String,
+    #[serde(rename = "KeyId")]
+    pub key_id: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StopStreamEncryptionRequest {
+    #[serde(rename = "StreamName")]
+    pub stream_name: Option<String>,
+    #[serde(rename = "StreamARN")]
+    pub stream_arn: Option<String>,
+    #[serde(rename = "EncryptionType")]
+    pub encryption_type: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RegisterStreamConsumerRequest {
+    #[serde(rename = "StreamARN")]
+    pub stream_arn: String,
+    #[serde(rename = "ConsumerName")]
+    pub consumer_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegisterStreamConsumerResponse {
+    #[serde(rename = "Consumer")]
+    pub consumer: Consumer,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Consumer {
+    #[serde(rename = "ConsumerName")]
+    pub consumer_name: String,
+    #[serde(rename = "ConsumerARN")]
+    pub consumer_arn: String,
+    #[serde(rename = "ConsumerStatus")]
+    pub consumer_status: String,
+    #[serde(rename = "ConsumerCreationTimestamp")]
+    pub consumer_creation_timestamp: f64,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DeregisterStreamConsumerRequest {
+    #[serde(rename = "StreamARN")]
+    pub stream_arn: Option<String>,
+    #[serde(rename = "ConsumerName")]
+    pub consumer_name: Option<String>,
+    #[serde(rename = "ConsumerARN")]
+    pub consumer_arn: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DescribeStreamConsumerRequest {
+    #[serde(rename = "StreamARN")]
+    pub stream_arn: Option<String>,
+    #[serde(rename = "ConsumerName")]
+    pub consumer_name: Option<String>,
+    #[serde(rename = "ConsumerARN")]
+    pub consumer_arn: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DescribeStreamConsumerResponse {
+    #[serde(rename = "ConsumerDescription")]
+    pub consumer_description: ConsumerDescription,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsumerDescription {
+    #[serde(rename = "ConsumerName")]
+    pub consumer_name: String,
+    #[serde(rename = "ConsumerARN")]
+    pub consumer_arn: String,
+    #[serde(rename = "ConsumerStatus")]
+    pub consumer_status: String,
+    #[serde(rename = "ConsumerCreationTimestamp")]
+    pub consumer_creation_timestamp: f64,
+    #[serde(rename = "StreamARN")]
+    pub stream_arn: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ListStreamConsumersRequest {
+    #[serde(rename = "StreamARN")]
+    pub stream_arn: String,
+    #[serde(rename = "MaxResults")]
+    pub max_results: Option<usize>,
+    #[serde(rename = "NextToken")]
+    pub next_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListStreamConsumersResponse {
+    #[serde(rename = "Consumers")]
+    pub consumers: Vec<Consumer>,
+    #[serde(rename = "NextToken")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SubscribeToShardRequest {
+    #[serde(rename = "ConsumerARN")]
+    pub consumer_arn: String,
+    #[serde(rename = "ShardId")]
+    pub shard_id: String,
+    #[serde(rename = "StartingPosition")]
+    pub starting_position: StartingPosition,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StartingPosition {
+    #[serde(rename = "Type")]
+    pub position_type: String,
+    #[serde(rename = "SequenceNumber")]
+    pub sequence_number: Option<String>,
+    #[serde(rename = "Timestamp")]
+    pub timestamp: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscribeToShardEvent {
+    #[serde(rename = "Records")]
+    pub records: Vec<Record>,
+    #[serde(rename = "ContinuationSequenceNumber")]
+    pub continuation_sequence_number: String,
+    #[serde(rename = "MillisBehindLatest")]
+    pub millis_behind_latest: u64,
+}
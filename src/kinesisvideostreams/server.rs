@@ -55,6 +55,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<KinesisvideostreamsState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("kinesisvideostreams"))
         .with_state(state)
 }
 
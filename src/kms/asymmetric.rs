@@ -0,0 +1,275 @@
+//! Key material and operations for asymmetric KMS keys (RSA and NIST
+//! elliptic curves). Symmetric keys keep the simulated ciphertext format
+//! implemented in `state.rs`.
+
+use p256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use rsa::pkcs8::EncodePublicKey;
+use rsa::{Oaep, Pkcs1v15Sign, Pss, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use super::error::KmsError;
+
+pub enum KeyPair {
+    Rsa(Box<RsaPrivateKey>),
+    P256(p256::ecdsa::SigningKey),
+    P384(p384::ecdsa::SigningKey),
+}
+
+/// Checks that `key_usage` is allowed for `key_spec`.
+pub fn validate_spec(key_spec: &str, key_usage: &str) -> Result<(), KmsError> {
+    let allowed: &[&str] = match key_spec {
+        "SYMMETRIC_DEFAULT" => &["ENCRYPT_DECRYPT"],
+        "RSA_2048" | "RSA_3072" | "RSA_4096" => &["ENCRYPT_DECRYPT", "SIGN_VERIFY"],
+        "ECC_NIST_P256" | "ECC_NIST_P384" => &["SIGN_VERIFY"],
+        "HMAC_224" | "HMAC_256" | "HMAC_384" | "HMAC_512" => &["GENERATE_VERIFY_MAC"],
+        other => {
+            return Err(KmsError::UnsupportedOperationException(format!(
+                "KeySpec {other} is not supported"
+            )))
+        }
+    };
+    if !allowed.contains(&key_usage) {
+        return Err(KmsError::InvalidParameterException(format!(
+            "KeyUsage {key_usage} is not compatible with KeySpec {key_spec}"
+        )));
+    }
+    Ok(())
+}
+
+/// Generates key material for an asymmetric `key_spec`; `None` for
+/// symmetric keys.
+pub fn generate(key_spec: &str) -> Result<Option<KeyPair>, KmsError> {
+    let mut rng = rand::thread_rng();
+    let bits = match key_spec {
+        "RSA_2048" => 2048,
+        "RSA_3072" => 3072,
+        "RSA_4096" => 4096,
+        "ECC_NIST_P256" => return Ok(Some(KeyPair::P256(p256::ecdsa::SigningKey::random(&mut rng)))),
+        "ECC_NIST_P384" => return Ok(Some(KeyPair::P384(p384::ecdsa::SigningKey::random(&mut rng)))),
+        _ => return Ok(None),
+    };
+    let key = RsaPrivateKey::new(&mut rng, bits)
+        .map_err(|e| KmsError::InvalidParameterException(format!("Key generation failed: {e}")))?;
+    Ok(Some(KeyPair::Rsa(Box::new(key))))
+}
+
+pub fn signing_algorithms(key_spec: &str) -> Vec<String> {
+    let algorithms: &[&str] = match key_spec {
+        "RSA_2048" | "RSA_3072" | "RSA_4096" => &[
+            "RSASSA_PKCS1_V1_5_SHA_256",
+            "RSASSA_PKCS1_V1_5_SHA_384",
+            "RSASSA_PKCS1_V1_5_SHA_512",
+            "RSASSA_PSS_SHA_256",
+            "RSASSA_PSS_SHA_384",
+            "RSASSA_PSS_SHA_512",
+        ],
+        "ECC_NIST_P256" => &["ECDSA_SHA_256"],
+        "ECC_NIST_P384" => &["ECDSA_SHA_384"],
+        _ => &[],
+    };
+    algorithms.iter().map(|a| a.to_string()).collect()
+}
+
+pub fn encryption_algorithms(key_spec: &str) -> Vec<String> {
+    match key_spec {
+        "RSA_2048" | "RSA_3072" | "RSA_4096" => {
+            vec!["RSAES_OAEP_SHA_1".to_string(), "RSAES_OAEP_SHA_256".to_string()]
+        }
+        _ => vec!["SYMMETRIC_DEFAULT".to_string()],
+    }
+}
+
+enum Hash {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Hash {
+    fn for_algorithm(algorithm: &str) -> Hash {
+        if algorithm.ends_with("384") {
+            Hash::Sha384
+        } else if algorithm.ends_with("512") {
+            Hash::Sha512
+        } else {
+            Hash::Sha256
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Hash::Sha256 => 32,
+            Hash::Sha384 => 48,
+            Hash::Sha512 => 64,
+        }
+    }
+
+    /// Hashes a `RAW` message, or checks the length of a `DIGEST`.
+    fn digest(&self, message: &[u8], message_type: Option<&str>) -> Result<Vec<u8>, KmsError> {
+        match message_type.unwrap_or("RAW") {
+            "RAW" => Ok(match self {
+                Hash::Sha256 => Sha256::digest(message).to_vec(),
+                Hash::Sha384 => Sha384::digest(message).to_vec(),
+                Hash::Sha512 => Sha512::digest(message).to_vec(),
+            }),
+            "DIGEST" if message.len() == self.len() => Ok(message.to_vec()),
+            "DIGEST" => Err(KmsError::InvalidParameterException(format!(
+                "Digest must be {} bytes for this signing algorithm",
+                self.len()
+            ))),
+            other => Err(KmsError::InvalidParameterException(format!(
+                "Invalid MessageType: {other}"
+            ))),
+        }
+    }
+
+    fn pkcs1v15(&self) -> Pkcs1v15Sign {
+        match self {
+            Hash::Sha256 => Pkcs1v15Sign::new::<Sha256>(),
+            Hash::Sha384 => Pkcs1v15Sign::new::<Sha384>(),
+            Hash::Sha512 => Pkcs1v15Sign::new::<Sha512>(),
+        }
+    }
+
+    fn pss(&self) -> Pss {
+        match self {
+            Hash::Sha256 => Pss::new::<Sha256>(),
+            Hash::Sha384 => Pss::new::<Sha384>(),
+            Hash::Sha512 => Pss::new::<Sha512>(),
+        }
+    }
+}
+
+fn unsupported_algorithm(algorithm: &str) -> KmsError {
+    KmsError::InvalidKeyUsageException(format!(
+        "Algorithm {algorithm} is not supported by this key"
+    ))
+}
+
+impl KeyPair {
+    /// DER-encoded SubjectPublicKeyInfo, as returned by `GetPublicKey`.
+    pub fn public_key_der(&self) -> Vec<u8> {
+        let der = match self {
+            KeyPair::Rsa(key) => RsaPublicKey::from(key.as_ref()).to_public_key_der(),
+            KeyPair::P256(key) => key.verifying_key().to_public_key_der(),
+            KeyPair::P384(key) => key.verifying_key().to_public_key_der(),
+        };
+        der.map(|d| d.as_bytes().to_vec()).unwrap_or_default()
+    }
+
+    pub fn sign(
+        &self,
+        algorithm: &str,
+        message: &[u8],
+        message_type: Option<&str>,
+    ) -> Result<Vec<u8>, KmsError> {
+        let hash = Hash::for_algorithm(algorithm);
+        let digest = hash.digest(message, message_type)?;
+        let sign_error = |e: &dyn std::fmt::Display| {
+            KmsError::InvalidParameterException(format!("Signing failed: {e}"))
+        };
+        match (self, algorithm) {
+            (KeyPair::Rsa(key), a) if a.starts_with("RSASSA_PKCS1_V1_5_") => key
+                .sign(hash.pkcs1v15(), &digest)
+                .map_err(|e| sign_error(&e)),
+            (KeyPair::Rsa(key), a) if a.starts_with("RSASSA_PSS_") => key
+                .sign_with_rng(&mut rand::thread_rng(), hash.pss(), &digest)
+                .map_err(|e| sign_error(&e)),
+            (KeyPair::P256(key), "ECDSA_SHA_256") => {
+                let signature: p256::ecdsa::Signature =
+                    key.sign_prehash(&digest).map_err(|e| sign_error(&e))?;
+                Ok(signature.to_der().as_bytes().to_vec())
+            }
+            (KeyPair::P384(key), "ECDSA_SHA_384") => {
+                let signature: p384::ecdsa::Signature =
+                    key.sign_prehash(&digest).map_err(|e| sign_error(&e))?;
+                Ok(signature.to_der().as_bytes().to_vec())
+            }
+            _ => Err(unsupported_algorithm(algorithm)),
+        }
+    }
+
+    pub fn verify(
+        &self,
+        algorithm: &str,
+        message: &[u8],
+        message_type: Option<&str>,
+        signature: &[u8],
+    ) -> Result<bool, KmsError> {
+        let hash = Hash::for_algorithm(algorithm);
+        let digest = hash.digest(message, message_type)?;
+        let valid = match (self, algorithm) {
+            (KeyPair::Rsa(key), a) if a.starts_with("RSASSA_PKCS1_V1_5_") => RsaPublicKey::from(key.as_ref())
+                .verify(hash.pkcs1v15(), &digest, signature)
+                .is_ok(),
+            (KeyPair::Rsa(key), a) if a.starts_with("RSASSA_PSS_") => RsaPublicKey::from(key.as_ref())
+                .verify(hash.pss(), &digest, signature)
+                .is_ok(),
+            (KeyPair::P256(key), "ECDSA_SHA_256") => p256::ecdsa::Signature::from_der(signature)
+                .is_ok_and(|s| key.verifying_key().verify_prehash(&digest, &s).is_ok()),
+            (KeyPair::P384(key), "ECDSA_SHA_384") => p384::ecdsa::Signature::from_der(signature)
+                .is_ok_and(|s| key.verifying_key().verify_prehash(&digest, &s).is_ok()),
+            _ => return Err(unsupported_algorithm(algorithm)),
+        };
+        Ok(valid)
+    }
+
+    pub fn encrypt(&self, algorithm: &str, plaintext: &[u8]) -> Result<Vec<u8>, KmsError> {
+        let KeyPair::Rsa(key) = self else {
+            return Err(unsupported_algorithm(algorithm));
+        };
+        let public = RsaPublicKey::from(key.as_ref());
+        let mut rng = rand::thread_rng();
+        let result = match algorithm {
+            "RSAES_OAEP_SHA_1" => public.encrypt(&mut rng, Oaep::new::<sha1::Sha1>(), plaintext),
+            "RSAES_OAEP_SHA_256" => public.encrypt(&mut rng, Oaep::new::<Sha256>(), plaintext),
+            _ => return Err(unsupported_algorithm(algorithm)),
+        };
+        result.map_err(|e| KmsError::InvalidParameterException(format!("Encryption failed: {e}")))
+    }
+
+    pub fn decrypt(&self, algorithm: &str, ciphertext: &[u8]) -> Result<Vec<u8>, KmsError> {
+        let KeyPair::Rsa(key) = self else {
+            return Err(unsupported_algorithm(algorithm));
+        };
+        let result = match algorithm {
+            "RSAES_OAEP_SHA_1" => key.decrypt(Oaep::new::<sha1::Sha1>(), ciphertext),
+            "RSAES_OAEP_SHA_256" => key.decrypt(Oaep::new::<Sha256>(), ciphertext),
+            _ => return Err(unsupported_algorithm(algorithm)),
+        };
+        result.map_err(|_| KmsError::InvalidCiphertextException("Unable to decrypt ciphertext".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_spec() {
+        assert!(validate_spec("RSA_2048", "SIGN_VERIFY").is_ok());
+        assert!(validate_spec("RSA_2048", "ENCRYPT_DECRYPT").is_ok());
+        assert!(validate_spec("ECC_NIST_P256", "ENCRYPT_DECRYPT").is_err());
+        assert!(validate_spec("SYMMETRIC_DEFAULT", "SIGN_VERIFY").is_err());
+        assert!(validate_spec("SM2", "SIGN_VERIFY").is_err());
+    }
+
+    #[test]
+    fn test_ecdsa_digest_message_type() {
+        let key = generate("ECC_NIST_P256").unwrap().unwrap();
+        let digest = Sha256::digest(b"payload").to_vec();
+        let signature = key.sign("ECDSA_SHA_256", &digest, Some("DIGEST")).unwrap();
+        assert!(key.verify("ECDSA_SHA_256", b"payload", Some("RAW"), &signature).unwrap());
+        assert!(key.sign("ECDSA_SHA_256", b"short", Some("DIGEST")).is_err());
+        assert!(key.sign("RSASSA_PSS_SHA_256", b"payload", None).is_err());
+    }
+
+    #[test]
+    fn test_p384_public_key_der() {
+        let key = generate("ECC_NIST_P384").unwrap().unwrap();
+        let der = key.public_key_der();
+        // SEQUENCE header of a SubjectPublicKeyInfo.
+        assert_eq!(der[0], 0x30);
+        assert!(generate("SYMMETRIC_DEFAULT").unwrap().is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum KmsError {
@@ -8,6 +9,10 @@ pub enum KmsError {
     InvalidCiphertextException(String),
     DisabledException(String),
     InvalidParameterException(String),
+    InvalidKeyUsageException(String),
+    KMSInvalidSignatureException(String),
+    UnsupportedOperationException(String),
+    AccessDeniedException(String),
     InvalidAction(String),
 }
 
@@ -18,6 +23,10 @@ impl KmsError {
             KmsError::InvalidCiphertextException(_) => "InvalidCiphertextException",
             KmsError::DisabledException(_) => "DisabledException",
             KmsError::InvalidParameterException(_) => "InvalidParameterException",
+            KmsError::InvalidKeyUsageException(_) => "InvalidKeyUsageException",
+            KmsError::KMSInvalidSignatureException(_) => "KMSInvalidSignatureException",
+            KmsError::UnsupportedOperationException(_) => "UnsupportedOperationException",
+            KmsError::AccessDeniedException(_) => "AccessDeniedException",
             KmsError::InvalidAction(_) => "InvalidAction",
         }
     }
@@ -28,6 +37,10 @@ impl KmsError {
             KmsError::InvalidCiphertextException(_) => StatusCode::BAD_REQUEST,
             KmsError::DisabledException(_) => StatusCode::BAD_REQUEST,
             KmsError::InvalidParameterException(_) => StatusCode::BAD_REQUEST,
+            KmsError::InvalidKeyUsageException(_) => StatusCode::BAD_REQUEST,
+            KmsError::KMSInvalidSignatureException(_) => StatusCode::BAD_REQUEST,
+            KmsError::UnsupportedOperationException(_) => StatusCode::BAD_REQUEST,
+            KmsError::AccessDeniedException(_) => StatusCode::BAD_REQUEST,
             KmsError::InvalidAction(_) => StatusCode::BAD_REQUEST,
         }
     }
@@ -38,18 +51,32 @@ impl KmsError {
             | KmsError::InvalidCiphertextException(m)
             | KmsError::DisabledException(m)
             | KmsError::InvalidParameterException(m)
+            | KmsError::InvalidKeyUsageException(m)
+            | KmsError::KMSInvalidSignatureException(m)
+            | KmsError::UnsupportedOperationException(m)
+            | KmsError::AccessDeniedException(m)
             | KmsError::InvalidAction(m) => m,
         }
     }
 }
 
+impl AwsError for KmsError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        KmsError::message(self)
+    }
+}
+
 impl IntoResponse for KmsError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -83,6 +110,20 @@ mod tests {
         assert_eq!(err.error_code(), "InvalidAction");
     }
     #[test]
+    fn test_asymmetric_error_codes() {
+        let err = KmsError::InvalidKeyUsageException("test".to_string());
+        assert_eq!(err.error_code(), "InvalidKeyUsageException");
+        let err = KmsError::KMSInvalidSignatureException("test".to_string());
+        assert_eq!(err.error_code(), "KMSInvalidSignatureException");
+        let err = KmsError::UnsupportedOperationException("test".to_string());
+        assert_eq!(err.error_code(), "UnsupportedOperationException");
+    }
+    #[test]
+    fn test_accessdeniedexception_error_code() {
+        let err = KmsError::AccessDeniedException("test".to_string());
+        assert_eq!(err.error_code(), "AccessDeniedException");
+    }
+    #[test]
     fn test_message() {
         let err = KmsError::NotFoundException("hello world".to_string());
         assert_eq!(err.message(), "hello world");
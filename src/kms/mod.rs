@@ -1,3 +1,4 @@
+pub mod asymmetric;
 pub mod error;
 pub mod server;
 pub mod state;
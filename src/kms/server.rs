@@ -29,6 +29,22 @@ macro_rules! dispatch_empty {
     }};
 }
 
+// Like `dispatch!`, but records the calling principal for key policy enforcement.
+macro_rules! dispatch_as_caller {
+    ($state:expr, $headers:expr, $body:expr, $req_type:ty, $method:ident) => {{
+        let mut req: $req_type = serde_json::from_slice(&$body)
+            .map_err(|e| KmsError::InvalidParameterException(e.to_string()))?;
+        req.caller = caller(&$state, &$headers);
+        let resp = $state.$method(req).await?;
+        Ok(Json(resp).into_response())
+    }};
+}
+
+fn caller(state: &KmsState, headers: &HeaderMap) -> Option<String> {
+    let header = state.principal_header()?;
+    headers.get(header).and_then(|v| v.to_str().ok()).map(str::to_string)
+}
+
 async fn handle_request(
     State(state): State<Arc<KmsState>>,
     headers: HeaderMap,
@@ -55,8 +71,8 @@ async fn handle_request(
         }
         "EnableKey" => dispatch_empty!(state, body, EnableKeyRequest, enable_key),
         "DisableKey" => dispatch_empty!(state, body, DisableKeyRequest, disable_key),
-        "Encrypt" => dispatch!(state, body, EncryptRequest, encrypt),
-        "Decrypt" => dispatch!(state, body, DecryptRequest, decrypt),
+        "Encrypt" => dispatch_as_caller!(state, headers, body, EncryptRequest, encrypt),
+        "Decrypt" => dispatch_as_caller!(state, headers, body, DecryptRequest, decrypt),
         "GenerateDataKey" => dispatch!(state, body, GenerateDataKeyRequest, generate_data_key),
         "GenerateDataKeyWithoutPlaintext" => {
             dispatch!(
@@ -67,8 +83,9 @@ async fn handle_request(
             )
         }
         "GenerateRandom" => dispatch!(state, body, GenerateRandomRequest, generate_random),
-        "Sign" => dispatch!(state, body, SignRequest, sign),
+        "Sign" => dispatch_as_caller!(state, headers, body, SignRequest, sign),
         "Verify" => dispatch!(state, body, VerifyRequest, verify),
+        "GetPublicKey" => dispatch!(state, body, GetPublicKeyRequest, get_public_key),
         "TagResource" => dispatch_empty!(state, body, TagResourceRequest, tag_resource),
         "UntagResource" => dispatch_empty!(state, body, UntagResourceRequest, untag_resource),
         "ListResourceTags" => {
@@ -79,6 +96,10 @@ async fn handle_request(
         "ListAliases" => dispatch!(state, body, ListAliasesRequest, list_aliases),
         "GetKeyPolicy" => dispatch!(state, body, GetKeyPolicyRequest, get_key_policy),
         "PutKeyPolicy" => dispatch_empty!(state, body, PutKeyPolicyRequest, put_key_policy),
+        "CreateGrant" => dispatch!(state, body, CreateGrantRequest, create_grant),
+        "ListGrants" => dispatch!(state, body, ListGrantsRequest, list_grants),
+        "RevokeGrant" => dispatch_empty!(state, body, RevokeGrantRequest, revoke_grant),
+        "RetireGrant" => dispatch_empty!(state, body, RetireGrantRequest, retire_grant),
         _ => Err(KmsError::InvalidAction(format!("Unknown action: {action}"))),
     }
 }
@@ -86,6 +107,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<KmsState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("kms"))
         .with_state(state)
 }
 
@@ -196,4 +218,34 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_encrypt_reads_principal_header() {
+        let state = Arc::new(
+            KmsState::new("123456789012".to_string(), "us-east-1".to_string())
+                .with_policy_enforcement(Some("x-caller".to_string())),
+        );
+        let key = state.create_key(CreateKeyRequest::default()).await.unwrap();
+        let key_id = key.key_metadata.key_id;
+        state.put_key_policy(PutKeyPolicyRequest {
+            key_id: key_id.clone(),
+            policy: r#"{"Statement":[{"Effect":"Allow","Principal":{"AWS":"arn:aws:iam::123456789012:role/admin"},"Action":"kms:Encrypt"}]}"#.to_string(),
+        }).await.unwrap();
+        let body = format!(r#"{{"KeyId":"{key_id}","Plaintext":"ZGF0YQ=="}}"#);
+        for (principal, expected) in [
+            ("arn:aws:iam::123456789012:role/admin", StatusCode::OK),
+            ("arn:aws:iam::123456789012:role/other", StatusCode::BAD_REQUEST),
+        ] {
+            let req = Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/x-amz-json-1.1")
+                .header("x-amz-target", "TrentService.Encrypt")
+                .header("x-caller", principal)
+                .body(Body::from(body.clone()))
+                .unwrap();
+            let resp = create_router(state.clone()).oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), expected);
+        }
+    }
 }
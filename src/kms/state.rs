@@ -5,6 +5,7 @@ use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+use super::asymmetric::{self, KeyPair};
 use super::error::KmsError;
 use super::types::*;
 
@@ -12,7 +13,122 @@ struct KmsKey {
     metadata: KeyMetadata,
     tags: HashMap<String, String>,
     policy: String,
-    // For encrypt/decrypt simulation: just store a marker prefix
+    // Symmetric keys use a simulated ciphertext format and have no material.
+    key_pair: Option<KeyPair>,
+    grants: Vec<Grant>,
+}
+
+struct Grant {
+    entry: GrantListEntry,
+    token: String,
+}
+
+const GRANT_OPERATIONS: &[&str] = &[
+    "Decrypt",
+    "Encrypt",
+    "GenerateDataKey",
+    "GenerateDataKeyWithoutPlaintext",
+    "ReEncryptFrom",
+    "ReEncryptTo",
+    "Sign",
+    "Verify",
+    "GetPublicKey",
+    "CreateGrant",
+    "RetireGrant",
+    "DescribeKey",
+];
+
+fn one_or_many(value: &serde_json::Value) -> Vec<&str> {
+    match value {
+        serde_json::Value::String(s) => vec![s.as_str()],
+        serde_json::Value::Array(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn action_matches(pattern: &str, action: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => action.to_ascii_lowercase().starts_with(&prefix.to_ascii_lowercase()),
+        None => pattern.eq_ignore_ascii_case(action),
+    }
+}
+
+// The account root (or bare account id) delegates to every principal in that account.
+fn principal_matches(value: &serde_json::Value, caller: Option<&str>, account_id: &str) -> bool {
+    if value.as_str() == Some("*") {
+        return true;
+    }
+    let root = format!("arn:aws:iam::{account_id}:root");
+    let caller_account = caller.and_then(|c| c.split(':').nth(4));
+    value.get("AWS").map(one_or_many).unwrap_or_default().into_iter().any(|p| {
+        p == "*"
+            || Some(p) == caller
+            || ((p == root || p == account_id) && caller_account == Some(account_id))
+    })
+}
+
+/// Evaluates a key policy: `Some(false)` for an explicit deny, `Some(true)` for an
+/// allow, `None` when no statement applies.
+fn policy_decision(policy: &str, caller: Option<&str>, action: &str, account_id: &str) -> Option<bool> {
+    let doc: serde_json::Value = serde_json::from_str(policy).ok()?;
+    let statements = match &doc["Statement"] {
+        serde_json::Value::Array(items) => items.clone(),
+        single @ serde_json::Value::Object(_) => vec![single.clone()],
+        _ => return None,
+    };
+    let mut decision = None;
+    for statement in &statements {
+        let applies = principal_matches(&statement["Principal"], caller, account_id)
+            && one_or_many(&statement["Action"]).into_iter().any(|a| action_matches(a, action));
+        if !applies {
+            continue;
+        }
+        match statement["Effect"].as_str() {
+            Some("Deny") => return Some(false),
+            Some("Allow") => decision = Some(true),
+            _ => {}
+        }
+    }
+    decision
+}
+
+fn grant_allows(
+    grant: &GrantListEntry,
+    caller: &str,
+    operation: &str,
+    context: Option<&HashMap<String, String>>,
+) -> bool {
+    if grant.grantee_principal != caller || !grant.operations.iter().any(|o| o == operation) {
+        return false;
+    }
+    let empty = HashMap::new();
+    let context = context.unwrap_or(&empty);
+    match &grant.constraints {
+        Some(c) => {
+            let subset_ok = c.encryption_context_subset.as_ref().is_none_or(|required| {
+                required.iter().all(|(k, v)| context.get(k) == Some(v))
+            });
+            let equals_ok = c.encryption_context_equals.as_ref().is_none_or(|required| required == context);
+            subset_ok && equals_ok
+        }
+        None => true,
+    }
+}
+
+fn decode_blob(data: &str, field: &str) -> Result<Vec<u8>, KmsError> {
+    BASE64
+        .decode(data)
+        .map_err(|_| KmsError::InvalidParameterException(format!("{field} must be base64-encoded")))
+}
+
+fn require_usage(key: &KmsKey, usage: &str) -> Result<(), KmsError> {
+    if key.metadata.key_usage != usage {
+        return Err(KmsError::InvalidKeyUsageException(format!(
+            "{} key usage is {}, expected {}",
+            key.metadata.arn, key.metadata.key_usage, usage
+        )));
+    }
+    Ok(())
 }
 
 struct KmsStateInner {
@@ -24,6 +140,9 @@ struct KmsStateInner {
 
 pub struct KmsState {
     inner: Arc<Mutex<KmsStateInner>>,
+    // When set, Encrypt/Decrypt/Sign are checked against key policies and grants
+    // for the principal named in this request header.
+    principal_header: Option<String>,
 }
 
 impl KmsState {
@@ -35,7 +154,55 @@ impl KmsState {
                 account_id,
                 region,
             })),
+            principal_header: None,
+        }
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    pub fn with_policy_enforcement(mut self, principal_header: Option<String>) -> Self {
+        self.principal_header = principal_header;
+        self
+    }
+
+    pub fn principal_header(&self) -> Option<&str> {
+        self.principal_header.as_deref()
+    }
+
+    fn authorize(
+        &self,
+        key: &KmsKey,
+        account_id: &str,
+        operation: &str,
+        caller: Option<&str>,
+        context: Option<&HashMap<String, String>>,
+    ) -> Result<(), KmsError> {
+        if self.principal_header.is_none() {
+            return Ok(());
+        }
+        let action = format!("kms:{operation}");
+        match policy_decision(&key.policy, caller, &action, account_id) {
+            Some(true) => return Ok(()),
+            Some(false) => {}
+            None => {
+                if let Some(caller) = caller {
+                    if key.grants.iter().any(|g| grant_allows(&g.entry, caller, operation, context)) {
+                        return Ok(());
+                    }
+                }
+            }
         }
+        Err(KmsError::AccessDeniedException(format!(
+            "User: {} is not authorized to perform: {} on resource: {}",
+            caller.unwrap_or("anonymous"),
+            action,
+            key.metadata.arn
+        )))
     }
 
     fn now_secs() -> f64 {
@@ -81,14 +248,26 @@ impl KmsState {
         &self,
         req: CreateKeyRequest,
     ) -> Result<CreateKeyResponse, KmsError> {
+        let key_usage = req.key_usage.unwrap_or_else(|| "ENCRYPT_DECRYPT".to_string());
+        let key_spec = req.key_spec.unwrap_or_else(|| "SYMMETRIC_DEFAULT".to_string());
+        asymmetric::validate_spec(&key_spec, &key_usage)?;
+        // RSA generation takes long enough to stall other requests, so it
+        // runs on a blocking thread before the state is locked.
+        let spec = key_spec.clone();
+        let key_pair = tokio::task::spawn_blocking(move || asymmetric::generate(&spec))
+            .await
+            .map_err(|e| KmsError::InvalidParameterException(format!("Key generation failed: {e}")))??;
+        let (encryption_algorithms, signing_algorithms) = match key_usage.as_str() {
+            "SIGN_VERIFY" => (None, Some(asymmetric::signing_algorithms(&key_spec))),
+            "GENERATE_VERIFY_MAC" => (None, None),
+            _ => (Some(asymmetric::encryption_algorithms(&key_spec)), None),
+        };
         let mut state = self.inner.lock().await;
         let key_id = Uuid::new_v4().to_string();
         let arn = format!(
             "arn:aws:kms:{}:{}:key/{}",
             state.region, state.account_id, key_id
         );
-        let key_usage = req.key_usage.unwrap_or_else(|| "ENCRYPT_DECRYPT".to_string());
-        let key_spec = req.key_spec.unwrap_or_else(|| "SYMMETRIC_DEFAULT".to_string());
         let metadata = KeyMetadata {
             key_id: key_id.clone(),
             arn: arn.clone(),
@@ -100,6 +279,8 @@ impl KmsState {
             creation_date: Self::now_secs(),
             key_manager: "CUSTOMER".to_string(),
             multi_region: false,
+            encryption_algorithms,
+            signing_algorithms,
         };
         let mut tags = HashMap::new();
         if let Some(t) = req.tags {
@@ -111,6 +292,8 @@ impl KmsState {
             metadata: metadata.clone(),
             tags,
             policy: r#"{"Version":"2012-10-17","Statement":[{"Effect":"Allow","Principal":{"AWS":"*"},"Action":"kms:*","Resource":"*"}]}"#.to_string(),
+            key_pair,
+            grants: Vec::new(),
         });
         Ok(CreateKeyResponse { key_metadata: metadata })
     }
@@ -216,6 +399,21 @@ impl KmsState {
         if !key.metadata.enabled {
             return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
         }
+        require_usage(key, "ENCRYPT_DECRYPT")?;
+        self.authorize(key, &state.account_id, "Encrypt", req.caller.as_deref(), req.encryption_context.as_ref())?;
+        if let Some(ref pair) = key.key_pair {
+            let algorithm = req.encryption_algorithm.ok_or_else(|| {
+                KmsError::InvalidParameterException(
+                    "EncryptionAlgorithm is required for asymmetric keys".to_string(),
+                )
+            })?;
+            let ciphertext = pair.encrypt(&algorithm, &decode_blob(&req.plaintext, "Plaintext")?)?;
+            return Ok(EncryptResponse {
+                key_id: key.metadata.arn.clone(),
+                ciphertext_blob: BASE64.encode(ciphertext),
+                encryption_algorithm: algorithm,
+            });
+        }
         // Simulate encryption: prefix key_id + ":" + original base64
         let plaintext_b64 = req.plaintext;
         let simulated = format!("{}:{}", resolved, plaintext_b64);
@@ -229,6 +427,32 @@ impl KmsState {
 
     pub async fn decrypt(&self, req: DecryptRequest) -> Result<DecryptResponse, KmsError> {
         let state = self.inner.lock().await;
+        // Asymmetric ciphertexts carry no key reference, so KeyId is required.
+        if let Some(explicit) = &req.key_id {
+            let resolved = Self::resolve_key_id(&state, explicit)
+                .ok_or_else(|| KmsError::NotFoundException(format!("Invalid keyId {}", explicit)))?;
+            let key = &state.keys[resolved];
+            if let Some(ref pair) = key.key_pair {
+                if !key.metadata.enabled {
+                    return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
+                }
+                require_usage(key, "ENCRYPT_DECRYPT")?;
+                self.authorize(key, &state.account_id, "Decrypt", req.caller.as_deref(), req.encryption_context.as_ref())?;
+                let algorithm = req.encryption_algorithm.clone().ok_or_else(|| {
+                    KmsError::InvalidParameterException(
+                        "EncryptionAlgorithm is required for asymmetric keys".to_string(),
+                    )
+                })?;
+                let ciphertext = BASE64.decode(&req.ciphertext_blob)
+                    .map_err(|_| KmsError::InvalidCiphertextException("Invalid ciphertext".to_string()))?;
+                let plaintext = pair.decrypt(&algorithm, &ciphertext)?;
+                return Ok(DecryptResponse {
+                    key_id: key.metadata.arn.clone(),
+                    plaintext: BASE64.encode(plaintext),
+                    encryption_algorithm: algorithm,
+                });
+            }
+        }
         let decoded = BASE64.decode(&req.ciphertext_blob)
             .map_err(|_| KmsError::InvalidCiphertextException("Invalid ciphertext".to_string()))?;
         let decoded_str = String::from_utf8(decoded)
@@ -251,6 +475,7 @@ impl KmsState {
         if !key.metadata.enabled {
             return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
         }
+        self.authorize(key, &state.account_id, "Decrypt", req.caller.as_deref(), req.encryption_context.as_ref())?;
 
         Ok(DecryptResponse {
             key_id: key.metadata.arn.clone(),
@@ -272,6 +497,11 @@ impl KmsState {
         if !key.metadata.enabled {
             return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
         }
+        if key.key_pair.is_some() {
+            return Err(KmsError::InvalidKeyUsageException(format!(
+                "{} is not a symmetric encryption key", key.metadata.arn
+            )));
+        }
         let num_bytes = match req.key_spec.as_deref() {
             Some("AES_256") => 32,
             Some("AES_128") => 16,
@@ -302,6 +532,11 @@ impl KmsState {
         if !key.metadata.enabled {
             return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
         }
+        if key.key_pair.is_some() {
+            return Err(KmsError::InvalidKeyUsageException(format!(
+                "{} is not a symmetric encryption key", key.metadata.arn
+            )));
+        }
         let num_bytes = match req.key_spec.as_deref() {
             Some("AES_256") => 32,
             Some("AES_128") => 16,
@@ -338,6 +573,17 @@ impl KmsState {
         if !key.metadata.enabled {
             return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
         }
+        self.authorize(key, &state.account_id, "Sign", req.caller.as_deref(), None)?;
+        if let Some(ref pair) = key.key_pair {
+            require_usage(key, "SIGN_VERIFY")?;
+            let message = decode_blob(&req.message, "Message")?;
+            let signature = pair.sign(&req.signing_algorithm, &message, req.message_type.as_deref())?;
+            return Ok(SignResponse {
+                key_id: key.metadata.arn.clone(),
+                signature: BASE64.encode(signature),
+                signing_algorithm: req.signing_algorithm,
+            });
+        }
         // Simulate: signature = base64(key_id + ":" + message_b64)
         let simulated = format!("{}:{}", resolved, req.message);
         let signature = BASE64.encode(simulated.as_bytes());
@@ -358,6 +604,21 @@ impl KmsState {
         if !key.metadata.enabled {
             return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
         }
+        if let Some(ref pair) = key.key_pair {
+            require_usage(key, "SIGN_VERIFY")?;
+            let message = decode_blob(&req.message, "Message")?;
+            let signature = decode_blob(&req.signature, "Signature")?;
+            if !pair.verify(&req.signing_algorithm, &message, req.message_type.as_deref(), &signature)? {
+                return Err(KmsError::KMSInvalidSignatureException(
+                    "The signature is not valid for this message".to_string(),
+                ));
+            }
+            return Ok(VerifyResponse {
+                key_id: key.metadata.arn.clone(),
+                signature_valid: true,
+                signing_algorithm: req.signing_algorithm,
+            });
+        }
         // Verify: decode signature and check it matches our simulation format
         let decoded = BASE64.decode(&req.signature).unwrap_or_default();
         let decoded_str = String::from_utf8(decoded).unwrap_or_default();
@@ -370,6 +631,34 @@ impl KmsState {
         })
     }
 
+    pub async fn get_public_key(
+        &self,
+        req: GetPublicKeyRequest,
+    ) -> Result<GetPublicKeyResponse, KmsError> {
+        let state = self.inner.lock().await;
+        let key_id_str = req.key_id.clone();
+        let resolved = Self::resolve_key_id(&state, &key_id_str)
+            .ok_or_else(|| KmsError::NotFoundException(format!("Invalid keyId {}", key_id_str)))?
+            .to_string();
+        let key = &state.keys[&resolved];
+        if !key.metadata.enabled {
+            return Err(KmsError::DisabledException(format!("KMS key {} is disabled", resolved)));
+        }
+        let pair = key.key_pair.as_ref().ok_or_else(|| {
+            KmsError::UnsupportedOperationException(format!(
+                "{} is a symmetric key and has no public key", key.metadata.arn
+            ))
+        })?;
+        Ok(GetPublicKeyResponse {
+            key_id: key.metadata.arn.clone(),
+            public_key: BASE64.encode(pair.public_key_der()),
+            key_spec: key.metadata.key_spec.clone(),
+            key_usage: key.metadata.key_usage.clone(),
+            encryption_algorithms: key.metadata.encryption_algorithms.clone(),
+            signing_algorithms: key.metadata.signing_algorithms.clone(),
+        })
+    }
+
     pub async fn tag_resource(&self, req: TagResourceRequest) -> Result<(), KmsError> {
         let mut state = self.inner.lock().await;
         let key_id_str = req.key_id.clone();
@@ -487,6 +776,104 @@ impl KmsState {
         key.policy = req.policy;
         Ok(())
     }
+
+    pub async fn create_grant(
+        &self,
+        req: CreateGrantRequest,
+    ) -> Result<CreateGrantResponse, KmsError> {
+        let mut state = self.inner.lock().await;
+        let key_id_str = req.key_id.clone();
+        let resolved = Self::resolve_key_id(&state, &key_id_str)
+            .ok_or_else(|| KmsError::NotFoundException(format!("Invalid keyId {}", key_id_str)))?
+            .to_string();
+        if req.operations.is_empty() {
+            return Err(KmsError::InvalidParameterException(
+                "Operations must contain at least one grant operation".to_string(),
+            ));
+        }
+        if let Some(op) = req.operations.iter().find(|op| !GRANT_OPERATIONS.contains(&op.as_str())) {
+            return Err(KmsError::InvalidParameterException(format!("Invalid grant operation: {op}")));
+        }
+        let grant_id = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let grant_token = BASE64.encode([Uuid::new_v4().as_bytes().as_slice(), grant_id.as_bytes()].concat());
+        let issuing_account = format!("arn:aws:iam::{}:root", state.account_id);
+        let key = state.keys.get_mut(&resolved).unwrap();
+        let entry = GrantListEntry {
+            key_id: key.metadata.arn.clone(),
+            grant_id: grant_id.clone(),
+            name: req.name,
+            creation_date: Self::now_secs(),
+            grantee_principal: req.grantee_principal,
+            retiring_principal: req.retiring_principal,
+            issuing_account,
+            operations: req.operations,
+            constraints: req.constraints,
+        };
+        key.grants.push(Grant { entry, token: grant_token.clone() });
+        Ok(CreateGrantResponse { grant_id, grant_token })
+    }
+
+    pub async fn list_grants(
+        &self,
+        req: ListGrantsRequest,
+    ) -> Result<ListGrantsResponse, KmsError> {
+        let state = self.inner.lock().await;
+        let key_id_str = req.key_id.clone();
+        let resolved = Self::resolve_key_id(&state, &key_id_str)
+            .ok_or_else(|| KmsError::NotFoundException(format!("Invalid keyId {}", key_id_str)))?;
+        let limit = req.limit.unwrap_or(50);
+        if !(1..=100).contains(&limit) {
+            return Err(KmsError::InvalidParameterException(
+                "Limit must be between 1 and 100".to_string(),
+            ));
+        }
+        let mut grants: Vec<GrantListEntry> = state.keys[resolved]
+            .grants
+            .iter()
+            .map(|g| &g.entry)
+            .filter(|g| req.grant_id.as_ref().is_none_or(|id| &g.grant_id == id))
+            .filter(|g| req.grantee_principal.as_ref().is_none_or(|p| &g.grantee_principal == p))
+            .cloned()
+            .collect();
+        let truncated = grants.len() > limit;
+        grants.truncate(limit);
+        Ok(ListGrantsResponse { grants, truncated })
+    }
+
+    pub async fn revoke_grant(&self, req: RevokeGrantRequest) -> Result<(), KmsError> {
+        let mut state = self.inner.lock().await;
+        let key_id_str = req.key_id.clone();
+        let resolved = Self::resolve_key_id(&state, &key_id_str)
+            .ok_or_else(|| KmsError::NotFoundException(format!("Invalid keyId {}", key_id_str)))?
+            .to_string();
+        let key = state.keys.get_mut(&resolved).unwrap();
+        let before = key.grants.len();
+        key.grants.retain(|g| g.entry.grant_id != req.grant_id);
+        if key.grants.len() == before {
+            return Err(KmsError::NotFoundException(format!("Grant ID {} not found", req.grant_id)));
+        }
+        Ok(())
+    }
+
+    pub async fn retire_grant(&self, req: RetireGrantRequest) -> Result<(), KmsError> {
+        let mut state = self.inner.lock().await;
+        if let Some(token) = req.grant_token {
+            for key in state.keys.values_mut() {
+                if let Some(pos) = key.grants.iter().position(|g| g.token == token) {
+                    key.grants.remove(pos);
+                    return Ok(());
+                }
+            }
+            return Err(KmsError::NotFoundException("Grant token not found".to_string()));
+        }
+        let (Some(key_id), Some(grant_id)) = (req.key_id, req.grant_id) else {
+            return Err(KmsError::InvalidParameterException(
+                "Either GrantToken or both KeyId and GrantId must be specified".to_string(),
+            ));
+        };
+        drop(state);
+        self.revoke_grant(RevokeGrantRequest { key_id, grant_id }).await
+    }
 }
 
 
@@ -765,6 +1152,8 @@ mod tests {
             key_id: key_id.clone(),
             message: message.clone(),
             signing_algorithm: "RSASSA_PSS_SHA_256".to_string(),
+            message_type: None,
+            caller: None,
         }).await.unwrap();
         assert!(!sign_resp.signature.is_empty());
 
@@ -773,6 +1162,7 @@ mod tests {
             message: message.clone(),
             signature: sign_resp.signature,
             signing_algorithm: "RSASSA_PSS_SHA_256".to_string(),
+            message_type: None,
         }).await.unwrap();
         assert!(verify_resp.signature_valid);
     }
@@ -787,6 +1177,7 @@ mod tests {
             message: "msg".to_string(),
             signature: "aW52YWxpZA==".to_string(), // base64("invalid")
             signing_algorithm: "RSASSA_PSS_SHA_256".to_string(),
+            message_type: None,
         }).await.unwrap();
         assert!(!verify_resp.signature_valid);
     }
@@ -801,6 +1192,8 @@ mod tests {
             key_id,
             message: "msg".to_string(),
             signing_algorithm: "RSASSA_PSS_SHA_256".to_string(),
+            message_type: None,
+            caller: None,
         }).await;
         assert!(result.is_err());
     }
@@ -816,6 +1209,7 @@ mod tests {
             message: "msg".to_string(),
             signature: "sig".to_string(),
             signing_algorithm: "RSASSA_PSS_SHA_256".to_string(),
+            message_type: None,
         }).await;
         assert!(result.is_err());
     }
@@ -1059,4 +1453,255 @@ mod tests {
         }).await.unwrap();
         assert_eq!(dec.plaintext, "secret");
     }
+
+    async fn create_asymmetric_key(state: &KmsState, key_spec: &str, key_usage: &str) -> String {
+        state.create_key(CreateKeyRequest {
+            key_spec: Some(key_spec.to_string()),
+            key_usage: Some(key_usage.to_string()),
+            ..Default::default()
+        }).await.unwrap().key_metadata.key_id
+    }
+
+    #[tokio::test]
+    async fn test_asymmetric_sign_verify_roundtrip() {
+        let state = make_state();
+        for (spec, algorithm) in [
+            ("ECC_NIST_P256", "ECDSA_SHA_256"),
+            ("RSA_2048", "RSASSA_PKCS1_V1_5_SHA_256"),
+            ("RSA_2048", "RSASSA_PSS_SHA_384"),
+        ] {
+            let key_id = create_asymmetric_key(&state, spec, "SIGN_VERIFY").await;
+            let message = BASE64.encode(b"header.payload");
+            let signed = state.sign(SignRequest {
+                key_id: key_id.clone(),
+                message: message.clone(),
+                signing_algorithm: algorithm.to_string(),
+                ..Default::default()
+            }).await.unwrap();
+
+            let verified = state.verify(VerifyRequest {
+                key_id: key_id.clone(),
+                message,
+                signature: signed.signature.clone(),
+                signing_algorithm: algorithm.to_string(),
+                ..Default::default()
+            }).await.unwrap();
+            assert!(verified.signature_valid, "{spec} {algorithm}");
+
+            let tampered = state.verify(VerifyRequest {
+                key_id,
+                message: BASE64.encode(b"header.payl0ad"),
+                signature: signed.signature,
+                signing_algorithm: algorithm.to_string(),
+                ..Default::default()
+            }).await;
+            assert!(matches!(tampered, Err(KmsError::KMSInvalidSignatureException(_))), "{spec} {algorithm}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_public_key_verifies_signature_externally() {
+        use p256::ecdsa::signature::Verifier;
+        use p256::pkcs8::DecodePublicKey;
+
+        let state = make_state();
+        let key_id = create_asymmetric_key(&state, "ECC_NIST_P256", "SIGN_VERIFY").await;
+        let public = state.get_public_key(GetPublicKeyRequest { key_id: key_id.clone() }).await.unwrap();
+        assert_eq!(public.key_spec, "ECC_NIST_P256");
+        assert_eq!(public.signing_algorithms, Some(vec!["ECDSA_SHA_256".to_string()]));
+
+        let signed = state.sign(SignRequest {
+            key_id,
+            message: BASE64.encode(b"document"),
+            signing_algorithm: "ECDSA_SHA_256".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let verifying_key = p256::ecdsa::VerifyingKey::from_public_key_der(
+            &BASE64.decode(public.public_key).unwrap(),
+        ).unwrap();
+        let signature = p256::ecdsa::Signature::from_der(&BASE64.decode(signed.signature).unwrap()).unwrap();
+        assert!(verifying_key.verify(b"document", &signature).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_asymmetric_encrypt_decrypt() {
+        let state = make_state();
+        let key_id = create_asymmetric_key(&state, "RSA_2048", "ENCRYPT_DECRYPT").await;
+        let encrypted = state.encrypt(EncryptRequest {
+            key_id: key_id.clone(),
+            plaintext: BASE64.encode(b"secret"),
+            encryption_algorithm: Some("RSAES_OAEP_SHA_256".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        let decrypted = state.decrypt(DecryptRequest {
+            ciphertext_blob: encrypted.ciphertext_blob,
+            key_id: Some(key_id.clone()),
+            encryption_algorithm: Some("RSAES_OAEP_SHA_256".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(BASE64.decode(decrypted.plaintext).unwrap(), b"secret");
+
+        let sign = state.sign(SignRequest {
+            key_id,
+            message: BASE64.encode(b"x"),
+            signing_algorithm: "RSASSA_PSS_SHA_256".to_string(),
+            ..Default::default()
+        }).await;
+        assert!(matches!(sign, Err(KmsError::InvalidKeyUsageException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_key_rejects_invalid_spec_usage() {
+        let state = make_state();
+        let result = state.create_key(CreateKeyRequest {
+            key_spec: Some("ECC_NIST_P256".to_string()),
+            key_usage: Some("ENCRYPT_DECRYPT".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(result.is_err());
+        let symmetric = create_key(&state).await;
+        let public = state.get_public_key(GetPublicKeyRequest { key_id: symmetric }).await;
+        assert!(matches!(public, Err(KmsError::UnsupportedOperationException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_hmac_key() {
+        let state = make_state();
+        for spec in ["HMAC_224", "HMAC_256", "HMAC_384", "HMAC_512"] {
+            let key = state.create_key(CreateKeyRequest {
+                key_spec: Some(spec.to_string()),
+                key_usage: Some("GENERATE_VERIFY_MAC".to_string()),
+                ..Default::default()
+            }).await.unwrap().key_metadata;
+            assert_eq!(key.key_spec, spec);
+            assert!(key.encryption_algorithms.is_none() && key.signing_algorithms.is_none());
+        }
+    }
+
+    const RESTRICTED_POLICY: &str = r#"{"Version":"2012-10-17","Statement":[{"Effect":"Allow","Principal":{"AWS":"arn:aws:iam::123456789012:role/admin"},"Action":"kms:*","Resource":"*"}]}"#;
+
+    async fn create_restricted_key(state: &KmsState) -> String {
+        let key_id = create_key(state).await;
+        state.put_key_policy(PutKeyPolicyRequest {
+            key_id: key_id.clone(),
+            policy: RESTRICTED_POLICY.to_string(),
+        }).await.unwrap();
+        key_id
+    }
+
+    fn context(pairs: &[(&str, &str)]) -> Option<HashMap<String, String>> {
+        Some(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+    }
+
+    #[tokio::test]
+    async fn test_enforcement_denies_unpermitted_principal() {
+        let state = make_state().with_policy_enforcement(Some("x-amz-principal".to_string()));
+        let key_id = create_restricted_key(&state).await;
+        let admin = Some("arn:aws:iam::123456789012:role/admin".to_string());
+        let enc = state.encrypt(EncryptRequest {
+            key_id: key_id.clone(),
+            plaintext: BASE64.encode(b"data"),
+            caller: admin,
+            ..Default::default()
+        }).await.unwrap();
+
+        let denied = state.decrypt(DecryptRequest {
+            ciphertext_blob: enc.ciphertext_blob.clone(),
+            caller: Some("arn:aws:iam::123456789012:role/intruder".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(matches!(denied, Err(KmsError::AccessDeniedException(_))));
+        let anonymous = state.sign(SignRequest {
+            key_id: key_id.clone(),
+            message: BASE64.encode(b"data"),
+            signing_algorithm: "RSASSA_PSS_SHA_256".to_string(),
+            ..Default::default()
+        }).await;
+        assert!(matches!(anonymous, Err(KmsError::AccessDeniedException(_))));
+
+        // Without enforcement the same call succeeds.
+        let open = make_state();
+        let key_id = create_restricted_key(&open).await;
+        let enc = open.encrypt(EncryptRequest {
+            key_id,
+            plaintext: BASE64.encode(b"data"),
+            caller: Some("arn:aws:iam::123456789012:role/intruder".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(enc.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_grant_allows_decrypt_with_matching_context() {
+        let state = make_state().with_policy_enforcement(Some("x-amz-principal".to_string()));
+        let key_id = create_restricted_key(&state).await;
+        let app = "arn:aws:iam::123456789012:role/app".to_string();
+        let enc = state.encrypt(EncryptRequest {
+            key_id: key_id.clone(),
+            plaintext: BASE64.encode(b"data"),
+            encryption_context: context(&[("tenant", "a")]),
+            caller: Some("arn:aws:iam::123456789012:role/admin".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+
+        let grant = state.create_grant(CreateGrantRequest {
+            key_id: key_id.clone(),
+            grantee_principal: app.clone(),
+            operations: vec!["Decrypt".to_string()],
+            constraints: Some(GrantConstraints {
+                encryption_context_subset: context(&[("tenant", "a")]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }).await.unwrap();
+
+        let decrypt = |ctx: Option<HashMap<String, String>>| DecryptRequest {
+            ciphertext_blob: enc.ciphertext_blob.clone(),
+            encryption_context: ctx,
+            caller: Some(app.clone()),
+            ..Default::default()
+        };
+        let dec = state.decrypt(decrypt(context(&[("tenant", "a"), ("extra", "x")]))).await.unwrap();
+        assert_eq!(BASE64.decode(dec.plaintext).unwrap(), b"data");
+        let wrong_context = state.decrypt(decrypt(context(&[("tenant", "b")]))).await;
+        assert!(matches!(wrong_context, Err(KmsError::AccessDeniedException(_))));
+        // The grant does not cover Encrypt.
+        let encrypt = state.encrypt(EncryptRequest {
+            key_id: key_id.clone(),
+            plaintext: BASE64.encode(b"data"),
+            encryption_context: context(&[("tenant", "a")]),
+            caller: Some(app.clone()),
+            ..Default::default()
+        }).await;
+        assert!(matches!(encrypt, Err(KmsError::AccessDeniedException(_))));
+
+        let listed = state.list_grants(ListGrantsRequest {
+            key_id: key_id.clone(),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(listed.grants.len(), 1);
+        assert_eq!(listed.grants[0].grantee_principal, app);
+
+        state.retire_grant(RetireGrantRequest {
+            grant_token: Some(grant.grant_token),
+            ..Default::default()
+        }).await.unwrap();
+        let retired = state.decrypt(decrypt(context(&[("tenant", "a")]))).await;
+        assert!(matches!(retired, Err(KmsError::AccessDeniedException(_))));
+        let revoke = state.revoke_grant(RevokeGrantRequest { key_id, grant_id: grant.grant_id }).await;
+        assert!(matches!(revoke, Err(KmsError::NotFoundException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_grant_rejects_unknown_operation() {
+        let state = make_state();
+        let key_id = create_key(&state).await;
+        let result = state.create_grant(CreateGrantRequest {
+            key_id,
+            grantee_principal: "arn:aws:iam::123456789012:role/app".to_string(),
+            operations: vec!["DeleteEverything".to_string()],
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(KmsError::InvalidParameterException(_))));
+    }
 }
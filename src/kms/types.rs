@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -40,6 +42,12 @@ pub struct KeyMetadata {
     pub key_manager: String,
     #[serde(rename = "MultiRegion")]
     pub multi_region: bool,
+    #[serde(rename = "EncryptionAlgorithms")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_algorithms: Option<Vec<String>>,
+    #[serde(rename = "SigningAlgorithms")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_algorithms: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -131,6 +139,11 @@ pub struct EncryptRequest {
     pub plaintext: String, // base64
     #[serde(rename = "EncryptionAlgorithm")]
     pub encryption_algorithm: Option<String>,
+    #[serde(rename = "EncryptionContext")]
+    pub encryption_context: Option<HashMap<String, String>>,
+    // Calling principal, taken from the configured header when policies are enforced.
+    #[serde(skip)]
+    pub caller: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -151,6 +164,10 @@ pub struct DecryptRequest {
     pub key_id: Option<String>,
     #[serde(rename = "EncryptionAlgorithm")]
     pub encryption_algorithm: Option<String>,
+    #[serde(rename = "EncryptionContext")]
+    pub encryption_context: Option<HashMap<String, String>>,
+    #[serde(skip)]
+    pub caller: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -219,8 +236,12 @@ pub struct SignRequest {
     pub key_id: String,
     #[serde(rename = "Message")]
     pub message: String, // base64
+    #[serde(rename = "MessageType")]
+    pub message_type: Option<String>,
     #[serde(rename = "SigningAlgorithm")]
     pub signing_algorithm: String,
+    #[serde(skip)]
+    pub caller: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -241,6 +262,8 @@ pub struct VerifyRequest {
     pub message: String, // base64
     #[serde(rename = "Signature")]
     pub signature: String, // base64
+    #[serde(rename = "MessageType")]
+    pub message_type: Option<String>,
     #[serde(rename = "SigningAlgorithm")]
     pub signing_algorithm: String,
 }
@@ -255,6 +278,30 @@ pub struct VerifyResponse {
     pub signing_algorithm: String,
 }
 
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetPublicKeyRequest {
+    #[serde(rename = "KeyId")]
+    pub key_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetPublicKeyResponse {
+    #[serde(rename = "KeyId")]
+    pub key_id: String,
+    #[serde(rename = "PublicKey")]
+    pub public_key: String, // base64 DER SubjectPublicKeyInfo
+    #[serde(rename = "KeySpec")]
+    pub key_spec: String,
+    #[serde(rename = "KeyUsage")]
+    pub key_usage: String,
+    #[serde(rename = "EncryptionAlgorithms")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_algorithms: Option<Vec<String>>,
+    #[serde(rename = "SigningAlgorithms")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_algorithms: Option<Vec<String>>,
+}
+
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct TagResourceRequest {
     #[serde(rename = "KeyId")]
@@ -354,3 +401,100 @@ pub struct PutKeyPolicyRequest {
     #[serde(rename = "Policy")]
     pub policy: String,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct GrantConstraints {
+    #[serde(rename = "EncryptionContextSubset")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_context_subset: Option<HashMap<String, String>>,
+    #[serde(rename = "EncryptionContextEquals")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_context_equals: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CreateGrantRequest {
+    #[serde(rename = "KeyId")]
+    pub key_id: String,
+    #[serde(rename = "GranteePrincipal")]
+    pub grantee_principal: String,
+    #[serde(rename = "RetiringPrincipal")]
+    pub retiring_principal: Option<String>,
+    #[serde(rename = "Operations")]
+    pub operations: Vec<String>,
+    #[serde(rename = "Constraints")]
+    pub constraints: Option<GrantConstraints>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateGrantResponse {
+    #[serde(rename = "GrantId")]
+    pub grant_id: String,
+    #[serde(rename = "GrantToken")]
+    pub grant_token: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ListGrantsRequest {
+    #[serde(rename = "KeyId")]
+    pub key_id: String,
+    #[serde(rename = "GrantId")]
+    pub grant_id: Option<String>,
+    #[serde(rename = "GranteePrincipal")]
+    pub grantee_principal: Option<String>,
+    #[serde(rename = "Limit")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GrantListEntry {
+    #[serde(rename = "KeyId")]
+    pub key_id: String,
+    #[serde(rename = "GrantId")]
+    pub grant_id: String,
+    #[serde(rename = "Name")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "CreationDate")]
+    pub creation_date: f64,
+    #[serde(rename = "GranteePrincipal")]
+    pub grantee_principal: String,
+    #[serde(rename = "RetiringPrincipal")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retiring_principal: Option<String>,
+    #[serde(rename = "IssuingAccount")]
+    pub issuing_account: String,
+    #[serde(rename = "Operations")]
+    pub operations: Vec<String>,
+    #[serde(rename = "Constraints")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<GrantConstraints>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListGrantsResponse {
+    #[serde(rename = "Grants")]
+    pub grants: Vec<GrantListEntry>,
+    #[serde(rename = "Truncated")]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RevokeGrantRequest {
+    #[serde(rename = "KeyId")]
+    pub key_id: String,
+    #[serde(rename = "GrantId")]
+    pub grant_id: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RetireGrantRequest {
+    #[serde(rename = "GrantToken")]
+    pub grant_token: Option<String>,
+    #[serde(rename = "KeyId")]
+    pub key_id: Option<String>,
+    #[serde(rename = "GrantId")]
+    pub grant_id: Option<String>,
+}
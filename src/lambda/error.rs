@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum LambdaError {
@@ -35,17 +36,23 @@ impl LambdaError {
     }
 }
 
+impl AwsError for LambdaError {
+    fn code(&self) -> &str {
+        self.error_type()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        LambdaError::message(self)
+    }
+}
+
 impl IntoResponse for LambdaError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "Message": self.message(),
-        });
-        (
-            self.status_code(),
-            [("x-amzn-ErrorType", self.error_type())],
-            axum::Json(body),
-        )
-            .into_response()
+        error_response(&self, Protocol::RestJson)
     }
 }
 
@@ -15,6 +15,7 @@ pub struct LambdaFunction {
     pub last_modified: String,
     pub version: String,
     pub state: String,
+    pub last_update_status: String,
     pub package_type: String,
     pub environment: HashMap<String, String>,
     pub tags: HashMap<String, String>,
@@ -40,6 +40,14 @@ async fn get_function_handler(
     Ok(json_response(StatusCode::OK, &resp))
 }
 
+async fn get_function_configuration_handler(
+    State(state): State<Arc<LambdaState>>,
+    Path(function_name): Path<String>,
+) -> Result<Response, LambdaError> {
+    let config = state.get_function_configuration(&function_name).await?;
+    Ok(json_response(StatusCode::OK, &config))
+}
+
 async fn delete_function_handler(
     State(state): State<Arc<LambdaState>>,
     Path(function_name): Path<String>,
@@ -74,20 +82,55 @@ async fn invoke_handler(
     State(state): State<Arc<LambdaState>>,
     Path(function_name): Path<String>,
     headers: HeaderMap,
-    _body: Bytes,
+    body: Bytes,
 ) -> Result<Response, LambdaError> {
     let invocation_type = headers
         .get("X-Amz-Invocation-Type")
         .and_then(|v| v.to_str().ok());
 
-    let (status, body) = state.invoke(&function_name, invocation_type).await?;
+    let result = state.invoke(&function_name, invocation_type, &body).await?;
 
-    Ok((
-        status,
+    let mut resp = (
+        result.status,
         [("X-Amz-Executed-Version", "$LATEST")],
-        body,
+        result.payload,
     )
-        .into_response())
+        .into_response();
+    if result.function_error {
+        resp.headers_mut().insert("X-Amz-Function-Error", axum::http::HeaderValue::from_static("Unhandled"));
+    }
+    Ok(resp)
+}
+
+// --- Function stubs (not an AWS API) ---
+
+// GET /_aws/lambda/functions/{name}/stub returns the stub, PUT replaces it
+// and DELETE removes it.
+async fn get_stub_handler(
+    State(state): State<Arc<LambdaState>>,
+    Path(function_name): Path<String>,
+) -> Result<Response, LambdaError> {
+    let stub = state.get_stub(&function_name).await?;
+    Ok(json_response(StatusCode::OK, &stub))
+}
+
+async fn put_stub_handler(
+    State(state): State<Arc<LambdaState>>,
+    Path(function_name): Path<String>,
+    body: Bytes,
+) -> Result<Response, LambdaError> {
+    let stub: FunctionStub = serde_json::from_slice(&body)
+        .map_err(|e| LambdaError::InvalidParameterValueException(format!("Invalid stub: {e}")))?;
+    state.set_stub(&function_name, Some(stub.clone())).await?;
+    Ok(json_response(StatusCode::OK, &stub))
+}
+
+async fn delete_stub_handler(
+    State(state): State<Arc<LambdaState>>,
+    Path(function_name): Path<String>,
+) -> Result<Response, LambdaError> {
+    state.set_stub(&function_name, None).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 // --- Permission handlers ---
@@ -261,13 +304,17 @@ pub fn create_router(state: Arc<LambdaState>) -> Router {
         )
         .route(
             "/2015-03-31/functions/{name}/configuration",
-            put(update_function_configuration_handler),
+            get(get_function_configuration_handler).put(update_function_configuration_handler),
         )
         // Invoke
         .route(
             "/2015-03-31/functions/{name}/invocations",
             post(invoke_handler),
         )
+        .route(
+            "/_aws/lambda/functions/{name}/stub",
+            get(get_stub_handler).put(put_stub_handler).delete(delete_stub_handler),
+        )
         // Policy
         .route(
             "/2015-03-31/functions/{name}/policy",
@@ -314,9 +361,38 @@ pub fn create_router(state: Arc<LambdaState>) -> Router {
                 .delete(untag_resource_handler)
                 .get(list_tags_handler),
         )
+        .merge(crate::healthcheck::router("lambda"))
         .with_state(state)
 }
 
+/// Creates `name` through `app` and gives it `stub`, as a client would, for
+/// tests of services that invoke functions.
+#[cfg(test)]
+pub async fn create_stubbed_function(app: &Router, name: &str, stub: serde_json::Value) {
+    use tower::ServiceExt;
+
+    let create = serde_json::json!({
+        "FunctionName": name,
+        "Role": "arn:aws:iam::123456789012:role/role",
+        "Code": { "ZipFile": "UEsDBBQ=" },
+        "Runtime": "python3.12",
+        "Handler": "index.handler",
+    });
+    for (method, uri, body) in [
+        ("POST", "/2015-03-31/functions".to_string(), create),
+        ("PUT", format!("/_aws/lambda/functions/{name}/stub"), stub),
+    ] {
+        let req = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert!(resp.status().is_success(), "{method} {}", resp.status());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -387,6 +463,61 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_stub_drives_invoke() {
+        let app = create_router(new_state());
+        create_stubbed_function(&app, "my-func", serde_json::json!({ "mode": "echo" })).await;
+        let invoke = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/2015-03-31/functions/my-func/invocations")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let resp = app.clone().oneshot(invoke(r#"{"n":1}"#)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(extract_body(resp).await, serde_json::json!({ "n": 1 }));
+
+        let req = Request::builder()
+            .uri("/_aws/lambda/functions/my-func/stub")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(extract_body(app.clone().oneshot(req).await.unwrap()).await["mode"], "echo");
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_aws/lambda/functions/my-func/stub")
+            .body(Body::from(r#"{"mode": "error", "errorMessage": "boom"}"#))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+        let resp = app.clone().oneshot(invoke("{}")).await.unwrap();
+        assert_eq!(resp.headers()["X-Amz-Function-Error"], "Unhandled");
+        assert_eq!(extract_body(resp).await["errorMessage"], "boom");
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_aws/lambda/functions/my-func/stub")
+            .body(Body::from(r#"{"mode": "shrug"}"#))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::BAD_REQUEST);
+
+        let req = Request::builder()
+            .method("DELETE")
+            .uri("/_aws/lambda/functions/my-func/stub")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::NO_CONTENT);
+        let resp = app.clone().oneshot(invoke("{}")).await.unwrap();
+        assert_eq!(extract_body(resp).await, serde_json::Value::Null);
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_aws/lambda/functions/missing/stub")
+            .body(Body::from(r#"{"mode": "echo"}"#))
+            .unwrap();
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_get_function_not_found() {
         let app = create_router(new_state());
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 
 use base64::Engine;
@@ -13,9 +15,20 @@ use super::function::{
 };
 use super::types::*;
 
+/// In-process stand-in for a function's code, for tests that need more
+/// than a [`FunctionStub`]. The handler receives the event and returns the
+/// function's result, or an error message for a function error.
+pub type FunctionHandler = Arc<
+    dyn Fn(serde_json::Value) -> Pin<Box<dyn Future<Output = Result<serde_json::Value, String>> + Send>>
+        + Send
+        + Sync,
+>;
+
 struct LambdaStateInner {
     functions: HashMap<String, LambdaFunction>,
     event_source_mappings: HashMap<String, EventSourceMapping>,
+    handlers: HashMap<String, FunctionHandler>,
+    stubs: HashMap<String, FunctionStub>,
     account_id: String,
     region: String,
 }
@@ -37,12 +50,32 @@ impl LambdaState {
             inner: Arc::new(Mutex::new(LambdaStateInner {
                 functions: HashMap::new(),
                 event_source_mappings: HashMap::new(),
+                handlers: HashMap::new(),
+                stubs: HashMap::new(),
                 account_id,
                 region,
             })),
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// ARNs and tags of every function that carries at least one tag.
+    pub async fn tagged_resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let state = self.inner.lock().await;
+        state
+            .functions
+            .values()
+            .filter(|func| !func.tags.is_empty())
+            .map(|func| (func.function_arn.clone(), func.tags.clone()))
+            .collect()
+    }
+
     fn compute_sha256(data: &[u8]) -> String {
         let mut hasher = Sha256::new();
         hasher.update(data);
@@ -78,6 +111,11 @@ impl LambdaState {
             code_sha256: func.code_sha256.clone(),
             version: func.version.clone(),
             state: func.state.clone(),
+            state_reason: None,
+            state_reason_code: None,
+            last_update_status: func.last_update_status.clone(),
+            last_update_status_reason: None,
+            last_update_status_reason_code: None,
             package_type: func.package_type.clone(),
             environment: if func.environment.is_empty() {
                 None
@@ -108,6 +146,11 @@ impl LambdaState {
             code_sha256: pv.code_sha256.clone(),
             version: pv.version.clone(),
             state: "Active".to_string(),
+            state_reason: None,
+            state_reason_code: None,
+            last_update_status: "Successful".to_string(),
+            last_update_status_reason: None,
+            last_update_status_reason_code: None,
             package_type: func.package_type.clone(),
             environment: if pv.environment.is_empty() {
                 None
@@ -120,6 +163,28 @@ impl LambdaState {
         }
     }
 
+    /// Decodes a request's base64 `ZipFile`; no code is an empty package.
+    fn decode_zip(zip_file: Option<&str>) -> Vec<u8> {
+        zip_file
+            .map(|zip| base64::engine::general_purpose::STANDARD.decode(zip).unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    /// Deployment is instant, so stored functions are always settled; the
+    /// create and update responses report the transition AWS would be in.
+    fn in_progress(mut config: FunctionConfiguration, creating: bool) -> FunctionConfiguration {
+        if creating {
+            config.state = "Pending".to_string();
+            config.state_reason = Some("The function is being created.".to_string());
+            config.state_reason_code = Some("Creating".to_string());
+        } else {
+            config.last_update_status = "InProgress".to_string();
+            config.last_update_status_reason = Some("The function is being created.".to_string());
+            config.last_update_status_reason_code = Some("Creating".to_string());
+        }
+        config
+    }
+
     pub async fn create_function(
         &self,
         req: CreateFunctionRequest,
@@ -133,14 +198,7 @@ impl LambdaState {
             )));
         }
 
-        let code_bytes = if let Some(ref zip_file) = req.code.zip_file {
-            base64::engine::general_purpose::STANDARD
-                .decode(zip_file)
-                .unwrap_or_default()
-        } else {
-            Vec::new()
-        };
-
+        let code_bytes = Self::decode_zip(req.code.zip_file.as_deref());
         let code_sha256 = Self::compute_sha256(&code_bytes);
         let code_size = code_bytes.len() as i64;
         let arn = Self::make_arn(&inner.account_id, &inner.region, &req.function_name);
@@ -165,6 +223,7 @@ impl LambdaState {
             last_modified: now,
             version: "$LATEST".to_string(),
             state: "Active".to_string(),
+            last_update_status: "Successful".to_string(),
             package_type: req.package_type.unwrap_or_else(|| "Zip".to_string()),
             environment: env_vars,
             tags: req.tags.unwrap_or_default(),
@@ -177,7 +236,7 @@ impl LambdaState {
             policy_statements: Vec::new(),
         };
 
-        let config = Self::function_to_config(&func);
+        let config = Self::in_progress(Self::function_to_config(&func), true);
         inner.functions.insert(req.function_name, func);
         Ok(config)
     }
@@ -221,9 +280,89 @@ impl LambdaState {
         let mut inner = self.inner.lock().await;
         let err = not_found_err(&inner.account_id, &inner.region, function_name);
         inner.functions.remove(function_name).ok_or(err)?;
+        inner.handlers.remove(function_name);
+        inner.stubs.remove(function_name);
+        Ok(())
+    }
+
+    /// Backs `GET /_aws/lambda/functions/{name}/stub`.
+    pub async fn get_stub(&self, function_name: &str) -> Result<FunctionStub, LambdaError> {
+        let inner = self.inner.lock().await;
+        if !inner.functions.contains_key(function_name) {
+            return Err(not_found_err(&inner.account_id, &inner.region, function_name));
+        }
+        inner.stubs.get(function_name).cloned().ok_or_else(|| {
+            LambdaError::ResourceNotFoundException(format!("Function {function_name} has no stub"))
+        })
+    }
+
+    /// Backs `PUT` and `DELETE /_aws/lambda/functions/{name}/stub`; `None`
+    /// removes the stub.
+    pub async fn set_stub(&self, function_name: &str, stub: Option<FunctionStub>) -> Result<(), LambdaError> {
+        let mut inner = self.inner.lock().await;
+        if !inner.functions.contains_key(function_name) {
+            return Err(not_found_err(&inner.account_id, &inner.region, function_name));
+        }
+        match stub {
+            Some(stub) => inner.stubs.insert(function_name.to_string(), stub),
+            None => inner.stubs.remove(function_name),
+        };
+        Ok(())
+    }
+
+    /// Attaches `handler` as the code of an existing function.
+    #[cfg(test)]
+    pub async fn set_handler(
+        &self,
+        function_name: &str,
+        handler: FunctionHandler,
+    ) -> Result<(), LambdaError> {
+        let mut inner = self.inner.lock().await;
+        if !inner.functions.contains_key(function_name) {
+            return Err(not_found_err(&inner.account_id, &inner.region, function_name));
+        }
+        inner.handlers.insert(function_name.to_string(), handler);
         Ok(())
     }
 
+    /// Runs the function's handler or stub on `payload`, or returns `None`
+    /// if it has neither.
+    async fn run(
+        &self,
+        function_name: &str,
+        payload: serde_json::Value,
+    ) -> Result<Option<Result<serde_json::Value, String>>, LambdaError> {
+        let (handler, stub) = {
+            let inner = self.inner.lock().await;
+            if !inner.functions.contains_key(function_name) {
+                return Err(not_found_err(&inner.account_id, &inner.region, function_name));
+            }
+            (inner.handlers.get(function_name).cloned(), inner.stubs.get(function_name).cloned())
+        };
+        Ok(match (handler, stub) {
+            (Some(handler), _) => Some(handler(payload).await),
+            (None, Some(stub)) => Some(stub.run(payload)),
+            (None, None) => None,
+        })
+    }
+
+    /// Synchronously invokes a function with `payload`. The outer error is an
+    /// API error (unknown function); the inner result is the function's own
+    /// outcome. A function without a stub fails with a function error, as
+    /// its deployment package cannot run here.
+    pub async fn invoke_with_payload(
+        &self,
+        function_name: &str,
+        payload: serde_json::Value,
+    ) -> Result<Result<serde_json::Value, String>, LambdaError> {
+        Ok(self.run(function_name, payload).await?.unwrap_or_else(|| {
+            Err(format!(
+                "Function {function_name} cannot run: deployment packages are not executed; \
+                 set a stub with PUT /_aws/lambda/functions/{function_name}/stub"
+            ))
+        }))
+    }
+
     pub async fn update_function_code(
         &self,
         function_name: &str,
@@ -233,20 +372,13 @@ impl LambdaState {
         let err = not_found_err(&inner.account_id, &inner.region, function_name);
         let func = inner.functions.get_mut(function_name).ok_or(err)?;
 
-        let code_bytes = if let Some(ref zip_file) = req.zip_file {
-            base64::engine::general_purpose::STANDARD
-                .decode(zip_file)
-                .unwrap_or_default()
-        } else {
-            Vec::new()
-        };
-
+        let code_bytes = Self::decode_zip(req.zip_file.as_deref());
         func.code_sha256 = Self::compute_sha256(&code_bytes);
         func.code_size = code_bytes.len() as i64;
         func.code = code_bytes;
         func.last_modified = Self::now_iso();
 
-        Ok(Self::function_to_config(func))
+        Ok(Self::in_progress(Self::function_to_config(func), false))
     }
 
     pub async fn update_function_configuration(
@@ -281,27 +413,54 @@ impl LambdaState {
         }
         func.last_modified = Self::now_iso();
 
+        Ok(Self::in_progress(Self::function_to_config(func), false))
+    }
+
+    pub async fn get_function_configuration(
+        &self,
+        function_name: &str,
+    ) -> Result<FunctionConfiguration, LambdaError> {
+        let inner = self.inner.lock().await;
+        let err = not_found_err(&inner.account_id, &inner.region, function_name);
+        let func = inner.functions.get(function_name).ok_or(err)?;
         Ok(Self::function_to_config(func))
     }
 
+    /// Backs the `Invoke` API. A `RequestResponse` call runs the function's
+    /// stub; a function without one returns `null`.
     pub async fn invoke(
         &self,
         function_name: &str,
         invocation_type: Option<&str>,
-    ) -> Result<(axum::http::StatusCode, String), LambdaError> {
-        let inner = self.inner.lock().await;
-        if !inner.functions.contains_key(function_name) {
-            return Err(not_found_err(
-                &inner.account_id,
-                &inner.region,
-                function_name,
-            ));
-        }
-
+        payload: &[u8],
+    ) -> Result<InvokeResult, LambdaError> {
+        let event = if payload.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(payload).map_err(|e| {
+                LambdaError::InvalidParameterValueException(format!("Could not parse request body into json: {e}"))
+            })?
+        };
+        let empty = |status| InvokeResult { status, function_error: false, payload: String::new() };
         match invocation_type {
-            Some("Event") => Ok((axum::http::StatusCode::ACCEPTED, String::new())),
-            Some("DryRun") => Ok((axum::http::StatusCode::NO_CONTENT, String::new())),
-            _ => Ok((axum::http::StatusCode::OK, "null".to_string())),
+            Some("Event") => {
+                self.run(function_name, serde_json::Value::Null).await?;
+                Ok(empty(axum::http::StatusCode::ACCEPTED))
+            }
+            Some("DryRun") => {
+                self.run(function_name, serde_json::Value::Null).await?;
+                Ok(empty(axum::http::StatusCode::NO_CONTENT))
+            }
+            _ => {
+                let (function_error, payload) = match self.run(function_name, event).await? {
+                    Some(Ok(result)) => (false, result.to_string()),
+                    Some(Err(message)) => {
+                        (true, serde_json::json!({ "errorMessage": message, "errorType": "Unhandled" }).to_string())
+                    }
+                    None => (false, "null".to_string()),
+                };
+                Ok(InvokeResult { status: axum::http::StatusCode::OK, function_error, payload })
+            }
         }
     }
 
@@ -758,6 +917,35 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_code_hash_size_and_state_transitions() {
+        use base64::Engine;
+        let state = make_state();
+        let mut req = make_create_fn_req("fn1");
+        req.code.zip_file = Some(base64::engine::general_purpose::STANDARD.encode(b"hello zip"));
+        let created = state.create_function(req).await.unwrap();
+        assert_eq!(created.code_size, 9);
+        assert_eq!(created.state, "Pending");
+        assert_eq!(created.state_reason_code.as_deref(), Some("Creating"));
+
+        let got = state.get_function("fn1").await.unwrap().configuration;
+        assert_eq!(got.state, "Active");
+        assert_eq!(got.last_update_status, "Successful");
+        assert_eq!(got.code_sha256, created.code_sha256);
+
+        let updated = state.update_function_code("fn1", UpdateFunctionCodeRequest {
+            zip_file: Some(base64::engine::general_purpose::STANDARD.encode(b"new code")),
+        }).await.unwrap();
+        assert_eq!(updated.last_update_status, "InProgress");
+        assert_eq!(updated.code_size, 8);
+        assert_ne!(updated.code_sha256, created.code_sha256);
+
+        let config = state.get_function_configuration("fn1").await.unwrap();
+        assert_eq!(config.code_sha256, updated.code_sha256);
+        assert_eq!(config.last_update_status, "Successful");
+        assert!(config.last_update_status_reason.is_none());
+    }
+
     #[tokio::test]
     async fn test_get_function_not_found() {
         let state = make_state();
@@ -804,8 +992,40 @@ mod tests {
     async fn test_invoke() {
         let state = make_state();
         state.create_function(make_create_fn_req("fn1")).await.unwrap();
-        let result = state.invoke("fn1", None).await;
-        assert!(result.is_ok());
+        let result = state.invoke("fn1", None, b"").await.unwrap();
+        assert_eq!(result.payload, "null");
+
+        state.set_stub("fn1", Some(FunctionStub::Echo)).await.unwrap();
+        let result = state.invoke("fn1", None, br#"{"a":1}"#).await.unwrap();
+        assert_eq!(result.payload, r#"{"a":1}"#);
+        assert!(!result.function_error);
+
+        let stub = FunctionStub::Error { error_message: "boom".to_string() };
+        state.set_stub("fn1", Some(stub)).await.unwrap();
+        let result = state.invoke("fn1", None, b"{}").await.unwrap();
+        assert!(result.function_error);
+        assert!(result.payload.contains("boom"));
+        assert!(state.invoke("fn1", None, b"not json").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_with_payload_uses_handler() {
+        let state = make_state();
+        state.create_function(make_create_fn_req("fn1")).await.unwrap();
+        // Without a handler the function cannot run.
+        let err = state.invoke_with_payload("fn1", serde_json::json!({})).await.unwrap().unwrap_err();
+        assert!(err.contains("cannot run"));
+
+        let handler: FunctionHandler = Arc::new(|event| {
+            Box::pin(async move { Ok(serde_json::json!({ "echo": event["name"] })) })
+        });
+        state.set_handler("fn1", handler).await.unwrap();
+        let result = state
+            .invoke_with_payload("fn1", serde_json::json!({ "name": "x" }))
+            .await
+            .unwrap();
+        assert_eq!(result, Ok(serde_json::json!({ "echo": "x" })));
+        assert!(state.invoke_with_payload("missing", serde_json::Value::Null).await.is_err());
     }
 
     #[tokio::test]
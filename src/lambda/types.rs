@@ -63,6 +63,15 @@ pub struct FunctionConfiguration {
     pub code_sha256: String,
     pub version: String,
     pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_reason_code: Option<String>,
+    pub last_update_status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_update_status_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_update_status_reason_code: Option<String>,
     pub package_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub environment: Option<Environment>,
@@ -256,3 +265,44 @@ pub struct TagResourceRequest {
 pub struct ListTagsResponse {
     pub tags: HashMap<String, String>,
 }
+
+// --- Function stubs (not an AWS API) ---
+
+/// What a function returns when invoked. Deployment packages are never
+/// executed, so a function only produces a result once a stub is set with
+/// `PUT /_aws/lambda/functions/{name}/stub`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum FunctionStub {
+    /// Returns the event unchanged.
+    Echo,
+    /// Returns `result`, whatever the event.
+    Return {
+        #[serde(default)]
+        result: serde_json::Value,
+    },
+    /// Fails with a function error carrying `errorMessage`.
+    Error {
+        #[serde(rename = "errorMessage")]
+        error_message: String,
+    },
+}
+
+impl FunctionStub {
+    pub fn run(&self, event: serde_json::Value) -> Result<serde_json::Value, String> {
+        match self {
+            FunctionStub::Echo => Ok(event),
+            FunctionStub::Return { result } => Ok(result.clone()),
+            FunctionStub::Error { error_message } => Err(error_message.clone()),
+        }
+    }
+}
+
+/// The outcome of the `Invoke` API.
+#[derive(Debug)]
+pub struct InvokeResult {
+    pub status: axum::http::StatusCode,
+    /// Set when the function failed; the payload then describes the error.
+    pub function_error: bool,
+    pub payload: String,
+}
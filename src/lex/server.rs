@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<LexState>) -> Router {
     Router::new()
         .route("/bots", post(create_bot_handler).get(list_bots_handler))
         .route("/bots/{name}", get(get_bot_handler).delete(delete_bot_handler))
+        .merge(crate::healthcheck::router("lex"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<LocationState>) -> Router {
     Router::new()
         .route("/maps", post(create_map_handler).get(list_maps_handler))
         .route("/maps/{name}", get(get_map_handler).delete(delete_map_handler))
+        .merge(crate::healthcheck::router("location"))
         .with_state(state)
 }
 
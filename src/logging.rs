@@ -0,0 +1,306 @@
+//! Request/response logging shared by every service router.
+
+use std::io::IsTerminal;
+use std::time::Instant;
+
+use axum::body::{Body, HttpBody};
+use axum::extract::{Request, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use tracing::Level;
+
+/// Logged payloads are cut off after this many bytes.
+const MAX_LOGGED_BODY: usize = 4096;
+
+/// Form bodies larger than this are not buffered to find their `Action`.
+const MAX_FORM_BODY: usize = 1024 * 1024;
+
+#[derive(Clone, Copy)]
+struct LogConfig {
+    service: &'static str,
+    bodies: bool,
+}
+
+/// Installs the global `tracing` subscriber. `level` is one of `off`,
+/// `error`, `warn`, `info`, `debug` or `trace`.
+pub fn init(level: &str) -> Result<(), String> {
+    let filter: tracing_subscriber::filter::LevelFilter =
+        level.parse().map_err(|_| format!("invalid log level: {level}"))?;
+    tracing_subscriber::fmt()
+        .with_max_level(filter)
+        .with_target(false)
+        .with_ansi(std::io::stdout().is_terminal())
+        .init();
+    Ok(())
+}
+
+/// Wraps a service router so every request is logged with its method,
+/// path, action, status and latency, and optionally its payloads.
+pub fn layer(router: Router, service: &'static str, bodies: bool) -> Router {
+    router.layer(middleware::from_fn_with_state(LogConfig { service, bodies }, log_request))
+}
+
+async fn log_request(State(config): State<LogConfig>, req: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let mut action = req
+        .headers()
+        .get("x-amz-target")
+        .and_then(|v| v.to_str().ok())
+        .map(|target| target.rsplit('.').next().unwrap_or(target).to_string())
+        .or_else(|| query_action(req.uri().query().unwrap_or_default()));
+
+    // Query-protocol services carry the action in a form-encoded body, so
+    // small form bodies are buffered; any body is buffered for --log-bodies.
+    let is_form = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/x-www-form-urlencoded"));
+    let small = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse::<usize>().ok())
+        .is_some_and(|len| len <= MAX_FORM_BODY);
+    let find_action = is_form && small && action.is_none();
+    let mut request_body = None;
+    let req = if config.bodies || find_action {
+        let (parts, body) = req.into_parts();
+        let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                tracing::warn!(service = config.service, %method, path, %error, "failed to read request body");
+                return (StatusCode::BAD_REQUEST, format!("Failed to read request body: {error}")).into_response();
+            }
+        };
+        if find_action {
+            action = query_action(&String::from_utf8_lossy(&bytes));
+        }
+        if config.bodies {
+            request_body = Some(truncate(&bytes));
+        }
+        Request::from_parts(parts, Body::from(bytes))
+    } else {
+        req
+    };
+
+    let response = next.run(req).await;
+    let status = response.status();
+    // Only bodies already held in memory are captured: buffering a stream
+    // (event streams, streamed S3 objects) would hold it until it ends.
+    let capture = config.bodies && !is_event_stream(&response) && response.body().size_hint().exact().is_some();
+    let (response, response_body) = if capture {
+        let (parts, body) = response.into_parts();
+        match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(bytes) => {
+                let logged = truncate(&bytes);
+                (Response::from_parts(parts, Body::from(bytes)), Some(logged))
+            }
+            Err(error) => {
+                tracing::error!(service = config.service, %method, path, %error, "failed to read response body");
+                return (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read response body: {error}"))
+                    .into_response();
+            }
+        }
+    } else {
+        (response, config.bodies.then(|| "(streamed)".to_string()))
+    };
+
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let action = action.as_deref().unwrap_or("-");
+    macro_rules! emit {
+        ($level:expr) => {
+            match (request_body, response_body) {
+                (Some(request_body), Some(response_body)) => tracing::event!(
+                    $level,
+                    service = config.service,
+                    %method,
+                    path,
+                    action,
+                    status = status.as_u16(),
+                    latency_ms = format_args!("{latency_ms:.2}"),
+                    request_body,
+                    response_body,
+                    "request"
+                ),
+                _ => tracing::event!(
+                    $level,
+                    service = config.service,
+                    %method,
+                    path,
+                    action,
+                    status = status.as_u16(),
+                    latency_ms = format_args!("{latency_ms:.2}"),
+                    "request"
+                ),
+            }
+        };
+    }
+    if status.is_server_error() {
+        emit!(Level::ERROR);
+    } else if status.is_client_error() {
+        emit!(Level::WARN);
+    } else {
+        emit!(Level::INFO);
+    }
+    response
+}
+
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with(crate::eventstream::CONTENT_TYPE))
+}
+
+fn query_action(query: &str) -> Option<String> {
+    form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "Action")
+        .map(|(_, value)| value.into_owned())
+}
+
+fn truncate(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_LOGGED_BODY)]).into_owned();
+    if bytes.len() > MAX_LOGGED_BODY {
+        format!("{text}... ({} bytes)", bytes.len())
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture() -> (Captured, tracing::subscriber::DefaultGuard) {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        (captured, tracing::subscriber::set_default(subscriber))
+    }
+
+    fn app(bodies: bool) -> Router {
+        let router = Router::new().route(
+            "/",
+            post(|body: String| async move {
+                if body.contains("Fail") {
+                    (StatusCode::BAD_REQUEST, "bad".to_string())
+                } else {
+                    (StatusCode::OK, format!("echo {body}"))
+                }
+            }),
+        );
+        layer(router, "SQS", bodies)
+    }
+
+    #[tokio::test]
+    async fn test_logs_form_action_and_status() {
+        let (captured, _guard) = capture();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(CONTENT_LENGTH, 33)
+            .body(Body::from("Action=SendMessage&MessageBody=hi"))
+            .unwrap();
+        let resp = app(false).oneshot(req).await.unwrap();
+        // The handler still sees the buffered body.
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"echo Action=SendMessage&MessageBody=hi");
+
+        let log = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(log.contains("INFO"), "{log}");
+        assert!(log.contains("service=\"SQS\""), "{log}");
+        assert!(log.contains("action=\"SendMessage\""), "{log}");
+        assert!(log.contains("status=200"), "{log}");
+        assert!(!log.contains("request_body"), "{log}");
+    }
+
+    #[tokio::test]
+    async fn test_logs_target_header_and_bodies() {
+        let (captured, _guard) = capture();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("x-amz-target", "AmazonSQS.FailQueue")
+            .body(Body::from("{\"Fail\": true}"))
+            .unwrap();
+        let resp = app(true).oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let log = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(log.contains("WARN"), "{log}");
+        assert!(log.contains("action=\"FailQueue\""), "{log}");
+        assert!(log.contains("status=400"), "{log}");
+        assert!(log.contains("response_body=\"bad\""), "{log}");
+    }
+
+    #[tokio::test]
+    async fn test_streamed_responses_are_not_buffered() {
+        let (captured, _guard) = capture();
+        let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(1);
+        let rx = std::sync::Arc::new(std::sync::Mutex::new(Some(rx)));
+        let router = Router::new().route(
+            "/",
+            post(move || {
+                let rx = rx.lock().unwrap().take().unwrap();
+                async move {
+                    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+                        rx.recv().await.map(|chunk| (chunk, rx))
+                    });
+                    Body::from_stream(stream)
+                }
+            }),
+        );
+        let req = Request::builder().method("POST").uri("/").body(Body::from("{}")).unwrap();
+        // The response arrives while the stream is still open.
+        let resp = tokio::time::timeout(std::time::Duration::from_secs(1), layer(router, "Kinesis", true).oneshot(req))
+            .await
+            .expect("response was held back")
+            .unwrap();
+        tx.send(Ok("event".to_string())).await.unwrap();
+        drop(tx);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"event");
+
+        let log = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(log.contains("response_body=\"(streamed)\""), "{log}");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate(b"short"), "short");
+        let long = vec![b'a'; MAX_LOGGED_BODY + 10];
+        assert!(truncate(&long).ends_with(&format!("... ({} bytes)", MAX_LOGGED_BODY + 10)));
+    }
+}
@@ -1,17 +1,21 @@
 use std::sync::Arc;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
 mod acm;
+mod admin;
 mod amplify;
 mod apigateway;
+mod apigatewayv2;
 mod appfabric;
 mod appflow;
 mod appmesh;
 mod apprunner;
 mod appsync;
+mod arn;
 mod athena;
 mod autoscaling;
+mod aws_error;
 mod b2bi;
 mod backup;
 mod batch;
@@ -19,6 +23,7 @@ mod bedrock;
 mod billingconductor;
 mod braket;
 mod budgets;
+mod bus;
 mod chime;
 mod cleanrooms;
 mod cloudformation;
@@ -39,6 +44,7 @@ mod cognito;
 mod comprehend;
 mod computeoptimizer;
 mod config;
+mod config_file;
 mod connect;
 mod controltower;
 mod costexplorer;
@@ -67,6 +73,8 @@ mod elb;
 mod emr;
 mod entityresolution;
 mod eventbridge;
+mod eventstream;
+mod faults;
 mod finspace;
 mod firehose;
 mod firewallmanager;
@@ -80,6 +88,7 @@ mod glue;
 mod groundstation;
 mod guardduty;
 mod health;
+mod healthcheck;
 mod healthlake;
 mod iam;
 mod iamidentitycenter;
@@ -103,6 +112,7 @@ mod lex;
 mod licensemanager;
 mod lightsail;
 mod location;
+mod logging;
 mod macie;
 mod mainframemod;
 mod managedblockchain;
@@ -123,6 +133,7 @@ mod networkfirewall;
 mod opensearch;
 mod organizations;
 mod outposts;
+mod pagination;
 mod personalize;
 mod pinpoint;
 mod polly;
@@ -133,6 +144,7 @@ mod ram;
 mod rds;
 mod redshift;
 mod rekognition;
+mod resourcegroupstaggingapi;
 mod route53;
 mod s3;
 mod sagemaker;
@@ -174,6 +186,8 @@ struct Args {
     amplify_port: u16,
     #[arg(long, default_value = "4567")]
     apigateway_port: u16,
+    #[arg(long, default_value = "10160")]
+    apigatewayv2_port: u16,
     #[arg(long, default_value = "10128")]
     appfabric_port: u16,
     #[arg(long, default_value = "10112")]
@@ -274,6 +288,12 @@ struct Args {
     drs_port: u16,
     #[arg(long, default_value = "8000")]
     dynamodb_port: u16,
+    /// Directory of `<table>.json` fixtures loaded into DynamoDB at startup
+    #[arg(long)]
+    dynamodb_seed: Option<std::path::PathBuf>,
+    /// Cap each BatchWriteItem at a provisioned table's WriteCapacityUnits, returning the rest as UnprocessedItems
+    #[arg(long)]
+    dynamodb_enforce_throughput: bool,
     #[arg(long, default_value = "10001")]
     ec2_port: u16,
     #[arg(long, default_value = "10002")]
@@ -354,10 +374,19 @@ struct Args {
     keyspaces_port: u16,
     #[arg(long, default_value = "4568")]
     kinesis_port: u16,
+    /// Enforce Kinesis per-shard write limits (1 MB/s, 1,000 records/s)
+    #[arg(long)]
+    kinesis_shard_limits: bool,
     #[arg(long, default_value = "10055")]
     kinesisvideostreams_port: u16,
     #[arg(long, default_value = "7600")]
     kms_port: u16,
+    /// Check KMS Encrypt/Decrypt/Sign calls against key policies and grants
+    #[arg(long)]
+    kms_enforce_policies: bool,
+    /// Request header carrying the calling principal ARN when KMS policies are enforced
+    #[arg(long, default_value = "x-amz-principal")]
+    kms_principal_header: String,
     #[arg(long, default_value = "10066")]
     lakeformation_port: u16,
     #[arg(long, default_value = "9001")]
@@ -430,6 +459,8 @@ struct Args {
     redshift_port: u16,
     #[arg(long, default_value = "10101")]
     rekognition_port: u16,
+    #[arg(long, default_value = "10159")]
+    resourcegroupstaggingapi_port: u16,
     #[arg(long, default_value = "10022")]
     route53_port: u16,
     #[arg(long, default_value = "9000")]
@@ -446,6 +477,12 @@ struct Args {
     servicecatalog_port: u16,
     #[arg(long, default_value = "9300")]
     ses_port: u16,
+    /// Recipients SES accepts in any 24 hours before returning LimitExceededException (unlimited if unset)
+    #[arg(long)]
+    ses_max_24_hour_send: Option<f64>,
+    /// Recipients per second SES accepts before returning Throttling (unlimited if unset)
+    #[arg(long)]
+    ses_max_send_rate: Option<f64>,
     #[arg(long, default_value = "10036")]
     shield_port: u16,
     #[arg(long, default_value = "9911")]
@@ -454,6 +491,13 @@ struct Args {
     sqs_port: u16,
     #[arg(long, default_value = "9100")]
     ssm_port: u16,
+    /// Run aws:runShellScript steps of SSM Run Command on this host, for any
+    /// client that can reach the SSM port
+    #[arg(long)]
+    ssm_allow_exec: bool,
+    /// Comma-separated fake managed instance IDs that SSM Run Command can target
+    #[arg(long, value_delimiter = ',', default_value = ssm::state::DEFAULT_INSTANCE_ID)]
+    ssm_instance_ids: Vec<String>,
     #[arg(long, default_value = "8083")]
     stepfunctions_port: u16,
     #[arg(long, default_value = "10148")]
@@ -486,15 +530,76 @@ struct Args {
     workspaces_port: u16,
     #[arg(long, default_value = "10089")]
     xray_port: u16,
+    /// Port for the admin endpoints (`/_health`, `/_admin/reset`)
+    #[arg(long, default_value = "4599")]
+    admin_port: u16,
     #[arg(long, default_value = "us-east-1")]
     region: String,
     #[arg(long, default_value = "000000000000")]
     account_id: String,
+    /// Accept ARNs of other accounts and regions (DLQs, SNS topics, KMS keys, EventBridge targets)
+    #[arg(long)]
+    allow_cross_account: bool,
+    /// Request log level: off, error, warn, info, debug or trace
+    #[arg(long, default_value = "info", value_parser = ["off", "error", "warn", "info", "debug", "trace"])]
+    log_level: String,
+    /// Include request and response payloads (truncated) in request logs
+    #[arg(long)]
+    log_bodies: bool,
+    /// Comma-separated services to run, named as in `--<service>-port` (default: all)
+    #[arg(long, value_delimiter = ',')]
+    services: Vec<String>,
+    /// TOML or YAML file of settings; flags on the command line take precedence
+    #[arg(long)]
+    config: Option<std::path::PathBuf>,
+}
+
+impl Args {
+    fn is_enabled(&self, service: &str) -> bool {
+        self.services.is_empty() || self.services.iter().any(|s| s == service)
+    }
+}
+
+/// Service names as used in `--<service>-port`.
+fn service_names() -> Vec<String> {
+    Args::command()
+        .get_arguments()
+        .filter_map(|arg| arg.get_id().as_str().strip_suffix("_port").map(str::to_string))
+        .filter(|service| service != "admin")
+        .collect()
 }
 
 #[tokio::main]
 async fn main() {
-    let args = Args::parse();
+    let services = service_names();
+    let argv = config_file::expand_args(std::env::args().collect(), &services).unwrap_or_else(|e| {
+        eprintln!("error: --config {e}");
+        std::process::exit(2);
+    });
+    let (argv, mut fault_configs) = faults::extract_args(argv, &services).unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        std::process::exit(2);
+    });
+    let args = Args::parse_from(argv);
+    if let Some(unknown) = args.services.iter().find(|s| !services.contains(s)) {
+        eprintln!("error: unknown service '{unknown}' in --services");
+        std::process::exit(2);
+    }
+    if let Err(e) = logging::init(&args.log_level) {
+        eprintln!("{e}");
+        std::process::exit(2);
+    }
+    if args.ssm_allow_exec && args.is_enabled("ssm") {
+        // Listeners bind every interface, so anyone who can reach the SSM
+        // port can run commands as this user.
+        eprintln!(
+            "WARNING: --ssm-allow-exec is on: SSM SendCommand runs aws:runShellScript steps with `sh -c` on this \
+             host for any client that can reach port {} (listeners bind 0.0.0.0). Only use it on a trusted network.",
+            args.ssm_port
+        );
+    }
+
+    let service_bus = Arc::new(bus::ServiceBus::new());
 
     let acm_state = Arc::new(acm::state::ACMState::new(
         args.account_id.clone(),
@@ -504,10 +609,14 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let apigateway_state = Arc::new(apigateway::state::ApiGatewayState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let apigateway_state = Arc::new(
+        apigateway::state::ApiGatewayState::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone()),
+    );
+    let apigatewayv2_state = Arc::new(
+        apigatewayv2::state::ApiGatewayV2State::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone()),
+    );
     let appfabric_state = Arc::new(appfabric::state::AppfabricState::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -604,6 +713,7 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
+    cloudwatchlogs_state.start_retention_enforcement(std::time::Duration::from_secs(60));
     let codeartifact_state = Arc::new(codeartifact::state::CodeartifactState::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -704,10 +814,16 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let dynamodb_state = Arc::new(dynamodb::state::DynamoDbState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let dynamodb_state = Arc::new(
+        dynamodb::state::DynamoDbState::new(args.account_id.clone(), args.region.clone())
+            .with_throughput_enforcement(args.dynamodb_enforce_throughput),
+    );
+    if let Some(dir) = &args.dynamodb_seed {
+        if let Err(e) = dynamodb_state.seed_from_dir(dir).await {
+            eprintln!("error: --dynamodb-seed {e}");
+            std::process::exit(2);
+        }
+    }
     let ec2_state = Arc::new(ec2::state::EC2State::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -752,18 +868,19 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let eventbridge_state = Arc::new(eventbridge::state::EventBridgeState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let eventbridge_state = Arc::new(
+        eventbridge::state::EventBridgeState::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone())
+            .with_cross_account(args.allow_cross_account),
+    );
     let finspace_state = Arc::new(finspace::state::FinspaceState::new(
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let firehose_state = Arc::new(firehose::state::FirehoseState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let firehose_state = Arc::new(
+        firehose::state::FirehoseState::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone()),
+    );
     let firewallmanager_state = Arc::new(firewallmanager::state::FirewallmanagerState::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -864,18 +981,21 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let kinesis_state = Arc::new(kinesis::state::KinesisState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let kinesis_state = Arc::new(
+        kinesis::state::KinesisState::new(args.account_id.clone(), args.region.clone())
+            .with_shard_limits(args.kinesis_shard_limits)
+            .with_cross_account(args.allow_cross_account),
+    );
     let kinesisvideostreams_state = Arc::new(kinesisvideostreams::state::KinesisvideostreamsState::new(
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let kms_state = Arc::new(kms::state::KmsState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let kms_state = Arc::new(
+        kms::state::KmsState::new(args.account_id.clone(), args.region.clone())
+            .with_policy_enforcement(
+                args.kms_enforce_policies.then(|| args.kms_principal_header.clone()),
+            ),
+    );
     let lakeformation_state = Arc::new(lakeformation::state::LakeformationState::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -1020,6 +1140,9 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
+    let resourcegroupstaggingapi_state = Arc::new(
+        resourcegroupstaggingapi::state::TaggingState::new().with_bus(service_bus.clone()),
+    );
     let route53_state = Arc::new(route53::state::Route53State::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -1032,10 +1155,11 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let secretsmanager_state = Arc::new(secretsmanager::state::SecretsManagerState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let secretsmanager_state = Arc::new(
+        secretsmanager::state::SecretsManagerState::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone()),
+    );
+    secretsmanager_state.start_rotation_schedule(std::time::Duration::from_secs(60));
     let securityhub_state = Arc::new(securityhub::state::SecurityhubState::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -1048,31 +1172,33 @@ async fn main() {
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let ses_state = Arc::new(ses::state::SesState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let ses_state = Arc::new(
+        ses::state::SesState::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone())
+            .with_send_limits(args.ses_max_24_hour_send, args.ses_max_send_rate)
+            .with_cross_account(args.allow_cross_account),
+    );
     let shield_state = Arc::new(shield::state::ShieldState::new(
         args.account_id.clone(),
         args.region.clone(),
     ));
-    let sns_state = Arc::new(sns::state::SnsState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
-    let sqs_state = Arc::new(sqs::state::SqsState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-        args.sqs_port,
-    ));
-    let ssm_state = Arc::new(ssm::state::SsmState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
-    let stepfunctions_state = Arc::new(stepfunctions::state::SfnState::new(
-        args.account_id.clone(),
-        args.region.clone(),
-    ));
+    let sns_state = Arc::new(
+        sns::state::SnsState::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone())
+            .with_cross_account(args.allow_cross_account),
+    );
+    let sqs_state = Arc::new(
+        sqs::state::SqsState::new(args.account_id.clone(), args.region.clone(), args.sqs_port)
+            .with_cross_account(args.allow_cross_account),
+    );
+    let ssm_state = Arc::new(
+        ssm::state::SsmState::new(args.account_id.clone(), args.region.clone())
+            .with_run_command(args.ssm_instance_ids.clone(), args.ssm_allow_exec),
+    );
+    let stepfunctions_state = Arc::new(
+        stepfunctions::state::SfnState::new(args.account_id.clone(), args.region.clone())
+            .with_bus(service_bus.clone()),
+    );
     let storagegateway_state = Arc::new(storagegateway::state::StoragegatewayState::new(
         args.account_id.clone(),
         args.region.clone(),
@@ -1134,189 +1260,213 @@ async fn main() {
         args.region.clone(),
     ));
 
-    let acm_app = acm::server::create_router(acm_state);
-    let amplify_app = amplify::server::create_router(amplify_state);
-    let apigateway_app = apigateway::server::create_router(apigateway_state);
-    let appfabric_app = appfabric::server::create_router(appfabric_state);
-    let appflow_app = appflow::server::create_router(appflow_state);
-    let appmesh_app = appmesh::server::create_router(appmesh_state);
-    let apprunner_app = apprunner::server::create_router(apprunner_state);
-    let appsync_app = appsync::server::create_router(appsync_state);
-    let athena_app = athena::server::create_router(athena_state);
-    let autoscaling_app = autoscaling::server::create_router(autoscaling_state);
-    let b2bi_app = b2bi::server::create_router(b2bi_state);
-    let backup_app = backup::server::create_router(backup_state);
-    let batch_app = batch::server::create_router(batch_state);
-    let bedrock_app = bedrock::server::create_router(bedrock_state);
-    let billingconductor_app = billingconductor::server::create_router(billingconductor_state);
-    let braket_app = braket::server::create_router(braket_state);
-    let budgets_app = budgets::server::create_router(budgets_state);
-    let chime_app = chime::server::create_router(chime_state);
-    let cleanrooms_app = cleanrooms::server::create_router(cleanrooms_state);
-    let cloudformation_app = cloudformation::server::create_router(cloudformation_state);
-    let cloudfront_app = cloudfront::server::create_router(cloudfront_state);
-    let cloudhsm_app = cloudhsm::server::create_router(cloudhsm_state);
-    let cloudmap_app = cloudmap::server::create_router(cloudmap_state);
-    let cloudsearch_app = cloudsearch::server::create_router(cloudsearch_state);
-    let cloudtrail_app = cloudtrail::server::create_router(cloudtrail_state);
-    let cloudwatch_app = cloudwatch::server::create_router(cloudwatch_state);
-    let cloudwatchlogs_app = cloudwatchlogs::server::create_router(cloudwatchlogs_state);
-    let codeartifact_app = codeartifact::server::create_router(codeartifact_state);
-    let codebuild_app = codebuild::server::create_router(codebuild_state);
-    let codecatalyst_app = codecatalyst::server::create_router(codecatalyst_state);
-    let codecommit_app = codecommit::server::create_router(codecommit_state);
-    let codedeploy_app = codedeploy::server::create_router(codedeploy_state);
-    let codepipeline_app = codepipeline::server::create_router(codepipeline_state);
-    let cognito_app = cognito::server::create_router(cognito_state);
-    let comprehend_app = comprehend::server::create_router(comprehend_state);
-    let computeoptimizer_app = computeoptimizer::server::create_router(computeoptimizer_state);
-    let config_app = config::server::create_router(config_state);
-    let connect_app = connect::server::create_router(connect_state);
-    let controltower_app = controltower::server::create_router(controltower_state);
-    let costexplorer_app = costexplorer::server::create_router(costexplorer_state);
-    let dataexchange_app = dataexchange::server::create_router(dataexchange_state);
-    let datapipeline_app = datapipeline::server::create_router(datapipeline_state);
-    let datasync_app = datasync::server::create_router(datasync_state);
-    let datazone_app = datazone::server::create_router(datazone_state);
-    let detective_app = detective::server::create_router(detective_state);
-    let devicefarm_app = devicefarm::server::create_router(devicefarm_state);
-    let devopsguru_app = devopsguru::server::create_router(devopsguru_state);
-    let directconnect_app = directconnect::server::create_router(directconnect_state);
-    let directoryservice_app = directoryservice::server::create_router(directoryservice_state);
-    let dms_app = dms::server::create_router(dms_state);
-    let documentdb_app = documentdb::server::create_router(documentdb_state);
-    let drs_app = drs::server::create_router(drs_state);
-    let dynamodb_app = dynamodb::server::create_router(dynamodb_state);
-    let ec2_app = ec2::server::create_router(ec2_state);
-    let ecr_app = ecr::server::create_router(ecr_state);
-    let ecs_app = ecs::server::create_router(ecs_state);
-    let efs_app = efs::server::create_router(efs_state);
-    let eks_app = eks::server::create_router(eks_state);
-    let elasticache_app = elasticache::server::create_router(elasticache_state);
-    let elasticbeanstalk_app = elasticbeanstalk::server::create_router(elasticbeanstalk_state);
-    let elastictranscoder_app = elastictranscoder::server::create_router(elastictranscoder_state);
-    let elb_app = elb::server::create_router(elb_state);
-    let emr_app = emr::server::create_router(emr_state);
-    let entityresolution_app = entityresolution::server::create_router(entityresolution_state);
-    let eventbridge_app = eventbridge::server::create_router(eventbridge_state);
-    let finspace_app = finspace::server::create_router(finspace_state);
-    let firehose_app = firehose::server::create_router(firehose_state);
-    let firewallmanager_app = firewallmanager::server::create_router(firewallmanager_state);
-    let fis_app = fis::server::create_router(fis_state);
-    let forecast_app = forecast::server::create_router(forecast_state);
-    let frauddetector_app = frauddetector::server::create_router(frauddetector_state);
-    let fsx_app = fsx::server::create_router(fsx_state);
-    let gamelift_app = gamelift::server::create_router(gamelift_state);
-    let globalaccelerator_app = globalaccelerator::server::create_router(globalaccelerator_state);
-    let glue_app = glue::server::create_router(glue_state);
-    let groundstation_app = groundstation::server::create_router(groundstation_state);
-    let guardduty_app = guardduty::server::create_router(guardduty_state);
-    let health_app = health::server::create_router(health_state);
-    let healthlake_app = healthlake::server::create_router(healthlake_state);
-    let iam_app = iam::server::create_router(iam_state);
-    let iamidentitycenter_app = iamidentitycenter::server::create_router(iamidentitycenter_state);
-    let imagebuilder_app = imagebuilder::server::create_router(imagebuilder_state);
-    let inspector_app = inspector::server::create_router(inspector_state);
-    let iotcore_app = iotcore::server::create_router(iotcore_state);
-    let iotevents_app = iotevents::server::create_router(iotevents_state);
-    let iotfleetwise_app = iotfleetwise::server::create_router(iotfleetwise_state);
-    let iotgreengrass_app = iotgreengrass::server::create_router(iotgreengrass_state);
-    let iotsitewise_app = iotsitewise::server::create_router(iotsitewise_state);
-    let iottwinmaker_app = iottwinmaker::server::create_router(iottwinmaker_state);
-    let ivs_app = ivs::server::create_router(ivs_state);
-    let kendra_app = kendra::server::create_router(kendra_state);
-    let keyspaces_app = keyspaces::server::create_router(keyspaces_state);
-    let kinesis_app = kinesis::server::create_router(kinesis_state);
-    let kinesisvideostreams_app = kinesisvideostreams::server::create_router(kinesisvideostreams_state);
-    let kms_app = kms::server::create_router(kms_state);
-    let lakeformation_app = lakeformation::server::create_router(lakeformation_state);
-    let lambda_app = lambda::server::create_router(lambda_state);
-    let lex_app = lex::server::create_router(lex_state);
-    let licensemanager_app = licensemanager::server::create_router(licensemanager_state);
-    let lightsail_app = lightsail::server::create_router(lightsail_state);
-    let location_app = location::server::create_router(location_state);
-    let macie_app = macie::server::create_router(macie_state);
-    let mainframemod_app = mainframemod::server::create_router(mainframemod_state);
-    let managedblockchain_app = managedblockchain::server::create_router(managedblockchain_state);
-    let managedflink_app = managedflink::server::create_router(managedflink_state);
-    let managedgrafana_app = managedgrafana::server::create_router(managedgrafana_state);
-    let managedprometheus_app = managedprometheus::server::create_router(managedprometheus_state);
-    let mediaconvert_app = mediaconvert::server::create_router(mediaconvert_state);
-    let medialive_app = medialive::server::create_router(medialive_state);
-    let mediapackage_app = mediapackage::server::create_router(mediapackage_state);
-    let mediastore_app = mediastore::server::create_router(mediastore_state);
-    let memorydb_app = memorydb::server::create_router(memorydb_state);
-    let migrationhub_app = migrationhub::server::create_router(migrationhub_state);
-    let mq_app = mq::server::create_router(mq_state);
-    let msk_app = msk::server::create_router(msk_state);
-    let mwaa_app = mwaa::server::create_router(mwaa_state);
-    let neptune_app = neptune::server::create_router(neptune_state);
-    let networkfirewall_app = networkfirewall::server::create_router(networkfirewall_state);
-    let opensearch_app = opensearch::server::create_router(opensearch_state);
-    let organizations_app = organizations::server::create_router(organizations_state);
-    let outposts_app = outposts::server::create_router(outposts_state);
-    let personalize_app = personalize::server::create_router(personalize_state);
-    let pinpoint_app = pinpoint::server::create_router(pinpoint_state);
-    let polly_app = polly::server::create_router(polly_state);
-    let proton_app = proton::server::create_router(proton_state);
-    let qbusiness_app = qbusiness::server::create_router(qbusiness_state);
-    let quicksight_app = quicksight::server::create_router(quicksight_state);
-    let ram_app = ram::server::create_router(ram_state);
-    let rds_app = rds::server::create_router(rds_state);
-    let redshift_app = redshift::server::create_router(redshift_state);
-    let rekognition_app = rekognition::server::create_router(rekognition_state);
-    let route53_app = route53::server::create_router(route53_state);
-    let s3_app = s3::server::create_router(s3_state);
-    let sagemaker_app = sagemaker::server::create_router(sagemaker_state);
-    let secretsmanager_app = secretsmanager::server::create_router(secretsmanager_state);
-    let securityhub_app = securityhub::server::create_router(securityhub_state);
-    let securitylake_app = securitylake::server::create_router(securitylake_state);
-    let servicecatalog_app = servicecatalog::server::create_router(servicecatalog_state);
-    let ses_app = ses::server::create_router(ses_state);
-    let shield_app = shield::server::create_router(shield_state);
-    let sns_app = sns::server::create_router(sns_state);
-    let sqs_app = sqs::server::create_router(sqs_state);
-    let ssm_app = ssm::server::create_router(ssm_state);
-    let stepfunctions_app = stepfunctions::server::create_router(stepfunctions_state);
-    let storagegateway_app = storagegateway::server::create_router(storagegateway_state);
-    let swf_app = swf::server::create_router(swf_state);
-    let textract_app = textract::server::create_router(textract_state);
-    let timestream_app = timestream::server::create_router(timestream_state);
-    let transcribe_app = transcribe::server::create_router(transcribe_state);
-    let transferfamily_app = transferfamily::server::create_router(transferfamily_state);
-    let translate_app = translate::server::create_router(translate_state);
-    let trustedadvisor_app = trustedadvisor::server::create_router(trustedadvisor_state);
-    let verifiedpermissions_app = verifiedpermissions::server::create_router(verifiedpermissions_state);
-    let vpclattice_app = vpclattice::server::create_router(vpclattice_state);
-    let waf_app = waf::server::create_router(waf_state);
-    let workdocs_app = workdocs::server::create_router(workdocs_state);
-    let workmail_app = workmail::server::create_router(workmail_state);
-    let workspaces_app = workspaces::server::create_router(workspaces_state);
-    let xray_app = xray::server::create_router(xray_state);
+    service_bus.attach_sqs(sqs_state.clone());
+    service_bus.attach_lambda(lambda_state.clone());
+    service_bus.attach_s3(s3_state.clone());
+    service_bus.attach_sns(sns_state.clone());
+    service_bus.attach_dynamodb(dynamodb_state.clone());
+    service_bus.attach_memorydb(memorydb_state.clone());
+
+    let mut registry = admin::Registry::default();
+    let acm_app = acm::server::create_router(register_reset!(registry, acm_state));
+    let amplify_app = amplify::server::create_router(register_reset!(registry, amplify_state));
+    let apigateway_app = apigateway::server::create_router(register_reset!(registry, apigateway_state));
+    let apigatewayv2_app = apigatewayv2::server::create_router(register_reset!(registry, apigatewayv2_state));
+    let appfabric_app = appfabric::server::create_router(register_reset!(registry, appfabric_state));
+    let appflow_app = appflow::server::create_router(register_reset!(registry, appflow_state));
+    let appmesh_app = appmesh::server::create_router(register_reset!(registry, appmesh_state));
+    let apprunner_app = apprunner::server::create_router(register_reset!(registry, apprunner_state));
+    let appsync_app = appsync::server::create_router(register_reset!(registry, appsync_state));
+    let athena_app = athena::server::create_router(register_reset!(registry, athena_state));
+    let autoscaling_app = autoscaling::server::create_router(register_reset!(registry, autoscaling_state));
+    let b2bi_app = b2bi::server::create_router(register_reset!(registry, b2bi_state));
+    let backup_app = backup::server::create_router(register_reset!(registry, backup_state));
+    let batch_app = batch::server::create_router(register_reset!(registry, batch_state));
+    let bedrock_app = bedrock::server::create_router(register_reset!(registry, bedrock_state));
+    let billingconductor_app = billingconductor::server::create_router(register_reset!(registry, billingconductor_state));
+    let braket_app = braket::server::create_router(register_reset!(registry, braket_state));
+    let budgets_app = budgets::server::create_router(register_reset!(registry, budgets_state));
+    let chime_app = chime::server::create_router(register_reset!(registry, chime_state));
+    let cleanrooms_app = cleanrooms::server::create_router(register_reset!(registry, cleanrooms_state));
+    let cloudformation_app = cloudformation::server::create_router(register_reset!(registry, cloudformation_state));
+    let cloudfront_app = cloudfront::server::create_router(register_reset!(registry, cloudfront_state));
+    let cloudhsm_app = cloudhsm::server::create_router(register_reset!(registry, cloudhsm_state));
+    let cloudmap_app = cloudmap::server::create_router(register_reset!(registry, cloudmap_state));
+    let cloudsearch_app = cloudsearch::server::create_router(register_reset!(registry, cloudsearch_state));
+    let cloudtrail_app = cloudtrail::server::create_router(register_reset!(registry, cloudtrail_state));
+    let cloudwatch_app = cloudwatch::server::create_router(register_reset!(registry, cloudwatch_state));
+    let cloudwatchlogs_app = cloudwatchlogs::server::create_router(register_reset!(registry, cloudwatchlogs_state));
+    let codeartifact_app = codeartifact::server::create_router(register_reset!(registry, codeartifact_state));
+    let codebuild_app = codebuild::server::create_router(register_reset!(registry, codebuild_state));
+    let codecatalyst_app = codecatalyst::server::create_router(register_reset!(registry, codecatalyst_state));
+    let codecommit_app = codecommit::server::create_router(register_reset!(registry, codecommit_state));
+    let codedeploy_app = codedeploy::server::create_router(register_reset!(registry, codedeploy_state));
+    let codepipeline_app = codepipeline::server::create_router(register_reset!(registry, codepipeline_state));
+    let cognito_app = cognito::server::create_router(register_reset!(registry, cognito_state));
+    let comprehend_app = comprehend::server::create_router(register_reset!(registry, comprehend_state));
+    let computeoptimizer_app = computeoptimizer::server::create_router(register_reset!(registry, computeoptimizer_state));
+    let config_app = config::server::create_router(register_reset!(registry, config_state));
+    let connect_app = connect::server::create_router(register_reset!(registry, connect_state));
+    let controltower_app = controltower::server::create_router(register_reset!(registry, controltower_state));
+    let costexplorer_app = costexplorer::server::create_router(register_reset!(registry, costexplorer_state));
+    let dataexchange_app = dataexchange::server::create_router(register_reset!(registry, dataexchange_state));
+    let datapipeline_app = datapipeline::server::create_router(register_reset!(registry, datapipeline_state));
+    let datasync_app = datasync::server::create_router(register_reset!(registry, datasync_state));
+    let datazone_app = datazone::server::create_router(register_reset!(registry, datazone_state));
+    let detective_app = detective::server::create_router(register_reset!(registry, detective_state));
+    let devicefarm_app = devicefarm::server::create_router(register_reset!(registry, devicefarm_state));
+    let devopsguru_app = devopsguru::server::create_router(register_reset!(registry, devopsguru_state));
+    let directconnect_app = directconnect::server::create_router(register_reset!(registry, directconnect_state));
+    let directoryservice_app = directoryservice::server::create_router(register_reset!(registry, directoryservice_state));
+    let dms_app = dms::server::create_router(register_reset!(registry, dms_state));
+    let documentdb_app = documentdb::server::create_router(register_reset!(registry, documentdb_state));
+    let drs_app = drs::server::create_router(register_reset!(registry, drs_state));
+    let dynamodb_app = dynamodb::server::create_router(register_reset!(registry, dynamodb_state));
+    let ec2_app = ec2::server::create_router(register_reset!(registry, ec2_state));
+    let ecr_app = ecr::server::create_router(register_reset!(registry, ecr_state));
+    let ecs_app = ecs::server::create_router(register_reset!(registry, ecs_state));
+    let efs_app = efs::server::create_router(register_reset!(registry, efs_state));
+    let eks_app = eks::server::create_router(register_reset!(registry, eks_state));
+    let elasticache_app = elasticache::server::create_router(register_reset!(registry, elasticache_state));
+    let elasticbeanstalk_app = elasticbeanstalk::server::create_router(register_reset!(registry, elasticbeanstalk_state));
+    let elastictranscoder_app = elastictranscoder::server::create_router(register_reset!(registry, elastictranscoder_state));
+    let elb_app = elb::server::create_router(register_reset!(registry, elb_state));
+    let emr_app = emr::server::create_router(register_reset!(registry, emr_state));
+    let entityresolution_app = entityresolution::server::create_router(register_reset!(registry, entityresolution_state));
+    let eventbridge_app = eventbridge::server::create_router(register_reset!(registry, eventbridge_state));
+    let finspace_app = finspace::server::create_router(register_reset!(registry, finspace_state));
+    let firehose_app = firehose::server::create_router(register_reset!(registry, firehose_state));
+    let firewallmanager_app = firewallmanager::server::create_router(register_reset!(registry, firewallmanager_state));
+    let fis_app = fis::server::create_router(register_reset!(registry, fis_state));
+    let forecast_app = forecast::server::create_router(register_reset!(registry, forecast_state));
+    let frauddetector_app = frauddetector::server::create_router(register_reset!(registry, frauddetector_state));
+    let fsx_app = fsx::server::create_router(register_reset!(registry, fsx_state));
+    let gamelift_app = gamelift::server::create_router(register_reset!(registry, gamelift_state));
+    let globalaccelerator_app = globalaccelerator::server::create_router(register_reset!(registry, globalaccelerator_state));
+    let glue_app = glue::server::create_router(register_reset!(registry, glue_state));
+    let groundstation_app = groundstation::server::create_router(register_reset!(registry, groundstation_state));
+    let guardduty_app = guardduty::server::create_router(register_reset!(registry, guardduty_state));
+    let health_app = health::server::create_router(register_reset!(registry, health_state));
+    let healthlake_app = healthlake::server::create_router(register_reset!(registry, healthlake_state));
+    let iam_app = iam::server::create_router(register_reset!(registry, iam_state));
+    let iamidentitycenter_app = iamidentitycenter::server::create_router(register_reset!(registry, iamidentitycenter_state));
+    let imagebuilder_app = imagebuilder::server::create_router(register_reset!(registry, imagebuilder_state));
+    let inspector_app = inspector::server::create_router(register_reset!(registry, inspector_state));
+    let iotcore_app = iotcore::server::create_router(register_reset!(registry, iotcore_state));
+    let iotevents_app = iotevents::server::create_router(register_reset!(registry, iotevents_state));
+    let iotfleetwise_app = iotfleetwise::server::create_router(register_reset!(registry, iotfleetwise_state));
+    let iotgreengrass_app = iotgreengrass::server::create_router(register_reset!(registry, iotgreengrass_state));
+    let iotsitewise_app = iotsitewise::server::create_router(register_reset!(registry, iotsitewise_state));
+    let iottwinmaker_app = iottwinmaker::server::create_router(register_reset!(registry, iottwinmaker_state));
+    let ivs_app = ivs::server::create_router(register_reset!(registry, ivs_state));
+    let kendra_app = kendra::server::create_router(register_reset!(registry, kendra_state));
+    let keyspaces_app = keyspaces::server::create_router(register_reset!(registry, keyspaces_state));
+    let kinesis_app = kinesis::server::create_router(register_reset!(registry, kinesis_state));
+    let kinesisvideostreams_app = kinesisvideostreams::server::create_router(register_reset!(registry, kinesisvideostreams_state));
+    let kms_app = kms::server::create_router(register_reset!(registry, kms_state));
+    let lakeformation_app = lakeformation::server::create_router(register_reset!(registry, lakeformation_state));
+    let lambda_app = lambda::server::create_router(register_reset!(registry, lambda_state));
+    let lex_app = lex::server::create_router(register_reset!(registry, lex_state));
+    let licensemanager_app = licensemanager::server::create_router(register_reset!(registry, licensemanager_state));
+    let lightsail_app = lightsail::server::create_router(register_reset!(registry, lightsail_state));
+    let location_app = location::server::create_router(register_reset!(registry, location_state));
+    let macie_app = macie::server::create_router(register_reset!(registry, macie_state));
+    let mainframemod_app = mainframemod::server::create_router(register_reset!(registry, mainframemod_state));
+    let managedblockchain_app = managedblockchain::server::create_router(register_reset!(registry, managedblockchain_state));
+    let managedflink_app = managedflink::server::create_router(register_reset!(registry, managedflink_state));
+    let managedgrafana_app = managedgrafana::server::create_router(register_reset!(registry, managedgrafana_state));
+    let managedprometheus_app = managedprometheus::server::create_router(register_reset!(registry, managedprometheus_state));
+    let mediaconvert_app = mediaconvert::server::create_router(register_reset!(registry, mediaconvert_state));
+    let medialive_app = medialive::server::create_router(register_reset!(registry, medialive_state));
+    let mediapackage_app = mediapackage::server::create_router(register_reset!(registry, mediapackage_state));
+    let mediastore_app = mediastore::server::create_router(register_reset!(registry, mediastore_state));
+    let memorydb_app = memorydb::server::create_router(register_reset!(registry, memorydb_state));
+    let migrationhub_app = migrationhub::server::create_router(register_reset!(registry, migrationhub_state));
+    let mq_app = mq::server::create_router(register_reset!(registry, mq_state));
+    let msk_app = msk::server::create_router(register_reset!(registry, msk_state));
+    let mwaa_app = mwaa::server::create_router(register_reset!(registry, mwaa_state));
+    let neptune_app = neptune::server::create_router(register_reset!(registry, neptune_state));
+    let networkfirewall_app = networkfirewall::server::create_router(register_reset!(registry, networkfirewall_state));
+    let opensearch_app = opensearch::server::create_router(register_reset!(registry, opensearch_state));
+    let organizations_app = organizations::server::create_router(register_reset!(registry, organizations_state));
+    let outposts_app = outposts::server::create_router(register_reset!(registry, outposts_state));
+    let personalize_app = personalize::server::create_router(register_reset!(registry, personalize_state));
+    let pinpoint_app = pinpoint::server::create_router(register_reset!(registry, pinpoint_state));
+    let polly_app = polly::server::create_router(register_reset!(registry, polly_state));
+    let proton_app = proton::server::create_router(register_reset!(registry, proton_state));
+    let qbusiness_app = qbusiness::server::create_router(register_reset!(registry, qbusiness_state));
+    let quicksight_app = quicksight::server::create_router(register_reset!(registry, quicksight_state));
+    let ram_app = ram::server::create_router(register_reset!(registry, ram_state));
+    let rds_app = rds::server::create_router(register_reset!(registry, rds_state));
+    let redshift_app = redshift::server::create_router(register_reset!(registry, redshift_state));
+    let rekognition_app = rekognition::server::create_router(register_reset!(registry, rekognition_state));
+    // Holds no state of its own, so there is nothing to register for reset.
+    let resourcegroupstaggingapi_app = resourcegroupstaggingapi::server::create_router(resourcegroupstaggingapi_state);
+    let route53_app = route53::server::create_router(register_reset!(registry, route53_state));
+    let s3_app = s3::server::create_router(register_reset!(registry, s3_state));
+    let sagemaker_app = sagemaker::server::create_router(register_reset!(registry, sagemaker_state));
+    let secretsmanager_app = secretsmanager::server::create_router(register_reset!(registry, secretsmanager_state));
+    let securityhub_app = securityhub::server::create_router(register_reset!(registry, securityhub_state));
+    let securitylake_app = securitylake::server::create_router(register_reset!(registry, securitylake_state));
+    let servicecatalog_app = servicecatalog::server::create_router(register_reset!(registry, servicecatalog_state));
+    let ses_app = ses::server::create_router(register_reset!(registry, ses_state));
+    let shield_app = shield::server::create_router(register_reset!(registry, shield_state));
+    let sns_app = sns::server::create_router(register_reset!(registry, sns_state));
+    let sqs_app = sqs::server::create_router(register_reset!(registry, sqs_state));
+    let ssm_app = ssm::server::create_router(register_reset!(registry, ssm_state));
+    let stepfunctions_app = stepfunctions::server::create_router(register_reset!(registry, stepfunctions_state));
+    let storagegateway_app = storagegateway::server::create_router(register_reset!(registry, storagegateway_state));
+    let swf_app = swf::server::create_router(register_reset!(registry, swf_state));
+    let textract_app = textract::server::create_router(register_reset!(registry, textract_state));
+    let timestream_app = timestream::server::create_router(register_reset!(registry, timestream_state));
+    let transcribe_app = transcribe::server::create_router(register_reset!(registry, transcribe_state));
+    let transferfamily_app = transferfamily::server::create_router(register_reset!(registry, transferfamily_state));
+    let translate_app = translate::server::create_router(register_reset!(registry, translate_state));
+    let trustedadvisor_app = trustedadvisor::server::create_router(register_reset!(registry, trustedadvisor_state));
+    let verifiedpermissions_app = verifiedpermissions::server::create_router(register_reset!(registry, verifiedpermissions_state));
+    let vpclattice_app = vpclattice::server::create_router(register_reset!(registry, vpclattice_state));
+    let waf_app = waf::server::create_router(register_reset!(registry, waf_state));
+    let workdocs_app = workdocs::server::create_router(register_reset!(registry, workdocs_state));
+    let workmail_app = workmail::server::create_router(register_reset!(registry, workmail_state));
+    let workspaces_app = workspaces::server::create_router(register_reset!(registry, workspaces_state));
+    let xray_app = xray::server::create_router(register_reset!(registry, xray_state));
 
+    let listeners = admin::Listeners::default();
     macro_rules! spawn_service {
         ($app:expr, $port:expr, $name:expr) => {{
             let port = $port;
-            let app = $app;
-            tokio::spawn(async move {
-                let listener = match tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await {
-                    Ok(l) => l,
-                    Err(e) => {
-                        eprintln!("Failed to bind {} service on port {}: {}", $name, port, e);
-                        return;
+            let service = stringify!($port).trim_start_matches("args.").trim_end_matches("_port");
+            if !args.is_enabled(service) {
+                // Left out by --services: nothing listens, and /_health does not wait for it.
+                tokio::spawn(async {})
+            } else {
+                let faults = fault_configs.remove(service).unwrap_or_default();
+                let app = logging::layer(faults::layer($app, service, faults), $name, args.log_bodies);
+                let listeners = listeners.clone();
+                listeners.starting(service, $name, port);
+                tokio::spawn(async move {
+                    let listener = match tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await {
+                        Ok(l) => l,
+                        Err(e) => {
+                            listeners.set_status(service, admin::ListenerStatus::Failed);
+                            eprintln!("Failed to bind {} service on port {}: {}", $name, port, e);
+                            return;
+                        }
+                    };
+                    listeners.set_status(service, admin::ListenerStatus::Ok);
+                    println!("{} service listening on port {}", $name, port);
+                    if let Err(e) = axum::serve(listener, app).await {
+                        eprintln!("{} service on port {} exited with error: {}", $name, port, e);
                     }
-                };
-                println!("{} service listening on port {}", $name, port);
-                if let Err(e) = axum::serve(listener, app).await {
-                    eprintln!("{} service on port {} exited with error: {}", $name, port, e);
-                }
-            })
+                })
+            }
         }};
     }
 
     let acm_handle = spawn_service!(acm_app, args.acm_port, "ACM");
     let amplify_handle = spawn_service!(amplify_app, args.amplify_port, "Amplify");
     let apigateway_handle = spawn_service!(apigateway_app, args.apigateway_port, "API Gateway");
+    let apigatewayv2_handle = spawn_service!(apigatewayv2_app, args.apigatewayv2_port, "API Gateway V2");
     let appfabric_handle = spawn_service!(appfabric_app, args.appfabric_port, "AppFabric");
     let appflow_handle = spawn_service!(appflow_app, args.appflow_port, "AppFlow");
     let appmesh_handle = spawn_service!(appmesh_app, args.appmesh_port, "AppMesh");
@@ -1445,6 +1595,11 @@ async fn main() {
     let rds_handle = spawn_service!(rds_app, args.rds_port, "RDS");
     let redshift_handle = spawn_service!(redshift_app, args.redshift_port, "Redshift");
     let rekognition_handle = spawn_service!(rekognition_app, args.rekognition_port, "Rekognition");
+    let resourcegroupstaggingapi_handle = spawn_service!(
+        resourcegroupstaggingapi_app,
+        args.resourcegroupstaggingapi_port,
+        "ResourceGroupsTaggingAPI"
+    );
     let route53_handle = spawn_service!(route53_app, args.route53_port, "Route53");
     let s3_handle = spawn_service!(s3_app, args.s3_port, "S3");
     let sagemaker_handle = spawn_service!(sagemaker_app, args.sagemaker_port, "SageMaker");
@@ -1474,10 +1629,27 @@ async fn main() {
     let workspaces_handle = spawn_service!(workspaces_app, args.workspaces_port, "WorkSpaces");
     let xray_handle = spawn_service!(xray_app, args.xray_port, "XRay");
 
+    let admin_port = args.admin_port;
+    let admin_app = admin::create_router(registry, listeners);
+    let admin_handle = tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(format!("0.0.0.0:{}", admin_port)).await {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("Failed to bind admin endpoints on port {}: {}", admin_port, e);
+                return;
+            }
+        };
+        println!("Admin endpoints listening on port {}", admin_port);
+        if let Err(e) = axum::serve(listener, admin_app).await {
+            eprintln!("Admin endpoints on port {} exited with error: {}", admin_port, e);
+        }
+    });
+
     let mut handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
     handles.push(acm_handle);
     handles.push(amplify_handle);
     handles.push(apigateway_handle);
+    handles.push(apigatewayv2_handle);
     handles.push(appfabric_handle);
     handles.push(appflow_handle);
     handles.push(appmesh_handle);
@@ -1606,6 +1778,7 @@ async fn main() {
     handles.push(rds_handle);
     handles.push(redshift_handle);
     handles.push(rekognition_handle);
+    handles.push(resourcegroupstaggingapi_handle);
     handles.push(route53_handle);
     handles.push(s3_handle);
     handles.push(sagemaker_handle);
@@ -1634,6 +1807,7 @@ async fn main() {
     handles.push(workmail_handle);
     handles.push(workspaces_handle);
     handles.push(xray_handle);
+    handles.push(admin_handle);
 
     // Wait for Ctrl+C to shut down
     match tokio::signal::ctrl_c().await {
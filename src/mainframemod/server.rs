@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<MainframemodState>) -> Router {
     Router::new()
         .route("/applications", post(create_application_handler).get(list_applications_handler))
         .route("/applications/{name}", get(get_application_handler).delete(delete_application_handler))
+        .merge(crate::healthcheck::router("mainframemod"))
         .with_state(state)
 }
 
@@ -37,6 +37,13 @@ impl MainframemodState {
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
     #[allow(dead_code)]
     pub async fn create_application(&self, req: CreateApplicationRequest) -> Result<ApplicationDetail, MainframemodError> {
         let mut state = self.inner.lock().await;
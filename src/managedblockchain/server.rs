@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<ManagedblockchainState>) -> Router {
     Router::new()
         .route("/networks", post(create_network_handler).get(list_networks_handler))
         .route("/networks/{name}", get(get_network_handler).delete(delete_network_handler))
+        .merge(crate::healthcheck::router("managedblockchain"))
         .with_state(state)
 }
 
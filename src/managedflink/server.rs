@@ -55,6 +55,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<ManagedflinkState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("managedflink"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<ManagedprometheusState>) -> Router {
     Router::new()
         .route("/workspaces", post(create_workspace_handler).get(list_workspaces_handler))
         .route("/workspaces/{name}", get(get_workspace_handler).delete(delete_workspace_handler))
+        .merge(crate::healthcheck::router("managedprometheus"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<MediaconvertState>) -> Router {
     Router::new()
         .route("/jobs", post(create_job_handler).get(list_jobs_handler))
         .route("/jobs/{name}", get(get_job_handler).delete(delete_job_handler))
+        .merge(crate::healthcheck::router("mediaconvert"))
         .with_state(state)
 }
 
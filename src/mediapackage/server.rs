@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<MediapackageState>) -> Router {
     Router::new()
         .route("/channels", post(create_channel_handler).get(list_channels_handler))
         .route("/channels/{name}", get(get_channel_handler).delete(delete_channel_handler))
+        .merge(crate::healthcheck::router("mediapackage"))
         .with_state(state)
 }
 
@@ -55,6 +55,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<MediastoreState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("mediastore"))
         .with_state(state)
 }
 
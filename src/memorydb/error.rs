@@ -1,6 +1,8 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
+use crate::pagination::InvalidToken;
 
 #[derive(Debug, Clone)]
 pub enum MemoryDbError {
@@ -76,13 +78,29 @@ impl MemoryDbError {
     }
 }
 
+impl From<InvalidToken> for MemoryDbError {
+    fn from(_: InvalidToken) -> Self {
+        MemoryDbError::InvalidParameterValue("Invalid NextToken value.".to_string())
+    }
+}
+
+impl AwsError for MemoryDbError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        MemoryDbError::message(self)
+    }
+}
+
 impl IntoResponse for MemoryDbError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -1,11 +1,63 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
 
 use super::cluster::*;
 use super::error::MemoryDbError;
 use super::types::*;
+use crate::pagination;
+
+/// How long a new cluster reports `creating` before it becomes `available`.
+const CLUSTER_CREATE_DELAY: Duration = Duration::from_millis(500);
+
+/// Hash slots of a Redis cluster, divided evenly between its shards.
+const HASH_SLOTS: i32 = 16384;
+
+/// The shards of a new cluster, one primary node each, with the hash slots
+/// split between them in order.
+fn build_shards(cluster_name: &str, region: &str, num_shards: i32, status: &str) -> Vec<Shard> {
+    let region_short = region.replace('-', "");
+    let num_shards = num_shards.max(1);
+    (0..num_shards)
+        .map(|i| {
+            let shard_name = format!("{:04}", i + 1);
+            let node_name = format!("{cluster_name}-{shard_name}-001");
+            let node = Node {
+                name: node_name.clone(),
+                status: status.to_string(),
+                availability_zone: Some(format!("{region}a")),
+                create_time: Some(chrono::Utc::now().to_rfc3339()),
+                endpoint: Some(Endpoint {
+                    address: format!("{node_name}.xxxxx.0001.{region_short}.cache.amazonaws.com"),
+                    port: 6379,
+                }),
+            };
+            let first_slot = HASH_SLOTS * i / num_shards;
+            let last_slot = HASH_SLOTS * (i + 1) / num_shards - 1;
+            Shard {
+                name: shard_name,
+                status: status.to_string(),
+                slots: Some(format!("{first_slot}-{last_slot}")),
+                number_of_nodes: 1,
+                nodes: Some(vec![node]),
+            }
+        })
+        .collect()
+}
+
+/// Sets the status of a cluster, its shards and their nodes.
+fn set_cluster_status(cluster: &mut Cluster, status: &str) {
+    cluster.status = status.to_string();
+    for shard in cluster.shards.iter_mut().flatten() {
+        shard.status = status.to_string();
+        for node in shard.nodes.iter_mut().flatten() {
+            node.status = status.to_string();
+        }
+    }
+}
 
 struct MemoryDbStateInner {
     clusters: HashMap<String, Cluster>,
@@ -14,6 +66,8 @@ struct MemoryDbStateInner {
     acls: HashMap<String, Acl>,
     snapshots: HashMap<String, Snapshot>,
     tags: HashMap<String, Vec<Tag>>,
+    /// Timers that make `creating` clusters available, by cluster name.
+    creating: HashMap<String, AbortHandle>,
     account_id: String,
     region: String,
 }
@@ -32,12 +86,37 @@ impl MemoryDbState {
                 acls: HashMap::new(),
                 snapshots: HashMap::new(),
                 tags: HashMap::new(),
+                creating: HashMap::new(),
                 account_id,
                 region,
             })),
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        for timer in state.creating.values() {
+            timer.abort();
+        }
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// ARNs and tags of every resource that carries at least one tag.
+    pub async fn tagged_resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let state = self.inner.lock().await;
+        state
+            .tags
+            .iter()
+            .filter(|(_, tags)| !tags.is_empty())
+            .map(|(arn, tags)| {
+                let tags = tags.iter().map(|t| (t.key.clone(), t.value.clone().unwrap_or_default())).collect();
+                (arn.clone(), tags)
+            })
+            .collect()
+    }
+
     // --- Cluster operations ---
 
     pub async fn create_cluster(
@@ -72,30 +151,6 @@ impl MemoryDbState {
             port: 6379,
         };
 
-        let node_endpoint = Endpoint {
-            address: format!(
-                "{}-0001-001.xxxxx.0001.{}.cache.amazonaws.com",
-                req.cluster_name, region_short
-            ),
-            port: 6379,
-        };
-
-        let node = Node {
-            name: format!("{}-0001-001", req.cluster_name),
-            status: "available".to_string(),
-            availability_zone: Some(format!("{}a", state.region)),
-            create_time: Some(chrono::Utc::now().to_rfc3339()),
-            endpoint: Some(node_endpoint),
-        };
-
-        let shard = Shard {
-            name: "0001".to_string(),
-            status: "available".to_string(),
-            slots: Some("0-16383".to_string()),
-            number_of_nodes: 1,
-            nodes: Some(vec![node]),
-        };
-
         let security_groups = req.security_group_ids.map(|ids| {
             ids.into_iter()
                 .map(|id| SecurityGroupMembership {
@@ -109,10 +164,12 @@ impl MemoryDbState {
             .parameter_group_name
             .unwrap_or_else(|| "default.memorydb-redis7".to_string());
 
+        let shards = build_shards(&req.cluster_name, &state.region, num_shards, "creating");
+
         let cluster = Cluster {
             name: req.cluster_name.clone(),
             arn: arn.clone(),
-            status: "available".to_string(),
+            status: "creating".to_string(),
             description: req.description,
             node_type: req.node_type,
             engine,
@@ -127,7 +184,7 @@ impl MemoryDbState {
             parameter_group_name,
             parameter_group_status: "in-sync".to_string(),
             security_groups,
-            shards: Some(vec![shard]),
+            shards: Some(shards),
             cluster_endpoint: Some(cluster_endpoint),
             auto_minor_version_upgrade: req.auto_minor_version_upgrade.unwrap_or(true),
             snapshot_retention_limit: req.snapshot_retention_limit.unwrap_or(0),
@@ -140,6 +197,19 @@ impl MemoryDbState {
 
         state.clusters.insert(req.cluster_name.clone(), cluster.clone());
 
+        // Provisioning is instant, but pollers expect to wait on `creating`.
+        let inner = self.inner.clone();
+        let name = req.cluster_name.clone();
+        let timer = tokio::spawn(async move {
+            tokio::time::sleep(CLUSTER_CREATE_DELAY).await;
+            let mut state = inner.lock().await;
+            state.creating.remove(&name);
+            if let Some(cluster) = state.clusters.get_mut(&name).filter(|c| c.status == "creating") {
+                set_cluster_status(cluster, "available");
+            }
+        });
+        state.creating.insert(req.cluster_name, timer.abort_handle());
+
         Ok(CreateClusterResponse { cluster })
     }
 
@@ -161,6 +231,9 @@ impl MemoryDbState {
 
         // Remove tags for this cluster
         state.tags.remove(&cluster.arn);
+        if let Some(timer) = state.creating.remove(&req.cluster_name) {
+            timer.abort();
+        }
 
         let mut deleted_cluster = cluster;
         deleted_cluster.status = "deleting".to_string();
@@ -193,19 +266,18 @@ impl MemoryDbState {
         };
 
         let max_results = req.max_results.unwrap_or(100) as usize;
-        let start = req
-            .next_token
-            .as_ref()
-            .and_then(|t| t.parse::<usize>().ok())
-            .unwrap_or(0);
-
-        let end = (start + max_results).min(clusters.len());
-        let page = clusters[start..end].to_vec();
-        let next_token = if end < clusters.len() {
-            Some(end.to_string())
-        } else {
-            None
-        };
+        let (mut page, next_token) = pagination::paginate(
+            clusters,
+            |item| item.name.as_str(),
+            req.next_token.as_deref(),
+            "memorydb:DescribeClusters",
+            max_results,
+        )?;
+        if !req.show_shard_details.unwrap_or(false) {
+            for cluster in &mut page {
+                cluster.shards = None;
+            }
+        }
 
         Ok(DescribeClustersResponse {
             clusters: page,
@@ -376,19 +448,13 @@ impl MemoryDbState {
         };
 
         let max_results = req.max_results.unwrap_or(100) as usize;
-        let start = req
-            .next_token
-            .as_ref()
-            .and_then(|t| t.parse::<usize>().ok())
-            .unwrap_or(0);
-
-        let end = (start + max_results).min(groups.len());
-        let page = groups[start..end].to_vec();
-        let next_token = if end < groups.len() {
-            Some(end.to_string())
-        } else {
-            None
-        };
+        let (page, next_token) = pagination::paginate(
+            groups,
+            |item| item.name.as_str(),
+            req.next_token.as_deref(),
+            "memorydb:DescribeSubnetGroups",
+            max_results,
+        )?;
 
         Ok(DescribeSubnetGroupsResponse {
             subnet_groups: page,
@@ -497,19 +563,13 @@ impl MemoryDbState {
         };
 
         let max_results = req.max_results.unwrap_or(100) as usize;
-        let start = req
-            .next_token
-            .as_ref()
-            .and_then(|t| t.parse::<usize>().ok())
-            .unwrap_or(0);
-
-        let end = (start + max_results).min(users.len());
-        let page = users[start..end].to_vec();
-        let next_token = if end < users.len() {
-            Some(end.to_string())
-        } else {
-            None
-        };
+        let (page, next_token) = pagination::paginate(
+            users,
+            |item| item.name.as_str(),
+            req.next_token.as_deref(),
+            "memorydb:DescribeUsers",
+            max_results,
+        )?;
 
         Ok(DescribeUsersResponse {
             users: page,
@@ -650,19 +710,13 @@ impl MemoryDbState {
         };
 
         let max_results = req.max_results.unwrap_or(100) as usize;
-        let start = req
-            .next_token
-            .as_ref()
-            .and_then(|t| t.parse::<usize>().ok())
-            .unwrap_or(0);
-
-        let end = (start + max_results).min(acls.len());
-        let page = acls[start..end].to_vec();
-        let next_token = if end < acls.len() {
-            Some(end.to_string())
-        } else {
-            None
-        };
+        let (page, next_token) = pagination::paginate(
+            acls,
+            |item| item.name.as_str(),
+            req.next_token.as_deref(),
+            "memorydb:DescribeAcls",
+            max_results,
+        )?;
 
         Ok(DescribeAclsResponse {
             acls: page,
@@ -774,11 +828,18 @@ impl MemoryDbState {
             subnet_group_name: cluster.subnet_group_name.clone(),
             vpc_id: None,
             number_of_shards: cluster.number_of_shards,
-            shards: Some(vec![ShardDetail {
-                name: "0001".to_string(),
-                size: Some("0".to_string()),
-                snapshot_creation_time: chrono::Utc::now().to_rfc3339(),
-            }]),
+            shards: Some(
+                cluster
+                    .shards
+                    .iter()
+                    .flatten()
+                    .map(|shard| ShardDetail {
+                        name: shard.name.clone(),
+                        size: Some("0".to_string()),
+                        snapshot_creation_time: chrono::Utc::now().to_rfc3339(),
+                    })
+                    .collect(),
+            ),
         };
 
         let snapshot = Snapshot {
@@ -852,19 +913,13 @@ impl MemoryDbState {
         };
 
         let max_results = req.max_results.unwrap_or(100) as usize;
-        let start = req
-            .next_token
-            .as_ref()
-            .and_then(|t| t.parse::<usize>().ok())
-            .unwrap_or(0);
-
-        let end = (start + max_results).min(snapshots.len());
-        let page = snapshots[start..end].to_vec();
-        let next_token = if end < snapshots.len() {
-            Some(end.to_string())
-        } else {
-            None
-        };
+        let (page, next_token) = pagination::paginate(
+            snapshots,
+            |item| item.name.as_str(),
+            req.next_token.as_deref(),
+            "memorydb:DescribeSnapshots",
+            max_results,
+        )?;
 
         Ok(DescribeSnapshotsResponse {
             snapshots: page,
@@ -1015,6 +1070,56 @@ mod tests {
         assert_eq!(result.clusters.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_cluster_becomes_available_and_shard_details_are_optional() {
+        let state = make_state();
+        let created = state.create_cluster(CreateClusterRequest {
+            cluster_name: "poll".to_string(),
+            node_type: "db.r6g.large".to_string(),
+            acl_name: "open-access".to_string(),
+            num_shards: Some(2),
+            ..Default::default()
+        }).await.unwrap().cluster;
+        assert_eq!(created.status, "creating");
+
+        let describe = |show_shard_details| state.describe_clusters(DescribeClustersRequest {
+            cluster_name: Some("poll".to_string()),
+            show_shard_details,
+            ..Default::default()
+        });
+        let brief = describe(None).await.unwrap().clusters.remove(0);
+        assert_eq!(brief.status, "creating");
+        assert!(brief.shards.is_none());
+
+        tokio::time::sleep(CLUSTER_CREATE_DELAY + Duration::from_millis(100)).await;
+        let detailed = describe(Some(true)).await.unwrap().clusters.remove(0);
+        assert_eq!(detailed.status, "available");
+        let shards = detailed.shards.unwrap();
+        assert_eq!(shards.len(), 2);
+        assert_eq!(shards[0].slots.as_deref(), Some("0-8191"));
+        assert_eq!(shards[1].slots.as_deref(), Some("8192-16383"));
+        assert!(shards.iter().flat_map(|s| s.nodes.iter().flatten()).all(|n| n.status == "available"));
+        assert!(describe(Some(false)).await.unwrap().clusters[0].shards.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reset_while_creating_stops_the_creation_timer() {
+        let state = make_state();
+        state.create_cluster(CreateClusterRequest {
+            cluster_name: "resetme".to_string(),
+            node_type: "db.r6g.large".to_string(),
+            acl_name: "open-access".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        let timer = state.inner.lock().await.creating["resetme"].clone();
+
+        state.reset().await;
+        tokio::task::yield_now().await;
+        assert!(timer.is_finished());
+        assert!(state.inner.lock().await.creating.is_empty());
+        assert!(state.describe_clusters(DescribeClustersRequest::default()).await.unwrap().clusters.is_empty());
+    }
+
     #[tokio::test]
     async fn test_delete_cluster() {
         let state = make_state();
@@ -75,6 +75,9 @@ pub struct DescribeClustersRequest {
     pub max_results: Option<i32>,
     #[serde(default)]
     pub next_token: Option<String>,
+    /// Include each cluster's shards and nodes; omitted unless true.
+    #[serde(default)]
+    pub show_shard_details: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<MQState>) -> Router {
     Router::new()
         .route("/brokers", post(create_broker_handler).get(list_brokers_handler))
         .route("/brokers/{name}", get(get_broker_handler).delete(delete_broker_handler))
+        .merge(crate::healthcheck::router("mq"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<MwaaState>) -> Router {
     Router::new()
         .route("/environments", post(create_environment_handler).get(list_environments_handler))
         .route("/environments/{name}", get(get_environment_handler).delete(delete_environment_handler))
+        .merge(crate::healthcheck::router("mwaa"))
         .with_state(state)
 }
 
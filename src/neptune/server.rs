@@ -78,6 +78,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<NeptuneState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("neptune"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<OpensearchState>) -> Router {
     Router::new()
         .route("/domains", post(create_domain_handler).get(list_domains_handler))
         .route("/domains/{name}", get(get_domain_handler).delete(delete_domain_handler))
+        .merge(crate::healthcheck::router("opensearch"))
         .with_state(state)
 }
 
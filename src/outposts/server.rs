@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<OutpostsState>) -> Router {
     Router::new()
         .route("/outposts", post(create_outpost_handler).get(list_outposts_handler))
         .route("/outposts/{name}", get(get_outpost_handler).delete(delete_outpost_handler))
+        .merge(crate::healthcheck::router("outposts"))
         .with_state(state)
 }
 
@@ -0,0 +1,109 @@
+//! Opaque `NextToken`s shared by the list APIs.
+//!
+//! A token is URL-safe base64 over a small JSON document: the key of the
+//! last item returned and a fingerprint of the listing it came from. Tokens
+//! that do not decode, or that belong to a different listing, are rejected
+//! with [`InvalidToken`], which each service maps to its own "invalid next
+//! token" error.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// A page of items and the token for the next page, if there is one.
+pub type Page<T> = (Vec<T>, Option<String>);
+
+/// A `NextToken` that is malformed or was issued for another listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidToken;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PageToken {
+    /// Key of the last item returned; the next page starts after it.
+    #[serde(rename = "k")]
+    after: String,
+    #[serde(rename = "f")]
+    fingerprint: u64,
+}
+
+impl PageToken {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Parses `token`, rejecting it unless it was issued for `listing`.
+    fn decode(token: &str, listing: &str) -> Result<Self, InvalidToken> {
+        let bytes = URL_SAFE_NO_PAD.decode(token).map_err(|_| InvalidToken)?;
+        let token: PageToken = serde_json::from_slice(&bytes).map_err(|_| InvalidToken)?;
+        if token.fingerprint != fingerprint(listing) {
+            return Err(InvalidToken);
+        }
+        Ok(token)
+    }
+}
+
+/// FNV-1a of the listing name, stable across runs so tokens stay valid for
+/// as long as the listing does.
+fn fingerprint(listing: &str) -> u64 {
+    listing.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Pages through `items`, which must be sorted by `key`, resuming after the
+/// last key returned so that inserts and deletes between calls neither skip
+/// nor repeat items. `listing` names the collection and any filter applied
+/// to it, e.g. `sqs:ListQueues:<prefix>`.
+pub fn paginate<T>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> &str,
+    token: Option<&str>,
+    listing: &str,
+    max: usize,
+) -> Result<Page<T>, InvalidToken> {
+    let after = match token.filter(|t| !t.is_empty()) {
+        None => None,
+        Some(token) => Some(PageToken::decode(token, listing)?.after),
+    };
+    let mut remaining = items
+        .into_iter()
+        .skip_while(|item| after.as_deref().is_some_and(|last| key(item) <= last))
+        .peekable();
+    let page: Vec<T> = remaining.by_ref().take(max).collect();
+    let next = match (page.last(), remaining.peek()) {
+        (Some(last), Some(_)) => {
+            Some(PageToken { after: key(last).to_string(), fingerprint: fingerprint(listing) }.encode())
+        }
+        _ => None,
+    };
+    Ok((page, next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_resumes_after_last_key() {
+        let items = vec!["a", "b", "c"];
+        let (page, next) = paginate(items, |s| *s, None, "l", 2).unwrap();
+        assert_eq!(page, vec!["a", "b"]);
+        // "b" was deleted and "bb" added between calls.
+        let (page, next) = paginate(vec!["a", "bb", "c"], |s| *s, next.as_deref(), "l", 2).unwrap();
+        assert_eq!(page, vec!["bb", "c"]);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn test_tampered_or_foreign_tokens_are_rejected() {
+        let items = || vec!["a", "b", "c"];
+        let (_, next) = paginate(items(), |s| *s, None, "l", 1).unwrap();
+        let next = next.unwrap();
+        assert!(paginate(items(), |s| *s, Some(&next), "l", 1).is_ok());
+        assert_eq!(paginate(items(), |s| *s, Some("not-a-token"), "l", 1), Err(InvalidToken));
+        assert_eq!(paginate(items(), |s| *s, Some(&next[1..]), "l", 1), Err(InvalidToken));
+        assert_eq!(paginate(items(), |s| *s, Some(&next), "other", 1), Err(InvalidToken));
+        let forged = URL_SAFE_NO_PAD.encode(br#"{"k":"a","f":1}"#);
+        assert_eq!(paginate(items(), |s| *s, Some(&forged), "l", 1), Err(InvalidToken));
+    }
+}
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<PollyState>) -> Router {
     Router::new()
         .route("/lexicons", post(create_lexicon_handler).get(list_lexicons_handler))
         .route("/lexicons/{name}", get(get_lexicon_handler).delete(delete_lexicon_handler))
+        .merge(crate::healthcheck::router("polly"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<RAMState>) -> Router {
     Router::new()
         .route("/resource-shares", post(create_resource_share_handler).get(list_resource_shares_handler))
         .route("/resource-shares/{name}", get(get_resource_share_handler).delete(delete_resource_share_handler))
+        .merge(crate::healthcheck::router("ram"))
         .with_state(state)
 }
 
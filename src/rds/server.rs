@@ -96,6 +96,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<RDSState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("rds"))
         .with_state(state)
 }
 
@@ -78,6 +78,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<RedshiftState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("redshift"))
         .with_state(state)
 }
 
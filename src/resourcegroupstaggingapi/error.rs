@@ -0,0 +1,85 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+use crate::aws_error::{error_response, AwsError, Protocol};
+
+#[derive(Debug, Clone)]
+pub enum TaggingError {
+    InvalidParameterException(String),
+    PaginationTokenExpiredException(String),
+    InvalidAction(String),
+}
+
+impl TaggingError {
+    fn error_code(&self) -> &str {
+        match self {
+            TaggingError::InvalidParameterException(_) => "InvalidParameterException",
+            TaggingError::PaginationTokenExpiredException(_) => "PaginationTokenExpiredException",
+            TaggingError::InvalidAction(_) => "InvalidAction",
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            TaggingError::InvalidParameterException(m)
+            | TaggingError::PaginationTokenExpiredException(m)
+            | TaggingError::InvalidAction(m) => m,
+        }
+    }
+}
+
+impl AwsError for TaggingError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        TaggingError::message(self)
+    }
+}
+
+impl IntoResponse for TaggingError {
+    fn into_response(self) -> Response {
+        error_response(&self, Protocol::Json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalidparameterexception_error_code() {
+        let err = TaggingError::InvalidParameterException("test".to_string());
+        assert_eq!(err.error_code(), "InvalidParameterException");
+    }
+    #[test]
+    fn test_paginationtokenexpiredexception_error_code() {
+        let err = TaggingError::PaginationTokenExpiredException("test".to_string());
+        assert_eq!(err.error_code(), "PaginationTokenExpiredException");
+    }
+    #[test]
+    fn test_invalidaction_error_code() {
+        let err = TaggingError::InvalidAction("test".to_string());
+        assert_eq!(err.error_code(), "InvalidAction");
+    }
+    #[test]
+    fn test_message() {
+        let err = TaggingError::InvalidParameterException("hello world".to_string());
+        assert_eq!(err.message(), "hello world");
+    }
+    #[test]
+    fn test_into_response() {
+        let err = TaggingError::InvalidParameterException("test error".to_string());
+        let resp = err.into_response();
+        assert!(resp.status().is_client_error());
+    }
+}
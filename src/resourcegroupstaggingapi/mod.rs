@@ -0,0 +1,4 @@
+pub mod error;
+pub mod server;
+pub mod state;
+pub mod types;
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use axum::routing::post;
+use axum::{Json, Router};
+
+use super::error::TaggingError;
+use super::state::TaggingState;
+use super::types::*;
+
+macro_rules! dispatch {
+    ($state:expr, $body:expr, $req_type:ty, $method:ident) => {{
+        let req: $req_type = serde_json::from_slice(&$body)
+            .map_err(|e| TaggingError::InvalidParameterException(e.to_string()))?;
+        let resp = $state.$method(req).await?;
+        Ok(Json(resp).into_response())
+    }};
+}
+
+async fn handle_request(
+    State(state): State<Arc<TaggingState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<axum::response::Response, TaggingError> {
+    let target = headers
+        .get("x-amz-target")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| TaggingError::InvalidAction("Missing X-Amz-Target header".into()))?;
+
+    let action = target
+        .strip_prefix("ResourceGroupsTaggingAPI_20170126.")
+        .ok_or_else(|| TaggingError::InvalidAction(format!("Invalid target: {target}")))?;
+
+    match action {
+        "GetResources" => dispatch!(state, body, GetResourcesRequest, get_resources),
+        "GetTagKeys" => dispatch!(state, body, GetTagKeysRequest, get_tag_keys),
+        "GetTagValues" => dispatch!(state, body, GetTagValuesRequest, get_tag_values),
+        _ => Err(TaggingError::InvalidAction(format!("Unknown action: {action}"))),
+    }
+}
+
+pub fn create_router(state: Arc<TaggingState>) -> Router {
+    Router::new()
+        .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("resourcegroupstaggingapi"))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_missing_target_header() {
+        let app = create_router(Arc::new(TaggingState::new()));
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/x-amz-json-1.1")
+            .body(Body::from("{}"))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_get_resources_action() {
+        let app = create_router(Arc::new(TaggingState::new()));
+        let req = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/x-amz-json-1.1")
+            .header("x-amz-target", "ResourceGroupsTaggingAPI_20170126.GetResources")
+            .body(Body::from("{}"))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use crate::bus::ServiceBus;
+
+use super::error::TaggingError;
+use super::types::*;
+
+/// Default and maximum `ResourcesPerPage`.
+const DEFAULT_RESOURCES_PER_PAGE: usize = 50;
+const MAX_RESOURCES_PER_PAGE: usize = 100;
+
+/// Page size for `GetTagKeys` and `GetTagValues`.
+const TAGS_PER_PAGE: usize = 1000;
+
+/// Answers tag queries across services. Tags stay with the service that
+/// owns each resource; every call collects them through the bus, so this
+/// service holds no state of its own.
+#[derive(Default)]
+pub struct TaggingState {
+    bus: Arc<ServiceBus>,
+}
+
+/// The service and resource type of an ARN, as used by
+/// `ResourceTypeFilters` (`sqs`, `dynamodb:table`, ...).
+fn resource_type(arn: &str) -> Option<(&str, &str)> {
+    let parts: Vec<&str> = arn.splitn(6, ':').collect();
+    if parts.len() != 6 {
+        return None;
+    }
+    let service = parts[2];
+    let resource = parts[5];
+    let kind = match resource.find(['/', ':']) {
+        Some(end) => &resource[..end],
+        None => match service {
+            "sqs" => "queue",
+            "sns" => "topic",
+            "s3" => "bucket",
+            _ => "",
+        },
+    };
+    Some((service, kind))
+}
+
+fn matches_type_filters(arn: &str, filters: &[String]) -> bool {
+    let Some((service, kind)) = resource_type(arn) else {
+        return false;
+    };
+    filters.iter().any(|filter| match filter.split_once(':') {
+        Some((s, k)) => s == service && k == kind,
+        None => filter == service,
+    })
+}
+
+/// Every filter must match: the key must be present and, when values are
+/// given, carry one of them.
+fn matches_tag_filters(tags: &HashMap<String, String>, filters: &[TagFilter]) -> bool {
+    filters.iter().all(|filter| {
+        tags.get(&filter.key)
+            .is_some_and(|value| filter.values.is_empty() || filter.values.contains(value))
+    })
+}
+
+/// Reads a pagination token produced by [`paginate`]: the offset of the
+/// next item.
+fn start_offset(token: Option<&str>) -> Result<usize, TaggingError> {
+    match token.filter(|t| !t.is_empty()) {
+        None => Ok(0),
+        Some(token) => token.parse().map_err(|_| {
+            TaggingError::PaginationTokenExpiredException(format!("Invalid pagination token: {token}"))
+        }),
+    }
+}
+
+/// Returns one page of `items` and the token for the next, which is empty
+/// on the last page.
+fn paginate<T>(items: Vec<T>, start: usize, per_page: usize) -> (Vec<T>, String) {
+    let total = items.len();
+    let page: Vec<T> = items.into_iter().skip(start).take(per_page).collect();
+    let next = start + page.len();
+    let token = if next < total { next.to_string() } else { String::new() };
+    (page, token)
+}
+
+impl TaggingState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queries the services attached to `bus` instead of a detached one.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Tagged resources across services, ordered by ARN so pages are stable.
+    async fn resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let mut resources = self.bus.tagged_resources().await;
+        resources.sort_by(|a, b| a.0.cmp(&b.0));
+        resources
+    }
+
+    pub async fn get_resources(&self, req: GetResourcesRequest) -> Result<GetResourcesResponse, TaggingError> {
+        let per_page = req.resources_per_page.unwrap_or(DEFAULT_RESOURCES_PER_PAGE);
+        if !(1..=MAX_RESOURCES_PER_PAGE).contains(&per_page) {
+            return Err(TaggingError::InvalidParameterException(format!(
+                "ResourcesPerPage must be between 1 and {MAX_RESOURCES_PER_PAGE}"
+            )));
+        }
+        if req.resource_arn_list.is_some() && (req.tag_filters.is_some() || req.resource_type_filters.is_some()) {
+            return Err(TaggingError::InvalidParameterException(
+                "ResourceARNList cannot be combined with TagFilters or ResourceTypeFilters".into(),
+            ));
+        }
+        let start = start_offset(req.pagination_token.as_deref())?;
+        let tag_filters = req.tag_filters.unwrap_or_default();
+        let type_filters = req.resource_type_filters.unwrap_or_default();
+        let mappings: Vec<ResourceTagMapping> = self
+            .resources()
+            .await
+            .into_iter()
+            .filter(|(arn, tags)| {
+                req.resource_arn_list.as_ref().is_none_or(|arns| arns.contains(arn))
+                    && (type_filters.is_empty() || matches_type_filters(arn, &type_filters))
+                    && matches_tag_filters(tags, &tag_filters)
+            })
+            .map(|(resource_arn, tags)| {
+                let mut tags: Vec<Tag> = tags.into_iter().map(|(key, value)| Tag { key, value }).collect();
+                tags.sort_by(|a, b| a.key.cmp(&b.key));
+                ResourceTagMapping { resource_arn, tags }
+            })
+            .collect();
+        let (resource_tag_mapping_list, pagination_token) = paginate(mappings, start, per_page);
+        Ok(GetResourcesResponse { pagination_token, resource_tag_mapping_list })
+    }
+
+    pub async fn get_tag_keys(&self, req: GetTagKeysRequest) -> Result<GetTagKeysResponse, TaggingError> {
+        let start = start_offset(req.pagination_token.as_deref())?;
+        let keys: BTreeSet<String> = self.resources().await.into_iter().flat_map(|(_, tags)| tags.into_keys()).collect();
+        let (tag_keys, pagination_token) = paginate(keys.into_iter().collect(), start, TAGS_PER_PAGE);
+        Ok(GetTagKeysResponse { pagination_token, tag_keys })
+    }
+
+    pub async fn get_tag_values(&self, req: GetTagValuesRequest) -> Result<GetTagValuesResponse, TaggingError> {
+        if req.key.is_empty() {
+            return Err(TaggingError::InvalidParameterException("Key is required".into()));
+        }
+        let start = start_offset(req.pagination_token.as_deref())?;
+        let values: BTreeSet<String> = self
+            .resources()
+            .await
+            .into_iter()
+            .filter_map(|(_, mut tags)| tags.remove(&req.key))
+            .collect();
+        let (tag_values, pagination_token) = paginate(values.into_iter().collect(), start, TAGS_PER_PAGE);
+        Ok(GetTagValuesResponse { pagination_token, tag_values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamodb::state::DynamoDbState;
+    use crate::sqs::state::SqsState;
+
+    async fn setup() -> TaggingState {
+        let sqs = Arc::new(SqsState::new("123456789012".into(), "us-east-1".into(), 9324));
+        sqs.create_queue(crate::sqs::types::CreateQueueRequest {
+            queue_name: "orders".into(),
+            attributes: None,
+            tags: Some(HashMap::from([("team".to_string(), "payments".to_string())])),
+        })
+        .await
+        .unwrap();
+        sqs.create_queue(crate::sqs::types::CreateQueueRequest {
+            queue_name: "untagged".into(),
+            attributes: None,
+            tags: None,
+        })
+        .await
+        .unwrap();
+
+        let dynamodb = Arc::new(DynamoDbState::new("123456789012".into(), "us-east-1".into()));
+        let table: crate::dynamodb::types::CreateTableRequest = serde_json::from_value(serde_json::json!({
+            "TableName": "ledger",
+            "KeySchema": [{"AttributeName": "id", "KeyType": "HASH"}],
+            "AttributeDefinitions": [{"AttributeName": "id", "AttributeType": "S"}],
+            "Tags": [{"Key": "team", "Value": "payments"}, {"Key": "env", "Value": "prod"}]
+        }))
+        .unwrap();
+        dynamodb.create_table(table).await.unwrap();
+
+        let bus = Arc::new(ServiceBus::new());
+        bus.attach_sqs(sqs);
+        bus.attach_dynamodb(dynamodb);
+        TaggingState::new().with_bus(bus)
+    }
+
+    #[tokio::test]
+    async fn test_get_resources_by_tag_across_services() {
+        let state = setup().await;
+        let resp = state
+            .get_resources(GetResourcesRequest {
+                tag_filters: Some(vec![TagFilter { key: "team".into(), values: vec!["payments".into()] }]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let arns: Vec<&str> = resp.resource_tag_mapping_list.iter().map(|m| m.resource_arn.as_str()).collect();
+        assert_eq!(arns, vec![
+            "arn:aws:dynamodb:us-east-1:123456789012:table/ledger",
+            "arn:aws:sqs:us-east-1:123456789012:orders",
+        ]);
+        assert_eq!(resp.pagination_token, "");
+
+        let resp = state
+            .get_resources(GetResourcesRequest {
+                tag_filters: Some(vec![TagFilter { key: "team".into(), values: vec![] }]),
+                resource_type_filters: Some(vec!["dynamodb:table".into()]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(resp.resource_tag_mapping_list.len(), 1);
+        assert_eq!(resp.resource_tag_mapping_list[0].tags, vec![
+            Tag { key: "env".into(), value: "prod".into() },
+            Tag { key: "team".into(), value: "payments".into() },
+        ]);
+
+        let resp = state
+            .get_resources(GetResourcesRequest {
+                tag_filters: Some(vec![TagFilter { key: "team".into(), values: vec!["search".into()] }]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(resp.resource_tag_mapping_list.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_resources_pagination() {
+        let state = setup().await;
+        let first = state
+            .get_resources(GetResourcesRequest { resources_per_page: Some(1), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(first.resource_tag_mapping_list.len(), 1);
+        assert_eq!(first.pagination_token, "1");
+        let second = state
+            .get_resources(GetResourcesRequest {
+                resources_per_page: Some(1),
+                pagination_token: Some(first.pagination_token),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(second.resource_tag_mapping_list[0].resource_arn, "arn:aws:sqs:us-east-1:123456789012:orders");
+        assert_eq!(second.pagination_token, "");
+    }
+
+    #[tokio::test]
+    async fn test_get_tag_keys_and_values() {
+        let state = setup().await;
+        let keys = state.get_tag_keys(GetTagKeysRequest::default()).await.unwrap();
+        assert_eq!(keys.tag_keys, vec!["env", "team"]);
+        let values = state
+            .get_tag_values(GetTagValuesRequest { key: "team".into(), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(values.tag_values, vec!["payments"]);
+        assert!(state.get_tag_values(GetTagValuesRequest::default()).await.is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TagFilter {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "Values", default)]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetResourcesRequest {
+    #[serde(rename = "PaginationToken")]
+    pub pagination_token: Option<String>,
+    #[serde(rename = "TagFilters")]
+    pub tag_filters: Option<Vec<TagFilter>>,
+    #[serde(rename = "ResourcesPerPage")]
+    pub resources_per_page: Option<usize>,
+    #[serde(rename = "ResourceTypeFilters")]
+    pub resource_type_filters: Option<Vec<String>>,
+    #[serde(rename = "ResourceARNList")]
+    pub resource_arn_list: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Tag {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ResourceTagMapping {
+    #[serde(rename = "ResourceARN")]
+    pub resource_arn: String,
+    #[serde(rename = "Tags")]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetResourcesResponse {
+    #[serde(rename = "PaginationToken")]
+    pub pagination_token: String,
+    #[serde(rename = "ResourceTagMappingList")]
+    pub resource_tag_mapping_list: Vec<ResourceTagMapping>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetTagKeysRequest {
+    #[serde(rename = "PaginationToken")]
+    pub pagination_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetTagKeysResponse {
+    #[serde(rename = "PaginationToken")]
+    pub pagination_token: String,
+    #[serde(rename = "TagKeys")]
+    pub tag_keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetTagValuesRequest {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "PaginationToken")]
+    pub pagination_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetTagValuesResponse {
+    #[serde(rename = "PaginationToken")]
+    pub pagination_token: String,
+    #[serde(rename = "TagValues")]
+    pub tag_values: Vec<String>,
+}
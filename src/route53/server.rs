@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<Route53State>) -> Router {
     Router::new()
         .route("/hosted-zones", post(create_hosted_zone_handler).get(list_hosted_zones_handler))
         .route("/hosted-zones/{name}", get(get_hosted_zone_handler).delete(delete_hosted_zone_handler))
+        .merge(crate::healthcheck::router("route53"))
         .with_state(state)
 }
 
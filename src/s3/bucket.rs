@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
+use axum::body::Bytes;
 use chrono::{DateTime, Utc};
 use md5::{Digest, Md5};
 
+use super::types::{CorsConfiguration, CorsRule};
+
 fn now_rfc3339() -> String {
     let dt: DateTime<Utc> = Utc::now();
     dt.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
@@ -18,20 +21,42 @@ pub fn etag_quoted(data: &[u8]) -> String {
     format!("\"{}\"", md5_hex(data))
 }
 
+/// Storage classes accepted in `x-amz-storage-class`.
+pub const STORAGE_CLASSES: [&str; 10] = [
+    "STANDARD",
+    "REDUCED_REDUNDANCY",
+    "STANDARD_IA",
+    "ONEZONE_IA",
+    "INTELLIGENT_TIERING",
+    "GLACIER",
+    "DEEP_ARCHIVE",
+    "GLACIER_IR",
+    "OUTPOSTS",
+    "EXPRESS_ONEZONE",
+];
+
+/// Storage classes whose objects must be restored before they can be read.
+const ARCHIVE_STORAGE_CLASSES: [&str; 2] = ["GLACIER", "DEEP_ARCHIVE"];
+
 #[derive(Debug, Clone)]
 pub struct Object {
     pub key: String,
-    pub data: Vec<u8>,
+    /// Shared, so reads and copies hand out the stored body instead of
+    /// cloning it.
+    pub data: Bytes,
     pub content_type: String,
     pub etag: String,
     pub last_modified: String,
     pub metadata: HashMap<String, String>,
     pub tags: HashMap<String, String>,
     pub storage_class: String,
+    /// When the temporary copy made by `RestoreObject` expires.
+    pub restore_expiry: Option<DateTime<Utc>>,
 }
 
 impl Object {
-    pub fn new(key: String, data: Vec<u8>, content_type: Option<String>, metadata: HashMap<String, String>) -> Self {
+    pub fn new(key: String, data: impl Into<Bytes>, content_type: Option<String>, metadata: HashMap<String, String>) -> Self {
+        let data = data.into();
         let etag = etag_quoted(&data);
         Object {
             key,
@@ -42,18 +67,74 @@ impl Object {
             metadata,
             tags: HashMap::new(),
             storage_class: "STANDARD".into(),
+            restore_expiry: None,
         }
     }
 
     pub fn size(&self) -> usize {
         self.data.len()
     }
+
+    pub fn is_archived(&self) -> bool {
+        ARCHIVE_STORAGE_CLASSES.contains(&self.storage_class.as_str())
+    }
+
+    /// The expiry of an unexpired restored copy.
+    pub fn restored_until(&self) -> Option<DateTime<Utc>> {
+        self.restore_expiry.filter(|expiry| *expiry > Utc::now())
+    }
+
+    /// Whether the body can be read: the object is not archived, or has a
+    /// restored copy that has not expired.
+    pub fn is_readable(&self) -> bool {
+        !self.is_archived() || self.restored_until().is_some()
+    }
+}
+
+/// Object settings taken from `PutObject` headers.
+#[derive(Debug, Clone, Default)]
+pub struct PutObjectOptions {
+    /// From `x-amz-tagging`.
+    pub tags: HashMap<String, String>,
+    /// From `x-amz-storage-class`; STANDARD when unset.
+    pub storage_class: Option<String>,
+}
+
+/// Object settings taken from `CopyObject` headers.
+#[derive(Debug, Clone, Default)]
+pub struct CopyObjectOptions {
+    /// From `x-amz-metadata-directive`. `REPLACE` takes the content type
+    /// and metadata below; anything else copies them from the source.
+    pub metadata_directive: Option<String>,
+    pub content_type: Option<String>,
+    pub metadata: HashMap<String, String>,
+    /// From `x-amz-storage-class`; STANDARD when unset.
+    pub storage_class: Option<String>,
+}
+
+/// A `Range` request header, before it is resolved against an object's size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ByteRange {
+    /// `bytes=start-` or `bytes=start-end`.
+    FromTo(u64, Option<u64>),
+    /// `bytes=-n`: the last `n` bytes.
+    Suffix(u64),
+}
+
+/// A `GetObject` read: the object and the requested part of its body, which
+/// is a slice of the stored body rather than a copy.
+#[derive(Debug, Clone)]
+pub struct ObjectRead {
+    pub object: Object,
+    pub body: Bytes,
+    /// `(start, end, total)` when a range was requested.
+    pub range: Option<(u64, u64, u64)>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Part {
     pub part_number: i32,
-    pub data: Vec<u8>,
+    pub data: Bytes,
     pub etag: String,
     pub size: usize,
     pub last_modified: String,
@@ -85,6 +166,7 @@ pub struct Bucket {
     pub tags: HashMap<String, String>,
     pub versioning: VersioningStatus,
     pub multipart_uploads: HashMap<String, MultipartUpload>,
+    pub cors: Option<CorsConfiguration>,
 }
 
 impl Bucket {
@@ -97,6 +179,32 @@ impl Bucket {
             tags: HashMap::new(),
             versioning: VersioningStatus::Disabled,
             multipart_uploads: HashMap::new(),
+            cors: None,
+        }
+    }
+
+    /// The first CORS rule that allows `method` from `origin` with the
+    /// request `headers`, which are matched case-insensitively.
+    pub fn cors_rule(&self, origin: &str, method: &str, headers: &[String]) -> Option<&CorsRule> {
+        self.cors.as_ref()?.rules.iter().find(|rule| {
+            rule.allowed_origins.iter().any(|o| wildcard_match(o, origin))
+                && rule.allowed_methods.iter().any(|m| m == method)
+                && headers.iter().all(|h| {
+                    rule.allowed_headers
+                        .iter()
+                        .any(|a| wildcard_match(&a.to_ascii_lowercase(), &h.to_ascii_lowercase()))
+                })
+        })
+    }
+}
+
+/// Whether `value` matches `pattern`, which may hold one `*` wildcard, as
+/// in `AllowedOrigin` `https://*.example.com`.
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == value,
+        Some((prefix, suffix)) => {
+            value.len() >= prefix.len() + suffix.len() && value.starts_with(prefix) && value.ends_with(suffix)
         }
     }
 }
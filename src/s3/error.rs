@@ -1,6 +1,8 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 
+use crate::aws_error::{error_response, AwsError, Protocol};
+
 #[derive(Debug, Clone)]
 pub enum S3Error {
     NoSuchBucket(String),
@@ -14,8 +16,13 @@ pub enum S3Error {
     InvalidArgument(String),
     InvalidRequest(String),
     NoSuchTagSet(String),
+    NoSuchCORSConfiguration(String),
+    AccessForbidden(String),
+    InvalidTag(String),
     MalformedXML(String),
     InvalidRange(String),
+    InvalidObjectState(String),
+    InvalidStorageClass(String),
     InternalError(String),
 }
 
@@ -33,17 +40,24 @@ impl S3Error {
             S3Error::InvalidArgument(_) => "InvalidArgument",
             S3Error::InvalidRequest(_) => "InvalidRequest",
             S3Error::NoSuchTagSet(_) => "NoSuchTagSet",
+            S3Error::NoSuchCORSConfiguration(_) => "NoSuchCORSConfiguration",
+            S3Error::AccessForbidden(_) => "AccessForbidden",
+            S3Error::InvalidTag(_) => "InvalidTag",
             S3Error::MalformedXML(_) => "MalformedXML",
             S3Error::InvalidRange(_) => "InvalidRange",
+            S3Error::InvalidObjectState(_) => "InvalidObjectState",
+            S3Error::InvalidStorageClass(_) => "InvalidStorageClass",
             S3Error::InternalError(_) => "InternalError",
         }
     }
 
     fn status_code(&self) -> StatusCode {
         match self {
-            S3Error::NoSuchBucket(_) | S3Error::NoSuchKey(_) | S3Error::NoSuchUpload(_) | S3Error::NoSuchTagSet(_) => {
-                StatusCode::NOT_FOUND
-            }
+            S3Error::NoSuchBucket(_)
+            | S3Error::NoSuchKey(_)
+            | S3Error::NoSuchUpload(_)
+            | S3Error::NoSuchTagSet(_)
+            | S3Error::NoSuchCORSConfiguration(_) => StatusCode::NOT_FOUND,
             S3Error::BucketAlreadyOwnedByYou(_) => {
                 StatusCode::CONFLICT
             }
@@ -53,7 +67,10 @@ impl S3Error {
             | S3Error::InvalidPartOrder(_)
             | S3Error::InvalidArgument(_)
             | S3Error::InvalidRequest(_)
+            | S3Error::InvalidTag(_)
+            | S3Error::InvalidStorageClass(_)
             | S3Error::MalformedXML(_) => StatusCode::BAD_REQUEST,
+            S3Error::InvalidObjectState(_) | S3Error::AccessForbidden(_) => StatusCode::FORBIDDEN,
             S3Error::InvalidRange(_) => StatusCode::RANGE_NOT_SATISFIABLE,
             S3Error::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
@@ -72,30 +89,35 @@ impl S3Error {
             | S3Error::InvalidArgument(m)
             | S3Error::InvalidRequest(m)
             | S3Error::NoSuchTagSet(m)
+            | S3Error::NoSuchCORSConfiguration(m)
+            | S3Error::AccessForbidden(m)
+            | S3Error::InvalidTag(m)
             | S3Error::MalformedXML(m)
             | S3Error::InvalidRange(m)
+            | S3Error::InvalidObjectState(m)
+            | S3Error::InvalidStorageClass(m)
             | S3Error::InternalError(m) => m,
         }
     }
 }
 
+impl AwsError for S3Error {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        S3Error::message(self)
+    }
+}
+
 impl IntoResponse for S3Error {
     fn into_response(self) -> Response {
-        let body = format!(
-            r#"<?xml version="1.0" encoding="UTF-8"?>
-<Error>
-  <Code>{}</Code>
-  <Message>{}</Message>
-</Error>"#,
-            self.error_code(),
-            self.message()
-        );
-        (
-            self.status_code(),
-            [("content-type", "application/xml")],
-            body,
-        )
-            .into_response()
+        error_response(&self, Protocol::RestXml)
     }
 }
 
@@ -134,6 +156,18 @@ mod tests {
         assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
     }
     #[test]
+    fn test_invalidtag_status() {
+        let err = S3Error::InvalidTag("test".to_string());
+        assert_eq!(err.error_code(), "InvalidTag");
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    }
+    #[test]
+    fn test_invalidobjectstate_status() {
+        let err = S3Error::InvalidObjectState("test".to_string());
+        assert_eq!(err.error_code(), "InvalidObjectState");
+        assert_eq!(err.status_code(), StatusCode::FORBIDDEN);
+    }
+    #[test]
     fn test_into_response() {
         let err = S3Error::NoSuchBucket("test".to_string());
         let resp = err.into_response();
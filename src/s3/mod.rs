@@ -1,5 +1,6 @@
 pub mod bucket;
 pub mod error;
+pub mod select;
 pub mod server;
 pub mod state;
 pub mod types;
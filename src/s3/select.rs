@@ -0,0 +1,1353 @@
+//! A subset of S3 Select SQL over CSV and JSON objects:
+//!
+//! ```sql
+//! SELECT <* | expr [AS name], ...> FROM S3Object[[*]] [[AS] alias]
+//!     [WHERE expr] [LIMIT n]
+//! ```
+//!
+//! Expressions support comparisons, `AND`/`OR`/`NOT`, arithmetic, `||`,
+//! `IS [NOT] NULL`, `[NOT] LIKE`, `[NOT] BETWEEN`, `[NOT] IN`, `CAST`,
+//! `LOWER`, `UPPER`, `TRIM`, `CHAR_LENGTH`, `SUBSTRING`, `COALESCE`,
+//! `NULLIF`, and the aggregates `COUNT`, `SUM`, `AVG`, `MIN` and `MAX`.
+//! CSV fields are strings, but compare and compute as numbers when both
+//! sides parse as one.
+
+use std::cmp::Ordering;
+use std::io::Read;
+
+use serde_json::{Number, Value};
+
+use super::error::S3Error;
+use super::types::{CsvInput, SelectObjectContentRequest};
+
+const CAST_TYPES: &[&str] = &[
+    "INT", "INTEGER", "BIGINT", "SMALLINT", "FLOAT", "DOUBLE", "REAL", "DECIMAL", "NUMERIC", "STRING", "VARCHAR",
+    "CHAR", "BOOL", "BOOLEAN",
+];
+
+/// The serialized records a query returned, and how much input it read.
+pub struct SelectOutput {
+    pub records: Vec<u8>,
+    pub bytes_scanned: u64,
+    pub bytes_processed: u64,
+}
+
+pub fn select(data: &[u8], req: &SelectObjectContentRequest) -> Result<SelectOutput, S3Error> {
+    if !req.expression_type.as_deref().unwrap_or("SQL").eq_ignore_ascii_case("SQL") {
+        return Err(S3Error::InvalidArgument("ExpressionType must be SQL".into()));
+    }
+    let input = &req.input_serialization;
+    let compression = input.compression_type.as_deref().unwrap_or("NONE").to_ascii_uppercase();
+    let data = match compression.as_str() {
+        "NONE" => data.to_vec(),
+        "GZIP" => {
+            let mut out = Vec::new();
+            flate2::read::MultiGzDecoder::new(data)
+                .read_to_end(&mut out)
+                .map_err(|e| S3Error::InvalidRequest(format!("Invalid GZIP input: {e}")))?;
+            out
+        }
+        other => return Err(S3Error::InvalidArgument(format!("CompressionType {other} is not supported"))),
+    };
+    let query = Parser::new(&req.expression)?.query()?;
+    let output = Output::new(req)?;
+
+    let text = String::from_utf8_lossy(&data);
+    let (header, rows) = if let Some(csv) = &input.csv {
+        read_csv(&text, csv)?
+    } else if let Some(json) = &input.json {
+        let json_type = json.json_type.as_deref().unwrap_or("DOCUMENT").to_ascii_uppercase();
+        if json_type != "DOCUMENT" && json_type != "LINES" {
+            return Err(S3Error::InvalidArgument(format!("Invalid JSON Type: {json_type}")));
+        }
+        (None, read_json(&text, query.from_star)?)
+    } else if input.parquet.is_some() {
+        return Err(S3Error::InvalidArgument("Parquet input is not supported".into()));
+    } else {
+        return Err(S3Error::InvalidArgument("InputSerialization must specify CSV or JSON".into()));
+    };
+
+    let ctx = Context { alias: query.alias.as_deref(), header: header.as_deref() };
+    let mut records = Vec::new();
+    match &query.projection {
+        Projection::Items(items) if items.iter().any(|i| i.expr.is_aggregate()) => {
+            if !items.iter().all(|i| i.expr.is_aggregate()) {
+                return Err(S3Error::InvalidRequest(
+                    "Aggregate and non-aggregate expressions cannot be mixed in SELECT".into(),
+                ));
+            }
+            let mut accumulators: Vec<Accumulator> = items.iter().map(|_| Accumulator::default()).collect();
+            for row in &rows {
+                if !query.matches(row, &ctx)? {
+                    continue;
+                }
+                for (item, acc) in items.iter().zip(&mut accumulators) {
+                    acc.add(&item.expr, row, &ctx)?;
+                }
+            }
+            let values: Vec<Value> = items.iter().zip(accumulators).map(|(item, acc)| acc.finish(&item.expr)).collect();
+            output.write(&mut records, &names(items), &values);
+        }
+        projection => {
+            let mut emitted = 0;
+            for row in &rows {
+                if query.limit.is_some_and(|limit| emitted >= limit) {
+                    break;
+                }
+                if !query.matches(row, &ctx)? {
+                    continue;
+                }
+                match projection {
+                    Projection::All => {
+                        let (names, values) = ctx.all_columns(row);
+                        output.write(&mut records, &names, &values);
+                    }
+                    Projection::Items(items) => {
+                        let values = items.iter().map(|i| eval(&i.expr, row, &ctx)).collect::<Result<Vec<_>, _>>()?;
+                        output.write(&mut records, &names(items), &values);
+                    }
+                }
+                emitted += 1;
+            }
+        }
+    }
+
+    Ok(SelectOutput { records, bytes_scanned: data.len() as u64, bytes_processed: data.len() as u64 })
+}
+
+fn sql_error(message: impl Into<String>) -> S3Error {
+    S3Error::InvalidRequest(message.into())
+}
+
+// --- Input ---
+
+enum Row {
+    Csv(Vec<String>),
+    Json(Value),
+}
+
+/// What column references resolve against: the `FROM` alias and the CSV
+/// header, when `FileHeaderInfo` is `USE`.
+struct Context<'a> {
+    alias: Option<&'a str>,
+    header: Option<&'a [String]>,
+}
+
+impl Context<'_> {
+    fn column(&self, row: &Row, path: &[PathSeg]) -> Value {
+        let path = match path.first() {
+            Some(PathSeg::Key { name, quoted: false })
+                if name.eq_ignore_ascii_case("S3Object") || self.alias.is_some_and(|a| a.eq_ignore_ascii_case(name)) =>
+            {
+                &path[1..]
+            }
+            _ => path,
+        };
+        match row {
+            Row::Csv(fields) => {
+                let [PathSeg::Key { name, quoted }] = path else {
+                    return Value::Null;
+                };
+                let index = match name.strip_prefix('_').and_then(|n| n.parse::<usize>().ok()) {
+                    Some(n) => n.checked_sub(1),
+                    None => self.header.and_then(|h| h.iter().position(|c| ident_eq(c, name, *quoted))),
+                };
+                index.and_then(|i| fields.get(i)).map(|f| Value::String(f.clone())).unwrap_or(Value::Null)
+            }
+            Row::Json(value) => {
+                let mut current = value;
+                for seg in path {
+                    let next = match (seg, current) {
+                        (PathSeg::Key { name, quoted }, Value::Object(map)) => map
+                            .get(name)
+                            .or_else(|| map.iter().find(|(k, _)| ident_eq(k, name, *quoted)).map(|(_, v)| v)),
+                        (PathSeg::Index(i), Value::Array(items)) => items.get(*i),
+                        _ => None,
+                    };
+                    match next {
+                        Some(v) => current = v,
+                        None => return Value::Null,
+                    }
+                }
+                current.clone()
+            }
+        }
+    }
+
+    /// The names and values `SELECT *` returns.
+    fn all_columns(&self, row: &Row) -> (Vec<String>, Vec<Value>) {
+        match row {
+            Row::Csv(fields) => {
+                let names = (0..fields.len())
+                    .map(|i| self.header.and_then(|h| h.get(i).cloned()).unwrap_or_else(|| format!("_{}", i + 1)))
+                    .collect();
+                (names, fields.iter().cloned().map(Value::String).collect())
+            }
+            Row::Json(Value::Object(map)) => (map.keys().cloned().collect(), map.values().cloned().collect()),
+            Row::Json(other) => (vec!["_1".to_string()], vec![other.clone()]),
+        }
+    }
+}
+
+fn ident_eq(candidate: &str, name: &str, quoted: bool) -> bool {
+    if quoted {
+        candidate == name
+    } else {
+        candidate.eq_ignore_ascii_case(name)
+    }
+}
+
+fn single_char(value: Option<&str>, default: char) -> char {
+    value.and_then(|v| v.chars().next()).unwrap_or(default)
+}
+
+type CsvTable = (Option<Vec<String>>, Vec<Row>);
+
+fn read_csv(text: &str, csv: &CsvInput) -> Result<CsvTable, S3Error> {
+    let field_delim = single_char(csv.field_delimiter.as_deref(), ',');
+    // `\r\n` is handled by stripping the `\r` from each record.
+    let record_delim = match csv.record_delimiter.as_deref() {
+        Some("\r\n") | None => '\n',
+        Some(d) => single_char(Some(d), '\n'),
+    };
+    let quote = single_char(csv.quote_character.as_deref(), '"');
+    let comment = csv.comments.as_deref().and_then(|c| c.chars().next());
+    let mut records = parse_csv(text, field_delim, record_delim, quote, comment).into_iter();
+    let header = match csv.file_header_info.as_deref().unwrap_or("NONE").to_ascii_uppercase().as_str() {
+        "USE" => records.next(),
+        "IGNORE" => {
+            records.next();
+            None
+        }
+        "NONE" => None,
+        other => return Err(S3Error::InvalidArgument(format!("Invalid FileHeaderInfo: {other}"))),
+    };
+    Ok((header, records.map(Row::Csv).collect()))
+}
+
+fn read_json(text: &str, from_star: bool) -> Result<Vec<Row>, S3Error> {
+    let mut rows = Vec::new();
+    for value in serde_json::Deserializer::from_str(text).into_iter::<Value>() {
+        let value = value.map_err(|e| S3Error::InvalidRequest(format!("Invalid JSON input: {e}")))?;
+        match value {
+            Value::Array(items) if from_star => rows.extend(items.into_iter().map(Row::Json)),
+            value => rows.push(Row::Json(value)),
+        }
+    }
+    Ok(rows)
+}
+
+/// Splits CSV text into records of fields, honouring quoted fields that
+/// contain delimiters, doubled quotes and line breaks. Blank lines and
+/// lines starting with `comment` are skipped.
+fn parse_csv(text: &str, field_delim: char, record_delim: char, quote: char, comment: Option<char>) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut at_record_start = true;
+    let mut chars = text.chars().peekable();
+    let mut end_record = |record: &mut Vec<String>, mut field: String| {
+        if record_delim == '\n' && field.ends_with('\r') {
+            field.pop();
+        }
+        record.push(field);
+        if record.len() == 1 && record[0].is_empty() {
+            record.clear();
+        } else {
+            records.push(std::mem::take(record));
+        }
+    };
+    while let Some(c) = chars.next() {
+        if at_record_start && comment == Some(c) {
+            for c in chars.by_ref() {
+                if c == record_delim {
+                    break;
+                }
+            }
+            continue;
+        }
+        at_record_start = false;
+        if in_quotes {
+            if c != quote {
+                field.push(c);
+            } else if chars.peek() == Some(&quote) {
+                field.push(quote);
+                chars.next();
+            } else {
+                in_quotes = false;
+            }
+        } else if c == quote {
+            in_quotes = true;
+        } else if c == field_delim {
+            record.push(std::mem::take(&mut field));
+        } else if c == record_delim {
+            end_record(&mut record, std::mem::take(&mut field));
+            at_record_start = true;
+        } else {
+            field.push(c);
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        end_record(&mut record, field);
+    }
+    records
+}
+
+// --- Output ---
+
+enum Output {
+    Csv { field: String, record: String, quote: char, always: bool },
+    Json { record: String },
+}
+
+impl Output {
+    fn new(req: &SelectObjectContentRequest) -> Result<Self, S3Error> {
+        let out = &req.output_serialization;
+        if let Some(csv) = &out.csv {
+            Ok(Output::Csv {
+                field: csv.field_delimiter.clone().unwrap_or_else(|| ",".into()),
+                record: csv.record_delimiter.clone().unwrap_or_else(|| "\n".into()),
+                quote: single_char(csv.quote_character.as_deref(), '"'),
+                always: csv.quote_fields.as_deref().is_some_and(|q| q.eq_ignore_ascii_case("ALWAYS")),
+            })
+        } else if let Some(json) = &out.json {
+            Ok(Output::Json { record: json.record_delimiter.clone().unwrap_or_else(|| "\n".into()) })
+        } else {
+            Err(S3Error::InvalidArgument("OutputSerialization must specify CSV or JSON".into()))
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>, names: &[String], values: &[Value]) {
+        let line = match self {
+            Output::Csv { field, record, quote, always } => {
+                let fields: Vec<String> = values
+                    .iter()
+                    .map(|v| {
+                        let s = text(v).unwrap_or_default();
+                        let needs_quotes = s.contains(field.as_str())
+                            || s.contains(*quote)
+                            || s.contains(['\n', '\r'])
+                            || s.contains(record.as_str());
+                        if *always || needs_quotes {
+                            let doubled: String = [*quote, *quote].iter().collect();
+                            format!("{quote}{}{quote}", s.replace(*quote, &doubled))
+                        } else {
+                            s
+                        }
+                    })
+                    .collect();
+                fields.join(field) + record
+            }
+            // Built by hand so the keys keep the projection's order.
+            Output::Json { record } => {
+                let pairs: Vec<String> = names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| format!("{}:{value}", Value::from(name.as_str())))
+                    .collect();
+                format!("{{{}}}{record}", pairs.join(","))
+            }
+        };
+        out.extend_from_slice(line.as_bytes());
+    }
+}
+
+/// Output names: the `AS` alias, else the column's last path segment, else
+/// `_N` for the item's position.
+fn names(items: &[SelectItem]) -> Vec<String> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            item.alias.clone().unwrap_or_else(|| match &item.expr {
+                Expr::Column(path) => match path.last() {
+                    Some(PathSeg::Key { name, .. }) => name.clone(),
+                    _ => format!("_{}", i + 1),
+                },
+                _ => format!("_{}", i + 1),
+            })
+        })
+        .collect()
+}
+
+// --- Parsing ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier or keyword, and whether it was double-quoted.
+    Ident(String, bool),
+    Str(String),
+    Num(Value),
+    Sym(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(s, _) => s.clone(),
+            Token::Str(s) => format!("'{s}'"),
+            Token::Num(v) => v.to_string(),
+            Token::Sym(s) => s.to_string(),
+        }
+    }
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token>, S3Error> {
+    const PAIRS: &[&str] = &["<=", ">=", "<>", "!=", "||"];
+    const SINGLES: &[&str] = &["(", ")", ",", ".", "*", "[", "]", "=", "<", ">", "+", "-", "/", "%", ";"];
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '\'' || c == '"' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(sql_error(format!("Unterminated quoted string in expression: {sql}"))),
+                    Some(&ch) if ch == c && chars.get(i + 1) == Some(&c) => {
+                        s.push(c);
+                        i += 2;
+                    }
+                    Some(&ch) if ch == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&ch) => {
+                        s.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(if c == '\'' { Token::Str(s) } else { Token::Ident(s, true) });
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let s: String = chars[start..i].iter().collect();
+            let value = match s.parse::<i64>() {
+                Ok(n) => Value::from(n),
+                Err(_) => s
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map(Value::Number)
+                    .ok_or_else(|| sql_error(format!("Invalid number: {s}")))?,
+            };
+            tokens.push(Token::Num(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect(), false));
+        } else {
+            let pair: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let sym = PAIRS
+                .iter()
+                .find(|s| **s == pair)
+                .or_else(|| SINGLES.iter().find(|s| s.starts_with(c)))
+                .ok_or_else(|| sql_error(format!("Unexpected character '{c}' in expression")))?;
+            i += sym.len();
+            tokens.push(Token::Sym(sym));
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSeg {
+    Key { name: String, quoted: bool },
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Concat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Agg {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Literal(Value),
+    Column(Vec<PathSeg>),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    IsNull(Box<Expr>, bool),
+    Like { expr: Box<Expr>, pattern: Box<Expr>, escape: Option<Box<Expr>>, negated: bool },
+    Between { expr: Box<Expr>, low: Box<Expr>, high: Box<Expr>, negated: bool },
+    In { expr: Box<Expr>, list: Vec<Expr>, negated: bool },
+    Cast(Box<Expr>, String),
+    Call(String, Vec<Expr>),
+    /// `None` is `COUNT(*)`.
+    Aggregate(Agg, Option<Box<Expr>>),
+}
+
+impl Expr {
+    fn is_aggregate(&self) -> bool {
+        matches!(self, Expr::Aggregate(..))
+    }
+}
+
+struct SelectItem {
+    expr: Expr,
+    alias: Option<String>,
+}
+
+enum Projection {
+    All,
+    Items(Vec<SelectItem>),
+}
+
+struct Query {
+    projection: Projection,
+    alias: Option<String>,
+    /// `FROM S3Object[*]`: a top-level JSON array is one record per element.
+    from_star: bool,
+    where_clause: Option<Expr>,
+    limit: Option<usize>,
+}
+
+impl Query {
+    fn matches(&self, row: &Row, ctx: &Context) -> Result<bool, S3Error> {
+        Ok(match &self.where_clause {
+            Some(expr) => truth(&eval(expr, row, ctx)?) == Some(true),
+            None => true,
+        })
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(sql: &str) -> Result<Self, S3Error> {
+        Ok(Self { tokens: tokenize(sql)?, pos: 0 })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn is_kw_at(&self, offset: usize, kw: &str) -> bool {
+        matches!(self.tokens.get(self.pos + offset), Some(Token::Ident(s, false)) if s.eq_ignore_ascii_case(kw))
+    }
+
+    fn is_kw(&self, kw: &str) -> bool {
+        self.is_kw_at(0, kw)
+    }
+
+    fn eat_kw(&mut self, kw: &str) -> bool {
+        let found = self.is_kw(kw);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_kw(&mut self, kw: &str) -> Result<(), S3Error> {
+        if self.eat_kw(kw) {
+            Ok(())
+        } else {
+            Err(self.unexpected(kw))
+        }
+    }
+
+    fn eat_sym(&mut self, sym: &'static str) -> bool {
+        let found = self.peek() == Some(&Token::Sym(sym));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_sym(&mut self, sym: &'static str) -> Result<(), S3Error> {
+        if self.eat_sym(sym) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{sym}'")))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> S3Error {
+        match self.peek() {
+            Some(token) => sql_error(format!("Expected {expected} but found {}", token.describe())),
+            None => sql_error(format!("Expected {expected} but reached the end of the expression")),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, S3Error> {
+        match self.peek().cloned() {
+            Some(Token::Ident(name, _)) => {
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    /// An identifier that is not one of `reserved`, used for optional
+    /// aliases written without `AS`.
+    fn bare_alias(&mut self, reserved: &[&str]) -> Result<Option<String>, S3Error> {
+        if self.eat_kw("AS") {
+            return self.ident().map(Some);
+        }
+        match self.peek() {
+            Some(Token::Ident(_, quoted)) if *quoted || !reserved.iter().any(|kw| self.is_kw(kw)) => {
+                self.ident().map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn query(mut self) -> Result<Query, S3Error> {
+        self.expect_kw("SELECT")?;
+        let projection = if self.eat_sym("*") {
+            Projection::All
+        } else {
+            let mut items = Vec::new();
+            loop {
+                let expr = self.expr()?;
+                let alias = self.bare_alias(&["FROM"])?;
+                items.push(SelectItem { expr, alias });
+                if !self.eat_sym(",") {
+                    break;
+                }
+            }
+            Projection::Items(items)
+        };
+        self.expect_kw("FROM")?;
+        self.expect_kw("S3Object")?;
+        let from_star = self.eat_sym("[");
+        if from_star {
+            self.expect_sym("*")?;
+            self.expect_sym("]")?;
+        }
+        let alias = self.bare_alias(&["WHERE", "LIMIT"])?;
+        let where_clause = if self.eat_kw("WHERE") { Some(self.expr()?) } else { None };
+        let limit = if self.eat_kw("LIMIT") {
+            match self.peek() {
+                Some(Token::Num(n)) if n.is_u64() => {
+                    let limit = n.as_u64().unwrap_or_default() as usize;
+                    self.pos += 1;
+                    Some(limit)
+                }
+                _ => return Err(self.unexpected("a non-negative integer LIMIT")),
+            }
+        } else {
+            None
+        };
+        self.eat_sym(";");
+        if self.peek().is_some() {
+            return Err(self.unexpected("the end of the expression"));
+        }
+        Ok(Query { projection, alias, from_star, where_clause, limit })
+    }
+
+    fn expr(&mut self) -> Result<Expr, S3Error> {
+        let mut left = self.and()?;
+        while self.eat_kw("OR") {
+            left = Expr::Binary(BinOp::Or, Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, S3Error> {
+        let mut left = self.not()?;
+        while self.eat_kw("AND") {
+            left = Expr::Binary(BinOp::And, Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, S3Error> {
+        if self.eat_kw("NOT") {
+            Ok(Expr::Not(Box::new(self.not()?)))
+        } else {
+            self.predicate()
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Expr, S3Error> {
+        let left = self.additive()?;
+        if self.eat_kw("IS") {
+            let negated = self.eat_kw("NOT");
+            if !self.eat_kw("NULL") && !self.eat_kw("MISSING") {
+                return Err(self.unexpected("NULL"));
+            }
+            return Ok(Expr::IsNull(Box::new(left), negated));
+        }
+        let negated = self.is_kw("NOT") && ["LIKE", "BETWEEN", "IN"].iter().any(|kw| self.is_kw_at(1, kw));
+        if negated {
+            self.pos += 1;
+        }
+        let expr = Box::new(left);
+        if self.eat_kw("LIKE") {
+            let pattern = Box::new(self.additive()?);
+            let escape = if self.eat_kw("ESCAPE") { Some(Box::new(self.additive()?)) } else { None };
+            return Ok(Expr::Like { expr, pattern, escape, negated });
+        }
+        if self.eat_kw("BETWEEN") {
+            let low = Box::new(self.additive()?);
+            self.expect_kw("AND")?;
+            let high = Box::new(self.additive()?);
+            return Ok(Expr::Between { expr, low, high, negated });
+        }
+        if self.eat_kw("IN") {
+            self.expect_sym("(")?;
+            let list = self.args()?;
+            return Ok(Expr::In { expr, list, negated });
+        }
+        let op = match self.peek() {
+            Some(Token::Sym("=")) => BinOp::Eq,
+            Some(Token::Sym("!=" | "<>")) => BinOp::Ne,
+            Some(Token::Sym("<")) => BinOp::Lt,
+            Some(Token::Sym("<=")) => BinOp::Le,
+            Some(Token::Sym(">")) => BinOp::Gt,
+            Some(Token::Sym(">=")) => BinOp::Ge,
+            _ => return Ok(*expr),
+        };
+        self.pos += 1;
+        Ok(Expr::Binary(op, expr, Box::new(self.additive()?)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, S3Error> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("+")) => BinOp::Add,
+                Some(Token::Sym("-")) => BinOp::Sub,
+                Some(Token::Sym("||")) => BinOp::Concat,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, S3Error> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("*")) => BinOp::Mul,
+                Some(Token::Sym("/")) => BinOp::Div,
+                Some(Token::Sym("%")) => BinOp::Mod,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, S3Error> {
+        if self.eat_sym("-") {
+            Ok(Expr::Neg(Box::new(self.unary()?)))
+        } else if self.eat_sym("+") {
+            self.unary()
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, S3Error> {
+        match self.peek().cloned() {
+            Some(Token::Num(value)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(value))
+            }
+            Some(Token::Str(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(Value::String(s)))
+            }
+            Some(Token::Sym("(")) => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                self.expect_sym(")")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name, false)) if self.tokens.get(self.pos + 1) == Some(&Token::Sym("(")) => {
+                self.pos += 2;
+                self.call(name.to_ascii_uppercase())
+            }
+            Some(Token::Ident(name, false)) if ["NULL", "TRUE", "FALSE"].iter().any(|kw| name.eq_ignore_ascii_case(kw)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(match name.to_ascii_uppercase().as_str() {
+                    "TRUE" => Value::Bool(true),
+                    "FALSE" => Value::Bool(false),
+                    _ => Value::Null,
+                }))
+            }
+            Some(Token::Ident(..)) => self.path(),
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    /// A column reference: `name`, `alias.name`, `a.b[0].c`.
+    fn path(&mut self) -> Result<Expr, S3Error> {
+        let mut path = Vec::new();
+        loop {
+            match self.peek().cloned() {
+                Some(Token::Ident(name, quoted)) => {
+                    self.pos += 1;
+                    path.push(PathSeg::Key { name, quoted });
+                }
+                _ => return Err(self.unexpected("an identifier")),
+            }
+            while self.eat_sym("[") {
+                match self.peek().and_then(|t| match t {
+                    Token::Num(n) => n.as_u64(),
+                    _ => None,
+                }) {
+                    Some(index) => {
+                        self.pos += 1;
+                        path.push(PathSeg::Index(index as usize));
+                    }
+                    None => return Err(self.unexpected("an array index")),
+                }
+                self.expect_sym("]")?;
+            }
+            if !self.eat_sym(".") {
+                return Ok(Expr::Column(path));
+            }
+        }
+    }
+
+    /// Comma-separated expressions up to and including the closing `)`.
+    fn args(&mut self) -> Result<Vec<Expr>, S3Error> {
+        let mut args = Vec::new();
+        if self.eat_sym(")") {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expr()?);
+            if self.eat_sym(")") {
+                return Ok(args);
+            }
+            self.expect_sym(",")?;
+        }
+    }
+
+    /// A function call whose name and `(` have been consumed.
+    fn call(&mut self, name: String) -> Result<Expr, S3Error> {
+        let agg = match name.as_str() {
+            "COUNT" => Some(Agg::Count),
+            "SUM" => Some(Agg::Sum),
+            "AVG" => Some(Agg::Avg),
+            "MIN" => Some(Agg::Min),
+            "MAX" => Some(Agg::Max),
+            _ => None,
+        };
+        if let Some(agg) = agg {
+            if agg == Agg::Count && self.eat_sym("*") {
+                self.expect_sym(")")?;
+                return Ok(Expr::Aggregate(agg, None));
+            }
+            let arg = self.expr()?;
+            self.expect_sym(")")?;
+            return Ok(Expr::Aggregate(agg, Some(Box::new(arg))));
+        }
+        if name == "CAST" {
+            let expr = self.expr()?;
+            self.expect_kw("AS")?;
+            let ty = self.ident()?.to_ascii_uppercase();
+            self.expect_sym(")")?;
+            if !CAST_TYPES.contains(&ty.as_str()) {
+                return Err(sql_error(format!("Unsupported CAST type: {ty}")));
+            }
+            return Ok(Expr::Cast(Box::new(expr), ty));
+        }
+        let args = if name == "SUBSTRING" {
+            // Both SUBSTRING(s FROM start [FOR len]) and SUBSTRING(s, start[, len]).
+            let mut args = vec![self.expr()?];
+            if self.eat_kw("FROM") {
+                args.push(self.expr()?);
+                if self.eat_kw("FOR") {
+                    args.push(self.expr()?);
+                }
+                self.expect_sym(")")?;
+            } else if self.eat_sym(",") {
+                args.extend(self.args()?);
+            } else {
+                self.expect_sym(")")?;
+            }
+            args
+        } else {
+            self.args()?
+        };
+        let arity_ok = match name.as_str() {
+            "LOWER" | "UPPER" | "TRIM" | "CHAR_LENGTH" | "CHARACTER_LENGTH" => args.len() == 1,
+            "SUBSTRING" => (2..=3).contains(&args.len()),
+            "NULLIF" => args.len() == 2,
+            "COALESCE" => !args.is_empty(),
+            _ => return Err(sql_error(format!("Unsupported function: {name}"))),
+        };
+        if !arity_ok {
+            return Err(sql_error(format!("Incorrect number of arguments to {name}")));
+        }
+        Ok(Expr::Call(name, args))
+    }
+}
+
+// --- Evaluation ---
+
+#[derive(Clone, Copy)]
+enum Num {
+    Int(i64),
+    Float(f64),
+}
+
+impl Num {
+    fn as_f64(self) -> f64 {
+        match self {
+            Num::Int(i) => i as f64,
+            Num::Float(f) => f,
+        }
+    }
+
+    fn as_i64(self) -> i64 {
+        match self {
+            Num::Int(i) => i,
+            Num::Float(f) => f as i64,
+        }
+    }
+}
+
+/// A number, or a string that parses as one (every CSV field is a string).
+fn num(value: &Value) -> Option<Num> {
+    match value {
+        Value::Number(n) => n.as_i64().map(Num::Int).or_else(|| n.as_f64().map(Num::Float)),
+        Value::String(s) => {
+            let s = s.trim();
+            s.parse::<i64>()
+                .ok()
+                .map(Num::Int)
+                .or_else(|| s.parse::<f64>().ok().filter(|f| f.is_finite()).map(Num::Float))
+        }
+        _ => None,
+    }
+}
+
+fn float(f: f64) -> Value {
+    Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null)
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn truth(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) if s.eq_ignore_ascii_case("true") => Some(true),
+        Value::String(s) if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+/// Orders two values numerically when both are numbers, else as text.
+/// `None` when either side is null.
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    if left.is_null() || right.is_null() {
+        return None;
+    }
+    match (num(left), num(right)) {
+        (Some(Num::Int(a)), Some(Num::Int(b))) => Some(a.cmp(&b)),
+        (Some(a), Some(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        _ => match (left, right) {
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            _ => Some(text(left)?.cmp(&text(right)?)),
+        },
+    }
+}
+
+fn eval(expr: &Expr, row: &Row, ctx: &Context) -> Result<Value, S3Error> {
+    Ok(match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Column(path) => ctx.column(row, path),
+        Expr::Neg(inner) => match num(&eval(inner, row, ctx)?) {
+            Some(Num::Int(i)) => i.checked_neg().map(Value::from).unwrap_or_else(|| float(-(i as f64))),
+            Some(Num::Float(f)) => float(-f),
+            None => Value::Null,
+        },
+        Expr::Not(inner) => match truth(&eval(inner, row, ctx)?) {
+            Some(b) => Value::Bool(!b),
+            None => Value::Null,
+        },
+        Expr::Binary(op @ (BinOp::And | BinOp::Or), left, right) => {
+            let left = truth(&eval(left, row, ctx)?);
+            let right = truth(&eval(right, row, ctx)?);
+            // Three-valued logic: a decisive side wins over a null one.
+            let decisive = *op == BinOp::Or;
+            if left == Some(decisive) || right == Some(decisive) {
+                Value::Bool(decisive)
+            } else if left.is_some() && right.is_some() {
+                Value::Bool(!decisive)
+            } else {
+                Value::Null
+            }
+        }
+        Expr::Binary(op, left, right) => binary(*op, &eval(left, row, ctx)?, &eval(right, row, ctx)?)?,
+        Expr::IsNull(inner, negated) => Value::Bool(eval(inner, row, ctx)?.is_null() != *negated),
+        Expr::Like { expr, pattern, escape, negated } => {
+            let (Some(s), Some(pattern)) = (text(&eval(expr, row, ctx)?), text(&eval(pattern, row, ctx)?)) else {
+                return Ok(Value::Null);
+            };
+            let escape = match escape {
+                Some(escape) => text(&eval(escape, row, ctx)?).and_then(|e| e.chars().next()),
+                None => None,
+            };
+            Value::Bool(like(&s, &pattern, escape) != *negated)
+        }
+        Expr::Between { expr, low, high, negated } => {
+            let value = eval(expr, row, ctx)?;
+            match (compare(&value, &eval(low, row, ctx)?), compare(&value, &eval(high, row, ctx)?)) {
+                (Some(lo), Some(hi)) => Value::Bool((lo != Ordering::Less && hi != Ordering::Greater) != *negated),
+                _ => Value::Null,
+            }
+        }
+        Expr::In { expr, list, negated } => {
+            let value = eval(expr, row, ctx)?;
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            let mut found = false;
+            for item in list {
+                if compare(&value, &eval(item, row, ctx)?) == Some(Ordering::Equal) {
+                    found = true;
+                    break;
+                }
+            }
+            Value::Bool(found != *negated)
+        }
+        Expr::Cast(inner, ty) => cast(eval(inner, row, ctx)?, ty)?,
+        Expr::Call(name, args) => {
+            let args = args.iter().map(|a| eval(a, row, ctx)).collect::<Result<Vec<_>, _>>()?;
+            call(name, args)
+        }
+        Expr::Aggregate(..) => {
+            return Err(sql_error("Aggregate functions are only supported as top-level SELECT items"));
+        }
+    })
+}
+
+fn binary(op: BinOp, left: &Value, right: &Value) -> Result<Value, S3Error> {
+    let ordering = |accept: fn(Ordering) -> bool| compare(left, right).map(|o| Value::Bool(accept(o)));
+    let result = match op {
+        BinOp::Eq => ordering(|o| o == Ordering::Equal),
+        BinOp::Ne => ordering(|o| o != Ordering::Equal),
+        BinOp::Lt => ordering(|o| o == Ordering::Less),
+        BinOp::Le => ordering(|o| o != Ordering::Greater),
+        BinOp::Gt => ordering(|o| o == Ordering::Greater),
+        BinOp::Ge => ordering(|o| o != Ordering::Less),
+        BinOp::Concat => match (text(left), text(right)) {
+            (Some(a), Some(b)) => Some(Value::String(a + &b)),
+            _ => None,
+        },
+        BinOp::And | BinOp::Or => None,
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod => {
+            let (Some(a), Some(b)) = (num(left), num(right)) else {
+                return Ok(Value::Null);
+            };
+            if matches!(op, BinOp::Div | BinOp::Mod) && b.as_f64() == 0.0 {
+                return Err(sql_error("Division by zero"));
+            }
+            let int = match (a, b) {
+                (Num::Int(a), Num::Int(b)) => match op {
+                    BinOp::Add => a.checked_add(b),
+                    BinOp::Sub => a.checked_sub(b),
+                    BinOp::Mul => a.checked_mul(b),
+                    BinOp::Div => a.checked_div(b),
+                    _ => a.checked_rem(b),
+                },
+                _ => None,
+            };
+            Some(int.map(Value::from).unwrap_or_else(|| {
+                let (a, b) = (a.as_f64(), b.as_f64());
+                float(match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    BinOp::Div => a / b,
+                    _ => a % b,
+                })
+            }))
+        }
+    };
+    Ok(result.unwrap_or(Value::Null))
+}
+
+/// SQL `LIKE`: `%` matches any run of characters and `_` any one.
+fn like(s: &str, pattern: &str, escape: Option<char>) -> bool {
+    enum Part {
+        Any,
+        One,
+        Lit(char),
+    }
+    let mut parts = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        parts.push(match c {
+            _ if Some(c) == escape => match chars.next() {
+                Some(next) => Part::Lit(next),
+                None => Part::Lit(c),
+            },
+            '%' => Part::Any,
+            '_' => Part::One,
+            _ => Part::Lit(c),
+        });
+    }
+    let s: Vec<char> = s.chars().collect();
+    // matched[j]: whether the parts so far match the first j characters.
+    let mut matched = vec![false; s.len() + 1];
+    matched[0] = true;
+    for part in &parts {
+        let mut next = vec![false; s.len() + 1];
+        match part {
+            Part::Any => {
+                let mut seen = false;
+                for (slot, &m) in next.iter_mut().zip(&matched) {
+                    seen |= m;
+                    *slot = seen;
+                }
+            }
+            Part::One | Part::Lit(_) => {
+                for (j, slot) in next.iter_mut().enumerate().skip(1) {
+                    let fits = match part {
+                        Part::Lit(c) => s[j - 1] == *c,
+                        _ => true,
+                    };
+                    *slot = matched[j - 1] && fits;
+                }
+            }
+        }
+        matched = next;
+    }
+    matched[s.len()]
+}
+
+fn cast(value: Value, ty: &str) -> Result<Value, S3Error> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+    let failed = || sql_error(format!("Attempt to convert from one data type to another failed: {value} to {ty}"));
+    match ty {
+        "STRING" | "VARCHAR" | "CHAR" => Ok(Value::String(text(&value).unwrap_or_default())),
+        "BOOL" | "BOOLEAN" => truth(&value).map(Value::Bool).ok_or_else(failed),
+        "FLOAT" | "DOUBLE" | "REAL" | "DECIMAL" | "NUMERIC" => num(&value).map(|n| float(n.as_f64())).ok_or_else(failed),
+        _ => num(&value).map(|n| Value::from(n.as_i64())).ok_or_else(failed),
+    }
+}
+
+fn call(name: &str, args: Vec<Value>) -> Value {
+    let string = |f: fn(&str) -> Value| text(&args[0]).map(|s| f(&s)).unwrap_or(Value::Null);
+    match name {
+        "LOWER" => string(|s| Value::String(s.to_lowercase())),
+        "UPPER" => string(|s| Value::String(s.to_uppercase())),
+        "TRIM" => string(|s| Value::String(s.trim().to_string())),
+        "CHAR_LENGTH" | "CHARACTER_LENGTH" => string(|s| Value::from(s.chars().count())),
+        "NULLIF" if compare(&args[0], &args[1]) == Some(Ordering::Equal) => Value::Null,
+        "NULLIF" => args[0].clone(),
+        "SUBSTRING" => {
+            // 1-based; a start before 1 still counts towards the length.
+            let (Some(s), Some(start)) = (text(&args[0]), num(&args[1]).map(Num::as_i64)) else {
+                return Value::Null;
+            };
+            let end = match args.get(2) {
+                Some(len) => match num(len) {
+                    Some(len) => start.saturating_add(len.as_i64().max(0)),
+                    None => return Value::Null,
+                },
+                None => i64::MAX,
+            };
+            let from = start.max(1);
+            let take = usize::try_from(end.saturating_sub(from)).unwrap_or(0);
+            Value::String(s.chars().skip(from as usize - 1).take(take).collect())
+        }
+        _ => args.into_iter().find(|v| !v.is_null()).unwrap_or(Value::Null),
+    }
+}
+
+#[derive(Default)]
+struct Accumulator {
+    count: u64,
+    int_sum: i64,
+    float_sum: f64,
+    floats: bool,
+    best: Option<Value>,
+}
+
+impl Accumulator {
+    fn add(&mut self, expr: &Expr, row: &Row, ctx: &Context) -> Result<(), S3Error> {
+        let Expr::Aggregate(agg, arg) = expr else {
+            return Ok(());
+        };
+        let value = match arg {
+            Some(arg) => eval(arg, row, ctx)?,
+            None => {
+                self.count += 1;
+                return Ok(());
+            }
+        };
+        if value.is_null() {
+            return Ok(());
+        }
+        match agg {
+            Agg::Count => {}
+            Agg::Sum | Agg::Avg => match num(&value) {
+                Some(Num::Int(i)) if !self.floats => match self.int_sum.checked_add(i) {
+                    Some(sum) => self.int_sum = sum,
+                    None => {
+                        self.floats = true;
+                        self.float_sum = self.int_sum as f64 + i as f64;
+                    }
+                },
+                Some(n) => {
+                    if !self.floats {
+                        self.floats = true;
+                        self.float_sum = self.int_sum as f64;
+                    }
+                    self.float_sum += n.as_f64();
+                }
+                None => return Err(sql_error(format!("Cannot aggregate non-numeric value: {value}"))),
+            },
+            Agg::Min | Agg::Max => {
+                let wanted = if *agg == Agg::Min { Ordering::Less } else { Ordering::Greater };
+                if self.best.as_ref().is_none_or(|best| compare(&value, best) == Some(wanted)) {
+                    self.best = Some(value);
+                }
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(self, expr: &Expr) -> Value {
+        let Expr::Aggregate(agg, _) = expr else {
+            return Value::Null;
+        };
+        let sum = if self.floats { self.float_sum } else { self.int_sum as f64 };
+        match agg {
+            Agg::Count => Value::from(self.count),
+            _ if self.count == 0 => Value::Null,
+            Agg::Sum if self.floats => float(self.float_sum),
+            Agg::Sum => Value::from(self.int_sum),
+            Agg::Avg => float(sum / self.count as f64),
+            Agg::Min | Agg::Max => self.best.unwrap_or(Value::Null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::types::{CsvOutput, InputSerialization, JsonInput, JsonOutput, OutputSerialization};
+
+    const PEOPLE: &str = "Name,Age,City\nalice,30,Seattle\nbob,25,\"Portland, OR\"\ncarol,41,Seattle\n";
+
+    fn csv_request(expression: &str) -> SelectObjectContentRequest {
+        SelectObjectContentRequest {
+            expression: expression.to_string(),
+            input_serialization: InputSerialization {
+                csv: Some(CsvInput { file_header_info: Some("USE".into()), ..Default::default() }),
+                ..Default::default()
+            },
+            output_serialization: OutputSerialization { csv: Some(CsvOutput::default()), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    fn run(data: &str, req: &SelectObjectContentRequest) -> String {
+        String::from_utf8(select(data.as_bytes(), req).unwrap().records).unwrap()
+    }
+
+    #[test]
+    fn test_csv_where_and_projection() {
+        let req = csv_request("SELECT s.name, s.City FROM S3Object s WHERE CAST(s.age AS INT) > 26");
+        assert_eq!(run(PEOPLE, &req), "alice,Seattle\ncarol,Seattle\n");
+
+        let req = csv_request("select _1, _3 from s3object where _3 LIKE 'Port%' limit 5");
+        assert_eq!(run(PEOPLE, &req), "bob,\"Portland, OR\"\n");
+
+        let req = csv_request("SELECT Name FROM S3Object WHERE Age BETWEEN 26 AND 40 OR City IN ('Nowhere')");
+        assert_eq!(run(PEOPLE, &req), "alice\n");
+
+        let req = csv_request("SELECT * FROM S3Object LIMIT 1");
+        assert_eq!(run(PEOPLE, &req), "alice,30,Seattle\n");
+    }
+
+    #[test]
+    fn test_aggregates_and_functions() {
+        let req = csv_request("SELECT COUNT(*), SUM(Age), MAX(Age), AVG(Age) FROM S3Object WHERE City = 'Seattle'");
+        assert_eq!(run(PEOPLE, &req), "2,71,41,35.5\n");
+
+        let req = csv_request("SELECT UPPER(Name) || '!', SUBSTRING(City FROM 1 FOR 4), CHAR_LENGTH(Name) FROM S3Object LIMIT 1");
+        assert_eq!(run(PEOPLE, &req), "ALICE!,Seat,5\n");
+
+        let req = csv_request("SELECT Name, COUNT(*) FROM S3Object");
+        assert!(matches!(select(PEOPLE.as_bytes(), &req), Err(S3Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_json_lines_to_json() {
+        let data = "{\"id\": 1, \"user\": {\"name\": \"a\"}, \"tags\": [\"x\"]}\n{\"id\": 2, \"user\": {\"name\": \"b\"}}\n";
+        let req = SelectObjectContentRequest {
+            expression: "SELECT s.id AS ident, s.user.name, s.tags[0] FROM S3Object s WHERE s.id >= 1".to_string(),
+            input_serialization: InputSerialization {
+                json: Some(JsonInput { json_type: Some("LINES".into()) }),
+                ..Default::default()
+            },
+            output_serialization: OutputSerialization { json: Some(JsonOutput::default()), ..Default::default() },
+            ..Default::default()
+        };
+        assert_eq!(run(data, &req), "{\"ident\":1,\"name\":\"a\",\"_3\":\"x\"}\n{\"ident\":2,\"name\":\"b\",\"_3\":null}\n");
+    }
+
+    #[test]
+    fn test_invalid_sql_is_rejected() {
+        for sql in ["SELECT FROM S3Object", "SELECT * FROM Elsewhere", "SELECT * FROM S3Object WHERE", "DELETE x"] {
+            assert!(matches!(select(PEOPLE.as_bytes(), &csv_request(sql)), Err(S3Error::InvalidRequest(_))), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_like_and_csv_parsing() {
+        assert!(like("hello", "h_l%", None));
+        assert!(!like("hello", "h_l", None));
+        assert!(like("50%", "50!%", Some('!')));
+        let records = parse_csv("a,\"b \"\"q\"\"\"\r\n# note\n\nc,d", ',', '\n', '"', Some('#'));
+        assert_eq!(records, vec![vec!["a", "b \"q\""], vec!["c", "d"]]);
+    }
+}
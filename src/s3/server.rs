@@ -2,17 +2,24 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::body::Bytes;
-use axum::extract::{Path, Query, State};
-use axum::http::{HeaderMap, StatusCode};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{response, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
 use axum::response::{IntoResponse, Response};
 use axum::extract::DefaultBodyLimit;
 use axum::routing::get;
 use axum::Router;
 
+use crate::eventstream;
+use crate::s3::bucket::{ByteRange, CopyObjectOptions, Object, PutObjectOptions};
 use crate::s3::error::S3Error;
+use crate::s3::select::SelectOutput;
 use crate::s3::state::S3State;
 use crate::s3::types::*;
 
+/// Records are split across `Records` events of at most this many bytes.
+const MAX_SELECT_RECORDS_PAYLOAD: usize = 64 * 1024;
+
 fn xml_response<T: serde::Serialize>(value: &T) -> Response {
     let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
     if let Ok(s) = quick_xml::se::to_string(value) {
@@ -39,7 +46,7 @@ fn extract_metadata(headers: &HeaderMap) -> HashMap<String, String> {
     metadata
 }
 
-fn parse_range(header: Option<&str>, total_size: u64) -> Option<(u64, Option<u64>)> {
+fn parse_range(header: Option<&str>) -> Option<ByteRange> {
     let header = header?;
     let range = header.strip_prefix("bytes=")?;
     let parts: Vec<&str> = range.split('-').collect();
@@ -49,7 +56,7 @@ fn parse_range(header: Option<&str>, total_size: u64) -> Option<(u64, Option<u64
     if parts[0].is_empty() {
         // suffix range: -N means last N bytes
         let n: u64 = parts[1].parse().ok()?;
-        Some((total_size.saturating_sub(n), None))
+        Some(ByteRange::Suffix(n))
     } else {
         let start: u64 = parts[0].parse().ok()?;
         let end = if parts[1].is_empty() {
@@ -57,10 +64,24 @@ fn parse_range(header: Option<&str>, total_size: u64) -> Option<(u64, Option<u64
         } else {
             Some(parts[1].parse::<u64>().ok()?)
         };
-        Some((start, end))
+        Some(ByteRange::FromTo(start, end))
     }
 }
 
+/// Size of the chunks a `GetObject` body is streamed in.
+const GET_OBJECT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Streams `body` in chunks that are slices of it, so nothing is copied.
+fn stream_body(body: Bytes) -> axum::body::Body {
+    let chunks = (0..body.len())
+        .step_by(GET_OBJECT_CHUNK_SIZE)
+        .map(move |start| {
+            let end = (start + GET_OBJECT_CHUNK_SIZE).min(body.len());
+            Ok::<_, std::convert::Infallible>(body.slice(start..end))
+        });
+    axum::body::Body::from_stream(futures_util::stream::iter(chunks))
+}
+
 fn parse_copy_source(header: &str) -> (String, String) {
     let path = if let Some(stripped) = header.strip_prefix('/') {
         stripped
@@ -78,14 +99,40 @@ fn parse_copy_source(header: &str) -> (String, String) {
     }
 }
 
+/// Adds `x-amz-storage-class` for objects outside STANDARD and
+/// `x-amz-restore` while a restored copy of an archived object is readable.
+fn storage_headers(mut builder: response::Builder, obj: &Object) -> response::Builder {
+    if obj.storage_class != "STANDARD" {
+        builder = builder.header("x-amz-storage-class", &obj.storage_class);
+    }
+    if let Some(expiry) = obj.restored_until() {
+        builder = builder.header(
+            "x-amz-restore",
+            format!(
+                "ongoing-request=\"false\", expiry-date=\"{}\"",
+                expiry.format("%a, %d %b %Y %H:%M:%S GMT")
+            ),
+        );
+    }
+    builder
+}
+
+fn storage_class_header(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-amz-storage-class")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+}
+
 fn tags_to_xml(tags: &HashMap<String, String>) -> String {
-    let tag_entries: Vec<Tag> = tags
+    let mut tag_entries: Vec<Tag> = tags
         .iter()
         .map(|(k, v)| Tag {
             key: k.clone(),
             value: v.clone(),
         })
         .collect();
+    tag_entries.sort_by(|a, b| a.key.cmp(&b.key));
     let tagging = Tagging {
         tag_set: TagSet { tags: tag_entries },
     };
@@ -99,9 +146,27 @@ fn tags_to_xml(tags: &HashMap<String, String>) -> String {
 fn parse_tags_xml(body: &[u8]) -> Result<HashMap<String, String>, S3Error> {
     let tagging: Tagging = quick_xml::de::from_reader(body)
         .map_err(|e| S3Error::MalformedXML(format!("Invalid tagging XML: {e}")))?;
+    collect_tags(tagging.tag_set.tags.into_iter().map(|tag| (tag.key, tag.value)))
+}
+
+/// Parses the URL-encoded `x-amz-tagging` header of `PutObject`, e.g.
+/// `env=prod&team=data`.
+fn parse_tagging_header(headers: &HeaderMap) -> Result<HashMap<String, String>, S3Error> {
+    let Some(value) = headers.get("x-amz-tagging") else {
+        return Ok(HashMap::new());
+    };
+    let value = value
+        .to_str()
+        .map_err(|_| S3Error::InvalidArgument("Invalid x-amz-tagging header".into()))?;
+    collect_tags(form_urlencoded::parse(value.as_bytes()).map(|(k, v)| (k.into_owned(), v.into_owned())))
+}
+
+fn collect_tags(tags: impl Iterator<Item = (String, String)>) -> Result<HashMap<String, String>, S3Error> {
     let mut map = HashMap::new();
-    for tag in tagging.tag_set.tags {
-        map.insert(tag.key, tag.value);
+    for (key, value) in tags {
+        if map.insert(key, value).is_some() {
+            return Err(S3Error::InvalidTag("Cannot provide multiple Tags with the same key".into()));
+        }
     }
     Ok(map)
 }
@@ -143,6 +208,11 @@ async fn bucket_get_handler(
         return Ok(xml_response(&result));
     }
 
+    if params.contains_key("cors") {
+        let config = state.get_bucket_cors(&bucket).await?;
+        return Ok(xml_response(&config));
+    }
+
     // Default: ListObjectsV2
     let prefix = params.get("prefix").map(|s| s.as_str()).unwrap_or("");
     let delimiter = params.get("delimiter").map(|s| s.as_str());
@@ -181,6 +251,13 @@ async fn bucket_put_handler(
         return Ok(StatusCode::NO_CONTENT.into_response());
     }
 
+    if params.contains_key("cors") {
+        let config: CorsConfiguration = quick_xml::de::from_reader(body.as_ref())
+            .map_err(|e| S3Error::MalformedXML(format!("Invalid CORS XML: {e}")))?;
+        state.put_bucket_cors(&bucket, config).await?;
+        return Ok(StatusCode::OK.into_response());
+    }
+
     // CreateBucket
     let location = if body.is_empty() {
         None
@@ -205,6 +282,11 @@ async fn bucket_delete_handler(
         return Ok(StatusCode::NO_CONTENT.into_response());
     }
 
+    if params.contains_key("cors") {
+        state.delete_bucket_cors(&bucket).await?;
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+
     state.delete_bucket(&bucket).await?;
     Ok(StatusCode::NO_CONTENT.into_response())
 }
@@ -244,21 +326,8 @@ async fn object_get_handler(
         .get("range")
         .and_then(|v| v.to_str().ok());
 
-    // We need the object size for suffix ranges, so first get object to determine size
-    let (obj, range_info) = if let Some(range_str) = range {
-        // First get object to know total size for suffix ranges
-        let (obj, _) = state.get_object(&bucket, &key, None).await?;
-        let total = obj.data.len() as u64;
-        let parsed_range = parse_range(Some(range_str), total);
-        if let Some(r) = parsed_range {
-            let (obj, ri) = state.get_object(&bucket, &key, Some(r)).await?;
-            (obj, ri)
-        } else {
-            (obj, None)
-        }
-    } else {
-        state.get_object(&bucket, &key, None).await?
-    };
+    let read = state.get_object(&bucket, &key, parse_range(range)).await?;
+    let obj = &read.object;
 
     let mut builder = Response::builder();
     builder = builder.header("content-type", &obj.content_type);
@@ -268,30 +337,21 @@ async fn object_get_handler(
     for (k, v) in &obj.metadata {
         builder = builder.header(format!("x-amz-meta-{}", k), v);
     }
+    if !obj.tags.is_empty() {
+        builder = builder.header("x-amz-tagging-count", obj.tags.len().to_string());
+    }
+    builder = storage_headers(builder, obj);
 
-    if let Some((start, end, total)) = range_info {
-        let start_idx = start as usize;
-        let end_idx = (end as usize).min(obj.data.len().saturating_sub(1));
-        if start_idx > end_idx || start_idx >= obj.data.len() {
-            return Err(S3Error::InvalidRange(format!(
-                "Range {}-{} not satisfiable for object of size {}",
-                start, end, obj.data.len()
-            )));
-        }
-        let slice = &obj.data[start_idx..=end_idx];
+    if let Some((start, end, total)) = read.range {
         builder = builder.status(StatusCode::PARTIAL_CONTENT);
         builder = builder.header("content-range", format!("bytes {}-{}/{}", start, end, total));
-        builder = builder.header("content-length", slice.len().to_string());
-        Ok(builder
-            .body(axum::body::Body::from(slice.to_vec()))
-            .map_err(|_| S3Error::InternalError("Failed to build response".into()))?)
     } else {
         builder = builder.status(StatusCode::OK);
-        builder = builder.header("content-length", obj.data.len().to_string());
-        Ok(builder
-            .body(axum::body::Body::from(obj.data))
-            .map_err(|_| S3Error::InternalError("Failed to build response".into()))?)
     }
+    builder = builder.header("content-length", read.body.len().to_string());
+    builder
+        .body(stream_body(read.body))
+        .map_err(|_| S3Error::InternalError("Failed to build response".into()))
 }
 
 async fn object_put_handler(
@@ -315,7 +375,7 @@ async fn object_put_handler(
             .parse()
             .map_err(|_| S3Error::InvalidArgument("Invalid partNumber".into()))?;
         let etag = state
-            .upload_part(&bucket, &key, upload_id, part_number, body.to_vec())
+            .upload_part(&bucket, &key, upload_id, part_number, body)
             .await?;
         return Ok((StatusCode::OK, [("etag", etag.as_str())], "").into_response());
     }
@@ -323,26 +383,20 @@ async fn object_put_handler(
     // Check for CopyObject
     if let Some(copy_source) = headers.get("x-amz-copy-source").and_then(|v| v.to_str().ok()) {
         let (src_bucket, src_key) = parse_copy_source(copy_source);
-        let metadata_directive = headers
-            .get("x-amz-metadata-directive")
-            .and_then(|v| v.to_str().ok());
-        let content_type = headers
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .map(String::from);
-        let metadata = extract_metadata(&headers);
+        let options = CopyObjectOptions {
+            metadata_directive: headers
+                .get("x-amz-metadata-directive")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+            content_type: headers
+                .get("content-type")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+            metadata: extract_metadata(&headers),
+            storage_class: storage_class_header(&headers),
+        };
 
-        let result = state
-            .copy_object(
-                &bucket,
-                key,
-                &src_bucket,
-                &src_key,
-                metadata_directive,
-                content_type,
-                metadata,
-            )
-            .await?;
+        let result = state.copy_object(&bucket, key, &src_bucket, &src_key, options).await?;
         return Ok(xml_response(&result));
     }
 
@@ -352,9 +406,13 @@ async fn object_put_handler(
         .and_then(|v| v.to_str().ok())
         .map(String::from);
     let metadata = extract_metadata(&headers);
+    let options = PutObjectOptions {
+        tags: parse_tagging_header(&headers)?,
+        storage_class: storage_class_header(&headers),
+    };
 
     let etag = state
-        .put_object(&bucket, key, body.to_vec(), content_type, metadata)
+        .put_object_with_options(&bucket, key, body, content_type, metadata, options)
         .await?;
     Ok((StatusCode::OK, [("etag", etag.as_str())], "").into_response())
 }
@@ -395,6 +453,7 @@ async fn head_object_handler(
     for (k, v) in &obj.metadata {
         builder = builder.header(format!("x-amz-meta-{}", k), v);
     }
+    builder = storage_headers(builder, &obj);
 
     Ok(builder
         .body(axum::body::Body::empty())
@@ -421,6 +480,25 @@ async fn object_post_handler(
         return Ok(xml_response(&result));
     }
 
+    if params.contains_key("restore") {
+        let req: RestoreRequest = if body.is_empty() {
+            RestoreRequest::default()
+        } else {
+            quick_xml::de::from_reader(body.as_ref())
+                .map_err(|e| S3Error::MalformedXML(format!("Invalid restore XML: {e}")))?
+        };
+        let already_restored = state.restore_object(&bucket, &key, req).await?;
+        let status = if already_restored { StatusCode::OK } else { StatusCode::ACCEPTED };
+        return Ok(status.into_response());
+    }
+
+    if params.contains_key("select") {
+        let req: SelectObjectContentRequest = quick_xml::de::from_reader(body.as_ref())
+            .map_err(|e| S3Error::MalformedXML(format!("Invalid select XML: {e}")))?;
+        let output = state.select_object_content(&bucket, &key, &req).await?;
+        return Ok(select_response(output));
+    }
+
     if let Some(upload_id) = params.get("uploadId") {
         // CompleteMultipartUpload
         let req: CompleteMultipartUploadRequest = quick_xml::de::from_reader(body.as_ref())
@@ -434,6 +512,30 @@ async fn object_post_handler(
     Err(S3Error::InvalidRequest("Unknown POST operation".into()))
 }
 
+/// Frames a SelectObjectContent result as `Records` events followed by
+/// `Stats` and `End`, the event stream the SDKs decode.
+fn select_response(output: SelectOutput) -> Response {
+    let mut body = Vec::new();
+    for chunk in output.records.chunks(MAX_SELECT_RECORDS_PAYLOAD) {
+        let headers = [
+            (":event-type", "Records"),
+            (":content-type", "application/octet-stream"),
+            (":message-type", "event"),
+        ];
+        body.extend(eventstream::encode_message(&headers, chunk));
+    }
+    let stats = SelectStats {
+        bytes_scanned: output.bytes_scanned,
+        bytes_processed: output.bytes_processed,
+        bytes_returned: output.records.len() as u64,
+    };
+    let stats = quick_xml::se::to_string(&stats).unwrap_or_default();
+    let headers = [(":event-type", "Stats"), (":content-type", "text/xml"), (":message-type", "event")];
+    body.extend(eventstream::encode_message(&headers, stats.as_bytes()));
+    body.extend(eventstream::encode_message(&[(":event-type", "End"), (":message-type", "event")], &[]));
+    (StatusCode::OK, [("content-type", eventstream::CONTENT_TYPE)], body).into_response()
+}
+
 async fn bucket_post_handler(
     State(state): State<Arc<S3State>>,
     Path(bucket): Path<String>,
@@ -452,6 +554,91 @@ async fn bucket_post_handler(
     Err(S3Error::InvalidRequest("Unknown POST operation".into()))
 }
 
+// --- CORS ---
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Adds the `Access-Control-*` headers granted by `rule` to a response to
+/// `origin`.
+fn add_cors_headers(headers: &mut HeaderMap, rule: &CorsRule, origin: &str) {
+    let wildcard = rule.allowed_origins.iter().any(|o| o == "*");
+    let mut set = |name: &'static str, value: String| {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    };
+    set("access-control-allow-origin", if wildcard { "*".into() } else { origin.to_string() });
+    set("access-control-allow-methods", rule.allowed_methods.join(", "));
+    if !wildcard {
+        set("access-control-allow-credentials", "true".into());
+    }
+    if !rule.expose_headers.is_empty() {
+        set("access-control-expose-headers", rule.expose_headers.join(", "));
+    }
+    if let Some(max_age) = rule.max_age_seconds {
+        set("access-control-max-age", max_age.to_string());
+    }
+    set("vary", "Origin, Access-Control-Request-Headers, Access-Control-Request-Method".into());
+}
+
+/// Answers an `OPTIONS` preflight against the bucket's CORS rules.
+async fn preflight(state: &S3State, bucket: &str, headers: &HeaderMap) -> Result<Response, S3Error> {
+    let (Some(origin), Some(method)) =
+        (header_str(headers, "origin"), header_str(headers, "access-control-request-method"))
+    else {
+        return Err(S3Error::InvalidRequest(
+            "Insufficient information. Origin request header needed.".into(),
+        ));
+    };
+    let requested: Vec<String> = header_str(headers, "access-control-request-headers")
+        .map(|h| h.split(',').map(str::trim).filter(|h| !h.is_empty()).map(String::from).collect())
+        .unwrap_or_default();
+    let rule = state.cors_preflight(bucket, origin, method, &requested).await?;
+    let mut response = StatusCode::OK.into_response();
+    add_cors_headers(response.headers_mut(), &rule, origin);
+    if !requested.is_empty() {
+        if let Ok(value) = HeaderValue::from_str(&requested.join(", ")) {
+            response.headers_mut().insert("access-control-allow-headers", value);
+        }
+    }
+    Ok(response)
+}
+
+async fn bucket_options_handler(
+    State(state): State<Arc<S3State>>,
+    Path(bucket): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, S3Error> {
+    preflight(&state, &bucket, &headers).await
+}
+
+async fn object_options_handler(
+    State(state): State<Arc<S3State>>,
+    Path((bucket, _key)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Response, S3Error> {
+    preflight(&state, &bucket, &headers).await
+}
+
+/// Adds CORS headers to responses to cross-origin requests that a rule of
+/// the bucket allows. Preflights are answered by the `OPTIONS` handlers.
+async fn cors_middleware(State(state): State<Arc<S3State>>, req: Request, next: Next) -> Response {
+    let origin = header_str(req.headers(), "origin").map(String::from);
+    let (Some(origin), false) = (origin, req.method() == Method::OPTIONS) else {
+        return next.run(req).await;
+    };
+    let method = req.method().as_str().to_string();
+    let bucket = req.uri().path().trim_start_matches('/').split('/').next().unwrap_or_default();
+    let bucket = percent_encoding::percent_decode_str(bucket).decode_utf8_lossy().to_string();
+    let mut response = next.run(req).await;
+    if let Some(rule) = state.cors_rule(&bucket, &origin, &method).await {
+        add_cors_headers(response.headers_mut(), &rule, &origin);
+    }
+    response
+}
+
 pub fn create_router(state: Arc<S3State>) -> Router {
     Router::new()
         .route(
@@ -464,7 +651,8 @@ pub fn create_router(state: Arc<S3State>) -> Router {
                 .put(bucket_put_handler)
                 .delete(bucket_delete_handler)
                 .head(head_bucket_handler)
-                .post(bucket_post_handler),
+                .post(bucket_post_handler)
+                .options(bucket_options_handler),
         )
         .route(
             "/{bucket}/{*key}",
@@ -472,9 +660,12 @@ pub fn create_router(state: Arc<S3State>) -> Router {
                 .put(object_put_handler)
                 .delete(object_delete_handler)
                 .head(head_object_handler)
-                .post(object_post_handler),
+                .post(object_post_handler)
+                .options(object_options_handler),
         )
+        .layer(middleware::from_fn_with_state(state.clone(), cors_middleware))
         .layer(DefaultBodyLimit::max(5 * 1024 * 1024 * 1024)) // 5GB max
+        .merge(crate::healthcheck::router("s3"))
         .with_state(state)
 }
 
@@ -629,6 +820,72 @@ mod tests {
         assert_eq!(&body[..], b"hello world");
     }
 
+    #[tokio::test]
+    async fn test_glacier_object_requires_restore() {
+        let state = new_state();
+        let send = |req: Request<Body>| create_router(state.clone()).oneshot(req);
+        send(Request::builder().method("PUT").uri("/archive").body(Body::empty()).unwrap()).await.unwrap();
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/archive/2020.log")
+            .header("x-amz-storage-class", "GLACIER")
+            .body(Body::from("old data"))
+            .unwrap();
+        assert_eq!(send(req).await.unwrap().status(), StatusCode::OK);
+
+        let get = || Request::builder().method("GET").uri("/archive/2020.log").body(Body::empty()).unwrap();
+        let resp = send(get()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("InvalidObjectState"));
+
+        let head = Request::builder().method("HEAD").uri("/archive/2020.log").body(Body::empty()).unwrap();
+        let resp = send(head).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["x-amz-storage-class"], "GLACIER");
+        assert!(resp.headers().get("x-amz-restore").is_none());
+
+        let restore = || {
+            Request::builder()
+                .method("POST")
+                .uri("/archive/2020.log?restore")
+                .body(Body::from(
+                    "<RestoreRequest><Days>2</Days><GlacierJobParameters><Tier>Standard</Tier></GlacierJobParameters></RestoreRequest>",
+                ))
+                .unwrap()
+        };
+        assert_eq!(send(restore()).await.unwrap().status(), StatusCode::ACCEPTED);
+        assert_eq!(send(restore()).await.unwrap().status(), StatusCode::OK);
+
+        let resp = send(get()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.headers()["x-amz-restore"].to_str().unwrap().starts_with("ongoing-request=\"false\", expiry-date="));
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"old data");
+
+        // Copying the restored copy back to STANDARD makes it permanently readable.
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/archive/2020-standard.log")
+            .header("x-amz-copy-source", "/archive/2020.log")
+            .header("x-amz-storage-class", "STANDARD")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(send(req).await.unwrap().status(), StatusCode::OK);
+        let req = Request::builder().method("POST").uri("/archive/2020-standard.log?restore").body(Body::from(
+            "<RestoreRequest><Days>1</Days></RestoreRequest>",
+        )).unwrap();
+        assert_eq!(send(req).await.unwrap().status(), StatusCode::FORBIDDEN);
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/archive/bad.log")
+            .header("x-amz-storage-class", "COLD")
+            .body(Body::from("x"))
+            .unwrap();
+        assert_eq!(send(req).await.unwrap().status(), StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn test_head_object() {
         let state = new_state();
@@ -936,6 +1193,72 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_put_object_tagging_header_round_trip() {
+        let state = new_state();
+        let app = create_router(state);
+        let req = Request::builder().method("PUT").uri("/bkt").body(Body::empty()).unwrap();
+        app.clone().oneshot(req).await.unwrap();
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/bkt/key")
+            .header("x-amz-tagging", "env=prod&team=data%20eng")
+            .body(Body::from("data"))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+
+        let req = Request::builder().uri("/bkt/key").body(Body::empty()).unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.headers()["x-amz-tagging-count"], "2");
+
+        let req = Request::builder().uri("/bkt/key?tagging").body(Body::empty()).unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let xml = String::from_utf8(body.to_vec()).unwrap();
+        assert!(
+            xml.contains("<TagSet><Tag><Key>env</Key><Value>prod</Value></Tag><Tag><Key>team</Key><Value>data eng</Value></Tag></TagSet>"),
+            "{xml}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_object_tag_limit() {
+        let state = new_state();
+        let app = create_router(state);
+        let req = Request::builder().method("PUT").uri("/bkt").body(Body::empty()).unwrap();
+        app.clone().oneshot(req).await.unwrap();
+
+        let eleven: Vec<String> = (0..11).map(|i| format!("k{i}=v")).collect();
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/bkt/key")
+            .header("x-amz-tagging", eleven.join("&"))
+            .body(Body::from("data"))
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("<Code>InvalidTag</Code>"));
+
+        // Ten tags are fine; PutObjectTagging enforces the same limit.
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/bkt/key")
+            .header("x-amz-tagging", eleven[..10].join("&"))
+            .body(Body::from("data"))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+        let tags: String = (0..11).map(|i| format!("<Tag><Key>k{i}</Key><Value>v</Value></Tag>")).collect();
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/bkt/key?tagging")
+            .body(Body::from(format!("<Tagging><TagSet>{tags}</TagSet></Tagging>")))
+            .unwrap();
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn test_delete_objects_batch() {
         let state = new_state();
@@ -1018,4 +1341,119 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
+
+    /// Splits an event stream into (`:event-type`, payload) pairs.
+    fn decode_events(mut bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut events = Vec::new();
+        while bytes.len() >= 16 {
+            let total = u32::from_be_bytes(bytes[0..4].try_into().unwrap()) as usize;
+            let headers_len = u32::from_be_bytes(bytes[4..8].try_into().unwrap()) as usize;
+            let (mut headers, payload) = bytes[12..total - 4].split_at(headers_len);
+            let mut event_type = String::new();
+            while !headers.is_empty() {
+                let name_len = headers[0] as usize;
+                let name = std::str::from_utf8(&headers[1..1 + name_len]).unwrap();
+                let value_len = u16::from_be_bytes(headers[2 + name_len..4 + name_len].try_into().unwrap()) as usize;
+                let value = &headers[4 + name_len..4 + name_len + value_len];
+                if name == ":event-type" {
+                    event_type = String::from_utf8(value.to_vec()).unwrap();
+                }
+                headers = &headers[4 + name_len + value_len..];
+            }
+            events.push((event_type, payload.to_vec()));
+            bytes = &bytes[total..];
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn test_select_object_content_csv() {
+        let state = new_state();
+        let app = create_router(state);
+        let req = Request::builder().method("PUT").uri("/bkt").body(Body::empty()).unwrap();
+        app.clone().oneshot(req).await.unwrap();
+        let csv = "id,name,score\n1,alice,90\n2,bob,70\n3,carol,85\n";
+        let req = Request::builder().method("PUT").uri("/bkt/scores.csv").body(Body::from(csv)).unwrap();
+        app.clone().oneshot(req).await.unwrap();
+
+        let select = r#"<SelectObjectContentRequest xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+            <Expression>SELECT s.name, s.score FROM S3Object s WHERE CAST(s.score AS INT) &gt;= 80</Expression>
+            <ExpressionType>SQL</ExpressionType>
+            <InputSerialization><CSV><FileHeaderInfo>USE</FileHeaderInfo></CSV><CompressionType>NONE</CompressionType></InputSerialization>
+            <OutputSerialization><CSV/></OutputSerialization>
+        </SelectObjectContentRequest>"#;
+        let req = Request::builder()
+            .method("POST")
+            .uri("/bkt/scores.csv?select&select-type=2")
+            .body(Body::from(select))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], crate::eventstream::CONTENT_TYPE);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+
+        let events = decode_events(&body);
+        let types: Vec<&str> = events.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(types, ["Records", "Stats", "End"]);
+        assert_eq!(String::from_utf8(events[0].1.clone()).unwrap(), "alice,90\ncarol,85\n");
+        let stats = String::from_utf8(events[1].1.clone()).unwrap();
+        assert!(stats.contains(&format!("<BytesScanned>{}</BytesScanned>", csv.len())), "{stats}");
+        assert!(stats.contains("<BytesReturned>18</BytesReturned>"), "{stats}");
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_and_object_response_headers() {
+        let state = new_state();
+        let send = |req: Request<Body>| create_router(state.clone()).oneshot(req);
+        send(Request::builder().method("PUT").uri("/bkt").body(Body::empty()).unwrap()).await.unwrap();
+        send(Request::builder().method("PUT").uri("/bkt/key").body(Body::from("data")).unwrap()).await.unwrap();
+
+        let cors_xml = r#"<CORSConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/"><CORSRule><AllowedOrigin>https://*.example.com</AllowedOrigin><AllowedMethod>GET</AllowedMethod><AllowedMethod>PUT</AllowedMethod><AllowedHeader>Content-*</AllowedHeader><ExposeHeader>ETag</ExposeHeader><MaxAgeSeconds>3000</MaxAgeSeconds></CORSRule></CORSConfiguration>"#;
+        let resp = send(Request::builder().method("PUT").uri("/bkt?cors").body(Body::from(cors_xml)).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = send(Request::builder().uri("/bkt?cors").body(Body::empty()).unwrap()).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("<AllowedOrigin>https://*.example.com</AllowedOrigin>"));
+
+        let preflight = |origin: &'static str| {
+            Request::builder()
+                .method("OPTIONS")
+                .uri("/bkt/key")
+                .header("origin", origin)
+                .header("access-control-request-method", "PUT")
+                .header("access-control-request-headers", "content-type")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let resp = send(preflight("https://app.example.com")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let header = |name: &str| resp.headers().get(name).unwrap().to_str().unwrap().to_string();
+        assert_eq!(header("access-control-allow-origin"), "https://app.example.com");
+        assert_eq!(header("access-control-allow-methods"), "GET, PUT");
+        assert_eq!(header("access-control-allow-headers"), "content-type");
+        assert_eq!(header("access-control-max-age"), "3000");
+
+        let resp = send(preflight("https://evil.test")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert!(resp.headers().get("access-control-allow-origin").is_none());
+
+        // Actual requests from an allowed origin carry the headers too.
+        let resp = send(
+            Request::builder().uri("/bkt/key").header("origin", "https://app.example.com").body(Body::empty()).unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["access-control-allow-origin"], "https://app.example.com");
+        assert_eq!(resp.headers()["access-control-expose-headers"], "ETag");
+
+        let resp = send(Request::builder().method("DELETE").uri("/bkt?cors").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let resp = send(preflight("https://app.example.com")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use axum::body::Bytes;
+use chrono::{Duration, Utc};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::s3::bucket::*;
 use crate::s3::error::S3Error;
+use crate::s3::select::{select, SelectOutput};
 use crate::s3::types::*;
 
 struct S3StateInner {
@@ -29,6 +32,24 @@ impl S3State {
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// ARNs and tags of every bucket that carries at least one tag.
+    pub async fn tagged_resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let state = self.inner.lock().await;
+        state
+            .buckets
+            .values()
+            .filter(|bucket| !bucket.tags.is_empty())
+            .map(|bucket| (format!("arn:aws:s3:::{}", bucket.name), bucket.tags.clone()))
+            .collect()
+    }
+
     // --- Bucket operations ---
 
     pub async fn create_bucket(
@@ -173,23 +194,128 @@ impl S3State {
         Ok(())
     }
 
+    // --- CORS ---
+
+    pub async fn put_bucket_cors(&self, name: &str, config: CorsConfiguration) -> Result<(), S3Error> {
+        if config.rules.is_empty() || config.rules.len() > MAX_CORS_RULES {
+            return Err(S3Error::MalformedXML(format!(
+                "A CORS configuration must have between 1 and {MAX_CORS_RULES} rules"
+            )));
+        }
+        for rule in &config.rules {
+            if rule.allowed_origins.is_empty() || rule.allowed_methods.is_empty() {
+                return Err(S3Error::MalformedXML(
+                    "Each CORSRule must have at least one AllowedOrigin and one AllowedMethod".into(),
+                ));
+            }
+            if let Some(method) = rule.allowed_methods.iter().find(|m| !CORS_METHODS.contains(&m.as_str())) {
+                return Err(S3Error::InvalidRequest(format!(
+                    "Found unsupported HTTP method in CORS config. Unsupported method is {method}"
+                )));
+            }
+            if rule.allowed_origins.iter().chain(&rule.allowed_headers).any(|v| v.matches('*').count() > 1) {
+                return Err(S3Error::InvalidRequest(
+                    "AllowedOrigin and AllowedHeader can have at most one * wildcard".into(),
+                ));
+            }
+        }
+        let mut inner = self.inner.lock().await;
+        let bucket = inner.buckets.get_mut(name).ok_or_else(|| {
+            S3Error::NoSuchBucket(format!("The specified bucket does not exist: {name}"))
+        })?;
+        bucket.cors = Some(config);
+        Ok(())
+    }
+
+    pub async fn get_bucket_cors(&self, name: &str) -> Result<CorsConfiguration, S3Error> {
+        let inner = self.inner.lock().await;
+        let bucket = inner.buckets.get(name).ok_or_else(|| {
+            S3Error::NoSuchBucket(format!("The specified bucket does not exist: {name}"))
+        })?;
+        bucket
+            .cors
+            .clone()
+            .ok_or_else(|| S3Error::NoSuchCORSConfiguration("The CORS configuration does not exist".into()))
+    }
+
+    pub async fn delete_bucket_cors(&self, name: &str) -> Result<(), S3Error> {
+        let mut inner = self.inner.lock().await;
+        let bucket = inner.buckets.get_mut(name).ok_or_else(|| {
+            S3Error::NoSuchBucket(format!("The specified bucket does not exist: {name}"))
+        })?;
+        bucket.cors = None;
+        Ok(())
+    }
+
+    /// Answers a preflight request: the rule allowing `method` from `origin`
+    /// with the request `headers`, or `AccessForbidden`.
+    pub async fn cors_preflight(
+        &self,
+        name: &str,
+        origin: &str,
+        method: &str,
+        headers: &[String],
+    ) -> Result<CorsRule, S3Error> {
+        let inner = self.inner.lock().await;
+        let bucket = inner.buckets.get(name).ok_or_else(|| {
+            S3Error::NoSuchBucket(format!("The specified bucket does not exist: {name}"))
+        })?;
+        if bucket.cors.is_none() {
+            return Err(S3Error::AccessForbidden("CORSResponse: CORS is not enabled for this bucket.".into()));
+        }
+        bucket.cors_rule(origin, method, headers).cloned().ok_or_else(|| {
+            S3Error::AccessForbidden(
+                "CORSResponse: This CORS request is not allowed. This is usually because the evaluation of Origin, \
+                 request method / Access-Control-Request-Method or Access-Control-Request-Headers are not \
+                 whitelisted by the resource's CORS spec."
+                    .into(),
+            )
+        })
+    }
+
+    /// The rule that applies to an actual (non-preflight) request, if any.
+    pub async fn cors_rule(&self, name: &str, origin: &str, method: &str) -> Option<CorsRule> {
+        let inner = self.inner.lock().await;
+        inner.buckets.get(name)?.cors_rule(origin, method, &[]).cloned()
+    }
+
     // --- Object operations ---
 
     pub async fn put_object(
         &self,
         bucket_name: &str,
         key: String,
-        data: Vec<u8>,
+        data: impl Into<Bytes>,
+        content_type: Option<String>,
+        metadata: HashMap<String, String>,
+    ) -> Result<String, S3Error> {
+        self.put_object_with_options(bucket_name, key, data, content_type, metadata, PutObjectOptions::default())
+            .await
+    }
+
+    /// `PutObject` with the settings of its `x-amz-tagging` and
+    /// `x-amz-storage-class` headers.
+    pub async fn put_object_with_options(
+        &self,
+        bucket_name: &str,
+        key: String,
+        data: impl Into<Bytes>,
         content_type: Option<String>,
         metadata: HashMap<String, String>,
+        options: PutObjectOptions,
     ) -> Result<String, S3Error> {
+        let PutObjectOptions { tags, storage_class } = options;
+        validate_object_tags(&tags)?;
+        let storage_class = validate_storage_class(storage_class)?;
         let mut inner = self.inner.lock().await;
         let bucket = inner.buckets.get_mut(bucket_name).ok_or_else(|| {
             S3Error::NoSuchBucket(format!(
                 "The specified bucket does not exist: {bucket_name}"
             ))
         })?;
-        let obj = Object::new(key.clone(), data, content_type, metadata);
+        let mut obj = Object::new(key.clone(), data, content_type, metadata);
+        obj.tags = tags;
+        obj.storage_class = storage_class;
         let etag = obj.etag.clone();
         bucket.objects.insert(key, obj);
         Ok(etag)
@@ -199,8 +325,8 @@ impl S3State {
         &self,
         bucket_name: &str,
         key: &str,
-        range: Option<(u64, Option<u64>)>,
-    ) -> Result<(Object, Option<(u64, u64, u64)>), S3Error> {
+        range: Option<ByteRange>,
+    ) -> Result<ObjectRead, S3Error> {
         let inner = self.inner.lock().await;
         let bucket = inner.buckets.get(bucket_name).ok_or_else(|| {
             S3Error::NoSuchBucket(format!(
@@ -210,17 +336,43 @@ impl S3State {
         let obj = bucket.objects.get(key).ok_or_else(|| {
             S3Error::NoSuchKey(format!("The specified key does not exist: {key}"))
         })?;
+        check_readable(obj)?;
 
-        if let Some((start, end)) = range {
-            let total = obj.data.len() as u64;
-            let end = end.unwrap_or(total - 1).min(total - 1);
-            if start >= total {
-                return Err(S3Error::InvalidRange("Range not satisfiable".into()));
+        let Some(range) = range else {
+            return Ok(ObjectRead {
+                object: obj.clone(),
+                body: obj.data.clone(),
+                range: None,
+            });
+        };
+        let total = obj.data.len() as u64;
+        let (start, end) = match range {
+            ByteRange::FromTo(start, end) => {
+                (start, end.unwrap_or(u64::MAX).min(total.saturating_sub(1)))
             }
-            Ok((obj.clone(), Some((start, end, total))))
-        } else {
-            Ok((obj.clone(), None))
+            ByteRange::Suffix(n) if n > 0 => (total.saturating_sub(n), total.saturating_sub(1)),
+            ByteRange::Suffix(_) => (total, total),
+        };
+        if start >= total || start > end {
+            return Err(S3Error::InvalidRange(format!(
+                "Range {start}-{end} not satisfiable for object of size {total}"
+            )));
         }
+        Ok(ObjectRead {
+            object: obj.clone(),
+            body: obj.data.slice(start as usize..=end as usize),
+            range: Some((start, end, total)),
+        })
+    }
+
+    pub async fn select_object_content(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        req: &SelectObjectContentRequest,
+    ) -> Result<SelectOutput, S3Error> {
+        let read = self.get_object(bucket_name, key, None).await?;
+        select(&read.body, req)
     }
 
     pub async fn head_object(
@@ -257,10 +409,9 @@ impl S3State {
         dest_key: String,
         source_bucket: &str,
         source_key: &str,
-        metadata_directive: Option<&str>,
-        content_type: Option<String>,
-        metadata: HashMap<String, String>,
+        options: CopyObjectOptions,
     ) -> Result<CopyObjectResult, S3Error> {
+        let storage_class = validate_storage_class(options.storage_class)?;
         let mut inner = self.inner.lock().await;
 
         let src_obj = {
@@ -275,14 +426,16 @@ impl S3State {
                 ))
             })?.clone()
         };
+        check_readable(&src_obj)?;
 
-        let (new_ct, new_meta) = if metadata_directive == Some("REPLACE") {
-            (content_type.unwrap_or(src_obj.content_type.clone()), metadata)
+        let (new_ct, new_meta) = if options.metadata_directive.as_deref() == Some("REPLACE") {
+            (options.content_type.unwrap_or(src_obj.content_type.clone()), options.metadata)
         } else {
             (src_obj.content_type.clone(), src_obj.metadata.clone())
         };
 
-        let new_obj = Object::new(dest_key.clone(), src_obj.data.clone(), Some(new_ct), new_meta);
+        let mut new_obj = Object::new(dest_key.clone(), src_obj.data.clone(), Some(new_ct), new_meta);
+        new_obj.storage_class = storage_class;
         let result = CopyObjectResult {
             etag: new_obj.etag.clone(),
             last_modified: new_obj.last_modified.clone(),
@@ -297,6 +450,40 @@ impl S3State {
         Ok(result)
     }
 
+    /// Makes a temporary copy of an archived object readable for `days`.
+    /// Restores complete immediately. Returns whether a restored copy
+    /// already existed, in which case only its expiry is changed.
+    pub async fn restore_object(&self, bucket_name: &str, key: &str, req: RestoreRequest) -> Result<bool, S3Error> {
+        let days = req.days.ok_or_else(|| {
+            S3Error::MalformedXML("RestoreRequest must contain Days for an archived object".into())
+        })?;
+        if days < 1 {
+            return Err(S3Error::InvalidArgument(format!("Days must be at least 1, got {days}")));
+        }
+        if let Some(tier) = req.glacier_job_parameters.and_then(|p| p.tier) {
+            if !["Standard", "Bulk", "Expedited"].contains(&tier.as_str()) {
+                return Err(S3Error::MalformedXML(format!("Invalid Tier: {tier}")));
+            }
+        }
+        let mut inner = self.inner.lock().await;
+        let bucket = inner.buckets.get_mut(bucket_name).ok_or_else(|| {
+            S3Error::NoSuchBucket(format!(
+                "The specified bucket does not exist: {bucket_name}"
+            ))
+        })?;
+        let obj = bucket.objects.get_mut(key).ok_or_else(|| {
+            S3Error::NoSuchKey(format!("The specified key does not exist: {key}"))
+        })?;
+        if !obj.is_archived() {
+            return Err(S3Error::InvalidObjectState(
+                "Restore is not allowed for the object's current storage class".into(),
+            ));
+        }
+        let already_restored = obj.restored_until().is_some();
+        obj.restore_expiry = Some(Utc::now() + Duration::days(days));
+        Ok(already_restored)
+    }
+
     pub async fn list_objects_v2(
         &self,
         bucket_name: &str,
@@ -433,6 +620,7 @@ impl S3State {
         key: &str,
         tags: HashMap<String, String>,
     ) -> Result<(), S3Error> {
+        validate_object_tags(&tags)?;
         let mut inner = self.inner.lock().await;
         let bucket = inner.buckets.get_mut(bucket_name).ok_or_else(|| {
             S3Error::NoSuchBucket(format!(
@@ -506,7 +694,7 @@ impl S3State {
         key: &str,
         upload_id: &str,
         part_number: i32,
-        data: Vec<u8>,
+        data: impl Into<Bytes>,
     ) -> Result<String, S3Error> {
         let mut inner = self.inner.lock().await;
         let bucket = inner.buckets.get_mut(bucket_name).ok_or_else(|| {
@@ -528,6 +716,7 @@ impl S3State {
             return Err(S3Error::InvalidArgument("Key does not match upload".into()));
         }
 
+        let data: Bytes = data.into();
         let etag = etag_quoted(&data);
         let size = data.len();
         upload.parts.insert(
@@ -739,6 +928,53 @@ impl S3State {
     }
 }
 
+fn validate_storage_class(storage_class: Option<String>) -> Result<String, S3Error> {
+    match storage_class {
+        None => Ok("STANDARD".into()),
+        Some(class) if STORAGE_CLASSES.contains(&class.as_str()) => Ok(class),
+        Some(class) => Err(S3Error::InvalidStorageClass(format!(
+            "The storage class you specified is not valid: {class}"
+        ))),
+    }
+}
+
+/// Archived objects can only be read through a restored copy.
+fn check_readable(obj: &Object) -> Result<(), S3Error> {
+    if obj.is_readable() {
+        Ok(())
+    } else {
+        Err(S3Error::InvalidObjectState(
+            "The operation is not valid for the object's storage class".into(),
+        ))
+    }
+}
+
+/// The most tags an object may carry.
+const MAX_OBJECT_TAGS: usize = 10;
+
+/// The most rules a bucket CORS configuration may hold.
+const MAX_CORS_RULES: usize = 100;
+
+/// Methods an `AllowedMethod` may name.
+const CORS_METHODS: [&str; 5] = ["GET", "PUT", "POST", "DELETE", "HEAD"];
+
+fn validate_object_tags(tags: &HashMap<String, String>) -> Result<(), S3Error> {
+    if tags.len() > MAX_OBJECT_TAGS {
+        return Err(S3Error::InvalidTag(format!(
+            "Object tags cannot be greater than {MAX_OBJECT_TAGS}"
+        )));
+    }
+    for (key, value) in tags {
+        if key.is_empty() || key.chars().count() > 128 {
+            return Err(S3Error::InvalidTag("The TagKey you have provided is invalid".into()));
+        }
+        if value.chars().count() > 256 {
+            return Err(S3Error::InvalidTag("The TagValue you have provided is invalid".into()));
+        }
+    }
+    Ok(())
+}
+
 fn md5_raw(data: &[u8]) -> Vec<u8> {
     use md5::{Digest, Md5};
     let mut hasher = Md5::new();
@@ -871,8 +1107,9 @@ mod tests {
 
         let result = state.get_object("obj-bucket", "key1", None).await;
         assert!(result.is_ok());
-        let (obj, _range) = result.unwrap();
-        assert_eq!(obj.data, b"hello world");
+        let read = result.unwrap();
+        assert_eq!(read.body, &b"hello world"[..]);
+        assert_eq!(read.object.data, read.body);
     }
 
     #[tokio::test]
@@ -882,6 +1119,35 @@ mod tests {
         assert!(state.get_object("obj-bucket", "nope", None).await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_ranged_get_of_large_object_does_not_copy_body() {
+        let state = make_state();
+        state.create_bucket("big".to_string(), None).await.unwrap();
+        let data: Vec<u8> = (0..64 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        state.put_object("big", "blob".to_string(), data, None, HashMap::new()).await.unwrap();
+        let stored = state.get_object("big", "blob", None).await.unwrap().body;
+
+        // Many ranged reads of a 64 MiB object: each must be a view into the
+        // stored body, never a fresh allocation of it.
+        for i in 0..1000u64 {
+            let start = i * 65_536;
+            let read = state
+                .get_object("big", "blob", Some(ByteRange::FromTo(start, Some(start + 99))))
+                .await
+                .unwrap();
+            assert_eq!(read.range, Some((start, start + 99, 64 * 1024 * 1024)));
+            assert_eq!(read.body.len(), 100);
+            assert_eq!(read.body.as_ptr(), stored[start as usize..].as_ptr());
+        }
+
+        let tail = state.get_object("big", "blob", Some(ByteRange::Suffix(10))).await.unwrap();
+        assert_eq!(tail.body.as_ptr(), stored[stored.len() - 10..].as_ptr());
+        assert!(matches!(
+            state.get_object("big", "blob", Some(ByteRange::FromTo(64 * 1024 * 1024, None))).await,
+            Err(S3Error::InvalidRange(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_head_object() {
         let state = make_state();
@@ -913,7 +1179,9 @@ mod tests {
         let state = make_state();
         state.create_bucket("src-bucket".to_string(), None).await.unwrap();
         state.put_object("src-bucket", "key1".to_string(), b"data".to_vec(), None, HashMap::new()).await.unwrap();
-        let result = state.copy_object("src-bucket", "key2".to_string(), "src-bucket", "key1", None, None, HashMap::new()).await;
+        let result = state
+            .copy_object("src-bucket", "key2".to_string(), "src-bucket", "key1", CopyObjectOptions::default())
+            .await;
         assert!(result.is_ok());
     }
 
@@ -146,6 +146,31 @@ pub struct DeleteErrorEntry {
     pub message: String,
 }
 
+// --- CORS ---
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename = "CORSConfiguration")]
+pub struct CorsConfiguration {
+    #[serde(rename = "CORSRule", default)]
+    pub rules: Vec<CorsRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorsRule {
+    #[serde(rename = "ID", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "AllowedHeader", default)]
+    pub allowed_headers: Vec<String>,
+    #[serde(rename = "AllowedMethod", default)]
+    pub allowed_methods: Vec<String>,
+    #[serde(rename = "AllowedOrigin", default)]
+    pub allowed_origins: Vec<String>,
+    #[serde(rename = "ExposeHeader", default)]
+    pub expose_headers: Vec<String>,
+    #[serde(rename = "MaxAgeSeconds", default, skip_serializing_if = "Option::is_none")]
+    pub max_age_seconds: Option<u32>,
+}
+
 // --- Tagging ---
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -292,3 +317,107 @@ pub struct CreateBucketConfiguration {
     #[serde(rename = "LocationConstraint", default)]
     pub location_constraint: Option<String>,
 }
+
+// --- RestoreObject ---
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename = "RestoreRequest")]
+pub struct RestoreRequest {
+    #[serde(rename = "Days", default)]
+    pub days: Option<i64>,
+    #[serde(rename = "GlacierJobParameters", default)]
+    pub glacier_job_parameters: Option<GlacierJobParameters>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct GlacierJobParameters {
+    #[serde(rename = "Tier", default)]
+    pub tier: Option<String>,
+}
+
+// --- SelectObjectContent ---
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename = "SelectObjectContentRequest")]
+pub struct SelectObjectContentRequest {
+    #[serde(rename = "Expression")]
+    pub expression: String,
+    #[serde(rename = "ExpressionType", default)]
+    pub expression_type: Option<String>,
+    #[serde(rename = "InputSerialization", default)]
+    pub input_serialization: InputSerialization,
+    #[serde(rename = "OutputSerialization", default)]
+    pub output_serialization: OutputSerialization,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct InputSerialization {
+    #[serde(rename = "CSV", default)]
+    pub csv: Option<CsvInput>,
+    #[serde(rename = "JSON", default)]
+    pub json: Option<JsonInput>,
+    #[serde(rename = "Parquet", default)]
+    pub parquet: Option<ParquetInput>,
+    #[serde(rename = "CompressionType", default)]
+    pub compression_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct CsvInput {
+    #[serde(rename = "FileHeaderInfo", default)]
+    pub file_header_info: Option<String>,
+    #[serde(rename = "FieldDelimiter", default)]
+    pub field_delimiter: Option<String>,
+    #[serde(rename = "RecordDelimiter", default)]
+    pub record_delimiter: Option<String>,
+    #[serde(rename = "QuoteCharacter", default)]
+    pub quote_character: Option<String>,
+    #[serde(rename = "Comments", default)]
+    pub comments: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JsonInput {
+    #[serde(rename = "Type", default)]
+    pub json_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ParquetInput {}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct OutputSerialization {
+    #[serde(rename = "CSV", default)]
+    pub csv: Option<CsvOutput>,
+    #[serde(rename = "JSON", default)]
+    pub json: Option<JsonOutput>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct CsvOutput {
+    #[serde(rename = "FieldDelimiter", default)]
+    pub field_delimiter: Option<String>,
+    #[serde(rename = "RecordDelimiter", default)]
+    pub record_delimiter: Option<String>,
+    #[serde(rename = "QuoteCharacter", default)]
+    pub quote_character: Option<String>,
+    #[serde(rename = "QuoteFields", default)]
+    pub quote_fields: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JsonOutput {
+    #[serde(rename = "RecordDelimiter", default)]
+    pub record_delimiter: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename = "Stats")]
+pub struct SelectStats {
+    #[serde(rename = "BytesScanned")]
+    pub bytes_scanned: u64,
+    #[serde(rename = "BytesProcessed")]
+    pub bytes_processed: u64,
+    #[serde(rename = "BytesReturned")]
+    pub bytes_returned: u64,
+}
@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum SecretsManagerError {
@@ -8,6 +9,8 @@ pub enum SecretsManagerError {
     ResourceExistsException(String),
     InvalidParameterException(String),
     InvalidRequestException(String),
+    MalformedPolicyDocumentException(String),
+    PublicPolicyException(String),
     InvalidAction(String),
 }
 
@@ -18,6 +21,10 @@ impl SecretsManagerError {
             SecretsManagerError::ResourceExistsException(_) => "ResourceExistsException",
             SecretsManagerError::InvalidParameterException(_) => "InvalidParameterException",
             SecretsManagerError::InvalidRequestException(_) => "InvalidRequestException",
+            SecretsManagerError::MalformedPolicyDocumentException(_) => {
+                "MalformedPolicyDocumentException"
+            }
+            SecretsManagerError::PublicPolicyException(_) => "PublicPolicyException",
             SecretsManagerError::InvalidAction(_) => "InvalidAction",
         }
     }
@@ -28,6 +35,8 @@ impl SecretsManagerError {
             SecretsManagerError::ResourceExistsException(_) => StatusCode::BAD_REQUEST,
             SecretsManagerError::InvalidParameterException(_) => StatusCode::BAD_REQUEST,
             SecretsManagerError::InvalidRequestException(_) => StatusCode::BAD_REQUEST,
+            SecretsManagerError::MalformedPolicyDocumentException(_) => StatusCode::BAD_REQUEST,
+            SecretsManagerError::PublicPolicyException(_) => StatusCode::BAD_REQUEST,
             SecretsManagerError::InvalidAction(_) => StatusCode::BAD_REQUEST,
         }
     }
@@ -38,18 +47,30 @@ impl SecretsManagerError {
             | SecretsManagerError::ResourceExistsException(m)
             | SecretsManagerError::InvalidParameterException(m)
             | SecretsManagerError::InvalidRequestException(m)
+            | SecretsManagerError::MalformedPolicyDocumentException(m)
+            | SecretsManagerError::PublicPolicyException(m)
             | SecretsManagerError::InvalidAction(m) => m,
         }
     }
 }
 
+impl AwsError for SecretsManagerError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        SecretsManagerError::message(self)
+    }
+}
+
 impl IntoResponse for SecretsManagerError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "Message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -78,6 +99,13 @@ mod tests {
         assert_eq!(err.error_code(), "InvalidRequestException");
     }
     #[test]
+    fn test_policy_error_codes() {
+        let err = SecretsManagerError::MalformedPolicyDocumentException("test".to_string());
+        assert_eq!(err.error_code(), "MalformedPolicyDocumentException");
+        let err = SecretsManagerError::PublicPolicyException("test".to_string());
+        assert_eq!(err.error_code(), "PublicPolicyException");
+    }
+    #[test]
     fn test_invalidaction_error_code() {
         let err = SecretsManagerError::InvalidAction("test".to_string());
         assert_eq!(err.error_code(), "InvalidAction");
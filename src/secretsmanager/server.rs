@@ -54,6 +54,22 @@ async fn handle_request(
         "RestoreSecret" => dispatch!(state, body, RestoreSecretRequest, restore_secret),
         "TagResource" => dispatch_empty!(state, body, TagResourceRequest, tag_resource),
         "UntagResource" => dispatch_empty!(state, body, UntagResourceRequest, untag_resource),
+        "RotateSecret" => dispatch!(state, body, RotateSecretRequest, rotate_secret),
+        "GetRandomPassword" => {
+            dispatch!(state, body, GetRandomPasswordRequest, get_random_password)
+        }
+        "PutResourcePolicy" => {
+            dispatch!(state, body, PutResourcePolicyRequest, put_resource_policy)
+        }
+        "GetResourcePolicy" => {
+            dispatch!(state, body, GetResourcePolicyRequest, get_resource_policy)
+        }
+        "DeleteResourcePolicy" => {
+            dispatch!(state, body, DeleteResourcePolicyRequest, delete_resource_policy)
+        }
+        "ValidateResourcePolicy" => {
+            dispatch!(state, body, ValidateResourcePolicyRequest, validate_resource_policy)
+        }
         "ListSecretVersionIds" => {
             dispatch!(state, body, ListSecretVersionIdsRequest, list_secret_version_ids)
         }
@@ -66,6 +82,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<SecretsManagerState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("secretsmanager"))
         .with_state(state)
 }
 
@@ -77,6 +94,60 @@ mod tests {
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
 
+    #[tokio::test]
+    async fn test_rotation_with_stubbed_lambda_through_routers() {
+        let lambda = Arc::new(crate::lambda::state::LambdaState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        ));
+        let lambda_app = crate::lambda::server::create_router(lambda.clone());
+        crate::lambda::server::create_stubbed_function(&lambda_app, "rotator", serde_json::json!({ "mode": "echo" }))
+            .await;
+        let bus = Arc::new(crate::bus::ServiceBus::new());
+        bus.attach_lambda(lambda);
+        let state = SecretsManagerState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let app = create_router(Arc::new(state));
+        let call = |action: &str, body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/x-amz-json-1.1")
+                .header("x-amz-target", format!("secretsmanager.{action}"))
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let token = "11111111-2222-3333-4444-555555555555";
+        for (action, body) in [
+            ("CreateSecret", serde_json::json!({ "Name": "db", "SecretString": "old" })),
+            // The stub cannot write the new value, so the client stages it.
+            (
+                "PutSecretValue",
+                serde_json::json!({
+                    "SecretId": "db",
+                    "SecretString": "new",
+                    "ClientRequestToken": token,
+                    "VersionStages": ["AWSPENDING"],
+                }),
+            ),
+            (
+                "RotateSecret",
+                serde_json::json!({
+                    "SecretId": "db",
+                    "RotationLambdaARN": "arn:aws:lambda:us-east-1:123456789012:function:rotator",
+                    "ClientRequestToken": token,
+                }),
+            ),
+        ] {
+            let resp = app.clone().oneshot(call(action, body)).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK, "{action}");
+        }
+        let resp = app.oneshot(call("GetSecretValue", serde_json::json!({ "SecretId": "db" }))).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["SecretString"], "new");
+        assert_eq!(body["VersionId"], token);
+    }
+
     #[tokio::test]
     async fn test_missing_target_header() {
         let state = Arc::new(SecretsManagerState::new("123456789012".to_string(), "us-east-1".to_string()));
@@ -6,6 +6,111 @@ use uuid::Uuid;
 
 use super::error::SecretsManagerError;
 use super::types::*;
+use crate::bus::ServiceBus;
+
+const ROTATION_STEPS: [&str; 4] = ["createSecret", "setSecret", "testSecret", "finishSecret"];
+
+const PUNCTUATION: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+/// Builds a password from the character classes the request leaves
+/// enabled, with at least one character of each class unless
+/// `RequireEachIncludedType` is false.
+fn generate_password(req: &GetRandomPasswordRequest) -> Result<String, SecretsManagerError> {
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+
+    let length = req.password_length.unwrap_or(32);
+    if !(1..=4096).contains(&length) {
+        return Err(SecretsManagerError::InvalidParameterException(
+            "PasswordLength must be between 1 and 4096".to_string(),
+        ));
+    }
+    let exclude = req.exclude_characters.as_deref().unwrap_or_default();
+    let mut classes: Vec<Vec<char>> = Vec::new();
+    let mut add_class = |enabled: bool, chars: &str| {
+        let chars: Vec<char> = chars.chars().filter(|c| !exclude.contains(*c)).collect();
+        if enabled && !chars.is_empty() {
+            classes.push(chars);
+        }
+    };
+    add_class(!req.exclude_lowercase.unwrap_or(false), "abcdefghijklmnopqrstuvwxyz");
+    add_class(!req.exclude_uppercase.unwrap_or(false), "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+    add_class(!req.exclude_numbers.unwrap_or(false), "0123456789");
+    add_class(!req.exclude_punctuation.unwrap_or(false), PUNCTUATION);
+    add_class(req.include_space.unwrap_or(false), " ");
+    if classes.is_empty() {
+        return Err(SecretsManagerError::InvalidParameterException(
+            "The request excludes every character type".to_string(),
+        ));
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut password: Vec<char> = Vec::with_capacity(length);
+    if req.require_each_included_type.unwrap_or(true) {
+        if length < classes.len() {
+            return Err(SecretsManagerError::InvalidParameterException(format!(
+                "PasswordLength must be at least {} to include each character type",
+                classes.len()
+            )));
+        }
+        for class in &classes {
+            password.push(class[rng.gen_range(0..class.len())]);
+        }
+    }
+    let pool: Vec<char> = classes.concat();
+    while password.len() < length {
+        password.push(pool[rng.gen_range(0..pool.len())]);
+    }
+    password.shuffle(&mut rng);
+    Ok(password.into_iter().collect())
+}
+
+/// Structural checks for a resource policy document, in the shape
+/// returned by `ValidateResourcePolicy`.
+fn policy_errors(document: &str) -> Vec<ValidationErrorsEntry> {
+    let error = |check: &str, message: &str| ValidationErrorsEntry {
+        check_name: check.to_string(),
+        error_message: message.to_string(),
+    };
+    let policy: serde_json::Value = match serde_json::from_str(document) {
+        Ok(p) => p,
+        Err(e) => return vec![error("SYNTAX_CHECKS", &format!("Invalid JSON: {e}"))],
+    };
+    let statements = match &policy["Statement"] {
+        serde_json::Value::Array(list) => list.clone(),
+        serde_json::Value::Object(_) => vec![policy["Statement"].clone()],
+        _ => return vec![error("SYNTAX_CHECKS", "Policy must contain a Statement")],
+    };
+    let mut errors = Vec::new();
+    for statement in &statements {
+        if !matches!(statement["Effect"].as_str(), Some("Allow") | Some("Deny")) {
+            errors.push(error("SYNTAX_CHECKS", "Statement Effect must be Allow or Deny"));
+        }
+        if statement.get("Principal").is_none() {
+            errors.push(error("SYNTAX_CHECKS", "Statement must specify a Principal"));
+        }
+        if statement.get("Action").is_none() {
+            errors.push(error("SYNTAX_CHECKS", "Statement must specify an Action"));
+        }
+    }
+    errors
+}
+
+/// Whether the policy allows access to any principal (`"*"`).
+fn is_public_policy(document: &str) -> bool {
+    let Ok(policy) = serde_json::from_str::<serde_json::Value>(document) else {
+        return false;
+    };
+    let statements = match &policy["Statement"] {
+        serde_json::Value::Array(list) => list.clone(),
+        other => vec![other.clone()],
+    };
+    statements.iter().any(|s| {
+        s["Effect"] == "Allow"
+            && (s["Principal"] == "*" || s["Principal"]["AWS"] == "*")
+            && s.get("Condition").is_none()
+    })
+}
 
 struct SecretVersion {
     version_id: String,
@@ -26,6 +131,70 @@ struct Secret {
     created_date: f64,
     last_changed_date: f64,
     deleted: bool,
+    rotation_enabled: bool,
+    rotation_lambda_arn: Option<String>,
+    rotation_rules: Option<RotationRules>,
+    last_rotated_date: Option<f64>,
+    next_rotation_date: Option<f64>,
+    resource_policy: Option<String>,
+}
+
+impl Secret {
+    /// Attaches `stage` to `version_id`, detaching it from every other
+    /// version. Moving `AWSCURRENT` marks the old current version
+    /// `AWSPREVIOUS`.
+    fn move_stage(&mut self, stage: &str, version_id: &str) {
+        let previous = self
+            .versions
+            .iter()
+            .find(|v| v.version_stages.iter().any(|s| s == stage))
+            .map(|v| v.version_id.clone());
+        for v in self.versions.iter_mut() {
+            if v.version_id != version_id {
+                v.version_stages.retain(|s| s != stage);
+            } else if !v.version_stages.iter().any(|s| s == stage) {
+                v.version_stages.push(stage.to_string());
+            }
+        }
+        if stage == "AWSCURRENT" {
+            self.current_version_id = version_id.to_string();
+            if let Some(previous) = previous.filter(|p| p != version_id) {
+                self.move_stage("AWSPREVIOUS", &previous);
+            }
+        }
+    }
+}
+
+/// Seconds until the next scheduled rotation, from `AutomaticallyAfterDays`
+/// or a `rate(N days|hours)` schedule expression.
+fn rotation_interval(rules: &RotationRules) -> Result<Option<f64>, SecretsManagerError> {
+    if let Some(days) = rules.automatically_after_days {
+        if !(1..=1000).contains(&days) {
+            return Err(SecretsManagerError::InvalidParameterException(
+                "AutomaticallyAfterDays must be between 1 and 1000".to_string(),
+            ));
+        }
+        return Ok(Some(days as f64 * 86400.0));
+    }
+    let Some(ref expr) = rules.schedule_expression else {
+        return Ok(None);
+    };
+    let invalid = || {
+        SecretsManagerError::InvalidParameterException(format!(
+            "Unsupported ScheduleExpression: {expr}"
+        ))
+    };
+    let body = expr
+        .strip_prefix("rate(")
+        .and_then(|e| e.strip_suffix(')'))
+        .ok_or_else(invalid)?;
+    let (amount, unit) = body.split_once(' ').ok_or_else(invalid)?;
+    let amount: f64 = amount.parse().map_err(|_| invalid())?;
+    match unit {
+        "day" | "days" => Ok(Some(amount * 86400.0)),
+        "hour" | "hours" => Ok(Some(amount * 3600.0)),
+        _ => Err(invalid()),
+    }
 }
 
 struct SecretsManagerStateInner {
@@ -36,6 +205,7 @@ struct SecretsManagerStateInner {
 
 pub struct SecretsManagerState {
     inner: Arc<Mutex<SecretsManagerStateInner>>,
+    bus: Arc<ServiceBus>,
 }
 
 impl SecretsManagerState {
@@ -46,6 +216,61 @@ impl SecretsManagerState {
                 account_id,
                 region,
             })),
+            bus: Arc::new(ServiceBus::new()),
+        }
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Invokes rotation Lambdas through `bus` instead of a detached one.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Periodically rotates secrets whose `NextRotationDate` has passed.
+    pub fn start_rotation_schedule(self: &Arc<Self>, interval: std::time::Duration) {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                state.rotate_due_secrets().await;
+            }
+        });
+    }
+
+    async fn rotate_due_secrets(&self) {
+        let now = Self::now();
+        let due: Vec<String> = {
+            let state = self.inner.lock().await;
+            state
+                .secrets
+                .values()
+                .filter(|s| {
+                    s.rotation_enabled
+                        && !s.deleted
+                        && s.next_rotation_date.is_some_and(|d| d <= now)
+                })
+                .map(|s| s.arn.clone())
+                .collect()
+        };
+        for arn in due {
+            // Failures leave AWSPENDING in place, as in AWS; the next
+            // attempt happens on the following schedule tick.
+            if let Err(err) = self
+                .rotate_secret(RotateSecretRequest {
+                    secret_id: arn.clone(),
+                    ..Default::default()
+                })
+                .await
+            {
+                tracing::warn!(secret = %arn, error = ?err, "scheduled rotation failed");
+            }
         }
     }
 
@@ -114,6 +339,12 @@ impl SecretsManagerState {
             created_date: now,
             last_changed_date: now,
             deleted: false,
+            rotation_enabled: false,
+            rotation_lambda_arn: None,
+            rotation_rules: None,
+            last_rotated_date: None,
+            next_rotation_date: None,
+            resource_policy: None,
         });
         Ok(CreateSecretResponse {
             arn,
@@ -173,12 +404,19 @@ impl SecretsManagerState {
         let version_id = req.client_request_token.unwrap_or_else(|| Uuid::new_v4().to_string());
         let stages = req.version_stages.unwrap_or_else(|| vec!["AWSCURRENT".to_string()]);
         let secret = state.secrets.get_mut(&resolved).unwrap();
-        // Remove AWSCURRENT from previous version
-        for v in secret.versions.iter_mut() {
-            v.version_stages.retain(|s| s != "AWSCURRENT");
-            if v.version_stages.is_empty() {
-                v.version_stages.push("AWSPREVIOUS".to_string());
+        // Repeating a request with the same token and value is a no-op.
+        if let Some(existing) = secret.versions.iter().find(|v| v.version_id == version_id) {
+            if existing.secret_string != req.secret_string || existing.secret_binary != req.secret_binary {
+                return Err(SecretsManagerError::ResourceExistsException(format!(
+                    "A version with VersionId {version_id} already exists"
+                )));
             }
+            return Ok(PutSecretValueResponse {
+                arn: secret.arn.clone(),
+                name: secret.name.clone(),
+                version_id,
+                version_stages: existing.version_stages.clone(),
+            });
         }
         let arn = secret.arn.clone();
         let name = secret.name.clone();
@@ -186,10 +424,12 @@ impl SecretsManagerState {
             version_id: version_id.clone(),
             secret_string: req.secret_string,
             secret_binary: req.secret_binary,
-            version_stages: stages.clone(),
+            version_stages: Vec::new(),
             created_date: now,
         });
-        secret.current_version_id = version_id.clone();
+        for stage in &stages {
+            secret.move_stage(stage, &version_id);
+        }
         secret.last_changed_date = now;
         Ok(PutSecretValueResponse {
             arn,
@@ -223,7 +463,11 @@ impl SecretsManagerState {
             name: secret.name.clone(),
             description: secret.description.clone(),
             kms_key_id: secret.kms_key_id.clone(),
-            rotation_enabled: false,
+            rotation_enabled: secret.rotation_enabled,
+            rotation_lambda_arn: secret.rotation_lambda_arn.clone(),
+            rotation_rules: secret.rotation_rules.clone(),
+            last_rotated_date: secret.last_rotated_date,
+            next_rotation_date: secret.next_rotation_date,
             tags,
             created_date: secret.created_date,
             last_changed_date: secret.last_changed_date,
@@ -274,20 +518,14 @@ impl SecretsManagerState {
         let mut version_id = secret.current_version_id.clone();
         if req.secret_string.is_some() || req.secret_binary.is_some() {
             version_id = Uuid::new_v4().to_string();
-            for v in secret.versions.iter_mut() {
-                v.version_stages.retain(|s| s != "AWSCURRENT");
-                if v.version_stages.is_empty() {
-                    v.version_stages.push("AWSPREVIOUS".to_string());
-                }
-            }
             secret.versions.push(SecretVersion {
                 version_id: version_id.clone(),
                 secret_string: req.secret_string,
                 secret_binary: req.secret_binary,
-                version_stages: vec!["AWSCURRENT".to_string()],
+                version_stages: Vec::new(),
                 created_date: now,
             });
-            secret.current_version_id = version_id.clone();
+            secret.move_stage("AWSCURRENT", &version_id);
         }
         secret.last_changed_date = now;
         let arn = secret.arn.clone();
@@ -371,6 +609,217 @@ impl SecretsManagerState {
         Ok(())
     }
 
+    /// Runs the four rotation steps against the secret's rotation Lambda,
+    /// then promotes the `AWSPENDING` version to `AWSCURRENT` if the Lambda
+    /// did not already do so in `finishSecret`.
+    pub async fn rotate_secret(
+        &self,
+        req: RotateSecretRequest,
+    ) -> Result<RotateSecretResponse, SecretsManagerError> {
+        let now = Self::now();
+        let token = req
+            .client_request_token
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let (arn, name, lambda_arn) = {
+            let mut state = self.inner.lock().await;
+            let resolved = Self::resolve(&state, &req.secret_id)
+                .ok_or_else(|| SecretsManagerError::ResourceNotFoundException(format!(
+                    "Secrets Manager can't find the specified secret: {}", req.secret_id
+                )))?
+                .to_string();
+            let secret = state.secrets.get_mut(&resolved).unwrap();
+            if secret.deleted {
+                return Err(SecretsManagerError::InvalidRequestException(
+                    "Secret is scheduled for deletion".to_string(),
+                ));
+            }
+            let lambda_arn = req
+                .rotation_lambda_arn
+                .or_else(|| secret.rotation_lambda_arn.clone())
+                .ok_or_else(|| SecretsManagerError::InvalidRequestException(
+                    "No Lambda rotation function ARN is associated with this secret".to_string(),
+                ))?;
+            if let Some(rules) = req.rotation_rules {
+                let interval = rotation_interval(&rules)?;
+                secret.next_rotation_date = interval.map(|i| now + i);
+                secret.rotation_rules = Some(rules);
+            }
+            if secret.versions.iter().any(|v| {
+                v.version_stages.iter().any(|s| s == "AWSPENDING")
+                    && !v.version_stages.iter().any(|s| s == "AWSCURRENT")
+                    && v.version_id != token
+            }) {
+                return Err(SecretsManagerError::InvalidRequestException(
+                    "A previous rotation isn't complete. That rotation will be reattempted.".to_string(),
+                ));
+            }
+            secret.rotation_enabled = true;
+            secret.rotation_lambda_arn = Some(lambda_arn.clone());
+            if req.rotate_immediately == Some(false) {
+                return Ok(RotateSecretResponse {
+                    arn: secret.arn.clone(),
+                    name: secret.name.clone(),
+                    version_id: None,
+                });
+            }
+            (secret.arn.clone(), secret.name.clone(), lambda_arn)
+        };
+
+        // The lock is released while the Lambda runs: rotation functions
+        // call back into Secrets Manager (PutSecretValue, ...).
+        for step in ROTATION_STEPS {
+            let event = serde_json::json!({
+                "Step": step,
+                "SecretId": arn,
+                "ClientRequestToken": token,
+            });
+            self.bus.invoke_lambda(&lambda_arn, event).await.map_err(|reason| {
+                SecretsManagerError::InvalidRequestException(format!(
+                    "Rotation Lambda {lambda_arn} failed during {step}: {reason}"
+                ))
+            })?;
+            if step == "createSecret" {
+                self.require_pending_version(&name, &token).await?;
+            }
+        }
+
+        let mut state = self.inner.lock().await;
+        let secret = state.secrets.get_mut(&name).ok_or_else(|| {
+            SecretsManagerError::ResourceNotFoundException(format!(
+                "Secrets Manager can't find the specified secret: {}", req.secret_id
+            ))
+        })?;
+        if secret.current_version_id != token {
+            secret.move_stage("AWSCURRENT", &token);
+        }
+        for v in secret.versions.iter_mut() {
+            v.version_stages.retain(|s| s != "AWSPENDING");
+        }
+        let now = Self::now();
+        secret.last_rotated_date = Some(now);
+        secret.last_changed_date = now;
+        if let Some(ref rules) = secret.rotation_rules {
+            secret.next_rotation_date = rotation_interval(rules)?.map(|i| now + i);
+        }
+        Ok(RotateSecretResponse {
+            arn,
+            name,
+            version_id: Some(token),
+        })
+    }
+
+    async fn require_pending_version(
+        &self,
+        name: &str,
+        token: &str,
+    ) -> Result<(), SecretsManagerError> {
+        let state = self.inner.lock().await;
+        let staged = state.secrets.get(name).is_some_and(|s| {
+            s.versions.iter().any(|v| {
+                v.version_id == token && v.version_stages.iter().any(|s| s == "AWSPENDING")
+            })
+        });
+        if staged {
+            Ok(())
+        } else {
+            Err(SecretsManagerError::InvalidRequestException(format!(
+                "The rotation Lambda did not create an AWSPENDING version with ClientRequestToken {token}"
+            )))
+        }
+    }
+
+    pub async fn get_random_password(
+        &self,
+        req: GetRandomPasswordRequest,
+    ) -> Result<GetRandomPasswordResponse, SecretsManagerError> {
+        Ok(GetRandomPasswordResponse {
+            random_password: generate_password(&req)?,
+        })
+    }
+
+    pub async fn put_resource_policy(
+        &self,
+        req: PutResourcePolicyRequest,
+    ) -> Result<ResourcePolicyResponse, SecretsManagerError> {
+        if let Some(first) = policy_errors(&req.resource_policy).first() {
+            return Err(SecretsManagerError::MalformedPolicyDocumentException(
+                first.error_message.clone(),
+            ));
+        }
+        if req.block_public_policy.unwrap_or(false) && is_public_policy(&req.resource_policy) {
+            return Err(SecretsManagerError::PublicPolicyException(
+                "BlockPublicPolicy prevents this resource policy from granting public access".to_string(),
+            ));
+        }
+        let mut state = self.inner.lock().await;
+        let resolved = Self::resolve(&state, &req.secret_id)
+            .ok_or_else(|| SecretsManagerError::ResourceNotFoundException(format!(
+                "Secrets Manager can't find the specified secret: {}", req.secret_id
+            )))?
+            .to_string();
+        let secret = state.secrets.get_mut(&resolved).unwrap();
+        secret.resource_policy = Some(req.resource_policy);
+        Ok(ResourcePolicyResponse {
+            arn: secret.arn.clone(),
+            name: secret.name.clone(),
+        })
+    }
+
+    pub async fn get_resource_policy(
+        &self,
+        req: GetResourcePolicyRequest,
+    ) -> Result<GetResourcePolicyResponse, SecretsManagerError> {
+        let state = self.inner.lock().await;
+        let resolved = Self::resolve(&state, &req.secret_id)
+            .ok_or_else(|| SecretsManagerError::ResourceNotFoundException(format!(
+                "Secrets Manager can't find the specified secret: {}", req.secret_id
+            )))?
+            .to_string();
+        let secret = &state.secrets[&resolved];
+        Ok(GetResourcePolicyResponse {
+            arn: secret.arn.clone(),
+            name: secret.name.clone(),
+            resource_policy: secret.resource_policy.clone(),
+        })
+    }
+
+    pub async fn delete_resource_policy(
+        &self,
+        req: DeleteResourcePolicyRequest,
+    ) -> Result<ResourcePolicyResponse, SecretsManagerError> {
+        let mut state = self.inner.lock().await;
+        let resolved = Self::resolve(&state, &req.secret_id)
+            .ok_or_else(|| SecretsManagerError::ResourceNotFoundException(format!(
+                "Secrets Manager can't find the specified secret: {}", req.secret_id
+            )))?
+            .to_string();
+        let secret = state.secrets.get_mut(&resolved).unwrap();
+        secret.resource_policy = None;
+        Ok(ResourcePolicyResponse {
+            arn: secret.arn.clone(),
+            name: secret.name.clone(),
+        })
+    }
+
+    pub async fn validate_resource_policy(
+        &self,
+        req: ValidateResourcePolicyRequest,
+    ) -> Result<ValidateResourcePolicyResponse, SecretsManagerError> {
+        if let Some(ref secret_id) = req.secret_id {
+            let state = self.inner.lock().await;
+            if Self::resolve(&state, secret_id).is_none() {
+                return Err(SecretsManagerError::ResourceNotFoundException(format!(
+                    "Secrets Manager can't find the specified secret: {secret_id}"
+                )));
+            }
+        }
+        let validation_errors = policy_errors(&req.resource_policy);
+        Ok(ValidateResourcePolicyResponse {
+            policy_validation_passed: validation_errors.is_empty(),
+            validation_errors,
+        })
+    }
+
     pub async fn list_secret_version_ids(
         &self,
         req: ListSecretVersionIdsRequest,
@@ -540,6 +989,43 @@ mod tests {
         assert!(!result.unwrap().versions.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_unlabeled_versions_are_still_listed() {
+        let state = make_state();
+        create_secret(&state, "s1").await;
+        for value in ["v2", "v3"] {
+            state.put_secret_value(PutSecretValueRequest {
+                secret_id: "s1".to_string(),
+                secret_string: Some(value.to_string()),
+                ..Default::default()
+            }).await.unwrap();
+        }
+        let req = ListSecretVersionIdsRequest { secret_id: "s1".to_string() };
+        let versions = state.list_secret_version_ids(req).await.unwrap().versions;
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions.iter().filter(|v| v.version_stages.is_empty()).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_put_secret_value_repeated_token_is_idempotent() {
+        let state = make_state();
+        create_secret(&state, "s1").await;
+        let put = |value: &str| PutSecretValueRequest {
+            secret_id: "s1".to_string(),
+            secret_string: Some(value.to_string()),
+            client_request_token: Some("token-1".to_string()),
+            ..Default::default()
+        };
+        let first = state.put_secret_value(put("v2")).await.unwrap();
+        let again = state.put_secret_value(put("v2")).await.unwrap();
+        assert_eq!(again.version_id, first.version_id);
+        assert_eq!(again.version_stages, vec!["AWSCURRENT".to_string()]);
+        assert!(matches!(
+            state.put_secret_value(put("other")).await,
+            Err(SecretsManagerError::ResourceExistsException(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_tag_and_untag_secret() {
         let state = make_state();
@@ -555,4 +1041,324 @@ mod tests {
             tag_keys: vec!["env".to_string()],
         }).await.unwrap();
     }
+
+    /// A rotation Lambda stub that stages a new password in `createSecret`
+    /// and leaves label promotion to Secrets Manager.
+    async fn rotation_setup() -> (Arc<SecretsManagerState>, Arc<std::sync::Mutex<Vec<String>>>) {
+        use crate::lambda::state::{FunctionHandler, LambdaState};
+
+        let lambda = Arc::new(LambdaState::new("123456789012".to_string(), "us-east-1".to_string()));
+        for name in ["rotator", "noop", "bare"] {
+            lambda
+                .create_function(serde_json::from_value(serde_json::json!({
+                    "FunctionName": name,
+                    "Role": "arn:aws:iam::123456789012:role/r",
+                    "Code": {}
+                }))
+                .unwrap())
+                .await
+                .unwrap();
+        }
+        let bus = Arc::new(ServiceBus::new());
+        bus.attach_lambda(lambda.clone());
+        let state = Arc::new(make_state().with_bus(bus));
+
+        let steps = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let handler: FunctionHandler = {
+            let state = state.clone();
+            let steps = steps.clone();
+            Arc::new(move |event| {
+                let state = state.clone();
+                let steps = steps.clone();
+                Box::pin(async move {
+                    let step = event["Step"].as_str().unwrap_or_default().to_string();
+                    steps.lock().unwrap().push(step.clone());
+                    if step == "createSecret" {
+                        state
+                            .put_secret_value(PutSecretValueRequest {
+                                secret_id: event["SecretId"].as_str().unwrap().to_string(),
+                                secret_string: Some("rotated-value".to_string()),
+                                client_request_token: event["ClientRequestToken"].as_str().map(str::to_string),
+                                version_stages: Some(vec!["AWSPENDING".to_string()]),
+                                ..Default::default()
+                            })
+                            .await
+                            .map_err(|e| format!("{e:?}"))?;
+                    }
+                    Ok(serde_json::Value::Null)
+                })
+            })
+        };
+        lambda.set_handler("rotator", handler).await.unwrap();
+        let noop: FunctionHandler = Arc::new(|_| Box::pin(async { Ok(serde_json::Value::Null) }));
+        lambda.set_handler("noop", noop).await.unwrap();
+        (state, steps)
+    }
+
+    #[tokio::test]
+    async fn test_rotate_secret_advances_awscurrent() {
+        let (state, steps) = rotation_setup().await;
+        create_secret(&state, "db").await;
+        let original = state
+            .get_secret_value(GetSecretValueRequest { secret_id: "db".to_string(), ..Default::default() })
+            .await
+            .unwrap()
+            .version_id;
+
+        let resp = state
+            .rotate_secret(RotateSecretRequest {
+                secret_id: "db".to_string(),
+                client_request_token: Some("token-1".to_string()),
+                rotation_lambda_arn: Some("arn:aws:lambda:us-east-1:123456789012:function:rotator".to_string()),
+                rotation_rules: Some(RotationRules {
+                    automatically_after_days: Some(30),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(resp.version_id.as_deref(), Some("token-1"));
+        assert_eq!(
+            *steps.lock().unwrap(),
+            vec!["createSecret", "setSecret", "testSecret", "finishSecret"]
+        );
+
+        let current = state
+            .get_secret_value(GetSecretValueRequest { secret_id: "db".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(current.version_id, "token-1");
+        assert_eq!(current.secret_string.as_deref(), Some("rotated-value"));
+        assert_eq!(current.version_stages, vec!["AWSCURRENT"]);
+        let previous = state
+            .get_secret_value(GetSecretValueRequest {
+                secret_id: "db".to_string(),
+                version_stage: Some("AWSPREVIOUS".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(previous.version_id, original);
+
+        let described = state
+            .describe_secret(DescribeSecretRequest { secret_id: "db".to_string() })
+            .await
+            .unwrap();
+        assert!(described.rotation_enabled);
+        let next = described.next_rotation_date.unwrap();
+        assert!((next - described.last_rotated_date.unwrap() - 30.0 * 86400.0).abs() < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_secret_fails_without_pending_version() {
+        let (state, _) = rotation_setup().await;
+        create_secret(&state, "db").await;
+        // The function succeeds but stages nothing.
+        let result = state
+            .rotate_secret(RotateSecretRequest {
+                secret_id: "db".to_string(),
+                rotation_lambda_arn: Some("noop".to_string()),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(result, Err(SecretsManagerError::InvalidRequestException(_))));
+        let current = state
+            .get_secret_value(GetSecretValueRequest { secret_id: "db".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(current.secret_string.as_deref(), Some("my-secret-value"));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_secret_fails_when_function_cannot_run() {
+        let (state, _) = rotation_setup().await;
+        create_secret(&state, "db").await;
+        let result = state
+            .rotate_secret(RotateSecretRequest {
+                secret_id: "db".to_string(),
+                rotation_lambda_arn: Some("bare".to_string()),
+                ..Default::default()
+            })
+            .await;
+        let Err(SecretsManagerError::InvalidRequestException(message)) = result else {
+            panic!("expected InvalidRequestException, got {result:?}");
+        };
+        assert!(message.contains("cannot run"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_rotate_secret_without_lambda() {
+        let state = make_state();
+        create_secret(&state, "db").await;
+        let result = state
+            .rotate_secret(RotateSecretRequest { secret_id: "db".to_string(), ..Default::default() })
+            .await;
+        assert!(matches!(result, Err(SecretsManagerError::InvalidRequestException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rotation_schedule_rotates_due_secrets() {
+        let (state, steps) = rotation_setup().await;
+        create_secret(&state, "db").await;
+        state
+            .rotate_secret(RotateSecretRequest {
+                secret_id: "db".to_string(),
+                rotation_lambda_arn: Some("rotator".to_string()),
+                rotation_rules: Some(RotationRules {
+                    automatically_after_days: Some(1),
+                    ..Default::default()
+                }),
+                rotate_immediately: Some(false),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(steps.lock().unwrap().is_empty());
+
+        state.inner.lock().await.secrets.get_mut("db").unwrap().next_rotation_date = Some(0.0);
+        state.rotate_due_secrets().await;
+        assert_eq!(steps.lock().unwrap().len(), 4);
+        let current = state
+            .get_secret_value(GetSecretValueRequest { secret_id: "db".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(current.secret_string.as_deref(), Some("rotated-value"));
+    }
+
+    #[tokio::test]
+    async fn test_get_random_password_respects_length_and_exclusions() {
+        let state = make_state();
+        let password = state
+            .get_random_password(GetRandomPasswordRequest {
+                password_length: Some(64),
+                exclude_characters: Some("abcXYZ012".to_string()),
+                exclude_punctuation: Some(true),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .random_password;
+        assert_eq!(password.chars().count(), 64);
+        assert!(!password.chars().any(|c| "abcXYZ012".contains(c)));
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        // RequireEachIncludedType defaults to true.
+        assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+        assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(password.chars().any(|c| c.is_ascii_digit()));
+    }
+
+    #[tokio::test]
+    async fn test_get_random_password_include_space_and_defaults() {
+        let state = make_state();
+        let password = state
+            .get_random_password(GetRandomPasswordRequest {
+                include_space: Some(true),
+                exclude_uppercase: Some(true),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .random_password;
+        assert_eq!(password.len(), 32);
+        assert!(password.contains(' '));
+        assert!(password.chars().any(|c| PUNCTUATION.contains(c)));
+        assert!(!password.chars().any(|c| c.is_ascii_uppercase()));
+    }
+
+    #[tokio::test]
+    async fn test_get_random_password_invalid_requests() {
+        let state = make_state();
+        let too_long = state
+            .get_random_password(GetRandomPasswordRequest {
+                password_length: Some(5000),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(too_long, Err(SecretsManagerError::InvalidParameterException(_))));
+        let nothing_left = state
+            .get_random_password(GetRandomPasswordRequest {
+                exclude_lowercase: Some(true),
+                exclude_uppercase: Some(true),
+                exclude_numbers: Some(true),
+                exclude_punctuation: Some(true),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(nothing_left, Err(SecretsManagerError::InvalidParameterException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resource_policy_round_trip() {
+        let state = make_state();
+        let arn = create_secret(&state, "shared").await;
+        let policy = r#"{"Version":"2012-10-17","Statement":[{"Effect":"Allow","Principal":{"AWS":"arn:aws:iam::111122223333:root"},"Action":"secretsmanager:GetSecretValue","Resource":"*"}]}"#;
+
+        let validated = state
+            .validate_resource_policy(ValidateResourcePolicyRequest {
+                secret_id: Some("shared".to_string()),
+                resource_policy: policy.to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(validated.policy_validation_passed);
+
+        let put = state
+            .put_resource_policy(PutResourcePolicyRequest {
+                secret_id: arn.clone(),
+                resource_policy: policy.to_string(),
+                block_public_policy: Some(true),
+            })
+            .await
+            .unwrap();
+        assert_eq!(put.arn, arn);
+        let fetched = state
+            .get_resource_policy(GetResourcePolicyRequest { secret_id: "shared".to_string() })
+            .await
+            .unwrap();
+        assert_eq!(fetched.resource_policy.as_deref(), Some(policy));
+
+        state
+            .delete_resource_policy(DeleteResourcePolicyRequest { secret_id: "shared".to_string() })
+            .await
+            .unwrap();
+        let fetched = state
+            .get_resource_policy(GetResourcePolicyRequest { secret_id: "shared".to_string() })
+            .await
+            .unwrap();
+        assert!(fetched.resource_policy.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_put_resource_policy_rejects_bad_documents() {
+        let state = make_state();
+        create_secret(&state, "shared").await;
+        let malformed = state
+            .put_resource_policy(PutResourcePolicyRequest {
+                secret_id: "shared".to_string(),
+                resource_policy: "{not json".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(malformed, Err(SecretsManagerError::MalformedPolicyDocumentException(_))));
+
+        let public = state
+            .put_resource_policy(PutResourcePolicyRequest {
+                secret_id: "shared".to_string(),
+                resource_policy: r#"{"Statement":[{"Effect":"Allow","Principal":"*","Action":"secretsmanager:*"}]}"#.to_string(),
+                block_public_policy: Some(true),
+            })
+            .await;
+        assert!(matches!(public, Err(SecretsManagerError::PublicPolicyException(_))));
+
+        let report = state
+            .validate_resource_policy(ValidateResourcePolicyRequest {
+                secret_id: None,
+                resource_policy: r#"{"Statement":[{"Effect":"Maybe"}]}"#.to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(!report.policy_validation_passed);
+        assert_eq!(report.validation_errors.len(), 3);
+    }
 }
@@ -103,6 +103,18 @@ pub struct DescribeSecretResponse {
     pub kms_key_id: Option<String>,
     #[serde(rename = "RotationEnabled")]
     pub rotation_enabled: bool,
+    #[serde(rename = "RotationLambdaARN")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation_lambda_arn: Option<String>,
+    #[serde(rename = "RotationRules")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation_rules: Option<RotationRules>,
+    #[serde(rename = "LastRotatedDate")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_rotated_date: Option<f64>,
+    #[serde(rename = "NextRotationDate")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_rotation_date: Option<f64>,
     #[serde(rename = "Tags")]
     pub tags: Vec<Tag>,
     #[serde(rename = "CreatedDate")]
@@ -243,6 +255,133 @@ pub struct SecretVersionEntry {
     pub created_date: f64,
 }
 
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RotateSecretRequest {
+    #[serde(rename = "SecretId")]
+    pub secret_id: String,
+    #[serde(rename = "ClientRequestToken")]
+    pub client_request_token: Option<String>,
+    #[serde(rename = "RotationLambdaARN")]
+    pub rotation_lambda_arn: Option<String>,
+    #[serde(rename = "RotationRules")]
+    pub rotation_rules: Option<RotationRules>,
+    #[serde(rename = "RotateImmediately")]
+    pub rotate_immediately: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct RotationRules {
+    #[serde(rename = "AutomaticallyAfterDays")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatically_after_days: Option<i64>,
+    #[serde(rename = "ScheduleExpression")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_expression: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateSecretResponse {
+    #[serde(rename = "ARN")]
+    pub arn: String,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "VersionId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetRandomPasswordRequest {
+    #[serde(rename = "PasswordLength")]
+    pub password_length: Option<usize>,
+    #[serde(rename = "ExcludeCharacters")]
+    pub exclude_characters: Option<String>,
+    #[serde(rename = "ExcludeNumbers")]
+    pub exclude_numbers: Option<bool>,
+    #[serde(rename = "ExcludePunctuation")]
+    pub exclude_punctuation: Option<bool>,
+    #[serde(rename = "ExcludeUppercase")]
+    pub exclude_uppercase: Option<bool>,
+    #[serde(rename = "ExcludeLowercase")]
+    pub exclude_lowercase: Option<bool>,
+    #[serde(rename = "IncludeSpace")]
+    pub include_space: Option<bool>,
+    #[serde(rename = "RequireEachIncludedType")]
+    pub require_each_included_type: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetRandomPasswordResponse {
+    #[serde(rename = "RandomPassword")]
+    pub random_password: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PutResourcePolicyRequest {
+    #[serde(rename = "SecretId")]
+    pub secret_id: String,
+    #[serde(rename = "ResourcePolicy")]
+    pub resource_policy: String,
+    #[serde(rename = "BlockPublicPolicy")]
+    pub block_public_policy: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetResourcePolicyRequest {
+    #[serde(rename = "SecretId")]
+    pub secret_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetResourcePolicyResponse {
+    #[serde(rename = "ARN")]
+    pub arn: String,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "ResourcePolicy")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_policy: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DeleteResourcePolicyRequest {
+    #[serde(rename = "SecretId")]
+    pub secret_id: String,
+}
+
+/// Response shared by `PutResourcePolicy` and `DeleteResourcePolicy`.
+#[derive(Debug, Serialize)]
+pub struct ResourcePolicyResponse {
+    #[serde(rename = "ARN")]
+    pub arn: String,
+    #[serde(rename = "Name")]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ValidateResourcePolicyRequest {
+    #[serde(rename = "SecretId")]
+    pub secret_id: Option<String>,
+    #[serde(rename = "ResourcePolicy")]
+    pub resource_policy: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidateResourcePolicyResponse {
+    #[serde(rename = "PolicyValidationPassed")]
+    pub policy_validation_passed: bool,
+    #[serde(rename = "ValidationErrors")]
+    pub validation_errors: Vec<ValidationErrorsEntry>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ValidationErrorsEntry {
+    #[serde(rename = "CheckName")]
+    pub check_name: String,
+    #[serde(rename = "ErrorMessage")]
+    pub error_message: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Tag {
     #[serde(rename = "Key")]
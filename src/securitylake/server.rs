@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<SecuritylakeState>) -> Router {
     Router::new()
         .route("/data-lakes", post(create_data_lake_handler).get(list_data_lakes_handler))
         .route("/data-lakes/{name}", get(get_data_lake_handler).delete(delete_data_lake_handler))
+        .merge(crate::healthcheck::router("securitylake"))
         .with_state(state)
 }
 
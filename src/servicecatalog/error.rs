@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum ServiceCatalogError {
@@ -43,13 +44,23 @@ impl ServiceCatalogError {
     }
 }
 
+impl AwsError for ServiceCatalogError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        ServiceCatalogError::message(self)
+    }
+}
+
 impl IntoResponse for ServiceCatalogError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "Message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
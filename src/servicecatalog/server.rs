@@ -114,6 +114,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<ServiceCatalogState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("servicecatalog"))
         .with_state(state)
 }
 
@@ -1,12 +1,18 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum SesError {
     NotFoundException(String),
     AlreadyExistsException(String),
     BadRequestException(String),
+    InvalidParameterValue(String),
+    /// The account's maximum send rate was exceeded.
+    Throttling(String),
+    /// The account's 24-hour sending quota was exceeded.
+    LimitExceededException(String),
 }
 
 impl SesError {
@@ -14,7 +20,10 @@ impl SesError {
         match self {
             SesError::NotFoundException(_) => StatusCode::NOT_FOUND,
             SesError::AlreadyExistsException(_) => StatusCode::CONFLICT,
-            SesError::BadRequestException(_) => StatusCode::BAD_REQUEST,
+            SesError::BadRequestException(_)
+            | SesError::InvalidParameterValue(_)
+            | SesError::Throttling(_)
+            | SesError::LimitExceededException(_) => StatusCode::BAD_REQUEST,
         }
     }
 
@@ -22,7 +31,10 @@ impl SesError {
         match self {
             SesError::NotFoundException(m)
             | SesError::AlreadyExistsException(m)
-            | SesError::BadRequestException(m) => m,
+            | SesError::BadRequestException(m)
+            | SesError::InvalidParameterValue(m)
+            | SesError::Throttling(m)
+            | SesError::LimitExceededException(m) => m,
         }
     }
 }
@@ -33,23 +45,37 @@ impl SesError {
             SesError::NotFoundException(_) => "NotFoundException",
             SesError::AlreadyExistsException(_) => "AlreadyExistsException",
             SesError::BadRequestException(_) => "BadRequestException",
+            SesError::InvalidParameterValue(_) => "InvalidParameterValue",
+            SesError::Throttling(_) => "Throttling",
+            SesError::LimitExceededException(_) => "LimitExceededException",
         }
     }
 }
 
+impl AwsError for SesError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        SesError::message(self)
+    }
+}
+
 impl IntoResponse for SesError {
     fn into_response(self) -> Response {
-        let error_code = self.error_code();
-        let body = json!({
-            "__type": error_code,
-            "message": self.message(),
-        });
-        let mut resp = (self.status_code(), axum::Json(body)).into_response();
-        resp.headers_mut().insert(
-            "x-amzn-ErrorType",
-            axum::http::HeaderValue::from_str(error_code).unwrap(),
-        );
-        resp
+        error_response(&self, Protocol::RestJson)
+    }
+}
+
+impl SesError {
+    /// The XML error document used by the classic (query protocol) API.
+    pub fn into_query_response(self) -> Response {
+        error_response(&self, Protocol::Query("http://ses.amazonaws.com/doc/2010-12-01/"))
     }
 }
 
@@ -88,6 +114,12 @@ mod tests {
         assert_eq!(err.status_code(), StatusCode::CONFLICT);
     }
     #[test]
+    fn test_into_query_response() {
+        let resp = SesError::InvalidParameterValue("bad".to_string()).into_query_response();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.headers()["content-type"], "text/xml");
+    }
+    #[test]
     fn test_into_response() {
         let err = SesError::NotFoundException("test".to_string());
         let resp = err.into_response();
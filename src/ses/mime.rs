@@ -0,0 +1,157 @@
+use base64::Engine;
+
+/// The parts of a raw MIME message the mailbox keeps.
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedMessage {
+    pub from: Option<String>,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub subject: Option<String>,
+    pub text: Option<String>,
+    pub html: Option<String>,
+}
+
+/// Parses the headers and the text/HTML bodies of a MIME message, walking
+/// multipart containers. Attachments are ignored.
+pub fn parse(raw: &str) -> ParsedMessage {
+    let raw = raw.replace("\r\n", "\n");
+    let (headers, body) = split_headers(&raw);
+    let mut message = ParsedMessage {
+        from: header(&headers, "From"),
+        to: header(&headers, "To").map(|v| addresses(&v)).unwrap_or_default(),
+        cc: header(&headers, "Cc").map(|v| addresses(&v)).unwrap_or_default(),
+        subject: header(&headers, "Subject"),
+        ..Default::default()
+    };
+    collect_bodies(&headers, body, &mut message);
+    message
+}
+
+fn split_headers(part: &str) -> (Vec<(String, String)>, &str) {
+    let (head, body) = match part.find("\n\n") {
+        Some(i) => (&part[..i], &part[i + 2..]),
+        None => (part, ""),
+    };
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            // A folded continuation of the previous header.
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn header(headers: &[(String, String)], name: &str) -> Option<String> {
+    headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone())
+}
+
+/// Splits an address list, keeping only the address of `Name <addr>` forms.
+fn addresses(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|a| {
+            let a = a.trim();
+            match (a.rfind('<'), a.rfind('>')) {
+                (Some(start), Some(end)) if start < end => a[start + 1..end].to_string(),
+                _ => a.to_string(),
+            }
+        })
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// Returns a parameter such as `boundary` from a header value.
+fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (key, v) = p.split_once('=')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+fn collect_bodies(headers: &[(String, String)], body: &str, message: &mut ParsedMessage) {
+    let content_type = header(headers, "Content-Type").unwrap_or_else(|| "text/plain".to_string());
+    let media_type = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+
+    if media_type.starts_with("multipart/") {
+        let Some(boundary) = parameter(&content_type, "boundary") else {
+            return;
+        };
+        let delimiter = format!("--{boundary}");
+        for part in body.split(&delimiter).skip(1) {
+            if part.starts_with("--") {
+                break;
+            }
+            let part = part.strip_prefix('\n').unwrap_or(part);
+            let (part_headers, part_body) = split_headers(part);
+            collect_bodies(&part_headers, part_body.trim_end_matches('\n'), message);
+        }
+        return;
+    }
+
+    let attachment = header(headers, "Content-Disposition")
+        .is_some_and(|d| d.to_ascii_lowercase().starts_with("attachment"));
+    let slot = match media_type.as_str() {
+        _ if attachment => return,
+        "text/plain" => &mut message.text,
+        "text/html" => &mut message.html,
+        _ => return,
+    };
+    if slot.is_none() {
+        *slot = Some(decode(headers, body));
+    }
+}
+
+fn decode(headers: &[(String, String)], body: &str) -> String {
+    let encoding = header(headers, "Content-Transfer-Encoding").unwrap_or_default();
+    if encoding.eq_ignore_ascii_case("base64") {
+        let compact: String = body.split_whitespace().collect();
+        if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(compact) {
+            return String::from_utf8_lossy(&bytes).into_owned();
+        }
+    }
+    body.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multipart_message() {
+        let raw = "From: Sender <sender@example.com>\r\n\
+                   To: a@example.com, B <b@example.com>\r\n\
+                   Subject: Hello\r\n \tthere\r\n\
+                   Content-Type: multipart/alternative; boundary=\"XYZ\"\r\n\
+                   \r\n\
+                   --XYZ\r\n\
+                   Content-Type: text/plain\r\n\
+                   \r\n\
+                   plain body\r\n\
+                   --XYZ\r\n\
+                   Content-Type: text/html\r\n\
+                   Content-Transfer-Encoding: base64\r\n\
+                   \r\n\
+                   PHA+aGk8L3A+\r\n\
+                   --XYZ--\r\n";
+        let message = parse(raw);
+        assert_eq!(message.from.as_deref(), Some("Sender <sender@example.com>"));
+        assert_eq!(message.to, vec!["a@example.com", "b@example.com"]);
+        assert_eq!(message.subject.as_deref(), Some("Hello there"));
+        assert_eq!(message.text.as_deref(), Some("plain body"));
+        assert_eq!(message.html.as_deref(), Some("<p>hi</p>"));
+    }
+
+    #[test]
+    fn test_parse_single_part_message() {
+        let message = parse("Subject: Hi\nContent-Type: text/html\n\n<b>x</b>");
+        assert_eq!(message.html.as_deref(), Some("<b>x</b>"));
+        assert!(message.text.is_none());
+        assert!(message.to.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod error;
+pub mod mime;
 pub mod server;
 pub mod state;
 pub mod types;
@@ -1,9 +1,10 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::body::Bytes;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::response::{IntoResponse, Response};
 use axum::routing::{delete, get, post};
 use axum::{Json, Router};
 use serde::Deserialize;
@@ -68,13 +69,172 @@ async fn list_email_identities(
     Ok((StatusCode::OK, Json(resp)).into_response())
 }
 
+// POST /v2/email/templates
+async fn create_email_template(
+    State(state): State<Arc<SesState>>,
+    body: Bytes,
+) -> Result<axum::response::Response, SesError> {
+    let req: CreateEmailTemplateRequest = serde_json::from_slice(&body)
+        .map_err(|e| SesError::BadRequestException(e.to_string()))?;
+    state.create_email_template(req).await?;
+    Ok((StatusCode::OK, Json(serde_json::json!({}))).into_response())
+}
+
+// GET /v2/email/templates/{TemplateName}
+async fn get_email_template(
+    State(state): State<Arc<SesState>>,
+    Path(name): Path<String>,
+) -> Result<axum::response::Response, SesError> {
+    let resp = state.get_email_template(name).await?;
+    Ok((StatusCode::OK, Json(resp)).into_response())
+}
+
+// DELETE /v2/email/templates/{TemplateName}
+async fn delete_email_template(
+    State(state): State<Arc<SesState>>,
+    Path(name): Path<String>,
+) -> Result<axum::response::Response, SesError> {
+    state.delete_email_template(name).await?;
+    Ok((StatusCode::OK, Json(serde_json::json!({}))).into_response())
+}
+
+// --- Classic SES (query protocol, POST /) ---
+
+const NS: &str = "http://ses.amazonaws.com/doc/2010-12-01/";
+
+type Params = HashMap<String, String>;
+
+fn required(params: &Params, key: &str) -> Result<String, SesError> {
+    params
+        .get(key)
+        .cloned()
+        .ok_or_else(|| SesError::InvalidParameterValue(format!("Missing required parameter: {key}")))
+}
+
+/// `Prefix.member.N` list parameters.
+fn member_list(params: &Params, prefix: &str) -> Vec<String> {
+    (1..)
+        .map_while(|i| params.get(&format!("{prefix}.member.{i}")).cloned())
+        .collect()
+}
+
+fn xml_response(action: &str, result: &str) -> Response {
+    let xml = format!(
+        "<{action}Response xmlns=\"{NS}\"><{action}Result>{result}</{action}Result>\
+         <ResponseMetadata><RequestId>{}</RequestId></ResponseMetadata></{action}Response>",
+        uuid::Uuid::new_v4(),
+    );
+    (StatusCode::OK, [("content-type", "text/xml")], xml).into_response()
+}
+
+fn escape(s: &str) -> String {
+    quick_xml::escape::escape(s).into_owned()
+}
+
+async fn handle_query(State(state): State<Arc<SesState>>, body: Bytes) -> Response {
+    let params: Params = form_urlencoded::parse(&body).into_owned().collect();
+    dispatch_query(&state, &params).await.unwrap_or_else(SesError::into_query_response)
+}
+
+async fn dispatch_query(state: &SesState, params: &Params) -> Result<Response, SesError> {
+    let action = required(params, "Action")?;
+    match action.as_str() {
+        "SendEmail" => {
+            let source = required(params, "Source")?;
+            let destination = EmailDestination {
+                to_addresses: member_list(params, "Destination.ToAddresses"),
+                cc_addresses: member_list(params, "Destination.CcAddresses"),
+                bcc_addresses: member_list(params, "Destination.BccAddresses"),
+            };
+            if destination.recipients().next().is_none() {
+                return Err(SesError::InvalidParameterValue("Missing final '@domain'".to_string()));
+            }
+            let message_id = state
+                .send_message(
+                    source,
+                    destination,
+                    MessageBody {
+                        subject: params.get("Message.Subject.Data").cloned(),
+                        text: params.get("Message.Body.Text.Data").cloned(),
+                        html: params.get("Message.Body.Html.Data").cloned(),
+                        raw: None,
+                    },
+                )
+                .await?;
+            Ok(xml_response(&action, &format!("<MessageId>{}</MessageId>", escape(&message_id))))
+        }
+        "SetIdentityNotificationTopic" => {
+            let identity = required(params, "Identity")?;
+            let notification_type = required(params, "NotificationType")?;
+            let topic = params.get("SnsTopic").filter(|t| !t.is_empty()).cloned();
+            state.set_identity_notification_topic(identity, &notification_type, topic).await?;
+            Ok(xml_response(&action, ""))
+        }
+        "GetIdentityNotificationAttributes" => {
+            let identities = member_list(params, "Identities");
+            let mut entries = String::new();
+            for (identity, topics) in state.get_identity_notification_topics(&identities).await {
+                let topic = |name: &str, arn: &Option<String>| {
+                    arn.as_ref().map(|a| format!("<{name}>{}</{name}>", escape(a))).unwrap_or_default()
+                };
+                entries.push_str(&format!(
+                    "<entry><key>{}</key><value>{}{}{}<ForwardingEnabled>true</ForwardingEnabled>\
+                     <HeadersInBounceNotificationsEnabled>false</HeadersInBounceNotificationsEnabled>\
+                     <HeadersInComplaintNotificationsEnabled>false</HeadersInComplaintNotificationsEnabled>\
+                     <HeadersInDeliveryNotificationsEnabled>false</HeadersInDeliveryNotificationsEnabled>\
+                     </value></entry>",
+                    escape(&identity),
+                    topic("BounceTopic", &topics.bounce),
+                    topic("ComplaintTopic", &topics.complaint),
+                    topic("DeliveryTopic", &topics.delivery),
+                ));
+            }
+            Ok(xml_response(&action, &format!("<NotificationAttributes>{entries}</NotificationAttributes>")))
+        }
+        "GetSendQuota" => {
+            let quota = state.get_send_quota().await;
+            Ok(xml_response(
+                &action,
+                &format!(
+                    "<Max24HourSend>{:.1}</Max24HourSend><MaxSendRate>{:.1}</MaxSendRate>\
+                     <SentLast24Hours>{:.1}</SentLast24Hours>",
+                    quota.max_24_hour_send, quota.max_send_rate, quota.sent_last_24_hours,
+                ),
+            ))
+        }
+        "GetSendStatistics" => {
+            let mut members = String::new();
+            for point in state.get_send_statistics().await {
+                members.push_str(&format!(
+                    "<member><Timestamp>{}</Timestamp><DeliveryAttempts>{}</DeliveryAttempts>\
+                     <Bounces>{}</Bounces><Complaints>{}</Complaints><Rejects>{}</Rejects></member>",
+                    point.timestamp, point.delivery_attempts, point.bounces, point.complaints, point.rejects,
+                ));
+            }
+            Ok(xml_response(&action, &format!("<SendDataPoints>{members}</SendDataPoints>")))
+        }
+        other => Err(SesError::InvalidParameterValue(format!("Unknown action: {other}"))),
+    }
+}
+
+// GET /_aws/ses — the local mailbox of captured messages
+async fn list_sent_emails(State(state): State<Arc<SesState>>) -> Response {
+    Json(serde_json::json!({ "messages": state.sent_emails().await })).into_response()
+}
+
 pub fn create_router(state: Arc<SesState>) -> Router {
     Router::new()
+        .route("/", post(handle_query))
+        .route("/_aws/ses", get(list_sent_emails))
         .route("/v2/email/outbound-emails", post(send_email))
         .route("/v2/email/identities", post(create_email_identity))
         .route("/v2/email/identities", get(list_email_identities))
         .route("/v2/email/identities/{email_identity}", get(get_email_identity))
         .route("/v2/email/identities/{email_identity}", delete(delete_email_identity))
+        .route("/v2/email/templates", post(create_email_template))
+        .route("/v2/email/templates/{template_name}", get(get_email_template))
+        .route("/v2/email/templates/{template_name}", delete(delete_email_template))
+        .merge(crate::healthcheck::router("ses"))
         .with_state(state)
 }
 
@@ -150,4 +310,80 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert!(resp.status().is_success() || resp.status().is_client_error());
     }
+    #[tokio::test]
+    async fn test_classic_notification_topic_and_send() {
+        use http_body_util::BodyExt;
+
+        let state = Arc::new(SesState::new("123456789012".to_string(), "us-east-1".to_string()));
+        let app = create_router(state.clone());
+        let call = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let resp = app.clone().oneshot(call(
+            "Action=SetIdentityNotificationTopic&Identity=a%40example.com&NotificationType=Bounce\
+             &SnsTopic=arn%3Aaws%3Asns%3Aus-east-1%3A123456789012%3Afeedback",
+        )).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = app.clone().oneshot(call(
+            "Action=GetIdentityNotificationAttributes&Identities.member.1=a%40example.com",
+        )).await.unwrap();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let xml = String::from_utf8(body.to_vec()).unwrap();
+        assert!(xml.contains("<BounceTopic>arn:aws:sns:us-east-1:123456789012:feedback</BounceTopic>"));
+
+        let resp = app.clone().oneshot(call(
+            "Action=SendEmail&Source=a%40example.com&Destination.ToAddresses.member.1=b%40example.com\
+             &Message.Subject.Data=Hi&Message.Body.Text.Data=Hello",
+        )).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let sent = state.sent_emails().await;
+        assert_eq!(sent[0].destination.to_addresses, vec!["b@example.com"]);
+        assert_eq!(sent[0].text_body.as_deref(), Some("Hello"));
+
+        let resp = app.oneshot(call("Action=SetIdentityNotificationTopic&Identity=x&NotificationType=Open")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.headers()["content-type"], "text/xml");
+    }
+
+    #[tokio::test]
+    async fn test_v2_send_email_simple_content_lands_in_mailbox() {
+        use http_body_util::BodyExt;
+
+        let state = Arc::new(SesState::new("123456789012".to_string(), "us-east-1".to_string()));
+        let app = create_router(state);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/v2/email/outbound-emails")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"FromEmailAddress": "sender@example.com",
+                    "Destination": {"ToAddresses": ["to@example.com"], "CcAddresses": ["cc@example.com"]},
+                    "Content": {"Simple": {"Subject": {"Data": "Greetings"},
+                                           "Body": {"Text": {"Data": "Hello"}, "Html": {"Data": "<p>Hello</p>"}}}}}"#,
+            ))
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let sent: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let message_id = sent["MessageId"].as_str().unwrap().to_string();
+
+        let req = Request::builder().uri("/_aws/ses").body(Body::empty()).unwrap();
+        let body = app.oneshot(req).await.unwrap().into_body().collect().await.unwrap().to_bytes();
+        let mailbox: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let message = &mailbox["messages"][0];
+        assert_eq!(message["Id"], message_id.as_str());
+        assert_eq!(message["Source"], "sender@example.com");
+        assert_eq!(message["Destination"]["ToAddresses"][0], "to@example.com");
+        assert_eq!(message["Destination"]["CcAddresses"][0], "cc@example.com");
+        assert_eq!(message["Subject"], "Greetings");
+        assert_eq!(message["TextBody"], "Hello");
+        assert_eq!(message["HtmlBody"], "<p>Hello</p>");
+    }
 }
@@ -1,11 +1,49 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 
+use base64::Engine;
+use serde_json::{json, Value};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use super::error::SesError;
+use super::mime;
 use super::types::*;
+use crate::bus::ServiceBus;
+
+const SIMULATOR_DOMAIN: &str = "simulator.amazonses.com";
+
+/// How far back `GetSendStatistics` reports, and so how long sends are kept.
+const STATISTICS_DAYS: i64 = 14;
+
+/// `GetSendStatistics` groups sends into data points of this many seconds.
+const DATA_POINT_SECONDS: i64 = 15 * 60;
+
+/// What the mailbox simulator does with a message to one recipient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    Delivery,
+    Bounce,
+    Complaint,
+}
+
+impl Outcome {
+    /// `bounce@`, `complaint@` and `success@simulator.amazonses.com` (with
+    /// an optional `+label`) simulate feedback; anything else is delivered.
+    fn for_recipient(address: &str) -> Self {
+        let Some((local, domain)) = address.rsplit_once('@') else {
+            return Outcome::Delivery;
+        };
+        if !domain.eq_ignore_ascii_case(SIMULATOR_DOMAIN) {
+            return Outcome::Delivery;
+        }
+        match local.split('+').next().unwrap_or(local).to_ascii_lowercase().as_str() {
+            "bounce" => Outcome::Bounce,
+            "complaint" => Outcome::Complaint,
+            _ => Outcome::Delivery,
+        }
+    }
+}
 
 struct EmailIdentity {
     identity_name: String,
@@ -14,34 +52,446 @@ struct EmailIdentity {
     verified: bool,
 }
 
+/// One accepted send. Quotas and statistics count recipients, not messages.
+struct SendRecord {
+    at: chrono::DateTime<chrono::Utc>,
+    recipients: u64,
+    bounces: u64,
+    complaints: u64,
+}
+
 struct SesStateInner {
     identities: HashMap<String, EmailIdentity>,
     sent_emails: Vec<StoredEmail>,
+    send_log: VecDeque<SendRecord>,
+    notification_topics: HashMap<String, NotificationTopics>,
+    templates: HashMap<String, EmailTemplateContent>,
+    account_id: String,
+    region: String,
 }
 
 pub struct SesState {
     inner: Arc<Mutex<SesStateInner>>,
+    bus: Arc<ServiceBus>,
+    max_24_hour_send: Option<f64>,
+    max_send_rate: Option<f64>,
+    allow_cross_account: bool,
 }
 
 impl SesState {
-    pub fn new(_account_id: String, _region: String) -> Self {
+    pub fn new(account_id: String, region: String) -> Self {
         SesState {
             inner: Arc::new(Mutex::new(SesStateInner {
                 identities: HashMap::new(),
                 sent_emails: Vec::new(),
+                send_log: VecDeque::new(),
+                notification_topics: HashMap::new(),
+                templates: HashMap::new(),
+                account_id,
+                region,
             })),
+            bus: Arc::new(ServiceBus::new()),
+            max_24_hour_send: None,
+            max_send_rate: None,
+            allow_cross_account: false,
         }
     }
 
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Publishes bounce, complaint and delivery notifications to SNS
+    /// through the shared service bus.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Enforces a sending quota of `max_24_hour_send` recipients in any 24
+    /// hours and `max_send_rate` recipients per second. `None` leaves that
+    /// limit unlimited.
+    pub fn with_send_limits(mut self, max_24_hour_send: Option<f64>, max_send_rate: Option<f64>) -> Self {
+        self.max_24_hour_send = max_24_hour_send;
+        self.max_send_rate = max_send_rate;
+        self
+    }
+
+    /// Accepts notification topics of other accounts and regions.
+    pub fn with_cross_account(mut self, allow: bool) -> Self {
+        self.allow_cross_account = allow;
+        self
+    }
+
     fn identity_type(name: &str) -> &'static str {
         if name.contains('@') { "EMAIL_ADDRESS" } else { "DOMAIN" }
     }
 
-    pub async fn send_email(&self, _req: SendEmailRequest) -> Result<SendEmailResponse, SesError> {
+    /// SES v2 `SendEmail`: maps simple, raw and templated content onto the
+    /// same mailbox as the classic API.
+    pub async fn send_email(&self, req: SendEmailRequest) -> Result<SendEmailResponse, SesError> {
+        let content = req.content;
+        let (from, destination, body) = match (content.simple, content.raw, content.template) {
+            (Some(simple), None, None) => {
+                let body = MessageBody {
+                    subject: Some(simple.subject.data),
+                    text: simple.body.text.map(|c| c.data),
+                    html: simple.body.html.map(|c| c.data),
+                    raw: None,
+                };
+                (req.from_email_address, req.destination.unwrap_or_default(), body)
+            }
+            (None, Some(raw), None) => {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(raw.data.trim())
+                    .map_err(|_| SesError::BadRequestException("Raw message data must be base64-encoded".to_string()))?;
+                let parsed = mime::parse(&String::from_utf8_lossy(&bytes));
+                // Without an explicit destination, SES sends to the To and Cc headers.
+                let destination = req.destination.unwrap_or(EmailDestination {
+                    to_addresses: parsed.to,
+                    cc_addresses: parsed.cc,
+                    bcc_addresses: Vec::new(),
+                });
+                let body = MessageBody { subject: parsed.subject, text: parsed.text, html: parsed.html, raw: Some(raw.data) };
+                (req.from_email_address.or(parsed.from), destination, body)
+            }
+            (None, None, Some(template)) => {
+                let body = self.render_template(&template).await?;
+                (req.from_email_address, req.destination.unwrap_or_default(), body)
+            }
+            _ => {
+                return Err(SesError::BadRequestException(
+                    "Content must specify exactly one of Simple, Raw or Template".to_string(),
+                ))
+            }
+        };
+        let from = from.filter(|f| !f.is_empty()).ok_or_else(|| {
+            SesError::BadRequestException("FromEmailAddress is required".to_string())
+        })?;
+        if destination.recipients().next().is_none() {
+            return Err(SesError::BadRequestException("Destination must contain at least one recipient".to_string()));
+        }
+        let message_id = self.send_message(from, destination, body).await?;
+        Ok(SendEmailResponse { message_id })
+    }
+
+    /// Renders a stored template, replacing `{{name}}` placeholders with
+    /// values from the JSON `TemplateData`.
+    async fn render_template(&self, template: &TemplateContent) -> Result<MessageBody, SesError> {
+        let name = template
+            .template_name
+            .clone()
+            .or_else(|| template.template_arn.as_ref().and_then(|arn| arn.rsplit_once('/')).map(|(_, n)| n.to_string()))
+            .ok_or_else(|| SesError::BadRequestException("TemplateName or TemplateArn is required".to_string()))?;
+        let stored = self
+            .inner
+            .lock()
+            .await
+            .templates
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| SesError::NotFoundException(format!("Template {name} does not exist")))?;
+        let data: Value = match template.template_data.as_deref() {
+            None | Some("") => json!({}),
+            Some(raw) => serde_json::from_str(raw)
+                .map_err(|_| SesError::BadRequestException("TemplateData must be a JSON object".to_string()))?,
+        };
+        let render = |s: Option<String>| s.map(|s| render_placeholders(&s, &data));
+        Ok(MessageBody {
+            subject: render(stored.subject),
+            text: render(stored.text),
+            html: render(stored.html),
+            raw: None,
+        })
+    }
+
+    pub async fn create_email_template(&self, req: CreateEmailTemplateRequest) -> Result<(), SesError> {
+        if req.template_name.is_empty() {
+            return Err(SesError::BadRequestException("TemplateName is required".to_string()));
+        }
+        let mut state = self.inner.lock().await;
+        if state.templates.contains_key(&req.template_name) {
+            return Err(SesError::AlreadyExistsException(format!(
+                "Template {} already exists", req.template_name
+            )));
+        }
+        state.templates.insert(req.template_name, req.template_content);
+        Ok(())
+    }
+
+    pub async fn get_email_template(&self, name: String) -> Result<GetEmailTemplateResponse, SesError> {
+        let state = self.inner.lock().await;
+        let content = state
+            .templates
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| SesError::NotFoundException(format!("Template {name} does not exist")))?;
+        Ok(GetEmailTemplateResponse { template_name: name, template_content: content })
+    }
+
+    pub async fn delete_email_template(&self, name: String) -> Result<(), SesError> {
+        let mut state = self.inner.lock().await;
+        if state.templates.remove(&name).is_none() {
+            return Err(SesError::NotFoundException(format!("Template {name} does not exist")));
+        }
+        Ok(())
+    }
+
+    /// Captures an outgoing message in the mailbox and publishes the
+    /// feedback notifications the mailbox simulator would produce to the
+    /// source identity's notification topics.
+    pub async fn send_message(
+        &self,
+        source: String,
+        destination: EmailDestination,
+        body: MessageBody,
+    ) -> Result<String, SesError> {
         let mut state = self.inner.lock().await;
+        let now = chrono::Utc::now();
+        Self::prune_send_log(&mut state.send_log, now);
+        let outcomes: Vec<Outcome> = destination.recipients().map(|r| Outcome::for_recipient(r)).collect();
+        let recipients = outcomes.len() as u64;
+        self.check_send_limits(&state.send_log, now, recipients)?;
+        state.send_log.push_back(SendRecord {
+            at: now,
+            recipients,
+            bounces: outcomes.iter().filter(|o| **o == Outcome::Bounce).count() as u64,
+            complaints: outcomes.iter().filter(|o| **o == Outcome::Complaint).count() as u64,
+        });
         let message_id = format!("010{}@email.amazonses.com", Uuid::new_v4().to_string().replace('-', ""));
-        state.sent_emails.push(StoredEmail {});
-        Ok(SendEmailResponse { message_id })
+        let email = StoredEmail {
+            message_id: message_id.clone(),
+            source,
+            destination,
+            subject: body.subject,
+            text_body: body.text,
+            html_body: body.html,
+            raw_data: body.raw,
+            timestamp: now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        };
+        let topics = Self::topics_for(&state.notification_topics, &email.source);
+        let notifications = Self::notifications(&email, &topics, &state.account_id, &state.region);
+        state.sent_emails.push(email);
+        drop(state);
+
+        for (topic_arn, notification) in notifications {
+            // Notifications are best effort, as in SES.
+            let _ = self.bus.publish_sns(&topic_arn, None, notification.to_string()).await;
+        }
+        Ok(message_id)
+    }
+
+    fn prune_send_log(log: &mut VecDeque<SendRecord>, now: chrono::DateTime<chrono::Utc>) {
+        let cutoff = now - chrono::Duration::days(STATISTICS_DAYS);
+        while log.front().is_some_and(|r| r.at <= cutoff) {
+            log.pop_front();
+        }
+    }
+
+    fn sent_since(log: &VecDeque<SendRecord>, since: chrono::DateTime<chrono::Utc>) -> u64 {
+        log.iter().rev().take_while(|r| r.at > since).map(|r| r.recipients).sum()
+    }
+
+    /// Rejects a send to `recipients` more addresses that would exceed the
+    /// 24-hour quota or the per-second rate.
+    fn check_send_limits(
+        &self,
+        log: &VecDeque<SendRecord>,
+        now: chrono::DateTime<chrono::Utc>,
+        recipients: u64,
+    ) -> Result<(), SesError> {
+        if let Some(max) = self.max_24_hour_send {
+            if (Self::sent_since(log, now - chrono::Duration::hours(24)) + recipients) as f64 > max {
+                return Err(SesError::LimitExceededException("Daily message quota exceeded.".to_string()));
+            }
+        }
+        if let Some(max) = self.max_send_rate {
+            if (Self::sent_since(log, now - chrono::Duration::seconds(1)) + recipients) as f64 > max {
+                return Err(SesError::Throttling("Maximum sending rate exceeded.".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// `GetSendQuota`: the configured limits and the recipients sent to in
+    /// the last 24 hours.
+    pub async fn get_send_quota(&self) -> SendQuota {
+        let state = self.inner.lock().await;
+        let now = chrono::Utc::now();
+        SendQuota {
+            max_24_hour_send: self.max_24_hour_send.unwrap_or(-1.0),
+            max_send_rate: self.max_send_rate.unwrap_or(-1.0),
+            sent_last_24_hours: Self::sent_since(&state.send_log, now - chrono::Duration::hours(24)) as f64,
+        }
+    }
+
+    /// `GetSendStatistics`: sends of the last two weeks in 15-minute data
+    /// points, oldest first.
+    pub async fn get_send_statistics(&self) -> Vec<SendDataPoint> {
+        let mut state = self.inner.lock().await;
+        Self::prune_send_log(&mut state.send_log, chrono::Utc::now());
+        let mut points: BTreeMap<i64, SendDataPoint> = BTreeMap::new();
+        for record in &state.send_log {
+            let start = record.at.timestamp() - record.at.timestamp().rem_euclid(DATA_POINT_SECONDS);
+            let point = points.entry(start).or_insert_with(|| SendDataPoint {
+                timestamp: chrono::DateTime::from_timestamp(start, 0)
+                    .unwrap_or_default()
+                    .format("%Y-%m-%dT%H:%M:%SZ")
+                    .to_string(),
+                delivery_attempts: 0,
+                bounces: 0,
+                complaints: 0,
+                rejects: 0,
+            });
+            point.delivery_attempts += record.recipients;
+            point.bounces += record.bounces;
+            point.complaints += record.complaints;
+        }
+        points.into_values().collect()
+    }
+
+    /// Topics configured for the sending address, falling back to those of
+    /// its domain.
+    fn topics_for(configured: &HashMap<String, NotificationTopics>, source: &str) -> NotificationTopics {
+        let address = source.rsplit_once('<').map(|(_, a)| a.trim_end_matches('>')).unwrap_or(source);
+        let domain = address.rsplit_once('@').map(|(_, d)| d).unwrap_or_default();
+        let email_topics = configured.get(address).cloned().unwrap_or_default();
+        let domain_topics = configured.get(domain).cloned().unwrap_or_default();
+        NotificationTopics {
+            bounce: email_topics.bounce.or(domain_topics.bounce),
+            complaint: email_topics.complaint.or(domain_topics.complaint),
+            delivery: email_topics.delivery.or(domain_topics.delivery),
+        }
+    }
+
+    /// Builds one SES notification per outcome, in the format SES publishes
+    /// to SNS.
+    fn notifications(
+        email: &StoredEmail,
+        topics: &NotificationTopics,
+        account_id: &str,
+        region: &str,
+    ) -> Vec<(String, Value)> {
+        let recipients: Vec<&String> = email.destination.recipients().collect();
+        let mail = json!({
+            "timestamp": email.timestamp,
+            "source": email.source,
+            "sourceArn": format!("arn:aws:ses:{region}:{account_id}:identity/{}", email.source),
+            "sendingAccountId": account_id,
+            "messageId": email.message_id,
+            "destination": recipients,
+            "headersTruncated": false,
+            "commonHeaders": {
+                "from": [email.source],
+                "to": email.destination.to_addresses,
+                "messageId": email.message_id,
+                "subject": email.subject,
+            },
+        });
+        let with = |outcome: Outcome| -> Vec<&String> {
+            recipients.iter().copied().filter(|r| Outcome::for_recipient(r) == outcome).collect()
+        };
+        let feedback_id = || format!("010{}-000000", Uuid::new_v4().simple());
+
+        let mut notifications = Vec::new();
+        let bounced = with(Outcome::Bounce);
+        if let (Some(topic), false) = (&topics.bounce, bounced.is_empty()) {
+            notifications.push((topic.clone(), json!({
+                "notificationType": "Bounce",
+                "bounce": {
+                    "bounceType": "Permanent",
+                    "bounceSubType": "General",
+                    "bouncedRecipients": bounced.iter().map(|r| json!({
+                        "emailAddress": r,
+                        "action": "failed",
+                        "status": "5.1.1",
+                        "diagnosticCode": "smtp; 550 5.1.1 user unknown",
+                    })).collect::<Vec<_>>(),
+                    "timestamp": email.timestamp,
+                    "feedbackId": feedback_id(),
+                    "remoteMtaIp": "127.0.0.1",
+                    "reportingMTA": "dsn; localhost",
+                },
+                "mail": mail,
+            })));
+        }
+        let complained = with(Outcome::Complaint);
+        if let (Some(topic), false) = (&topics.complaint, complained.is_empty()) {
+            notifications.push((topic.clone(), json!({
+                "notificationType": "Complaint",
+                "complaint": {
+                    "complainedRecipients": complained.iter().map(|r| json!({"emailAddress": r})).collect::<Vec<_>>(),
+                    "timestamp": email.timestamp,
+                    "feedbackId": feedback_id(),
+                    "userAgent": "Amazon SES Mailbox Simulator",
+                    "complaintFeedbackType": "abuse",
+                },
+                "mail": mail,
+            })));
+        }
+        // Complaints are reported for messages that were delivered.
+        let delivered: Vec<&String> = recipients.iter().copied()
+            .filter(|r| Outcome::for_recipient(r) != Outcome::Bounce)
+            .collect();
+        if let (Some(topic), false) = (&topics.delivery, delivered.is_empty()) {
+            notifications.push((topic.clone(), json!({
+                "notificationType": "Delivery",
+                "delivery": {
+                    "timestamp": email.timestamp,
+                    "processingTimeMillis": 0,
+                    "recipients": delivered,
+                    "smtpResponse": "250 2.6.0 Message received",
+                    "remoteMtaIp": "127.0.0.1",
+                    "reportingMTA": "localhost",
+                },
+                "mail": mail,
+            })));
+        }
+        notifications
+    }
+
+    /// Every message sent so far, oldest first.
+    pub async fn sent_emails(&self) -> Vec<StoredEmail> {
+        self.inner.lock().await.sent_emails.clone()
+    }
+
+    pub async fn set_identity_notification_topic(
+        &self,
+        identity: String,
+        notification_type: &str,
+        topic_arn: Option<String>,
+    ) -> Result<(), SesError> {
+        let mut state = self.inner.lock().await;
+        if let Some(arn) = &topic_arn {
+            crate::arn::check_local(arn, &state.account_id, &state.region, self.allow_cross_account)
+                .map_err(SesError::InvalidParameterValue)?;
+        }
+        let topics = state.notification_topics.entry(identity).or_default();
+        match notification_type {
+            "Bounce" => topics.bounce = topic_arn,
+            "Complaint" => topics.complaint = topic_arn,
+            "Delivery" => topics.delivery = topic_arn,
+            other => {
+                return Err(SesError::InvalidParameterValue(format!(
+                    "Invalid notification type: {other}. Must be one of Bounce, Complaint, Delivery"
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn get_identity_notification_topics(
+        &self,
+        identities: &[String],
+    ) -> Vec<(String, NotificationTopics)> {
+        let state = self.inner.lock().await;
+        identities
+            .iter()
+            .map(|id| (id.clone(), state.notification_topics.get(id).cloned().unwrap_or_default()))
+            .collect()
     }
 
     pub async fn create_email_identity(
@@ -128,6 +578,29 @@ impl SesState {
 }
 
 
+/// Replaces `{{name}}` (or `{{a.b}}`) with the matching value in `data`.
+/// Unknown placeholders render as empty strings.
+fn render_placeholders(template: &str, data: &Value) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let path = rest[start + 2..start + len].trim();
+        let value = path.split('.').try_fold(data, |v, key| v.get(key));
+        match value {
+            Some(Value::String(s)) => out.push_str(s),
+            Some(Value::Null) | None => {}
+            Some(other) => out.push_str(&other.to_string()),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,8 +769,201 @@ mod tests {
     #[tokio::test]
     async fn test_send_email_returns_message_id() {
         let state = make_state();
-        let result = state.send_email(SendEmailRequest::default()).await.unwrap();
+        let req: SendEmailRequest = serde_json::from_value(json!({
+            "FromEmailAddress": "sender@example.com",
+            "Destination": {"ToAddresses": ["to@example.com"]},
+            "Content": {"Simple": {"Subject": {"Data": "Hi"}, "Body": {"Text": {"Data": "Hello"}}}},
+        }))
+        .unwrap();
+        let result = state.send_email(req).await.unwrap();
         assert!(!result.message_id.is_empty());
         assert!(result.message_id.contains("@email.amazonses.com"));
     }
+
+    #[tokio::test]
+    async fn test_send_email_requires_content_and_recipients() {
+        let state = make_state();
+        assert!(matches!(
+            state.send_email(SendEmailRequest::default()).await,
+            Err(SesError::BadRequestException(_))
+        ));
+        let no_recipients: SendEmailRequest = serde_json::from_value(json!({
+            "FromEmailAddress": "sender@example.com",
+            "Content": {"Simple": {"Subject": {"Data": "Hi"}, "Body": {}}},
+        }))
+        .unwrap();
+        assert!(state.send_email(no_recipients).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_email_raw_uses_mime_headers() {
+        let state = make_state();
+        let mime = "From: sender@example.com\r\nTo: Someone <to@example.com>\r\nSubject: Raw\r\n\r\nbody";
+        let req: SendEmailRequest = serde_json::from_value(json!({
+            "Content": {"Raw": {"Data": base64::engine::general_purpose::STANDARD.encode(mime)}},
+        }))
+        .unwrap();
+        state.send_email(req).await.unwrap();
+        let sent = state.sent_emails().await;
+        assert_eq!(sent[0].source, "sender@example.com");
+        assert_eq!(sent[0].destination.to_addresses, vec!["to@example.com"]);
+        assert_eq!(sent[0].subject.as_deref(), Some("Raw"));
+        assert_eq!(sent[0].text_body.as_deref(), Some("body"));
+        assert!(sent[0].raw_data.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_send_email_template() {
+        let state = make_state();
+        state
+            .create_email_template(CreateEmailTemplateRequest {
+                template_name: "welcome".into(),
+                template_content: EmailTemplateContent {
+                    subject: Some("Hi {{name}}".into()),
+                    text: Some("Your plan: {{account.plan}}{{missing}}".into()),
+                    html: None,
+                },
+            })
+            .await
+            .unwrap();
+        let req: SendEmailRequest = serde_json::from_value(json!({
+            "FromEmailAddress": "sender@example.com",
+            "Destination": {"ToAddresses": ["to@example.com"]},
+            "Content": {"Template": {
+                "TemplateArn": "arn:aws:ses:us-east-1:123456789012:template/welcome",
+                "TemplateData": "{\"name\": \"Ana\", \"account\": {\"plan\": \"pro\"}}",
+            }},
+        }))
+        .unwrap();
+        state.send_email(req).await.unwrap();
+        let sent = state.sent_emails().await;
+        assert_eq!(sent[0].subject.as_deref(), Some("Hi Ana"));
+        assert_eq!(sent[0].text_body.as_deref(), Some("Your plan: pro"));
+
+        let missing: SendEmailRequest = serde_json::from_value(json!({
+            "FromEmailAddress": "sender@example.com",
+            "Destination": {"ToAddresses": ["to@example.com"]},
+            "Content": {"Template": {"TemplateName": "nope"}},
+        }))
+        .unwrap();
+        assert!(matches!(state.send_email(missing).await, Err(SesError::NotFoundException(_))));
+    }
+
+    #[tokio::test]
+    async fn test_bounce_simulator_notifies_subscribed_queue() {
+        use crate::sns::state::SnsState;
+        use crate::sns::types::{CreateTopicRequest, SubscribeRequest};
+        use crate::sqs::state::SqsState;
+        use crate::sqs::types::{CreateQueueRequest, ReceiveMessageRequest};
+
+        let bus = Arc::new(ServiceBus::new());
+        let sqs = Arc::new(SqsState::new("123456789012".into(), "us-east-1".into(), 9324));
+        let sns = Arc::new(SnsState::new("123456789012".into(), "us-east-1".into()).with_bus(bus.clone()));
+        bus.attach_sqs(sqs.clone());
+        bus.attach_sns(sns.clone());
+        let queue_url = sqs
+            .create_queue(CreateQueueRequest { queue_name: "bounces".into(), attributes: None, tags: None })
+            .await
+            .unwrap()
+            .queue_url;
+        let topic_arn = sns
+            .create_topic(CreateTopicRequest { name: "ses-feedback".into(), ..Default::default() })
+            .await
+            .unwrap()
+            .topic_arn;
+        sns.subscribe(SubscribeRequest {
+            topic_arn: topic_arn.clone(),
+            protocol: "sqs".into(),
+            endpoint: Some("arn:aws:sqs:us-east-1:123456789012:bounces".into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let ses = SesState::new("123456789012".into(), "us-east-1".into()).with_bus(bus);
+        ses.set_identity_notification_topic("example.com".into(), "Bounce", Some(topic_arn)).await.unwrap();
+        let message_id = ses
+            .send_message(
+                "sender@example.com".into(),
+                EmailDestination {
+                    to_addresses: vec!["bounce@simulator.amazonses.com".into(), "success@simulator.amazonses.com".into()],
+                    ..Default::default()
+                },
+                MessageBody {
+                    subject: Some("Hello".into()),
+                    text: Some("Body".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let messages = sqs
+            .receive_message(ReceiveMessageRequest {
+                queue_url,
+                max_number_of_messages: Some(10),
+                wait_time_seconds: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .messages
+            .unwrap_or_default();
+        assert_eq!(messages.len(), 1);
+        let envelope: Value = serde_json::from_str(&messages[0].body).unwrap();
+        assert_eq!(envelope["Type"], "Notification");
+        let notification: Value = serde_json::from_str(envelope["Message"].as_str().unwrap()).unwrap();
+        assert_eq!(notification["notificationType"], "Bounce");
+        assert_eq!(notification["bounce"]["bounceType"], "Permanent");
+        assert_eq!(notification["bounce"]["bouncedRecipients"][0]["emailAddress"], "bounce@simulator.amazonses.com");
+        assert_eq!(notification["mail"]["messageId"], message_id);
+        assert_eq!(ses.sent_emails().await.len(), 1);
+    }
+
+    #[test]
+    fn test_simulator_outcomes() {
+        assert_eq!(Outcome::for_recipient("bounce+test@simulator.amazonses.com"), Outcome::Bounce);
+        assert_eq!(Outcome::for_recipient("complaint@SIMULATOR.amazonses.com"), Outcome::Complaint);
+        assert_eq!(Outcome::for_recipient("success@simulator.amazonses.com"), Outcome::Delivery);
+        assert_eq!(Outcome::for_recipient("bounce@example.com"), Outcome::Delivery);
+    }
+
+    fn to(addresses: &[&str]) -> EmailDestination {
+        EmailDestination { to_addresses: addresses.iter().map(|a| a.to_string()).collect(), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_send_rate_throttles_bursts_and_statistics_count_sends() {
+        let state = make_state().with_send_limits(Some(200.0), Some(2.0));
+        let mut throttled = 0;
+        for address in ["a@example.com", "bounce@simulator.amazonses.com", "b@example.com", "c@example.com"] {
+            match state.send_message("me@example.com".to_string(), to(&[address]), MessageBody::default()).await {
+                Ok(_) => {}
+                Err(SesError::Throttling(m)) => {
+                    assert_eq!(m, "Maximum sending rate exceeded.");
+                    throttled += 1;
+                }
+                Err(e) => panic!("unexpected error: {e:?}"),
+            }
+        }
+        assert_eq!(throttled, 2);
+
+        let quota = state.get_send_quota().await;
+        assert_eq!(quota, SendQuota { max_24_hour_send: 200.0, max_send_rate: 2.0, sent_last_24_hours: 2.0 });
+        let points = state.get_send_statistics().await;
+        assert_eq!(points.len(), 1);
+        assert_eq!((points[0].delivery_attempts, points[0].bounces, points[0].complaints), (2, 1, 0));
+        assert_eq!(state.sent_emails().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_daily_quota_counts_recipients() {
+        let state = make_state().with_send_limits(Some(3.0), None);
+        let two = to(&["a@example.com", "b@example.com"]);
+        state.send_message("me@example.com".to_string(), two.clone(), MessageBody::default()).await.unwrap();
+        let err = state.send_message("me@example.com".to_string(), two, MessageBody::default()).await.unwrap_err();
+        assert!(matches!(err, SesError::LimitExceededException(_)));
+        state.send_message("me@example.com".to_string(), to(&["c@example.com"]), MessageBody::default()).await.unwrap();
+        assert_eq!(state.get_send_quota().await.max_send_rate, -1.0);
+    }
 }
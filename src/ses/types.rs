@@ -4,6 +4,62 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct SendEmailRequest {
+    #[serde(rename = "FromEmailAddress")]
+    pub from_email_address: Option<String>,
+    #[serde(rename = "Destination")]
+    pub destination: Option<EmailDestination>,
+    #[serde(rename = "Content", default)]
+    pub content: EmailContent,
+}
+
+/// Exactly one of `Simple`, `Raw` or `Template` is set.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct EmailContent {
+    #[serde(rename = "Simple")]
+    pub simple: Option<SimpleMessage>,
+    #[serde(rename = "Raw")]
+    pub raw: Option<RawMessage>,
+    #[serde(rename = "Template")]
+    pub template: Option<TemplateContent>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SimpleMessage {
+    #[serde(rename = "Subject")]
+    pub subject: Content,
+    #[serde(rename = "Body", default)]
+    pub body: Body,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Content {
+    #[serde(rename = "Data")]
+    pub data: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Body {
+    #[serde(rename = "Text")]
+    pub text: Option<Content>,
+    #[serde(rename = "Html")]
+    pub html: Option<Content>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RawMessage {
+    /// The MIME message, base64-encoded.
+    #[serde(rename = "Data")]
+    pub data: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TemplateContent {
+    #[serde(rename = "TemplateName")]
+    pub template_name: Option<String>,
+    #[serde(rename = "TemplateArn")]
+    pub template_arn: Option<String>,
+    #[serde(rename = "TemplateData")]
+    pub template_data: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -12,6 +68,33 @@ pub struct SendEmailResponse {
     pub message_id: String,
 }
 
+// Email Template
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct EmailTemplateContent {
+    #[serde(rename = "Subject", skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(rename = "Text", skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(rename = "Html", skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CreateEmailTemplateRequest {
+    #[serde(rename = "TemplateName")]
+    pub template_name: String,
+    #[serde(rename = "TemplateContent")]
+    pub template_content: EmailTemplateContent,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetEmailTemplateResponse {
+    #[serde(rename = "TemplateName")]
+    pub template_name: String,
+    #[serde(rename = "TemplateContent")]
+    pub template_content: EmailTemplateContent,
+}
+
 // Email Identity
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct CreateEmailIdentityRequest {
@@ -82,7 +165,77 @@ pub struct GetEmailIdentityResponse {
     pub tags: Vec<Tag>,
 }
 
-// Stored email record
-#[derive(Debug, Clone)]
+/// The content of an outgoing message, however it was supplied.
+#[derive(Debug, Clone, Default)]
+pub struct MessageBody {
+    pub subject: Option<String>,
+    pub text: Option<String>,
+    pub html: Option<String>,
+    /// The base64 MIME message of a raw send.
+    pub raw: Option<String>,
+}
+
+/// An email captured by the local mailbox instead of being sent.
+#[derive(Debug, Clone, Serialize)]
 pub struct StoredEmail {
+    #[serde(rename = "Id")]
+    pub message_id: String,
+    #[serde(rename = "Source")]
+    pub source: String,
+    #[serde(rename = "Destination")]
+    pub destination: EmailDestination,
+    #[serde(rename = "Subject")]
+    pub subject: Option<String>,
+    #[serde(rename = "TextBody")]
+    pub text_body: Option<String>,
+    #[serde(rename = "HtmlBody")]
+    pub html_body: Option<String>,
+    #[serde(rename = "RawData", skip_serializing_if = "Option::is_none")]
+    pub raw_data: Option<String>,
+    #[serde(rename = "Timestamp")]
+    pub timestamp: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct EmailDestination {
+    #[serde(rename = "ToAddresses", default)]
+    pub to_addresses: Vec<String>,
+    #[serde(rename = "CcAddresses", default)]
+    pub cc_addresses: Vec<String>,
+    #[serde(rename = "BccAddresses", default)]
+    pub bcc_addresses: Vec<String>,
+}
+
+impl EmailDestination {
+    pub fn recipients(&self) -> impl Iterator<Item = &String> {
+        self.to_addresses.iter().chain(&self.cc_addresses).chain(&self.bcc_addresses)
+    }
+}
+
+/// The SNS topics an identity publishes its feedback notifications to.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationTopics {
+    pub bounce: Option<String>,
+    pub complaint: Option<String>,
+    pub delivery: Option<String>,
+}
+
+/// The account's sending limits and usage, as reported by `GetSendQuota`.
+/// A limit of `-1` means unlimited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendQuota {
+    pub max_24_hour_send: f64,
+    pub max_send_rate: f64,
+    pub sent_last_24_hours: f64,
+}
+
+/// Sending activity over one 15-minute interval, as reported by
+/// `GetSendStatistics`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendDataPoint {
+    pub timestamp: String,
+    pub delivery_attempts: u64,
+    pub bounces: u64,
+    pub complaints: u64,
+    pub rejects: u64,
 }
@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use uuid::Uuid;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum SnsError {
@@ -43,38 +44,28 @@ impl SnsError {
     }
 }
 
-impl IntoResponse for SnsError {
-    fn into_response(self) -> Response {
-        let request_id = Uuid::new_v4().to_string();
-        let body = format!(
-            r#"<ErrorResponse xmlns="http://sns.amazonaws.com/doc/2010-03-31/">
-  <Error>
-    <Type>{}</Type>
-    <Code>{}</Code>
-    <Message>{}</Message>
-  </Error>
-  <RequestId>{}</RequestId>
-</ErrorResponse>"#,
-            self.error_type(),
-            self.error_code(),
-            xml_escape(self.message()),
-            request_id,
-        );
-        (
-            self.status_code(),
-            [("content-type", "text/xml")],
-            body,
-        )
-            .into_response()
+impl AwsError for SnsError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        SnsError::message(self)
+    }
+
+    fn sender_fault(&self) -> bool {
+        self.error_type() == "Sender"
     }
 }
 
-fn xml_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&apos;")
+impl IntoResponse for SnsError {
+    fn into_response(self) -> Response {
+        error_response(&self, Protocol::Query("http://sns.amazonaws.com/doc/2010-03-31/"))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// How long one HTTP delivery attempt may take end to end.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// POSTs `body` to a plain `http://` endpoint and returns the response
+/// status. `https` endpoints are not supported.
+pub async fn post(endpoint: &str, headers: &[(&str, &str)], body: &str) -> Result<u16, String> {
+    let rest = endpoint
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Unsupported endpoint: {endpoint}"))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+
+    let mut request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {authority}\r\nContent-Type: text/plain; charset=UTF-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+
+    let exchange = async {
+        let mut stream = TcpStream::connect(&address).await.map_err(|e| e.to_string())?;
+        stream.write_all(request.as_bytes()).await.map_err(|e| e.to_string())?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+        let response = String::from_utf8_lossy(&response);
+        response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse::<u16>().ok())
+            .ok_or_else(|| "Malformed HTTP response".to_string())
+    };
+    tokio::time::timeout(DELIVERY_TIMEOUT, exchange)
+        .await
+        .map_err(|_| format!("Timed out delivering to {endpoint}"))?
+}
@@ -94,7 +94,7 @@ fn parse_batch_entries(params: &Params, prefix: &str) -> Vec<PublishBatchEntry>
                 entries.push(PublishBatchEntry {
                     id: id.clone(),
                     message: msg.clone(),
-                    _subject: params.get(&subject_key).cloned(),
+                    subject: params.get(&subject_key).cloned(),
                     _message_attributes: None,
                     _message_deduplication_id: params.get(&dedup_key).cloned(),
                     message_group_id: params.get(&group_key).cloned(),
@@ -365,7 +365,7 @@ async fn handle_list_subscriptions(
     params: Params,
 ) -> Result<Response, SnsError> {
     let req = ListSubscriptionsRequest {
-        _next_token: param(&params, "NextToken"),
+        next_token: param(&params, "NextToken"),
     };
     let resp = state.list_subscriptions(req).await?;
     Ok(xml_ok(
@@ -380,7 +380,7 @@ async fn handle_list_subscriptions_by_topic(
 ) -> Result<Response, SnsError> {
     let req = ListSubscriptionsByTopicRequest {
         topic_arn: require(&params, "TopicArn")?,
-        _next_token: param(&params, "NextToken"),
+        next_token: param(&params, "NextToken"),
     };
     let resp = state.list_subscriptions_by_topic(req).await?;
     Ok(xml_ok(
@@ -446,7 +446,7 @@ async fn handle_publish(
         topic_arn: param(&params, "TopicArn"),
         target_arn: param(&params, "TargetArn"),
         message: require(&params, "Message")?,
-        _subject: param(&params, "Subject"),
+        subject: param(&params, "Subject"),
         _message_structure: param(&params, "MessageStructure"),
         _message_attributes: None,
         _message_deduplication_id: param(&params, "MessageDeduplicationId"),
@@ -575,6 +575,7 @@ async fn handle_list_tags_for_resource(
 pub fn create_router(state: Arc<SnsState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("sns"))
         .with_state(state)
 }
 
@@ -627,6 +628,76 @@ mod tests {
             .unwrap()
     }
 
+    #[tokio::test]
+    async fn test_lambda_subscription_with_stubbed_function_through_routers() {
+        use crate::sqs::types::{CreateQueueRequest, ReceiveMessageRequest};
+        use serde_json::json;
+
+        let sqs = Arc::new(crate::sqs::state::SqsState::new("123456789012".into(), "us-east-1".into(), 9324));
+        let dlq_url = sqs
+            .create_queue(CreateQueueRequest { queue_name: "alerts-dlq".into(), attributes: None, tags: None })
+            .await
+            .unwrap()
+            .queue_url;
+        let lambda = Arc::new(crate::lambda::state::LambdaState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        ));
+        let lambda_app = crate::lambda::server::create_router(lambda.clone());
+        crate::lambda::server::create_stubbed_function(&lambda_app, "pager", json!({ "mode": "echo" })).await;
+        let bus = Arc::new(crate::bus::ServiceBus::new());
+        bus.attach_sqs(sqs.clone());
+        bus.attach_lambda(lambda);
+        let app = create_router(Arc::new(
+            SnsState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus),
+        ));
+        let call = |params: &[(&str, &str)]| {
+            let body = form_urlencoded::Serializer::new(String::new()).extend_pairs(params).finish();
+            let resp = app.clone().oneshot(sns_req(&body));
+            async move { assert_eq!(resp.await.unwrap().status(), StatusCode::OK) }
+        };
+        let topic_arn = "arn:aws:sns:us-east-1:123456789012:alerts";
+
+        call(&[("Action", "CreateTopic"), ("Name", "alerts")]).await;
+        call(&[
+            ("Action", "Subscribe"),
+            ("TopicArn", topic_arn),
+            ("Protocol", "lambda"),
+            ("Endpoint", "arn:aws:lambda:us-east-1:123456789012:function:pager"),
+            ("Attributes.entry.1.key", "RedrivePolicy"),
+            ("Attributes.entry.1.value", r#"{"deadLetterTargetArn":"arn:aws:sqs:us-east-1:123456789012:alerts-dlq"}"#),
+            ("Attributes.entry.2.key", "DeliveryPolicy"),
+            ("Attributes.entry.2.value", r#"{"healthyRetryPolicy":{"minDelayTarget":0,"maxDelayTarget":0}}"#),
+        ])
+        .await;
+        // The echo stub succeeds, so "disk ok" is never redriven.
+        call(&[("Action", "Publish"), ("TopicArn", topic_arn), ("Message", "disk ok")]).await;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let stub = json!({ "mode": "error", "errorMessage": "pager offline" });
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_aws/lambda/functions/pager/stub")
+            .body(Body::from(stub.to_string()))
+            .unwrap();
+        assert!(lambda_app.oneshot(req).await.unwrap().status().is_success());
+        call(&[("Action", "Publish"), ("TopicArn", topic_arn), ("Message", "disk full")]).await;
+
+        let messages = sqs
+            .receive_message(ReceiveMessageRequest {
+                queue_url: dlq_url,
+                max_number_of_messages: Some(10),
+                wait_time_seconds: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .messages
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        let envelope: serde_json::Value = serde_json::from_str(&messages[0].body).unwrap();
+        assert_eq!(envelope["Message"], "disk full");
+    }
+
     #[tokio::test]
     async fn test_create_topic() {
         let app = create_router(new_state());
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::AbortHandle;
 use uuid::Uuid;
 
+use crate::bus::ServiceBus;
 use crate::sns::error::SnsError;
 use crate::sns::topic::*;
 use crate::sns::types::*;
@@ -12,10 +15,41 @@ struct SnsStateInner {
     account_id: String,
     region: String,
     sequence_counter: u64,
+    /// Queues of pending deliveries by subscription ARN. Each is worked
+    /// through in order by its own task, so retries against a failing
+    /// endpoint only hold up that subscription's messages.
+    delivery_queues: HashMap<String, DeliveryQueue>,
+}
+
+struct DeliveryQueue {
+    sender: mpsc::UnboundedSender<Delivery>,
+    /// The task working through the queue; it stops by itself once the
+    /// sender is dropped and the queue is drained.
+    worker: AbortHandle,
 }
 
 pub struct SnsState {
     inner: Arc<Mutex<SnsStateInner>>,
+    bus: Arc<ServiceBus>,
+    allow_cross_account: bool,
+}
+
+/// Page size of `ListSubscriptions` and `ListSubscriptionsByTopic`.
+const SUBSCRIPTIONS_PER_PAGE: usize = 100;
+
+/// A message to hand to one subscriber once the state lock is released.
+struct Delivery {
+    protocol: String,
+    endpoint: String,
+    payload: String,
+    raw: bool,
+    message_id: String,
+    topic_arn: String,
+    subscription_arn: String,
+    /// The wait before each attempt made after the first one fails.
+    retry_delays: Vec<Duration>,
+    /// Where the payload goes once every attempt has failed.
+    dead_letter_target_arn: Option<String>,
 }
 
 impl SnsState {
@@ -26,7 +60,167 @@ impl SnsState {
                 account_id,
                 region,
                 sequence_counter: 0,
+                delivery_queues: HashMap::new(),
             })),
+            bus: Arc::new(ServiceBus::new()),
+            allow_cross_account: false,
+        }
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        for queue in state.delivery_queues.values() {
+            queue.worker.abort();
+        }
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// ARNs and tags of every topic that carries at least one tag.
+    pub async fn tagged_resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let state = self.inner.lock().await;
+        state
+            .topics
+            .values()
+            .filter(|topic| !topic.tags.is_empty())
+            .map(|topic| (topic.arn.clone(), topic.tags.clone()))
+            .collect()
+    }
+
+    /// Delivers published messages to `sqs` and `lambda` subscriptions
+    /// through the shared service bus.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Accepts dead-letter queue ARNs of other accounts and regions.
+    pub fn with_cross_account(mut self, allow: bool) -> Self {
+        self.allow_cross_account = allow;
+        self
+    }
+
+    /// Checks the dead-letter queue of a `RedrivePolicy` subscription
+    /// attribute; other attributes, and malformed policies, are left to
+    /// `SubscriptionAttributes::set`.
+    fn check_dead_letter_target(&self, inner: &SnsStateInner, name: &str, value: &str) -> Result<(), SnsError> {
+        if name != "RedrivePolicy" {
+            return Ok(());
+        }
+        let policy: serde_json::Value = serde_json::from_str(value).unwrap_or_default();
+        let Some(arn) = policy["deadLetterTargetArn"].as_str() else {
+            return Ok(());
+        };
+        crate::arn::check_local(arn, &inner.account_id, &inner.region, self.allow_cross_account)
+            .map(|_| ())
+            .map_err(|reason| SnsError::InvalidParameter(format!("Invalid parameter: RedrivePolicy: {reason}")))
+    }
+
+    /// Builds the per-subscriber payloads for a message published to
+    /// `topic`: the SNS notification envelope, or the bare message for
+    /// subscriptions with `RawMessageDelivery`. Only `sqs`, `lambda` and
+    /// `http` subscriptions are delivered to.
+    fn deliveries(topic: &Topic, region: &str, message_id: &str, subject: Option<&str>, message: &str) -> Vec<Delivery> {
+        topic
+            .subscriptions
+            .values()
+            .filter(|sub| sub.confirmed && matches!(sub.protocol.as_str(), "sqs" | "lambda" | "http"))
+            .map(|sub| {
+                let payload = if sub.attributes.raw_message_delivery {
+                    message.to_string()
+                } else {
+                    let mut envelope = serde_json::json!({
+                        "Type": "Notification",
+                        "MessageId": message_id,
+                        "TopicArn": topic.arn,
+                        "Message": message,
+                        "Timestamp": chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+                        "SignatureVersion": "1",
+                        "Signature": "EXAMPLE",
+                        "SigningCertURL": format!("https://sns.{region}.amazonaws.com/SimpleNotificationService.pem"),
+                        "UnsubscribeURL": format!(
+                            "https://sns.{region}.amazonaws.com/?Action=Unsubscribe&SubscriptionArn={}",
+                            sub.arn
+                        ),
+                    });
+                    if let Some(subject) = subject {
+                        envelope["Subject"] = serde_json::Value::String(subject.to_string());
+                    }
+                    envelope.to_string()
+                };
+                Delivery {
+                    protocol: sub.protocol.clone(),
+                    endpoint: sub.endpoint.clone(),
+                    payload,
+                    raw: sub.attributes.raw_message_delivery,
+                    message_id: message_id.to_string(),
+                    topic_arn: topic.arn.clone(),
+                    subscription_arn: sub.arn.clone(),
+                    retry_delays: sub.attributes.retry_delays(),
+                    dead_letter_target_arn: sub.attributes.dead_letter_target_arn(),
+                }
+            })
+            .collect()
+    }
+
+    /// Makes one delivery attempt; HTTP endpoints fail on any non-2xx status.
+    async fn attempt(bus: &ServiceBus, delivery: &Delivery) -> Result<(), String> {
+        if delivery.protocol != "http" {
+            return bus.deliver(&delivery.endpoint, &delivery.payload).await;
+        }
+        let mut headers = vec![
+            ("x-amz-sns-message-type", "Notification"),
+            ("x-amz-sns-message-id", delivery.message_id.as_str()),
+            ("x-amz-sns-topic-arn", delivery.topic_arn.as_str()),
+            ("x-amz-sns-subscription-arn", delivery.subscription_arn.as_str()),
+        ];
+        if delivery.raw {
+            headers.push(("x-amz-sns-rawdelivery", "true"));
+        }
+        match super::http::post(&delivery.endpoint, &headers, &delivery.payload).await? {
+            200..=299 => Ok(()),
+            status => Err(format!("Endpoint returned HTTP {status}")),
+        }
+    }
+
+    /// Delivers one message, retrying failures after the subscription's
+    /// retry delays. A message that exhausts its retries is sent to the
+    /// subscription's dead-letter queue, or dropped when it has none.
+    async fn deliver(bus: &ServiceBus, delivery: Delivery) {
+        let mut result = Self::attempt(bus, &delivery).await;
+        for delay in &delivery.retry_delays {
+            if result.is_ok() {
+                break;
+            }
+            tokio::time::sleep(*delay).await;
+            result = Self::attempt(bus, &delivery).await;
+        }
+        if let (Err(error), Some(dlq)) = (result, &delivery.dead_letter_target_arn) {
+            tracing::debug!(
+                "Redriving message {} for {} to {dlq}: {error}",
+                delivery.message_id,
+                delivery.subscription_arn
+            );
+            let _ = bus.deliver(dlq, &delivery.payload).await;
+        }
+    }
+
+    /// Queues `deliveries` behind any earlier ones for the same
+    /// subscriptions and returns without waiting for them.
+    fn dispatch(&self, inner: &mut SnsStateInner, deliveries: Vec<Delivery>) {
+        for delivery in deliveries {
+            let queue = inner.delivery_queues.entry(delivery.subscription_arn.clone()).or_insert_with(|| {
+                let (sender, mut rx) = mpsc::unbounded_channel::<Delivery>();
+                let bus = self.bus.clone();
+                let worker = tokio::spawn(async move {
+                    while let Some(delivery) = rx.recv().await {
+                        Self::deliver(&bus, delivery).await;
+                    }
+                });
+                DeliveryQueue { sender, worker: worker.abort_handle() }
+            });
+            let _ = queue.sender.send(delivery);
         }
     }
 
@@ -106,7 +300,11 @@ impl SnsState {
 
     pub async fn delete_topic(&self, req: DeleteTopicRequest) -> Result<(), SnsError> {
         let mut inner = self.inner.lock().await;
-        inner.topics.remove(&req.topic_arn);
+        if let Some(topic) = inner.topics.remove(&req.topic_arn) {
+            for arn in topic.subscriptions.keys() {
+                inner.delivery_queues.remove(arn);
+            }
+        }
         Ok(())
     }
 
@@ -201,6 +399,10 @@ impl SnsState {
         req: SubscribeRequest,
     ) -> Result<SubscribeResponse, SnsError> {
         let mut inner = self.inner.lock().await;
+        let attributes = req.attributes.unwrap_or_default();
+        for (key, value) in &attributes {
+            self.check_dead_letter_target(&inner, key, value)?;
+        }
         let region = inner.region.clone();
         let account_id = inner.account_id.clone();
         let topic = inner.topics.get_mut(&req.topic_arn).ok_or_else(|| {
@@ -218,24 +420,8 @@ impl SnsState {
             &account_id,
         );
 
-        if let Some(attrs) = req.attributes {
-            for (key, value) in attrs {
-                match key.as_str() {
-                    "RawMessageDelivery" => {
-                        sub.attributes.raw_message_delivery = value == "true";
-                    }
-                    "FilterPolicy" => {
-                        sub.attributes.filter_policy = Some(value);
-                    }
-                    "FilterPolicyScope" => {
-                        sub.attributes.filter_policy_scope = value;
-                    }
-                    "RedrivePolicy" => {
-                        sub.attributes.redrive_policy = Some(value);
-                    }
-                    _ => {}
-                }
-            }
+        for (key, value) in attributes {
+            sub.attributes.set(&key, value)?;
         }
 
         let arn = sub.arn.clone();
@@ -251,6 +437,7 @@ impl SnsState {
         // Find the topic that contains this subscription
         for topic in inner.topics.values_mut() {
             if topic.subscriptions.remove(&req.subscription_arn).is_some() {
+                inner.delivery_queues.remove(&req.subscription_arn);
                 return Ok(());
             }
         }
@@ -287,7 +474,7 @@ impl SnsState {
 
     pub async fn list_subscriptions(
         &self,
-        _req: ListSubscriptionsRequest,
+        req: ListSubscriptionsRequest,
     ) -> Result<ListSubscriptionsResponse, SnsError> {
         let inner = self.inner.lock().await;
         let mut entries = Vec::new();
@@ -302,11 +489,7 @@ impl SnsState {
                 });
             }
         }
-        entries.sort_by(|a, b| a.subscription_arn.cmp(&b.subscription_arn));
-        Ok(ListSubscriptionsResponse {
-            subscriptions: entries,
-            next_token: None,
-        })
+        Ok(subscriptions_page(entries, req.next_token.as_deref()))
     }
 
     pub async fn list_subscriptions_by_topic(
@@ -318,7 +501,7 @@ impl SnsState {
             SnsError::NotFound("Topic does not exist".into())
         })?;
 
-        let mut entries: Vec<SubscriptionEntry> = topic
+        let entries: Vec<SubscriptionEntry> = topic
             .subscriptions
             .values()
             .map(|sub| SubscriptionEntry {
@@ -329,12 +512,7 @@ impl SnsState {
                 topic_arn: sub.topic_arn.clone(),
             })
             .collect();
-        entries.sort_by(|a, b| a.subscription_arn.cmp(&b.subscription_arn));
-
-        Ok(ListSubscriptionsResponse {
-            subscriptions: entries,
-            next_token: None,
-        })
+        Ok(subscriptions_page(entries, req.next_token.as_deref()))
     }
 
     pub async fn get_subscription_attributes(
@@ -357,37 +535,12 @@ impl SnsState {
         req: SetSubscriptionAttributesRequest,
     ) -> Result<(), SnsError> {
         let mut inner = self.inner.lock().await;
+        if let Some(value) = &req.attribute_value {
+            self.check_dead_letter_target(&inner, &req.attribute_name, value)?;
+        }
         for topic in inner.topics.values_mut() {
             if let Some(sub) = topic.subscriptions.get_mut(&req.subscription_arn) {
-                let value = req.attribute_value.unwrap_or_default();
-                match req.attribute_name.as_str() {
-                    "RawMessageDelivery" => {
-                        sub.attributes.raw_message_delivery = value == "true";
-                    }
-                    "FilterPolicy" => {
-                        sub.attributes.filter_policy = if value.is_empty() {
-                            None
-                        } else {
-                            Some(value)
-                        };
-                    }
-                    "FilterPolicyScope" => {
-                        sub.attributes.filter_policy_scope = value;
-                    }
-                    "RedrivePolicy" => {
-                        sub.attributes.redrive_policy = if value.is_empty() {
-                            None
-                        } else {
-                            Some(value)
-                        };
-                    }
-                    _ => {
-                        return Err(SnsError::InvalidParameter(format!(
-                            "Invalid attribute name: {}",
-                            req.attribute_name
-                        )));
-                    }
-                }
+                sub.attributes.set(&req.attribute_name, req.attribute_value.unwrap_or_default())?;
                 return Ok(());
             }
         }
@@ -429,12 +582,15 @@ impl SnsState {
         }
 
         let message_id = Uuid::new_v4().to_string();
+        let deliveries =
+            Self::deliveries(topic, &inner.region, &message_id, req.subject.as_deref(), &req.message);
         let sequence_number = if topic.attributes.fifo_topic {
             inner.sequence_counter += 1;
             Some(format!("{:020}", inner.sequence_counter))
         } else {
             None
         };
+        self.dispatch(&mut inner, deliveries);
 
         Ok(PublishResponse {
             message_id,
@@ -463,8 +619,10 @@ impl SnsState {
         }
 
         let is_fifo = topic.attributes.fifo_topic;
+        let topic = topic.clone();
         let mut successful = Vec::new();
         let mut failed = Vec::new();
+        let mut deliveries = Vec::new();
 
         for entry in req.publish_batch_request_entries {
             if entry.message.is_empty() {
@@ -488,6 +646,13 @@ impl SnsState {
             }
 
             let message_id = Uuid::new_v4().to_string();
+            deliveries.extend(Self::deliveries(
+                &topic,
+                &inner.region,
+                &message_id,
+                entry.subject.as_deref(),
+                &entry.message,
+            ));
             let sequence_number = if is_fifo {
                 inner.sequence_counter += 1;
                 Some(format!("{:020}", inner.sequence_counter))
@@ -501,6 +666,7 @@ impl SnsState {
                 sequence_number,
             });
         }
+        self.dispatch(&mut inner, deliveries);
 
         Ok(PublishBatchResponse {
             successful,
@@ -562,6 +728,17 @@ impl SnsState {
     }
 }
 
+/// One page of subscriptions ordered by ARN. The `NextToken` is the ARN
+/// that starts the next page.
+fn subscriptions_page(mut entries: Vec<SubscriptionEntry>, next_token: Option<&str>) -> ListSubscriptionsResponse {
+    entries.sort_by(|a, b| a.subscription_arn.cmp(&b.subscription_arn));
+    let start = next_token.map_or(0, |token| entries.partition_point(|e| e.subscription_arn.as_str() < token));
+    let mut page: Vec<SubscriptionEntry> = entries.drain(start..).collect();
+    let next_token = page.get(SUBSCRIPTIONS_PER_PAGE).map(|e| e.subscription_arn.clone());
+    page.truncate(SUBSCRIPTIONS_PER_PAGE);
+    ListSubscriptionsResponse { subscriptions: page, next_token }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -1014,7 +1191,7 @@ mod tests {
                 PublishBatchEntry {
                     id: "1".to_string(),
                     message: "msg1".to_string(),
-                    _subject: None,
+                    subject: None,
                     _message_attributes: None,
                     _message_deduplication_id: None,
                     message_group_id: None,
@@ -1022,7 +1199,7 @@ mod tests {
                 PublishBatchEntry {
                     id: "2".to_string(),
                     message: "msg2".to_string(),
-                    _subject: None,
+                    subject: None,
                     _message_attributes: None,
                     _message_deduplication_id: None,
                     message_group_id: None,
@@ -1051,7 +1228,7 @@ mod tests {
         let entries: Vec<PublishBatchEntry> = (0..11).map(|i| PublishBatchEntry {
             id: format!("{}", i),
             message: format!("msg{}", i),
-            _subject: None,
+            subject: None,
             _message_attributes: None,
             _message_deduplication_id: None,
             message_group_id: None,
@@ -1073,7 +1250,7 @@ mod tests {
                 PublishBatchEntry {
                     id: "ok".to_string(),
                     message: "valid".to_string(),
-                    _subject: None,
+                    subject: None,
                     _message_attributes: None,
                     _message_deduplication_id: None,
                     message_group_id: None,
@@ -1081,7 +1258,7 @@ mod tests {
                 PublishBatchEntry {
                     id: "bad".to_string(),
                     message: "".to_string(),
-                    _subject: None,
+                    subject: None,
                     _message_attributes: None,
                     _message_deduplication_id: None,
                     message_group_id: None,
@@ -1101,7 +1278,7 @@ mod tests {
             publish_batch_request_entries: vec![PublishBatchEntry {
                 id: "1".to_string(),
                 message: "msg".to_string(),
-                _subject: None,
+                subject: None,
                 _message_attributes: None,
                 _message_deduplication_id: None,
                 message_group_id: None,
@@ -1185,4 +1362,377 @@ mod tests {
         }).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_publish_fans_out_to_sqs_subscriptions() {
+        use crate::sqs::state::SqsState;
+        use crate::sqs::types::{CreateQueueRequest, ReceiveMessageRequest};
+
+        let bus = Arc::new(ServiceBus::new());
+        let sqs = Arc::new(SqsState::new("123456789012".into(), "us-east-1".into(), 9324));
+        bus.attach_sqs(sqs.clone());
+        let state = SnsState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let topic_arn = state
+            .create_topic(CreateTopicRequest { name: "events".into(), ..Default::default() })
+            .await
+            .unwrap()
+            .topic_arn;
+        let mut urls = Vec::new();
+        for (queue, raw) in [("wrapped", "false"), ("raw", "true")] {
+            urls.push(
+                sqs.create_queue(CreateQueueRequest { queue_name: queue.into(), attributes: None, tags: None })
+                    .await
+                    .unwrap()
+                    .queue_url,
+            );
+            state
+                .subscribe(SubscribeRequest {
+                    topic_arn: topic_arn.clone(),
+                    protocol: "sqs".into(),
+                    endpoint: Some(format!("arn:aws:sqs:us-east-1:123456789012:{queue}")),
+                    attributes: Some(HashMap::from([("RawMessageDelivery".to_string(), raw.to_string())])),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        let published = state
+            .publish(PublishRequest {
+                topic_arn: Some(topic_arn.clone()),
+                message: "hello".into(),
+                subject: Some("greeting".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let mut bodies = Vec::new();
+        for url in urls {
+            let messages = sqs
+                .receive_message(ReceiveMessageRequest { queue_url: url, wait_time_seconds: Some(1), ..Default::default() })
+                .await
+                .unwrap()
+                .messages
+                .unwrap_or_default();
+            assert_eq!(messages.len(), 1);
+            bodies.push(messages[0].body.clone());
+        }
+        let envelope: serde_json::Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(envelope["MessageId"], published.message_id);
+        assert_eq!(envelope["TopicArn"], topic_arn);
+        assert_eq!(envelope["Subject"], "greeting");
+        assert_eq!(envelope["Message"], "hello");
+        assert_eq!(bodies[1], "hello");
+    }
+
+    #[tokio::test]
+    async fn test_set_raw_message_delivery_applies_to_next_publish() {
+        use crate::sqs::state::SqsState;
+        use crate::sqs::types::{CreateQueueRequest, ReceiveMessageRequest};
+
+        let bus = Arc::new(ServiceBus::new());
+        let sqs = Arc::new(SqsState::new("123456789012".into(), "us-east-1".into(), 9324));
+        bus.attach_sqs(sqs.clone());
+        let state = SnsState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let topic_arn = create_topic(&state, "orders").await;
+        let queue_url = sqs
+            .create_queue(CreateQueueRequest { queue_name: "orders-q".into(), attributes: None, tags: None })
+            .await
+            .unwrap()
+            .queue_url;
+        let sub = state
+            .subscribe(SubscribeRequest {
+                topic_arn: topic_arn.clone(),
+                protocol: "sqs".into(),
+                endpoint: Some("arn:aws:sqs:us-east-1:123456789012:orders-q".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let publish_and_receive = || async {
+            state
+                .publish(PublishRequest { topic_arn: Some(topic_arn.clone()), message: "order-1".into(), ..Default::default() })
+                .await
+                .unwrap();
+            let messages = sqs
+                .receive_message(ReceiveMessageRequest {
+                    queue_url: queue_url.clone(),
+                    wait_time_seconds: Some(1),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .messages
+                .unwrap();
+            sqs.delete_message(crate::sqs::types::DeleteMessageRequest {
+                queue_url: queue_url.clone(),
+                receipt_handle: messages[0].receipt_handle.clone(),
+            })
+            .await
+            .unwrap();
+            messages[0].body.clone()
+        };
+
+        let wrapped: serde_json::Value = serde_json::from_str(&publish_and_receive().await).unwrap();
+        assert_eq!(wrapped["Message"], "order-1");
+
+        state
+            .set_subscription_attributes(SetSubscriptionAttributesRequest {
+                subscription_arn: sub.subscription_arn.clone(),
+                attribute_name: "RawMessageDelivery".into(),
+                attribute_value: Some("true".into()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(publish_and_receive().await, "order-1");
+        let attrs = state
+            .get_subscription_attributes(GetSubscriptionAttributesRequest { subscription_arn: sub.subscription_arn })
+            .await
+            .unwrap()
+            .attributes;
+        assert_eq!(attrs["RawMessageDelivery"], "true");
+    }
+
+    #[tokio::test]
+    async fn test_failed_http_delivery_is_redriven_to_dead_letter_queue() {
+        use crate::sqs::state::SqsState;
+        use crate::sqs::types::{CreateQueueRequest, ReceiveMessageRequest};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { axum::http::StatusCode::INTERNAL_SERVER_ERROR }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let bus = Arc::new(ServiceBus::new());
+        let sqs = Arc::new(SqsState::new("123456789012".into(), "us-east-1".into(), 9324));
+        bus.attach_sqs(sqs.clone());
+        let state = SnsState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let topic_arn = create_topic(&state, "alerts").await;
+        let dlq_url = sqs
+            .create_queue(CreateQueueRequest { queue_name: "alerts-dlq".into(), attributes: None, tags: None })
+            .await
+            .unwrap()
+            .queue_url;
+        state
+            .subscribe(SubscribeRequest {
+                topic_arn: topic_arn.clone(),
+                protocol: "http".into(),
+                endpoint: Some(endpoint),
+                attributes: Some(HashMap::from([
+                    (
+                        "RedrivePolicy".to_string(),
+                        r#"{"deadLetterTargetArn":"arn:aws:sqs:us-east-1:123456789012:alerts-dlq"}"#.to_string(),
+                    ),
+                    (
+                        "DeliveryPolicy".to_string(),
+                        r#"{"healthyRetryPolicy":{"minDelayTarget":0,"maxDelayTarget":0}}"#.to_string(),
+                    ),
+                ])),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let published = state
+            .publish(PublishRequest { topic_arn: Some(topic_arn), message: "disk full".into(), ..Default::default() })
+            .await
+            .unwrap();
+
+        let messages = sqs
+            .receive_message(ReceiveMessageRequest { queue_url: dlq_url, wait_time_seconds: Some(2), ..Default::default() })
+            .await
+            .unwrap()
+            .messages
+            .unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 1 + crate::sns::topic::DEFAULT_DELIVERY_RETRIES as usize);
+        assert_eq!(messages.len(), 1);
+        let envelope: serde_json::Value = serde_json::from_str(&messages[0].body).unwrap();
+        assert_eq!(envelope["Message"], "disk full");
+        assert_eq!(envelope["MessageId"], published.message_id);
+    }
+
+    #[tokio::test]
+    async fn test_reset_stops_delivery_retries() {
+        let state = make_state();
+        let topic_arn = create_topic(&state, "alerts").await;
+        let sub = state
+            .subscribe(SubscribeRequest {
+                topic_arn: topic_arn.clone(),
+                protocol: "sqs".into(),
+                endpoint: Some("arn:aws:sqs:us-east-1:123456789012:no-such-queue".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        state
+            .publish(PublishRequest { topic_arn: Some(topic_arn), message: "lost".into(), ..Default::default() })
+            .await
+            .unwrap();
+        // Let the worker fail its first attempt and start waiting to retry.
+        tokio::task::yield_now().await;
+        let worker = state.inner.lock().await.delivery_queues[&sub.subscription_arn].worker.clone();
+        assert!(!worker.is_finished());
+
+        state.reset().await;
+        tokio::task::yield_now().await;
+        assert!(worker.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_publish_does_not_wait_for_failing_endpoints() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { axum::http::StatusCode::SERVICE_UNAVAILABLE }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let state = make_state();
+        let topic_arn = create_topic(&state, "slow").await;
+        state.subscribe(SubscribeRequest {
+            topic_arn: topic_arn.clone(),
+            protocol: "http".into(),
+            endpoint: Some(endpoint),
+            ..Default::default()
+        }).await.unwrap();
+
+        let started = std::time::Instant::now();
+        for message in ["one", "two"] {
+            state
+                .publish(PublishRequest { topic_arn: Some(topic_arn.clone()), message: message.into(), ..Default::default() })
+                .await
+                .unwrap();
+        }
+        assert!(started.elapsed() < Duration::from_secs(1));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        // The first message waits out the default retry delay; the second
+        // queues behind it.
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscription_attribute_values_are_validated() {
+        let state = make_state();
+        let arn = create_topic(&state, "validate").await;
+        let sub = state.subscribe(SubscribeRequest {
+            topic_arn: arn.clone(),
+            protocol: "sqs".to_string(),
+            endpoint: Some("arn:aws:sqs:us-east-1:123456789012:q".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        let set = |name: &str, value: &str| SetSubscriptionAttributesRequest {
+            subscription_arn: sub.subscription_arn.clone(),
+            attribute_name: name.to_string(),
+            attribute_value: Some(value.to_string()),
+        };
+        assert!(state.set_subscription_attributes(set("RawMessageDelivery", "yes")).await.is_err());
+        assert!(state.set_subscription_attributes(set("FilterPolicy", "{not json")).await.is_err());
+        assert!(state.set_subscription_attributes(set("RedrivePolicy", "{}")).await.is_err());
+        let delivery_policy = r#"{"healthyRetryPolicy":{"numRetries":3}}"#;
+        state.set_subscription_attributes(set("DeliveryPolicy", delivery_policy)).await.unwrap();
+        let attrs = state
+            .get_subscription_attributes(GetSubscriptionAttributesRequest { subscription_arn: sub.subscription_arn.clone() })
+            .await
+            .unwrap()
+            .attributes;
+        assert_eq!(attrs["DeliveryPolicy"], delivery_policy);
+
+        let result = state.subscribe(SubscribeRequest {
+            topic_arn: arn,
+            protocol: "sqs".to_string(),
+            endpoint: Some("arn:aws:sqs:us-east-1:123456789012:q2".to_string()),
+            attributes: Some(HashMap::from([("Colour".to_string(), "blue".to_string())])),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(SnsError::InvalidParameter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redrive_policy_rejects_dead_letter_queue_in_other_account_or_region() {
+        let redrive = |dlq: &str| {
+            HashMap::from([("RedrivePolicy".to_string(), format!(r#"{{"deadLetterTargetArn":"{dlq}"}}"#))])
+        };
+        let wrong_account = "arn:aws:sqs:us-east-1:999999999999:dlq";
+        let wrong_region = "arn:aws:sqs:us-west-2:123456789012:dlq";
+        let state = make_state();
+        let arn = create_topic(&state, "alerts").await;
+        let subscribe = |attributes: Option<HashMap<String, String>>| SubscribeRequest {
+            topic_arn: arn.clone(),
+            protocol: "sqs".to_string(),
+            endpoint: Some("arn:aws:sqs:us-east-1:123456789012:q".to_string()),
+            attributes,
+            ..Default::default()
+        };
+        match state.subscribe(subscribe(Some(redrive(wrong_account)))).await {
+            Err(SnsError::InvalidParameter(m)) => assert!(m.contains("999999999999"), "{m}"),
+            other => panic!("expected InvalidParameter, got {other:?}"),
+        }
+        let sub = state.subscribe(subscribe(None)).await.unwrap();
+        let set = SetSubscriptionAttributesRequest {
+            subscription_arn: sub.subscription_arn.clone(),
+            attribute_name: "RedrivePolicy".to_string(),
+            attribute_value: Some(format!(r#"{{"deadLetterTargetArn":"{wrong_region}"}}"#)),
+        };
+        match state.set_subscription_attributes(set.clone()).await {
+            Err(SnsError::InvalidParameter(m)) => assert!(m.contains("us-west-2"), "{m}"),
+            other => panic!("expected InvalidParameter, got {other:?}"),
+        }
+
+        // The topic ARN is the same on a second server with the same account.
+        let state = make_state().with_cross_account(true);
+        create_topic(&state, "alerts").await;
+        state.subscribe(subscribe(Some(redrive(wrong_account)))).await.unwrap();
+        let sub = state.subscribe(subscribe(None)).await.unwrap();
+        state
+            .set_subscription_attributes(SetSubscriptionAttributesRequest { subscription_arn: sub.subscription_arn, ..set })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_subscriptions_pagination() {
+        let state = make_state();
+        let arn = create_topic(&state, "many").await;
+        for i in 0..150 {
+            state.subscribe(SubscribeRequest {
+                topic_arn: arn.clone(),
+                protocol: "sqs".to_string(),
+                endpoint: Some(format!("arn:aws:sqs:us-east-1:123456789012:q{i}")),
+                ..Default::default()
+            }).await.unwrap();
+        }
+        let first = state
+            .list_subscriptions_by_topic(ListSubscriptionsByTopicRequest { topic_arn: arn.clone(), next_token: None })
+            .await
+            .unwrap();
+        assert_eq!(first.subscriptions.len(), 100);
+        let second = state
+            .list_subscriptions_by_topic(ListSubscriptionsByTopicRequest { topic_arn: arn, next_token: first.next_token })
+            .await
+            .unwrap();
+        assert_eq!(second.subscriptions.len(), 50);
+        assert!(second.next_token.is_none());
+        assert!(second.subscriptions[0].subscription_arn > first.subscriptions[99].subscription_arn);
+
+        let all = state.list_subscriptions(ListSubscriptionsRequest::default()).await.unwrap();
+        assert_eq!(all.subscriptions.len(), 100);
+        assert!(all.next_token.is_some());
+    }
 }
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use uuid::Uuid;
 
+use super::error::SnsError;
+
 #[derive(Debug, Clone)]
 pub struct TopicAttributes {
     pub display_name: String,
@@ -103,6 +106,7 @@ pub struct SubscriptionAttributes {
     pub filter_policy: Option<String>,
     pub filter_policy_scope: String,
     pub redrive_policy: Option<String>,
+    pub delivery_policy: Option<String>,
 }
 
 impl Default for SubscriptionAttributes {
@@ -112,11 +116,128 @@ impl Default for SubscriptionAttributes {
             filter_policy: None,
             filter_policy_scope: "MessageAttributes".into(),
             redrive_policy: None,
+            delivery_policy: None,
         }
     }
 }
 
+/// Retries after a failed delivery when the `DeliveryPolicy` sets none.
+pub const DEFAULT_DELIVERY_RETRIES: u32 = 3;
+/// Upper bound on `healthyRetryPolicy.numRetries`.
+pub const MAX_DELIVERY_RETRIES: u32 = 100;
+/// Seconds between retries when the `DeliveryPolicy` sets no delay targets.
+pub const DEFAULT_DELIVERY_DELAY: u64 = 20;
+/// Upper bound on `minDelayTarget` and `maxDelayTarget`, in seconds.
+pub const MAX_DELIVERY_DELAY: u64 = 3600;
+
+/// Parses a JSON-object attribute; an empty value clears it.
+fn json_policy(name: &str, value: String) -> Result<Option<String>, SnsError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match serde_json::from_str::<serde_json::Value>(&value) {
+        Ok(serde_json::Value::Object(_)) => Ok(Some(value)),
+        _ => Err(SnsError::InvalidParameter(format!(
+            "Invalid parameter: {name}: failed to parse JSON"
+        ))),
+    }
+}
+
 impl SubscriptionAttributes {
+    /// Applies one attribute, as given to `Subscribe` or
+    /// `SetSubscriptionAttributes`.
+    pub fn set(&mut self, name: &str, value: String) -> Result<(), SnsError> {
+        match name {
+            "RawMessageDelivery" => {
+                self.raw_message_delivery = match value.to_ascii_lowercase().as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => {
+                        return Err(SnsError::InvalidParameter(format!(
+                            "Invalid parameter: RawMessageDelivery: must be true or false, got {value}"
+                        )))
+                    }
+                };
+            }
+            "FilterPolicy" => self.filter_policy = json_policy(name, value)?,
+            "FilterPolicyScope" => {
+                if !matches!(value.as_str(), "MessageAttributes" | "MessageBody") {
+                    return Err(SnsError::InvalidParameter(format!(
+                        "Invalid parameter: FilterPolicyScope: must be MessageAttributes or MessageBody, got {value}"
+                    )));
+                }
+                self.filter_policy_scope = value;
+            }
+            "RedrivePolicy" => {
+                let policy = json_policy(name, value)?;
+                if let Some(ref policy) = policy {
+                    let parsed: serde_json::Value = serde_json::from_str(policy).unwrap_or_default();
+                    if !parsed["deadLetterTargetArn"].is_string() {
+                        return Err(SnsError::InvalidParameter(
+                            "Invalid parameter: RedrivePolicy: deadLetterTargetArn is missing".into(),
+                        ));
+                    }
+                }
+                self.redrive_policy = policy;
+            }
+            "DeliveryPolicy" => self.delivery_policy = json_policy(name, value)?,
+            _ => {
+                return Err(SnsError::InvalidParameter(format!(
+                    "Invalid attribute name: {name}"
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// The `deadLetterTargetArn` of the subscription's `RedrivePolicy`.
+    pub fn dead_letter_target_arn(&self) -> Option<String> {
+        let policy: serde_json::Value = serde_json::from_str(self.redrive_policy.as_deref()?).ok()?;
+        policy["deadLetterTargetArn"].as_str().map(str::to_string)
+    }
+
+    /// The wait before each retry of a failed delivery, from the
+    /// `healthyRetryPolicy` of the `DeliveryPolicy`. As in AWS, the first
+    /// `numNoDelayRetries` retries are immediate and the next
+    /// `numMinDelayRetries` wait `minDelayTarget`. The backoff retries that
+    /// follow climb to `maxDelayTarget`, linearly or, with a `geometric` or
+    /// `exponential` `backoffFunction`, exponentially; the last
+    /// `numMaxDelayRetries` wait `maxDelayTarget`.
+    pub fn retry_delays(&self) -> Vec<Duration> {
+        let policy = self
+            .delivery_policy
+            .as_deref()
+            .and_then(|policy| serde_json::from_str::<serde_json::Value>(policy).ok())
+            .map(|policy| policy["healthyRetryPolicy"].clone())
+            .unwrap_or_default();
+        let field = |name: &str| policy[name].as_u64();
+        let retries = field("numRetries")
+            .map_or(DEFAULT_DELIVERY_RETRIES, |n| n.min(MAX_DELIVERY_RETRIES as u64) as u32);
+        let min = field("minDelayTarget").unwrap_or(DEFAULT_DELIVERY_DELAY).min(MAX_DELIVERY_DELAY);
+        let max = field("maxDelayTarget").unwrap_or(DEFAULT_DELIVERY_DELAY).clamp(min, MAX_DELIVERY_DELAY);
+        let count = |name: &str| field(name).unwrap_or(0).min(retries as u64) as u32;
+        let no_delay = count("numNoDelayRetries");
+        let min_delay = count("numMinDelayRetries").min(retries - no_delay);
+        let max_delay = count("numMaxDelayRetries").min(retries - no_delay - min_delay);
+        let backoff = retries - no_delay - min_delay - max_delay;
+        let exponential = matches!(policy["backoffFunction"].as_str(), Some("geometric" | "exponential"));
+        let (min_secs, max_secs) = (min as f64, max as f64);
+
+        let mut delays = vec![Duration::ZERO; no_delay as usize];
+        delays.extend(std::iter::repeat_n(Duration::from_secs(min), min_delay as usize));
+        delays.extend((1..=backoff).map(|i| {
+            let t = f64::from(i) / f64::from(backoff);
+            let secs = if exponential && min > 0 {
+                min_secs * (max_secs / min_secs).powf(t)
+            } else {
+                min_secs + (max_secs - min_secs) * t
+            };
+            Duration::from_secs_f64(secs)
+        }));
+        delays.extend(std::iter::repeat_n(Duration::from_secs(max), max_delay as usize));
+        delays
+    }
+
     pub fn to_map(&self, sub: &Subscription) -> HashMap<String, String> {
         let mut m = HashMap::new();
         m.insert("SubscriptionArn".into(), sub.arn.clone());
@@ -139,6 +260,9 @@ impl SubscriptionAttributes {
         if let Some(ref rp) = self.redrive_policy {
             m.insert("RedrivePolicy".into(), rp.clone());
         }
+        if let Some(ref dp) = self.delivery_policy {
+            m.insert("DeliveryPolicy".into(), dp.clone());
+        }
         m.insert(
             "PendingConfirmation".into(),
             (!sub.confirmed).to_string(),
@@ -187,3 +311,25 @@ impl Subscription {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delays(policy: &str) -> Vec<u64> {
+        let attrs = SubscriptionAttributes { delivery_policy: Some(policy.to_string()), ..Default::default() };
+        attrs.retry_delays().iter().map(Duration::as_secs).collect()
+    }
+
+    #[test]
+    fn test_retry_delays_follow_the_delivery_policy() {
+        assert_eq!(SubscriptionAttributes::default().retry_delays(), vec![Duration::from_secs(20); 3]);
+        let policy = r#"{"healthyRetryPolicy":{"numRetries":6,"numNoDelayRetries":1,"numMinDelayRetries":1,
+            "numMaxDelayRetries":1,"minDelayTarget":2,"maxDelayTarget":8}}"#;
+        assert_eq!(delays(policy), vec![0, 2, 4, 6, 8, 8]);
+        let policy = r#"{"healthyRetryPolicy":{"numRetries":2,"minDelayTarget":2,"maxDelayTarget":8,
+            "backoffFunction":"exponential"}}"#;
+        assert_eq!(delays(policy), vec![4, 8]);
+        // Counts beyond numRetries are cut back to it.
+        assert_eq!(delays(r#"{"healthyRetryPolicy":{"numRetries":2,"numNoDelayRetries":5}}"#), vec![0, 0]);
+    }
+}
@@ -146,7 +146,7 @@ pub struct ConfirmSubscriptionResponse {
 #[serde(rename_all = "PascalCase")]
 pub struct ListSubscriptionsRequest {
     #[serde(default)]
-    pub _next_token: Option<String>,
+    pub next_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -174,7 +174,7 @@ pub struct SubscriptionEntry {
 pub struct ListSubscriptionsByTopicRequest {
     pub topic_arn: String,
     #[serde(default)]
-    pub _next_token: Option<String>,
+    pub next_token: Option<String>,
 }
 
 // --- GetSubscriptionAttributes ---
@@ -213,7 +213,7 @@ pub struct PublishRequest {
     pub target_arn: Option<String>,
     pub message: String,
     #[serde(default)]
-    pub _subject: Option<String>,
+    pub subject: Option<String>,
     #[serde(default)]
     pub _message_structure: Option<String>,
     #[serde(default)]
@@ -247,7 +247,7 @@ pub struct PublishBatchEntry {
     pub id: String,
     pub message: String,
     #[serde(default)]
-    pub _subject: Option<String>,
+    pub subject: Option<String>,
     #[serde(default)]
     pub _message_attributes: Option<HashMap<String, MessageAttributeValueJson>>,
     #[serde(default)]
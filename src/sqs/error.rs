@@ -1,6 +1,8 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
+use crate::pagination::InvalidToken;
 
 #[derive(Debug, Clone)]
 pub enum SqsError {
@@ -73,13 +75,29 @@ impl SqsError {
     }
 }
 
+impl From<InvalidToken> for SqsError {
+    fn from(_: InvalidToken) -> Self {
+        SqsError::InvalidParameterValue("Invalid NextToken value.".to_string())
+    }
+}
+
+impl AwsError for SqsError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        SqsError::message(self)
+    }
+}
+
 impl IntoResponse for SqsError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": format!("com.amazonaws.sqs#{}", self.error_code()),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::NamespacedJson("com.amazonaws.sqs#"))
     }
 }
 
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use md5::{Digest as Md5Digest, Md5};
@@ -6,7 +6,10 @@ use sha2::Sha256;
 use uuid::Uuid;
 
 use super::error::SqsError;
-use super::types::{MessageAttributeValue, ReceiveMessageResult, SendMessageResponse};
+use super::types::{
+    DebugMessage, DebugQueueResponse, DumpedMessage, MessageAttributeValue, MessageDump, ReceiveMessageResult,
+    SendMessageResponse,
+};
 
 fn now_millis() -> u64 {
     SystemTime::now()
@@ -79,6 +82,30 @@ pub fn compute_md5_of_attributes(attrs: &HashMap<String, MessageAttributeValue>)
     Some(md5_hex(&buf))
 }
 
+/// `AWSTraceHeader` is the only message system attribute SQS accepts, and
+/// it must be a `String`.
+fn validate_system_attributes(attrs: &HashMap<String, MessageAttributeValue>) -> Result<(), SqsError> {
+    for (name, attr) in attrs {
+        if name != "AWSTraceHeader" {
+            return Err(SqsError::InvalidParameterValue(format!(
+                "Message system attribute name '{name}' is invalid."
+            )));
+        }
+        if attr.data_type != "String" {
+            return Err(SqsError::InvalidParameterValue(format!(
+                "Message system attribute '{name}' must be of type String, got {}.",
+                attr.data_type
+            )));
+        }
+        if attr.string_value.as_deref().is_none_or(str::is_empty) {
+            return Err(SqsError::InvalidParameterValue(format!(
+                "Message system attribute '{name}' must contain a non-empty value of type String."
+            )));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub struct RedrivePolicy {
     pub dead_letter_target_arn: String,
@@ -400,6 +427,9 @@ pub struct DlqRedrive {
     pub dlq_arn: String,
 }
 
+/// Most FIFO message groups that may have messages in flight at once.
+pub const MAX_INFLIGHT_FIFO_GROUPS: usize = 20;
+
 pub struct Queue {
     pub arn: String,
     pub url: String,
@@ -414,7 +444,9 @@ pub struct Queue {
     // FIFO state
     dedup_cache: HashMap<String, (SendMessageResponse, Instant)>,
     sequence_counter: u64,
-    locked_groups: HashSet<String>,
+    /// In-flight message count per FIFO message group; a group with any
+    /// message in flight delivers nothing more until they are all settled.
+    locked_groups: HashMap<String, usize>,
 }
 
 impl Queue {
@@ -433,7 +465,36 @@ impl Queue {
             last_purge: None,
             dedup_cache: HashMap::new(),
             sequence_counter: 0,
-            locked_groups: HashSet::new(),
+            locked_groups: HashMap::new(),
+        }
+    }
+
+    fn high_throughput_fifo(&self) -> bool {
+        self.attributes.fifo_queue && self.attributes.fifo_throughput_limit == "PerMessageGroupId"
+    }
+
+    fn unlock_group(&mut self, msg: &Message) {
+        if let Some(ref gid) = msg.message_group_id {
+            if let Some(count) = self.locked_groups.get_mut(gid) {
+                *count -= 1;
+                if *count == 0 {
+                    self.locked_groups.remove(gid);
+                }
+            }
+        }
+    }
+
+    /// Returns an in-flight message to the queue, ahead of any later
+    /// messages of its FIFO group so the group stays in order.
+    fn requeue(&mut self, msg: Message) {
+        let pos = msg.message_group_id.as_ref().and_then(|gid| {
+            self.messages.iter().position(|m| {
+                m.message_group_id.as_ref() == Some(gid) && m.sequence_number > msg.sequence_number
+            })
+        });
+        match pos {
+            Some(pos) => self.messages.insert(pos, msg),
+            None => self.messages.push_back(msg),
         }
     }
 
@@ -460,6 +521,10 @@ impl Queue {
             ));
         }
 
+        if let Some(ref attrs) = sys_attrs {
+            validate_system_attributes(attrs)?;
+        }
+
         // FIFO checks
         if self.attributes.fifo_queue {
             if group_id.is_none() {
@@ -563,10 +628,7 @@ impl Queue {
 
         for handle in expired_handles {
             if let Some(mut msg) = self.inflight.remove(&handle) {
-                // Unlock FIFO group
-                if let Some(ref gid) = msg.message_group_id {
-                    self.locked_groups.remove(gid);
-                }
+                self.unlock_group(&msg);
 
                 msg.receipt_handle = None;
                 msg.visibility_deadline = None;
@@ -582,7 +644,7 @@ impl Queue {
                     }
                 }
 
-                self.messages.push_back(msg);
+                self.requeue(msg);
             }
         }
 
@@ -617,12 +679,15 @@ impl Queue {
         let mut indices_to_remove: Vec<usize> = Vec::new();
 
         if self.attributes.fifo_queue {
-            // FIFO: deliver in order, one per group, skip locked groups
-            let mut seen_groups: HashSet<String> = HashSet::new();
+            // FIFO: deliver in order, skipping locked groups. A call takes one
+            // message per group, or with PerMessageGroupId throughput several,
+            // round-robin across groups so each progresses independently.
+            // Groups not yet in flight are only opened while fewer than
+            // MAX_INFLIGHT_FIFO_GROUPS are; the rest wait for a later call.
+            let per_group = if self.high_throughput_fifo() { max_count as usize } else { 1 };
+            let mut groups: Vec<(Option<&String>, Vec<usize>)> = Vec::new();
+            let mut group_capped = false;
             for (i, msg) in self.messages.iter().enumerate() {
-                if results.len() >= max_count as usize {
-                    break;
-                }
                 if msg.visible_at > now {
                     continue;
                 }
@@ -631,14 +696,35 @@ impl Queue {
                     indices_to_remove.push(i);
                     continue;
                 }
-                if let Some(ref gid) = msg.message_group_id {
-                    if self.locked_groups.contains(gid) || seen_groups.contains(gid) {
-                        continue;
+                let gid = msg.message_group_id.as_ref();
+                if gid.is_some_and(|gid| self.locked_groups.contains_key(gid)) {
+                    continue;
+                }
+                let opened = groups.len();
+                match groups.iter_mut().find(|(g, _)| gid.is_some() && *g == gid) {
+                    Some((_, taken)) if taken.len() < per_group => taken.push(i),
+                    Some(_) => {}
+                    None if gid.is_some() && self.locked_groups.len() + opened >= MAX_INFLIGHT_FIFO_GROUPS => {
+                        group_capped = true;
+                    }
+                    None => groups.push((gid, vec![i])),
+                }
+            }
+            if groups.is_empty() && group_capped {
+                return Err(SqsError::OverLimit(format!(
+                    "Too many message groups in flight (limit {})",
+                    MAX_INFLIGHT_FIFO_GROUPS
+                )));
+            }
+            for round in 0..per_group {
+                for (_, taken) in &groups {
+                    if results.len() < max_count as usize {
+                        if let Some(&i) = taken.get(round) {
+                            indices_to_remove.push(i);
+                            results.push(i);
+                        }
                     }
-                    seen_groups.insert(gid.clone());
                 }
-                indices_to_remove.push(i);
-                results.push(i);
             }
         } else {
             // Standard queue: take visible messages
@@ -658,12 +744,8 @@ impl Queue {
             }
         }
 
-        // Collect messages to move to inflight (the ones in results)
-        let result_set: HashSet<usize> = results.iter().copied().collect();
-
         // Remove from deque in reverse order
-        let all_remove: Vec<usize> = indices_to_remove;
-        let mut removed_indices: Vec<usize> = all_remove.clone();
+        let mut removed_indices: Vec<usize> = indices_to_remove;
         removed_indices.sort_unstable();
         removed_indices.dedup();
 
@@ -675,70 +757,67 @@ impl Queue {
             }
         }
 
+        // Deliver in `results` order; expired messages extracted above are dropped
         let mut receive_results = Vec::new();
-        for &idx in &all_remove {
+        for &idx in &results {
             if let Some(mut msg) = extracted.remove(&idx) {
-                if result_set.contains(&idx) {
-                    // This is a received message
-                    msg.receive_count += 1;
-                    if msg.first_receive_timestamp.is_none() {
-                        msg.first_receive_timestamp = Some(now_ms);
-                    }
-                    let receipt_handle = Uuid::new_v4().to_string();
-                    msg.receipt_handle = Some(receipt_handle.clone());
-                    msg.visibility_deadline =
-                        Some(now + Duration::from_secs(vis_timeout as u64));
-
-                    // Lock FIFO group
-                    if let Some(ref gid) = msg.message_group_id {
-                        self.locked_groups.insert(gid.clone());
-                    }
+                msg.receive_count += 1;
+                if msg.first_receive_timestamp.is_none() {
+                    msg.first_receive_timestamp = Some(now_ms);
+                }
+                let receipt_handle = Uuid::new_v4().to_string();
+                msg.receipt_handle = Some(receipt_handle.clone());
+                msg.visibility_deadline =
+                    Some(now + Duration::from_secs(vis_timeout as u64));
 
-                    let mut sys_attrs = HashMap::new();
-                    sys_attrs.insert("SenderId".into(), account_id.to_string());
-                    sys_attrs.insert("SentTimestamp".into(), msg.sent_timestamp.to_string());
-                    sys_attrs.insert(
-                        "ApproximateReceiveCount".into(),
-                        msg.receive_count.to_string(),
-                    );
-                    sys_attrs.insert(
-                        "ApproximateFirstReceiveTimestamp".into(),
-                        msg.first_receive_timestamp.unwrap().to_string(),
-                    );
-                    if let Some(ref did) = msg.dedup_id {
-                        sys_attrs.insert("MessageDeduplicationId".into(), did.clone());
-                    }
-                    if let Some(ref gid) = msg.message_group_id {
-                        sys_attrs.insert("MessageGroupId".into(), gid.clone());
-                    }
-                    if let Some(ref seq) = msg.sequence_number {
-                        sys_attrs.insert("SequenceNumber".into(), seq.clone());
-                    }
-                    // AWSTraceHeader from system attributes
-                    if let Some(trace) = msg.system_attributes.get("AWSTraceHeader") {
-                        if let Some(ref sv) = trace.string_value {
-                            sys_attrs.insert("AWSTraceHeader".into(), sv.clone());
-                        }
+                // Lock FIFO group
+                if let Some(ref gid) = msg.message_group_id {
+                    *self.locked_groups.entry(gid.clone()).or_default() += 1;
+                }
+
+                let mut sys_attrs = HashMap::new();
+                sys_attrs.insert("SenderId".into(), account_id.to_string());
+                sys_attrs.insert("SentTimestamp".into(), msg.sent_timestamp.to_string());
+                sys_attrs.insert(
+                    "ApproximateReceiveCount".into(),
+                    msg.receive_count.to_string(),
+                );
+                sys_attrs.insert(
+                    "ApproximateFirstReceiveTimestamp".into(),
+                    msg.first_receive_timestamp.unwrap().to_string(),
+                );
+                if let Some(ref did) = msg.dedup_id {
+                    sys_attrs.insert("MessageDeduplicationId".into(), did.clone());
+                }
+                if let Some(ref gid) = msg.message_group_id {
+                    sys_attrs.insert("MessageGroupId".into(), gid.clone());
+                }
+                if let Some(ref seq) = msg.sequence_number {
+                    sys_attrs.insert("SequenceNumber".into(), seq.clone());
+                }
+                // AWSTraceHeader from system attributes, validated on send
+                if let Some(trace) = msg.system_attributes.get("AWSTraceHeader") {
+                    if let Some(ref sv) = trace.string_value {
+                        sys_attrs.insert("AWSTraceHeader".into(), sv.clone());
                     }
+                }
 
-                    let result = ReceiveMessageResult {
-                        message_id: msg.message_id.clone(),
-                        receipt_handle: receipt_handle.clone(),
-                        body: msg.body.clone(),
-                        md5_of_body: msg.md5_of_body.clone(),
-                        md5_of_message_attributes: msg.md5_of_message_attributes.clone(),
-                        attributes: Some(sys_attrs),
-                        message_attributes: if msg.message_attributes.is_empty() {
-                            None
-                        } else {
-                            Some(msg.message_attributes.clone())
-                        },
-                    };
+                let result = ReceiveMessageResult {
+                    message_id: msg.message_id.clone(),
+                    receipt_handle: receipt_handle.clone(),
+                    body: msg.body.clone(),
+                    md5_of_body: msg.md5_of_body.clone(),
+                    md5_of_message_attributes: msg.md5_of_message_attributes.clone(),
+                    attributes: Some(sys_attrs),
+                    message_attributes: if msg.message_attributes.is_empty() {
+                        None
+                    } else {
+                        Some(msg.message_attributes.clone())
+                    },
+                };
 
-                    self.inflight.insert(receipt_handle, msg);
-                    receive_results.push(result);
-                }
-                // else: expired message, just dropped
+                self.inflight.insert(receipt_handle, msg);
+                receive_results.push(result);
             }
         }
 
@@ -747,10 +826,7 @@ impl Queue {
 
     pub fn delete_message(&mut self, receipt_handle: &str) -> Result<(), SqsError> {
         if let Some(msg) = self.inflight.remove(receipt_handle) {
-            // Unlock FIFO group
-            if let Some(ref gid) = msg.message_group_id {
-                self.locked_groups.remove(gid);
-            }
+            self.unlock_group(&msg);
         }
         // Idempotent: succeed even if not found
         Ok(())
@@ -771,12 +847,10 @@ impl Queue {
             if timeout == 0 {
                 // Make immediately visible
                 let mut msg = self.inflight.remove(receipt_handle).unwrap();
-                if let Some(ref gid) = msg.message_group_id {
-                    self.locked_groups.remove(gid);
-                }
+                self.unlock_group(&msg);
                 msg.receipt_handle = None;
                 msg.visibility_deadline = None;
-                self.messages.push_back(msg);
+                self.requeue(msg);
             } else {
                 msg.visibility_deadline =
                     Some(Instant::now() + Duration::from_secs(timeout as u64));
@@ -886,4 +960,131 @@ impl Queue {
     pub fn approximate_messages(&self) -> usize {
         self.messages.len()
     }
+
+    /// Every message in the queue: in-flight messages first, as they were
+    /// received before the rest, then the queue in delivery order.
+    pub fn dump_messages(&self) -> MessageDump {
+        let mut inflight: Vec<&Message> = self.inflight.values().collect();
+        inflight.sort_by(|a, b| (a.sent_timestamp, &a.sequence_number).cmp(&(b.sent_timestamp, &b.sequence_number)));
+        let messages = inflight
+            .into_iter()
+            .chain(&self.messages)
+            .map(|m| DumpedMessage {
+                message_id: m.message_id.clone(),
+                body: m.body.clone(),
+                message_attributes: m.message_attributes.clone(),
+                message_system_attributes: m.system_attributes.clone(),
+                sent_timestamp: m.sent_timestamp,
+                receive_count: m.receive_count,
+                first_receive_timestamp: m.first_receive_timestamp,
+                message_group_id: m.message_group_id.clone(),
+                message_deduplication_id: m.dedup_id.clone(),
+            })
+            .collect();
+        MessageDump { messages }
+    }
+
+    /// Appends dumped messages, all visible at once. Attributes, receive
+    /// counts and deduplication IDs are kept; FIFO sequence numbers are
+    /// assigned afresh. As with a send, a message whose deduplication ID
+    /// was seen in the last five minutes is dropped, and loaded IDs
+    /// deduplicate later sends. Nothing is loaded unless every message fits
+    /// this queue. Returns the number of messages loaded.
+    pub fn restore_messages(&mut self, dump: MessageDump) -> Result<usize, SqsError> {
+        for m in &dump.messages {
+            if m.body.is_empty() {
+                return Err(SqsError::InvalidParameterValue("Message body must not be empty".into()));
+            }
+            if self.attributes.fifo_queue && m.message_group_id.is_none() {
+                return Err(SqsError::MissingParameter("MessageGroupId is required for FIFO queues".into()));
+            }
+            validate_system_attributes(&m.message_system_attributes)?;
+        }
+        self.clean_dedup_cache();
+        let mut count = 0;
+        let now = Instant::now();
+        for m in dump.messages {
+            let dedup_id = match m.message_deduplication_id {
+                None if self.attributes.fifo_queue => Some(sha256_hex(m.body.as_bytes())),
+                dedup_id => dedup_id,
+            };
+            if dedup_id.as_ref().is_some_and(|did| self.dedup_cache.contains_key(did)) {
+                continue;
+            }
+            let sequence_number = self.attributes.fifo_queue.then(|| {
+                self.sequence_counter += 1;
+                format!("{:020}", self.sequence_counter)
+            });
+            let message_id = if m.message_id.is_empty() { Uuid::new_v4().to_string() } else { m.message_id };
+            let md5_of_body = md5_hex(m.body.as_bytes());
+            let md5_of_message_attributes = compute_md5_of_attributes(&m.message_attributes);
+            if let Some(ref did) = dedup_id {
+                let response = SendMessageResponse {
+                    message_id: message_id.clone(),
+                    md5_of_message_body: md5_of_body.clone(),
+                    md5_of_message_attributes: md5_of_message_attributes.clone(),
+                    md5_of_message_system_attributes: compute_md5_of_attributes(&m.message_system_attributes),
+                    sequence_number: sequence_number.clone(),
+                };
+                self.dedup_cache.insert(did.clone(), (response, Instant::now()));
+            }
+            self.messages.push_back(Message {
+                message_id,
+                md5_of_body,
+                body: m.body,
+                md5_of_message_attributes,
+                message_attributes: m.message_attributes,
+                system_attributes: m.message_system_attributes,
+                sent_timestamp: if m.sent_timestamp == 0 { now_millis() } else { m.sent_timestamp },
+                visible_at: now,
+                receive_count: m.receive_count,
+                first_receive_timestamp: m.first_receive_timestamp,
+                receipt_handle: None,
+                visibility_deadline: None,
+                message_group_id: m.message_group_id,
+                dedup_id,
+                sequence_number,
+            });
+            count += 1;
+        }
+        self.last_modified = now_secs();
+        Ok(count)
+    }
+
+    /// A read-only view of the queue's contents; unlike a receive, it
+    /// leaves visibility and receive counts untouched.
+    pub fn debug_snapshot(&self, name: &str) -> DebugQueueResponse {
+        let now = Instant::now();
+        let describe = |m: &Message, inflight: bool| DebugMessage {
+            message_id: m.message_id.clone(),
+            body: m.body.clone(),
+            receive_count: m.receive_count,
+            sent_timestamp: m.sent_timestamp,
+            receipt_handle: if inflight { m.receipt_handle.clone() } else { None },
+            visible_in_seconds: if inflight {
+                m.visibility_deadline.map(|d| d.saturating_duration_since(now).as_secs())
+            } else {
+                None
+            },
+            message_group_id: m.message_group_id.clone(),
+            sequence_number: m.sequence_number.clone(),
+        };
+        DebugQueueResponse {
+            queue_name: name.to_string(),
+            queue_url: self.url.clone(),
+            visible_messages: self
+                .messages
+                .iter()
+                .filter(|m| m.visible_at <= now)
+                .map(|m| describe(m, false))
+                .collect(),
+            inflight_messages: self.inflight.values().map(|m| describe(m, true)).collect(),
+            delayed_count: self.messages.iter().filter(|m| m.visible_at > now).count(),
+            dedup_cache_size: self
+                .dedup_cache
+                .values()
+                .filter(|(_, ts)| ts.elapsed() < Duration::from_secs(300))
+                .count(),
+        }
+    }
 }
@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
 use axum::body::Bytes;
-use axum::extract::State;
-use axum::http::HeaderMap;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
-use axum::routing::post;
+use axum::routing::{get, post};
 use axum::{Json, Router};
 
 use super::error::SqsError;
@@ -131,9 +131,60 @@ async fn handle_request(
     }
 }
 
+// GET /_debug/queues/{name} — a read-only view of a queue's contents for
+// test assertions; not part of the AWS API.
+async fn debug_queue(
+    State(state): State<Arc<SqsState>>,
+    Path(name): Path<String>,
+) -> axum::response::Response {
+    match state.debug_queue(&name).await {
+        Some(queue) => Json(queue).into_response(),
+        None => queue_not_found(&name),
+    }
+}
+
+fn queue_not_found(name: &str) -> axum::response::Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "message": format!("Queue {name} does not exist") })),
+    )
+        .into_response()
+}
+
+// GET /_debug/queues/{name}/messages — dump every message of a queue,
+// including in-flight ones, for reloading elsewhere.
+async fn export_messages(
+    State(state): State<Arc<SqsState>>,
+    Path(name): Path<String>,
+) -> Result<axum::response::Response, SqsError> {
+    match state.export_messages(&name).await {
+        Ok(dump) => Ok(Json(dump).into_response()),
+        Err(SqsError::QueueDoesNotExist(_)) => Ok(queue_not_found(&name)),
+        Err(e) => Err(e),
+    }
+}
+
+// PUT /_debug/queues/{name}/messages — append a dump, all messages visible.
+async fn import_messages(
+    State(state): State<Arc<SqsState>>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Result<axum::response::Response, SqsError> {
+    let dump: MessageDump =
+        serde_json::from_slice(&body).map_err(|e| SqsError::InvalidParameterValue(e.to_string()))?;
+    match state.import_messages(&name, dump).await {
+        Ok(count) => Ok(Json(serde_json::json!({ "MessageCount": count })).into_response()),
+        Err(SqsError::QueueDoesNotExist(_)) => Ok(queue_not_found(&name)),
+        Err(e) => Err(e),
+    }
+}
+
 pub fn create_router(state: Arc<SqsState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .route("/_debug/queues/{name}", get(debug_queue))
+        .route("/_debug/queues/{name}/messages", get(export_messages).put(import_messages))
+        .merge(crate::healthcheck::router("sqs"))
         .with_state(state)
 }
 
@@ -142,7 +193,7 @@ pub fn create_router(state: Arc<SqsState>) -> Router {
 mod tests {
     use super::*;
     use axum::body::Body;
-    use axum::http::{Request, StatusCode};
+    use axum::http::Request;
     use tower::ServiceExt;
 
     #[tokio::test]
@@ -230,4 +281,117 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert!(resp.status().is_client_error());
     }
+
+    #[tokio::test]
+    async fn test_debug_queue_reports_visible_messages() {
+        let state = Arc::new(SqsState::new("123456789012".to_string(), "us-east-1".to_string(), 4100));
+        let app = create_router(state);
+        let call = |target: &str, body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/x-amz-json-1.0")
+                .header("x-amz-target", format!("AmazonSQS.{target}"))
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let resp = app.clone().oneshot(call("CreateQueue", serde_json::json!({ "QueueName": "jobs" }))).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let url = serde_json::from_slice::<serde_json::Value>(&body).unwrap()["QueueUrl"].as_str().unwrap().to_string();
+        for text in ["first", "second"] {
+            let req = call("SendMessage", serde_json::json!({ "QueueUrl": url, "MessageBody": text }));
+            assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+        }
+
+        let peek = || Request::builder().uri("/_debug/queues/jobs").body(Body::empty()).unwrap();
+        let resp = app.clone().oneshot(peek()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let debug: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let visible = debug["VisibleMessages"].as_array().unwrap();
+        assert_eq!(visible.len(), 2);
+        assert_eq!(visible[0]["Body"], "first");
+        assert_eq!(visible[1]["Body"], "second");
+        assert_eq!(visible[0]["ReceiveCount"], 0);
+        assert_eq!(debug["InflightMessages"].as_array().unwrap().len(), 0);
+        assert_eq!(debug["DelayedCount"], 0);
+
+        // Peeking does not consume: a receive still gets the first message.
+        let req = call("ReceiveMessage", serde_json::json!({ "QueueUrl": url }));
+        let body = axum::body::to_bytes(app.clone().oneshot(req).await.unwrap().into_body(), usize::MAX).await.unwrap();
+        let received: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(received["Messages"][0]["Body"], "first");
+
+        let req = Request::builder().uri("/_debug/queues/missing").body(Body::empty()).unwrap();
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_debug_messages_round_trip() {
+        let state = Arc::new(SqsState::new("123456789012".to_string(), "us-east-1".to_string(), 4100));
+        let app = create_router(state);
+        let call = |target: &str, body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("x-amz-target", format!("AmazonSQS.{target}"))
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let json = |resp: axum::response::Response| async move {
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+        let mut urls = Vec::new();
+        for name in ["source", "target"] {
+            let resp = app.clone().oneshot(call("CreateQueue", serde_json::json!({ "QueueName": name }))).await.unwrap();
+            urls.push(json(resp).await["QueueUrl"].as_str().unwrap().to_string());
+        }
+        for text in ["first", "second", "third"] {
+            let req = call("SendMessage", serde_json::json!({
+                "QueueUrl": urls[0],
+                "MessageBody": text,
+                "MessageAttributes": { "step": { "DataType": "String", "StringValue": text } },
+            }));
+            assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+        }
+        // One message is in flight when the dump is taken.
+        let req = call("ReceiveMessage", serde_json::json!({ "QueueUrl": urls[0] }));
+        assert_eq!(json(app.clone().oneshot(req).await.unwrap()).await["Messages"][0]["Body"], "first");
+
+        let req = Request::builder().uri("/_debug/queues/source/messages").body(Body::empty()).unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let dump = json(resp).await;
+        assert_eq!(dump["Messages"].as_array().unwrap().len(), 3);
+        assert_eq!(dump["Messages"][0]["ReceiveCount"], 1);
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/_debug/queues/target/messages")
+            .body(Body::from(dump.to_string()))
+            .unwrap();
+        assert_eq!(json(app.clone().oneshot(req).await.unwrap()).await["MessageCount"], 3);
+
+        let req = call("ReceiveMessage", serde_json::json!({
+            "QueueUrl": urls[1],
+            "MaxNumberOfMessages": 10,
+            "MessageAttributeNames": ["All"],
+            "AttributeNames": ["ApproximateReceiveCount"],
+        }));
+        let received = json(app.clone().oneshot(req).await.unwrap()).await;
+        let messages = received["Messages"].as_array().unwrap();
+        let bodies: Vec<&str> = messages.iter().map(|m| m["Body"].as_str().unwrap()).collect();
+        assert_eq!(bodies, ["first", "second", "third"]);
+        for (m, original) in messages.iter().zip(dump["Messages"].as_array().unwrap()) {
+            assert_eq!(m["MessageId"], original["MessageId"]);
+            assert_eq!(m["MessageAttributes"]["step"]["StringValue"], m["Body"]);
+        }
+        assert_eq!(messages[0]["Attributes"]["ApproximateReceiveCount"], "2");
+
+        let req = Request::builder().uri("/_debug/queues/missing/messages").body(Body::empty()).unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), StatusCode::NOT_FOUND);
+        let req = Request::builder().method("PUT").uri("/_debug/queues/missing/messages").body(Body::from("{}")).unwrap();
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -7,8 +7,9 @@ use tokio::sync::{Mutex, Notify};
 use uuid::Uuid;
 
 use super::error::SqsError;
-use super::queue::{DlqRedrive, Permission, Queue, QueueAttributes};
+use super::queue::{DlqRedrive, Permission, Queue, QueueAttributes, RedrivePolicy};
 use super::types::*;
+use crate::pagination;
 
 struct MessageMoveTask {
     task_handle: String,
@@ -24,6 +25,8 @@ struct MessageMoveTask {
 
 struct QueueEntry {
     queue: Queue,
+    /// Long polls waiting on the queue, woken one at a time, oldest first,
+    /// as messages arrive.
     notify: Arc<Notify>,
 }
 
@@ -37,6 +40,7 @@ struct SqsStateInner {
 
 pub struct SqsState {
     inner: Arc<Mutex<SqsStateInner>>,
+    allow_cross_account: bool,
 }
 
 impl SqsState {
@@ -49,9 +53,34 @@ impl SqsState {
                 region,
                 base_url: format!("http://localhost:{}", port),
             })),
+            allow_cross_account: false,
         }
     }
 
+    /// Accepts dead-letter queue ARNs of other accounts and regions.
+    pub fn with_cross_account(mut self, allow: bool) -> Self {
+        self.allow_cross_account = allow;
+        self
+    }
+
+    /// Discards every queue, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        state.queues.clear();
+        state.move_tasks.clear();
+    }
+
+    /// ARNs and tags of every queue that carries at least one tag.
+    pub async fn tagged_resources(&self) -> Vec<(String, HashMap<String, String>)> {
+        let state = self.inner.lock().await;
+        state
+            .queues
+            .values()
+            .filter(|entry| !entry.queue.tags.is_empty())
+            .map(|entry| (entry.queue.arn.clone(), entry.queue.tags.clone()))
+            .collect()
+    }
+
     fn resolve_queue_name(queue_url: &str) -> Result<String, SqsError> {
         queue_url
             .split('/')
@@ -129,6 +158,52 @@ impl SqsState {
         Ok(())
     }
 
+    /// Checks that a `RedrivePolicy` being set names an existing queue of
+    /// the same type (FIFO or standard) as the source queue, in this
+    /// account and region.
+    fn validate_redrive_target(
+        &self,
+        inner: &SqsStateInner,
+        attrs: &HashMap<String, String>,
+        is_fifo: bool,
+    ) -> Result<(), SqsError> {
+        let Some(policy) = attrs.get("RedrivePolicy").filter(|v| !v.is_empty()) else {
+            return Ok(());
+        };
+        let policy = RedrivePolicy::from_json(policy)?;
+        crate::arn::check_local(
+            &policy.dead_letter_target_arn,
+            &inner.account_id,
+            &inner.region,
+            self.allow_cross_account,
+        )
+        .map_err(|reason| {
+            SqsError::InvalidParameterValue(format!(
+                "Value {} for parameter RedrivePolicy is invalid. Reason: {reason}.",
+                policy.dead_letter_target_arn
+            ))
+        })?;
+        let dlq_name = policy.dead_letter_target_arn.rsplit(':').next().unwrap_or("");
+        let dlq = inner.queues.get(dlq_name).ok_or_else(|| {
+            SqsError::InvalidParameterValue(format!(
+                "Value {} for parameter RedrivePolicy is invalid. Reason: Dead letter target does not exist.",
+                policy.dead_letter_target_arn
+            ))
+        })?;
+        if dlq.queue.attributes.fifo_queue != is_fifo {
+            let reason = if is_fifo {
+                "Dead-letter queue of a FIFO queue must also be a FIFO queue."
+            } else {
+                "Dead-letter queue of a standard queue must also be a standard queue."
+            };
+            return Err(SqsError::InvalidParameterValue(format!(
+                "Value {} for parameter RedrivePolicy is invalid. Reason: {reason}",
+                policy.dead_letter_target_arn
+            )));
+        }
+        Ok(())
+    }
+
     fn handle_dlq_redrives(
         queues: &mut HashMap<String, QueueEntry>,
         redrives: Vec<DlqRedrive>,
@@ -139,9 +214,15 @@ impl SqsState {
             let dlq_name = redrive.dlq_arn.split(':').last().unwrap_or("");
             if let Some(dlq_entry) = queues.get_mut(dlq_name) {
                 dlq_entry.queue.messages.push_back(redrive.message);
-                dlq_entry.notify.notify_waiters();
+                dlq_entry.notify.notify_one();
             } else {
-                // DLQ doesn't exist — return message to source queue
+                // The DLQ was deleted after the policy was set — keep the
+                // message on the source queue rather than dropping it.
+                tracing::warn!(
+                    queue = source_queue_name,
+                    dead_letter_target_arn = %redrive.dlq_arn,
+                    "dead-letter queue no longer exists; message returned to source queue"
+                );
                 if let Some(source_entry) = queues.get_mut(source_queue_name) {
                     source_entry.queue.messages.push_back(redrive.message);
                 }
@@ -203,6 +284,7 @@ impl SqsState {
             // Remove FifoQueue from attrs before applying since we already set it
             let mut attrs = attrs.clone();
             attrs.remove("FifoQueue");
+            self.validate_redrive_target(&inner, &attrs, is_fifo)?;
             attributes.apply(&attrs)?;
         }
 
@@ -226,7 +308,19 @@ impl SqsState {
     pub async fn delete_queue(&self, req: DeleteQueueRequest) -> Result<(), SqsError> {
         let name = Self::resolve_queue_name(&req.queue_url)?;
         let mut inner = self.inner.lock().await;
-        inner.queues.remove(&name);
+        if let Some(entry) = inner.queues.remove(&name) {
+            let arn = &entry.queue.arn;
+            let sources = inner.queues.iter().filter(|(_, e)| {
+                e.queue.attributes.redrive_policy.as_ref().is_some_and(|rp| &rp.dead_letter_target_arn == arn)
+            });
+            for (source, _) in sources {
+                tracing::warn!(
+                    queue = %source,
+                    dead_letter_target_arn = %arn,
+                    "deleted queue is the dead-letter target of another queue"
+                );
+            }
+        }
         Ok(())
     }
 
@@ -261,24 +355,10 @@ impl SqsState {
             names.retain(|n| n.starts_with(prefix));
         }
 
-        // Apply pagination
-        let start = if let Some(ref token) = req.next_token {
-            names.iter().position(|n| n.as_str() > token.as_str()).unwrap_or(names.len())
-        } else {
-            0
-        };
-
-        let page: Vec<String> = names[start..]
-            .iter()
-            .take(max)
-            .map(|n| inner.queues[*n].queue.url.clone())
-            .collect();
-
-        let next_token = if start + max < names.len() {
-            names.get(start + max).map(|n| (*n).clone())
-        } else {
-            None
-        };
+        let listing = format!("sqs:ListQueues:{}", req.queue_name_prefix.as_deref().unwrap_or_default());
+        let (names, next_token) =
+            pagination::paginate(names, |n| n.as_str(), req.next_token.as_deref(), &listing, max)?;
+        let page: Vec<String> = names.into_iter().map(|n| inner.queues[n].queue.url.clone()).collect();
 
         Ok(ListQueuesResponse {
             queue_urls: if page.is_empty() { None } else { Some(page) },
@@ -306,6 +386,14 @@ impl SqsState {
     ) -> Result<(), SqsError> {
         let name = Self::resolve_queue_name(&req.queue_url)?;
         let mut inner = self.inner.lock().await;
+        let is_fifo = inner
+            .queues
+            .get(&name)
+            .ok_or_else(|| SqsError::QueueDoesNotExist("The specified queue does not exist.".into()))?
+            .queue
+            .attributes
+            .fifo_queue;
+        self.validate_redrive_target(&inner, &req.attributes, is_fifo)?;
         let entry = inner.queues.get_mut(&name).ok_or_else(|| {
             SqsError::QueueDoesNotExist("The specified queue does not exist.".into())
         })?;
@@ -342,7 +430,7 @@ impl SqsState {
             &account_id,
         )?;
 
-        entry.notify.notify_waiters();
+        entry.notify.notify_one();
         Ok(result)
     }
 
@@ -402,7 +490,7 @@ impl SqsState {
         }
 
         if any_success {
-            entry.notify.notify_waiters();
+            entry.notify.notify_one();
         }
 
         Ok(SendMessageBatchResponse {
@@ -417,99 +505,79 @@ impl SqsState {
     ) -> Result<ReceiveMessageResponse, SqsError> {
         let name = Self::resolve_queue_name(&req.queue_url)?;
         let max_count = req.max_number_of_messages.unwrap_or(1).min(10).max(1);
+        let mut deadline = None;
 
-        // First attempt
-        let (wait_time, notify) = {
+        loop {
             let mut inner = self.inner.lock().await;
-            let account_id = inner.account_id.clone();
-
-            if !inner.queues.contains_key(&name) {
+            if let Some(results) = Self::try_receive(&mut inner, &name, &req, max_count)? {
+                return Ok(ReceiveMessageResponse { messages: Some(results) });
+            }
+            let Some(entry) = inner.queues.get(&name) else {
                 return Err(SqsError::QueueDoesNotExist(
                     "The specified queue does not exist.".into(),
                 ));
-            }
-
-            // Handle DLQ redrives
-            if let Some(entry) = inner.queues.get_mut(&name) {
-                let redrives = entry.queue.return_expired_inflight();
-                if !redrives.is_empty() {
-                    Self::handle_dlq_redrives(&mut inner.queues, redrives, &name);
-                }
-            }
-
-            if let Some(entry) = inner.queues.get_mut(&name) {
-                let results =
-                    entry
-                        .queue
-                        .receive_messages(max_count, req.visibility_timeout, &account_id)?;
-                if !results.is_empty() {
-                    let results = filter_receive_results(
-                        results,
-                        &req.attribute_names,
-                        &req.message_attribute_names,
-                    );
-                    return Ok(ReceiveMessageResponse {
-                        messages: Some(results),
-                    });
-                }
-
+            };
+            let deadline = *deadline.get_or_insert_with(|| {
                 let wait_time = req
                     .wait_time_seconds
                     .unwrap_or(entry.queue.attributes.receive_message_wait_time_seconds as i32);
-                let notify = entry.notify.clone();
-                (wait_time, notify)
-            } else {
-                return Err(SqsError::QueueDoesNotExist(
-                    "The specified queue does not exist.".into(),
-                ));
+                tokio::time::Instant::now() + Duration::from_secs(wait_time.max(0) as u64)
+            });
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(ReceiveMessageResponse { messages: None });
             }
-        };
 
-        if wait_time <= 0 {
-            return Ok(ReceiveMessageResponse { messages: None });
+            // Join the line of waiters before releasing the lock, so a send
+            // that follows cannot be missed. Each send wakes only the
+            // longest-waiting receiver; the rest keep sleeping.
+            let notify = entry.notify.clone();
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            drop(inner);
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                // Messages that became visible without a send, such as
+                // delayed ones, are picked up by this last attempt.
+                let mut inner = self.inner.lock().await;
+                let messages = Self::try_receive(&mut inner, &name, &req, max_count)?;
+                return Ok(ReceiveMessageResponse { messages });
+            }
         }
+    }
 
-        // Long polling: wait for notification or timeout
-        let _ = tokio::time::timeout(
-            Duration::from_secs(wait_time as u64),
-            notify.notified(),
-        )
-        .await;
-
-        // Second attempt after waiting
-        let mut inner = self.inner.lock().await;
-        if !inner.queues.contains_key(&name) {
-            return Err(SqsError::QueueDoesNotExist(
-                "The specified queue does not exist.".into(),
-            ));
-        }
+    /// One receive attempt, after returning expired in-flight messages.
+    /// When messages remain after this receiver's share, the next waiter is
+    /// woken to take them.
+    fn try_receive(
+        inner: &mut SqsStateInner,
+        name: &str,
+        req: &ReceiveMessageRequest,
+        max_count: i32,
+    ) -> Result<Option<Vec<ReceiveMessageResult>>, SqsError> {
         let account_id = inner.account_id.clone();
-
-        // Handle DLQ redrives again
-        if let Some(entry) = inner.queues.get_mut(&name) {
-            let redrives = entry.queue.return_expired_inflight();
-            if !redrives.is_empty() {
-                Self::handle_dlq_redrives(&mut inner.queues, redrives, &name);
-            }
+        let entry = inner.queues.get_mut(name).ok_or_else(|| {
+            SqsError::QueueDoesNotExist("The specified queue does not exist.".into())
+        })?;
+        let redrives = entry.queue.return_expired_inflight();
+        if !redrives.is_empty() {
+            Self::handle_dlq_redrives(&mut inner.queues, redrives, name);
         }
-        if let Some(entry) = inner.queues.get_mut(&name) {
-            let results =
-                entry
-                    .queue
-                    .receive_messages(max_count, req.visibility_timeout, &account_id)?;
-            if !results.is_empty() {
-                let results = filter_receive_results(
-                    results,
-                    &req.attribute_names,
-                    &req.message_attribute_names,
-                );
-                return Ok(ReceiveMessageResponse {
-                    messages: Some(results),
-                });
-            }
+        let Some(entry) = inner.queues.get_mut(name) else {
+            return Ok(None);
+        };
+        let results = entry.queue.receive_messages(max_count, req.visibility_timeout, &account_id)?;
+        if results.is_empty() {
+            return Ok(None);
         }
-
-        Ok(ReceiveMessageResponse { messages: None })
+        if entry.queue.approximate_messages() > 0 {
+            entry.notify.notify_one();
+        }
+        Ok(Some(filter_receive_results(
+            results,
+            &req.attribute_names,
+            &req.message_attribute_names,
+        )))
     }
 
     pub async fn delete_message(&self, req: DeleteMessageRequest) -> Result<(), SqsError> {
@@ -710,6 +778,32 @@ impl SqsState {
         Ok(())
     }
 
+    /// Backs `GET /_debug/queues/{name}`; `None` if the queue does not exist.
+    pub async fn debug_queue(&self, name: &str) -> Option<DebugQueueResponse> {
+        let inner = self.inner.lock().await;
+        inner.queues.get(name).map(|entry| entry.queue.debug_snapshot(name))
+    }
+
+    /// Backs `GET /_debug/queues/{name}/messages`.
+    pub async fn export_messages(&self, name: &str) -> Result<MessageDump, SqsError> {
+        let inner = self.inner.lock().await;
+        let entry = inner.queues.get(name).ok_or_else(|| {
+            SqsError::QueueDoesNotExist("The specified queue does not exist.".into())
+        })?;
+        Ok(entry.queue.dump_messages())
+    }
+
+    /// Backs `PUT /_debug/queues/{name}/messages`, waking any long polls.
+    pub async fn import_messages(&self, name: &str, dump: MessageDump) -> Result<usize, SqsError> {
+        let mut inner = self.inner.lock().await;
+        let entry = inner.queues.get_mut(name).ok_or_else(|| {
+            SqsError::QueueDoesNotExist("The specified queue does not exist.".into())
+        })?;
+        let count = entry.queue.restore_messages(dump)?;
+        entry.notify.notify_one();
+        Ok(count)
+    }
+
     pub async fn list_dead_letter_source_queues(
         &self,
         req: ListDeadLetterSourceQueuesRequest,
@@ -737,21 +831,9 @@ impl SqsState {
         source_urls.sort();
 
         let max = req.max_results.unwrap_or(1000).min(1000) as usize;
-        let start = if let Some(ref token) = req.next_token {
-            source_urls
-                .iter()
-                .position(|u| u.as_str() > token.as_str())
-                .unwrap_or(source_urls.len())
-        } else {
-            0
-        };
-
-        let page: Vec<String> = source_urls[start..].iter().take(max).cloned().collect();
-        let next_token = if start + max < source_urls.len() {
-            source_urls.get(start + max).cloned()
-        } else {
-            None
-        };
+        let listing = format!("sqs:ListDeadLetterSourceQueues:{target_arn}");
+        let (page, next_token) =
+            pagination::paginate(source_urls, |u| u.as_str(), req.next_token.as_deref(), &listing, max)?;
 
         Ok(ListDeadLetterSourceQueuesResponse {
             queue_urls: page,
@@ -866,7 +948,7 @@ impl SqsState {
 
                     if let Some(dest_entry) = inner.queues.get_mut(&dest_name) {
                         dest_entry.queue.messages.push_back(msg);
-                        dest_entry.notify.notify_waiters();
+                        dest_entry.notify.notify_one();
                     }
                     messages_moved.fetch_add(1, Ordering::Relaxed);
                     drop(inner);
@@ -1271,6 +1353,60 @@ mod tests {
         assert!(result.messages.is_none() || result.messages.unwrap().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_long_polls_are_woken_one_per_message() {
+        let state = Arc::new(make_state());
+        let url = create_queue(&state, "busy-q").await;
+        let receivers: Vec<_> = (0..50)
+            .map(|_| {
+                let (state, url) = (state.clone(), url.clone());
+                tokio::spawn(async move {
+                    let req = ReceiveMessageRequest {
+                        queue_url: url,
+                        wait_time_seconds: Some(2),
+                        ..Default::default()
+                    };
+                    state.receive_message(req).await.unwrap().messages.unwrap_or_default().len()
+                })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let finished = || receivers.iter().filter(|r| r.is_finished()).count();
+        assert_eq!(finished(), 0);
+
+        state.send_message(SendMessageRequest {
+            queue_url: url.clone(),
+            message_body: "one".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // Only the receiver that got the message returned; the rest still wait.
+        assert_eq!(finished(), 1);
+
+        // A batch wakes one receiver, which hands the rest on.
+        let entry = |id: &str| SendMessageBatchEntry {
+            id: id.to_string(),
+            message_body: id.to_string(),
+            delay_seconds: None,
+            message_attributes: None,
+            message_system_attributes: None,
+            message_deduplication_id: None,
+            message_group_id: None,
+        };
+        state.send_message_batch(SendMessageBatchRequest {
+            queue_url: url,
+            entries: vec![entry("a"), entry("b"), entry("c")],
+        }).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(finished(), 4);
+
+        let mut received = 0;
+        for receiver in receivers {
+            received += receiver.await.unwrap();
+        }
+        assert_eq!(received, 4);
+    }
+
     #[tokio::test]
     async fn test_receive_message_queue_not_found() {
         let state = make_state();
@@ -1445,6 +1581,43 @@ mod tests {
         assert_eq!(result.queue_urls.unwrap_or_default().len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_list_queues_pagination_rejects_tampered_token() {
+        let state = make_state();
+        for name in ["page-a", "page-b", "page-c"] {
+            create_queue(&state, name).await;
+        }
+        let first = state.list_queues(ListQueuesRequest {
+            max_results: Some(2),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(first.queue_urls.unwrap_or_default().len(), 2);
+        let token = first.next_token.unwrap();
+        let second = state.list_queues(ListQueuesRequest {
+            max_results: Some(2),
+            next_token: Some(token.clone()),
+            ..Default::default()
+        }).await.unwrap();
+        assert!(second.queue_urls.unwrap_or_default()[0].ends_with("/page-c"));
+        assert!(second.next_token.is_none());
+
+        for bad in [format!("{token}x"), "page-a".to_string()] {
+            let result = state.list_queues(ListQueuesRequest {
+                max_results: Some(2),
+                next_token: Some(bad),
+                ..Default::default()
+            }).await;
+            assert!(matches!(result, Err(SqsError::InvalidParameterValue(_))));
+        }
+        // A token only resumes the listing it was issued for.
+        let result = state.list_queues(ListQueuesRequest {
+            queue_name_prefix: Some("page".to_string()),
+            next_token: Some(token),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(SqsError::InvalidParameterValue(_))));
+    }
+
     #[tokio::test]
     async fn test_delete_message_batch() {
         let state = make_state();
@@ -1527,6 +1700,67 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_send_message_rejects_invalid_system_attribute() {
+        let state = make_state();
+        let url = create_queue(&state, "sysattr-q").await;
+        let send = |name: &str, data_type: &str| SendMessageRequest {
+            queue_url: url.clone(),
+            message_body: "body".to_string(),
+            message_system_attributes: Some(HashMap::from([(name.to_string(), MessageAttributeValue {
+                data_type: data_type.to_string(),
+                string_value: Some("Root=1-5759e988-bd862e3fe1be46a994272793".to_string()),
+                binary_value: None,
+            })])),
+            ..Default::default()
+        };
+        let result = state.send_message(send("SenderId", "String")).await;
+        assert!(matches!(result, Err(SqsError::InvalidParameterValue(m)) if m.contains("SenderId")));
+        let result = state.send_message(send("AWSTraceHeader", "Number")).await;
+        assert!(matches!(result, Err(SqsError::InvalidParameterValue(_))));
+
+        let batch = state.send_message_batch(SendMessageBatchRequest {
+            queue_url: url.clone(),
+            entries: vec![SendMessageBatchEntry {
+                id: "1".to_string(),
+                message_body: "body".to_string(),
+                delay_seconds: None,
+                message_attributes: None,
+                message_system_attributes: send("Foo", "String").message_system_attributes,
+                message_deduplication_id: None,
+                message_group_id: None,
+            }],
+        }).await.unwrap();
+        assert!(batch.successful.is_empty());
+        assert_eq!(batch.failed[0].code, "InvalidParameterValue");
+    }
+
+    #[tokio::test]
+    async fn test_trace_header_round_trip() {
+        let state = make_state();
+        let url = create_queue(&state, "trace-q").await;
+        let trace = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1";
+        let sent = state.send_message(SendMessageRequest {
+            queue_url: url.clone(),
+            message_body: "body".to_string(),
+            message_system_attributes: Some(HashMap::from([("AWSTraceHeader".to_string(), MessageAttributeValue {
+                data_type: "String".to_string(),
+                string_value: Some(trace.to_string()),
+                binary_value: None,
+            })])),
+            ..Default::default()
+        }).await.unwrap();
+        assert!(sent.md5_of_message_system_attributes.is_some());
+
+        let recv = state.receive_message(ReceiveMessageRequest {
+            queue_url: url,
+            attribute_names: Some(vec!["AWSTraceHeader".to_string()]),
+            ..Default::default()
+        }).await.unwrap();
+        let attrs = recv.messages.unwrap()[0].attributes.clone().unwrap();
+        assert_eq!(attrs, HashMap::from([("AWSTraceHeader".to_string(), trace.to_string())]));
+    }
+
     #[tokio::test]
     async fn test_set_queue_attributes_visibility_timeout() {
         let state = make_state();
@@ -1560,6 +1794,154 @@ mod tests {
         assert!(result.unwrap().queue_url.contains("my-queue.fifo"));
     }
 
+    async fn create_fifo_queue(state: &SqsState, name: &str, throughput_limit: &str) -> String {
+        let mut attrs = HashMap::new();
+        attrs.insert("FifoQueue".to_string(), "true".to_string());
+        attrs.insert("ContentBasedDeduplication".to_string(), "true".to_string());
+        attrs.insert("FifoThroughputLimit".to_string(), throughput_limit.to_string());
+        state.create_queue(CreateQueueRequest {
+            queue_name: name.to_string(),
+            attributes: Some(attrs),
+            ..Default::default()
+        }).await.unwrap().queue_url
+    }
+
+    async fn receive_bodies(state: &SqsState, url: &str, max: i32) -> Vec<ReceiveMessageResult> {
+        state.receive_message(ReceiveMessageRequest {
+            queue_url: url.to_string(),
+            max_number_of_messages: Some(max),
+            ..Default::default()
+        }).await.unwrap().messages.unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_fifo_per_message_group_throughput_interleaves_groups() {
+        let state = make_state();
+        let url = create_fifo_queue(&state, "ht.fifo", "PerMessageGroupId").await;
+        for n in 1..=3 {
+            for group in ["a", "b", "c"] {
+                state.send_message(SendMessageRequest {
+                    queue_url: url.clone(),
+                    message_body: format!("{group}{n}"),
+                    message_group_id: Some(group.to_string()),
+                    ..Default::default()
+                }).await.unwrap();
+            }
+        }
+
+        // Groups are delivered round-robin, several messages each.
+        let first = receive_bodies(&state, &url, 4).await;
+        let bodies: Vec<&str> = first.iter().map(|m| m.body.as_str()).collect();
+        assert_eq!(bodies, ["a1", "b1", "c1", "a2"]);
+
+        // Settling b1 frees group b while a and c are still in flight.
+        state.delete_message(DeleteMessageRequest {
+            queue_url: url.clone(),
+            receipt_handle: first[1].receipt_handle.clone(),
+        }).await.unwrap();
+        let more = receive_bodies(&state, &url, 10).await;
+        let bodies: Vec<&str> = more.iter().map(|m| m.body.as_str()).collect();
+        assert_eq!(bodies, ["b2", "b3"]);
+
+        // Group a stays locked until all its in-flight messages are settled.
+        for m in first.iter().filter(|m| m.body.starts_with('a')) {
+            state.delete_message(DeleteMessageRequest {
+                queue_url: url.clone(),
+                receipt_handle: m.receipt_handle.clone(),
+            }).await.unwrap();
+        }
+        let next = receive_bodies(&state, &url, 10).await;
+        let bodies: Vec<&str> = next.iter().map(|m| m.body.as_str()).collect();
+        assert_eq!(bodies, ["a3"]);
+    }
+
+    #[tokio::test]
+    async fn test_fifo_single_group_stays_ordered() {
+        let state = make_state();
+        for (name, limit) in [("pq.fifo", "PerQueue"), ("pg.fifo", "PerMessageGroupId")] {
+            let url = create_fifo_queue(&state, name, limit).await;
+            for n in 1..=4 {
+                state.send_message(SendMessageRequest {
+                    queue_url: url.clone(),
+                    message_body: format!("m{n}"),
+                    message_group_id: Some("g".to_string()),
+                    ..Default::default()
+                }).await.unwrap();
+            }
+            let expected: &[&str] = if limit == "PerQueue" { &["m1"] } else { &["m1", "m2", "m3", "m4"] };
+
+            let first = receive_bodies(&state, &url, 10).await;
+            let bodies: Vec<&str> = first.iter().map(|m| m.body.as_str()).collect();
+            assert_eq!(bodies, expected);
+            assert!(receive_bodies(&state, &url, 10).await.is_empty());
+
+            // Returned messages go back ahead of the rest of their group.
+            for m in first.iter().rev() {
+                state.change_message_visibility(ChangeMessageVisibilityRequest {
+                    queue_url: url.clone(),
+                    receipt_handle: m.receipt_handle.clone(),
+                    visibility_timeout: 0,
+                }).await.unwrap();
+            }
+            let mut order = Vec::new();
+            loop {
+                let batch = receive_bodies(&state, &url, 10).await;
+                if batch.is_empty() {
+                    break;
+                }
+                for m in batch {
+                    state.delete_message(DeleteMessageRequest {
+                        queue_url: url.clone(),
+                        receipt_handle: m.receipt_handle,
+                    }).await.unwrap();
+                    order.push(m.body);
+                }
+            }
+            assert_eq!(order, ["m1", "m2", "m3", "m4"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fifo_in_flight_group_limit() {
+        let state = make_state();
+        let url = create_fifo_queue(&state, "groups.fifo", "PerQueue").await;
+        for g in 0..25 {
+            state.send_message(SendMessageRequest {
+                queue_url: url.clone(),
+                message_body: format!("g{g:02}"),
+                message_group_id: Some(format!("g{g:02}")),
+                ..Default::default()
+            }).await.unwrap();
+        }
+        let mut in_flight = receive_bodies(&state, &url, 10).await;
+        in_flight.extend(receive_bodies(&state, &url, 10).await);
+        assert_eq!(in_flight.len(), 20);
+
+        // Every further message is in a new group, so the receive is refused.
+        let result = state.receive_message(ReceiveMessageRequest {
+            queue_url: url.clone(),
+            max_number_of_messages: Some(10),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(SqsError::OverLimit(_))));
+
+        // Messages of groups already in flight can still be settled...
+        state.change_message_visibility(ChangeMessageVisibilityRequest {
+            queue_url: url.clone(),
+            receipt_handle: in_flight[1].receipt_handle.clone(),
+            visibility_timeout: 60,
+        }).await.unwrap();
+        state.delete_message(DeleteMessageRequest {
+            queue_url: url.clone(),
+            receipt_handle: in_flight[0].receipt_handle.clone(),
+        }).await.unwrap();
+
+        // ...and each settled group makes room for exactly one new one.
+        let next = receive_bodies(&state, &url, 10).await;
+        let bodies: Vec<&str> = next.iter().map(|m| m.body.as_str()).collect();
+        assert_eq!(bodies, ["g20"]);
+    }
+
     #[tokio::test]
     async fn test_list_dead_letter_source_queues() {
         let state = make_state();
@@ -1591,4 +1973,118 @@ mod tests {
         }).await.unwrap();
         assert_eq!(result.queue_urls.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_redrive_policy_rejects_dlq_in_another_region() {
+        let wrong_region = HashMap::from([(
+            "RedrivePolicy".to_string(),
+            serde_json::json!({
+                "deadLetterTargetArn": "arn:aws:sqs:us-west-2:123456789012:dlq",
+                "maxReceiveCount": 3
+            })
+            .to_string(),
+        )]);
+        let source = |attrs: &HashMap<String, String>| CreateQueueRequest {
+            queue_name: "source".to_string(),
+            attributes: Some(attrs.clone()),
+            ..Default::default()
+        };
+
+        let state = make_state();
+        create_queue(&state, "dlq").await;
+        match state.create_queue(source(&wrong_region)).await {
+            Err(SqsError::InvalidParameterValue(m)) => assert!(m.contains("us-west-2"), "{m}"),
+            other => panic!("expected InvalidParameterValue, got {other:?}"),
+        }
+
+        let state = make_state().with_cross_account(true);
+        create_queue(&state, "dlq").await;
+        state.create_queue(source(&wrong_region)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_redrive_policy_rejects_mismatched_dlq_type() {
+        let state = make_state();
+        create_queue(&state, "standard-dlq").await;
+        let fifo_dlq = state
+            .create_queue(CreateQueueRequest { queue_name: "fifo-dlq.fifo".to_string(), ..Default::default() })
+            .await
+            .unwrap()
+            .queue_url;
+        let policy = |dlq: &str| {
+            let arn = format!("arn:aws:sqs:us-east-1:123456789012:{dlq}");
+            HashMap::from([(
+                "RedrivePolicy".to_string(),
+                serde_json::json!({ "deadLetterTargetArn": arn, "maxReceiveCount": 3 }).to_string(),
+            )])
+        };
+
+        // A standard queue cannot dead-letter into a FIFO queue.
+        let req = CreateQueueRequest {
+            queue_name: "source".to_string(),
+            attributes: Some(policy("fifo-dlq.fifo")),
+            ..Default::default()
+        };
+        assert!(matches!(state.create_queue(req).await, Err(SqsError::InvalidParameterValue(_))));
+
+        // Nor a FIFO queue into a standard one, via SetQueueAttributes.
+        let source = state
+            .create_queue(CreateQueueRequest { queue_name: "source.fifo".to_string(), ..Default::default() })
+            .await
+            .unwrap()
+            .queue_url;
+        let req = SetQueueAttributesRequest { queue_url: source.clone(), attributes: policy("standard-dlq") };
+        assert!(matches!(state.set_queue_attributes(req).await, Err(SqsError::InvalidParameterValue(_))));
+
+        // A target that does not exist is rejected too.
+        let req = SetQueueAttributesRequest { queue_url: source.clone(), attributes: policy("missing.fifo") };
+        assert!(matches!(state.set_queue_attributes(req).await, Err(SqsError::InvalidParameterValue(_))));
+
+        let req = SetQueueAttributesRequest { queue_url: source, attributes: policy("fifo-dlq.fifo") };
+        state.set_queue_attributes(req).await.unwrap();
+        state.delete_queue(DeleteQueueRequest { queue_url: fifo_dlq }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_messages_validates_and_deduplicates() {
+        let state = make_state();
+        let url = state
+            .create_queue(CreateQueueRequest { queue_name: "orders.fifo".to_string(), ..Default::default() })
+            .await
+            .unwrap()
+            .queue_url;
+        let dumped = |body: &str, dedup_id: &str| DumpedMessage {
+            body: body.to_string(),
+            message_group_id: Some(body.to_string()),
+            message_deduplication_id: Some(dedup_id.to_string()),
+            ..Default::default()
+        };
+
+        let mut bad = dumped("traced", "t");
+        bad.message_system_attributes.insert(
+            "SenderId".to_string(),
+            MessageAttributeValue { data_type: "String".to_string(), string_value: Some("x".to_string()), ..Default::default() },
+        );
+        let result = state.import_messages("orders.fifo", MessageDump { messages: vec![dumped("ok", "o"), bad] }).await;
+        assert!(matches!(result, Err(SqsError::InvalidParameterValue(_))));
+
+        // A repeated deduplication ID within the dump is dropped.
+        let dump = MessageDump { messages: vec![dumped("first", "a"), dumped("again", "a"), dumped("second", "b")] };
+        assert_eq!(state.import_messages("orders.fifo", dump).await.unwrap(), 2);
+
+        // And a send reusing a restored ID is deduplicated too.
+        let req = SendMessageRequest {
+            queue_url: url.clone(),
+            message_body: "later".to_string(),
+            message_group_id: Some("g".to_string()),
+            message_deduplication_id: Some("b".to_string()),
+            ..Default::default()
+        };
+        state.send_message(req).await.unwrap();
+
+        let req = ReceiveMessageRequest { queue_url: url, max_number_of_messages: Some(10), ..Default::default() };
+        let bodies: Vec<String> =
+            state.receive_message(req).await.unwrap().messages.unwrap_or_default().into_iter().map(|m| m.body).collect();
+        assert_eq!(bodies, ["first", "second"]);
+    }
 }
@@ -471,3 +471,67 @@ pub struct MessageMoveTaskResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub started_timestamp: Option<i64>,
 }
+
+// --- Debug (non-AWS) ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DebugQueueResponse {
+    pub queue_name: String,
+    pub queue_url: String,
+    pub visible_messages: Vec<DebugMessage>,
+    pub inflight_messages: Vec<DebugMessage>,
+    pub delayed_count: usize,
+    pub dedup_cache_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DebugMessage {
+    pub message_id: String,
+    pub body: String,
+    pub receive_count: u32,
+    pub sent_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt_handle: Option<String>,
+    /// For inflight messages, seconds until the visibility timeout lapses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible_in_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_group_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence_number: Option<String>,
+}
+
+/// A queue's messages as dumped by `GET /_debug/queues/{name}/messages` and
+/// loaded by the `PUT` on the same path.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct MessageDump {
+    #[serde(default)]
+    pub messages: Vec<DumpedMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct DumpedMessage {
+    /// Kept on import so that logs and assertions can follow a message
+    /// across queues; a new ID is assigned when empty.
+    #[serde(default)]
+    pub message_id: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub message_attributes: HashMap<String, MessageAttributeValue>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub message_system_attributes: HashMap<String, MessageAttributeValue>,
+    #[serde(default)]
+    pub sent_timestamp: u64,
+    #[serde(default)]
+    pub receive_count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_receive_timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_group_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_deduplication_id: Option<String>,
+}
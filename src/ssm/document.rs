@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::error::SsmError;
+use super::types::DocumentParameter;
+
+/// Output beyond this many characters is cut off, as in GetCommandInvocation.
+const MAX_OUTPUT_CHARS: usize = 24_000;
+
+const DEFAULT_TIMEOUT_SECONDS: u64 = 3600;
+
+/// The parts of a Command document that Run Command needs.
+#[derive(Debug, Clone)]
+pub struct DocumentSpec {
+    pub schema_version: String,
+    pub parameters: Vec<DocumentParameter>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub name: String,
+    pub action: String,
+    pub run_command: Vec<String>,
+    working_directory: Option<String>,
+    timeout_seconds: Option<String>,
+}
+
+impl Step {
+    /// The step's commands as one script, with parameters substituted.
+    pub fn script(&self, values: &HashMap<String, String>) -> String {
+        self.run_command.iter().map(|line| substitute(line, values)).collect::<Vec<_>>().join("\n")
+    }
+
+    pub fn working_directory(&self, values: &HashMap<String, String>) -> Option<String> {
+        self.working_directory
+            .as_deref()
+            .map(|dir| substitute(dir, values))
+            .filter(|dir| !dir.is_empty())
+    }
+
+    pub fn timeout(&self, values: &HashMap<String, String>) -> Duration {
+        let seconds = self
+            .timeout_seconds
+            .as_deref()
+            .and_then(|t| substitute(t, values).trim().parse().ok())
+            .unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+        Duration::from_secs(seconds)
+    }
+}
+
+/// Parses JSON document content. Schema 2.x documents list `mainSteps`;
+/// schema 1.2 documents list plugins under `runtimeConfig`.
+pub fn parse(content: &str) -> Result<DocumentSpec, SsmError> {
+    let doc: Value = serde_json::from_str(content)
+        .map_err(|e| SsmError::InvalidDocumentContent(format!("Document content is not valid JSON: {e}")))?;
+    let schema_version = doc["schemaVersion"]
+        .as_str()
+        .ok_or_else(|| SsmError::InvalidDocumentContent("schemaVersion is required".to_string()))?
+        .to_string();
+
+    let mut parameters: Vec<DocumentParameter> = doc["parameters"]
+        .as_object()
+        .map(|params| {
+            params
+                .iter()
+                .map(|(name, p)| DocumentParameter {
+                    name: name.clone(),
+                    param_type: p["type"].as_str().unwrap_or("String").to_string(),
+                    description: p["description"].as_str().map(str::to_string),
+                    default_value: match &p["default"] {
+                        Value::Null => None,
+                        Value::String(s) => Some(s.clone()),
+                        Value::Array(items) => Some(items.iter().map(text).collect::<Vec<_>>().join("\n")),
+                        other => Some(other.to_string()),
+                    },
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    parameters.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let steps = if let Some(main_steps) = doc["mainSteps"].as_array() {
+        main_steps
+            .iter()
+            .map(|step| {
+                let action = step["action"]
+                    .as_str()
+                    .ok_or_else(|| SsmError::InvalidDocumentContent("Each step requires an action".to_string()))?;
+                let name = step["name"].as_str().unwrap_or(action);
+                Ok(plugin(name, action, &step["inputs"]))
+            })
+            .collect::<Result<Vec<_>, SsmError>>()?
+    } else if let Some(runtime) = doc["runtimeConfig"].as_object() {
+        runtime
+            .iter()
+            .flat_map(|(action, config)| {
+                let properties = match &config["properties"] {
+                    Value::Array(items) => items.clone(),
+                    Value::Null => Vec::new(),
+                    single => vec![single.clone()],
+                };
+                properties.into_iter().map(move |p| plugin(action, action, &p)).collect::<Vec<_>>()
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if steps.is_empty() {
+        return Err(SsmError::InvalidDocumentContent("Document must contain at least one step".to_string()));
+    }
+    Ok(DocumentSpec { schema_version, parameters, steps })
+}
+
+fn plugin(name: &str, action: &str, inputs: &Value) -> Step {
+    let run_command = match &inputs["runCommand"] {
+        Value::Array(lines) => lines.iter().map(text).collect(),
+        Value::String(s) => vec![s.clone()],
+        _ => Vec::new(),
+    };
+    Step {
+        name: name.to_string(),
+        action: action.to_string(),
+        run_command,
+        working_directory: inputs["workingDirectory"].as_str().map(str::to_string),
+        timeout_seconds: match &inputs["timeoutSeconds"] {
+            Value::Null => None,
+            other => Some(text(other)),
+        },
+    }
+}
+
+fn text(value: &Value) -> String {
+    value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string())
+}
+
+/// Resolves the values sent with a command against the document's
+/// declared parameters, filling in defaults.
+pub fn resolve_parameters(
+    declared: &[DocumentParameter],
+    supplied: &HashMap<String, Vec<String>>,
+) -> Result<HashMap<String, String>, SsmError> {
+    if let Some(unknown) = supplied.keys().find(|k| !declared.iter().any(|p| &p.name == *k)) {
+        return Err(SsmError::InvalidParameters(format!(
+            "Parameter \"{unknown}\" is not defined in the document"
+        )));
+    }
+    declared
+        .iter()
+        .map(|p| {
+            let value = match supplied.get(&p.name) {
+                Some(values) => values.join("\n"),
+                None => p.default_value.clone().ok_or_else(|| {
+                    SsmError::InvalidParameters(format!("Missing required parameter \"{}\"", p.name))
+                })?,
+            };
+            Ok((p.name.clone(), value))
+        })
+        .collect()
+}
+
+/// Replaces `{{ name }}` placeholders with parameter values.
+pub fn substitute(line: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + len].trim();
+        out.push_str(&rest[..start]);
+        match values.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The outcome of one plugin on one instance.
+#[derive(Debug, Clone)]
+pub struct PluginOutcome {
+    pub status: String,
+    pub response_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl PluginOutcome {
+    fn failed(message: &str) -> Self {
+        PluginOutcome {
+            status: "Failed".to_string(),
+            response_code: -1,
+            stdout: String::new(),
+            stderr: message.to_string(),
+        }
+    }
+}
+
+/// Runs an `aws:runShellScript` step with `sh` on the host. Only called when
+/// the server was started with `--ssm-allow-exec`.
+pub async fn run_shell(script: &str, working_directory: Option<&str>, timeout: Duration) -> PluginOutcome {
+    let mut command = tokio::process::Command::new("sh");
+    command.arg("-c").arg(script).kill_on_drop(true);
+    if let Some(dir) = working_directory {
+        command.current_dir(dir);
+    }
+    match tokio::time::timeout(timeout, command.output()).await {
+        Err(_) => PluginOutcome {
+            status: "TimedOut".to_string(),
+            response_code: -1,
+            stdout: String::new(),
+            stderr: format!("Execution timed out after {} seconds", timeout.as_secs()),
+        },
+        Ok(Err(e)) => PluginOutcome::failed(&format!("Failed to start shell: {e}")),
+        Ok(Ok(output)) => {
+            let code = output.status.code().unwrap_or(-1);
+            PluginOutcome {
+                status: if code == 0 { "Success" } else { "Failed" }.to_string(),
+                response_code: code,
+                stdout: truncate(&String::from_utf8_lossy(&output.stdout)),
+                stderr: truncate(&String::from_utf8_lossy(&output.stderr)),
+            }
+        }
+    }
+}
+
+/// The outcome of a step that is not executed.
+pub fn not_executed(action: &str, allow_exec: bool) -> PluginOutcome {
+    if action != "aws:runShellScript" {
+        PluginOutcome::failed(&format!("Plugin {action} is not supported"))
+    } else if !allow_exec {
+        PluginOutcome::failed("Command execution is disabled; start the server with --ssm-allow-exec")
+    } else {
+        PluginOutcome::failed("Step has no runCommand")
+    }
+}
+
+fn truncate(s: &str) -> String {
+    s.chars().take(MAX_OUTPUT_CHARS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schema_versions() {
+        let v22 = parse(
+            r#"{"schemaVersion": "2.2", "parameters": {"msg": {"type": "String", "default": "hi"}},
+                "mainSteps": [{"action": "aws:runShellScript", "name": "say", "inputs": {"runCommand": ["echo {{ msg }}"]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(v22.steps[0].name, "say");
+        assert_eq!(v22.parameters[0].default_value.as_deref(), Some("hi"));
+
+        let v12 = parse(
+            r#"{"schemaVersion": "1.2", "runtimeConfig": {"aws:runShellScript": {"properties": [{"runCommand": "ls"}]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(v12.steps[0].name, "aws:runShellScript");
+        assert_eq!(v12.steps[0].run_command, vec!["ls"]);
+        assert_eq!(v12.steps[0].timeout(&HashMap::new()), Duration::from_secs(3600));
+
+        assert!(parse(r#"{"schemaVersion": "2.2", "mainSteps": []}"#).is_err());
+        assert!(parse("mainSteps:").is_err());
+    }
+
+    #[test]
+    fn test_parameters_and_substitution() {
+        let declared = vec![DocumentParameter {
+            name: "msg".to_string(),
+            param_type: "String".to_string(),
+            description: None,
+            default_value: None,
+        }];
+        assert!(resolve_parameters(&declared, &HashMap::new()).is_err());
+        let extra = HashMap::from([("other".to_string(), vec!["x".to_string()])]);
+        assert!(resolve_parameters(&declared, &extra).is_err());
+        let values =
+            resolve_parameters(&declared, &HashMap::from([("msg".to_string(), vec!["hello".to_string()])])).unwrap();
+        assert_eq!(substitute("echo {{msg}} {{ msg }} {{ unknown }}", &values), "echo hello hello {{ unknown }}");
+    }
+}
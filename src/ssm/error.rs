@@ -1,12 +1,24 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum SsmError {
     ParameterNotFound(String),
     ParameterAlreadyExists(String),
     InvalidAction(String),
+    DocumentAlreadyExists(String),
+    InvalidDocument(String),
+    InvalidDocumentContent(String),
+    InvalidParameters(String),
+    InvalidInstanceId(String),
+    InvalidCommandId(String),
+    InvocationDoesNotExist(String),
+    InvalidPluginName(String),
+    InvalidFilterKey(String),
+    InvalidFilterOption(String),
+    InvalidFilterValue(String),
 }
 
 impl SsmError {
@@ -15,6 +27,17 @@ impl SsmError {
             SsmError::ParameterNotFound(_) => "ParameterNotFound",
             SsmError::ParameterAlreadyExists(_) => "ParameterAlreadyExists",
             SsmError::InvalidAction(_) => "InvalidAction",
+            SsmError::DocumentAlreadyExists(_) => "DocumentAlreadyExists",
+            SsmError::InvalidDocument(_) => "InvalidDocument",
+            SsmError::InvalidDocumentContent(_) => "InvalidDocumentContent",
+            SsmError::InvalidParameters(_) => "InvalidParameters",
+            SsmError::InvalidInstanceId(_) => "InvalidInstanceId",
+            SsmError::InvalidCommandId(_) => "InvalidCommandId",
+            SsmError::InvocationDoesNotExist(_) => "InvocationDoesNotExist",
+            SsmError::InvalidPluginName(_) => "InvalidPluginName",
+            SsmError::InvalidFilterKey(_) => "InvalidFilterKey",
+            SsmError::InvalidFilterOption(_) => "InvalidFilterOption",
+            SsmError::InvalidFilterValue(_) => "InvalidFilterValue",
         }
     }
 
@@ -29,18 +52,39 @@ impl SsmError {
         match self {
             SsmError::ParameterNotFound(m)
             | SsmError::ParameterAlreadyExists(m)
-            | SsmError::InvalidAction(m) => m,
+            | SsmError::InvalidAction(m)
+            | SsmError::DocumentAlreadyExists(m)
+            | SsmError::InvalidDocument(m)
+            | SsmError::InvalidDocumentContent(m)
+            | SsmError::InvalidParameters(m)
+            | SsmError::InvalidInstanceId(m)
+            | SsmError::InvalidCommandId(m)
+            | SsmError::InvocationDoesNotExist(m)
+            | SsmError::InvalidPluginName(m)
+            | SsmError::InvalidFilterKey(m)
+            | SsmError::InvalidFilterOption(m)
+            | SsmError::InvalidFilterValue(m) => m,
         }
     }
 }
 
+impl AwsError for SsmError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        SsmError::message(self)
+    }
+}
+
 impl IntoResponse for SsmError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -64,6 +108,11 @@ mod tests {
         assert_eq!(err.error_code(), "InvalidAction");
     }
     #[test]
+    fn test_invocationdoesnotexist_error_code() {
+        let err = SsmError::InvocationDoesNotExist("test".to_string());
+        assert_eq!(err.error_code(), "InvocationDoesNotExist");
+    }
+    #[test]
     fn test_message() {
         let err = SsmError::ParameterNotFound("hello world".to_string());
         assert_eq!(err.message(), "hello world");
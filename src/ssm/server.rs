@@ -66,6 +66,13 @@ async fn handle_request(
         "ListTagsForResource" => {
             dispatch!(state, body, ListTagsForResourceRequest, list_tags_for_resource)
         }
+        "CreateDocument" => dispatch!(state, body, CreateDocumentRequest, create_document),
+        "DescribeDocument" => dispatch!(state, body, DescribeDocumentRequest, describe_document),
+        "SendCommand" => dispatch!(state, body, SendCommandRequest, send_command),
+        "ListCommands" => dispatch!(state, body, ListCommandsRequest, list_commands),
+        "GetCommandInvocation" => {
+            dispatch!(state, body, GetCommandInvocationRequest, get_command_invocation)
+        }
         _ => Err(SsmError::InvalidAction(format!("Unknown action: {action}"))),
     }
 }
@@ -73,6 +80,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<SsmState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("ssm"))
         .with_state(state)
 }
 
@@ -1,11 +1,37 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sha2::{Digest, Sha256};
 use tokio::sync::Mutex;
 
+use super::document::{self, DocumentSpec, PluginOutcome, Step};
 use super::error::SsmError;
 use super::types::*;
 
+/// The managed instance Run Command targets when none are configured.
+pub const DEFAULT_INSTANCE_ID: &str = "i-0123456789abcdef0";
+
+const RUN_SHELL_SCRIPT_DOCUMENT: &str = r#"{
+  "schemaVersion": "1.2",
+  "description": "Run a shell script or specify the commands to run.",
+  "parameters": {
+    "commands": {"type": "StringList", "description": "(Required) Specify a shell script or a command to run."},
+    "workingDirectory": {"type": "String", "default": "", "description": "(Optional) The path to the working directory on your instance."},
+    "executionTimeout": {"type": "String", "default": "3600", "description": "(Optional) The time in seconds for a command to complete before it is considered to have failed."}
+  },
+  "runtimeConfig": {
+    "aws:runShellScript": {
+      "properties": [{
+        "id": "0.aws:runShellScript",
+        "runCommand": "{{ commands }}",
+        "workingDirectory": "{{ workingDirectory }}",
+        "timeoutSeconds": "{{ executionTimeout }}"
+      }]
+    }
+  }
+}"#;
+
 struct SsmParameter {
     name: String,
     value: String,
@@ -17,27 +43,232 @@ struct SsmParameter {
     tags: HashMap<String, String>,
     tier: String,
     data_type: String,
+    key_id: Option<String>,
+}
+
+impl SsmParameter {
+    /// The parameter as returned by the Get* APIs. SecureString values are
+    /// only returned in plaintext when decryption is requested.
+    fn view(&self, with_decryption: bool) -> Parameter {
+        let value = match &self.key_id {
+            Some(key_id) if self.param_type == "SecureString" && !with_decryption => {
+                Self::ciphertext(key_id, &self.value, self.version)
+            }
+            _ => self.value.clone(),
+        };
+        Parameter {
+            name: self.name.clone(),
+            param_type: self.param_type.clone(),
+            value,
+            version: self.version,
+            arn: self.arn.clone(),
+            last_modified_date: self.last_modified_date,
+            data_type: self.data_type.clone(),
+        }
+    }
+
+    /// An opaque stand-in for the KMS ciphertext of a SecureString value.
+    fn ciphertext(key_id: &str, value: &str, version: i64) -> String {
+        let digest = Sha256::new()
+            .chain_update(key_id.as_bytes())
+            .chain_update(version.to_be_bytes())
+            .chain_update(value.as_bytes())
+            .finalize();
+        BASE64.encode([b"AQICAH".as_slice(), digest.as_slice()].concat())
+    }
+
+    fn metadata(&self) -> ParameterMetadata {
+        ParameterMetadata {
+            name: self.name.clone(),
+            param_type: self.param_type.clone(),
+            version: self.version,
+            last_modified_date: self.last_modified_date,
+            arn: self.arn.clone(),
+            description: self.description.clone(),
+            tier: self.tier.clone(),
+            data_type: self.data_type.clone(),
+            key_id: self.key_id.clone(),
+        }
+    }
+
+    /// Whether the parameter passes every filter. `path_filters` enables the
+    /// `Name` and `Path` keys, which only DescribeParameters accepts.
+    fn matches(&self, filters: &[ParameterStringFilter], path_filters: bool) -> Result<bool, SsmError> {
+        for filter in filters {
+            let option = filter.option.as_deref().unwrap_or("Equals");
+            let key = filter.key.to_ascii_lowercase();
+            let field = match key.as_str() {
+                "type" => {
+                    if let Some(bad) = filter.values.iter()
+                        .find(|v| !["String", "StringList", "SecureString"].contains(&v.as_str()))
+                    {
+                        return Err(SsmError::InvalidFilterValue(format!("Invalid Type filter value: {bad}")));
+                    }
+                    Some(self.param_type.as_str())
+                }
+                "tier" => {
+                    if let Some(bad) = filter.values.iter()
+                        .find(|v| !["Standard", "Advanced", "Intelligent-Tiering"].contains(&v.as_str()))
+                    {
+                        return Err(SsmError::InvalidFilterValue(format!("Invalid Tier filter value: {bad}")));
+                    }
+                    Some(self.tier.as_str())
+                }
+                "keyid" => self.key_id.as_deref(),
+                "datatype" => Some(self.data_type.as_str()),
+                "name" if path_filters => Some(self.name.as_str()),
+                "path" if path_filters => {
+                    let recursive = match option {
+                        "Recursive" => true,
+                        "OneLevel" => false,
+                        other => {
+                            return Err(SsmError::InvalidFilterOption(format!(
+                                "Option {other} is not valid for the Path filter; use Recursive or OneLevel"
+                            )))
+                        }
+                    };
+                    if !filter.values.iter().any(|path| in_path(&self.name, path, recursive)) {
+                        return Ok(false);
+                    }
+                    continue;
+                }
+                _ => match filter.key.strip_prefix("tag:") {
+                    Some(tag) => self.tags.get(tag).map(String::as_str),
+                    None => {
+                        return Err(SsmError::InvalidFilterKey(format!("Invalid filter key: {}", filter.key)));
+                    }
+                },
+            };
+            let Some(field) = field else {
+                return Ok(false);
+            };
+            let matched = match option {
+                // A tag filter without values only requires the tag to exist.
+                _ if filter.values.is_empty() && filter.key.starts_with("tag:") => true,
+                "Equals" => filter.values.iter().any(|v| v == field),
+                "BeginsWith" => filter.values.iter().any(|v| field.starts_with(v.as_str())),
+                other => {
+                    return Err(SsmError::InvalidFilterOption(format!(
+                        "Option {other} is not valid for the {} filter", filter.key
+                    )))
+                }
+            };
+            if !matched {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Whether `name` lies under `path`, directly or (when `recursive`) at any
+/// depth.
+fn in_path(name: &str, path: &str, recursive: bool) -> bool {
+    let prefix = if path.ends_with('/') { path.to_string() } else { format!("{path}/") };
+    match name.strip_prefix(&prefix) {
+        Some(rest) => recursive || !rest.contains('/'),
+        None => false,
+    }
+}
+
+struct SsmDocument {
+    description: DocumentDescription,
+    spec: DocumentSpec,
+}
+
+struct CommandRecord {
+    command: Command,
+    invocations: Vec<Invocation>,
+}
+
+struct Invocation {
+    instance_id: String,
+    plugins: Vec<PluginResult>,
+}
+
+struct PluginResult {
+    name: String,
+    outcome: Option<PluginOutcome>,
+    status: String,
+    start: String,
+    end: String,
+}
+
+impl Invocation {
+    fn status(&self) -> &str {
+        if let Some(running) = self.plugins.iter().find(|p| p.outcome.is_none() && p.status != "Pending") {
+            return &running.status;
+        }
+        if self.plugins.iter().any(|p| p.outcome.is_none()) {
+            return "Pending";
+        }
+        self.plugins
+            .iter()
+            .map(|p| p.status.as_str())
+            .find(|status| *status != "Success")
+            .unwrap_or("Success")
+    }
 }
 
 struct SsmStateInner {
     parameters: HashMap<String, SsmParameter>,
+    documents: HashMap<String, SsmDocument>,
+    commands: Vec<CommandRecord>,
     account_id: String,
     region: String,
 }
 
 pub struct SsmState {
     inner: Arc<Mutex<SsmStateInner>>,
+    instance_ids: Vec<String>,
+    allow_exec: bool,
 }
 
 impl SsmState {
     pub fn new(account_id: String, region: String) -> Self {
+        let mut documents = HashMap::new();
+        let spec = document::parse(RUN_SHELL_SCRIPT_DOCUMENT).expect("built-in document is valid");
+        let description = Self::describe(
+            "AWS-RunShellScript",
+            "Command",
+            "JSON",
+            "Amazon",
+            RUN_SHELL_SCRIPT_DOCUMENT,
+            &spec,
+        );
+        documents.insert("AWS-RunShellScript".to_string(), SsmDocument { description, spec });
         SsmState {
             inner: Arc::new(Mutex::new(SsmStateInner {
                 parameters: HashMap::new(),
+                documents,
+                commands: Vec::new(),
                 account_id,
                 region,
             })),
+            instance_ids: vec![DEFAULT_INSTANCE_ID.to_string()],
+            allow_exec: false,
+        }
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
+    }
+
+    /// Sets the fake managed instances Run Command can target, and whether
+    /// `aws:runShellScript` steps actually run on the host.
+    pub fn with_run_command(mut self, instance_ids: Vec<String>, allow_exec: bool) -> Self {
+        if !instance_ids.is_empty() {
+            self.instance_ids = instance_ids;
         }
+        self.allow_exec = allow_exec;
+        self
+    }
+
+    fn timestamp() -> String {
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
     }
 
     fn now() -> f64 {
@@ -71,10 +302,15 @@ impl SsmState {
         }
         let tier = req.tier.unwrap_or_else(|| "Standard".to_string());
         let data_type = req.data_type.unwrap_or_else(|| "text".to_string());
+        let param_type = req.param_type.unwrap_or_else(|| "String".to_string());
+        // SecureStrings are encrypted with the account's SSM key unless
+        // another KMS key is given.
+        let key_id = (param_type == "SecureString")
+            .then(|| req.key_id.unwrap_or_else(|| "alias/aws/ssm".to_string()));
         state.parameters.insert(req.name.clone(), SsmParameter {
             name: req.name,
             value: req.value,
-            param_type: req.param_type.unwrap_or_else(|| "String".to_string()),
+            param_type,
             description: req.description,
             version,
             arn,
@@ -82,6 +318,7 @@ impl SsmState {
             tags,
             tier: tier.clone(),
             data_type,
+            key_id,
         });
         Ok(PutParameterResponse { version, tier })
     }
@@ -93,33 +330,18 @@ impl SsmState {
                 "Parameter {} not found", req.name
             )))?;
         Ok(GetParameterResponse {
-            parameter: Parameter {
-                name: param.name.clone(),
-                param_type: param.param_type.clone(),
-                value: param.value.clone(),
-                version: param.version,
-                arn: param.arn.clone(),
-                last_modified_date: param.last_modified_date,
-                data_type: param.data_type.clone(),
-            },
+            parameter: param.view(req.with_decryption.unwrap_or(false)),
         })
     }
 
     pub async fn get_parameters(&self, req: GetParametersRequest) -> Result<GetParametersResponse, SsmError> {
         let state = self.inner.lock().await;
+        let with_decryption = req.with_decryption.unwrap_or(false);
         let mut parameters = Vec::new();
         let mut invalid = Vec::new();
         for name in &req.names {
             if let Some(param) = state.parameters.get(name) {
-                parameters.push(Parameter {
-                    name: param.name.clone(),
-                    param_type: param.param_type.clone(),
-                    value: param.value.clone(),
-                    version: param.version,
-                    arn: param.arn.clone(),
-                    last_modified_date: param.last_modified_date,
-                    data_type: param.data_type.clone(),
-                });
+                parameters.push(param.view(with_decryption));
             } else {
                 invalid.push(name.clone());
             }
@@ -133,37 +355,15 @@ impl SsmState {
     ) -> Result<GetParametersByPathResponse, SsmError> {
         let state = self.inner.lock().await;
         let recursive = req.recursive.unwrap_or(false);
-        let path = if req.path.ends_with('/') {
-            req.path.clone()
-        } else {
-            format!("{}/", req.path)
-        };
+        let with_decryption = req.with_decryption.unwrap_or(false);
+        let filters = req.parameter_filters.unwrap_or_default();
 
-        let mut params: Vec<Parameter> = state.parameters.values()
-            .filter(|p| {
-                let pname = if p.name.starts_with('/') { p.name.as_str() } else { &p.name };
-                if recursive {
-                    pname.starts_with(&path) || pname == req.path.trim_end_matches('/')
-                } else {
-                    // Non-recursive: only direct children
-                    if pname.starts_with(&path) {
-                        let rest = &pname[path.len()..];
-                        !rest.contains('/')
-                    } else {
-                        false
-                    }
-                }
-            })
-            .map(|p| Parameter {
-                name: p.name.clone(),
-                param_type: p.param_type.clone(),
-                value: p.value.clone(),
-                version: p.version,
-                arn: p.arn.clone(),
-                last_modified_date: p.last_modified_date,
-                data_type: p.data_type.clone(),
-            })
-            .collect();
+        let mut params = Vec::new();
+        for p in state.parameters.values() {
+            if in_path(&p.name, &req.path, recursive) && p.matches(&filters, false)? {
+                params.push(p.view(with_decryption));
+            }
+        }
 
         params.sort_by(|a, b| a.name.cmp(&b.name));
         let limit = req.max_results.unwrap_or(10);
@@ -202,18 +402,13 @@ impl SsmState {
 
     pub async fn describe_parameters(&self, req: DescribeParametersRequest) -> Result<DescribeParametersResponse, SsmError> {
         let state = self.inner.lock().await;
-        let mut params: Vec<ParameterMetadata> = state.parameters.values()
-            .map(|p| ParameterMetadata {
-                name: p.name.clone(),
-                param_type: p.param_type.clone(),
-                version: p.version,
-                last_modified_date: p.last_modified_date,
-                arn: p.arn.clone(),
-                description: p.description.clone(),
-                tier: p.tier.clone(),
-                data_type: p.data_type.clone(),
-            })
-            .collect();
+        let filters = req.parameter_filters.unwrap_or_default();
+        let mut params = Vec::new();
+        for p in state.parameters.values() {
+            if p.matches(&filters, true)? {
+                params.push(p.metadata());
+            }
+        }
         params.sort_by(|a, b| a.name.cmp(&b.name));
         let limit = req.max_results.unwrap_or(50);
         let has_more = params.len() > limit;
@@ -262,6 +457,256 @@ impl SsmState {
         tag_list.sort_by(|a, b| a.key.cmp(&b.key));
         Ok(ListTagsForResourceResponse { tag_list })
     }
+
+    // --- Documents ---
+
+    fn describe(
+        name: &str,
+        document_type: &str,
+        document_format: &str,
+        owner: &str,
+        content: &str,
+        spec: &DocumentSpec,
+    ) -> DocumentDescription {
+        DocumentDescription {
+            name: name.to_string(),
+            document_type: document_type.to_string(),
+            document_format: document_format.to_string(),
+            schema_version: spec.schema_version.clone(),
+            document_version: "1".to_string(),
+            latest_version: "1".to_string(),
+            default_version: "1".to_string(),
+            status: "Active".to_string(),
+            owner: owner.to_string(),
+            created_date: Self::now(),
+            hash: format!("{:x}", Sha256::digest(content.as_bytes())),
+            hash_type: "Sha256".to_string(),
+            platform_types: vec!["Linux".to_string(), "MacOS".to_string()],
+            parameters: spec.parameters.clone(),
+        }
+    }
+
+    pub async fn create_document(&self, req: CreateDocumentRequest) -> Result<CreateDocumentResponse, SsmError> {
+        let mut state = self.inner.lock().await;
+        if state.documents.contains_key(&req.name) {
+            return Err(SsmError::DocumentAlreadyExists(format!(
+                "Document with name {} already exists", req.name
+            )));
+        }
+        let document_format = req.document_format.unwrap_or_else(|| "JSON".to_string());
+        if document_format != "JSON" {
+            return Err(SsmError::InvalidDocumentContent(format!(
+                "Document format {document_format} is not supported; use JSON"
+            )));
+        }
+        let spec = document::parse(&req.content)?;
+        let document_type = req.document_type.unwrap_or_else(|| "Command".to_string());
+        let owner = state.account_id.clone();
+        let description = Self::describe(&req.name, &document_type, &document_format, &owner, &req.content, &spec);
+        state.documents.insert(req.name, SsmDocument { description: description.clone(), spec });
+        Ok(CreateDocumentResponse { document_description: description })
+    }
+
+    pub async fn describe_document(&self, req: DescribeDocumentRequest) -> Result<DescribeDocumentResponse, SsmError> {
+        let state = self.inner.lock().await;
+        let doc = state.documents.get(&req.name)
+            .ok_or_else(|| SsmError::InvalidDocument(format!("Document {} does not exist", req.name)))?;
+        if let Some(version) = req.document_version.as_deref() {
+            if !["1", "$LATEST", "$DEFAULT"].contains(&version) {
+                return Err(SsmError::InvalidDocument(format!(
+                    "Document {} has no version {version}", req.name
+                )));
+            }
+        }
+        Ok(DescribeDocumentResponse { document: doc.description.clone() })
+    }
+
+    // --- Run Command ---
+
+    pub async fn send_command(&self, req: SendCommandRequest) -> Result<SendCommandResponse, SsmError> {
+        let mut state = self.inner.lock().await;
+        let doc = state.documents.get(&req.document_name)
+            .ok_or_else(|| SsmError::InvalidDocument(format!("Document {} does not exist", req.document_name)))?;
+        if doc.description.document_type != "Command" {
+            return Err(SsmError::InvalidDocument(format!(
+                "Document {} is not a Command document", req.document_name
+            )));
+        }
+        if req.instance_ids.is_empty() {
+            return Err(SsmError::InvalidInstanceId("InstanceIds must not be empty".to_string()));
+        }
+        if let Some(unknown) = req.instance_ids.iter().find(|id| !self.instance_ids.contains(id)) {
+            return Err(SsmError::InvalidInstanceId(format!("Instance {unknown} is not a managed instance")));
+        }
+        let values = document::resolve_parameters(&doc.spec.parameters, &req.parameters)?;
+        let steps = doc.spec.steps.clone();
+
+        let command = Command {
+            command_id: uuid::Uuid::new_v4().to_string(),
+            document_name: req.document_name,
+            comment: req.comment.unwrap_or_default(),
+            instance_ids: req.instance_ids.clone(),
+            parameters: req.parameters,
+            requested_date_time: Self::now(),
+            status: "Pending".to_string(),
+            status_details: "Pending".to_string(),
+            timeout_seconds: req.timeout_seconds.unwrap_or(3600),
+            target_count: req.instance_ids.len(),
+            completed_count: 0,
+            error_count: 0,
+        };
+        let invocations = req.instance_ids.iter().map(|instance_id| Invocation {
+            instance_id: instance_id.clone(),
+            plugins: steps.iter().map(|step| PluginResult {
+                name: step.name.clone(),
+                outcome: None,
+                status: "Pending".to_string(),
+                start: String::new(),
+                end: String::new(),
+            }).collect(),
+        }).collect();
+        state.commands.push(CommandRecord { command: command.clone(), invocations });
+        drop(state);
+
+        for instance_id in req.instance_ids {
+            tokio::spawn(run_invocation(
+                self.inner.clone(),
+                command.command_id.clone(),
+                instance_id,
+                steps.clone(),
+                values.clone(),
+                self.allow_exec,
+            ));
+        }
+        Ok(SendCommandResponse { command })
+    }
+
+    pub async fn list_commands(&self, req: ListCommandsRequest) -> Result<ListCommandsResponse, SsmError> {
+        let state = self.inner.lock().await;
+        if let Some(id) = req.command_id.as_deref() {
+            if !state.commands.iter().any(|c| c.command.command_id == id) {
+                return Err(SsmError::InvalidCommandId(format!("Command {id} does not exist")));
+            }
+        }
+        let mut commands: Vec<Command> = state.commands.iter().rev()
+            .filter(|c| req.command_id.as_deref().is_none_or(|id| c.command.command_id == id))
+            .filter(|c| req.instance_id.as_ref().is_none_or(|id| c.command.instance_ids.contains(id)))
+            .map(|c| c.command.clone())
+            .collect();
+        commands.truncate(req.max_results.unwrap_or(50));
+        Ok(ListCommandsResponse { commands })
+    }
+
+    pub async fn get_command_invocation(
+        &self,
+        req: GetCommandInvocationRequest,
+    ) -> Result<GetCommandInvocationResponse, SsmError> {
+        let state = self.inner.lock().await;
+        let record = state.commands.iter()
+            .find(|c| c.command.command_id == req.command_id)
+            .ok_or_else(|| SsmError::InvalidCommandId(format!("Command {} does not exist", req.command_id)))?;
+        let invocation = record.invocations.iter()
+            .find(|i| i.instance_id == req.instance_id)
+            .ok_or_else(|| SsmError::InvocationDoesNotExist(format!(
+                "Command {} was not sent to instance {}", req.command_id, req.instance_id
+            )))?;
+        let plugin = match req.plugin_name.as_deref() {
+            Some(name) => invocation.plugins.iter().find(|p| p.name == name)
+                .ok_or_else(|| SsmError::InvalidPluginName(format!("Plugin {name} is not in the document")))?,
+            None if invocation.plugins.len() == 1 => &invocation.plugins[0],
+            None => return Err(SsmError::InvalidPluginName(
+                "PluginName is required for documents with more than one step".to_string(),
+            )),
+        };
+        let outcome = plugin.outcome.as_ref();
+        Ok(GetCommandInvocationResponse {
+            command_id: record.command.command_id.clone(),
+            instance_id: invocation.instance_id.clone(),
+            document_name: record.command.document_name.clone(),
+            comment: record.command.comment.clone(),
+            plugin_name: plugin.name.clone(),
+            response_code: outcome.map(|o| o.response_code).unwrap_or(-1),
+            status: plugin.status.clone(),
+            status_details: plugin.status.clone(),
+            standard_output_content: outcome.map(|o| o.stdout.clone()).unwrap_or_default(),
+            standard_error_content: outcome.map(|o| o.stderr.clone()).unwrap_or_default(),
+            execution_start_date_time: plugin.start.clone(),
+            execution_end_date_time: plugin.end.clone(),
+        })
+    }
+}
+
+/// Runs a command's steps on one fake instance, recording each plugin's
+/// outcome and rolling the invocation up into the command status.
+async fn run_invocation(
+    inner: Arc<Mutex<SsmStateInner>>,
+    command_id: String,
+    instance_id: String,
+    steps: Vec<Step>,
+    values: HashMap<String, String>,
+    allow_exec: bool,
+) {
+    for (index, step) in steps.iter().enumerate() {
+        update_plugin(&inner, &command_id, &instance_id, index, |plugin| {
+            plugin.status = "InProgress".to_string();
+            plugin.start = SsmState::timestamp();
+        }).await;
+        let outcome = if allow_exec && step.action == "aws:runShellScript" && !step.run_command.is_empty() {
+            let dir = step.working_directory(&values);
+            document::run_shell(&step.script(&values), dir.as_deref(), step.timeout(&values)).await
+        } else {
+            document::not_executed(&step.action, allow_exec)
+        };
+        update_plugin(&inner, &command_id, &instance_id, index, |plugin| {
+            plugin.status = outcome.status.clone();
+            plugin.end = SsmState::timestamp();
+            plugin.outcome = Some(outcome);
+        }).await;
+    }
+
+    let mut state = inner.lock().await;
+    let Some(record) = state.commands.iter_mut().find(|c| c.command.command_id == command_id) else {
+        return;
+    };
+    let statuses: Vec<&str> = record.invocations.iter().map(Invocation::status).collect();
+    let finished = statuses.iter().filter(|s| !matches!(**s, "Pending" | "InProgress")).count();
+    let command = &mut record.command;
+    command.completed_count = finished;
+    command.error_count = statuses.iter().filter(|s| matches!(**s, "Failed" | "TimedOut")).count();
+    command.status = if finished < statuses.len() {
+        "InProgress"
+    } else if statuses.iter().all(|s| *s == "Success") {
+        "Success"
+    } else if statuses.contains(&"TimedOut") {
+        "TimedOut"
+    } else {
+        "Failed"
+    }
+    .to_string();
+    command.status_details = command.status.clone();
+}
+
+async fn update_plugin(
+    inner: &Mutex<SsmStateInner>,
+    command_id: &str,
+    instance_id: &str,
+    index: usize,
+    f: impl FnOnce(&mut PluginResult),
+) {
+    let mut state = inner.lock().await;
+    let plugin = state.commands.iter_mut()
+        .find(|c| c.command.command_id == command_id)
+        .and_then(|c| c.invocations.iter_mut().find(|i| i.instance_id == instance_id))
+        .and_then(|i| i.plugins.get_mut(index));
+    if let Some(plugin) = plugin {
+        f(plugin);
+    }
+    if let Some(record) = state.commands.iter_mut().find(|c| c.command.command_id == command_id) {
+        if record.command.status == "Pending" {
+            record.command.status = "InProgress".to_string();
+            record.command.status_details = "InProgress".to_string();
+        }
+    }
 }
 
 
@@ -360,6 +805,7 @@ mod tests {
             data_type: Some("text".to_string()),
             overwrite: None,
             tags: Some(vec![Tag { key: "env".to_string(), value: "prod".to_string() }]),
+            key_id: None,
         }).await.unwrap();
         assert_eq!(result.version, 1);
         assert_eq!(result.tier, "Advanced");
@@ -420,6 +866,7 @@ mod tests {
         put_param(&state, "/app/db-host", "localhost").await;
         let result = state.get_parameter(GetParameterRequest {
             name: "/app/db-host".to_string(),
+            ..Default::default()
         }).await.unwrap();
         assert_eq!(result.parameter.name, "/app/db-host");
         assert_eq!(result.parameter.value, "localhost");
@@ -436,6 +883,7 @@ mod tests {
         put_param(&state, "p2", "v2").await;
         let result = state.get_parameters(GetParametersRequest {
             names: vec!["p1".to_string(), "p2".to_string(), "missing".to_string()],
+            ..Default::default()
         }).await.unwrap();
         assert_eq!(result.parameters.len(), 2);
         assert_eq!(result.invalid_parameters, vec!["missing".to_string()]);
@@ -453,6 +901,7 @@ mod tests {
             path: "/app".to_string(),
             recursive: Some(false),
             max_results: None,
+            ..Default::default()
         }).await.unwrap();
         assert_eq!(result.parameters.len(), 2);
     }
@@ -466,6 +915,7 @@ mod tests {
             path: "/app".to_string(),
             recursive: Some(true),
             max_results: None,
+            ..Default::default()
         }).await.unwrap();
         assert_eq!(result.parameters.len(), 2);
     }
@@ -480,6 +930,7 @@ mod tests {
             path: "/app".to_string(),
             recursive: Some(false),
             max_results: Some(2),
+            ..Default::default()
         }).await.unwrap();
         assert_eq!(result.parameters.len(), 2);
         assert!(result.next_token.is_some());
@@ -493,10 +944,85 @@ mod tests {
             path: "/app/".to_string(),
             recursive: Some(false),
             max_results: None,
+            ..Default::default()
         }).await.unwrap();
         assert_eq!(result.parameters.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_get_parameters_by_path_decrypts_only_secure_strings() {
+        let state = make_state();
+        for env in ["dev", "prod"] {
+            put_param(&state, &format!("/app/{env}/db-host"), &format!("{env}.db.local")).await;
+            state.put_parameter(PutParameterRequest {
+                name: format!("/app/{env}/db-password"),
+                value: format!("{env}-secret"),
+                param_type: Some("SecureString".to_string()),
+                ..Default::default()
+            }).await.unwrap();
+        }
+        state.put_parameter(PutParameterRequest {
+            name: "/app/prod/hosts".to_string(),
+            value: "a.local,b.local".to_string(),
+            param_type: Some("StringList".to_string()),
+            tier: Some("Advanced".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+
+        let by_path = |with_decryption, filters| GetParametersByPathRequest {
+            path: "/app/prod".to_string(),
+            with_decryption: Some(with_decryption),
+            parameter_filters: filters,
+            ..Default::default()
+        };
+        let decrypted = state.get_parameters_by_path(by_path(true, None)).await.unwrap();
+        let values: Vec<(&str, &str)> = decrypted.parameters.iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(values, vec![
+            ("/app/prod/db-host", "prod.db.local"),
+            ("/app/prod/db-password", "prod-secret"),
+            ("/app/prod/hosts", "a.local,b.local"),
+        ]);
+
+        let encrypted = state.get_parameters_by_path(by_path(false, None)).await.unwrap();
+        assert_eq!(encrypted.parameters[0].value, "prod.db.local");
+        assert_ne!(encrypted.parameters[1].value, "prod-secret");
+        assert_eq!(encrypted.parameters[1].param_type, "SecureString");
+
+        let filter = |key: &str, value: &str| Some(vec![ParameterStringFilter {
+            key: key.to_string(),
+            option: None,
+            values: vec![value.to_string()],
+        }]);
+        let secure = state.get_parameters_by_path(by_path(true, filter("Type", "SecureString"))).await.unwrap();
+        assert_eq!(secure.parameters.len(), 1);
+        assert_eq!(secure.parameters[0].value, "prod-secret");
+        let advanced = state.get_parameters_by_path(by_path(true, filter("tier", "Advanced"))).await.unwrap();
+        assert_eq!(advanced.parameters.len(), 1);
+        assert_eq!(advanced.parameters[0].name, "/app/prod/hosts");
+        let bad = state.get_parameters_by_path(by_path(true, filter("Type", "Binary"))).await;
+        assert!(matches!(bad, Err(SsmError::InvalidFilterValue(_))));
+
+        let described = state.describe_parameters(DescribeParametersRequest {
+            parameter_filters: Some(vec![
+                ParameterStringFilter {
+                    key: "Path".to_string(),
+                    option: Some("Recursive".to_string()),
+                    values: vec!["/app".to_string()],
+                },
+                ParameterStringFilter {
+                    key: "Type".to_string(),
+                    option: None,
+                    values: vec!["SecureString".to_string()],
+                },
+            ]),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(described.parameters.len(), 2);
+        assert_eq!(described.parameters[0].key_id.as_deref(), Some("alias/aws/ssm"));
+    }
+
     // --- Extended coverage: delete_parameter ---
 
     #[tokio::test]
@@ -504,7 +1030,7 @@ mod tests {
         let state = make_state();
         put_param(&state, "my-param", "val").await;
         assert!(state.delete_parameter(DeleteParameterRequest { name: "my-param".to_string() }).await.is_ok());
-        assert!(state.get_parameter(GetParameterRequest { name: "my-param".to_string() }).await.is_err());
+        assert!(state.get_parameter(GetParameterRequest { name: "my-param".to_string(), ..Default::default() }).await.is_err());
     }
 
     // --- Extended coverage: delete_parameters ---
@@ -532,7 +1058,7 @@ mod tests {
             description: Some("my desc".to_string()),
             ..Default::default()
         }).await.unwrap();
-        let result = state.describe_parameters(DescribeParametersRequest { max_results: None }).await.unwrap();
+        let result = state.describe_parameters(DescribeParametersRequest { max_results: None, ..Default::default() }).await.unwrap();
         assert_eq!(result.parameters.len(), 1);
         assert_eq!(result.parameters[0].name, "/app/key");
         assert_eq!(result.parameters[0].description.as_deref(), Some("my desc"));
@@ -544,7 +1070,7 @@ mod tests {
         put_param(&state, "p1", "v1").await;
         put_param(&state, "p2", "v2").await;
         put_param(&state, "p3", "v3").await;
-        let result = state.describe_parameters(DescribeParametersRequest { max_results: Some(2) }).await.unwrap();
+        let result = state.describe_parameters(DescribeParametersRequest { max_results: Some(2), ..Default::default() }).await.unwrap();
         assert_eq!(result.parameters.len(), 2);
         assert!(result.next_token.is_some());
     }
@@ -627,4 +1153,99 @@ mod tests {
         assert_eq!(tags.tag_list[0].key, "env");
         assert_eq!(tags.tag_list[0].value, "prod");
     }
+
+    // --- Run Command ---
+
+    async fn wait_for_invocation(state: &SsmState, command_id: &str) -> GetCommandInvocationResponse {
+        for _ in 0..200 {
+            let invocation = state.get_command_invocation(GetCommandInvocationRequest {
+                command_id: command_id.to_string(),
+                instance_id: DEFAULT_INSTANCE_ID.to_string(),
+                plugin_name: None,
+            }).await.unwrap();
+            if !matches!(invocation.status.as_str(), "Pending" | "InProgress") {
+                return invocation;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("command {command_id} did not finish");
+    }
+
+    #[tokio::test]
+    async fn test_send_shell_command_captures_output() {
+        let state = make_state().with_run_command(Vec::new(), true);
+        let sent = state.send_command(SendCommandRequest {
+            document_name: "AWS-RunShellScript".to_string(),
+            instance_ids: vec![DEFAULT_INSTANCE_ID.to_string()],
+            parameters: HashMap::from([(
+                "commands".to_string(),
+                vec!["echo hello".to_string(), "echo oops >&2".to_string(), "exit 3".to_string()],
+            )]),
+            ..Default::default()
+        }).await.unwrap();
+
+        let invocation = wait_for_invocation(&state, &sent.command.command_id).await;
+        assert_eq!(invocation.status, "Failed");
+        assert_eq!(invocation.response_code, 3);
+        assert_eq!(invocation.standard_output_content, "hello\n");
+        assert_eq!(invocation.standard_error_content, "oops\n");
+        assert_eq!(invocation.plugin_name, "aws:runShellScript");
+
+        let listed = state.list_commands(ListCommandsRequest {
+            command_id: Some(sent.command.command_id.clone()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(listed.commands[0].status, "Failed");
+        assert_eq!(listed.commands[0].completed_count, 1);
+        assert_eq!(listed.commands[0].error_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_custom_document_with_parameters() {
+        let state = make_state().with_run_command(Vec::new(), true);
+        let created = state.create_document(CreateDocumentRequest {
+            name: "Greet".to_string(),
+            content: r#"{"schemaVersion": "2.2", "parameters": {"who": {"type": "String", "default": "world"}},
+                "mainSteps": [{"action": "aws:runShellScript", "name": "greet", "inputs": {"runCommand": ["echo hello {{ who }}"]}}]}"#
+                .to_string(),
+            document_type: Some("Command".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(created.document_description.schema_version, "2.2");
+        let described = state.describe_document(DescribeDocumentRequest {
+            name: "Greet".to_string(),
+            document_version: None,
+        }).await.unwrap();
+        assert_eq!(described.document.parameters[0].name, "who");
+
+        let sent = state.send_command(SendCommandRequest {
+            document_name: "Greet".to_string(),
+            instance_ids: vec![DEFAULT_INSTANCE_ID.to_string()],
+            ..Default::default()
+        }).await.unwrap();
+        let invocation = wait_for_invocation(&state, &sent.command.command_id).await;
+        assert_eq!(invocation.status, "Success");
+        assert_eq!(invocation.standard_output_content, "hello world\n");
+
+        let unknown_instance = state.send_command(SendCommandRequest {
+            document_name: "Greet".to_string(),
+            instance_ids: vec!["i-unknown".to_string()],
+            ..Default::default()
+        }).await;
+        assert!(matches!(unknown_instance, Err(SsmError::InvalidInstanceId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_send_command_without_exec_fails_invocation() {
+        let state = make_state();
+        let sent = state.send_command(SendCommandRequest {
+            document_name: "AWS-RunShellScript".to_string(),
+            instance_ids: vec![DEFAULT_INSTANCE_ID.to_string()],
+            parameters: HashMap::from([("commands".to_string(), vec!["touch /tmp/should-not-exist".to_string()])]),
+            ..Default::default()
+        }).await.unwrap();
+        let invocation = wait_for_invocation(&state, &sent.command.command_id).await;
+        assert_eq!(invocation.status, "Failed");
+        assert!(invocation.standard_error_content.contains("--ssm-allow-exec"));
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -18,6 +20,8 @@ pub struct PutParameterRequest {
     pub tier: Option<String>,
     #[serde(rename = "DataType")]
     pub data_type: Option<String>,
+    #[serde(rename = "KeyId")]
+    pub key_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -32,6 +36,8 @@ pub struct PutParameterResponse {
 pub struct GetParameterRequest {
     #[serde(rename = "Name")]
     pub name: String,
+    #[serde(rename = "WithDecryption")]
+    pub with_decryption: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -62,6 +68,8 @@ pub struct Parameter {
 pub struct GetParametersRequest {
     #[serde(rename = "Names")]
     pub names: Vec<String>,
+    #[serde(rename = "WithDecryption")]
+    pub with_decryption: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -78,10 +86,25 @@ pub struct GetParametersByPathRequest {
     pub path: String,
     #[serde(rename = "Recursive")]
     pub recursive: Option<bool>,
+    #[serde(rename = "WithDecryption")]
+    pub with_decryption: Option<bool>,
+    #[serde(rename = "ParameterFilters")]
+    pub parameter_filters: Option<Vec<ParameterStringFilter>>,
     #[serde(rename = "MaxResults")]
     pub max_results: Option<usize>,
 }
 
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ParameterStringFilter {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "Option")]
+    pub option: Option<String>,
+    #[serde(rename = "Values")]
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct GetParametersByPathResponse {
     #[serde(rename = "Parameters")]
@@ -113,6 +136,8 @@ pub struct DeleteParametersResponse {
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct DescribeParametersRequest {
+    #[serde(rename = "ParameterFilters")]
+    pub parameter_filters: Option<Vec<ParameterStringFilter>>,
     #[serde(rename = "MaxResults")]
     pub max_results: Option<usize>,
 }
@@ -145,6 +170,9 @@ pub struct ParameterMetadata {
     pub tier: String,
     #[serde(rename = "DataType")]
     pub data_type: String,
+    #[serde(rename = "KeyId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -182,3 +210,190 @@ pub struct Tag {
     #[serde(rename = "Value")]
     pub value: String,
 }
+
+// --- Documents ---
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CreateDocumentRequest {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Content")]
+    pub content: String,
+    #[serde(rename = "DocumentType")]
+    pub document_type: Option<String>,
+    #[serde(rename = "DocumentFormat")]
+    pub document_format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateDocumentResponse {
+    #[serde(rename = "DocumentDescription")]
+    pub document_description: DocumentDescription,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DocumentDescription {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "DocumentType")]
+    pub document_type: String,
+    #[serde(rename = "DocumentFormat")]
+    pub document_format: String,
+    #[serde(rename = "SchemaVersion")]
+    pub schema_version: String,
+    #[serde(rename = "DocumentVersion")]
+    pub document_version: String,
+    #[serde(rename = "LatestVersion")]
+    pub latest_version: String,
+    #[serde(rename = "DefaultVersion")]
+    pub default_version: String,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "Owner")]
+    pub owner: String,
+    #[serde(rename = "CreatedDate")]
+    pub created_date: f64,
+    #[serde(rename = "Hash")]
+    pub hash: String,
+    #[serde(rename = "HashType")]
+    pub hash_type: String,
+    #[serde(rename = "PlatformTypes")]
+    pub platform_types: Vec<String>,
+    #[serde(rename = "Parameters")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<DocumentParameter>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DocumentParameter {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub param_type: String,
+    #[serde(rename = "Description")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "DefaultValue")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DescribeDocumentRequest {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "DocumentVersion")]
+    pub document_version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DescribeDocumentResponse {
+    #[serde(rename = "Document")]
+    pub document: DocumentDescription,
+}
+
+// --- Run Command ---
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SendCommandRequest {
+    #[serde(rename = "DocumentName")]
+    pub document_name: String,
+    #[serde(rename = "InstanceIds")]
+    #[serde(default)]
+    pub instance_ids: Vec<String>,
+    #[serde(rename = "Parameters")]
+    #[serde(default)]
+    pub parameters: HashMap<String, Vec<String>>,
+    #[serde(rename = "Comment")]
+    pub comment: Option<String>,
+    #[serde(rename = "TimeoutSeconds")]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SendCommandResponse {
+    #[serde(rename = "Command")]
+    pub command: Command,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Command {
+    #[serde(rename = "CommandId")]
+    pub command_id: String,
+    #[serde(rename = "DocumentName")]
+    pub document_name: String,
+    #[serde(rename = "Comment")]
+    pub comment: String,
+    #[serde(rename = "InstanceIds")]
+    pub instance_ids: Vec<String>,
+    #[serde(rename = "Parameters")]
+    pub parameters: HashMap<String, Vec<String>>,
+    #[serde(rename = "RequestedDateTime")]
+    pub requested_date_time: f64,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "StatusDetails")]
+    pub status_details: String,
+    #[serde(rename = "TimeoutSeconds")]
+    pub timeout_seconds: u64,
+    #[serde(rename = "TargetCount")]
+    pub target_count: usize,
+    #[serde(rename = "CompletedCount")]
+    pub completed_count: usize,
+    #[serde(rename = "ErrorCount")]
+    pub error_count: usize,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ListCommandsRequest {
+    #[serde(rename = "CommandId")]
+    pub command_id: Option<String>,
+    #[serde(rename = "InstanceId")]
+    pub instance_id: Option<String>,
+    #[serde(rename = "MaxResults")]
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListCommandsResponse {
+    #[serde(rename = "Commands")]
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GetCommandInvocationRequest {
+    #[serde(rename = "CommandId")]
+    pub command_id: String,
+    #[serde(rename = "InstanceId")]
+    pub instance_id: String,
+    #[serde(rename = "PluginName")]
+    pub plugin_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GetCommandInvocationResponse {
+    #[serde(rename = "CommandId")]
+    pub command_id: String,
+    #[serde(rename = "InstanceId")]
+    pub instance_id: String,
+    #[serde(rename = "DocumentName")]
+    pub document_name: String,
+    #[serde(rename = "Comment")]
+    pub comment: String,
+    #[serde(rename = "PluginName")]
+    pub plugin_name: String,
+    #[serde(rename = "ResponseCode")]
+    pub response_code: i32,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "StatusDetails")]
+    pub status_details: String,
+    #[serde(rename = "StandardOutputContent")]
+    pub standard_output_content: String,
+    #[serde(rename = "StandardErrorContent")]
+    pub standard_error_content: String,
+    #[serde(rename = "ExecutionStartDateTime")]
+    pub execution_start_date_time: String,
+    #[serde(rename = "ExecutionEndDateTime")]
+    pub execution_end_date_time: String,
+}
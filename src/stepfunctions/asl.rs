@@ -0,0 +1,778 @@
+//! An Amazon States Language interpreter. `Task` states invoke Lambda
+//! functions through the [`ServiceBus`]; `Task`, `Parallel` and `Map` states
+//! honour `Retry` and `Catch`. Parallel branches and Map iterations run one
+//! after another rather than concurrently.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::bus::ServiceBus;
+
+use super::state::SfnState;
+use super::types::HistoryEvent;
+
+/// A failed state or execution: an error name such as `States.TaskFailed`
+/// and a human-readable cause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub error: String,
+    pub cause: String,
+}
+
+impl Failure {
+    fn new(error: &str, cause: impl Into<String>) -> Self {
+        Failure {
+            error: error.to_string(),
+            cause: cause.into(),
+        }
+    }
+
+    fn runtime(cause: impl Into<String>) -> Self {
+        Self::new("States.Runtime", cause)
+    }
+
+    /// Maps a Lambda failure to an error name: the function's `errorType`
+    /// when it reports one, otherwise `Lambda.Unknown`.
+    fn lambda(message: String) -> Self {
+        if message.starts_with("ResourceNotFoundException") {
+            return Self::new("Lambda.ResourceNotFoundException", message);
+        }
+        if let Ok(Value::Object(error)) = serde_json::from_str::<Value>(&message) {
+            if let Some(error_type) = error.get("errorType").and_then(Value::as_str) {
+                let cause = error.get("errorMessage").and_then(Value::as_str).unwrap_or_default();
+                return Self::new(error_type, cause);
+            }
+        }
+        Self::new("Lambda.Unknown", message)
+    }
+
+    /// The error object `Catch` places at its `ResultPath`.
+    fn to_value(&self) -> Value {
+        json!({ "Error": self.error, "Cause": self.cause })
+    }
+}
+
+const STATE_TYPES: [&str; 8] = ["Pass", "Task", "Choice", "Wait", "Succeed", "Fail", "Parallel", "Map"];
+
+/// Longest Wait or retry delay, the one-year limit on an execution.
+const MAX_DELAY_SECONDS: f64 = 31_536_000.0;
+/// Upper bound on `IntervalSeconds` and `MaxAttempts`, as in AWS.
+const MAX_RETRY_FIELD: u64 = 99_999_999;
+
+/// Checks one `Retry` rule: `IntervalSeconds` a positive integer,
+/// `MaxAttempts` a non-negative integer and `BackoffRate` at least 1.
+fn validate_retrier(name: &str, retrier: &Value) -> Result<(), String> {
+    let invalid = |field: &str, expected: &str| {
+        Err(format!("State '{name}' has an invalid Retry {field}: must be {expected}"))
+    };
+    if let Some(interval) = retrier.get("IntervalSeconds") {
+        if !interval.as_u64().is_some_and(|i| (1..=MAX_RETRY_FIELD).contains(&i)) {
+            return invalid("IntervalSeconds", "an integer from 1 to 99999999");
+        }
+    }
+    if let Some(attempts) = retrier.get("MaxAttempts") {
+        if attempts.as_u64().is_none_or(|a| a > MAX_RETRY_FIELD) {
+            return invalid("MaxAttempts", "an integer from 0 to 99999999");
+        }
+    }
+    if let Some(rate) = retrier.get("BackoffRate") {
+        if !rate.as_f64().is_some_and(|r| r.is_finite() && r >= 1.0) {
+            return invalid("BackoffRate", "a number of at least 1.0");
+        }
+    }
+    if let Some(max) = retrier.get("MaxDelaySeconds") {
+        if !max.as_f64().is_some_and(|m| m > 0.0 && m <= MAX_DELAY_SECONDS) {
+            return invalid("MaxDelaySeconds", "a positive number of seconds up to a year");
+        }
+    }
+    Ok(())
+}
+
+/// Converts a Wait or retry delay, failing the execution with
+/// `States.Runtime` when it is negative, not finite or over a year.
+fn delay(seconds: f64) -> Result<Duration, Failure> {
+    if !(0.0..=MAX_DELAY_SECONDS).contains(&seconds) {
+        return Err(Failure::runtime(format!(
+            "Delay of {seconds} seconds is out of range: must be between 0 and {MAX_DELAY_SECONDS}"
+        )));
+    }
+    Duration::try_from_secs_f64(seconds).map_err(|e| Failure::runtime(format!("Invalid delay: {e}")))
+}
+
+/// Parses and checks a definition: every state has a known `Type`, every
+/// transition names an existing state, and non-terminal states have `Next`
+/// or `End`. Parallel branches and Map processors are checked the same way.
+pub fn validate(definition: &str) -> Result<Value, String> {
+    let machine: Value =
+        serde_json::from_str(definition).map_err(|e| format!("Definition is not valid JSON: {e}"))?;
+    validate_machine(&machine)?;
+    Ok(machine)
+}
+
+fn validate_machine(machine: &Value) -> Result<(), String> {
+    let states = machine
+        .get("States")
+        .and_then(Value::as_object)
+        .ok_or("Definition is missing 'States'")?;
+    let start = machine
+        .get("StartAt")
+        .and_then(Value::as_str)
+        .ok_or("Definition is missing 'StartAt'")?;
+    if !states.contains_key(start) {
+        return Err(format!("StartAt state '{start}' does not exist"));
+    }
+    for (name, state) in states {
+        let state_type = state.get("Type").and_then(Value::as_str).unwrap_or_default();
+        if !STATE_TYPES.contains(&state_type) {
+            return Err(format!("State '{name}' has an invalid Type '{state_type}'"));
+        }
+        let mut targets: Vec<&Value> = vec![&state["Next"], &state["Default"]];
+        for list in ["Catch", "Choices"] {
+            if let Some(items) = state.get(list).and_then(Value::as_array) {
+                targets.extend(items.iter().map(|item| &item["Next"]));
+            }
+        }
+        for target in targets.into_iter().filter_map(Value::as_str) {
+            if !states.contains_key(target) {
+                return Err(format!("State '{name}' transitions to missing state '{target}'"));
+            }
+        }
+        let terminal = matches!(state_type, "Choice" | "Succeed" | "Fail");
+        if !terminal && state.get("Next").is_none() && state.get("End") != Some(&Value::Bool(true)) {
+            return Err(format!("State '{name}' must have either Next or End"));
+        }
+        for retrier in state.get("Retry").and_then(Value::as_array).into_iter().flatten() {
+            validate_retrier(name, retrier)?;
+        }
+        if state_type == "Parallel" {
+            for branch in state.get("Branches").and_then(Value::as_array).into_iter().flatten() {
+                validate_machine(branch)?;
+            }
+        }
+        if state_type == "Map" {
+            let processor = state
+                .get("ItemProcessor")
+                .or_else(|| state.get("Iterator"))
+                .ok_or_else(|| format!("Map state '{name}' has no ItemProcessor"))?;
+            validate_machine(processor)?;
+        }
+    }
+    Ok(())
+}
+
+type RunFuture<'a> = Pin<Box<dyn Future<Output = Result<Value, Failure>> + Send + 'a>>;
+
+/// Runs one execution, appending its events to a history shared with the
+/// execution record so `GetExecutionHistory` shows progress.
+pub struct Executor {
+    bus: Arc<ServiceBus>,
+    history: Arc<Mutex<Vec<HistoryEvent>>>,
+    /// The `$$` context object.
+    context: Value,
+}
+
+impl Executor {
+    pub fn new(bus: Arc<ServiceBus>, history: Arc<Mutex<Vec<HistoryEvent>>>, context: Value) -> Self {
+        Executor { bus, history, context }
+    }
+
+    pub fn record(&self, event_type: &str, details: Option<Value>) {
+        let mut history = self.history.lock().unwrap();
+        let id = history.len() as u64 + 1;
+        history.push(HistoryEvent::new(id, event_type, SfnState::now(), details));
+    }
+
+    /// Runs `machine`, a definition or a Parallel/Map branch, on `input`.
+    pub fn run<'a>(&'a self, machine: &'a Value, input: Value) -> RunFuture<'a> {
+        Box::pin(async move {
+            let mut name = machine["StartAt"].as_str().unwrap_or_default().to_string();
+            let mut input = input;
+            loop {
+                let state = machine["States"]
+                    .get(&name)
+                    .ok_or_else(|| Failure::runtime(format!("State '{name}' does not exist")))?;
+                let state_type = state["Type"].as_str().unwrap_or_default();
+                self.record(
+                    &format!("{state_type}StateEntered"),
+                    Some(json!({ "name": name, "input": input.to_string() })),
+                );
+                let (output, next) = self.step(state, input).await?;
+                self.record(
+                    &format!("{state_type}StateExited"),
+                    Some(json!({ "name": name, "output": output.to_string() })),
+                );
+                match next {
+                    Some(next) => {
+                        name = next;
+                        input = output;
+                    }
+                    None => return Ok(output),
+                }
+            }
+        })
+    }
+
+    /// Runs one state, returning its output and the state to go to next.
+    async fn step(&self, state: &Value, input: Value) -> Result<(Value, Option<String>), Failure> {
+        let next = state.get("Next").and_then(Value::as_str).map(str::to_string);
+        match state["Type"].as_str().unwrap_or_default() {
+            "Pass" => {
+                let effective = self.effective_input(state, &input)?;
+                let result = state.get("Result").cloned().unwrap_or(effective);
+                Ok((self.finish(state, &input, result)?, next))
+            }
+            "Succeed" => {
+                let effective = select(state.get("InputPath"), &input)?;
+                Ok((select(state.get("OutputPath"), &effective)?, None))
+            }
+            "Fail" => Err(Failure::new(
+                state["Error"].as_str().unwrap_or_default(),
+                state["Cause"].as_str().unwrap_or_default(),
+            )),
+            "Wait" => {
+                let effective = select(state.get("InputPath"), &input)?;
+                tokio::time::sleep(wait_duration(state, &effective)?).await;
+                Ok((select(state.get("OutputPath"), &effective)?, next))
+            }
+            "Choice" => {
+                let effective = select(state.get("InputPath"), &input)?;
+                let mut chosen = None;
+                for choice in state["Choices"].as_array().into_iter().flatten() {
+                    if evaluate(choice, &effective)? {
+                        chosen = choice["Next"].as_str();
+                        break;
+                    }
+                }
+                let next = chosen.or_else(|| state["Default"].as_str()).ok_or_else(|| {
+                    Failure::new("States.NoChoiceMatched", "No Choice matched and no Default was specified")
+                })?;
+                Ok((select(state.get("OutputPath"), &effective)?, Some(next.to_string())))
+            }
+            _ => self.run_with_retry(state, input, next).await,
+        }
+    }
+
+    /// Runs a `Task`, `Parallel` or `Map` state, retrying per its `Retry`
+    /// rules and, once they are exhausted, routing to a matching `Catch`.
+    async fn run_with_retry(
+        &self,
+        state: &Value,
+        input: Value,
+        next: Option<String>,
+    ) -> Result<(Value, Option<String>), Failure> {
+        let retriers = state.get("Retry").and_then(Value::as_array).map(Vec::as_slice).unwrap_or(&[]);
+        let mut attempts = vec![0u32; retriers.len()];
+        let failure = loop {
+            let failure = match self.execute(state, &input).await {
+                Ok(result) => return Ok((self.finish(state, &input, result)?, next)),
+                Err(failure) => failure,
+            };
+            let Some(i) = retriers.iter().position(|r| error_matches(r, &failure.error)) else {
+                break failure;
+            };
+            let retrier = &retriers[i];
+            if u64::from(attempts[i]) >= retrier["MaxAttempts"].as_u64().unwrap_or(3) {
+                break failure;
+            }
+            let interval = retrier["IntervalSeconds"].as_f64().unwrap_or(1.0);
+            let backoff = retrier["BackoffRate"].as_f64().unwrap_or(2.0);
+            let mut seconds = interval * backoff.powi(attempts[i] as i32);
+            if let Some(max) = retrier["MaxDelaySeconds"].as_f64() {
+                seconds = seconds.min(max);
+            }
+            attempts[i] += 1;
+            tokio::time::sleep(delay(seconds)?).await;
+        };
+
+        let catchers = state.get("Catch").and_then(Value::as_array).map(Vec::as_slice).unwrap_or(&[]);
+        let Some(catcher) = catchers.iter().find(|c| error_matches(c, &failure.error)) else {
+            return Err(failure);
+        };
+        let output = apply_result_path(catcher.get("ResultPath"), input, failure.to_value())?;
+        Ok((output, catcher["Next"].as_str().map(str::to_string)))
+    }
+
+    /// One attempt at a `Task`, `Parallel` or `Map` state, returning its raw
+    /// result before `ResultSelector` and `ResultPath`.
+    async fn execute(&self, state: &Value, input: &Value) -> Result<Value, Failure> {
+        match state["Type"].as_str().unwrap_or_default() {
+            "Task" => {
+                let effective = self.effective_input(state, input)?;
+                let resource = state["Resource"].as_str().unwrap_or_default();
+                self.invoke(resource, effective).await.inspect_err(|failure| {
+                    self.record(
+                        "TaskFailed",
+                        Some(json!({ "resource": resource, "error": failure.error, "cause": failure.cause })),
+                    );
+                })
+            }
+            "Parallel" => {
+                let effective = self.effective_input(state, input)?;
+                let mut results = Vec::new();
+                for branch in state["Branches"].as_array().into_iter().flatten() {
+                    results.push(self.run(branch, effective.clone()).await?);
+                }
+                Ok(Value::Array(results))
+            }
+            "Map" => {
+                let effective = select(state.get("InputPath"), input)?;
+                let items = match state.get("ItemsPath") {
+                    Some(path) => select(Some(path), &effective)?,
+                    None => effective.clone(),
+                };
+                let Value::Array(items) = items else {
+                    return Err(Failure::runtime("Map state input at ItemsPath is not an array"));
+                };
+                let processor = state.get("ItemProcessor").or_else(|| state.get("Iterator")).unwrap_or(&Value::Null);
+                let selector = state.get("ItemSelector").or_else(|| state.get("Parameters"));
+                let mut results = Vec::new();
+                for (index, item) in items.into_iter().enumerate() {
+                    let item_input = match selector {
+                        Some(template) => {
+                            let mut context = self.context.clone();
+                            context["Map"] = json!({ "Item": { "Index": index, "Value": item } });
+                            resolve(template, &effective, &context)?
+                        }
+                        None => item,
+                    };
+                    results.push(self.run(processor, item_input).await?);
+                }
+                Ok(Value::Array(results))
+            }
+            other => Err(Failure::runtime(format!("Unsupported state type '{other}'"))),
+        }
+    }
+
+    /// Invokes a Task resource: a Lambda function ARN, or the
+    /// `arn:aws:states:::lambda:invoke` integration.
+    async fn invoke(&self, resource: &str, input: Value) -> Result<Value, Failure> {
+        if resource == "arn:aws:states:::lambda:invoke" {
+            let function = input["FunctionName"]
+                .as_str()
+                .ok_or_else(|| Failure::runtime("lambda:invoke requires FunctionName"))?;
+            let payload = input.get("Payload").cloned().unwrap_or_else(|| json!({}));
+            let output = self.bus.invoke_lambda(function, payload).await.map_err(Failure::lambda)?;
+            return Ok(json!({ "ExecutedVersion": "$LATEST", "Payload": output, "StatusCode": 200 }));
+        }
+        if resource.starts_with("arn:aws:lambda:") {
+            return self.bus.invoke_lambda(resource, input).await.map_err(Failure::lambda);
+        }
+        Err(Failure::runtime(format!("Unsupported Task resource '{resource}'")))
+    }
+
+    /// Applies `InputPath`, then `Parameters`.
+    fn effective_input(&self, state: &Value, input: &Value) -> Result<Value, Failure> {
+        let selected = select(state.get("InputPath"), input)?;
+        match state.get("Parameters") {
+            Some(template) => resolve(template, &selected, &self.context),
+            None => Ok(selected),
+        }
+    }
+
+    /// Applies `ResultSelector`, `ResultPath` and `OutputPath` to a result.
+    fn finish(&self, state: &Value, input: &Value, result: Value) -> Result<Value, Failure> {
+        let result = match state.get("ResultSelector") {
+            Some(template) => resolve(template, &result, &self.context)?,
+            None => result,
+        };
+        let combined = apply_result_path(state.get("ResultPath"), input.clone(), result)?;
+        select(state.get("OutputPath"), &combined)
+    }
+}
+
+/// `States.ALL` matches everything but `States.Runtime`; `States.TaskFailed`
+/// additionally excludes `States.Timeout`.
+fn error_matches(rule: &Value, error: &str) -> bool {
+    rule["ErrorEquals"].as_array().into_iter().flatten().filter_map(Value::as_str).any(|e| {
+        e == error
+            || (e == "States.ALL" && error != "States.Runtime")
+            || (e == "States.TaskFailed" && error != "States.Runtime" && error != "States.Timeout")
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses a reference path such as `$`, `$.a.b`, `$.list[0]` or `$['a b']`.
+fn parse_path(path: &str) -> Option<Vec<Segment>> {
+    let mut rest = path.strip_prefix('$')?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return None;
+            }
+            segments.push(Segment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix("['") {
+            let end = after.find("']")?;
+            segments.push(Segment::Key(after[..end].to_string()));
+            rest = &after[end + 2..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            segments.push(Segment::Index(after[..end].parse().ok()?));
+            rest = &after[end + 1..];
+        } else {
+            return None;
+        }
+    }
+    Some(segments)
+}
+
+/// The value at `path` in `value`, or `None` if it is missing or the path is
+/// not valid.
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    parse_path(path)?.iter().try_fold(value, |current, segment| match segment {
+        Segment::Key(key) => current.get(key),
+        Segment::Index(index) => current.get(index),
+    })
+}
+
+/// Applies an `InputPath` or `OutputPath`: absent keeps the whole value,
+/// `null` gives `{}`.
+fn select(path: Option<&Value>, value: &Value) -> Result<Value, Failure> {
+    match path {
+        None => Ok(value.clone()),
+        Some(Value::Null) => Ok(json!({})),
+        Some(Value::String(path)) => lookup(value, path)
+            .cloned()
+            .ok_or_else(|| Failure::runtime(format!("Invalid path '{path}': no such field in the input"))),
+        Some(other) => Err(Failure::runtime(format!("Invalid path {other}"))),
+    }
+}
+
+/// Places `result` in `input` at `ResultPath`: absent replaces the input,
+/// `null` discards the result.
+fn apply_result_path(path: Option<&Value>, input: Value, result: Value) -> Result<Value, Failure> {
+    let path = match path {
+        None => return Ok(result),
+        Some(Value::Null) => return Ok(input),
+        Some(Value::String(path)) => path,
+        Some(other) => return Err(Failure::runtime(format!("Invalid ResultPath {other}"))),
+    };
+    let segments = parse_path(path).ok_or_else(|| Failure::runtime(format!("Invalid ResultPath '{path}'")))?;
+    let mut output = input;
+    let mut target = &mut output;
+    for segment in segments {
+        let Segment::Key(key) = segment else {
+            return Err(Failure::runtime(format!("ResultPath '{path}' cannot index into an array")));
+        };
+        if target.is_null() {
+            *target = json!({});
+        }
+        let Value::Object(fields) = target else {
+            return Err(Failure::runtime(format!("ResultPath '{path}' does not lead to an object")));
+        };
+        target = fields.entry(key).or_insert(Value::Null);
+    }
+    *target = result;
+    Ok(output)
+}
+
+/// Evaluates a `Parameters`, `ItemSelector` or `ResultSelector` template:
+/// fields named `key.$` take the value at their path, where `$$` paths read
+/// the context object.
+fn resolve(template: &Value, input: &Value, context: &Value) -> Result<Value, Failure> {
+    match template {
+        Value::Object(fields) => {
+            let mut resolved = serde_json::Map::new();
+            for (key, value) in fields {
+                if let Some(key) = key.strip_suffix(".$") {
+                    let path = value
+                        .as_str()
+                        .ok_or_else(|| Failure::runtime(format!("Field '{key}.$' must be a path")))?;
+                    let found = match path.strip_prefix('$') {
+                        Some(context_path) if context_path.starts_with('$') => lookup(context, context_path),
+                        Some(_) => lookup(input, path),
+                        None => {
+                            return Err(Failure::runtime(format!(
+                                "Intrinsic functions are not supported: '{path}'"
+                            )))
+                        }
+                    };
+                    let found = found.ok_or_else(|| {
+                        Failure::runtime(format!("Invalid path '{path}' for field '{key}.$'"))
+                    })?;
+                    resolved.insert(key.to_string(), found.clone());
+                } else {
+                    resolved.insert(key.clone(), resolve(value, input, context)?);
+                }
+            }
+            Ok(Value::Object(resolved))
+        }
+        Value::Array(items) => items.iter().map(|item| resolve(item, input, context)).collect(),
+        other => Ok(other.clone()),
+    }
+}
+
+fn wait_duration(state: &Value, input: &Value) -> Result<Duration, Failure> {
+    let seconds = |value: &Value| {
+        value.as_f64().ok_or_else(|| Failure::runtime("Wait seconds must be a number")).and_then(delay)
+    };
+    let until = |value: &Value| {
+        let timestamp = value
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .ok_or_else(|| Failure::runtime("Wait timestamp must be an RFC 3339 timestamp"))?;
+        Ok((timestamp.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+    };
+    if let Some(value) = state.get("Seconds") {
+        seconds(value)
+    } else if let Some(path) = state.get("SecondsPath") {
+        seconds(&select(Some(path), input)?)
+    } else if let Some(value) = state.get("Timestamp") {
+        until(value)
+    } else if let Some(path) = state.get("TimestampPath") {
+        until(&select(Some(path), input)?)
+    } else {
+        Err(Failure::runtime("Wait state needs Seconds, SecondsPath, Timestamp or TimestampPath"))
+    }
+}
+
+/// Evaluates a Choice rule, including `And`, `Or` and `Not` combinations.
+fn evaluate(rule: &Value, input: &Value) -> Result<bool, Failure> {
+    if let Some(rules) = rule.get("And").and_then(Value::as_array) {
+        for rule in rules {
+            if !evaluate(rule, input)? {
+                return Ok(false);
+            }
+        }
+        return Ok(true);
+    }
+    if let Some(rules) = rule.get("Or").and_then(Value::as_array) {
+        for rule in rules {
+            if evaluate(rule, input)? {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+    if let Some(rule) = rule.get("Not") {
+        return Ok(!evaluate(rule, input)?);
+    }
+    let variable = rule["Variable"]
+        .as_str()
+        .ok_or_else(|| Failure::runtime("Choice rule has no Variable"))?;
+    let actual = lookup(input, variable);
+    let (operator, expected) = rule
+        .as_object()
+        .and_then(|fields| fields.iter().find(|(key, _)| *key != "Variable" && *key != "Next"))
+        .ok_or_else(|| Failure::runtime("Choice rule has no comparison"))?;
+    let (operator, expected) = match operator.strip_suffix("Path") {
+        Some(operator) => {
+            let path = expected.as_str().unwrap_or_default();
+            let value = lookup(input, path)
+                .ok_or_else(|| Failure::runtime(format!("Invalid path '{path}' in {operator}Path")))?;
+            (operator, value)
+        }
+        _ => (operator.as_str(), expected),
+    };
+    let expect_flag = expected.as_bool().unwrap_or(false);
+    match operator {
+        "IsPresent" => return Ok(actual.is_some() == expect_flag),
+        "IsNull" => return Ok(actual.is_some_and(Value::is_null) == expect_flag),
+        "IsNumeric" => return Ok(actual.is_some_and(Value::is_number) == expect_flag),
+        "IsString" => return Ok(actual.is_some_and(Value::is_string) == expect_flag),
+        "IsBoolean" => return Ok(actual.is_some_and(Value::is_boolean) == expect_flag),
+        "IsTimestamp" => {
+            let is_timestamp = actual
+                .and_then(Value::as_str)
+                .is_some_and(|t| DateTime::parse_from_rfc3339(t).is_ok());
+            return Ok(is_timestamp == expect_flag);
+        }
+        _ => {}
+    }
+    let actual = actual.ok_or_else(|| Failure::runtime(format!("Invalid path '{variable}': no such field in the input")))?;
+    let ordering = |kind: &str| -> Option<std::cmp::Ordering> {
+        match kind {
+            "String" => Some(actual.as_str()?.cmp(expected.as_str()?)),
+            "Numeric" => actual.as_f64()?.partial_cmp(&expected.as_f64()?),
+            "Timestamp" => {
+                let a = DateTime::parse_from_rfc3339(actual.as_str()?).ok()?;
+                let b = DateTime::parse_from_rfc3339(expected.as_str()?).ok()?;
+                Some(a.cmp(&b))
+            }
+            _ => None,
+        }
+    };
+    use std::cmp::Ordering::*;
+    let result = match operator {
+        "BooleanEquals" => actual.as_bool().is_some_and(|a| Some(a) == expected.as_bool()),
+        "StringMatches" => match (actual.as_str(), expected.as_str()) {
+            (Some(a), Some(pattern)) => wildcard_matches(pattern, a),
+            _ => false,
+        },
+        _ => {
+            let (kind, comparison) = ["String", "Numeric", "Timestamp"]
+                .iter()
+                .find_map(|kind| operator.strip_prefix(kind).map(|c| (*kind, c)))
+                .ok_or_else(|| Failure::runtime(format!("Unsupported Choice operator '{operator}'")))?;
+            let Some(ordering) = ordering(kind) else {
+                return Ok(false);
+            };
+            match comparison {
+                "Equals" => ordering == Equal,
+                "LessThan" => ordering == Less,
+                "GreaterThan" => ordering == Greater,
+                "LessThanEquals" => ordering != Greater,
+                "GreaterThanEquals" => ordering != Less,
+                _ => return Err(Failure::runtime(format!("Unsupported Choice operator '{operator}'"))),
+            }
+        }
+    };
+    Ok(result)
+}
+
+/// `StringMatches`: `*` matches any run of characters.
+fn wildcard_matches(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !value.starts_with(first) || value.len() < first.len() + last.len() || !value.ends_with(last) {
+        return false;
+    }
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executor() -> Executor {
+        Executor::new(Arc::new(ServiceBus::new()), Arc::new(Mutex::new(Vec::new())), json!({}))
+    }
+
+    #[tokio::test]
+    async fn test_paths_and_parameters() {
+        let machine = validate(
+            r#"{"StartAt":"Shape","States":{"Shape":{"Type":"Pass",
+                "InputPath":"$.order","Parameters":{"id.$":"$.id","first.$":"$.items[0]","fixed":1},
+                "ResultPath":"$.shaped","OutputPath":"$.shaped","End":true}}}"#,
+        )
+        .unwrap();
+        let input = json!({"order": {"id": "o-1", "items": ["a", "b"]}});
+        let output = executor().run(&machine, input).await.unwrap();
+        assert_eq!(output, json!({"id": "o-1", "first": "a", "fixed": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_choice_rules() {
+        let machine = validate(
+            r#"{"StartAt":"Route","States":{
+                "Route":{"Type":"Choice","Choices":[
+                    {"And":[{"Variable":"$.n","NumericGreaterThan":10},{"Variable":"$.tag","StringMatches":"prio-*"}],"Next":"Big"},
+                    {"Variable":"$.missing","IsPresent":false,"Next":"Small"}],"Default":"Big"},
+                "Big":{"Type":"Pass","Result":"big","End":true},
+                "Small":{"Type":"Pass","Result":"small","End":true}}}"#,
+        )
+        .unwrap();
+        let run = |input| async { executor().run(&machine, input).await.unwrap() };
+        assert_eq!(run(json!({"n": 11, "tag": "prio-high"})).await, json!("big"));
+        assert_eq!(run(json!({"n": 11, "tag": "low"})).await, json!("small"));
+    }
+
+    #[tokio::test]
+    async fn test_map_and_parallel() {
+        let machine = validate(
+            r#"{"StartAt":"Each","States":{
+                "Each":{"Type":"Map","ItemsPath":"$.items","ItemSelector":{"v.$":"$$.Map.Item.Value","i.$":"$$.Map.Item.Index"},
+                    "ItemProcessor":{"StartAt":"Id","States":{"Id":{"Type":"Pass","End":true}}},"ResultPath":"$.mapped","Next":"Both"},
+                "Both":{"Type":"Parallel","Branches":[
+                    {"StartAt":"A","States":{"A":{"Type":"Pass","Result":1,"End":true}}},
+                    {"StartAt":"B","States":{"B":{"Type":"Pass","InputPath":"$.mapped[1].v","End":true}}}],"End":true}}}"#,
+        )
+        .unwrap();
+        let output = executor().run(&machine, json!({"items": ["x", "y"]})).await.unwrap();
+        assert_eq!(output, json!([1, "y"]));
+    }
+
+    #[tokio::test]
+    async fn test_fail_state_and_catch_result_path() {
+        let machine = validate(
+            r#"{"StartAt":"Work","States":{
+                "Work":{"Type":"Parallel","Branches":[{"StartAt":"Boom","States":{"Boom":{"Type":"Fail","Error":"Custom.Error","Cause":"bad"}}}],
+                    "Catch":[{"ErrorEquals":["Other"],"Next":"Done"},{"ErrorEquals":["States.ALL"],"ResultPath":"$.error","Next":"Done"}],"End":true},
+                "Done":{"Type":"Succeed"}}}"#,
+        )
+        .unwrap();
+        let output = executor().run(&machine, json!({"k": 1})).await.unwrap();
+        assert_eq!(output, json!({"k": 1, "error": {"Error": "Custom.Error", "Cause": "bad"}}));
+
+        let uncaught = validate(r#"{"StartAt":"F","States":{"F":{"Type":"Fail","Error":"E","Cause":"c"}}}"#).unwrap();
+        assert_eq!(executor().run(&uncaught, json!({})).await, Err(Failure::new("E", "c")));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_definitions() {
+        assert!(validate("not json").is_err());
+        assert!(validate(r#"{"StartAt":"Missing","States":{}}"#).is_err());
+        assert!(validate(r#"{"StartAt":"A","States":{"A":{"Type":"Pass"}}}"#).is_err());
+        assert!(validate(r#"{"StartAt":"A","States":{"A":{"Type":"Pass","Next":"B"}}}"#).is_err());
+        assert!(validate(r#"{"StartAt":"A","States":{"A":{"Type":"Bogus","End":true}}}"#).is_err());
+    }
+
+    #[test]
+    fn test_validate_checks_retry_fields() {
+        let with_retry = |retry: &str| {
+            validate(&format!(
+                r#"{{"StartAt":"T","States":{{"T":{{"Type":"Task","Resource":"f","Retry":[{retry}],"End":true}}}}}}"#
+            ))
+        };
+        assert!(with_retry(r#"{"ErrorEquals":["States.ALL"],"IntervalSeconds":2,"MaxAttempts":0,"BackoffRate":1.5}"#).is_ok());
+        assert!(with_retry(r#"{"ErrorEquals":["States.ALL"],"IntervalSeconds":0}"#).is_err());
+        assert!(with_retry(r#"{"ErrorEquals":["States.ALL"],"IntervalSeconds":1e300}"#).is_err());
+        assert!(with_retry(r#"{"ErrorEquals":["States.ALL"],"MaxAttempts":-1}"#).is_err());
+        assert!(with_retry(r#"{"ErrorEquals":["States.ALL"],"BackoffRate":0.5}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_delays_fail_with_runtime_error() {
+        let wait = validate(
+            r#"{"StartAt":"W","States":{"W":{"Type":"Wait","SecondsPath":"$.s","End":true}}}"#,
+        )
+        .unwrap();
+        for seconds in [json!(1e300), json!(-1)] {
+            let failure = executor().run(&wait, json!({ "s": seconds })).await.unwrap_err();
+            assert_eq!(failure.error, "States.Runtime");
+        }
+
+        // The first retry of this always-failing task would wait over a year.
+        let retry = validate(
+            r#"{"StartAt":"T","States":{"T":{"Type":"Task","Resource":"arn:aws:lambda:us-east-1:123456789012:function:f",
+                "Retry":[{"ErrorEquals":["States.ALL"],"IntervalSeconds":99999999,"BackoffRate":2}],"End":true}}}"#,
+        )
+        .unwrap();
+        let failure = executor().run(&retry, json!({})).await.unwrap_err();
+        assert_eq!(failure.error, "States.Runtime");
+    }
+
+    #[test]
+    fn test_error_matching() {
+        let rule = json!({"ErrorEquals": ["States.ALL"]});
+        assert!(error_matches(&rule, "Lambda.Unknown"));
+        assert!(!error_matches(&rule, "States.Runtime"));
+        let rule = json!({"ErrorEquals": ["States.TaskFailed"]});
+        assert!(error_matches(&rule, "Lambda.Unknown"));
+        assert!(!error_matches(&rule, "States.Timeout"));
+        assert_eq!(Failure::lambda(r#"{"errorType":"ValueError","errorMessage":"nope"}"#.into()).error, "ValueError");
+        assert_eq!(Failure::lambda("crashed".into()).error, "Lambda.Unknown");
+    }
+}
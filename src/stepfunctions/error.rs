@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+
+use crate::aws_error::{error_response, AwsError, Protocol};
 
 #[derive(Debug, Clone)]
 pub enum SfnError {
@@ -10,6 +11,8 @@ pub enum SfnError {
     ExecutionAlreadyExists(String),
     InvalidArn(String),
     InvalidAction(String),
+    InvalidDefinition(String),
+    InvalidExecutionInput(String),
 }
 
 impl SfnError {
@@ -21,6 +24,8 @@ impl SfnError {
             SfnError::ExecutionAlreadyExists(_) => "ExecutionAlreadyExists",
             SfnError::InvalidArn(_) => "InvalidArn",
             SfnError::InvalidAction(_) => "InvalidAction",
+            SfnError::InvalidDefinition(_) => "InvalidDefinition",
+            SfnError::InvalidExecutionInput(_) => "InvalidExecutionInput",
         }
     }
 
@@ -39,18 +44,30 @@ impl SfnError {
             | SfnError::ExecutionDoesNotExist(m)
             | SfnError::ExecutionAlreadyExists(m)
             | SfnError::InvalidArn(m)
-            | SfnError::InvalidAction(m) => m,
+            | SfnError::InvalidAction(m)
+            | SfnError::InvalidDefinition(m)
+            | SfnError::InvalidExecutionInput(m) => m,
         }
     }
 }
 
+impl AwsError for SfnError {
+    fn code(&self) -> &str {
+        self.error_code()
+    }
+
+    fn http_status(&self) -> StatusCode {
+        self.status_code()
+    }
+
+    fn message(&self) -> &str {
+        SfnError::message(self)
+    }
+}
+
 impl IntoResponse for SfnError {
     fn into_response(self) -> Response {
-        let body = json!({
-            "__type": self.error_code(),
-            "message": self.message(),
-        });
-        (self.status_code(), axum::Json(body)).into_response()
+        error_response(&self, Protocol::Json)
     }
 }
 
@@ -89,6 +106,16 @@ mod tests {
         assert_eq!(err.error_code(), "InvalidAction");
     }
     #[test]
+    fn test_invaliddefinition_error_code() {
+        let err = SfnError::InvalidDefinition("test".to_string());
+        assert_eq!(err.error_code(), "InvalidDefinition");
+    }
+    #[test]
+    fn test_invalidexecutioninput_error_code() {
+        let err = SfnError::InvalidExecutionInput("test".to_string());
+        assert_eq!(err.error_code(), "InvalidExecutionInput");
+    }
+    #[test]
     fn test_message() {
         let err = SfnError::StateMachineAlreadyExists("hello world".to_string());
         assert_eq!(err.message(), "hello world");
@@ -1,3 +1,4 @@
+pub mod asl;
 pub mod error;
 pub mod server;
 pub mod state;
@@ -83,6 +83,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<SfnState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("stepfunctions"))
         .with_state(state)
 }
 
@@ -94,6 +95,75 @@ mod tests {
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
 
+    #[tokio::test]
+    async fn test_lambda_task_with_stubbed_function_through_routers() {
+        use serde_json::json;
+
+        let lambda = Arc::new(crate::lambda::state::LambdaState::new(
+            "123456789012".to_string(),
+            "us-east-1".to_string(),
+        ));
+        let lambda_app = crate::lambda::server::create_router(lambda.clone());
+        let stub = json!({ "mode": "return", "result": { "total": 42 } });
+        crate::lambda::server::create_stubbed_function(&lambda_app, "price", stub).await;
+        let bus = Arc::new(crate::bus::ServiceBus::new());
+        bus.attach_lambda(lambda);
+        let state = SfnState::new("123456789012".to_string(), "us-east-1".to_string()).with_bus(bus);
+        let app = create_router(Arc::new(state));
+        let call = |action: &str, body: serde_json::Value| {
+            let app = app.clone();
+            let req = Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/x-amz-json-1.0")
+                .header("x-amz-target", format!("AWSStepFunctions.{action}"))
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let action = action.to_string();
+            async move {
+                let resp = app.oneshot(req).await.unwrap();
+                assert_eq!(resp.status(), StatusCode::OK, "{action}");
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let definition = json!({
+            "StartAt": "Price",
+            "States": {
+                "Price": {
+                    "Type": "Task",
+                    "Resource": "arn:aws:lambda:us-east-1:123456789012:function:price",
+                    "ResultPath": "$.price",
+                    "End": true
+                }
+            }
+        });
+        let machine = call("CreateStateMachine", json!({
+            "name": "pricing",
+            "definition": definition.to_string(),
+            "roleArn": "arn:aws:iam::123456789012:role/sfn-role",
+        }))
+        .await;
+        let execution = call("StartExecution", json!({
+            "stateMachineArn": machine["stateMachineArn"],
+            "input": r#"{"order":1}"#,
+        }))
+        .await;
+        let describe = json!({ "executionArn": execution["executionArn"] });
+        let mut described = call("DescribeExecution", describe.clone()).await;
+        for _ in 0..100 {
+            if described["status"] != "RUNNING" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            described = call("DescribeExecution", describe.clone()).await;
+        }
+        assert_eq!(described["status"], "SUCCEEDED");
+        let output: serde_json::Value = serde_json::from_str(described["output"].as_str().unwrap()).unwrap();
+        assert_eq!(output, json!({ "order": 1, "price": { "total": 42 } }));
+    }
+
     #[tokio::test]
     async fn test_missing_target_header() {
         let state = Arc::new(SfnState::new("123456789012".to_string(), "us-east-1".to_string()));
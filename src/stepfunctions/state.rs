@@ -1,9 +1,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use serde_json::json;
 use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
 use uuid::Uuid;
 
+use crate::bus::ServiceBus;
+
+use super::asl::{self, Executor};
 use super::error::SfnError;
 use super::types::*;
 
@@ -26,7 +31,11 @@ struct Execution {
     stop_date: Option<f64>,
     input: Option<String>,
     output: Option<String>,
-    history: Vec<HistoryEvent>,
+    error: Option<String>,
+    cause: Option<String>,
+    /// Shared with the running [`Executor`], which appends to it.
+    history: Arc<std::sync::Mutex<Vec<HistoryEvent>>>,
+    task: Option<AbortHandle>,
 }
 
 struct SfnStateInner {
@@ -38,6 +47,7 @@ struct SfnStateInner {
 
 pub struct SfnState {
     inner: Arc<Mutex<SfnStateInner>>,
+    bus: Arc<ServiceBus>,
 }
 
 impl SfnState {
@@ -49,10 +59,27 @@ impl SfnState {
                 account_id,
                 region,
             })),
+            bus: Arc::new(ServiceBus::new()),
+        }
+    }
+
+    /// Invokes Task state Lambdas through `bus` instead of a detached one.
+    pub fn with_bus(mut self, bus: Arc<ServiceBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Discards every resource, returning the service to its startup state.
+    pub async fn reset(&self) {
+        let mut state = self.inner.lock().await;
+        for task in state.executions.values().filter_map(|e| e.task.as_ref()) {
+            task.abort();
         }
+        let fresh = Self::new(state.account_id.clone(), state.region.clone());
+        std::mem::swap(&mut *state, &mut *fresh.inner.lock().await);
     }
 
-    fn now() -> f64 {
+    pub(super) fn now() -> f64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or(std::time::Duration::from_secs(0))
@@ -73,6 +100,7 @@ impl SfnState {
                 "State machine already exists: {}", arn
             )));
         }
+        asl::validate(&req.definition).map_err(SfnError::InvalidDefinition)?;
         let machine_type = req.machine_type.unwrap_or_else(|| "STANDARD".to_string());
         let created = Self::now();
         let mut tags = HashMap::new();
@@ -166,20 +194,59 @@ impl SfnState {
                 "Execution already exists: {}", exec_arn
             )));
         }
+        let input: serde_json::Value = match req.input.as_deref() {
+            Some(raw) => serde_json::from_str(raw).map_err(|e| {
+                SfnError::InvalidExecutionInput(format!("Invalid State Machine Execution Input: {e}"))
+            })?,
+            None => json!({}),
+        };
+        let machine = &state.state_machines[&req.state_machine_arn];
+        let definition = asl::validate(&machine.definition).map_err(SfnError::InvalidDefinition)?;
         let now = Self::now();
-        let history = vec![
-            HistoryEvent {
-                id: 1,
-                event_type: "ExecutionStarted".to_string(),
-                timestamp: now,
-                previous_event_id: 0,
-                execution_started_event_details: Some(serde_json::json!({
-                    "input": req.input.as_deref().unwrap_or("{}"),
-                    "roleArn": state.state_machines[&req.state_machine_arn].role_arn.clone(),
-                })),
-                execution_succeeded_event_details: None,
+        let context = json!({
+            "Execution": {
+                "Id": exec_arn,
+                "Name": exec_name,
+                "Input": input,
+                "RoleArn": machine.role_arn,
+                "StartTime": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
             },
-        ];
+            "StateMachine": { "Id": machine.arn, "Name": machine.name },
+        });
+        let history = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let executor = Executor::new(self.bus.clone(), history.clone(), context);
+        executor.record("ExecutionStarted", Some(json!({
+            "input": input.to_string(),
+            "roleArn": machine.role_arn,
+        })));
+
+        let inner = self.inner.clone();
+        let arn = exec_arn.clone();
+        let task = tokio::spawn(async move {
+            let result = executor.run(&definition, input).await;
+            let mut state = inner.lock().await;
+            let Some(exec) = state.executions.get_mut(&arn) else {
+                return;
+            };
+            match result {
+                Ok(output) => {
+                    executor.record("ExecutionSucceeded", Some(json!({ "output": output.to_string() })));
+                    exec.status = "SUCCEEDED".to_string();
+                    exec.output = Some(output.to_string());
+                }
+                Err(failure) => {
+                    executor.record("ExecutionFailed", Some(json!({
+                        "error": failure.error,
+                        "cause": failure.cause,
+                    })));
+                    exec.status = "FAILED".to_string();
+                    exec.error = Some(failure.error);
+                    exec.cause = Some(failure.cause);
+                }
+            }
+            exec.stop_date = Some(Self::now());
+            exec.task = None;
+        });
         state.executions.insert(exec_arn.clone(), Execution {
             arn: exec_arn.clone(),
             state_machine_arn: req.state_machine_arn,
@@ -189,7 +256,10 @@ impl SfnState {
             stop_date: None,
             input: req.input,
             output: None,
+            error: None,
+            cause: None,
             history,
+            task: Some(task.abort_handle()),
         });
         Ok(StartExecutionResponse {
             execution_arn: exec_arn,
@@ -207,9 +277,14 @@ impl SfnState {
                 "Execution does not exist: {}", req.execution_arn
             )))?;
         let now = Self::now();
-        exec.status = "ABORTED".to_string();
-        exec.stop_date = Some(now);
-        Ok(StopExecutionResponse { stop_date: now })
+        if exec.status == "RUNNING" {
+            if let Some(task) = exec.task.take() {
+                task.abort();
+            }
+            exec.status = "ABORTED".to_string();
+            exec.stop_date = Some(now);
+        }
+        Ok(StopExecutionResponse { stop_date: exec.stop_date.unwrap_or(now) })
     }
 
     pub async fn describe_execution(
@@ -230,6 +305,8 @@ impl SfnState {
             stop_date: exec.stop_date,
             input: exec.input.clone(),
             output: exec.output.clone(),
+            error: exec.error.clone(),
+            cause: exec.cause.clone(),
         })
     }
 
@@ -271,7 +348,7 @@ impl SfnState {
             .ok_or_else(|| SfnError::ExecutionDoesNotExist(format!(
                 "Execution does not exist: {}", req.execution_arn
             )))?;
-        let mut events = exec.history.clone();
+        let mut events = exec.history.lock().unwrap().clone();
         if req.reverse_order.unwrap_or(false) {
             events.reverse();
         }
@@ -494,6 +571,146 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    async fn wait_for_completion(state: &SfnState, execution_arn: &str) -> DescribeExecutionResponse {
+        for _ in 0..200 {
+            let exec = state.describe_execution(DescribeExecutionRequest {
+                execution_arn: execution_arn.to_string(),
+            }).await.unwrap();
+            if exec.status != "RUNNING" {
+                return exec;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        panic!("execution did not complete");
+    }
+
+    #[tokio::test]
+    async fn test_pass_execution_succeeds() {
+        let state = make_state();
+        let sm_arn = create_sm(&state).await;
+        let exec = state.start_execution(StartExecutionRequest {
+            state_machine_arn: sm_arn,
+            input: Some(r#"{"key":"value"}"#.to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        let done = wait_for_completion(&state, &exec.execution_arn).await;
+        assert_eq!(done.status, "SUCCEEDED");
+        assert_eq!(done.output.as_deref(), Some(r#"{"key":"value"}"#));
+        let history = state.get_execution_history(GetExecutionHistoryRequest {
+            execution_arn: exec.execution_arn,
+            ..Default::default()
+        }).await.unwrap();
+        let types: Vec<&str> = history.events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, ["ExecutionStarted", "PassStateEntered", "PassStateExited", "ExecutionSucceeded"]);
+    }
+
+    #[tokio::test]
+    async fn test_failing_task_retries_then_is_caught() {
+        use crate::lambda::state::{FunctionHandler, LambdaState};
+
+        let lambda = Arc::new(LambdaState::new("123456789012".to_string(), "us-east-1".to_string()));
+        lambda.create_function(serde_json::from_value(json!({
+            "FunctionName": "flaky",
+            "Role": "arn:aws:iam::123456789012:role/r",
+            "Code": {}
+        })).unwrap()).await.unwrap();
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let handler: FunctionHandler = {
+            let calls = calls.clone();
+            Arc::new(move |_event| {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Box::pin(async { Err("connection reset".to_string()) })
+            })
+        };
+        lambda.set_handler("flaky", handler).await.unwrap();
+        let bus = Arc::new(ServiceBus::new());
+        bus.attach_lambda(lambda);
+        let state = make_state().with_bus(bus);
+
+        let definition = json!({
+            "StartAt": "Call",
+            "States": {
+                "Call": {
+                    "Type": "Task",
+                    "Resource": "arn:aws:lambda:us-east-1:123456789012:function:flaky",
+                    "Retry": [
+                        {"ErrorEquals": ["States.Timeout"], "MaxAttempts": 5},
+                        {"ErrorEquals": ["Lambda.Unknown"], "IntervalSeconds": 1, "MaxAttempts": 2, "BackoffRate": 1.0}
+                    ],
+                    "Catch": [{"ErrorEquals": ["States.TaskFailed"], "ResultPath": "$.failure", "Next": "Recover"}],
+                    "End": true
+                },
+                "Recover": {"Type": "Pass", "Result": "recovered", "ResultPath": "$.status", "End": true}
+            }
+        });
+        let sm_arn = state.create_state_machine(CreateStateMachineRequest {
+            name: "retrying".to_string(),
+            definition: definition.to_string(),
+            role_arn: "arn:aws:iam::123456789012:role/sfn-role".to_string(),
+            ..Default::default()
+        }).await.unwrap().state_machine_arn;
+        let exec = state.start_execution(StartExecutionRequest {
+            state_machine_arn: sm_arn,
+            input: Some(r#"{"order":1}"#.to_string()),
+            ..Default::default()
+        }).await.unwrap();
+
+        let done = wait_for_completion(&state, &exec.execution_arn).await;
+        assert_eq!(done.status, "SUCCEEDED");
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+        let output: serde_json::Value = serde_json::from_str(done.output.as_deref().unwrap()).unwrap();
+        assert_eq!(output, json!({
+            "order": 1,
+            "failure": {"Error": "Lambda.Unknown", "Cause": "connection reset"},
+            "status": "recovered"
+        }));
+        let history = state.get_execution_history(GetExecutionHistoryRequest {
+            execution_arn: exec.execution_arn,
+            ..Default::default()
+        }).await.unwrap();
+        let failures = history.events.iter().filter(|e| e.event_type == "TaskFailed").count();
+        assert_eq!(failures, 3);
+        assert!(history.events.iter().any(|e| e.event_type == "PassStateEntered"));
+    }
+
+    #[tokio::test]
+    async fn test_uncaught_failure_fails_execution() {
+        let state = make_state();
+        let sm_arn = state.create_state_machine(CreateStateMachineRequest {
+            name: "failing".to_string(),
+            definition: r#"{"StartAt":"Call","States":{"Call":{"Type":"Task","Resource":"arn:aws:lambda:us-east-1:123456789012:function:missing","End":true}}}"#.to_string(),
+            role_arn: "arn:aws:iam::123456789012:role/sfn-role".to_string(),
+            ..Default::default()
+        }).await.unwrap().state_machine_arn;
+        let exec = state.start_execution(StartExecutionRequest {
+            state_machine_arn: sm_arn,
+            ..Default::default()
+        }).await.unwrap();
+        let done = wait_for_completion(&state, &exec.execution_arn).await;
+        assert_eq!(done.status, "FAILED");
+        assert!(done.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_definition_and_input_rejected() {
+        let state = make_state();
+        let result = state.create_state_machine(CreateStateMachineRequest {
+            name: "bad".to_string(),
+            definition: r#"{"StartAt":"Nope","States":{}}"#.to_string(),
+            role_arn: "arn:aws:iam::123456789012:role/sfn-role".to_string(),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(SfnError::InvalidDefinition(_))));
+
+        let sm_arn = create_sm(&state).await;
+        let result = state.start_execution(StartExecutionRequest {
+            state_machine_arn: sm_arn,
+            input: Some("not json".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(matches!(result, Err(SfnError::InvalidExecutionInput(_))));
+    }
+
     #[tokio::test]
     async fn test_tag_and_list_tags() {
         let state = make_state();
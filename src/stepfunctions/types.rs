@@ -136,6 +136,12 @@ pub struct DescribeExecutionResponse {
     #[serde(rename = "output")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<String>,
+    #[serde(rename = "error")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(rename = "cause")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cause: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -207,6 +213,47 @@ pub struct HistoryEvent {
     #[serde(rename = "executionSucceededEventDetails")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub execution_succeeded_event_details: Option<serde_json::Value>,
+    #[serde(rename = "executionFailedEventDetails")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_failed_event_details: Option<serde_json::Value>,
+    #[serde(rename = "stateEnteredEventDetails")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_entered_event_details: Option<serde_json::Value>,
+    #[serde(rename = "stateExitedEventDetails")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_exited_event_details: Option<serde_json::Value>,
+    #[serde(rename = "taskFailedEventDetails")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_failed_event_details: Option<serde_json::Value>,
+}
+
+impl HistoryEvent {
+    /// An event with `details` in the field AWS uses for `event_type`.
+    pub fn new(id: u64, event_type: &str, timestamp: f64, details: Option<serde_json::Value>) -> Self {
+        let mut event = HistoryEvent {
+            id,
+            event_type: event_type.to_string(),
+            timestamp,
+            previous_event_id: id.saturating_sub(1),
+            execution_started_event_details: None,
+            execution_succeeded_event_details: None,
+            execution_failed_event_details: None,
+            state_entered_event_details: None,
+            state_exited_event_details: None,
+            task_failed_event_details: None,
+        };
+        let slot = match event_type {
+            "ExecutionStarted" => &mut event.execution_started_event_details,
+            "ExecutionSucceeded" => &mut event.execution_succeeded_event_details,
+            "ExecutionFailed" => &mut event.execution_failed_event_details,
+            "TaskFailed" => &mut event.task_failed_event_details,
+            t if t.ends_with("StateEntered") => &mut event.state_entered_event_details,
+            t if t.ends_with("StateExited") => &mut event.state_exited_event_details,
+            _ => return event,
+        };
+        *slot = details;
+        event
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -55,6 +55,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<SwfState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("swf"))
         .with_state(state)
 }
 
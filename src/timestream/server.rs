@@ -59,6 +59,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<TimestreamState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("timestream"))
         .with_state(state)
 }
 
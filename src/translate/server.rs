@@ -55,6 +55,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<TranslateState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("translate"))
         .with_state(state)
 }
 
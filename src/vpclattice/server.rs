@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<VpclatticeState>) -> Router {
     Router::new()
         .route("/service-networks", post(create_service_network_handler).get(list_service_networks_handler))
         .route("/service-networks/{name}", get(get_service_network_handler).delete(delete_service_network_handler))
+        .merge(crate::healthcheck::router("vpclattice"))
         .with_state(state)
 }
 
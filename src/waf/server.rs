@@ -59,6 +59,7 @@ async fn handle_request(
 pub fn create_router(state: Arc<WAFState>) -> Router {
     Router::new()
         .route("/", post(handle_request))
+        .merge(crate::healthcheck::router("waf"))
         .with_state(state)
 }
 
@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<WorkdocsState>) -> Router {
     Router::new()
         .route("/folders", post(create_folder_handler).get(list_folders_handler))
         .route("/folders/{name}", get(get_folder_handler).delete(delete_folder_handler))
+        .merge(crate::healthcheck::router("workdocs"))
         .with_state(state)
 }
 
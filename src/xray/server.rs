@@ -45,6 +45,7 @@ pub fn create_router(state: Arc<XrayState>) -> Router {
     Router::new()
         .route("/groups", post(create_group_handler).get(list_groups_handler))
         .route("/groups/{name}", get(get_group_handler).delete(delete_group_handler))
+        .merge(crate::healthcheck::router("xray"))
         .with_state(state)
 }
 
@@ -0,0 +1,101 @@
+#!/usr/bin/env bash
+source "$(dirname "$0")/test_helpers.sh"
+
+PORT=$(service_port apigatewayv2)
+ENDPOINT="http://localhost:${PORT}"
+
+aws_apigw() {
+  aws apigatewayv2 "$@" \
+    --endpoint-url "$ENDPOINT" \
+    --region "$REGION" \
+    --no-sign-request \
+    --no-cli-pager \
+    --output json 2>&1
+}
+
+json_field() {
+  python3 -c "import sys,json; print(json.load(sys.stdin)['$1'])" 2>/dev/null
+}
+
+ensure_server
+
+# ── Tests ────────────────────────────────────────────────────────────────
+
+echo "Running API Gateway V2 integration tests..."
+
+# 1. CreateApi
+OUT=$(aws_apigw create-api --name items --protocol-type HTTP)
+assert_contains "CreateApi" "$OUT" "ApiId"
+API_ID=$(echo "$OUT" | json_field ApiId)
+
+# 2. GetApis
+OUT=$(aws_apigw get-apis)
+assert_contains "GetApis" "$OUT" "$API_ID"
+
+# 3. GetApi
+OUT=$(aws_apigw get-api --api-id "$API_ID")
+assert_contains "GetApi" "$OUT" "items"
+
+# 4. CreateIntegration
+OUT=$(aws_apigw create-integration \
+  --api-id "$API_ID" \
+  --integration-type AWS_PROXY \
+  --integration-uri "arn:aws:lambda:${REGION}:000000000000:function:items" \
+  --payload-format-version 2.0)
+assert_contains "CreateIntegration" "$OUT" "IntegrationId"
+INTEGRATION_ID=$(echo "$OUT" | json_field IntegrationId)
+
+# 5. GetIntegrations
+OUT=$(aws_apigw get-integrations --api-id "$API_ID")
+assert_contains "GetIntegrations" "$OUT" "$INTEGRATION_ID"
+
+# 6. CreateRoute
+OUT=$(aws_apigw create-route \
+  --api-id "$API_ID" \
+  --route-key 'GET /items/{id}' \
+  --target "integrations/${INTEGRATION_ID}")
+assert_contains "CreateRoute" "$OUT" "RouteId"
+ROUTE_ID=$(echo "$OUT" | json_field RouteId)
+
+OUT=$(aws_apigw create-route --api-id "$API_ID" --route-key 'GET /items/{id}')
+assert_contains "CreateRoute duplicate" "$OUT" "ConflictException"
+
+# 7. GetRoutes
+OUT=$(aws_apigw get-routes --api-id "$API_ID")
+assert_contains "GetRoutes" "$OUT" "GET /items/{id}"
+
+# 8. CreateStage
+OUT=$(aws_apigw create-stage --api-id "$API_ID" --stage-name prod)
+assert_contains "CreateStage" "$OUT" "prod"
+
+# 9. GetStages
+OUT=$(aws_apigw get-stages --api-id "$API_ID")
+assert_contains "GetStages" "$OUT" "prod"
+
+# 10. Invoke an unmatched route
+OUT=$(curl -s "${ENDPOINT}/apis/${API_ID}/prod/_user_request_/unknown")
+assert_contains "Invoke unmatched route" "$OUT" "Not Found"
+
+# 11. DeleteRoute
+OUT=$(aws_apigw delete-route --api-id "$API_ID" --route-id "$ROUTE_ID")
+assert_contains "DeleteRoute" "$OUT" ""
+
+# 12. DeleteStage
+OUT=$(aws_apigw delete-stage --api-id "$API_ID" --stage-name prod)
+assert_contains "DeleteStage" "$OUT" ""
+
+# 13. DeleteIntegration
+OUT=$(aws_apigw delete-integration --api-id "$API_ID" --integration-id "$INTEGRATION_ID")
+assert_contains "DeleteIntegration" "$OUT" ""
+
+# 14. DeleteApi
+OUT=$(aws_apigw delete-api --api-id "$API_ID")
+assert_contains "DeleteApi" "$OUT" ""
+
+OUT=$(aws_apigw get-api --api-id "$API_ID")
+assert_contains "DeleteApi verify" "$OUT" "NotFoundException"
+
+# ── report ───────────────────────────────────────────────────────────────
+
+report_results "API Gateway V2"
+exit $?
@@ -13,6 +13,15 @@ aws_cognito() {
     --output json 2>&1
 }
 
+aws_identity() {
+  aws cognito-identity "$@" \
+    --endpoint-url "$ENDPOINT" \
+    --region "$REGION" \
+    --no-sign-request \
+    --no-cli-pager \
+    --output json 2>&1
+}
+
 ensure_server
 
 # ── Tests ────────────────────────────────────────────────────────────────
@@ -234,6 +243,33 @@ assert_contains "DeleteUserPool" "$OUT" ""
 OUT=$(aws_cognito describe-user-pool --user-pool-id "$POOL_ID")
 assert_contains "DeleteUserPool verify" "$OUT" "ResourceNotFoundException"
 
+# 30. CreateIdentityPool
+OUT=$(aws_identity create-identity-pool \
+  --identity-pool-name guests \
+  --allow-unauthenticated-identities)
+assert_contains "CreateIdentityPool" "$OUT" "IdentityPoolId"
+IDENTITY_POOL_ID=$(echo "$OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['IdentityPoolId'])" 2>/dev/null)
+
+# 31. GetId
+OUT=$(aws_identity get-id --identity-pool-id "$IDENTITY_POOL_ID")
+assert_contains "GetId" "$OUT" "IdentityId"
+IDENTITY_ID=$(echo "$OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['IdentityId'])" 2>/dev/null)
+
+# 32. GetCredentialsForIdentity
+OUT=$(aws_identity get-credentials-for-identity --identity-id "$IDENTITY_ID")
+assert_contains "GetCredentialsForIdentity" "$OUT" "SessionToken"
+
+# 33. GetOpenIdToken
+OUT=$(aws_identity get-open-id-token --identity-id "$IDENTITY_ID")
+assert_contains "GetOpenIdToken" "$OUT" "Token"
+
+# 34. DeleteIdentityPool
+OUT=$(aws_identity delete-identity-pool --identity-pool-id "$IDENTITY_POOL_ID")
+assert_contains "DeleteIdentityPool" "$OUT" ""
+
+OUT=$(aws_identity describe-identity-pool --identity-pool-id "$IDENTITY_POOL_ID")
+assert_contains "DeleteIdentityPool verify" "$OUT" "ResourceNotFoundException"
+
 # ── report ───────────────────────────────────────────────────────────────
 
 report_results "Cognito"
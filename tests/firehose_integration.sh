@@ -29,9 +29,13 @@ assert_contains "CreateDeliveryStream arn" "$OUT" "mystream"
 OUT=$(aws_firehose describe-delivery-stream \
   --delivery-stream-name mystream)
 assert_contains "DescribeDeliveryStream name" "$OUT" "mystream"
-assert_contains "DescribeDeliveryStream status" "$OUT" "ACTIVE"
 assert_contains "DescribeDeliveryStream type" "$OUT" "DirectPut"
 
+sleep 1
+OUT=$(aws_firehose describe-delivery-stream \
+  --delivery-stream-name mystream)
+assert_contains "DescribeDeliveryStream active" "$OUT" "ACTIVE"
+
 # 3. ListDeliveryStreams
 OUT=$(aws_firehose list-delivery-streams)
 assert_contains "ListDeliveryStreams" "$OUT" "mystream"
@@ -34,7 +34,7 @@ OUT=$(aws_lambda create-function \
   --zip-file fileb:///tmp/lambda-dummy.zip)
 assert_contains "CreateFunction name" "$OUT" "my-func"
 assert_contains "CreateFunction runtime" "$OUT" "python3.12"
-assert_contains "CreateFunction state" "$OUT" "Active"
+assert_contains "CreateFunction state" "$OUT" "Pending"
 
 # 2. ListFunctions
 OUT=$(aws_lambda list-functions)
@@ -44,6 +44,7 @@ assert_contains "ListFunctions" "$OUT" "my-func"
 OUT=$(aws_lambda get-function --function-name my-func)
 assert_contains "GetFunction config" "$OUT" "my-func"
 assert_contains "GetFunction code" "$OUT" "S3"
+assert_contains "GetFunction state" "$OUT" "Active"
 
 # 4. UpdateFunctionConfiguration
 OUT=$(aws_lambda update-function-configuration \
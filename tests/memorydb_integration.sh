@@ -74,12 +74,17 @@ OUT=$(aws_mdb create-cluster \
   --node-type db.t4g.small \
   --acl-name myacl)
 assert_contains "CreateCluster" "$OUT" "mycluster"
-assert_contains "CreateCluster status" "$OUT" "available"
+assert_contains "CreateCluster status" "$OUT" "creating"
 assert_contains "CreateCluster endpoint" "$OUT" "6379"
 
 # 10. DescribeClusters
+sleep 1
 OUT=$(aws_mdb describe-clusters)
 assert_contains "DescribeClusters" "$OUT" "mycluster"
+assert_contains "DescribeClusters status" "$OUT" "available"
+assert_not_contains "DescribeClusters without shard details" "$OUT" "Shards"
+OUT=$(aws_mdb describe-clusters --show-shard-details)
+assert_contains "DescribeClusters shard details" "$OUT" "0-16383"
 
 # 11. UpdateCluster
 OUT=$(aws_mdb update-cluster \
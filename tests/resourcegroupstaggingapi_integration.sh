@@ -0,0 +1,57 @@
+#!/usr/bin/env bash
+source "$(dirname "$0")/test_helpers.sh"
+
+PORT=$(service_port resourcegroupstaggingapi)
+ENDPOINT="http://localhost:${PORT}"
+SQS_ENDPOINT="http://localhost:$(service_port sqs)"
+
+aws_tagging() {
+  aws resourcegroupstaggingapi "$@" \
+    --endpoint-url "$ENDPOINT" \
+    --region "$REGION" \
+    --no-sign-request \
+    --no-cli-pager \
+    --output json 2>&1
+}
+
+aws_sqs() {
+  aws sqs "$@" \
+    --endpoint-url "$SQS_ENDPOINT" \
+    --region "$REGION" \
+    --no-sign-request \
+    --no-cli-pager \
+    --output json 2>&1
+}
+
+ensure_server
+
+# ── setup ────────────────────────────────────────────────────────────────
+
+aws_sqs create-queue --queue-name tagging-orders --tags team=payments >/dev/null
+
+# 1. GetResources by tag
+OUT=$(aws_tagging get-resources --tag-filters Key=team,Values=payments)
+assert_contains "GetResources by tag" "$OUT" "tagging-orders"
+
+# 2. GetResources by resource type
+OUT=$(aws_tagging get-resources --resource-type-filters sqs)
+assert_contains "GetResources by type" "$OUT" "arn:aws:sqs"
+
+# 3. GetTagKeys
+OUT=$(aws_tagging get-tag-keys)
+assert_contains "GetTagKeys" "$OUT" "team"
+
+# 4. GetTagValues
+OUT=$(aws_tagging get-tag-values --key team)
+assert_contains "GetTagValues" "$OUT" "payments"
+
+# 5. GetResources with too many resources per page
+OUT=$(aws_tagging get-resources --resources-per-page 500)
+assert_contains "GetResources invalid page size" "$OUT" "InvalidParameterException"
+
+# ── cleanup ──────────────────────────────────────────────────────────────
+
+aws_sqs delete-queue --queue-url "$SQS_ENDPOINT/$ACCOUNT/tagging-orders" >/dev/null
+
+report_results "Resource Groups Tagging API"
+exit $?
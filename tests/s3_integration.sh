@@ -255,7 +255,33 @@ assert_exit_zero "AbortMultipartUpload: succeeds" \
     --bucket test-bucket --key abort-me.txt --upload-id "$ABORT_UPLOAD_ID"
 
 # ═════════════════════════════════════════════════════════════════════════
-# 17. DeleteBucket
+# 17. Bucket CORS
+# ═════════════════════════════════════════════════════════════════════════
+
+assert_exit_zero "PutBucketCors: succeeds" \
+  aws s3api put-bucket-cors \
+    --endpoint-url "$ENDPOINT" --region "$REGION" --no-sign-request --no-cli-pager \
+    --bucket test-bucket \
+    --cors-configuration '{"CORSRules":[{"AllowedOrigins":["https://app.example.com"],"AllowedMethods":["GET","PUT"],"AllowedHeaders":["*"]}]}'
+
+OUT=$(aws_s3api get-bucket-cors --bucket test-bucket)
+assert_contains "GetBucketCors: has origin" "$OUT" "https://app.example.com"
+
+OUT=$(curl -s -o /dev/null -w '%{http_code}' -X OPTIONS "$ENDPOINT/test-bucket/hello.txt" \
+  -H "Origin: https://app.example.com" -H "Access-Control-Request-Method: PUT")
+assert_contains "CORS preflight: allowed origin" "$OUT" "200"
+
+OUT=$(curl -s -o /dev/null -w '%{http_code}' -X OPTIONS "$ENDPOINT/test-bucket/hello.txt" \
+  -H "Origin: https://evil.test" -H "Access-Control-Request-Method: PUT")
+assert_contains "CORS preflight: other origin rejected" "$OUT" "403"
+
+assert_exit_zero "DeleteBucketCors: succeeds" \
+  aws s3api delete-bucket-cors \
+    --endpoint-url "$ENDPOINT" --region "$REGION" --no-sign-request --no-cli-pager \
+    --bucket test-bucket
+
+# ═════════════════════════════════════════════════════════════════════════
+# 18. DeleteBucket
 # ═════════════════════════════════════════════════════════════════════════
 
 # Delete all objects first
@@ -49,13 +49,13 @@ EXEC_ARN=$(echo "$OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)
 # 5. DescribeExecution
 OUT=$(aws_sfn describe-execution --execution-arn "$EXEC_ARN")
 assert_contains "DescribeExecution" "$OUT" "my-execution"
-assert_contains "DescribeExecution status" "$OUT" "RUNNING"
+assert_contains "DescribeExecution status" "$OUT" "SUCCEEDED"
 assert_contains "DescribeExecution input" "$OUT" "key"
 
 # 6. ListExecutions
 OUT=$(aws_sfn list-executions --state-machine-arn "$SM_ARN")
 assert_contains "ListExecutions" "$OUT" "my-execution"
-assert_contains "ListExecutions status" "$OUT" "RUNNING"
+assert_contains "ListExecutions status" "$OUT" "SUCCEEDED"
 
 # 7. GetExecutionHistory
 OUT=$(aws_sfn get-execution-history --execution-arn "$EXEC_ARN")
@@ -29,7 +29,7 @@ TESTS=()
 
 service_port() {
   case "$1" in
-    acm) echo 10034;; amplify) echo 10154;; apigateway) echo 4567;;
+    acm) echo 10034;; amplify) echo 10154;; apigateway) echo 4567;; apigatewayv2) echo 10160;;
     appfabric) echo 10128;; appflow) echo 10112;; appmesh) echo 10158;;
     apprunner) echo 10006;; appsync) echo 9700;; athena) echo 10050;;
     autoscaling) echo 10011;; b2bi) echo 10116;; backup) echo 10146;;
@@ -72,7 +72,7 @@ service_port() {
     outposts) echo 10009;; personalize) echo 10099;; pinpoint) echo 10125;;
     polly) echo 10100;; proton) echo 10077;; qbusiness) echo 10108;;
     quicksight) echo 10059;; ram) echo 10045;; rds) echo 10012;;
-    redshift) echo 10060;; rekognition) echo 10101;; route53) echo 10022;;
+    redshift) echo 10060;; rekognition) echo 10101;; resourcegroupstaggingapi) echo 10159;; route53) echo 10022;;
     s3) echo 9000;; sagemaker) echo 10102;; secretsmanager) echo 7700;;
     securityhub) echo 10046;; securitylake) echo 10041;; servicecatalog) echo 9400;;
     ses) echo 9300;; shield) echo 10036;; sns) echo 9911;;